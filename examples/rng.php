@@ -57,3 +57,51 @@ $multiWeighted = Rng::chooseMultipleWeighted(2, [
 var_dump($multiWeighted);
 // Example: array(2) { [0]=> string(1) "B" [1]=> string(1) "C" }
 
+// Random (v4) and time-ordered (v7) UUIDs
+var_dump(Rng::uuid4());
+// Example: string(36) "3f9c1e2a-7b4d-4c8e-9a1f-0d2e3c4b5a69"
+var_dump(Rng::uuid7());
+// Example: string(36) "01928f3a-6c1e-7d2b-8f4a-1b2c3d4e5f60"
+
+// Sortable ULID, monotonic within the same millisecond
+$ulid = Rng::ulid();
+var_dump($ulid, Rng::isValidUlid($ulid), Rng::ulidTimestamp($ulid));
+// Example: string(26) "01J9ZQ3XK5V7T2M8N4P6R0S1W3" bool(true) int(1728000000000)
+
+// 16-character password with a symbol and no look-alike characters
+var_dump(Rng::password(16, ['symbols' => true, 'excludeAmbiguous' => true]));
+// Example: string(16) "q7#HvT9m@Rk4zW^x"
+
+// Six-word recovery phrase from the EFF large wordlist (~77 bits)
+var_dump(Rng::passphrase(6));
+// Example: string(39) "exhale-gravy-nutmeg-unsaid-wobbly-tulip"
+
+// 32-byte session ID, and a hand-typeable code with a check symbol
+var_dump(Rng::token(32));
+// Example: string(43) "mB3k9Qx0v2Lr7TzYp4Wn8Hc1Jd6Fs5Ge0Ua_-KiVoE"
+var_dump(Rng::token(5, 'crockford', true));
+// Example: string(9) "7ZK3QX1M~"
+
+// Unbiased integer and float ranges, and a weighted coin flip
+var_dump(Rng::intBetween(1, 6), Rng::floatBetween(0.5, 1.5), Rng::bool(0.1));
+// Example: int(4) float(0.83914202387) bool(false)
+
+// Fisher–Yates shuffle and a 2-winner raffle draw
+var_dump(Rng::shuffle([1, 2, 3, 4]), Rng::sample(['alice', 'bob', 'carol', 'dave'], 2));
+// Example: array(4) { [0]=> int(3) [1]=> int(1) [2]=> int(4) [3]=> int(2) }
+//          array(2) { [0]=> string(5) "carol" [1]=> string(5) "alice" }
+
+// A/B assignment: returns a key, 90% "control" and 10% "variant"
+var_dump(Rng::weightedChoice(['control' => 90, 'variant' => 10]));
+// Example: string(7) "control"
+
+// 6-digit OTP without 000000/123456-style codes, checked in constant time
+$otp = Rng::numericCode(6, true);
+var_dump($otp, Rng::codeEquals($otp, '123456'));
+// Example: string(6) "049731" bool(false)
+
+// Deterministic, NON-cryptographic generator for reproducible tests
+$seeded = Rng::seeded('test suite');
+var_dump($seeded->password(12), $seeded->isCryptographic());
+// Example: string(12) "Tq4mZ8rWk2Hx" bool(false) — the same on every run
+
@@ -24,9 +24,9 @@ $policy->exposeHeaders(['X-Custom-Header']);
 $policy->maxAge(3600);
 
 // Apply headers manually
-foreach ($policy->build() as $name => $value) {
+foreach ($policy->build($_SERVER['HTTP_ORIGIN'] ?? null) as $name => $value) {
     header("$name: $value");
 }
 
 // Or simply:
-$policy->send();
+$policy->send($_SERVER['HTTP_ORIGIN'] ?? null);
@@ -0,0 +1,25 @@
+<?php
+declare(strict_types=1);
+
+use Hardened\SecurityHeaders\SetCookie;
+
+// Secure, HttpOnly, SameSite=Lax and Path=/ by default
+$cookie = new SetCookie('__Host-sid', bin2hex(random_bytes(16)));
+$cookie->sameSite('Strict');
+$cookie->maxAge(3600);
+
+// e.g. "__Host-sid=…; Path=/; Max-Age=3600; Secure; HttpOnly; SameSite=Strict"
+echo $cookie->build(), PHP_EOL;
+
+// Prefix rules are enforced: a __Host- cookie cannot have a Domain
+$cookie->domain('example.com');
+try {
+    $cookie->build();
+} catch (\Exception $e) {
+    echo 'Rejected: ', $e->getMessage(), PHP_EOL;
+}
+
+// Emit the header (adds to, rather than replaces, other Set-Cookie headers)
+$prefs = new SetCookie('theme', 'dark');
+$prefs->httpOnly(false);
+$prefs->send();
@@ -52,6 +52,334 @@ namespace Hardened {
      */
     function shell_exec(string $command, ?array $expected_commands): mixed {}
 
+    /**
+     * Constant-time comparison, selection and strict encoding helpers.
+     */
+    class ConstantTime {
+        /**
+         * Compare two strings in time that depends only on their lengths, e.g. MACs or tokens.
+         *
+         * # Parameters
+         * - `a`: `string` Known value.
+         * - `b`: `string` Untrusted value.
+         *
+         * # Returns
+         * - `bool` `true` if they are equal.
+         */
+        public static function equals(string $a, string $b): bool {}
+
+        /**
+         * Return `a` if `condition` is true, else `b`, without a secret-dependent branch.
+         *
+         * # Parameters
+         * - `condition`: `bool` The selector.
+         * - `a`: `string` Returned if `condition` is true.
+         * - `b`: `string` Returned otherwise; must have the same length as `a`.
+         *
+         * # Returns
+         * - `string` One of the inputs.
+         *
+         * # Exceptions
+         * - Throws an exception if `a` and `b` differ in length.
+         */
+        public static function select(bool $condition, string $a, string $b): string {}
+
+        /**
+         * Encode bytes as lowercase hex without table lookups.
+         *
+         * # Parameters
+         * - `bytes`: `string` Binary data.
+         *
+         * # Returns
+         * - `string` Hex digits.
+         */
+        public static function hexEncode(string $bytes): string {}
+
+        /**
+         * Decode lowercase hex; uppercase, whitespace and odd lengths are rejected.
+         *
+         * # Parameters
+         * - `hex`: `string` Hex digits.
+         *
+         * # Returns
+         * - `string` Binary data.
+         *
+         * # Exceptions
+         * - Throws an exception if the input is not canonical lowercase hex.
+         */
+        public static function hexDecode(string $hex): string {}
+
+        /**
+         * Encode bytes as padded standard base64 without table lookups.
+         *
+         * # Parameters
+         * - `bytes`: `string` Binary data.
+         *
+         * # Returns
+         * - `string` Base64 text.
+         */
+        public static function base64Encode(string $bytes): string {}
+
+        /**
+         * Decode padded standard base64, rejecting anything but the canonical encoding.
+         *
+         * # Parameters
+         * - `base64`: `string` Base64 text.
+         *
+         * # Returns
+         * - `string` Binary data.
+         *
+         * # Exceptions
+         * - Throws an exception on bad padding, whitespace, foreign characters or non-zero
+         *   unused bits.
+         */
+        public static function base64Decode(string $base64): string {}
+
+        /**
+         * Encode bytes as unpadded URL-safe base64 without table lookups.
+         *
+         * # Parameters
+         * - `bytes`: `string` Binary data.
+         *
+         * # Returns
+         * - `string` Base64url text.
+         */
+        public static function base64UrlEncode(string $bytes): string {}
+
+        /**
+         * Decode unpadded URL-safe base64, rejecting anything but the canonical encoding.
+         *
+         * # Parameters
+         * - `base64Url`: `string` Base64url text.
+         *
+         * # Returns
+         * - `string` Binary data.
+         *
+         * # Exceptions
+         * - Throws an exception on padding, foreign characters, an impossible length or
+         *   non-zero unused bits.
+         */
+        public static function base64UrlDecode(string $base64_url): string {}
+    }
+
+    /**
+     * Detection of serialized PHP objects in untrusted input.
+     */
+    class DeserializationGuard {
+        /**
+         * Check whether input contains a serialized object, class or enum construct.
+         *
+         * Looks for `O:`, `C:` and `E:` anywhere in the input, so constructs nested in arrays
+         * are found, and in every base64 run (standard or URL-safe alphabet) up to three layers
+         * deep. A match is not proof of an attack, only that `unserialize()` without
+         * `allowed_classes => false` must not see the input.
+         *
+         * # Parameters
+         * - `input`: `string` Cookie, form field or other untrusted value.
+         *
+         * # Returns
+         * - `bool`
+         */
+        public static function containsSerializedObject(string $input): bool {}
+
+        /**
+         * Throw if input contains a serialized object, class or enum construct.
+         *
+         * # Parameters
+         * - `input`: `string` Value about to be passed to `unserialize()`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `containsSerializedObject()` would return `true`.
+         */
+        public static function assertScalarOnly(string $input) {}
+    }
+
+    /**
+     * Context-specific output encoders.
+     */
+    class Escape {
+        /**
+         * Encode text for an HTML element body, like `htmlspecialchars($s, ENT_QUOTES)`.
+         *
+         * # Parameters
+         * - `input`: `string`
+         *
+         * # Returns
+         * - `string` `&`, `<`, `>`, `"` and `'` as entities; control characters replaced
+         *   with U+FFFD.
+         */
+        public static function html(string $input): string {}
+
+        /**
+         * Encode text for an HTML attribute value.
+         *
+         * Safe in quoted and unquoted attributes alike, but not in `href`/`src` (validate the
+         * URL first), `style` or event handler attributes (use `css()` or `js()`).
+         *
+         * # Parameters
+         * - `input`: `string`
+         *
+         * # Returns
+         * - `string` Everything but alphanumerics, `,.-_` and non-ASCII letters as entities.
+         */
+        public static function htmlAttr(string $input): string {}
+
+        /**
+         * Encode text for a quoted JavaScript string.
+         *
+         * # Parameters
+         * - `input`: `string`
+         *
+         * # Returns
+         * - `string` Everything but alphanumerics and `,._` as `\xHH` or `\uHHHH` escapes.
+         */
+        public static function js(string $input): string {}
+
+        /**
+         * Encode text for a CSS property value or quoted CSS string.
+         *
+         * # Parameters
+         * - `input`: `string`
+         *
+         * # Returns
+         * - `string` Everything but alphanumerics as `\HEX ` escapes.
+         */
+        public static function css(string $input): string {}
+
+        /**
+         * Encode a URL component (a path segment or query parameter), like `rawurlencode()`.
+         *
+         * # Parameters
+         * - `input`: `string`
+         *
+         * # Returns
+         * - `string` Everything but `A-Za-z0-9-._~` percent-encoded.
+         */
+        public static function url(string $input): string {}
+
+        /**
+         * Neutralize text for an HTML comment, e.g. debug output.
+         *
+         * # Parameters
+         * - `input`: `string`
+         *
+         * # Returns
+         * - `string` The text with `--` runs split and dangerous edges padded.
+         */
+        public static function htmlComment(string $input): string {}
+    }
+
+    /**
+     * LDAP filter and DN escaping.
+     */
+    class LdapEscape {
+        /**
+         * Escape a value for an LDAP search filter, like `ldap_escape($v, '', LDAP_ESCAPE_FILTER)`
+         * but also escaping every non-printable and non-ASCII byte.
+         *
+         * # Parameters
+         * - `value`: `string`
+         *
+         * # Returns
+         * - `string` e.g. `"\2a)(uid=\2a"` for `"*)(uid=*"`.
+         */
+        public static function filterValue(string $value): string {}
+
+        /**
+         * Escape a value for a distinguished name attribute, like
+         * `ldap_escape($v, '', LDAP_ESCAPE_DN)` but also covering leading `#` and edge spaces.
+         *
+         * # Parameters
+         * - `value`: `string`
+         *
+         * # Returns
+         * - `string` e.g. `"Smith\, John"` for `"Smith, John"`.
+         */
+        public static function dnValue(string $value): string {}
+    }
+
+    /**
+     * Shell argument quoting.
+     */
+    class ShellEscape {
+        /**
+         * Quote one argument, like a strict `escapeshellarg()`.
+         *
+         * # Parameters
+         * - `value`: `string`
+         * - `platform`: `?string` `posix` (default) or `windows`.
+         *
+         * # Returns
+         * - `string` e.g. `'it'\''s'` or `"C:\dir\\"`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the platform is unknown, the value contains NUL, or on Windows
+         *   it contains `"`, `%`, `!` or a line break.
+         */
+        public static function arg(string $value, ?string $platform): string {}
+
+        /**
+         * Quote every element of a list and join them into one command string.
+         *
+         * # Parameters
+         * - `values`: `array<string|int>` Program and arguments, in order.
+         * - `platform`: `?string` `posix` (default) or `windows`.
+         *
+         * # Returns
+         * - `string` e.g. `'tar' '-czf' 'my backup.tgz' 'dir'`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the platform is unknown, an element is not a string or int, or
+         *   an element cannot be quoted.
+         */
+        public static function argv(array $values, ?string $platform): string {}
+    }
+
+    /**
+     * SQL identifier quoting and `LIKE` pattern escaping.
+     */
+    class SqlEscape {
+        /**
+         * Quote a table, column or schema name for interpolation into SQL.
+         *
+         * The name is one identifier: `a.b` becomes a single quoted name containing a dot, so
+         * quote each part of a qualified name separately. Still check names against an
+         * allowlist when users pick them; quoting stops injection, not access to other columns.
+         *
+         * # Parameters
+         * - `name`: `string` The identifier.
+         * - `dialect`: `string` `mysql` (or `mariadb`), `postgres` (or `pgsql`), `mssql` (or
+         *   `sqlsrv`), or `sqlite`.
+         *
+         * # Returns
+         * - `string` e.g. `` `order` ``, `"order"` or `[order]`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the dialect is unknown, or the name is empty, contains control
+         *   characters or breaks the dialect's length or character rules.
+         */
+        public static function identifier(string $name, string $dialect): string {}
+
+        /**
+         * Escape a value for use as a literal inside a bound `LIKE` pattern.
+         *
+         * Bind the result as a parameter and name the escape character in the query, e.g.
+         * `WHERE name LIKE ? ESCAPE '\'` (SQL Server has no default escape character).
+         *
+         * # Parameters
+         * - `value`: `string` Text to match literally.
+         * - `escapeChar`: `?string` Escape character. Default: `\`.
+         *
+         * # Returns
+         * - `string` The value with `%`, `_`, `[` and the escape character escaped; add your own
+         *   wildcards around it, e.g. `'%' . SqlEscape::likePattern($q) . '%'`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `escapeChar` is not exactly one character, or is a wildcard.
+         */
+        public static function likePattern(string $value, ?string $escape_char): string {}
+    }
+
     /**
      * Safe subprocess launcher.
      *
@@ -236,9 +564,9 @@ namespace Hardened {
          * enforces the configured timeout, and optionally captures output into PHP variables.
          *
          * # Parameters
-         * - `stdout`: `?string &$stdout`
+         * - `capture_stdout`: `?string &$stdout`
          *   Optional reference to a PHP variable; if provided, the collected stdout will be written here.
-         * - `stderr`: `?string &$stderr`
+         * - `capture_stderr`: `?string &$stderr`
          *   Optional reference to a PHP variable; if provided, the collected stderr will be written here.
          *
          * # Returns
@@ -250,7 +578,7 @@ namespace Hardened {
          * Runs the command, streaming both stdout and stderr live, with a timeout and
          * selected environment variables passed through.
          */
-        public function run(?mixed $capture_stdout, ?mixed $capture_stderr): int {}
+        public function run(mixed $capture_stdout, mixed $capture_stderr): int {}
 
         /**
          * Constructs a new ShellCommand for the given program path.
@@ -268,7 +596,13 @@ namespace Hardened {
      * A secured wrapper around `url::Host` for use in PHP extensions.
      * Provides hostname parsing and normalization to prevent security issues.
      */
-    class Hostname {
+    class Hostname implements \JsonSerializable {
+        /**
+         * The normalized hostname, exposed as the read-only `hostname` property so that
+         * `var_export()` output carries it to `__set_state()`.
+         */
+        public $hostname;
+
         /**
          * Parses and normalizes a hostname string.
          *
@@ -416,1961 +750,6770 @@ namespace Hardened {
         public function isDomain(): bool {}
 
         /**
-         * Returns the string representation of this hostname.
+         * Returns true if this hostname is an IP address within any of the given networks.
          *
-         * # Returns
-         * - `string`: The normalized hostname string.
-         */
-        public function __toString(): string {}
-
-        /**
-         * Constructs a new Hostname instance (alias for `from`).
+         * IPv4-mapped IPv6 addresses (e.g. `[::ffff:10.0.0.1]`) are matched against IPv4 networks.
+         * Domain names never match; resolve them first.
          *
          * # Parameters
-         * - `hostname`: The hostname to initialize.
+         * - `cidrs`: `string|array` Networks such as `"10.0.0.0/8, fd00::/8"` or `['10.0.0.0/8', 'fd00::/8']`.
+         *   A bare address matches only itself.
          *
-         * # Errors
-         * Throws an exception if parsing the hostname fails.
+         * # Returns
+         * - `bool`: `true` if the address is in one of the networks.
+         *
+         * # Exceptions
+         * - Throws an exception if a network is malformed.
          */
-        public function __construct(mixed $hostname) {}
-    }
+        public function inCidr(mixed $cidrs): bool {}
 
-    class Path {
         /**
-         * Creates a new PathObj by lexically canonicalizing a given PHP value.
+         * Matches this hostname against a pattern with an optional leading wildcard label.
+         *
+         * `*.example.com` matches `api.example.com` but neither `a.b.example.com`, `example.com`
+         * nor `evilexample.com`; `**.example.com` matches any depth of subdomains.
+         * Patterns without a wildcard must match exactly.
          *
          * # Parameters
-         * - `path`: The PHP value to convert to a filesystem path.
+         * - `pattern`: `string` The pattern, e.g. `*.example.com`.
+         * - `includeApex`: `?bool` Whether a wildcard pattern also matches `example.com` itself (default `false`).
+         *
+         * # Returns
+         * - `bool`: `true` if the hostname matches.
          *
          * # Exceptions
-         * - Throws an exception if conversion of `$path` to string fails.
+         * - Throws an exception if the pattern is malformed (e.g. `a*.example.com`).
          */
-        public static function from(mixed $path): \Hardened\Path {}
+        public function matchesPattern(string $pattern, ?bool $include_apex): bool {}
 
         /**
-         * Checks if this path starts with the given prefix path.
+         * Returns true if this hostname equals, or is a subdomain of, any of the given hosts.
+         *
+         * Entries starting with `*.` or `**.` are treated as patterns (see `matchesPattern()`).
          *
          * # Parameters
-         * - `path`: The PHP value to compare against.
+         * - `hosts`: `array` Hostnames and/or patterns.
          *
          * # Returns
-         * `true` if this path starts with the given prefix.
+         * - `bool`: `true` on the first match.
          *
          * # Exceptions
-         * - Throws an exception if conversion from Zval to string fails.
+         * - Throws an exception if an entry is not a valid hostname or pattern.
          */
-        public function startsWith(mixed $path): bool {}
+        public function equalsOrSubdomainOfAny(array $hosts): bool {}
 
         /**
-         * Joins the given path onto this path and normalizes it.
+         * Resolves the hostname and pins the connection to addresses that pass the network policy.
+         *
+         * Validating a hostname and then letting the HTTP client resolve it again leaves a window
+         * for DNS rebinding: the second lookup can return `127.0.0.1`. The returned `resolve` entry
+         * makes curl connect only to the addresses checked here.
+         *
+         * ```php
+         * $pinned = Hostname::fromUrl($url)->resolvePinned(['port' => 443]);
+         * curl_setopt($ch, CURLOPT_RESOLVE, $pinned['resolve']);
+         * ```
          *
          * # Parameters
-         * - `path`: The PHP value to join.
+         * - `options`: `?array` Optional settings:
+         *   - `port`: `int` port of the connection (default `443`).
+         *   - `allowPrivate`: `bool` accept loopback, private and other non-public addresses (default `false`).
+         *   - `allowedCidrs`: `array` networks accepted even if non-public.
+         *   - `deniedCidrs`: `array` networks that are always rejected.
          *
          * # Returns
-         * A new PathObj representing the joined path.
+         * - `array`: `['ips' => string[], 'resolve' => ['host:port:ip1,ip2']]`.
          *
          * # Exceptions
-         * - Throws an exception if conversion from Zval to string fails.
+         * - Throws an exception if resolution fails, an option is invalid, or no address is permitted.
          */
-        public function join(mixed $path): \Hardened\Path {}
+        public function resolvePinned(?array $options): array {}
 
         /**
-         * Joins the given path onto this path, normalizes it, and ensures it's a subpath.
+         * Parses many hostnames in one call, e.g. a column of an uploaded CSV file.
          *
          * # Parameters
-         * - `path`: string|Path
+         * - `hosts`: `array` Raw hostname strings.
          *
-         * # Exceptions
-         * - Throws an exception if `$path` is not a string nor Path
+         * # Returns
+         * - `array` One entry per input, in order: `['hostname' => ?string, 'error' => ?string]`,
+         *   where `hostname` is the normalized form if valid, and `error` the reason otherwise.
          */
-        public function joinSubpath(mixed $path): \Hardened\Path {}
+        public static function validateAll(array $hosts): array {}
 
         /**
-         * Set the file name component of the path.
+         * Verifies a crawler's identity with forward-confirmed reverse DNS.
+         *
+         * Looks up the PTR name of `ip`, requires it to be within one of `expectedSuffixes`
+         * (e.g. `googlebot.com`), and requires the name to resolve back to `ip`. This is the check
+         * recommended by Google and Bing to tell genuine crawlers from spoofed user agents.
+         *
+         * Definitive results are cached per worker thread for 5 minutes; timeouts and resolver
+         * failures are not, so a DNS hiccup does not lock a genuine crawler out.
          *
          * # Parameters
-         * - `fileName`: string
+         * - `ip`: `string` The client IP address.
+         * - `expectedSuffixes`: `array` Allowed parent domains, e.g. `['googlebot.com', 'google.com']`.
+         * - `timeoutMs`: `?int` Timeout of each DNS lookup in milliseconds (default `2000`).
+         *
+         * # Returns
+         * - `bool`: `true` if the IP belongs to one of the expected domains.
+         *
+         * # Exceptions
+         * - Throws an exception if `ip` is not an IP address or a suffix is not a valid hostname.
          */
-        public function setFileName(string $file_name): \Hardened\Path {}
+        public static function verifyFcrdns(string $ip, array $expected_suffixes, ?int $timeout_ms): bool {}
 
         /**
-         * Set the file name component of the path.
+         * Returns the ASCII (punycode) form of this hostname, e.g. `xn--80ak6aa92e.com`.
          *
-         * # Parameters
-         * - `extension`: string
+         * # Returns
+         * - `string`: The ASCII hostname; IP addresses are returned unchanged.
+         *
+         * # Exceptions
+         * - Throws an exception if the hostname is not a valid IDN.
          */
-        public function setExtension(string $extension): \Hardened\Path {}
+        public function toAscii(): string {}
 
         /**
-         * Get the last component of the path.
-         */
-        public function fileName(): ?string {}
-
-        /**
-         * Get the directory name (similar to `dirname()`).
-         */
-        public function parent(): ?\Hardened\Path {}
-
-        /**
-         * Converts the path to its string representation.
+         * Returns the Unicode form of this hostname, e.g. `аррӏе.com` for `xn--80ak6aa92e.com`.
+         *
+         * Use this for display only; compare and store the ASCII form.
          *
          * # Returns
-         * The string representation of the path.
+         * - `string`: The Unicode hostname; IP addresses are returned unchanged.
          *
-         * # Errors
-         * Throws an exception if the path cannot be converted to a string.
+         * # Exceptions
+         * - Throws an exception if a punycode label cannot be decoded.
          */
-        public function __toString(): string {}
-
-        public function path(): string {}
+        public function toUnicode(): string {}
 
         /**
-         * Check if the path's extension is in the allowed list.
+         * Returns true if the hostname looks like a lookalike (homograph) of another host.
+         *
+         * A label is suspicious if it mixes scripts (e.g. Latin and Cyrillic in `аpple`), or is written
+         * entirely in non-Latin characters that imitate Latin letters (e.g. Cyrillic `аррӏе`).
+         * Additionally, if `protected` domains are given, the hostname is flagged when it
+         * visually resembles one of them without being equal to it.
          *
          * # Parameters
-         * - `allowed`: PHP array of allowed extensions (strings, without leading dot), case-insensitive.
+         * - `protected`: `?array` Domains to protect, e.g. `['apple.com', 'paypal.com']`.
          *
          * # Returns
-         * - `bool` `true` if the file extension matches one of the allowed values.
+         * - `bool`: `true` if the hostname should be treated as a spoofing attempt.
+         *
+         * # Exceptions
+         * - Throws an exception if a punycode label cannot be decoded.
          */
-        public function validateExtension(array $allowed): bool {}
+        public function isHomographSuspicious(?array $protected): bool {}
 
         /**
-         * Check if the path's extension is a common image type.
+         * Returns the public suffix (eTLD) of this hostname, e.g. `co.uk` for `www.example.co.uk`.
+         *
+         * Unknown TLDs are treated as public suffixes (the PSL `*` rule).
          *
          * # Returns
-         * - `bool` `true` if extension is one of `["png","jpg","jpeg","gif","webp","bmp","tiff","svg"]`.
+         * - `?string`: The public suffix, or `null` for IP addresses.
          */
-        public function validateExtensionImage(): bool {}
+        public function publicSuffix(): ?string {}
 
         /**
-         * Check if the path's extension is a common video type.
+         * Returns the registrable domain (eTLD+1) of this hostname, e.g. `example.co.uk`
+         * for `www.example.co.uk`. This is the widest domain a cookie may be scoped to,
+         * and the unit of "same site" comparisons.
          *
          * # Returns
-         * - `bool` `true` if extension is one of `["mp4","mov","avi","mkv","webm","flv"]`.
+         * - `?string`: The registrable domain, or `null` for IP addresses and public suffixes themselves.
          */
-        public function validateExtensionVideo(): bool {}
+        public function registrableDomain(): ?string {}
 
         /**
-         * Check if the path's extension is a common audio type.
+         * Returns true if this hostname is exactly a registrable domain (eTLD+1).
          *
          * # Returns
-         * - `bool` `true` if extension is one of `["mp3","wav","ogg","flac","aac"]`.
+         * - `bool`: `true` for `example.co.uk`; `false` for `co.uk`, `www.example.co.uk` and IPs.
          */
-        public function validateExtensionAudio(): bool {}
+        public function isRegistrableDomain(): bool {}
 
         /**
-         * Check if the path's extension is a common document type.
+         * Replaces the embedded public suffix list, e.g. with a freshly downloaded copy of
+         * <https://publicsuffix.org/list/public_suffix_list.dat> or a list with private additions.
          *
-         * # Returns
-         * - `bool` `true` if extension is one of `["pdf","doc","docx","xls","xlsx","ppt","pptx"]`.
+         * The list applies to the current PHP worker thread until `resetPublicSuffixList()`.
+         *
+         * # Parameters
+         * - `list`: `string` The list in the standard PSL format.
+         *
+         * # Exceptions
+         * - Throws an exception if the list cannot be parsed.
          */
-        public function validateExtensionDocument(): bool {}
+        public static function setPublicSuffixList(string $list) {}
 
         /**
-         * Returns true if the path is absolute (starts with root or drive prefix).
-         *
-         * # Returns
-         * - `bool` `true` if the path is absolute.
+         * Reverts to the public suffix list embedded at build time.
          */
-        public function isAbsolute(): bool {}
+        public static function resetPublicSuffixList() {}
 
         /**
-         * Returns true if the path is relative (not absolute).
+         * Returns true if the top-level domain is delegated in the root zone, e.g. `com`.
+         *
+         * By default, the ICANN section of the embedded public suffix list is used, which tracks the
+         * IANA root zone. Use `loadTldList()` to check against a fresh copy of the IANA list instead.
          *
          * # Returns
-         * - `bool` `true` if the path is relative.
+         * - `bool`: `true` for known TLDs; `false` for unknown TLDs like `notatld`, and for IP addresses.
          */
-        public function isRelative(): bool {}
+        public function hasKnownTld(): bool {}
 
         /**
-         * Returns true if the path tried to escape its base directory during normalization.
+         * Replaces the TLD list used by `hasKnownTld()`.
          *
-         * This is useful for detecting directory traversal attempts.
-         * A path "escapes" if it contains leading `..` components that would go above
-         * the starting directory, or if it starts with a root/prefix.
+         * The list applies to the current PHP worker thread until `resetTldList()`.
          *
-         * # Returns
-         * - `bool` `true` if the path escaped during normalization.
+         * # Parameters
+         * - `list`: `string` The list in the format of
+         *   <https://data.iana.org/TLD/tlds-alpha-by-domain.txt>: one TLD per line, `#` comments.
          */
-        public function hasEscaped(): bool {}
+        public static function loadTldList(string $list) {}
 
         /**
-         * Returns the file extension, if any.
-         *
-         * # Returns
-         * - `?string` The extension without the leading dot, or `null` if none.
+         * Reverts `hasKnownTld()` to the TLDs of the embedded public suffix list.
          */
-        public function extension(): ?string {}
+        public static function resetTldList() {}
 
         /**
-         * Constructs a new PathObj instance (alias for `from`).
-         *
-         * # Parameters
-         * - `path`: The PHP value to convert to a filesystem path.
+         * Returns the string representation of this hostname.
          *
-         * # Exceptions
-         * - Throws an exception if conversion from Zval to string fails.
+         * # Returns
+         * - `string`: The normalized hostname string.
          */
-        public function __construct(mixed $path) {}
-    }
+        public function __toString(): string {}
 
-    class Rng {
         /**
-         * Generate a random ASCII alphanumeric string of the specified length.
-         *
-         * # Parameters
-         * - `len`: Number of characters to generate.
+         * Serializes to the normalized hostname string for `json_encode()`.
          *
          * # Returns
-         * - `String` containing random ASCII alphanumeric characters.
+         * - `string`: The normalized hostname.
          */
-        public static function alphanumeric(int $len): string {}
+        public function jsonSerialize(): string {}
 
         /**
-         * Generate a random ASCII alphabetic string of the specified length.
-         *
-         * # Parameters
-         * - `len`: Number of characters to generate.
+         * Returns the data stored by `serialize()`.
          *
          * # Returns
-         * - `String` containing random ASCII alphabetic characters.
+         * - `array`: `['hostname' => string]`.
          */
-        public static function alphabetic(int $len): string {}
+        public function __serialize(): array {}
 
         /**
-         * Generate a sequence of random bytes of the specified length.
+         * Restores an instance from `unserialize()` data.
          *
-         * # Parameters
-         * - `len`: Number of bytes to generate.
+         * The stored hostname is parsed again, so tampered cache entries cannot produce
+         * a Hostname that would not pass `Hostname::from()`.
          *
-         * # Returns
-         * - `string` containing `len` random bytes.
+         * # Parameters
+         * - `data`: `array` The array returned by `__serialize()`.
          *
          * # Exceptions
-         * - Throws an exception if the uniform distribution for `u8` cannot be created.
+         * - Throws an exception if the data is malformed or the hostname is invalid.
          */
-        public static function bytes(int $len): string {}
+        public function __unserialize(array $data) {}
 
         /**
-         * Generate a vector of random integers in the inclusive range `[low, high]`.
+         * Restores an instance exported with `var_export()`.
          *
          * # Parameters
-         * - `n`: Number of integers to generate.
-         * - `low`: Lower bound (inclusive).
-         * - `high`: Upper bound (inclusive).
-         *
-         * # Returns
-         * - `array[int; n]` — array of random values within bounds
+         * - `properties`: `array` `['hostname' => string]`, the `hostname` property.
          *
          * # Exceptions
-         * - Throws an exception if the range is invalid (e.g. `low > high`) or distribution creation fails.
+         * - Throws an exception if the data is malformed or the hostname is invalid.
          */
-        public static function ints(int $n, int $low, int $high): array {}
+        public static function __set_state(array $properties): \Hardened\Hostname {}
 
         /**
-         * Generate a single random integer in the inclusive range `[low, high]`.
+         * Constructs a new Hostname instance (alias for `from`).
          *
          * # Parameters
-         * - `low`: Lower bound (inclusive).
-         * - `high`: Upper bound (inclusive).
-         *
-         * # Returns
-         * - `int` — random value within bounds
+         * - `hostname`: The hostname to initialize.
          *
-         * # Exceptions
-         * - Throws an exception if the range is invalid (e.g. `low > high`) or distribution creation fails.
+         * # Errors
+         * Throws an exception if parsing the hostname fails.
          */
-        public static function int(int $low, int $high): int {}
+        public function __construct(mixed $hostname) {}
+    }
 
+    /**
+     * A reusable allow/deny policy for hosts and URLs.
+     *
+     * Configure it once (e.g. at bootstrap) and call `check()`/`assert()` per request,
+     * instead of passing the same lists to `Hostname::equalsAny()` every time.
+     */
+    class HostnamePolicy {
         /**
-         * Sample random Unicode characters (code points) from the given string.
+         * Sets the hosts that are allowed exactly.
          *
          * # Parameters
-         * - `len`: Number of characters to generate.
-         * - `chars`: A string whose `char` elements form the sampling pool.
-         *
-         * # Returns
-         * - `string` of length `len`, or an empty string if `chars` is empty.
+         * - `hosts`: `array` Hostnames or IP literals, e.g. `['api.example.com', '[::1]']`.
          *
          * # Exceptions
-         * - Throws an exception if `char` does not contain at least one Unicode character.
+         * - Throws an exception if an entry is not a valid hostname.
          */
-        public static function customUnicodeChars(int $len, string $chars): string {}
+        public function allowHosts(array $hosts) {}
 
         /**
-         * Sample random Unicode grapheme clusters from the given string.
+         * Sets the domains whose subdomains (and the domain itself) are allowed.
          *
-         * # Parameters
-         * - `len`: Number of graphemes to generate.
-         * - `chars`: A string whose grapheme clusters form the sampling pool.
+         * Matching is label-based: `example.com` allows `api.example.com`, but not `evilexample.com`.
          *
-         * # Returns
-         * - `string` of length `len`, or an empty string if `chars` is empty.
+         * # Parameters
+         * - `suffixes`: `array` Parent domains, e.g. `['example.com']`.
          *
          * # Exceptions
-         * - Throws an exception if `char` does not contain at least one Unicode grapheme.
+         * - Throws an exception if an entry is not a valid hostname.
          */
-        public static function customUnicodeGraphemes(int $len, string $chars): string {}
+        public function allowSuffixes(array $suffixes) {}
 
         /**
-         * Sample random ASCII characters from the specified character set.
+         * Sets networks that IP literal hosts must not belong to, even if otherwise allowed.
          *
          * # Parameters
-         * - `len`: Number of characters to generate.
-         * - `chars`: A string slice whose bytes form the sampling pool.
-         *
-         * # Returns
-         * - `String` of length `len`, or an empty string if `chars` is empty.
+         * - `cidrs`: `array` Networks, e.g. `['127.0.0.0/8', '10.0.0.0/8', 'fc00::/7']`.
          *
          * # Exceptions
-         * - Throws an exception if `char` does not contain at least one byte.
+         * - Throws an exception if a network is malformed.
          */
-        public static function customAscii(int $len, string $chars): string {}
+        public function denyCidrs(array $cidrs) {}
 
         /**
-         * Randomly selects one element from the given list.
+         * Sets the URL schemes that are allowed. An empty list allows any scheme.
          *
          * # Parameters
-         * - `choices`: PHP array of values to pick from.
-         *
-         * # Returns
-         * - `mixed|null`: A randomly chosen element, or `null` if `choices` is empty.
+         * - `schemes`: `array` Schemes, e.g. `['https']`.
          */
-        public static function choose(array $choices): mixed {}
+        public function allowSchemes(array $schemes) {}
 
         /**
-         * Randomly selects exactly `amount` distinct elements without replacement.
+         * Sets the ports that are allowed; URLs without an explicit port use the scheme's default.
+         * An empty list allows any port.
          *
          * # Parameters
-         * - `amount`: Number of elements to select.
-         * - `choices`: PHP array of values to pick from.
-         *
-         * # Returns
-         * - `mixed[]`: Array of selected values.
-         *
-         * # Exceptions
-         * - Throws `Exception` if `amount` is greater than the number of available choices.
+         * - `ports`: `array` Ports, e.g. `[443, 8443]`.
          */
-        public static function chooseMultiple(int $amount, array $choices): array {}
+        public function allowPorts(array $ports) {}
 
         /**
-         * Randomly selects one element from weighted choices.
+         * Returns true if the host or URL satisfies the policy.
+         *
+         * Scheme and port rules only apply to URLs (inputs containing `://`).
+         * Unparseable input is rejected.
          *
          * # Parameters
-         * - `choices`: PHP array of `[value, weight]` pairs, where `weight` is an integer.
+         * - `hostOrUrl`: `string` A hostname, IP literal or URL.
          *
          * # Returns
-         * - `array{0: mixed, 1: int}` Two‐element array: the chosen value and its weight.
-         *
-         * # Exceptions
-         * - Throws `Exception` if any entry is not a two‐element array or weight is not an integer.
-         * - Throws `Exception` if selection fails.
+         * - `bool` `true` if allowed.
          */
-        public static function chooseWeighted(array $choices): array {}
+        public function check(string $host_or_url): bool {}
 
         /**
-         * Randomly selects `amount` elements from weighted choices without replacement.
+         * Like `check()`, but throws an exception describing the violated rule.
          *
          * # Parameters
-         * - `amount`: Number of elements to select.
-         * - `choices`: PHP array of `[value, weight]` pairs, where `weight` is a float.
-         *
-         * # Returns
-         * - `mixed[]`: Array of selected values.
+         * - `hostOrUrl`: `string` A hostname, IP literal or URL.
          *
          * # Exceptions
-         * - Throws `Exception` if any entry is not a two‐element array or weight is not a float.
-         * - Throws `Exception` if selection fails.
+         * - Throws an exception if the input cannot be parsed or is not allowed.
          */
-        public static function chooseMultipleWeighted(int $amount, array $choices): array {}
+        public function assert(string $host_or_url) {}
 
+        /**
+         * Constructs an empty policy, which allows every host, scheme and port.
+         */
         public function __construct() {}
     }
 
     /**
-     * CSRF protection for your application.
+     * A web origin: scheme, host and port, as used by CORS, `postMessage` and redirect checks.
+     *
+     * Default ports are normalized away, so `https://example.com` and `https://example.com:443`
+     * are the same origin, while `http://example.com` and `https://example.com` are not.
      */
-    class CsrfProtection {
-        public static function generateKey(): string {}
-
+    class Origin {
         /**
-         * Verifies a CSRF token & cookie pair from PHP.
+         * Parses the origin of a URL.
          *
          * # Parameters
-         * - `token`: `string` Base64URL-encoded CSRF token from client.
-         * - `cookie`: `string` Base64URL-encoded CSRF cookie from client.
-         *
-         * # Returns
-         * - `void` on success.
+         * - `url`: `string` A URL, e.g. `https://Example.com:443/path`.
          *
          * # Exceptions
-         * - Throws `Exception` if decoding fails or the token–cookie pair is invalid/expired.
+         * - Throws an exception if the URL is invalid or has an opaque origin (`data:`, `file:`, ...).
          */
-        public function verifyToken(string $token, ?string $cookie): mixed {}
+        public static function from(mixed $url): \Hardened\Origin {}
 
         /**
-         * Returns the CSRF cookie string to send in PHP.
-         *
-         * # Returns
-         * - `string` Base64URL-encoded cookie suitable for `Set-Cookie`.
+         * Returns the lowercase scheme, e.g. `https`.
          */
-        public function cookie(): string {}
+        public function scheme(): string {}
 
         /**
-         * Returns the CSRF token string for PHP forms or headers.
-         *
-         * # Returns
-         * - `string` Base64URL-encoded token.
+         * Returns the normalized host.
          */
-        public function token(): string {}
+        public function host(): \Hardened\Hostname {}
 
         /**
-         * Sets the name of the CSRF cookie to use in PHP calls.
+         * Returns the effective port, with the scheme's default filled in (e.g. `443` for `https`).
+         */
+        public function port(): int {}
+
+        /**
+         * Returns true if the URL belongs to this origin.
          *
          * # Parameters
-         * - `cookieName`: `string` the new name for the CSRF cookie.
+         * - `url`: `string` The URL to compare, e.g. a redirect target or an `Origin` header.
          *
          * # Returns
-         * - `void`
+         * - `bool` `true` if scheme, host and port are all equal; URLs with opaque origins never match.
          */
-        public function setCookieName(string $cookie_name) {}
+        public function sameOrigin(mixed $url): bool {}
 
         /**
-         * Returns the configured CSRF cookie name.
+         * Serializes the origin as in the `Origin` header, omitting the default port.
          *
          * # Returns
-         * - `string` the name of the CSRF cookie.
+         * - `string` E.g. `https://example.com` or `http://example.com:8080`.
          */
-        public function cookieName(): string {}
+        public function __toString(): string {}
 
         /**
-         * Sends the CSRF cookie to the client via `setcookie()`
+         * Constructs a new Origin instance (alias for `from`).
          *
          * # Parameters
-         * - `expires`: `?int` UNIX timestamp when the cookie expires (defaults to `0`, a session cookie).
-         * - `path`: `?string` Cookie path (defaults to `"/"`).
-         * - `domain`: `?string` Cookie domain (defaults to the current host).
-         * - `secure`: `?bool` Send only over HTTPS (defaults to `false`).
-         * - `httponly`: `?bool` HTTP-only flag (defaults to `true`).
+         * - `url`: `string` A URL.
          *
          * # Exceptions
-         * - Throws `Exception` if the PHP `setcookie()` function cannot be invoked.
+         * - Throws an exception if the URL is invalid or has an opaque origin.
          */
-        public function sendCookie(?int $expires, ?string $path, ?string $domain, ?bool $secure, ?bool $httponly): mixed {}
+        public function __construct(mixed $url) {}
+    }
 
+    /**
+     * Validator for URLs the server is asked to fetch (webhooks, "import from URL", avatars...).
+     *
+     * Rejects the usual SSRF tricks: non-HTTP schemes, embedded credentials, unexpected ports,
+     * loopback/private IP literals in any notation (`0x7f.1`, `2130706433`, `[::ffff:127.0.0.1]`),
+     * local names such as `localhost`, and parser-confusing input such as backslashes.
+     *
+     * Host names are not resolved; pair this with `Hostname::resolvePinned()` at connection time.
+     */
+    class UrlSafety {
         /**
-         * Constructs a CSRF protection instance for PHP.
+         * Sets the allowed schemes.
          *
          * # Parameters
-         * - `key`: `string` Base64URL-encoded 32-byte secret key.
-         * - `ttl`: `int` token time-to-live in seconds.
-         * - `previousTokenValue`: `?string` optional Base64URL-encoded previous token for rotation.
-         *
-         * # Exceptions
-         * - Throws `Exception` if key decoding or length validation fails.
-         * - Throws `Exception` if token pair generation fails.
+         * - `schemes`: `array` Schemes, e.g. `['https']`.
          */
-        public function __construct(string $key, int $ttl, ?string $previous_token_value) {}
-    }
-}
-
-namespace Hardened\Sanitizers {
-    /**
-     * PHP class wrapping Ammonia's HTML sanitizer builder.
-     * Allows customized sanitization through PHP method calls.
-     */
-    class HtmlSanitizer {
-        const TRUNCATE_DEFAULT_ENDING = null;
+        public function allowSchemes(array $schemes) {}
 
         /**
-         * Constructs a sanitizer with default configuration.
+         * Sets the allowed ports; URLs without an explicit port use the scheme's default.
+         * An empty list allows any port.
          *
-         * # Returns
-         * - HtmlSanitizer A new sanitizer instance.
+         * # Parameters
+         * - `ports`: `array` Ports, e.g. `[443, 8443]`.
          */
-        public static function Default(): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function allowPorts(array $ports) {}
 
         /**
-         * Denies all relative URLs in attributes.
+         * Allows loopback/private IP literals and local host names such as `localhost`.
          *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * # Parameters
+         * - `allow`: `bool` `true` to accept them (e.g. in development).
          */
-        public function urlRelativeDeny(): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function allowPrivate(bool $allow) {}
 
         /**
-         * Checks whether a URL is valid according to the sanitizer’s configured
-         * URL scheme whitelist and relative-URL policy.
+         * Sets the maximum accepted URL length in bytes.
          *
          * # Parameters
-         * - `url`: The URL string to validate.
-         *
-         * # Returns
-         * - `bool`: `true` if the URL’s scheme is whitelisted, or if it is a relative URL
-         *   and relative URLs are permitted; `false` otherwise.
-         *
-         * # Exceptions
-         * - Throws `Exception` if the sanitizer is not in a valid state.
+         * - `length`: `int` Maximum length.
          */
-        public function isValidUrl(string $url): bool {}
+        public function maxLength(int $length) {}
 
         /**
-         * Passes through relative URLs unchanged.
+         * Validates a URL and returns its normalized form.
          *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
-         */
-        public function urlRelativePassthrough(): \Hardened\Sanitizers\HtmlSanitizer {}
-
-        /**
-         * Rewrites relative URLs using the given base URL.
+         * Normalization lowercases the scheme and host, converts IDNs to punycode, canonicalizes
+         * IP literals, drops default ports and trailing dots in the host.
          *
          * # Parameters
-         * - `base_url`: The base URL to resolve relative URLs against.
+         * - `url`: `string` The untrusted URL.
+         *
+         * # Returns
+         * - `string` The normalized URL.
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
-         * - Exception if `base_url` is not a valid URL.
+         * - Throws an exception whose code identifies the violated rule.
          */
-        public function urlRelativeRewriteWithBase(string $base_url): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function validate(string $url): string {}
 
         /**
-         * Rewrites relative URLs using a root URL and path prefix.
+         * Returns true if the URL passes `validate()`.
          *
          * # Parameters
-         * - `root`: The root URL string.
-         * - `path`: The URL path prefix.
+         * - `url`: `string` The untrusted URL.
          *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
-         * - Exception if `root` is not a valid URL.
+         * # Returns
+         * - `bool` `true` if the URL is safe to fetch.
          */
-        public function urlRelativeRewriteWithRoot(string $root, string $path): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function isSafe(string $url): bool {}
 
         /**
-         * Sets the `rel` attribute for generated `<a>` tags.
-         *
-         * # Parameters
-         * - `value`: Optional `rel` attribute value; `None` clears it.
-         *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * Constructs a validator that accepts public `http`/`https` URLs on ports 80 and 443,
+         * up to 2048 bytes long.
          */
-        public function linkRel(?string $value): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function __construct() {}
+    }
 
+    class Path implements \JsonSerializable {
         /**
-         * Overwrites the set of allowed tags.
-         *
-         * # Parameters
-         * - `tags`: An array of allowed tag names.
-         *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
-         * - Exception if `tags` is not an array.
+         * The normalized path, exposed as the read-only `path` property so that `var_export()`
+         * output carries it to `__set_state()`; `null` if it is not valid UTF-8.
          */
-        public function tags(array $tags): \Hardened\Sanitizers\HtmlSanitizer {}
+        public $path;
 
         /**
-         * Sets the tags whose contents will be completely removed from the output.
+         * Creates a new PathObj by lexically canonicalizing a given PHP value.
          *
          * # Parameters
-         * - `tags`: An array of allowed tag names.
+         * - `path`: The PHP value to convert to a filesystem path.
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
-         * - Exception if `tags` is not an array.
-         * - Adding tags which are whitelisted in tags or tag_attributes will cause a panic.
+         * - Throws an exception if conversion of `$path` to string fails.
          */
-        public function cleanContentTags(array $tags): \Hardened\Sanitizers\HtmlSanitizer {}
+        public static function from(mixed $path): \Hardened\Path {}
 
         /**
-         * Add additional blacklisted clean-content tags without overwriting old ones.
-         *
-         * Does nothing if the tag is already there.
+         * Checks if this path starts with the given prefix path.
          *
          * # Parameters
-         * - `tags`: An array of tag names to add.
+         * - `path`: The PHP value to compare against.
+         *
+         * # Returns
+         * `true` if this path starts with the given prefix.
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
-         * - Exception if `tags` is not an array.
+         * - Throws an exception if conversion from Zval to string fails.
          */
-        public function addCleanContentTags(array $tags): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function startsWith(mixed $path): bool {}
 
         /**
-         * Remove already-blacklisted clean-content tags.
-         *
-         * Does nothing if the tags aren’t blacklisted.
+         * Joins the given path onto this path and normalizes it.
          *
          * # Parameters
-         * - `tags`: An array of tag names to add.
+         * - `path`: The PHP value to join.
+         *
+         * # Returns
+         * A new PathObj representing the joined path.
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
-         * - Exception if `tags` is not an array.
+         * - Throws an exception if conversion from Zval to string fails.
          */
-        public function rmCleanContentTags(array $tags): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function join(mixed $path): \Hardened\Path {}
 
         /**
-         * Adds additional allowed tags to the existing whitelist.
+         * Joins the given path onto this path, normalizes it, and ensures it's a subpath.
          *
          * # Parameters
-         * - `tags`: An array of tag names to add.
+         * - `path`: string|Path
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
-         * - Exception if `tags` is not an array.
+         * - Throws an exception if `$path` is not a string nor Path
          */
-        public function addTags(array $tags): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function joinSubpath(mixed $path): \Hardened\Path {}
 
         /**
-         * Removes tags from the whitelist.
+         * Set the file name component of the path.
          *
          * # Parameters
-         * - `tags`: An array of tag names to remove.
-         *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - `fileName`: string
          */
-        public function rmTags(array $tags): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function setFileName(string $file_name): \Hardened\Path {}
 
         /**
-         * Adds allowed CSS classes for a specific tag.
+         * Set the file name component of the path.
          *
          * # Parameters
-         * - `tag`: A string tag name.
-         * - `classes`: An array of CSS class names.
-         *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - `extension`: string
          */
-        public function addAllowedClasses(string $tag, array $classes): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function setExtension(string $extension): \Hardened\Path {}
 
         /**
-         * Removes allowed CSS classes from a specific tag.
-         *
-         * # Parameters
-         * - `tag`: A string tag name.
-         * - `classes`: An array of CSS class names to remove.
-         *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * Get the last component of the path.
          */
-        public function rmAllowedClasses(string $tag, array $classes): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function fileName(): ?string {}
 
         /**
-         * Adds allowed attributes to a specific tag.
-         *
-         * # Parameters
-         * - `tag`: A string tag name.
-         * - `attributes`: An array of attribute names.
-         *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * Get the directory name (similar to `dirname()`).
          */
-        public function addTagAttributes(string $tag, array $attributes): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function parent(): ?\Hardened\Path {}
 
         /**
-         * Removes attributes from a specific tag.
+         * Converts the path to its string representation.
          *
-         * # Parameters
-         * - `tag`: A string tag name.
-         * - `classes`: An array of attribute names to remove.
+         * # Returns
+         * The string representation of the path.
          *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * # Errors
+         * Throws an exception if the path cannot be converted to a string.
          */
-        public function rmTagAttributes(string $tag, array $classes): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function __toString(): string {}
+
+        public function path(): string {}
 
         /**
-         * Adds generic attributes to all tags.
+         * Serializes to the normalized path string for `json_encode()`.
          *
-         * # Parameters
-         * - `attributes`: An array of attribute names to allow.
+         * # Returns
+         * - `string` The normalized path.
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
-         * - `Exception` if `attributes` is not an array.
+         * - Throws an exception if the path is not valid UTF-8.
          */
-        public function addGenericAttributes(array $attributes): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function jsonSerialize(): string {}
 
         /**
-         * Removes generic attributes from all tags.
+         * Returns the data stored by `serialize()`.
          *
-         * # Parameters
-         * - `attributes`: An array of attribute names to remove.
+         * # Returns
+         * - `array` `['path' => string]`.
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - Throws an exception if the path is not valid UTF-8.
          */
-        public function rmGenericAttributes(array $attributes): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function __serialize(): array {}
 
         /**
-         * Adds prefixes for generic attributes.
+         * Restores an instance from `unserialize()` data.
+         *
+         * The stored path is normalized and checked against the limits again, so a tampered
+         * cache entry cannot produce a Path that `Path::from()` would reject.
          *
          * # Parameters
-         * - `prefixes`: An array of prefixes to allow.
+         * - `data`: `array` The array returned by `__serialize()`.
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - Throws an exception if the data is malformed or exceeds the limits.
          */
-        public function addGenericAttributePrefixes(array $prefixes): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function __unserialize(array $data) {}
 
         /**
-         * Removes prefixes for generic attributes.
+         * Restores an instance exported with `var_export()`.
          *
          * # Parameters
-         * - `prefixes`: An array of prefixes to remove.
+         * - `properties`: `array` `['path' => string]`, the `path` property.
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - Throws an exception if the data is malformed or exceeds the limits.
          */
-        public function rmGenericAttributePrefixes(array $prefixes): \Hardened\Sanitizers\HtmlSanitizer {}
+        public static function __set_state(array $properties): \Hardened\Path {}
 
         /**
-         * Sanitizes the given HTML string, applying any configured attribute filter.
+         * Check if the path's extension is in the allowed list.
          *
          * # Parameters
-         * - `html`: The HTML content to sanitize.
+         * - `allowed`: PHP array of allowed extensions (strings, without leading dot), case-insensitive.
          *
          * # Returns
-         * - `String` The sanitized HTML.
-         *
-         * # Notes
-         * - If an attribute filter is set, it will be invoked for each attribute.
+         * - `bool` `true` if the file extension matches one of the allowed values.
          */
-        public function clean(string $html): string {}
+        public function validateExtension(array $allowed): bool {}
 
         /**
-         * Whitelists URL schemes (e.g., "http", "https").
-         *
-         * # Parameters
-         * - `schemes`: An array of scheme strings to allow.
+         * Check if the path's extension is a common image type.
          *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * # Returns
+         * - `bool` `true` if extension is in the `image` category
+         *   (by default `["png","jpg","jpeg","gif","webp","bmp","tiff","svg"]`).
          */
-        public function urlSchemes(array $schemes): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function validateExtensionImage(): bool {}
 
         /**
-         * Enables or disables HTML comment stripping.
-         *
-         * # Parameters
-         * - `strip`: `true` to strip comments; `false` to preserve them.
-         *    Comments are stripped by default.
+         * Check if the path's extension is a common video type.
          *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * # Returns
+         * - `bool` `true` if extension is in the `video` category
+         *   (by default `["mp4","mov","avi","mkv","webm","flv"]`).
          */
-        public function stripComments(bool $strip): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function validateExtensionVideo(): bool {}
 
         /**
-         * Returns whether HTML comments will be stripped.
+         * Check if the path's extension is a common audio type.
          *
          * # Returns
-         * - `bool`: `true` if comments will be stripped; `false` otherwise.
-         *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - `bool` `true` if extension is in the `audio` category
+         *   (by default `["mp3","wav","ogg","flac","aac"]`).
          */
-        public function willStripComments(): bool {}
+        public function validateExtensionAudio(): bool {}
 
         /**
-         * Prefixes all `id` attributes with the given string.
-         *
-         * # Parameters
-         * - `prefix`: Optional string prefix to apply.
+         * Check if the path's extension is a common document type.
          *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * # Returns
+         * - `bool` `true` if extension is in the `document` category
+         *   (by default `["pdf","doc","docx","xls","xlsx","ppt","pptx"]`).
          */
-        public function idPrefix(?string $prefix): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function validateExtensionDocument(): bool {}
 
         /**
-         * Filters CSS style properties allowed in `style` attributes.
+         * Defines (or redefines) a named extension category.
          *
-         * # Parameters
-         * - `props`: An array of CSS property names to allow.
+         * Redefining a built-in category (`image`, `video`, `audio`, `document`) also changes the
+         * corresponding `validateExtension*()` method, e.g. to exclude `svg` from `image`.
          *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * # Parameters
+         * - `name`: `string` Category name.
+         * - `extensions`: `array` Allowed extensions, without leading dot (case-insensitive).
          */
-        public function newFilterStyleProperties(array $props): \Hardened\Sanitizers\HtmlSanitizer {}
-
-        public function filterStyleProperties(array $props): \Hardened\Sanitizers\HtmlSanitizer {}
+        public static function defineExtensionCategory(string $name, array $extensions) {}
 
         /**
-         * Sets a single tag attribute value.
+         * Check if the path's extension belongs to a named category.
          *
          * # Parameters
-         * - `tag`: The tag name as A string.
-         * - `attribute`: The attribute name as A string.
-         * - `value`: The value to set.
+         * - `name`: `string` Category name (built-in or defined with `defineExtensionCategory()`).
+         *
+         * # Returns
+         * - `bool` `true` if the file extension is in the category.
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - Throws an exception if the category is not defined.
          */
-        public function setTagAttributeValue(string $tag, string $attribute, string $value): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function validateExtensionCategory(string $name): bool {}
 
         /**
-         * Returns the configured tags as a vector of strings.
+         * Removes all custom extension categories and restores the built-in ones.
+         */
+        public static function resetExtensionCategories() {}
+
+        /**
+         * Returns true if the path is absolute (starts with root or drive prefix).
          *
          * # Returns
-         * - `Vec<String>` The list of allowed tag names.
-         *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - `bool` `true` if the path is absolute.
          */
-        public function cloneTags(): array {}
+        public function isAbsolute(): bool {}
 
         /**
-         * Gets all configured clean-content tags.
+         * Returns true if the path is relative (not absolute).
          *
          * # Returns
-         * - `Vec<String>` The list of tags whose content is preserved.
-         *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - `bool` `true` if the path is relative.
          */
-        public function cloneCleanContentTags(): array {}
+        public function isRelative(): bool {}
 
         /**
-         * Bulk overwrites generic attributes.
+         * Returns true if the path tried to escape its base directory during normalization.
          *
-         * # Parameters
-         * - `attrs`: An array of attribute names.
+         * This is useful for detecting directory traversal attempts.
+         * A path "escapes" if it contains leading `..` components that would go above
+         * the starting directory, or if it starts with a root/prefix.
          *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * # Returns
+         * - `bool` `true` if the path escaped during normalization.
          */
-        public function genericAttributes(array $attrs): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function hasEscaped(): bool {}
 
         /**
-         * Bulk overwrites generic attribute prefixes.
+         * Returns the file extension, if any.
+         *
+         * # Returns
+         * - `?string` The extension without the leading dot, or `null` if none.
+         */
+        public function extension(): ?string {}
+
+        /**
+         * Configures the limits enforced by `from()`, `__construct()`, `join()` and `joinSubpath()`.
+         *
+         * Limits apply to the normalized path. Keys that are omitted keep their current value;
+         * `0` disables a limit.
          *
          * # Parameters
-         * - `prefixes`: An array of prefixes.
+         * - `limits`: `array` Any of:
+         *   - `maxLength`: `int` maximum path length in bytes (default `4096`).
+         *   - `maxComponentLength`: `int` maximum component length in bytes (default `255`).
+         *   - `maxDepth`: `int` maximum number of components (default `256`).
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - Throws an exception if a value is not a non-negative integer.
          */
-        public function genericAttributePrefixes(array $prefixes): \Hardened\Sanitizers\HtmlSanitizer {}
+        public static function setLimits(array $limits) {}
 
         /**
-         * Adds tag attribute values.
+         * Returns the currently enforced limits.
+         *
+         * # Returns
+         * - `array` `['maxLength' => int, 'maxComponentLength' => int, 'maxDepth' => int]`.
+         */
+        public static function getLimits(): array {}
+
+        /**
+         * Produces a storage-safe file name from an arbitrary user-supplied one.
+         *
+         * Directory separators and characters forbidden on Windows are replaced, control, bidi and
+         * zero-width characters and leading dots are stripped, whitespace runs are collapsed, Unicode is normalized, and the
+         * result is truncated (preserving the extension) to the maximum length.
          *
          * # Parameters
-         * - `tag`: A string tag name.
-         * - `attr`: A string attribute name.
-         * - `values`: An array of values to allow.
+         * - `name`: `string` The untrusted file name, e.g. from `$_FILES['upload']['name']`.
+         * - `options`: `?array` Optional settings:
+         *   - `maxLength`: `int` maximum length in bytes (default `255`).
+         *   - `replacement`: `string` substitute for separators and forbidden characters (default `"_"`).
+         *   - `normalization`: `string` one of `NFC`, `NFD`, `NFKC`, `NFKD`, `none` (default `NFC`).
+         *   - `windowsSafe`: `bool` enforce Windows naming rules (default `true`).
+         *   - `fallback`: `string` name returned when nothing is left (default `"file"`, shortened
+         *     to `maxLength`); it must come out of sanitization unchanged.
+         *
+         * # Returns
+         * - `string` The sanitized file name.
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - Throws an exception if an option has the wrong type or an invalid value.
          */
-        public function addTagAttributeValues(string $tag, string $attr, array $values): \Hardened\Sanitizers\HtmlSanitizer {}
+        public static function sanitizeFileName(string $name, ?array $options): string {}
 
         /**
-         * Removes tag attribute values.
+         * Returns true if every component of the path is safe to store on Windows/SMB filesystems.
          *
-         * # Parameters
-         * - `tag`: A string tag name.
-         * - `attr`: A string attribute name.
-         * - `values`: An array of values to remove.
+         * A component is rejected if it is a reserved device name (`CON`, `PRN`, `AUX`, `NUL`,
+         * `COM1`–`COM9`, `LPT1`–`LPT9`, with or without an extension), ends with a dot or a space,
+         * contains a `:` (NTFS alternate data stream, e.g. `file.txt:stream`), or contains
+         * a character Windows does not allow in file names (`<>"|?*` and control characters).
+         * Both `/` and `\` are treated as separators, regardless of the host platform.
          *
-         * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * # Returns
+         * - `bool` `true` if the path can be safely created on Windows.
          */
-        public function rmTagAttributeValues(string $tag, string $attr, array $values): \Hardened\Sanitizers\HtmlSanitizer {}
+        public function isWindowsSafe(): bool {}
 
         /**
-         * Gets a single tag attribute value setting.
+         * Computes this path relative to `base`, without any string manipulation.
+         *
+         * Both paths are compared component by component after lexical normalization,
+         * so `/var/www-data` is not considered to be inside `/var/www`.
          *
          * # Parameters
-         * - `tag`: The tag name as A string.
-         * - `attr`: The attribute name as A string.
+         * - `base`: `string|Path` The base directory.
          *
          * # Returns
-         * - `Option<String>` The configured value or `None` if unset.
+         * - `?Path` The relative path (empty if both paths are equal), or `null` if this path is not under `$base`.
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - Throws an exception if conversion of `$base` to string fails.
          */
-        public function getSetTagAttributeValue(string $tag, string $attr): ?string {}
+        public function relativeTo(mixed $base): ?\Hardened\Path {}
 
         /**
-         * Checks if URL relative policy is Deny.
+         * Returns true if this path is a strict ancestor of `other`.
+         *
+         * # Parameters
+         * - `other`: `string|Path` The path to test.
          *
          * # Returns
-         * - `bool` `true` if the policy is Deny.
+         * - `bool` `true` if `$other` lies beneath this path (a path is not its own ancestor).
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - Throws an exception if conversion of `$other` to string fails.
          */
-        public function isUrlRelativeDeny(): bool {}
+        public function isAncestorOf(mixed $other): bool {}
 
         /**
-         * Checks if URL relative policy is PassThrough.
+         * Returns the longest leading sequence of components shared with `other`.
+         *
+         * # Parameters
+         * - `other`: `string|Path` The path to compare against.
          *
          * # Returns
-         * - `bool` `true` if the policy is PassThrough.
+         * - `Path` The common prefix (empty if the paths have nothing in common).
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - Throws an exception if conversion of `$other` to string fails.
          */
-        public function isUrlRelativePassThrough(): bool {}
+        public function commonPrefix(mixed $other): \Hardened\Path {}
 
         /**
-         * Checks if URL relative policy is custom (Rewrite).
+         * Securely creates a new, empty temporary file and returns its path.
+         *
+         * Unlike `tempnam()`, the file is created with `O_CREAT | O_EXCL` and mode `0600`,
+         * so it can neither be pre-created by an attacker nor read by other users.
+         *
+         * # Parameters
+         * - `dir`: `?string` Directory to create the file in (defaults to the system temporary directory).
+         * - `prefix`: `string` File name prefix; must not contain directory separators.
          *
          * # Returns
-         * - `bool` `true` if a custom rewrite policy is set.
+         * - `Path` Path of the created file.
          *
          * # Exceptions
-         * - `Exception` if the sanitizer is not in a valid state.
+         * - Throws an exception if the prefix is invalid or the file cannot be created.
          */
-        public function isUrlRelativeCustom(): bool {}
+        public static function tempFile(?string $dir, string $prefix): \Hardened\Path {}
 
         /**
-         * Sets the attribute filter callback.
+         * Securely creates a new, empty temporary directory with mode `0700` and returns its path.
          *
          * # Parameters
-         * - `callable`: A PHP callable of signature `(string Element, string Attribute, string Value) -> string|null`.
+         * - `dir`: `?string` Parent directory (defaults to the system temporary directory).
+         * - `prefix`: `string` Directory name prefix; must not contain directory separators.
+         *
+         * # Returns
+         * - `Path` Path of the created directory.
          *
          * # Exceptions
-         * - None.
+         * - Throws an exception if the prefix is invalid or the directory cannot be created.
          */
-        public function attributeFilter(mixed $callable): \Hardened\Sanitizers\HtmlSanitizer {}
+        public static function tempDir(?string $dir, string $prefix): \Hardened\Path {}
 
         /**
-         * Sanitize and truncate the given HTML by extended grapheme clusters.
+         * Lists entries beneath this path that match a glob pattern.
          *
-         * This is a convenience wrapper that ensures no user-perceived character
-         * (including complex emoji or combined sequences) is split in half.
+         * Supports `*`, `?`, `[...]` and `**` (recursive). The pattern is always anchored at this
+         * path: absolute patterns and `..` components are rejected, and matches reached through
+         * symlinks pointing outside the base directory are skipped.
          *
          * # Parameters
-         * - `html`: Raw HTML string to sanitize and truncate.
-         * - `max_units`: Maximum number of Unicode extended grapheme clusters
-         *   to retain (including the `etc` suffix).
-         * - `etc`: Optional suffix (e.g., ellipsis) to join when truncation occurs. Default is …
+         * - `pattern`: `string` Relative glob pattern, e.g. `"*.jpg"` or `"**/*.pdf"`.
+         *
+         * # Returns
+         * - `Path[]` Matching paths, in alphabetical order.
          *
          * # Exceptions
-         * - Throws `Exception` if sanitization or truncation fails.
+         * - Throws an exception if the pattern is absolute, contains `..`, or is malformed.
          */
-        public function cleanAndTruncate(string $html, int $max, mixed $flags, ?string $etc): string {}
+        public function glob(string $pattern): array {}
 
         /**
-         * Constructs a sanitizer with default configuration.
+         * Returns true if the path exists on the filesystem.
+         *
+         * # Parameters
+         * - `followSymlinks`: `?bool` Whether a final symlink is followed (default `true`). When `false`,
+         *   a dangling symlink counts as existing.
          *
          * # Returns
-         * - HtmlSanitizer A new sanitizer instance.
+         * - `bool` `true` if the entry exists.
          */
-        public function __construct() {}
-    }
-
-    class SvgSanitizer {
-        const PRESET_STRICT = null;
-
-        const PRESET_STANDARD = null;
-
-        const PRESET_PERMISSIVE = null;
+        public function exists(?bool $follow_symlinks): bool {}
 
         /**
-         * Create a new SvgSanitizer with default (standard) settings
+         * Returns true if the path itself is a symbolic link (never follows it).
+         *
+         * # Returns
+         * - `bool` `true` if the entry is a symlink.
          */
-        public static function Default(): \Hardened\Sanitizers\SvgSanitizer {}
+        public function isSymlink(): bool {}
 
         /**
-         * Create a sanitizer with a named preset
+         * Returns true if the file name starts with a dot (a hidden entry on Unix-like systems).
+         *
+         * This is a lexical check and does not touch the filesystem.
+         *
+         * # Returns
+         * - `bool` `true` if the last component is hidden.
          */
-        public static function withPreset(string $preset_name): \Hardened\Sanitizers\SvgSanitizer {}
+        public function isHidden(): bool {}
 
         /**
-         * Static method for file-based bomb detection (throws on dangerous SVG)
+         * Returns the size of the entry in bytes.
+         *
+         * # Parameters
+         * - `followSymlinks`: `?bool` Whether a final symlink is followed (default `true`). When `false`,
+         *   the size of the link itself is returned.
+         *
+         * # Returns
+         * - `int` Size in bytes.
+         *
+         * # Exceptions
+         * - Throws an exception if the entry cannot be stat'ed.
          */
-        public static function defuse(string $path, ?int $max_dimension): mixed {}
+        public function size(?bool $follow_symlinks): int {}
 
         /**
-         * Sanitize SVG content string
+         * Returns the permission bits of the entry (e.g. `0o644`), including setuid/setgid/sticky.
+         *
+         * On non-Unix platforms only the read-only flag is available: `0o444` or `0o666`.
+         *
+         * # Parameters
+         * - `followSymlinks`: `?bool` Whether a final symlink is followed (default `true`).
+         *
+         * # Returns
+         * - `int` Permission bits.
+         *
+         * # Exceptions
+         * - Throws an exception if the entry cannot be stat'ed.
          */
-        public function clean(string $svg): string {}
+        public function permissions(?bool $follow_symlinks): int {}
 
         /**
-         * Sanitize SVG file and return cleaned content
+         * Detects the MIME type of the file from its magic bytes, ignoring the extension.
+         *
+         * Only the first 4 KiB are read. Unrecognized binary content is reported as
+         * `application/octet-stream`, unrecognized text as `text/plain`.
+         *
+         * # Returns
+         * - `string` The detected MIME type, e.g. `image/png`.
+         *
+         * # Exceptions
+         * - Throws an exception if the file cannot be read.
          */
-        public function cleanFile(string $path): string {}
+        public function detectMime(): string {}
 
         /**
-         * Check if SVG content is safe without modification
+         * Checks that the file's content agrees with its extension.
+         *
+         * Catches e.g. a PHP script or an executable uploaded as `avatar.jpg`.
+         *
+         * # Returns
+         * - `bool` `true` if the extension is one usually used for the detected content type;
+         *   `false` if there is no extension or the content is unrecognized binary.
+         *
+         * # Exceptions
+         * - Throws an exception if the file cannot be read.
          */
-        public function isSafe(string $svg): bool {}
+        public function matchesExtension(): bool {}
 
         /**
-         * Check if SVG file is safe without modification
+         * Applies Unicode normalization to the whole path.
+         *
+         * Visually identical names can have different byte sequences (e.g. `é` as U+00E9 vs.
+         * `e` + U+0301), which defeats comparisons and allow-lists. Normalizing first makes them equal.
+         *
+         * # Parameters
+         * - `form`: `string` One of `NFC`, `NFD`, `NFKC`, `NFKD` (case-insensitive).
+         *
+         * # Returns
+         * - `Path` A new, normalized Path.
+         *
+         * # Exceptions
+         * - Throws an exception if `$form` is not a known normalization form, or the path is not valid UTF-8.
          */
-        public function isSafeFile(string $path): bool {}
+        public function normalizeUnicode(string $form): \Hardened\Path {}
 
         /**
-         * Set allowed SVG elements (overwrites defaults)
+         * Returns true if any path component contains bidirectional control or zero-width characters.
+         *
+         * Such characters are invisible, and are used to disguise file names: `invoice\u{202E}fdp.exe`
+         * is displayed as `invoiceexe.pdf`.
+         *
+         * # Returns
+         * - `bool` `true` if an RTL/LTR override, embedding, isolate, mark, or zero-width character is present.
          */
-        public function allowElements(array $elements): \Hardened\Sanitizers\SvgSanitizer {}
+        public function containsBidiOrZeroWidth(): bool {}
 
         /**
-         * Add elements to the allowlist
+         * Constructs a new PathObj instance (alias for `from`).
+         *
+         * # Parameters
+         * - `path`: The PHP value to convert to a filesystem path.
+         *
+         * # Exceptions
+         * - Throws an exception if conversion from Zval to string fails.
          */
-        public function addAllowedElements(array $elements): \Hardened\Sanitizers\SvgSanitizer {}
+        public function __construct(mixed $path) {}
+    }
 
+    class Rng {
         /**
-         * Remove elements from the allowlist
+         * Create a deterministic generator for tests; see `SeededRng`.
+         *
+         * # Parameters
+         * - `seed`: `string` Any string; equal seeds give equal sequences.
+         *
+         * # Returns
+         * - `SeededRng` A **non-cryptographic** generator. Never use it for real secrets.
          */
-        public function removeElements(array $elements): \Hardened\Sanitizers\SvgSanitizer {}
+        public static function seeded(string $seed): \Hardened\SeededRng {}
 
         /**
-         * Set allowed attributes (overwrites defaults)
+         * Generate a random ASCII alphanumeric string of the specified length.
+         *
+         * # Parameters
+         * - `len`: Number of characters to generate.
+         *
+         * # Returns
+         * - `String` containing random ASCII alphanumeric characters.
          */
-        public function allowAttributes(array $attributes): \Hardened\Sanitizers\SvgSanitizer {}
+        public static function alphanumeric(int $len): string {}
 
         /**
-         * Add attributes to the allowlist
+         * Generate a random ASCII alphabetic string of the specified length.
+         *
+         * # Parameters
+         * - `len`: Number of characters to generate.
+         *
+         * # Returns
+         * - `String` containing random ASCII alphabetic characters.
          */
-        public function addAllowedAttributes(array $attributes): \Hardened\Sanitizers\SvgSanitizer {}
+        public static function alphabetic(int $len): string {}
 
         /**
-         * Remove attributes from the allowlist
+         * Generate a sequence of random bytes of the specified length.
+         *
+         * # Parameters
+         * - `len`: Number of bytes to generate.
+         *
+         * # Returns
+         * - `string` containing `len` random bytes.
+         *
+         * # Exceptions
+         * - Throws an exception if the uniform distribution for `u8` cannot be created.
          */
-        public function removeAttributes(array $attributes): \Hardened\Sanitizers\SvgSanitizer {}
+        public static function bytes(int $len): string {}
 
         /**
-         * Set maximum allowed dimension (width/height/viewBox)
+         * Write random bytes straight to a new file, e.g. a key file, without returning them
+         * as a PHP string that could end up in logs, core dumps or swap.
+         *
+         * The file must not exist yet; it is created with `mode` (further restricted by the
+         * umask) and synced to disk before returning. On failure no partial file is left behind.
+         *
+         * # Parameters
+         * - `path`: `string` Path of the file to create.
+         * - `length`: `int` Number of bytes to write.
+         * - `mode`: `int` Permission bits of the new file. Default: `0600`.
+         *
+         * # Exceptions
+         * - Throws an exception if `mode` is not a permission mask, the file already exists,
+         *   or it cannot be written.
          */
-        public function setMaxDimension(int $max): \Hardened\Sanitizers\SvgSanitizer {}
+        public static function bytesToFile(string $path, int $length, ?int $mode) {}
 
         /**
-         * Set maximum nesting depth
+         * Generate a vector of random integers in the inclusive range `[low, high]`.
+         *
+         * # Parameters
+         * - `n`: Number of integers to generate.
+         * - `low`: Lower bound (inclusive).
+         * - `high`: Upper bound (inclusive).
+         *
+         * # Returns
+         * - `array[int; n]` — array of random values within bounds
+         *
+         * # Exceptions
+         * - Throws an exception if the range is invalid (e.g. `low > high`) or distribution creation fails.
          */
-        public function setMaxNestingDepth(int $max): \Hardened\Sanitizers\SvgSanitizer {}
+        public static function ints(int $n, int $low, int $high): array {}
 
         /**
-         * Enable/disable blocking of external references (http/https URLs)
+         * Generate a single random integer in the inclusive range `[low, high]`.
+         *
+         * # Parameters
+         * - `low`: Lower bound (inclusive).
+         * - `high`: Upper bound (inclusive).
+         *
+         * # Returns
+         * - `int` — random value within bounds
+         *
+         * # Exceptions
+         * - Throws an exception if the range is invalid (e.g. `low > high`) or distribution creation fails.
          */
-        public function blockExternalReferences(bool $block): \Hardened\Sanitizers\SvgSanitizer {}
+        public static function int(int $low, int $high): int {}
 
         /**
-         * Enable/disable blocking of data: URIs
+         * Generate a uniform random integer in the inclusive range `[min, max]`.
+         *
+         * Uses rejection sampling, so unlike `mt_rand() % n` or `rand($min, $max)` it has no
+         * modulo bias and draws from the CSPRNG.
+         *
+         * # Parameters
+         * - `min`: `int` Lower bound (inclusive).
+         * - `max`: `int` Upper bound (inclusive).
+         *
+         * # Returns
+         * - `int` A value within bounds.
+         *
+         * # Exceptions
+         * - Throws an exception if `min > max`.
+         */
+        public static function intBetween(int $min, int $max): int {}
+
+        /**
+         * Generate a uniform random float in `[0, 1)`.
+         *
+         * # Returns
+         * - `float` A value with 53 bits of randomness.
+         */
+        public static function float(): float {}
+
+        /**
+         * Generate a uniform random float in `[min, max)`.
+         *
+         * # Parameters
+         * - `min`: `float` Lower bound (inclusive).
+         * - `max`: `float` Upper bound (exclusive); equal bounds return `min`.
+         *
+         * # Returns
+         * - `float` A value within bounds.
+         *
+         * # Exceptions
+         * - Throws an exception if a bound is `INF` or `NAN`, or `min > max`.
+         */
+        public static function floatBetween(float $min, float $max): float {}
+
+        /**
+         * Return `true` with the given probability.
+         *
+         * # Parameters
+         * - `probability`: `?float` Chance of `true`, from `0.0` (never) to `1.0` (always);
+         *   default `0.5`.
+         *
+         * # Returns
+         * - `bool`
+         *
+         * # Exceptions
+         * - Throws an exception if `probability` is outside `[0, 1]`.
+         */
+        public static function bool(?float $probability): bool {}
+
+        /**
+         * Sample random Unicode characters (code points) from the given string.
+         *
+         * # Parameters
+         * - `len`: Number of characters to generate.
+         * - `chars`: A string whose `char` elements form the sampling pool.
+         *
+         * # Returns
+         * - `string` of length `len`, or an empty string if `chars` is empty.
+         *
+         * # Exceptions
+         * - Throws an exception if `char` does not contain at least one Unicode character.
+         */
+        public static function customUnicodeChars(int $len, string $chars): string {}
+
+        /**
+         * Sample random Unicode grapheme clusters from the given string.
+         *
+         * # Parameters
+         * - `len`: Number of graphemes to generate.
+         * - `chars`: A string whose grapheme clusters form the sampling pool.
+         *
+         * # Returns
+         * - `string` of length `len`, or an empty string if `chars` is empty.
+         *
+         * # Exceptions
+         * - Throws an exception if `char` does not contain at least one Unicode grapheme.
+         */
+        public static function customUnicodeGraphemes(int $len, string $chars): string {}
+
+        /**
+         * Sample random ASCII characters from the specified character set.
+         *
+         * # Parameters
+         * - `len`: Number of characters to generate.
+         * - `chars`: A string slice whose bytes form the sampling pool.
+         *
+         * # Returns
+         * - `String` of length `len`, or an empty string if `chars` is empty.
+         *
+         * # Exceptions
+         * - Throws an exception if `char` does not contain at least one byte.
+         */
+        public static function customAscii(int $len, string $chars): string {}
+
+        /**
+         * Generate a random (version 4) UUID.
+         *
+         * # Returns
+         * - `string` Canonical form, e.g. `"3f9c1e2a-7b4d-4c8e-9a1f-0d2e3c4b5a69"`.
+         */
+        public static function uuid4(): string {}
+
+        /**
+         * Generate a random (version 4) UUID in binary form.
+         *
+         * # Returns
+         * - `string` 16 bytes.
+         */
+        public static function uuid4Binary(): string {}
+
+        /**
+         * Generate a time-ordered (version 7) UUID, which sorts by creation time and makes a
+         * better database key than version 4.
+         *
+         * # Returns
+         * - `string` Canonical form, e.g. `"01928f3a-6c1e-7d2b-8f4a-1b2c3d4e5f60"`.
+         */
+        public static function uuid7(): string {}
+
+        /**
+         * Generate a time-ordered (version 7) UUID in binary form.
+         *
+         * # Returns
+         * - `string` 16 bytes.
+         */
+        public static function uuid7Binary(): string {}
+
+        /**
+         * Generate a ULID: a 26-character, lexicographically sortable identifier.
+         *
+         * ULIDs generated in the same millisecond by the same thread are still strictly
+         * increasing.
+         *
+         * # Returns
+         * - `string` e.g. `"01J9ZQ3XK5V7T2M8N4P6R0S1W3"`.
+         *
+         * # Exceptions
+         * - Throws an exception if the random component overflows within one millisecond.
+         */
+        public static function ulid(): string {}
+
+        /**
+         * Check whether a string is a well-formed ULID (case-insensitive).
+         *
+         * # Parameters
+         * - `ulid`: `string` The value to check.
+         *
+         * # Returns
+         * - `bool` `true` if it is a valid ULID.
+         */
+        public static function isValidUlid(string $ulid): bool {}
+
+        /**
+         * Extract the creation time of a ULID.
+         *
+         * # Parameters
+         * - `ulid`: `string` The ULID.
+         *
+         * # Returns
+         * - `int` Milliseconds since the UNIX epoch.
+         *
+         * # Exceptions
+         * - Throws an exception if the ULID is malformed.
+         */
+        public static function ulidTimestamp(string $ulid): int {}
+
+        /**
+         * Convert a ULID to its 16-byte binary form.
+         *
+         * # Parameters
+         * - `ulid`: `string` The ULID.
+         *
+         * # Returns
+         * - `string` 16 bytes, big-endian.
+         *
+         * # Exceptions
+         * - Throws an exception if the ULID is malformed.
+         */
+        public static function ulidToBinary(string $ulid): string {}
+
+        /**
+         * Generate a password that satisfies a character-class policy.
+         *
+         * # Parameters
+         * - `length`: `int` Number of characters.
+         * - `policy`: `?array` Options, all optional:
+         *   - `lower`, `upper`, `digits`: `bool` Require that class (default `true`).
+         *   - `symbols`: `bool` Require a symbol (default `false`).
+         *   - `symbolSet`: `string` Symbols to use (default `!#$%&*+-=?@^_`).
+         *   - `excludeAmbiguous`: `bool` Leave out `0 O o 1 l I |` (default `false`).
+         *
+         * # Returns
+         * - `string` A password containing at least one character of every enabled class.
+         *
+         * # Exceptions
+         * - Throws an exception if the policy has unknown keys, enables no class, or cannot be
+         *   satisfied in `length` characters.
+         */
+        public static function password(int $length, ?array $policy): string {}
+
+        /**
+         * Generate a random token as text, e.g. for session IDs, API keys or short codes.
+         *
+         * # Parameters
+         * - `bytes`: `int` Bytes of randomness (16 or more for secrets).
+         * - `encoding`: `?string` One of `"hex"`, `"base64url"` (default), `"base32"`, `"base58"`
+         *   or `"crockford"`.
+         * - `checksum`: `?bool` Append a Crockford check symbol, so typos in hand-typed codes are
+         *   detected (`"crockford"` only; default `false`).
+         *
+         * # Returns
+         * - `string` The encoded token.
+         *
+         * # Exceptions
+         * - Throws an exception if the encoding is unknown, or a checksum is requested for an
+         *   encoding other than `"crockford"`.
+         */
+        public static function token(int $bytes, ?string $encoding, ?bool $checksum): string {}
+
+        /**
+         * Generate a zero-padded numeric one-time code, e.g. for SMS or email verification.
+         *
+         * Every code of the given length is equally likely (no modulo bias).
+         *
+         * # Parameters
+         * - `digits`: `int` Code length, 1 to 19.
+         * - `excludeTrivial`: `?bool` Never return a repeated digit or a run such as `123456` or
+         *   `987654` (codes of 3 or more digits; default `false`).
+         *
+         * # Returns
+         * - `string` e.g. `"049731"`.
+         *
+         * # Exceptions
+         * - Throws an exception if `digits` is out of range.
+         */
+        public static function numericCode(int $digits, ?bool $exclude_trivial): string {}
+
+        /**
+         * Compare a submitted code with the expected one in constant time, so response timing
+         * does not reveal how many leading digits were right.
+         *
+         * # Parameters
+         * - `expected`: `string` The code that was sent.
+         * - `given`: `string` The code the user entered.
+         *
+         * # Returns
+         * - `bool` `true` if they are equal.
+         */
+        public static function codeEquals(string $expected, string $given): bool {}
+
+        /**
+         * Generate a diceware-style passphrase.
+         *
+         * With the built-in EFF large wordlist every word adds about 12.9 bits of entropy,
+         * so 6 words give roughly 77 bits.
+         *
+         * # Parameters
+         * - `words`: `int` Number of words.
+         * - `separator`: `?string` Placed between words (default `"-"`).
+         * - `wordlist`: `?array` Custom list of words; duplicates and empty strings are ignored.
+         *
+         * # Returns
+         * - `string` e.g. `"exhale-gravy-nutmeg-unsaid-wobbly-tulip"`.
+         *
+         * # Exceptions
+         * - Throws an exception if the custom wordlist has fewer than two distinct words.
+         */
+        public static function passphrase(int $words, ?string $separator, ?array $wordlist): string {}
+
+        /**
+         * Shuffle a list with Fisher–Yates and the CSPRNG, unlike PHP's `shuffle()`.
+         *
+         * # Parameters
+         * - `items`: `array` Values to shuffle; keys are discarded.
+         *
+         * # Returns
+         * - `array` The same values as a list, in uniformly random order.
+         */
+        public static function shuffle(array $items): array {}
+
+        /**
+         * Draw `k` distinct items without replacement, e.g. for raffles or audit sampling.
+         *
+         * # Parameters
+         * - `items`: `array` Values to draw from; keys are discarded.
+         * - `k`: `int` Number of items to draw.
+         *
+         * # Returns
+         * - `array` `k` values as a list, in random order.
+         *
+         * # Exceptions
+         * - Throws an exception if `k` exceeds the number of items.
+         */
+        public static function sample(array $items, int $k): array {}
+
+        /**
+         * Pick a key with probability proportional to its weight, e.g. for A/B assignment.
+         *
+         * # Parameters
+         * - `weights`: `array<int|string, int>` Non-negative integer weights by key,
+         *   e.g. `['control' => 90, 'variant' => 10]`.
+         *
+         * # Returns
+         * - `int|string` The chosen key.
+         *
+         * # Exceptions
+         * - Throws an exception if a weight is not a non-negative integer, all weights are
+         *   zero, or their sum overflows.
+         */
+        public static function weightedChoice(array $weights): mixed {}
+
+        /**
+         * Randomly selects one element from the given list.
+         *
+         * # Parameters
+         * - `choices`: PHP array of values to pick from.
+         *
+         * # Returns
+         * - `mixed|null`: A randomly chosen element, or `null` if `choices` is empty.
+         */
+        public static function choose(array $choices): mixed {}
+
+        /**
+         * Randomly selects exactly `amount` distinct elements without replacement.
+         *
+         * # Parameters
+         * - `amount`: Number of elements to select.
+         * - `choices`: PHP array of values to pick from.
+         *
+         * # Returns
+         * - `mixed[]`: Array of selected values.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `amount` is greater than the number of available choices.
+         */
+        public static function chooseMultiple(int $amount, array $choices): array {}
+
+        /**
+         * Randomly selects one element from weighted choices.
+         *
+         * # Parameters
+         * - `choices`: PHP array of `[value, weight]` pairs, where `weight` is an integer.
+         *
+         * # Returns
+         * - `array{0: mixed, 1: int}` Two‐element array: the chosen value and its weight.
+         *
+         * # Exceptions
+         * - Throws `Exception` if any entry is not a two‐element array or weight is not an integer.
+         * - Throws `Exception` if selection fails.
+         */
+        public static function chooseWeighted(array $choices): array {}
+
+        /**
+         * Randomly selects `amount` elements from weighted choices without replacement.
+         *
+         * # Parameters
+         * - `amount`: Number of elements to select.
+         * - `choices`: PHP array of `[value, weight]` pairs, where `weight` is a float.
+         *
+         * # Returns
+         * - `mixed[]`: Array of selected values.
+         *
+         * # Exceptions
+         * - Throws `Exception` if any entry is not a two‐element array or weight is not a float.
+         * - Throws `Exception` if selection fails.
+         */
+        public static function chooseMultipleWeighted(int $amount, array $choices): array {}
+    }
+
+    /**
+     * A deterministic, **non-cryptographic** generator for reproducible tests.
+     *
+     * The seed is hashed with SHA-256 into the key of a ChaCha12 stream (`StdRng`), so equal
+     * seeds produce equal outputs with the same build of the extension. Anyone who knows the
+     * seed can predict every value: never use it for real passwords, tokens or keys.
+     */
+    class SeededRng {
+        /**
+         * Always `false`: outputs are reproducible from the seed.
+         *
+         * # Returns
+         * - `bool`
+         */
+        public function isCryptographic(): bool {}
+
+        /**
+         * Like `Rng::alphanumeric()`.
+         */
+        public function alphanumeric(int $len): string {}
+
+        /**
+         * Like `Rng::bytes()`.
+         */
+        public function bytes(int $len): string {}
+
+        /**
+         * Like `Rng::intBetween()`.
+         *
+         * # Exceptions
+         * - Throws an exception if `min > max`.
+         */
+        public function intBetween(int $min, int $max): int {}
+
+        /**
+         * Like `Rng::float()`.
+         */
+        public function float(): float {}
+
+        /**
+         * Like `Rng::floatBetween()`.
+         *
+         * # Exceptions
+         * - Throws an exception if a bound is `INF` or `NAN`, or `min > max`.
+         */
+        public function floatBetween(float $min, float $max): float {}
+
+        /**
+         * Like `Rng::bool()`.
+         *
+         * # Exceptions
+         * - Throws an exception if `probability` is outside `[0, 1]`.
+         */
+        public function bool(?float $probability): bool {}
+
+        /**
+         * Like `Rng::numericCode()`.
+         *
+         * # Exceptions
+         * - Throws an exception if `digits` is out of range.
+         */
+        public function numericCode(int $digits, ?bool $exclude_trivial): string {}
+
+        /**
+         * Like `Rng::uuid4()`.
+         */
+        public function uuid4(): string {}
+
+        /**
+         * Like `Rng::password()`.
+         *
+         * # Exceptions
+         * - Throws an exception if the policy is invalid or cannot be satisfied.
+         */
+        public function password(int $length, ?array $policy): string {}
+
+        /**
+         * Like `Rng::passphrase()`.
+         *
+         * # Exceptions
+         * - Throws an exception if the custom wordlist has fewer than two distinct words.
+         */
+        public function passphrase(int $words, ?string $separator, ?array $wordlist): string {}
+
+        /**
+         * Like `Rng::token()`.
+         *
+         * # Exceptions
+         * - Throws an exception if the encoding is unknown or does not support a checksum.
+         */
+        public function token(int $bytes, ?string $encoding, ?bool $checksum): string {}
+
+        /**
+         * Like `Rng::shuffle()`.
+         */
+        public function shuffle(array $items): array {}
+
+        /**
+         * Like `Rng::sample()`.
+         *
+         * # Exceptions
+         * - Throws an exception if `k` exceeds the number of items.
+         */
+        public function sample(array $items, int $k): array {}
+
+        /**
+         * Like `Rng::weightedChoice()`.
+         *
+         * # Exceptions
+         * - Throws an exception if a weight is invalid or none is positive.
+         */
+        public function weightedChoice(array $weights): mixed {}
+
+        /**
+         * Constructs a generator from `seed`.
+         *
+         * # Parameters
+         * - `seed`: `string` Any string; equal seeds give equal sequences.
+         */
+        public function __construct(string $seed) {}
+    }
+
+    /**
+     * CSRF protection for your application.
+     */
+    class CsrfProtection {
+        public static function generateKey(): string {}
+
+        /**
+         * Verifies a CSRF token & cookie pair from PHP.
+         *
+         * # Parameters
+         * - `token`: `string` Base64URL-encoded CSRF token from client.
+         * - `cookie`: `string` Base64URL-encoded CSRF cookie from client.
+         *
+         * # Returns
+         * - `void` on success.
+         *
+         * # Exceptions
+         * - Throws `Exception` if decoding fails or the token–cookie pair is invalid/expired.
+         */
+        public function verifyToken(string $token, ?string $cookie) {}
+
+        /**
+         * Sets the lifetime of stateless, per-action, double-submit and claims tokens.
+         *
+         * The constructor's token–cookie pair keeps the lifetime it was generated with.
+         *
+         * # Parameters
+         * - `seconds`: `int` Token lifetime.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `seconds` is negative.
+         */
+        public function setTtl(int $seconds) {}
+
+        /**
+         * Returns the lifetime of time-bound tokens.
+         *
+         * # Returns
+         * - `int` Seconds.
+         */
+        public function ttl(): int {}
+
+        /**
+         * Sets how many seconds a token may be early or late, to tolerate clock drift between nodes.
+         *
+         * # Parameters
+         * - `seconds`: `int` Tolerance, `0` by default.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `seconds` is negative.
+         */
+        public function setClockSkew(int $seconds) {}
+
+        /**
+         * Returns when a stateless, per-action, double-submit or claims token expires,
+         * so an API can tell a SPA when to fetch a new one.
+         *
+         * This is an inspector, not a check: always verify the token before acting on it.
+         *
+         * # Parameters
+         * - `token`: `string` The token.
+         *
+         * # Returns
+         * - `?int` UNIX timestamp, or `null` if the token is not recognized.
+         */
+        public function expiresAt(string $token): ?int {}
+
+        /**
+         * Binds time-bound tokens to a session: the session id (and optionally a user agent hash)
+         * is mixed into their keys, so a token stolen from one session fails in any other,
+         * even before it expires.
+         *
+         * Applies to tokens issued and verified after the call; the constructor's token–cookie
+         * pair is not affected.
+         *
+         * # Parameters
+         * - `sessionId`: `string` The session identifier, e.g. `session_id()`.
+         * - `userAgentHash`: `?string` Optional digest of the user agent, to also tie tokens to the browser.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `sessionId` is empty.
+         */
+        public function bindTo(string $session_id, ?string $user_agent_hash) {}
+
+        /**
+         * Sets the storage used by `markUsed()` and `isUsed()` to enforce single-use tokens,
+         * e.g. for payment confirmation.
+         *
+         * The callbacks receive the SHA-256 hex digest of a token, never the token itself:
+         * - `markUsed(string $tokenHash, int $ttl): bool` must atomically record the hash for `$ttl`
+         *   seconds and return `true` only if it was not recorded yet
+         *   (e.g. `$redis->set("csrf:$tokenHash", 1, ['nx', 'ex' => $ttl])`).
+         * - `isUsed(string $tokenHash): bool` returns whether the hash is recorded.
+         *
+         * # Parameters
+         * - `markUsed`: `callable` Records a hash.
+         * - `isUsed`: `callable` Looks a hash up.
+         */
+        public function setReplayStore(mixed $mark_used, mixed $is_used) {}
+
+        /**
+         * Uses a `NonceStore` for `markUsed()` and `isUsed()` instead of callbacks.
+         *
+         * The store is shared, not copied: tokens marked here are consumed nonces of `store`, and
+         * a backend set on it later with `NonceStore::setBackend()` is used here too. Like any
+         * nonce, a token only reaches the backend as its SHA-256 digest.
+         *
+         * # Parameters
+         * - `store`: `NonceStore`
+         */
+        public function setReplayNonceStore(\Hardened\NonceStore $store) {}
+
+        /**
+         * Marks a token as used. Call it after verifying the token and act only if it returns `true`.
+         *
+         * The hash is kept until the token expires, after which verification rejects it anyway.
+         *
+         * # Parameters
+         * - `token`: `string` A verified token.
+         *
+         * # Returns
+         * - `bool` `true` on first use, `false` if the token was already used (a replay).
+         *
+         * # Exceptions
+         * - Throws `Exception` if no replay store is set or the store fails.
+         */
+        public function markUsed(string $token): bool {}
+
+        /**
+         * Returns true if `markUsed()` has already been called for this token.
+         *
+         * # Parameters
+         * - `token`: `string` The token.
+         *
+         * # Returns
+         * - `bool` `true` if the token was used.
+         *
+         * # Exceptions
+         * - Throws `Exception` if no replay store is set or the store fails.
+         */
+        public function isUsed(string $token): bool {}
+
+        /**
+         * Issues a stateless token: a signed issue time and nonce, optionally bound to a scope.
+         *
+         * No cookie or session storage is involved, so any node that shares the key can verify it
+         * (see `verifyStatelessToken()`), which suits deployments without sticky sessions.
+         *
+         * # Parameters
+         * - `scope`: `?string` Optional scope (e.g. a form name) the token is only valid for.
+         *
+         * # Returns
+         * - `string` Base64URL-encoded token.
+         */
+        public function statelessToken(?string $scope): string {}
+
+        /**
+         * Verifies a token issued by `statelessToken()`.
+         *
+         * # Parameters
+         * - `token`: `string` Token from the client.
+         * - `maxAge`: `?int` Maximum token age in seconds (defaults to the constructor's `ttl`).
+         * - `scope`: `?string` The scope the token must have been issued for.
+         *
+         * # Returns
+         * - `void` on success.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the token is malformed, was signed for another scope or key, or has expired.
+         */
+        public function verifyStatelessToken(string $token, ?int $max_age, ?string $scope) {}
+
+        /**
+         * Issues a token that is only valid for one action, e.g. `"delete-account"`.
+         *
+         * The action is mixed into the signing key, so a token leaked from one form cannot be
+         * replayed against another.
+         *
+         * # Parameters
+         * - `action`: `string` The action name.
+         *
+         * # Returns
+         * - `string` Base64URL-encoded token.
+         */
+        public function tokenFor(string $action): string {}
+
+        /**
+         * Verifies a token issued by `tokenFor()` for the same action, no older than `ttl`.
+         *
+         * # Parameters
+         * - `action`: `string` The action being performed.
+         * - `token`: `string` Token from the client.
+         *
+         * # Returns
+         * - `void` on success.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the token is malformed, was issued for another action, or has expired.
+         */
+        public function verifyFor(string $action, string $token) {}
+
+        /**
+         * Starts a double-submit cookie flow: sends a signed token as a cookie and returns the same
+         * token to embed in the form or an `X-CSRF-Token` header.
+         *
+         * By default the cookie is named `"__Host-" . cookieName()` and is `Secure`, `HttpOnly`
+         * and `SameSite=Strict`; the prefix keeps subdomains from planting their own token.
+         *
+         * # Parameters
+         * - `cookieOptions`: `?array` Overrides in `setcookie()` format: `expires`, `path`, `domain`,
+         *   `secure`, `httponly`, `samesite`. Changing `path`, `domain` or `secure` weakens the prefix
+         *   to `__Secure-` or none.
+         *
+         * # Returns
+         * - `string` The token to embed.
+         *
+         * # Exceptions
+         * - Throws `Exception` if an option is invalid or `header()` cannot be called.
+         */
+        public function issueCookieToken(?array $cookie_options): string {}
+
+        /**
+         * Verifies a double-submit pair issued by `issueCookieToken()`: the values must be equal
+         * and carry a valid signature no older than `ttl`.
+         *
+         * # Parameters
+         * - `cookieValue`: `?string` Cookie value; defaults to the `__Host-` prefixed cookie from `$_COOKIE`.
+         *   Pass it explicitly if the cookie was issued without the prefix (e.g. `secure => false`).
+         * - `formValue`: `?string` Submitted token; defaults to `$_POST['csrf_token']`.
+         *
+         * # Returns
+         * - `bool` `true` if the pair is valid.
+         */
+        public function verifyFromRequest(?string $cookie_value, ?string $form_value): bool {}
+
+        /**
+         * Issues a token carrying encrypted, authenticated claims, e.g. a user id and form id,
+         * that expires after `ttl` seconds.
+         *
+         * The server verifies intent from the token alone with `verifyAndDecode()`; the claims are not
+         * readable by the client.
+         *
+         * # Parameters
+         * - `claims`: `array` Scalar values keyed by name, e.g. `['uid' => 42, 'form' => 'profile']`.
+         *
+         * # Returns
+         * - `string` Base64URL-encoded token.
+         *
+         * # Exceptions
+         * - Throws `Exception` if encryption fails.
+         */
+        public function issueWithClaims(array $claims): string {}
+
+        /**
+         * Decrypts a token issued by `issueWithClaims()` and returns its claims.
+         *
+         * # Parameters
+         * - `token`: `string` Token from the client.
+         *
+         * # Returns
+         * - `array` The claims.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the token is malformed, was tampered with or has expired.
+         */
+        public function verifyAndDecode(string $token): array {}
+
+        /**
+         * Sets the hosts whose pages may submit requests, checked by `verifyRequest()`.
+         * By default only the request's own `Host` is allowed.
+         *
+         * # Parameters
+         * - `hosts`: `array` Hostnames, e.g. `['example.com', 'admin.example.com']`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if an entry is not a valid hostname.
+         */
+        public function setAllowedHosts(array $hosts) {}
+
+        /**
+         * Verifies a state-changing request in one call.
+         *
+         * `GET`, `HEAD`, `OPTIONS` and `TRACE` requests pass. For other methods the `Origin` header
+         * (or, without it, the `Referer`) must name an allowed host (see `setAllowedHosts()`), and the
+         * `csrf_token` form field or `X-CSRF-Token` header must hold a valid `statelessToken()`.
+         *
+         * # Parameters
+         * - `server`: `array` Usually `$_SERVER`.
+         * - `post`: `array` Usually `$_POST`.
+         * - `headers`: `array` Request headers, e.g. `getallheaders()` or a PSR-7 `getHeaders()` array.
+         *
+         * # Returns
+         * - `void` on success.
+         *
+         * # Exceptions
+         * - Throws `Exception` whose code identifies the failed check.
+         */
+        public function verifyRequest(array $server, array $post, array $headers) {}
+
+        /**
+         * Returns the CSRF cookie string to send in PHP.
+         *
+         * # Returns
+         * - `string` Base64URL-encoded cookie suitable for `Set-Cookie`.
+         */
+        public function cookie(): string {}
+
+        /**
+         * Returns the CSRF token string for PHP forms or headers.
+         *
+         * # Returns
+         * - `string` Base64URL-encoded token.
+         */
+        public function token(): string {}
+
+        /**
+         * Sets the name of the CSRF cookie to use in PHP calls.
+         *
+         * # Parameters
+         * - `cookieName`: `string` the new name for the CSRF cookie.
+         *
+         * # Returns
+         * - `void`
+         */
+        public function setCookieName(string $cookie_name) {}
+
+        /**
+         * Returns the configured CSRF cookie name.
+         *
+         * # Returns
+         * - `string` the name of the CSRF cookie.
+         */
+        public function cookieName(): string {}
+
+        /**
+         * Sends the CSRF cookie to the client via `setcookie()`
+         *
+         * # Parameters
+         * - `expires`: `?int` UNIX timestamp when the cookie expires (defaults to `0`, a session cookie).
+         * - `path`: `?string` Cookie path (defaults to `"/"`).
+         * - `domain`: `?string` Cookie domain (defaults to the current host).
+         * - `secure`: `?bool` Send only over HTTPS (defaults to `false`).
+         * - `httponly`: `?bool` HTTP-only flag (defaults to `true`).
+         *
+         * # Exceptions
+         * - Throws `Exception` if the PHP `setcookie()` function cannot be invoked.
+         */
+        public function sendCookie(?int $expires, ?string $path, ?string $domain, ?bool $secure, ?bool $httponly) {}
+
+        /**
+         * Constructs a CSRF protection instance for PHP.
+         *
+         * # Parameters
+         * - `key`: `string` Base64URL-encoded 32-byte secret key.
+         * - `ttl`: `int` token time-to-live in seconds.
+         * - `previousTokenValue`: `?string` optional Base64URL-encoded previous token for rotation.
+         *
+         * # Exceptions
+         * - Throws `Exception` if key decoding or length validation fails.
+         * - Throws `Exception` if token pair generation fails.
+         */
+        public function __construct(string $key, int $ttl, ?string $previous_token_value) {}
+    }
+
+    /**
+     * Subresource Integrity hash generator and verifier.
+     */
+    class Sri {
+        /**
+         * Compute the `integrity` attribute value for a string.
+         *
+         * # Parameters
+         * - `content`: `string` The resource body, byte for byte as served.
+         * - `algos`: `string|array|null` One or more of `sha256`, `sha384`, `sha512`.
+         *   Default: `sha384`.
+         *
+         * # Returns
+         * - `string` Space-separated metadata, e.g. `sha384-…`.
+         *
+         * # Exceptions
+         * - Throws an exception if an algorithm is not supported.
+         */
+        public static function hashString(string $content, mixed $algos): string {}
+
+        /**
+         * Compute the `integrity` attribute value for a file without loading it into memory.
+         *
+         * # Parameters
+         * - `path`: `string` Path of the built asset.
+         * - `algos`: `string|array|null` One or more of `sha256`, `sha384`, `sha512`.
+         *   Default: `sha384`.
+         *
+         * # Returns
+         * - `string` Space-separated metadata, e.g. `sha256-… sha384-…`.
+         *
+         * # Exceptions
+         * - Throws an exception if an algorithm is not supported or the file cannot be read.
+         */
+        public static function hashFile(string $path, mixed $algos): string {}
+
+        /**
+         * Check content against an `integrity` attribute value.
+         *
+         * As in browsers, only the strongest algorithm listed is used and unknown tokens
+         * are ignored.
+         *
+         * # Parameters
+         * - `content`: `string` The resource body.
+         * - `integrity`: `string` Metadata such as `sha384-… sha512-…`.
+         *
+         * # Returns
+         * - `bool` `true` if a digest of the strongest algorithm matches.
+         *
+         * # Exceptions
+         * - Throws an exception if the metadata contains no supported hash.
+         */
+        public static function verify(string $content, string $integrity): bool {}
+    }
+
+    /**
+     * Builder for RFC 9116 `security.txt` files, served at `/.well-known/security.txt`.
+     */
+    class SecurityTxt {
+        /**
+         * Adds a `Contact` field (required, may repeat; list the preferred contact first).
+         *
+         * # Parameters
+         * - `contact`: `string` An `https:` URL, a `mailto:`/`tel:` URI or a bare e-mail address.
+         *
+         * # Exceptions
+         * - Throws an exception if the value is not a valid contact URI.
+         */
+        public function contact(string $contact) {}
+
+        /**
+         * Sets the required `Expires` field. RFC 9116 recommends less than a year ahead.
+         *
+         * # Parameters
+         * - `timestamp`: `int` UNIX timestamp, e.g. `strtotime('+6 months')`.
+         *
+         * # Exceptions
+         * - Throws an exception if the timestamp is negative or after the year 9999.
+         */
+        public function expires(int $timestamp) {}
+
+        /**
+         * Adds an `Encryption` field pointing at the key to encrypt reports with.
+         *
+         * # Parameters
+         * - `uri`: `string` An `https:` URL, `openpgp4fpr:<fingerprint>` or `dns:` URI.
+         *
+         * # Exceptions
+         * - Throws an exception if the URI is invalid.
+         */
+        public function encryption(string $uri) {}
+
+        /**
+         * Adds an `Acknowledgments` field linking to the researcher hall of fame.
+         *
+         * # Parameters
+         * - `url`: `string` A public `https:` URL.
+         *
+         * # Exceptions
+         * - Throws an exception if the URL is not a public `https:` URL.
+         */
+        public function acknowledgments(string $url) {}
+
+        /**
+         * Sets the `Preferred-Languages` field.
+         *
+         * # Parameters
+         * - `languages`: `string[]` Language tags, e.g. `['en', 'pt-BR']`; `[]` removes the field.
+         *
+         * # Exceptions
+         * - Throws an exception if a tag is invalid.
+         */
+        public function preferredLanguages(array $languages) {}
+
+        /**
+         * Adds a `Canonical` field: a URL this file is published at.
+         *
+         * # Parameters
+         * - `url`: `string` e.g. `https://example.com/.well-known/security.txt`.
+         *
+         * # Exceptions
+         * - Throws an exception if the URL is not a public `https:` URL.
+         */
+        public function canonical(string $url) {}
+
+        /**
+         * Adds a `Policy` field linking to the vulnerability disclosure policy.
+         *
+         * # Parameters
+         * - `url`: `string` A public `https:` URL.
+         *
+         * # Exceptions
+         * - Throws an exception if the URL is not a public `https:` URL.
+         */
+        public function policy(string $url) {}
+
+        /**
+         * Adds a `Hiring` field linking to security-related job openings.
+         *
+         * # Parameters
+         * - `url`: `string` A public `https:` URL.
+         *
+         * # Exceptions
+         * - Throws an exception if the URL is not a public `https:` URL.
+         */
+        public function hiring(string $url) {}
+
+        /**
+         * Builds the file contents, optionally signed.
+         *
+         * The signer receives the plain text and must return an OpenPGP cleartext-signed
+         * message, e.g. `fn (string $text) => $gnupg->sign($text)` with `GNUPG_SIG_MODE_CLEAR`.
+         *
+         * # Parameters
+         * - `signer`: `?callable(string $text): string`
+         *
+         * # Returns
+         * - `string` The `security.txt` contents, to be served as `text/plain; charset=utf-8`.
+         *
+         * # Exceptions
+         * - Throws an exception if `Contact` or `Expires` is missing, `Expires` has passed, or
+         *   the signer fails or does not return a cleartext-signed message.
+         */
+        public function build(mixed $signer): string {}
+
+        /**
+         * Constructs an empty builder; `contact()` and `expires()` are required before `build()`.
+         */
+        public function __construct() {}
+    }
+
+    /**
+     * Generator and verifier of HMAC-signed, expiring URLs.
+     */
+    class SignedUrl {
+        /**
+         * Signs a URL, valid for `ttl` seconds.
+         *
+         * Appends `expires` and `signature` query parameters, replacing any already present,
+         * so a signed URL can be signed again to extend it.
+         *
+         * # Parameters
+         * - `url`: `string` Absolute URL.
+         * - `ttl`: `int` Lifetime in seconds.
+         *
+         * # Returns
+         * - `string` The signed URL.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the URL cannot be parsed or `ttl` is not positive.
+         */
+        public function sign(string $url, int $ttl): string {}
+
+        /**
+         * Checks a signed URL.
+         *
+         * The query string may be in any order; the fragment is ignored.
+         *
+         * # Parameters
+         * - `url`: `string` The full URL as requested.
+         *
+         * # Returns
+         * - `bool` `true` if the signature matches and the URL has not expired.
+         */
+        public function verify(string $url): bool {}
+
+        /**
+         * Constructs a signer.
+         *
+         * # Parameters
+         * - `key`: `string` Base64URL-encoded secret of at least 32 bytes.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the key cannot be decoded or is too short.
+         */
+        public function __construct(string $key) {}
+    }
+
+    /**
+     * One-time password generator and verifier for a single shared secret.
+     */
+    class Totp {
+        /**
+         * Generate a random base32 secret.
+         *
+         * # Parameters
+         * - `bytes`: `?int` Secret size in bytes, at least 16. Default: `20`.
+         *
+         * # Returns
+         * - `string` Unpadded base32 secret to store server-side and show to the user.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `bytes` is below 16 or random generation fails.
+         */
+        public static function generateSecret(?int $bytes): string {}
+
+        /**
+         * Compute the time-based code.
+         *
+         * # Parameters
+         * - `time`: `?int` UNIX timestamp. Default: now.
+         *
+         * # Returns
+         * - `string` Zero-padded code.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `time` is negative.
+         */
+        public function code(?int $time): string {}
+
+        /**
+         * Compute the counter-based (HOTP) code.
+         *
+         * # Parameters
+         * - `counter`: `int` Moving factor shared with the client.
+         *
+         * # Returns
+         * - `string` Zero-padded code.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `counter` is negative.
+         */
+        public function hotp(int $counter): string {}
+
+        /**
+         * Check a time-based code, allowing for clock drift.
+         *
+         * Every step in the window is computed and compared, so the run time is the same
+         * whether and where the code matches. Callers should remember the last accepted step
+         * (`intdiv(time(), $period)`) to reject a code being replayed within its window.
+         *
+         * # Parameters
+         * - `code`: `string` Code entered by the user.
+         * - `window`: `?int` Steps accepted before and after the current one, at most 10.
+         *   Default: `1`.
+         * - `time`: `?int` UNIX timestamp. Default: now.
+         *
+         * # Returns
+         * - `bool` `true` if the code matches a step in the window.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `window` is not between 0 and 10 or `time` is negative.
+         */
+        public function verify(string $code, ?int $window, ?int $time): bool {}
+
+        /**
+         * Build the `otpauth://` URI that authenticator apps import, usually via a QR code.
+         *
+         * # Parameters
+         * - `issuer`: `string` Service name shown in the app, e.g. `Example Inc.`.
+         * - `account`: `string` Account name, e.g. the user's e-mail address.
+         *
+         * # Returns
+         * - `string` `otpauth://totp/Issuer:account?secret=…&issuer=…&algorithm=…&digits=…&period=…`
+         *
+         * # Exceptions
+         * - Throws `Exception` if `issuer` or `account` is empty or contains a colon.
+         */
+        public function provisioningUri(string $issuer, string $account): string {}
+
+        /**
+         * Constructs a generator for a shared secret.
+         *
+         * # Parameters
+         * - `secret`: `string` Base32 secret; spaces, padding and lowercase are accepted.
+         * - `digits`: `?int` Code length, 6 to 8. Default: `6`.
+         * - `period`: `?int` Time step in seconds. Default: `30`.
+         * - `algorithm`: `?string` `sha1`, `sha256` or `sha512`. Default: `sha1`, the only one
+         *   every authenticator app supports.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the secret is not base32 or shorter than 16 bytes, or an option
+         *   is out of range.
+         */
+        public function __construct(string $secret, ?int $digits, ?int $period, ?string $algorithm) {}
+    }
+
+    /**
+     * JWT issuer and verifier bound to a single key.
+     */
+    class Jwt {
+        /**
+         * Set the allowed clock difference for `exp`, `nbf` and `iat`.
+         *
+         * # Parameters
+         * - `seconds`: `int` Leeway in seconds. Default: `60`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `seconds` is negative.
+         */
+        public function leeway(int $seconds) {}
+
+        /**
+         * Require the `iss` claim to equal `issuer`.
+         *
+         * # Parameters
+         * - `issuer`: `?string` Expected issuer; `null` disables the check.
+         */
+        public function issuer(?string $issuer) {}
+
+        /**
+         * Require the `aud` claim to contain one of the given audiences.
+         *
+         * Without an audience configured, tokens that carry an `aud` claim are rejected,
+         * since they are meant for a specific recipient.
+         *
+         * # Parameters
+         * - `audience`: `string|array|null` This service's identifiers.
+         *
+         * # Exceptions
+         * - Throws `Exception` if an audience is not a string.
+         */
+        public function audience(mixed $audience) {}
+
+        /**
+         * Set the claims every token must carry.
+         *
+         * # Parameters
+         * - `claims`: `array` Claim names. Default: `['exp']`.
+         */
+        public function requireClaims(array $claims) {}
+
+        /**
+         * Set the largest accepted token.
+         *
+         * # Parameters
+         * - `bytes`: `int` Limit in bytes. Default: `8192`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `bytes` is not positive.
+         */
+        public function maxSize(int $bytes) {}
+
+        /**
+         * Issue a signed token.
+         *
+         * # Parameters
+         * - `claims`: `array` Claims; `iat` is added unless present.
+         * - `ttl`: `?int` Lifetime in seconds; sets `exp`.
+         *
+         * # Returns
+         * - `string` Compact JWS.
+         *
+         * # Exceptions
+         * - Throws `Exception` without a signing key, for claims that are not a JSON object,
+         *   or a non-positive `ttl`.
+         */
+        public function sign(array $claims, ?int $ttl): string {}
+
+        /**
+         * Verify a token and return its claims.
+         *
+         * # Parameters
+         * - `token`: `string` Compact JWS.
+         *
+         * # Returns
+         * - `array` The claims.
+         *
+         * # Exceptions
+         * - Throws `Exception` describing the first check that failed; the code tells them apart.
+         */
+        public function decode(string $token): array {}
+
+        /**
+         * Check a token without returning its claims.
+         *
+         * # Parameters
+         * - `token`: `string` Compact JWS.
+         *
+         * # Returns
+         * - `bool` `true` if `decode()` would succeed.
+         */
+        public function verify(string $token): bool {}
+
+        /**
+         * Constructs a JWT issuer/verifier.
+         *
+         * # Parameters
+         * - `key`: `string` HMAC secret, or the PEM public key for RSA, EC and EdDSA.
+         * - `algorithms`: `string|array` Allowed algorithms, all of the same key family:
+         *   `HS256`…`HS512`, `RS256`…`RS512`, `PS256`…`PS512`, `ES256`, `ES384` or `EdDSA`.
+         *   The first one is used by `sign()`.
+         * - `signingKey`: `?string` PEM private key, needed to `sign()` with asymmetric algorithms.
+         *
+         * # Exceptions
+         * - Throws `Exception` for an unknown or mixed algorithm list, an HMAC secret shorter
+         *   than the hash, or a key that cannot be parsed.
+         */
+        public function __construct(string $key, mixed $algorithms, ?string $signing_key) {}
+    }
+
+    /**
+     * Password hashing policy.
+     */
+    class Password {
+        /**
+         * Constructs a scrypt policy, for platforms where Argon2 is not acceptable.
+         *
+         * # Parameters
+         * - `logN`: `?int` CPU/memory cost as a power of two, at least 13. Default: `17`.
+         * - `r`: `?int` Block size. Default: `8`.
+         * - `p`: `?int` Parallelism. Default: `1`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if a parameter is out of range.
+         */
+        public static function scrypt(?int $log_n, ?int $r, ?int $p): \Hardened\Password {}
+
+        /**
+         * Hash a password with a fresh random salt.
+         *
+         * # Parameters
+         * - `password`: `string` The password.
+         *
+         * # Returns
+         * - `string` PHC string, e.g. `$argon2id$v=19$m=19456,t=2,p=1$…$…`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if hashing fails.
+         */
+        public function hash(string $password): string {}
+
+        /**
+         * Check a password against a hash.
+         *
+         * Any Argon2 variant or scrypt hash is accepted, whatever its cost, so hashes keep
+         * verifying after the policy changes.
+         *
+         * # Parameters
+         * - `password`: `string` The password.
+         * - `hash`: `string` PHC string from `hash()` or `password_hash()`.
+         *
+         * # Returns
+         * - `bool` `true` if the password matches; `false` also for malformed or unsupported hashes.
+         */
+        public static function verify(string $password, string $hash): bool {}
+
+        /**
+         * Whether a hash was made with another algorithm or cost than this policy.
+         *
+         * # Parameters
+         * - `hash`: `string` Stored hash.
+         *
+         * # Returns
+         * - `bool` `true` if the hash should be replaced by `hash()` of the same password.
+         */
+        public function needsRehash(string $hash): bool {}
+
+        /**
+         * Pick the Argon2id time cost that takes about `targetMs` on this host.
+         *
+         * Memory stays fixed and the number of passes is raised as far as the target allows.
+         * Run it on production hardware and keep the result in configuration; do not call it
+         * per request.
+         *
+         * # Parameters
+         * - `targetMs`: `int` Time budget for one hash in milliseconds, e.g. `500`.
+         * - `memoryCost`: `?int` Memory in KiB. Default: `65536` (64 MiB).
+         * - `parallelism`: `?int` Number of lanes. Default: `1`.
+         *
+         * # Returns
+         * - `array` `memory_cost`, `time_cost` and `threads` (the keys `password_hash()` uses),
+         *   plus the measured `duration_ms`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if a parameter is out of range.
+         */
+        public static function benchmark(int $target_ms, ?int $memory_cost, ?int $parallelism): array {}
+
+        /**
+         * Constructs an Argon2id policy.
+         *
+         * # Parameters
+         * - `memoryCost`: `?int` Memory in KiB, at least 7168. Default: `19456` (19 MiB).
+         * - `timeCost`: `?int` Number of passes. Default: `2`.
+         * - `parallelism`: `?int` Number of lanes. Default: `1`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if a parameter is out of range.
+         */
+        public function __construct(?int $memory_cost, ?int $time_cost, ?int $parallelism) {}
+    }
+
+    /**
+     * Have I Been Pwned k-anonymity helpers.
+     */
+    class PwnedPasswords {
+        /**
+         * Hash a password for a range query.
+         *
+         * # Parameters
+         * - `password`: `string` The candidate password.
+         *
+         * # Returns
+         * - `array` `prefix` (5 hex digits, for `https://api.pwnedpasswords.com/range/{prefix}`)
+         *   and `suffix` (the remaining 35, to look up in the response). Both uppercase.
+         */
+        public static function prefix(string $password): array {}
+
+        /**
+         * Look up a suffix in a range response body.
+         *
+         * # Parameters
+         * - `suffix`: `string` Suffix from `prefix()` (or the full hash).
+         * - `rangeBody`: `string` Body returned by the range endpoint.
+         *
+         * # Returns
+         * - `?int` How many times the password appears in breaches, or `null` if it does not.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `suffix` is not a hex hash suffix.
+         */
+        public static function matchInRangeResponse(string $suffix, string $range_body): ?int {}
+    }
+
+    /**
+     * Signs, or encrypts, cookie values and checks them on the way back in.
+     */
+    class CookieGuard {
+        /**
+         * Generate a new random key.
+         *
+         * # Returns
+         * - `string` Base64URL-encoded 32-byte key.
+         */
+        public static function generateKey(): string {}
+
+        /**
+         * Protect a cookie value.
+         *
+         * # Parameters
+         * - `name`: `string` Cookie name the value is bound to, including any `__Host-` prefix.
+         * - `value`: `string` The value.
+         * - `ttl`: `?int` Seconds the value stays valid, enforced on read. Default: no limit.
+         *
+         * # Returns
+         * - `string` Cookie-safe protected value.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `ttl` is not positive or encryption fails.
+         */
+        public function seal(string $name, string $value, ?int $ttl): string {}
+
+        /**
+         * Check a protected value.
+         *
+         * # Parameters
+         * - `name`: `string` Cookie name given to `seal()`.
+         * - `sealed`: `string` The protected value.
+         *
+         * # Returns
+         * - `?string` The original value, or `null` if it was tampered with, has expired, was made
+         *   for another cookie or with an unknown key.
+         */
+        public function open(string $name, string $sealed): ?string {}
+
+        /**
+         * Read and check a cookie from `$_COOKIE`.
+         *
+         * # Parameters
+         * - `name`: `string` Cookie name.
+         *
+         * # Returns
+         * - `?string` The original value, or `null` if the cookie is missing or invalid.
+         */
+        public function get(string $name): ?string {}
+
+        /**
+         * Build a `SetCookie` carrying a protected value, to adjust and `send()`.
+         *
+         * # Parameters
+         * - `name`: `string` Cookie name.
+         * - `value`: `string` The value.
+         * - `ttl`: `?int` Lifetime in seconds, used for both `Max-Age` and the embedded expiry.
+         *   Default: a session cookie with no embedded expiry.
+         *
+         * # Returns
+         * - `Hardened\SecurityHeaders\SetCookie` with the secure defaults.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the name is not a valid cookie name or `ttl` is out of range.
+         */
+        public function cookie(string $name, string $value, ?int $ttl): \Hardened\SecurityHeaders\SetCookie {}
+
+        /**
+         * Constructs a guard from one key or a key ring.
+         *
+         * # Parameters
+         * - `keys`: `string|array` Base64URL-encoded 32-byte key, or a list of them. The first
+         *   protects new cookies, all of them are accepted on read.
+         * - `encrypt`: `?bool` Encrypt values instead of only signing them. Default: `true`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if no key is given or a key is not 32 bytes of Base64URL.
+         */
+        public function __construct(mixed $keys, ?bool $encrypt) {}
+    }
+
+    /**
+     * Issues and verifies HMAC-bound proof-of-work challenges.
+     */
+    class ProofOfWork {
+        /**
+         * Issue a challenge.
+         *
+         * # Parameters
+         * - `scope`: `?string` What the challenge is for (form name, client IP, account…); the
+         *   same scope must be passed to `verify()`. Default: `''`.
+         * - `difficulty`: `?int` Override the default difficulty, e.g. to raise it for clients
+         *   that already failed several times.
+         *
+         * # Returns
+         * - `string` e.g. `"v1.18.1760000000.<salt>.<tag>"`; the difficulty is the second field.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the difficulty is out of range.
+         */
+        public function challenge(?string $scope, ?int $difficulty): string {}
+
+        /**
+         * Verify a solution.
+         *
+         * Challenges are stateless, so one solution can be replayed until the challenge expires;
+         * keep short TTLs, or record used challenges when that matters.
+         *
+         * # Parameters
+         * - `challenge`: `string` Challenge returned by `challenge()`.
+         * - `nonce`: `string` The client's solution, at most 64 bytes.
+         * - `scope`: `?string` Scope given to `challenge()`. Default: `''`.
+         *
+         * # Returns
+         * - `bool` `true` if the challenge is authentic, unexpired and solved.
+         */
+        public function verify(string $challenge, string $nonce, ?string $scope): bool {}
+
+        /**
+         * Constructs a challenge issuer.
+         *
+         * # Parameters
+         * - `key`: `string` Secret of at least 32 bytes.
+         * - `difficulty`: `?int` Default number of leading zero bits required, 1–32. Each extra
+         *   bit doubles the average work. Default: 18.
+         * - `ttl`: `?int` Seconds a challenge stays solvable. Default: 300.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the key is too short, or the difficulty or TTL is out of range.
+         */
+        public function __construct(string $key, ?int $difficulty, ?int $ttl) {}
+    }
+
+    /**
+     * Honeypot fields and signed render times for public forms.
+     */
+    class FormSpam {
+        /**
+         * Set the least time a person needs to fill in the form.
+         *
+         * # Parameters
+         * - `seconds`: `int` Default: `3`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `seconds` is negative.
+         */
+        public function minimumFillTime(int $seconds) {}
+
+        /**
+         * Set how long a rendered form stays valid.
+         *
+         * # Parameters
+         * - `seconds`: `int` Default: `7200`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `seconds` is not positive.
+         */
+        public function maxAge(int $seconds) {}
+
+        /**
+         * Prepare the hidden fields for a form.
+         *
+         * # Parameters
+         * - `form`: `?string` Form identifier the token is bound to. Default: `''`.
+         *
+         * # Returns
+         * - `array` `honeypot` (name of the text field to hide from people), `token_field`
+         *   and `token` (the hidden input carrying the signed render time).
+         */
+        public function issue(?string $form): array {}
+
+        /**
+         * Render the hidden fields as HTML.
+         *
+         * # Parameters
+         * - `form`: `?string` Form identifier the token is bound to. Default: `''`.
+         *
+         * # Returns
+         * - `string` A hidden token input and an off-screen honeypot input.
+         */
+        public function fields(?string $form): string {}
+
+        /**
+         * Check a submission's token and honeypot.
+         *
+         * # Parameters
+         * - `post`: `array` The submitted fields, e.g. `$_POST`.
+         * - `form`: `?string` Form identifier given to `issue()`. Default: `''`.
+         *
+         * # Returns
+         * - `bool` `false` if the token is forged or expired, the form was submitted faster
+         *   than the minimum fill time, or the honeypot is missing or filled in.
+         */
+        public function check(array $post, ?string $form): bool {}
+
+        /**
+         * Estimate how likely a submission is spam.
+         *
+         * Combines `check()` with content heuristics: links, BBCode and HTML links, shouting,
+         * oversized values, the same text in several fields, and empty request headers.
+         *
+         * # Parameters
+         * - `post`: `array` The submitted fields, e.g. `$_POST`.
+         * - `meta`: `array` Optional `form`, `user_agent` and `accept_language` values; the
+         *   headers only count against the submission when passed and empty.
+         *
+         * # Returns
+         * - `int` Score from 0 (looks clean) to 100 (certainly spam).
+         */
+        public function score(array $post, array $meta): int {}
+
+        /**
+         * Constructs a form protector.
+         *
+         * # Parameters
+         * - `key`: `string` Secret of at least 32 bytes.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the key is too short.
+         */
+        public function __construct(string $key) {}
+    }
+
+    /**
+     * Session ID, timeout and fingerprint policy.
+     */
+    class Session {
+        /**
+         * Generate a session ID from the CSPRNG.
+         *
+         * # Parameters
+         * - `bits`: `?int` Entropy in bits, 128–512. Default: 192.
+         *
+         * # Returns
+         * - `string` Lowercase `[0-9a-v]` characters, 5 bits each, valid for `session_id()`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `bits` is out of range.
+         */
+        public static function generateId(?int $bits): string {}
+
+        /**
+         * Decide whether to call `session_regenerate_id(true)`.
+         *
+         * # Parameters
+         * - `context`: `array` Any of: `login`, `logout`, `privilege_change`, `role_change`,
+         *   `password_change`, `reauthenticated` (truthy when it happened in this request);
+         *   `user_id` and `previous_user_id` (rotate when both are given and differ); `rotated_at` (UNIX time
+         *   of the last rotation, rotate when older than the interval); `now` (defaults to the
+         *   current time).
+         *
+         * # Returns
+         * - `bool`
+         */
+        public function shouldRotate(array $context): bool {}
+
+        /**
+         * Evaluate the idle and absolute timeouts.
+         *
+         * # Parameters
+         * - `createdAt`: `int` UNIX time the session was created (or the user logged in).
+         * - `lastActivity`: `int` UNIX time of the previous request.
+         * - `now`: `?int` Defaults to the current time.
+         *
+         * # Returns
+         * - `?string` `null` if the session is still valid, otherwise `"idle"` or `"absolute"`.
+         */
+        public function timeoutStatus(int $created_at, int $last_activity, ?int $now): ?string {}
+
+        /**
+         * Whether either timeout has passed.
+         *
+         * # Parameters
+         * - `createdAt`: `int` UNIX time the session was created.
+         * - `lastActivity`: `int` UNIX time of the previous request.
+         * - `now`: `?int` Defaults to the current time.
+         *
+         * # Returns
+         * - `bool`
+         */
+        public function isExpired(int $created_at, int $last_activity, ?int $now): bool {}
+
+        /**
+         * Set the network prefix lengths used for fingerprints, so clients that hop between
+         * addresses of one network keep their session.
+         *
+         * # Parameters
+         * - `ipv4`: `int` Default: 24.
+         * - `ipv6`: `int` Default: 64.
+         *
+         * # Exceptions
+         * - Throws `Exception` if a prefix is longer than the address.
+         */
+        public function fingerprintPrefixes(int $ipv4, int $ipv6) {}
+
+        /**
+         * Fingerprint a client: a hash of its user agent and network prefix, to store in the
+         * session.
+         *
+         * # Parameters
+         * - `userAgent`: `string` e.g. `$_SERVER['HTTP_USER_AGENT']`.
+         * - `ip`: `string` Client IP address.
+         *
+         * # Returns
+         * - `string` Base64URL-encoded SHA-256.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `ip` is not an IP address.
+         */
+        public function fingerprint(string $user_agent, string $ip): string {}
+
+        /**
+         * Check a stored fingerprint in constant time.
+         *
+         * # Parameters
+         * - `stored`: `string` Value from `fingerprint()`.
+         * - `userAgent`: `string` Current user agent.
+         * - `ip`: `string` Current client IP address.
+         *
+         * # Returns
+         * - `bool` `false` on mismatch or an invalid IP.
+         */
+        public function verifyFingerprint(string $stored, string $user_agent, string $ip): bool {}
+
+        /**
+         * Constructs a session policy.
+         *
+         * # Parameters
+         * - `idleTimeout`: `?int` Seconds of inactivity after which a session ends. Default: 1800.
+         * - `absoluteTimeout`: `?int` Seconds after creation after which a session ends,
+         *   however active. Default: 28800.
+         * - `rotateInterval`: `?int` Seconds after which the ID is rotated even without a
+         *   privilege change. Default: 900.
+         *
+         * # Exceptions
+         * - Throws `Exception` if a value is not positive.
+         */
+        public function __construct(?int $idle_timeout, ?int $absolute_timeout, ?int $rotate_interval) {}
+    }
+
+    /**
+     * Issuer and verifier of hashed, purpose-bound action tokens.
+     */
+    class ActionToken {
+        /**
+         * Issue a token.
+         *
+         * # Parameters
+         * - `purpose`: `string` What the token authorizes, e.g. `"password-reset"`.
+         * - `subject`: `string` Who it is for, e.g. a user id.
+         * - `ttl`: `?int` Override the default lifetime.
+         *
+         * # Returns
+         * - `array` `token` (send it to the user, e.g. in a link) and `hash` (store it, never
+         *   the token).
+         *
+         * # Exceptions
+         * - Throws `Exception` if `purpose` is empty or `ttl` is not positive.
+         */
+        public function issue(string $purpose, string $subject, ?int $ttl): array {}
+
+        /**
+         * Check a presented token against the stored hash.
+         *
+         * # Parameters
+         * - `token`: `string` Token presented by the user.
+         * - `storedHash`: `string` Hash returned by `issue()`.
+         * - `purpose`: `string` Purpose it must have been issued for.
+         * - `subject`: `string` Subject it must have been issued for.
+         *
+         * # Returns
+         * - `bool` `true` if the token is well-formed, unexpired and matches in constant time.
+         *   Delete the stored hash once it has been used.
+         */
+        public function verify(string $token, string $stored_hash, string $purpose, string $subject): bool {}
+
+        /**
+         * Compute the hash of a presented token, to look up its stored row.
+         *
+         * # Parameters
+         * - `token`: `string` Token presented by the user.
+         * - `purpose`: `string`
+         * - `subject`: `string`
+         *
+         * # Returns
+         * - `?string` `null` if the token is malformed.
+         */
+        public function hash(string $token, string $purpose, string $subject): ?string {}
+
+        /**
+         * Read the expiry embedded in a token, e.g. to store alongside its hash for cleanup.
+         *
+         * The expiry is covered by the hash, so editing it makes the token invalid.
+         *
+         * # Parameters
+         * - `token`: `string`
+         *
+         * # Returns
+         * - `?int` UNIX timestamp, or `null` if the token is malformed.
+         */
+        public static function expiresAt(string $token): ?int {}
+
+        /**
+         * Constructs a token manager.
+         *
+         * # Parameters
+         * - `key`: `string` Secret of at least 32 bytes. Changing it invalidates outstanding
+         *   tokens.
+         * - `ttl`: `?int` Default lifetime in seconds. Default: 3600.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the key is too short or `ttl` is not positive.
+         */
+        public function __construct(string $key, ?int $ttl) {}
+    }
+
+    /**
+     * Generator and verifier of prefixed API keys.
+     */
+    class ApiKey {
+        /**
+         * Generate a key.
+         *
+         * # Returns
+         * - `array` `key` (show it to the user once), `id` (public, store it to look the key
+         *   up) and `hash` (store it, never the key).
+         */
+        public function generate(): array {}
+
+        /**
+         * Split a key into its parts without looking anything up.
+         *
+         * # Parameters
+         * - `key`: `string` Presented key.
+         *
+         * # Returns
+         * - `?array` `prefix` and `id`, or `null` if the key is malformed, has a wrong checksum
+         *   or another prefix.
+         */
+        public function parse(string $key): ?array {}
+
+        /**
+         * Check whether a string looks like a key of any prefix, e.g. to scan logs or
+         * commits for leaked keys.
+         *
+         * # Parameters
+         * - `key`: `string`
+         *
+         * # Returns
+         * - `bool` `true` if the shape and checksum are valid.
+         */
+        public static function isWellFormed(string $key): bool {}
+
+        /**
+         * Check a presented key against its stored verifier.
+         *
+         * # Parameters
+         * - `presented`: `string` Key from the request.
+         * - `storedHash`: `string` Hash returned by `generate()`.
+         *
+         * # Returns
+         * - `bool` `true` if the key is well-formed, has this prefix and matches in constant
+         *   time.
+         */
+        public function verify(string $presented, string $stored_hash): bool {}
+
+        /**
+         * Read the creation time recorded in a stored verifier.
+         *
+         * # Parameters
+         * - `storedHash`: `string`
+         *
+         * # Returns
+         * - `?int` UNIX timestamp, or `null` if the hash is malformed.
+         */
+        public static function createdAt(string $stored_hash): ?int {}
+
+        /**
+         * Whether a key is older than the rotation interval.
+         *
+         * # Parameters
+         * - `storedHash`: `string`
+         * - `now`: `?int` Defaults to the current time.
+         *
+         * # Returns
+         * - `bool` `true` if the key should be replaced; also for malformed hashes.
+         */
+        public function needsRotation(string $stored_hash, ?int $now): bool {}
+
+        /**
+         * Constructs a key generator.
+         *
+         * # Parameters
+         * - `prefix`: `string` 1–16 lowercase letters and digits, starting with a letter,
+         *   e.g. `"acme"` or `"sk"`. Register it with secret-scanning services.
+         * - `rotateAfter`: `?int` Age in seconds after which `needsRotation()` reports a key.
+         *   Default: never.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the prefix is invalid or `rotateAfter` is not positive.
+         */
+        public function __construct(string $prefix, ?int $rotate_after) {}
+    }
+
+    /**
+     * Replay-protection store for single-use values.
+     *
+     * Clones share the backend, so `CsrfProtection::setReplayNonceStore()` and the PHP object
+     * see the same nonces.
+     */
+    class NonceStore {
+        /**
+         * Keep nonces in an external store instead of memory.
+         *
+         * The callbacks receive the SHA-256 hex digest of a nonce, never the nonce itself:
+         * - `get(string $key): bool` returns whether the key is recorded.
+         * - `set(string $key, int $ttl): bool` must atomically record the key for `$ttl` seconds
+         *   and return `true` only if it was not recorded yet
+         *   (e.g. `$redis->set("nonce:$key", 1, ['nx', 'ex' => $ttl])` or `Memcached::add()`).
+         * - `delete(string $key): mixed` forgets the key.
+         *
+         * # Parameters
+         * - `get`: `callable`
+         * - `set`: `callable`
+         * - `delete`: `callable`
+         *
+         * # Exceptions
+         * - Throws `Exception` if an argument is not callable.
+         */
+        public function setBackend(mixed $get, mixed $set, mixed $delete) {}
+
+        /**
+         * Record a nonce as used.
+         *
+         * Check the nonce's signature or origin first, then act only if this returns `true`.
+         *
+         * # Parameters
+         * - `nonce`: `string`
+         * - `ttl`: `int` Seconds to remember it; at least as long as the nonce is otherwise
+         *   accepted (token lifetime, webhook timestamp tolerance…).
+         *
+         * # Returns
+         * - `bool` `true` on first use, `false` on a replay.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the nonce is empty, `ttl` is not positive, the memory store is
+         *   full of live nonces, or a backend callback fails.
+         */
+        public function consume(string $nonce, int $ttl): bool {}
+
+        /**
+         * Check whether a nonce has been consumed, without recording it.
+         *
+         * # Parameters
+         * - `nonce`: `string`
+         *
+         * # Returns
+         * - `bool`
+         *
+         * # Exceptions
+         * - Throws `Exception` if the nonce is empty or a backend callback fails.
+         */
+        public function isConsumed(string $nonce): bool {}
+
+        /**
+         * Forget a nonce, e.g. when the action it guarded failed and may be retried.
+         *
+         * # Parameters
+         * - `nonce`: `string`
+         *
+         * # Exceptions
+         * - Throws `Exception` if the nonce is empty or a backend callback fails.
+         */
+        public function release(string $nonce) {}
+
+        /**
+         * Constructs a store that keeps nonces in process memory.
+         *
+         * Memory is per process: under PHP-FPM each worker has its own, so use `setBackend()`
+         * with a shared store there.
+         *
+         * # Parameters
+         * - `capacity`: `?int` Most live nonces kept in memory. Default: 100000.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `capacity` is not positive.
+         */
+        public function __construct(?int $capacity) {}
+    }
+
+    /**
+     * RFC 9421 request signer and verifier.
+     */
+    class RequestSigner {
+        /**
+         * Set the components every signature must cover.
+         *
+         * # Parameters
+         * - `components`: `string[]` Derived components (`@method`, `@target-uri`, `@authority`,
+         *   `@scheme`, `@request-target`, `@path`, `@query`) and header names. Default:
+         *   `["@method", "@authority", "@path", "@query"]`. `content-digest` is added
+         *   automatically whenever there is a body.
+         *
+         * # Exceptions
+         * - Throws `Exception` on an unknown derived component or an invalid header name.
+         */
+        public function setComponents(array $components) {}
+
+        /**
+         * Set how old a signature may be.
+         *
+         * # Parameters
+         * - `seconds`: `int` Default: 300.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `seconds` is not positive.
+         */
+        public function maxAge(int $seconds) {}
+
+        /**
+         * Sign a request.
+         *
+         * # Parameters
+         * - `method`: `string` e.g. `"POST"`.
+         * - `url`: `string` Absolute URL.
+         * - `headers`: `array` Header name => value (or list of values); must contain every
+         *   covered header.
+         * - `body`: `?string` Request body; when given, a `Content-Digest` is computed and
+         *   covered.
+         *
+         * # Returns
+         * - `array` Headers to add: `Content-Digest` (with a body), `Signature-Input` and
+         *   `Signature`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the URL is invalid, a covered header is missing, or the key is
+         *   an Ed25519 public key.
+         */
+        public function sign(string $method, string $url, array $headers, ?string $body): array {}
+
+        /**
+         * Verify a signed request.
+         *
+         * The signature is the one whose `keyid` matches this signer; it must be recent, use
+         * this algorithm and cover the configured components.
+         *
+         * # Parameters
+         * - `method`: `string` e.g. `$_SERVER['REQUEST_METHOD']`.
+         * - `url`: `string` Absolute URL as requested.
+         * - `headers`: `array` Request headers, e.g. `getallheaders()`.
+         * - `body`: `?string` Request body, e.g. `file_get_contents('php://input')`; when given,
+         *   `Content-Digest` must match it and be covered.
+         *
+         * # Returns
+         * - `bool`
+         */
+        public function verify(string $method, string $url, array $headers, ?string $body): bool {}
+
+        /**
+         * Constructs a signer.
+         *
+         * # Parameters
+         * - `keyId`: `string` Identifier the receiver uses to pick the key (`keyid` parameter).
+         * - `algorithm`: `string` `"hmac-sha256"` or `"ed25519"`.
+         * - `key`: `string` Raw key bytes: an HMAC secret of at least 32 bytes, a 64-byte
+         *   `sodium_crypto_sign_secretkey()` (signs and verifies), or a 32-byte
+         *   `sodium_crypto_sign_publickey()` (verifies only).
+         *
+         * # Exceptions
+         * - Throws `Exception` if the algorithm is unknown or the key does not fit it.
+         */
+        public function __construct(string $key_id, string $algorithm, string $key) {}
+    }
+
+    /**
+     * Detector for secrets and personal data.
+     */
+    class Redactor {
+        /**
+         * Add a custom detector, run after the built-in ones.
+         *
+         * # Parameters
+         * - `name`: `string` Name used in the replacement, e.g. `"stripe_key"`.
+         * - `regex`: `string` Rust `regex` syntax without delimiters, e.g. `"\\bsk_live_[0-9a-zA-Z]{24,}"`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the name is empty or the regex does not compile.
+         */
+        public function addPattern(string $name, string $regex) {}
+
+        /**
+         * Replace the list of array keys whose values are always redacted.
+         *
+         * Keys match case-insensitively, with `-` and `_` treated alike.
+         *
+         * # Parameters
+         * - `keys`: `string[]` Default: `password`, `passwd`, `pwd`, `secret`, `client_secret`,
+         *   `token`, `access_token`, `refresh_token`, `api_key`, `apikey`, `authorization`,
+         *   `cookie`, `private_key`.
+         */
+        public function sensitiveKeys(array $keys) {}
+
+        /**
+         * Set the replacement text.
+         *
+         * # Parameters
+         * - `template`: `string` `{name}` becomes the detector name or key. Default:
+         *   `"[REDACTED:{name}]"`.
+         */
+        public function setReplacement(string $template) {}
+
+        /**
+         * Redact a string, or every string in a nested array.
+         *
+         * Array keys are kept; values under sensitive keys are replaced whole, whatever their
+         * type. Numbers, booleans and objects are returned unchanged.
+         *
+         * # Parameters
+         * - `data`: `mixed`
+         *
+         * # Returns
+         * - `mixed` A redacted copy of `data`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the engine fails to allocate the copy.
+         */
+        public function redact(mixed $data): mixed {}
+
+        /**
+         * List the detectors that match a string, without changing it.
+         *
+         * # Parameters
+         * - `text`: `string`
+         *
+         * # Returns
+         * - `string[]` Names of matching detectors.
+         */
+        public function detect(string $text): array {}
+
+        /**
+         * Constructs a redactor.
+         *
+         * # Parameters
+         * - `patterns`: `?string[]` Built-in detectors to enable: `private_key`, `jwt`, `bearer`,
+         *   `aws_access_key`, `credit_card`, `email`, `phone`. Default: all of them.
+         *
+         * # Exceptions
+         * - Throws `Exception` on an unknown detector name.
+         */
+        public function __construct(?array $patterns) {}
+    }
+
+    /**
+     * Client address resolution with trusted proxies.
+     */
+    class ClientIp {
+        /**
+         * Determine the address of the client that sent the request.
+         *
+         * When `REMOTE_ADDR` is not a trusted proxy, it is the client and no header is read.
+         * Otherwise the first configured header present is walked from the right: each address
+         * is accepted as the next hop only while the previous one is a trusted proxy, so entries
+         * a client prepends are never reached. An entry that is not an IP address stops the walk.
+         *
+         * # Parameters
+         * - `server`: `array` Usually `$_SERVER`.
+         * - `trustedProxyCidrs`: `string[]` Networks of your proxies and load balancers, e.g.
+         *   `["10.0.0.0/8", "2001:db8::/32"]`; bare addresses are single hosts.
+         * - `trustedHeaders`: `?string[]` Headers your proxies set, in order of preference:
+         *   `forwarded` (RFC 7239) or any list header such as `x-forwarded-for`, `x-real-ip` or
+         *   `cf-connecting-ip`. Default: `["x-forwarded-for"]`. List only headers every proxy
+         *   overwrites or strips, or clients can supply the one consulted.
+         *
+         * # Returns
+         * - `string` The client IP address; IPv4-mapped IPv6 addresses as IPv4.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `REMOTE_ADDR` is missing or invalid, a network is invalid, or
+         *   a header name is invalid.
+         */
+        public static function resolve(array $server, array $trusted_proxy_cidrs, ?array $trusted_headers): string {}
+    }
+
+    /**
+     * Pre-flight checks and curl options for server-side requests to untrusted URLs.
+     */
+    class SafeFetch {
+        /**
+         * Validate a URL, resolve and pin its host, and return curl options for fetching it.
+         *
+         * The URL must be `http:` or `https:` without credentials, backslashes or control
+         * characters, must satisfy `policy`, and its host must not be a local name. Every resolved
+         * address in a non-public or denied network is dropped; if none remain, the request is
+         * refused.
+         *
+         * # Parameters
+         * - `url`: `string` URL to fetch.
+         * - `policy`: `HostnamePolicy` Hosts, schemes, ports and denied networks.
+         *
+         * # Returns
+         * - `array` Options for `curl_setopt_array()`: `CURLOPT_URL`, `CURLOPT_RESOLVE` pinned to
+         *   the checked addresses, `CURLOPT_PROTOCOLS`/`CURLOPT_REDIR_PROTOCOLS` limited to the
+         *   URL's scheme, `CURLOPT_FOLLOWLOCATION` off, `CURLOPT_PROXY` disabled, and 10 s connect
+         *   and 30 s total timeouts (which may be overridden).
+         *
+         * # Exceptions
+         * - Throws `Exception` describing the first failed check, or if resolution fails.
+         */
+        public static function prepare(string $url, \Hardened\HostnamePolicy $policy): array {}
+
+        /**
+         * Validate a redirect and return curl options for following it.
+         *
+         * Call this for every 3xx response instead of enabling `CURLOPT_FOLLOWLOCATION`, and cap
+         * the number of hops yourself.
+         *
+         * # Parameters
+         * - `fromUrl`: `string` URL that returned the redirect.
+         * - `location`: `string` Its `Location` header, absolute or relative.
+         * - `policy`: `HostnamePolicy` Same policy as for `prepare()`.
+         *
+         * # Returns
+         * - `array` Options for the next request, as from `prepare()`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the location is malformed, downgrades `https` to `http`, or
+         *   fails any check of `prepare()`.
+         */
+        public static function validateRedirect(string $from_url, string $location, \Hardened\HostnamePolicy $policy): array {}
+    }
+
+    /**
+     * Hardened XML parsing.
+     */
+    class Xml {
+        /**
+         * Parse an XML document into nested arrays.
+         *
+         * Each element becomes `['name' => string, 'namespace' => ?string, 'attributes' =>
+         * array<string, string>, 'children' => array<array|string>]`, where children are elements
+         * and text in document order (CDATA included, entities decoded). Namespaced attributes
+         * are keyed as `{uri}name`. Comments and processing instructions are dropped.
+         *
+         * # Parameters
+         * - `xml`: `string` UTF-8 encoded document.
+         * - `limits`: `?array` Optional settings:
+         *   - `maxBytes`: `int` largest document (default 10 MiB).
+         *   - `maxDepth`: `int` deepest element nesting (default `64`, at most `256`).
+         *   - `maxNodes`: `int` most nodes (default `100000`).
+         *   - `preserveWhitespace`: `bool` keep whitespace-only text (default `false`).
+         *
+         * # Returns
+         * - `array` The root element.
+         *
+         * # Exceptions
+         * - Throws `Exception` if an option is invalid, a limit is exceeded, the document has a
+         *   `<!DOCTYPE>`, or it is not well-formed UTF-8 XML.
+         */
+        public static function parse(string $xml, ?array $limits): mixed {}
+    }
+
+    /**
+     * Strict JSON decoding and HTML-safe encoding.
+     */
+    class Json {
+        /**
+         * Decode JSON, rejecting anything `json_decode()` would accept ambiguously.
+         *
+         * Objects become associative arrays with their key order preserved. Integers that do
+         * not fit in an `int` become floats, as with `json_decode()`.
+         *
+         * # Parameters
+         * - `json`: `string`
+         * - `limits`: `?array` Optional settings:
+         *   - `maxBytes`: `int` largest document (default 10 MiB).
+         *   - `maxDepth`: `int` deepest array/object nesting, at most `100` (default `64`).
+         *
+         * # Returns
+         * - `mixed` The decoded value.
+         *
+         * # Exceptions
+         * - Throws `Exception` if an option is invalid, a limit is exceeded, an object repeats a
+         *   key, or the input is not valid UTF-8 JSON (lone surrogate escapes included).
+         */
+        public static function decodeStrict(string $json, ?array $limits): mixed {}
+
+        /**
+         * Encode a value as JSON that is safe to embed in HTML.
+         *
+         * Equivalent to `json_encode()` with `JSON_HEX_TAG | JSON_HEX_AMP | JSON_HEX_APOS |
+         * JSON_HEX_QUOT | JSON_UNESCAPED_SLASHES | JSON_UNESCAPED_UNICODE |
+         * JSON_THROW_ON_ERROR`, plus escaped U+2028/U+2029. The result can go inside
+         * `<script>`, and inside a single-quoted HTML attribute.
+         *
+         * # Parameters
+         * - `value`: `mixed` Scalars and arrays; lists become JSON arrays, other arrays objects.
+         *
+         * # Returns
+         * - `string`
+         *
+         * # Exceptions
+         * - Throws `Exception` for objects, resources, `NAN`/`INF`, non-UTF-8 strings or
+         *   nesting deeper than 100 levels.
+         */
+        public static function encodeSafe(mixed $value): string {}
+    }
+
+    /**
+     * Checks against impersonation in usernames and display names.
+     */
+    class UnicodeSecurity {
+        /**
+         * Reduce a string to its confusable skeleton.
+         *
+         * Two strings with the same skeleton look alike. A skeleton is a comparison key, to be
+         * stored next to a username and indexed for uniqueness; it is not meant for display.
+         *
+         * # Parameters
+         * - `s`: `string`
+         *
+         * # Returns
+         * - `string` e.g. `paypal` for `PаyPa1` with a Cyrillic `а`.
+         */
+        public static function skeleton(string $s): string {}
+
+        /**
+         * Check whether two strings look alike, i.e. have the same skeleton. Identical strings
+         * are confusable too.
+         *
+         * # Parameters
+         * - `a`: `string`
+         * - `b`: `string`
+         *
+         * # Returns
+         * - `bool`
+         */
+        public static function isConfusableWith(string $a, string $b): bool {}
+
+        /**
+         * Check whether a string mixes writing systems, e.g. Latin with Cyrillic.
+         *
+         * Digits, punctuation and combining marks fit any script. Han combined with kana or
+         * Hangul is not mixed, as Japanese and Korean use both.
+         *
+         * # Parameters
+         * - `s`: `string`
+         *
+         * # Returns
+         * - `bool`
+         */
+        public static function mixedScript(string $s): bool {}
+
+        /**
+         * Remove invisible characters: zero-width spaces and joiners, bidi controls, soft
+         * hyphens, variation selectors, fillers and tag characters.
+         *
+         * Joiners also hold emoji sequences and some Persian and Indic spellings together, so
+         * strip them from identifiers rather than from free text.
+         *
+         * # Parameters
+         * - `s`: `string`
+         *
+         * # Returns
+         * - `string`
+         */
+        public static function stripInvisible(string $s): string {}
+    }
+
+    /**
+     * Strict UTF-8 validation, repair and Unicode normalization.
+     */
+    class Utf8 {
+        /**
+         * Check whether a string is valid UTF-8.
+         *
+         * Overlong encodings, UTF-16 surrogates, code points above U+10FFFF, truncated
+         * sequences and stray continuation bytes are all invalid.
+         *
+         * # Parameters
+         * - `s`: `string`
+         *
+         * # Returns
+         * - `bool`
+         */
+        public static function isValid(string $s): bool {}
+
+        /**
+         * Repair invalid UTF-8.
+         *
+         * # Parameters
+         * - `s`: `string`
+         * - `strategy`: `string` `replace` (each invalid sequence becomes U+FFFD, the default)
+         *   or `strip` (invalid sequences are removed).
+         *
+         * # Returns
+         * - `string` Valid UTF-8; unchanged if `s` already was.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the strategy is unknown.
+         */
+        public static function repair(string $s, ?string $strategy): string {}
+
+        /**
+         * Normalize valid UTF-8 to a Unicode normalization form.
+         *
+         * # Parameters
+         * - `s`: `string`
+         * - `form`: `string` `NFC` (the default), `NFD`, `NFKC` or `NFKD`.
+         *
+         * # Returns
+         * - `string`
+         *
+         * # Exceptions
+         * - Throws `Exception` if the form is unknown or `s` is not valid UTF-8 (repair it first).
+         */
+        public static function normalize(string $s, ?string $form): string {}
+    }
+}
+
+namespace Hardened\Crypto {
+    /**
+     * XChaCha20-Poly1305 encryption with a key ring for rotation.
+     */
+    class SecretBox {
+        /**
+         * Generate a new random key.
+         *
+         * # Returns
+         * - `string` Base64URL-encoded 32-byte key.
+         */
+        public static function generateKey(): string {}
+
+        /**
+         * Encrypt and authenticate a message.
+         *
+         * # Parameters
+         * - `plaintext`: `string` The message.
+         * - `aad`: `?string` Associated data that is authenticated but not encrypted, e.g. the
+         *   table and row id, so a ciphertext cannot be moved to another record. Default: `''`.
+         *
+         * # Returns
+         * - `string` Base64URL-encoded ciphertext.
+         *
+         * # Exceptions
+         * - Throws `Exception` if encryption fails.
+         */
+        public function encrypt(string $plaintext, ?string $aad): string {}
+
+        /**
+         * Decrypt and verify a message.
+         *
+         * # Parameters
+         * - `ciphertext`: `string` Output of `encrypt()`.
+         * - `aad`: `?string` The associated data given to `encrypt()`. Default: `''`.
+         *
+         * # Returns
+         * - `string` The plaintext.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the ciphertext is malformed, was made with an unknown key,
+         *   or fails authentication.
+         */
+        public function decrypt(string $ciphertext, ?string $aad): string {}
+
+        /**
+         * Whether a ciphertext was made with a key other than the primary one.
+         *
+         * # Parameters
+         * - `ciphertext`: `string` Output of `encrypt()`.
+         *
+         * # Returns
+         * - `bool` `true` if it should be decrypted and encrypted again after a rotation.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the ciphertext is malformed or made with an unknown key.
+         */
+        public function needsReencrypt(string $ciphertext): bool {}
+
+        /**
+         * Constructs a box from one key or a key ring.
+         *
+         * # Parameters
+         * - `keys`: `string|array` Base64URL-encoded 32-byte key, or a list of them. The first
+         *   key encrypts, all of them decrypt, so a new key goes first and old ones stay until
+         *   their data has been re-encrypted.
+         *
+         * # Exceptions
+         * - Throws `Exception` if no key is given or a key is not 32 bytes of Base64URL.
+         */
+        public function __construct(mixed $keys) {}
+    }
+
+    /**
+     * Key derivation functions.
+     */
+    class Kdf {
+        /**
+         * Derive key material from a high-entropy key with HKDF.
+         *
+         * # Parameters
+         * - `key`: `string` Input key material (raw bytes). Not suitable for passwords.
+         * - `context`: `string` Label saying what the output is for, e.g. `"invoices:encryption"`.
+         *   Different labels give independent keys.
+         * - `length`: `?int` Output length in bytes. Default: 32.
+         * - `salt`: `?string` Optional salt. Default: none.
+         * - `algorithm`: `?string` `"sha256"` or `"sha512"`. Default: `"sha256"`.
+         *
+         * # Returns
+         * - `string` Raw derived bytes.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the context is empty, the length is out of range or the
+         *   algorithm is unknown.
+         */
+        public static function hkdf(string $key, string $context, ?int $length, ?string $salt, ?string $algorithm): string {}
+
+        /**
+         * Derive a per-purpose key from a master key.
+         *
+         * # Parameters
+         * - `masterKey`: `string` Base64URL-encoded 32-byte key.
+         * - `context`: `string` Label for the derived key, e.g. `"users:42"`.
+         *
+         * # Returns
+         * - `string` Base64URL-encoded 32-byte key, usable with `SecretBox`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the master key is invalid or the context is empty.
+         */
+        public static function deriveKey(string $master_key, string $context): string {}
+
+        /**
+         * Derive a key from a password with PBKDF2-HMAC.
+         *
+         * # Parameters
+         * - `password`: `string` The password.
+         * - `salt`: `string` Random salt of at least 16 bytes, stored alongside the result.
+         * - `iterations`: `?int` Iteration count, at least 100000. Default: 600000.
+         * - `length`: `?int` Output length in bytes, at most 64. Default: 32.
+         * - `algorithm`: `?string` `"sha256"` or `"sha512"`. Default: `"sha256"`.
+         *
+         * # Returns
+         * - `string` Raw derived bytes.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the salt is too short, the iteration count too low, the length
+         *   out of range or the algorithm unknown.
+         */
+        public static function pbkdf2(string $password, string $salt, ?int $iterations, ?int $length, ?string $algorithm): string {}
+    }
+
+    /**
+     * Envelope encryption under a rotatable master key ring.
+     */
+    class Envelope {
+        /**
+         * Encrypt a message under a new data key.
+         *
+         * # Parameters
+         * - `plaintext`: `string` The message.
+         * - `aad`: `?string` Associated data, authenticated but not encrypted. Default: `''`.
+         *
+         * # Returns
+         * - `string` Base64URL-encoded envelope holding the wrapped data key and the ciphertext.
+         *
+         * # Exceptions
+         * - Throws `Exception` if encryption fails.
+         */
+        public function encrypt(string $plaintext, ?string $aad): string {}
+
+        /**
+         * Decrypt an envelope.
+         *
+         * # Parameters
+         * - `envelope`: `string` Output of `encrypt()` or `rewrap()`.
+         * - `aad`: `?string` The associated data given to `encrypt()`. Default: `''`.
+         *
+         * # Returns
+         * - `string` The plaintext.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the envelope is malformed, its master key is not configured, or
+         *   it fails authentication.
+         */
+        public function decrypt(string $envelope, ?string $aad): string {}
+
+        /**
+         * Re-wrap an envelope's data key under the current master key without re-encrypting
+         * the data.
+         *
+         * # Parameters
+         * - `envelope`: `string` Output of `encrypt()`.
+         * - `aad`: `?string` The associated data given to `encrypt()`. Default: `''`.
+         *
+         * # Returns
+         * - `string` The updated envelope.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the envelope cannot be unwrapped.
+         */
+        public function rewrap(string $envelope, ?string $aad): string {}
+
+        /**
+         * Whether an envelope's data key is wrapped with a master key other than the first.
+         *
+         * # Parameters
+         * - `envelope`: `string` Output of `encrypt()`.
+         *
+         * # Returns
+         * - `bool` `true` if the envelope should be passed to `rewrap()`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the envelope is malformed or its master key is not configured.
+         */
+        public function needsRewrap(string $envelope): bool {}
+
+        /**
+         * Generate a data key for use outside of `encrypt()`, e.g. with `SecretBox`.
+         *
+         * # Parameters
+         * - `context`: `?string` Label the wrapped key is bound to. Default: `''`.
+         *
+         * # Returns
+         * - `array` `key` (Base64URL-encoded data key, keep in memory only) and `wrapped`
+         *   (Base64URL-encoded wrapped key, to store).
+         *
+         * # Exceptions
+         * - Throws `Exception` if wrapping fails.
+         */
+        public function generateDataKey(?string $context): array {}
+
+        /**
+         * Unwrap a data key made by `generateDataKey()`.
+         *
+         * # Parameters
+         * - `wrapped`: `string` The `wrapped` value.
+         * - `context`: `?string` The context given to `generateDataKey()`. Default: `''`.
+         *
+         * # Returns
+         * - `string` Base64URL-encoded data key.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the wrapped key is malformed, its master key is not
+         *   configured, or it fails authentication.
+         */
+        public function unwrapKey(string $wrapped, ?string $context): string {}
+
+        /**
+         * Constructs an envelope from one master key or a master key ring.
+         *
+         * # Parameters
+         * - `masterKeys`: `string|array` Base64URL-encoded 32-byte key, or a list of them. The
+         *   first wraps new data keys, all of them unwrap.
+         *
+         * # Exceptions
+         * - Throws `Exception` if no key is given or a key is not 32 bytes of Base64URL.
+         */
+        public function __construct(mixed $master_keys) {}
+    }
+}
+
+namespace Hardened\Http {
+    /**
+     * `Content-Disposition` header values.
+     */
+    class ContentDisposition {
+        /**
+         * Build a header value that makes the browser download the response.
+         *
+         * # Parameters
+         * - `filename`: `string` Suggested file name; cleaned of control characters, invisible
+         *   bidirectional overrides and path separators.
+         *
+         * # Returns
+         * - `string` e.g. `attachment; filename="resume.pdf"; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf`
+         *   for `résumé.pdf`.
+         */
+        public static function attachment(string $filename): string {}
+
+        /**
+         * Build a header value that lets the browser display the response, with a file name for
+         * when the user saves it.
+         *
+         * # Parameters
+         * - `filename`: `string` Suggested file name, cleaned as by `attachment()`.
+         *
+         * # Returns
+         * - `string` e.g. `inline; filename="report.pdf"`.
+         */
+        public static function inline(string $filename): string {}
+    }
+}
+
+namespace Hardened\Sanitizers {
+    class HtmlSanitizer {
+        /**
+         * Constructs a sanitizer with default configuration.
+         *
+         * # Returns
+         * - HtmlSanitizer A new sanitizer instance.
+         */
+        public static function newDefault(): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Denies all relative URLs in attributes.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function urlRelativeDeny(): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Checks whether a URL is valid according to the sanitizer’s configured
+         * URL scheme whitelist and relative-URL policy.
+         *
+         * # Parameters
+         * - `url`: The URL string to validate.
+         *
+         * # Returns
+         * - `bool`: `true` if the URL’s scheme is whitelisted, or if it is a relative URL
+         *   and relative URLs are permitted; `false` otherwise.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the sanitizer is not in a valid state.
+         */
+        public function isValidUrl(string $url): bool {}
+
+        /**
+         * Passes through relative URLs unchanged.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function urlRelativePassthrough(): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Rewrites relative URLs using the given base URL.
+         *
+         * # Parameters
+         * - `base_url`: The base URL to resolve relative URLs against.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         * - Exception if `base_url` is not a valid URL.
+         */
+        public function urlRelativeRewriteWithBase(string $base_url): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Rewrites relative URLs using a root URL and path prefix.
+         *
+         * # Parameters
+         * - `root`: The root URL string.
+         * - `path`: The URL path prefix.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         * - Exception if `root` is not a valid URL.
+         */
+        public function urlRelativeRewriteWithRoot(string $root, string $path): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Sets the `rel` attribute for generated `<a>` tags.
+         *
+         * # Parameters
+         * - `value`: Optional `rel` attribute value; `None` clears it.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function linkRel(?string $value): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Overwrites the set of allowed tags.
+         *
+         * # Parameters
+         * - `tags`: An array of allowed tag names.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         * - Exception if `tags` is not an array.
+         */
+        public function tags(array $tags): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Sets the tags whose contents will be completely removed from the output.
+         *
+         * # Parameters
+         * - `tags`: An array of allowed tag names.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         * - Exception if `tags` is not an array.
+         * - Adding tags which are whitelisted in tags or tag_attributes will cause a panic.
+         */
+        public function cleanContentTags(array $tags): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Add additional blacklisted clean-content tags without overwriting old ones.
+         *
+         * Does nothing if the tag is already there.
+         *
+         * # Parameters
+         * - `tags`: An array of tag names to add.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         * - Exception if `tags` is not an array.
+         */
+        public function addCleanContentTags(array $tags): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Remove already-blacklisted clean-content tags.
+         *
+         * Does nothing if the tags aren’t blacklisted.
+         *
+         * # Parameters
+         * - `tags`: An array of tag names to add.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         * - Exception if `tags` is not an array.
+         */
+        public function rmCleanContentTags(array $tags): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Adds additional allowed tags to the existing whitelist.
+         *
+         * # Parameters
+         * - `tags`: An array of tag names to add.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         * - Exception if `tags` is not an array.
+         */
+        public function addTags(array $tags): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Removes tags from the whitelist.
+         *
+         * # Parameters
+         * - `tags`: An array of tag names to remove.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function rmTags(array $tags): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Adds allowed CSS classes for a specific tag.
+         *
+         * # Parameters
+         * - `tag`: A string tag name.
+         * - `classes`: An array of CSS class names.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function addAllowedClasses(string $tag, array $classes): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Removes allowed CSS classes from a specific tag.
+         *
+         * # Parameters
+         * - `tag`: A string tag name.
+         * - `classes`: An array of CSS class names to remove.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function rmAllowedClasses(string $tag, array $classes): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Adds allowed attributes to a specific tag.
+         *
+         * # Parameters
+         * - `tag`: A string tag name.
+         * - `attributes`: An array of attribute names.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function addTagAttributes(string $tag, array $attributes): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Removes attributes from a specific tag.
+         *
+         * # Parameters
+         * - `tag`: A string tag name.
+         * - `classes`: An array of attribute names to remove.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function rmTagAttributes(string $tag, array $classes): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Adds generic attributes to all tags.
+         *
+         * # Parameters
+         * - `attributes`: An array of attribute names to allow.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         * - `Exception` if `attributes` is not an array.
+         */
+        public function addGenericAttributes(array $attributes): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Removes generic attributes from all tags.
+         *
+         * # Parameters
+         * - `attributes`: An array of attribute names to remove.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function rmGenericAttributes(array $attributes): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Adds prefixes for generic attributes.
+         *
+         * # Parameters
+         * - `prefixes`: An array of prefixes to allow.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function addGenericAttributePrefixes(array $prefixes): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Removes prefixes for generic attributes.
+         *
+         * # Parameters
+         * - `prefixes`: An array of prefixes to remove.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function rmGenericAttributePrefixes(array $prefixes): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Sanitizes the given HTML string, applying any configured attribute filter.
+         *
+         * # Parameters
+         * - `html`: The HTML content to sanitize.
+         *
+         * # Returns
+         * - `String` The sanitized HTML.
+         *
+         * # Notes
+         * - If an attribute filter is set, it will be invoked for each attribute.
+         * - After `useCspNonce()`, `nonce` attributes from the input never reach the filter.
+         */
+        public function clean(string $html): string {}
+
+        /**
+         * Whitelists URL schemes (e.g., "http", "https").
+         *
+         * # Parameters
+         * - `schemes`: An array of scheme strings to allow.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function urlSchemes(array $schemes): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Enables or disables HTML comment stripping.
+         *
+         * # Parameters
+         * - `strip`: `true` to strip comments; `false` to preserve them.
+         *    Comments are stripped by default.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function stripComments(bool $strip): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Returns whether HTML comments will be stripped.
+         *
+         * # Returns
+         * - `bool`: `true` if comments will be stripped; `false` otherwise.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function willStripComments(): bool {}
+
+        /**
+         * Prefixes all `id` attributes with the given string.
+         *
+         * # Parameters
+         * - `prefix`: Optional string prefix to apply.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function idPrefix(?string $prefix): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Filters CSS style properties allowed in `style` attributes.
+         *
+         * # Parameters
+         * - `props`: An array of CSS property names to allow.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function newFilterStyleProperties(array $props): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        public function filterStyleProperties(array $props): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Sets a single tag attribute value.
+         *
+         * # Parameters
+         * - `tag`: The tag name as A string.
+         * - `attribute`: The attribute name as A string.
+         * - `value`: The value to set.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function setTagAttributeValue(string $tag, string $attribute, string $value): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Returns the configured tags as a vector of strings.
+         *
+         * # Returns
+         * - `Vec<String>` The list of allowed tag names.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function cloneTags(): array {}
+
+        /**
+         * Gets all configured clean-content tags.
+         *
+         * # Returns
+         * - `Vec<String>` The list of tags whose content is preserved.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function cloneCleanContentTags(): array {}
+
+        /**
+         * Bulk overwrites generic attributes.
+         *
+         * # Parameters
+         * - `attrs`: An array of attribute names.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function genericAttributes(array $attrs): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Bulk overwrites generic attribute prefixes.
+         *
+         * # Parameters
+         * - `prefixes`: An array of prefixes.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function genericAttributePrefixes(array $prefixes): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Adds tag attribute values.
+         *
+         * # Parameters
+         * - `tag`: A string tag name.
+         * - `attr`: A string attribute name.
+         * - `values`: An array of values to allow.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function addTagAttributeValues(string $tag, string $attr, array $values): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Removes tag attribute values.
+         *
+         * # Parameters
+         * - `tag`: A string tag name.
+         * - `attr`: A string attribute name.
+         * - `values`: An array of values to remove.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function rmTagAttributeValues(string $tag, string $attr, array $values): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Gets a single tag attribute value setting.
+         *
+         * # Parameters
+         * - `tag`: The tag name as A string.
+         * - `attr`: The attribute name as A string.
+         *
+         * # Returns
+         * - `Option<String>` The configured value or `None` if unset.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function getSetTagAttributeValue(string $tag, string $attr): ?string {}
+
+        /**
+         * Checks if URL relative policy is Deny.
+         *
+         * # Returns
+         * - `bool` `true` if the policy is Deny.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function isUrlRelativeDeny(): bool {}
+
+        /**
+         * Checks if URL relative policy is PassThrough.
+         *
+         * # Returns
+         * - `bool` `true` if the policy is PassThrough.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function isUrlRelativePassThrough(): bool {}
+
+        /**
+         * Checks if URL relative policy is custom (Rewrite).
+         *
+         * # Returns
+         * - `bool` `true` if a custom rewrite policy is set.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function isUrlRelativeCustom(): bool {}
+
+        /**
+         * Sets the attribute filter callback.
+         *
+         * # Parameters
+         * - `callable`: A PHP callable of signature `(string Element, string Attribute, string Value) -> string|null`.
+         *
+         * # Exceptions
+         * - None.
+         */
+        public function attributeFilter(mixed $callable): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Lets trusted `<script>` and `<style>` blocks run under a nonce-based CSP.
+         *
+         * Every `<script>` and `<style>` tag that the sanitizer allows gets `nonce="…"` with the
+         * policy's nonce (generating it if needed), and `nonce` attributes in the input are
+         * dropped everywhere, so an attacker cannot smuggle in a guessed or leaked nonce.
+         * Only allow these tags for input you trust, e.g. embed snippets from your own CMS.
+         *
+         * The nonce is copied: call this again after `$csp->resetNonce()`.
+         *
+         * # Parameters
+         * - `csp`: `ContentSecurityPolicy` The policy sent with this response.
+         *
+         * # Exceptions
+         * - `Exception` if the sanitizer is not in a valid state.
+         */
+        public function useCspNonce(\Hardened\SecurityHeaders\ContentSecurityPolicy $csp): \Hardened\Sanitizers\HtmlSanitizer {}
+
+        /**
+         * Sanitize and truncate the given HTML by extended grapheme clusters.
+         *
+         * This is a convenience wrapper that ensures no user-perceived character
+         * (including complex emoji or combined sequences) is split in half.
+         *
+         * # Parameters
+         * - `html`: Raw HTML string to sanitize and truncate.
+         * - `max_units`: Maximum number of Unicode extended grapheme clusters
+         *   to retain (including the `etc` suffix).
+         * - `etc`: Optional suffix (e.g., ellipsis) to join when truncation occurs. Default is …
+         *
+         * # Exceptions
+         * - Throws `Exception` if sanitization or truncation fails.
+         */
+        public function cleanAndTruncate(string $html, int $max, array $flags, ?string $etc): string {}
+
+        /**
+         * Constructs a sanitizer with default configuration.
+         *
+         * # Returns
+         * - HtmlSanitizer A new sanitizer instance.
+         */
+        public function __construct() {}
+    }
+
+    enum HtmlSanitizerFlag: string {
+        case ExtendedGraphemes = 'extended-graphemes';
+
+        case Graphemes = 'graphemes';
+
+        case Unicode = 'unicode';
+
+        case Ascii = 'ascii';
+
+        case PreserveWords = 'preserve-words';
+    }
+
+    /**
+     * Markdown to sanitized HTML: CommonMark with GFM tables and strikethrough, rendered by
+     * pulldown-cmark and cleaned by an `HtmlSanitizer`.
+     */
+    class MarkdownSanitizer {
+        /**
+         * Enables or disables raw HTML in the Markdown.
+         *
+         * When disabled (the default), HTML in the input is shown as text. When enabled, it is
+         * passed on to the `HtmlSanitizer`, which still removes anything it does not allow.
+         *
+         * # Parameters
+         * - `allow`: `bool`
+         */
+        public function rawHtml(bool $allow): \Hardened\Sanitizers\MarkdownSanitizer {}
+
+        /**
+         * Sets the URL schemes links, autolinks and images may use; other absolute URLs are
+         * reduced to their text. Relative URLs are always kept.
+         *
+         * # Parameters
+         * - `schemes`: `string[]` e.g. `['https', 'mailto']`. Default `http`, `https`, `mailto`.
+         */
+        public function linkSchemes(array $schemes): \Hardened\Sanitizers\MarkdownSanitizer {}
+
+        /**
+         * Sets what becomes of images.
+         *
+         * # Parameters
+         * - `policy`: `string` `allow` (render `<img>`, the default), `link` (a link to the image)
+         *   or `alt` (only the alt text).
+         *
+         * # Exceptions
+         * - Throws `Exception` if the policy is unknown.
+         */
+        public function imagePolicy(string $policy): \Hardened\Sanitizers\MarkdownSanitizer {}
+
+        /**
+         * Renders Markdown to HTML and sanitizes the result.
+         *
+         * # Parameters
+         * - `markdown`: `string` Untrusted Markdown.
+         * - `sanitizer`: `?HtmlSanitizer` Policy for the rendered HTML; a default
+         *   `HtmlSanitizer` if omitted.
+         *
+         * # Returns
+         * - `string` Sanitized HTML.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the `HtmlSanitizer` is not in a valid state.
+         */
+        public function clean(string $markdown, ?\Hardened\Sanitizers\HtmlSanitizer $sanitizer): string {}
+
+        /**
+         * Constructs a sanitizer: raw HTML is escaped, links may use `http`, `https` and
+         * `mailto`, and images are allowed.
+         *
+         * # Returns
+         * - MarkdownSanitizer A new sanitizer instance.
+         */
+        public function __construct() {}
+    }
+
+    /**
+     * BBCode to safe HTML for content that cannot be migrated away from BBCode.
+     *
+     * Every piece of output is generated from escaped text and a fixed set of elements, so the
+     * result needs no further sanitization. Tags that are unknown, disabled, unbalanced or carry
+     * a rejected URL are shown as text.
+     */
+    class BbcodeSanitizer {
+        /**
+         * Sets the enabled tags; other tags are shown as text.
+         *
+         * # Parameters
+         * - `tags`: `string[]` e.g. `['b', 'i', 'url', 'quote']`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if a tag is not supported.
+         */
+        public function tags(array $tags): \Hardened\Sanitizers\BbcodeSanitizer {}
+
+        /**
+         * Converts BBCode to HTML.
+         *
+         * `[url]` and `[img]` accept only absolute `http`/`https` URLs without credentials, which
+         * must also pass `urlPolicy` if given. Links get `rel="nofollow ugc noopener"`. Line
+         * breaks become `<br>`, except inside `[code]`.
+         *
+         * # Parameters
+         * - `bbcode`: `string` Untrusted BBCode.
+         * - `urlPolicy`: `?HostnamePolicy` Hosts, schemes and ports allowed for links and images.
+         *
+         * # Returns
+         * - `string` Safe HTML.
+         */
+        public function clean(string $bbcode, ?\Hardened\HostnamePolicy $url_policy): string {}
+
+        /**
+         * Constructs a sanitizer with every supported tag enabled: `b`, `i`, `u`, `s`, `url`,
+         * `img`, `quote` and `code`.
+         *
+         * # Returns
+         * - BbcodeSanitizer A new sanitizer instance.
+         */
+        public function __construct() {}
+    }
+
+    /**
+     * Stylesheet sanitizer for untrusted CSS such as user themes.
+     *
+     * Keeps style rules whose selectors are well-formed, `@media`, `@supports`, `@keyframes` and
+     * `@font-face`; every other at-rule (`@import`, `@charset`, `@namespace`, ...) is removed.
+     * Declarations are kept only if the property is allowed and every value token is safe:
+     * functions come from a fixed allowlist (no `expression()`), and `url()` must pass the URL
+     * policy given to `clean()`.
+     */
+    class CssSanitizer {
+        /**
+         * Sets the allowed properties, replacing the default list. Vendor-prefixed variants
+         * (`-webkit-transform`) follow their unprefixed property; `behavior` and `-moz-binding`
+         * are never allowed.
+         *
+         * # Parameters
+         * - `properties`: `string[]` e.g. `['color', 'background-color', 'font-family']`.
+         */
+        public function allowedProperties(array $properties): \Hardened\Sanitizers\CssSanitizer {}
+
+        /**
+         * Sets the largest stylesheet accepted, in bytes.
+         *
+         * # Parameters
+         * - `bytes`: `int` Default 262144.
+         */
+        public function maxBytes(int $bytes): \Hardened\Sanitizers\CssSanitizer {}
+
+        /**
+         * Sets the most rules (style rules and at-rules) the output may contain.
+         *
+         * # Parameters
+         * - `rules`: `int` Default 2000.
+         */
+        public function maxRules(int $rules): \Hardened\Sanitizers\CssSanitizer {}
+
+        /**
+         * Sanitizes a stylesheet.
+         *
+         * `url()` accepts only absolute `http`/`https` URLs without credentials that pass
+         * `urlPolicy`; without a policy, declarations using `url()` are dropped. Strings are
+         * re-escaped so the result cannot close a `<style>` element.
+         *
+         * # Parameters
+         * - `css`: `string` Untrusted stylesheet.
+         * - `urlPolicy`: `?HostnamePolicy` Hosts, schemes and ports allowed in `url()`.
+         *
+         * # Returns
+         * - `string` Sanitized stylesheet, one rule per line.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the stylesheet exceeds the size or rule limit.
+         */
+        public function clean(string $css, ?\Hardened\HostnamePolicy $url_policy): string {}
+
+        /**
+         * Constructs a sanitizer with the default property allowlist (colors, typography, box
+         * model, flex and grid, transitions and animations; no `position` or `z-index`), a limit
+         * of 256 KiB and 2000 rules.
+         *
+         * # Returns
+         * - CssSanitizer A new sanitizer instance.
+         */
+        public function __construct() {}
+    }
+
+    class SvgSanitizer {
+        const PRESET_STRICT = null;
+
+        const PRESET_STANDARD = null;
+
+        const PRESET_PERMISSIVE = null;
+
+        /**
+         * Create a new SvgSanitizer with default (standard) settings
+         */
+        public static function newDefault(): \Hardened\Sanitizers\SvgSanitizer {}
+
+        /**
+         * Create a sanitizer with a named preset
+         */
+        public static function withPreset(string $preset_name): \Hardened\Sanitizers\SvgSanitizer {}
+
+        /**
+         * Static method for file-based bomb detection (throws on dangerous SVG)
+         */
+        public static function defuse(string $path, ?int $max_dimension) {}
+
+        /**
+         * Sanitize SVG content string
+         */
+        public function clean(string $svg): string {}
+
+        /**
+         * Sanitize SVG file and return cleaned content
+         */
+        public function cleanFile(string $path): string {}
+
+        /**
+         * Check if SVG content is safe without modification
+         */
+        public function isSafe(string $svg): bool {}
+
+        /**
+         * Check if SVG file is safe without modification
+         */
+        public function isSafeFile(string $path): bool {}
+
+        /**
+         * Set allowed SVG elements (overwrites defaults)
+         */
+        public function allowElements(array $elements): \Hardened\Sanitizers\SvgSanitizer {}
+
+        /**
+         * Add elements to the allowlist
+         */
+        public function addAllowedElements(array $elements): \Hardened\Sanitizers\SvgSanitizer {}
+
+        /**
+         * Remove elements from the allowlist
+         */
+        public function removeElements(array $elements): \Hardened\Sanitizers\SvgSanitizer {}
+
+        /**
+         * Set allowed attributes (overwrites defaults)
+         */
+        public function allowAttributes(array $attributes): \Hardened\Sanitizers\SvgSanitizer {}
+
+        /**
+         * Add attributes to the allowlist
+         */
+        public function addAllowedAttributes(array $attributes): \Hardened\Sanitizers\SvgSanitizer {}
+
+        /**
+         * Remove attributes from the allowlist
+         */
+        public function removeAttributes(array $attributes): \Hardened\Sanitizers\SvgSanitizer {}
+
+        /**
+         * Set maximum allowed dimension (width/height/viewBox)
+         */
+        public function setMaxDimension(int $max): \Hardened\Sanitizers\SvgSanitizer {}
+
+        /**
+         * Set maximum nesting depth
+         */
+        public function setMaxNestingDepth(int $max): \Hardened\Sanitizers\SvgSanitizer {}
+
+        /**
+         * Enable/disable blocking of external references (http/https URLs)
+         */
+        public function blockExternalReferences(bool $block): \Hardened\Sanitizers\SvgSanitizer {}
+
+        /**
+         * Enable/disable blocking of data: URIs
          */
         public function blockDataUris(bool $block): \Hardened\Sanitizers\SvgSanitizer {}
 
         /**
-         * Enable/disable XML comments removal
+         * Enable/disable XML comments removal
+         */
+        public function stripComments(bool $strip): \Hardened\Sanitizers\SvgSanitizer {}
+
+        /**
+         * Allow relative URLs
+         */
+        public function allowRelativeUrls(bool $allow): \Hardened\Sanitizers\SvgSanitizer {}
+
+        public function __construct() {}
+    }
+}
+
+namespace Hardened\Sanitizers\File {
+    /**
+     * Engine for detecting "PNG bombs" (images with unreasonable dimensions).
+     */
+    class PngSanitizer {
+        /**
+         * Scan a file at the given path and detect PNG bombs.
+         *
+         * # Parameters
+         * - `path`: `string` Filesystem path to the PNG file.
+         *
+         * # Returns
+         * - `bool` `true` if the file is a PNG *and* has width or height > 10000,
+         *   or if it's invalid PNG with missing IHDR. Returns `false` if it's not
+         *   a PNG or has acceptable dimensions.
+         *
+         * # Exceptions
+         * - Throws an exception if the file cannot be opened, read, or the format
+         *   is malformed (e.g. missing IHDR).
+         *
+         * ## Example
+         * ```php
+         * Hardened\Sanitizers\File\PngSanitizer::defuse('/tmp/image.png');
+         * ```
+         */
+        public static function defuse(string $path) {}
+    }
+
+    /**
+     * Archive bomb detector for ZIP and RAR files.
+     *
+     * Provides two methods in PHP:
+     *   - `scan_zip(string $path): bool`
+     *   - `scan_rar(string $path, ?int $maxRatio = 1000): bool`
+     */
+    class ArchiveSanitizer {
+        /**
+         * Perform archive‐bomb detection on a file.
+         *
+         * This internal helper examines the file at `path` and returns an error if it
+         * appears to be a "bomb" (i.e. an archive whose reported uncompressed size
+         * far exceeds its on‐disk compressed size or mismatches the local header).
+         *
+         * **ZIP**:
+         * - Reads the central directory to sum the uncompressed sizes of all entries.
+         * - Reads the 4‐byte little‐endian uncompressed size from the local file header at offset 22.
+         * - Fails if those two values differ.
+         *
+         * **RAR**:
+         * - Computes the on‐disk file size.
+         * - Lists the first entry's `unpacked_size` and divides by the compressed size.
+         * - Fails if that ratio ≥ `max_ratio` (default 1000).
+         *
+         * # Parameters
+         * - `path`: Filesystem path to the archive file to inspect.
+         * - `max_ratio`: Optional maximum unpacked/compressed ratio for RAR; Default is 1000
+         *
+         * # Exceptions
+         * - I/O errors opening, reading, or seeking the file.
+         * - ZIP archive mismatches (central-directory total vs. local-header size).
+         * - RAR archive exceeds the allowed unpacked/compressed ratio.
+         */
+        public static function defuse(string $path, ?int $max_ratio) {}
+    }
+
+    /**
+     * Client for the ClamAV daemon.
+     *
+     * Content is always streamed with `INSTREAM`, so clamd needs no access to the file system of
+     * the PHP process.
+     */
+    class AvScanner {
+        /**
+         * Scan a file by streaming its contents to clamd.
+         *
+         * # Parameters
+         * - `path`: `string` File to scan, e.g. an upload's `tmp_name`.
+         *
+         * # Returns
+         * - `array` `['infected' => bool, 'signature' => ?string]`, the signature being the name
+         *   of the detected malware.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the file cannot be read, clamd cannot be reached or times out,
+         *   or clamd reports an error (e.g. the file exceeds its `StreamMaxLength`).
+         */
+        public function scanFile(string $path): array {}
+
+        /**
+         * Scan the remaining contents of a PHP stream.
+         *
+         * Reads with `fread()` until it returns an empty string, so the stream is left at its end.
+         *
+         * # Parameters
+         * - `stream`: `resource` Readable stream, e.g. from `fopen()` or `php://input`.
+         *
+         * # Returns
+         * - `array` As for `scanFile()`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if reading fails, clamd cannot be reached or times out, or clamd
+         *   reports an error.
+         */
+        public function scanStream(mixed $stream): array {}
+
+        /**
+         * Constructs a scanner.
+         *
+         * # Parameters
+         * - `address`: `string` clamd socket: `unix:///var/run/clamav/clamd.ctl`, `/path/to/socket`,
+         *   `tcp://127.0.0.1:3310` or `127.0.0.1:3310`.
+         * - `timeoutMs`: `?int` Connect, read and write timeout in milliseconds (default `30000`).
+         *
+         * # Exceptions
+         * - Throws `Exception` if the address is malformed or the timeout is not positive.
+         */
+        public function __construct(string $address, ?int $timeout_ms) {}
+    }
+}
+
+namespace Hardened\SecurityHeaders {
+    /**
+     * Your application's CSP config.
+     */
+    class ContentSecurityPolicy {
+        /**
+         * Sets or replaces a CSP directive with the given keywords and host sources.
+         *
+         * # Parameters
+         * - `rule`: The directive name. One of `default-src`, `script-src`, `style-src`, `img-src`, `frame-ancestors`,
+         *   `connect-src`, `font-src`, `child-src`, `manifest-src`, `media-src`, `object-src`, `prefetch-src`,
+         *   `script-src-elem`, `script-src-attr`, `style-src-elem`, `style-src-attr`, `worker-src`,
+         *   `base-uri`, `form-action`, `sandbox`, `plugin-types`, `block-all-mixed-content`,
+         *   `upgrade-insecure-requests`, `report-uri`, `report-to`, `require-sri-for`,
+         *   `trusted-types`, `require-trusted-types-for`.
+         * - `keywords`: Slice of keyword tokens. One or more of `self`, `none`, `unsafe-inline`,
+         *   `unsafe-eval`, `unsafe-hashes`, `strict-dynamic`, `nonce`, `script`, `style`,
+         *   `allow-forms`, `allow-modals`, `allow-orientation-lock`, `allow-pointer-lock`,
+         *   `allow-presentation`, `allow-popups`, `allow-popups-to-escape-sandbox`,
+         *   `allow-same-origin`, `allow-scripts`, `allow-storage-access-by-user-activation`,
+         *   `allow-top-navigation-by-user-activation`, `allow-duplicates`, `wasm-unsafe-eval`,
+         *   `inline-speculation-rules`, `report-sample`.
+         * - `sources`: Optional list of host sources (e.g. `["example.com"]`)
+         *
+         * # Exceptions
+         * - Throws `Exception` if any array item in `keywords` is not a string.
+         * - Throws `Exception` if `rule` is not a valid CSP directive.
+         */
+        public function setRule(\Hardened\SecurityHeaders\CspRule $rule, array $keywords, ?array $sources) {}
+
+        /**
+         * Sets or replaces a directive of the report-only policy, which is sent alongside the
+         * enforced one as `Content-Security-Policy-Report-Only`.
+         *
+         * Use it to trial a stricter policy: violations are reported but nothing is blocked.
+         * Both policies share the same nonce.
+         *
+         * # Parameters
+         * - `rule`: `CspRule` The directive.
+         * - `keywords`: `CspKeyword[]` Keyword tokens.
+         * - `sources`: `?string[]` Host sources.
+         */
+        public function setReportOnlyRule(\Hardened\SecurityHeaders\CspRule $rule, array $keywords, ?array $sources) {}
+
+        /**
+         * Builds the `Content-Security-Policy-Report-Only` header value.
+         *
+         * # Returns
+         * - `string` The header value; empty if no report-only directive is set.
+         *
+         * # Exceptions
+         * - Throws `Exception` if formatting the header string fails.
+         */
+        public function buildReportOnly(): string {}
+
+        /**
+         * Send only the `Content-Security-Policy-Report-Only` header via PHP `header()`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the PHP `header()` function cannot be invoked.
+         */
+        public function sendReportOnly() {}
+
+        /**
+         * Builds the `Content-Security-Policy` header value from the configured directives.
+         *
+         * # Returns
+         * - `String` The full header value, for example:
+         *   `"default-src 'self'; script-src 'self' 'nonce-ABCD1234' example.com; …"`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if formatting the header string fails.
+         */
+        public function build(): string {}
+
+        /**
+         * Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+         * headers to a worker runtime's response object.
+         *
+         * # Parameters
+         * - `emitter`: `callable(string $name, string $value): void`
+         *
+         * # Exceptions
+         * - Throws `Exception` if `emitter` is not callable.
+         */
+        public function setEmitter(mixed $emitter) {}
+
+        /**
+         * Builds the headers `send()` would emit.
+         *
+         * # Returns
+         * - `list<array{string, string}>` `[name, value]` pairs in sending order.
+         */
+        public function buildPairs(): array {}
+
+        /**
+         * Send the `Content-Security-Policy` header via PHP `header()`, followed by
+         * `Content-Security-Policy-Report-Only` if a report-only policy is configured.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the PHP `header()` function cannot be invoked.
+         */
+        public function send() {}
+
+        /**
+         * Returns the most recently generated nonce, if any.
+         *
+         * # Returns
+         * - `Option<&str>` The raw nonce string (without the `'nonce-'` prefix), or `None` if `build()` has not yet generated one.
+         */
+        public function getNonce(): ?string {}
+
+        /**
+         * Clears the generated nonce. The next call of `build()` or `send()` will generate a new one.
+         */
+        public function resetNonce() {}
+
+        /**
+         * Creates the strict policy recommended by Google as a starting point.
+         *
+         * `script-src 'nonce-…' 'strict-dynamic' 'unsafe-inline' https:; object-src 'none';
+         * base-uri 'none'`. Browsers supporting CSP3 only trust nonced scripts (and those they
+         * load); `https:` and `'unsafe-inline'` are fallbacks that are ignored by such browsers.
+         * Put `nonce()` on every `<script>` tag.
+         *
+         * # Parameters
+         * - `reportEndpoint`: `?string` URL for violation reports, emitted as `report-uri`.
+         *
+         * # Returns
+         * - `ContentSecurityPolicy` The preset, ready to be extended.
+         *
+         * # Exceptions
+         * - Throws an exception if `reportEndpoint` contains whitespace, `;` or `,`.
+         */
+        public static function strictPreset(?string $report_endpoint): \Hardened\SecurityHeaders\ContentSecurityPolicy {}
+
+        /**
+         * Parses an existing `Content-Security-Policy` header value into a policy object.
+         *
+         * Directive names and keywords are case-insensitive. As browsers do, a repeated
+         * directive is ignored after its first occurrence. `'none'` becomes an empty directive,
+         * hash sources are kept as sources, and `'nonce-…'` becomes the `nonce` keyword, so a
+         * fresh nonce is generated by `build()` instead of replaying the parsed one.
+         *
+         * # Parameters
+         * - `header`: `string` The header value, e.g. `"default-src 'self'; img-src *"`.
+         *
+         * # Returns
+         * - `ContentSecurityPolicy` A policy that can be modified and rebuilt.
+         *
+         * # Exceptions
+         * - Throws an exception on an unknown directive or keyword.
+         */
+        public static function parse(string $header): \Hardened\SecurityHeaders\ContentSecurityPolicy {}
+
+        /**
+         * Checks the policy for common weaknesses, in the spirit of Google's CSP Evaluator.
+         *
+         * Checks include `'unsafe-inline'`/`'unsafe-eval'`, wildcard and `data:` script sources,
+         * missing `object-src`/`base-uri` and deprecated directives. Intended for CI, e.g.
+         * failing the build when any `high` finding appears.
+         *
+         * # Returns
+         * - `array` Findings, most severe first: `['severity' => 'high'|'medium'|'low',
+         *   'directive' => string, 'message' => string]`. Empty if nothing was found.
+         */
+        public function audit(): array {}
+
+        /**
+         * Merges another policy into this one, e.g. a per-page addition into a base platform policy.
+         *
+         * `union` keeps every keyword and source of either policy, while `intersection` keeps
+         * only what both allow (an empty result becomes `'none'`). A fetch directive set in only
+         * one policy is merged with what the other enforces for it instead: its `default-src`, or
+         * `*` without one. Other directives set in only one policy are copied as they are.
+         * Sources are compared literally, except that `*` covers host and scheme sources, and
+         * keep the order of first appearance, so the result is deterministic.
+         * Report-only policies are merged the same way; this policy's nonce is kept if it
+         * already has one.
+         *
+         * # Parameters
+         * - `other`: `ContentSecurityPolicy` The policy to merge in.
+         * - `strategy`: `string` `union` or `intersection`.
+         *
+         * # Exceptions
+         * - Throws an exception if `strategy` is unknown.
+         */
+        public function merge(\Hardened\SecurityHeaders\ContentSecurityPolicy $other, string $strategy) {}
+
+        /**
+         * Adds a per-request nonce to the given directives and returns it.
+         *
+         * The nonce is generated once and reused by every later `build()`/`send()` until
+         * `resetNonce()`. Directives that are not set yet are created with just the nonce.
+         *
+         * # Parameters
+         * - `directives`: `CspRule[]` Directives to protect, typically `script-src` and `style-src`.
+         *
+         * # Returns
+         * - `string` The nonce (without the `'nonce-'` prefix), for `nonce="…"` attributes.
+         */
+        public function enableNonce(array $directives): string {}
+
+        /**
+         * Computes a CSP hash source for an inline `<script>` or `<style>` block.
+         *
+         * The content must be exactly what appears between the opening and closing tags,
+         * including whitespace, or the browser will compute a different hash.
+         *
+         * # Parameters
+         * - `inlineContent`: `string` The inline block's text.
+         * - `algo`: `string` One of `sha256`, `sha384`, `sha512`. Default: `sha256`.
+         *
+         * # Returns
+         * - `string` The quoted source, e.g. `'sha256-…'`.
+         *
+         * # Exceptions
+         * - Throws an exception if `algo` is not supported.
+         */
+        public static function hashSource(string $inline_content, ?string $algo): string {}
+
+        /**
+         * Allows one specific inline `<script>` block by adding its SHA-256 hash to `script-src`.
+         *
+         * This permits inline scripts without nonces, so pages can be cached. `script-src` is
+         * created if it is not set yet.
+         *
+         * # Parameters
+         * - `content`: `string` The script's text, exactly as it appears between the tags.
+         *
+         * # Returns
+         * - `string` The hash source that was added.
+         */
+        public function allowInlineScript(string $content): string {}
+
+        /**
+         * Returns the nonce for templates, generating it if none has been generated yet.
+         *
+         * Unlike `getNonce()` this never returns `null`, so it can be called before `build()`.
+         *
+         * # Returns
+         * - `string` The nonce (without the `'nonce-'` prefix).
+         */
+        public function nonce(): string {}
+
+        /**
+         * Constructs a new `ContentSecurityPolicy` builder with no directives set.
+         *
+         * # Returns
+         * - `ContentSecurityPolicy` A fresh instance containing an empty rule map.
+         *
+         * # Notes
+         * - No errors are thrown.
+         */
+        public function __construct() {}
+    }
+
+    /**
+     * All valid source keywords for CSP directives.
+     *
+     * These include host-independent keywords, nonce placeholders, resource-type tokens,
+     * and sandbox flags that can appear after a directive name.
+     */
+    enum CspKeyword: string {
+        /**
+         * The `'self'` keyword, allowing the same origin.
+         */
+        case SelfOrigin = 'self';
+
+        /**
+         * The `'unsafe-inline'` keyword, allowing inline scripts or styles.
+         */
+        case UnsafeInline = 'unsafe-inline';
+
+        /**
+         * The `'unsafe-eval'` keyword, allowing `eval()` and similar.
+         */
+        case UnsafeEval = 'unsafe-eval';
+
+        /**
+         * The `'unsafe-hashes'` keyword, allowing hash-based inline resources.
+         */
+        case UnsafeHashes = 'unsafe-hashes';
+
+        /**
+         * The `'strict-dynamic'` keyword, enabling strict dynamic loading.
+         */
+        case StrictDynamic = 'strict-dynamic';
+
+        /**
+         * The `'nonce-…'` placeholder for single-use nonces.
+         */
+        case Nonce = 'nonce';
+
+        /**
+         * The `script` token for SRI or Trusted Types policies.
+         */
+        case Script = 'script';
+
+        /**
+         * The `style` token for SRI or Trusted Types policies.
+         */
+        case Style = 'style';
+
+        /**
+         * Allows form submission in a sandboxed context.
+         */
+        case AllowForms = 'allow-forms';
+
+        /**
+         * Allows modal dialogs in a sandboxed context.
+         */
+        case AllowModals = 'allow-modals';
+
+        /**
+         * Allows orientation lock in a sandboxed context.
+         */
+        case AllowOrientationLock = 'allow-orientation-lock';
+
+        /**
+         * Allows pointer lock in a sandboxed context.
+         */
+        case AllowPointerLock = 'allow-pointer-lock';
+
+        /**
+         * Allows presentation mode in a sandboxed context.
+         */
+        case AllowPresentation = 'allow-presentation';
+
+        /**
+         * Allows pop-ups in a sandboxed context.
+         */
+        case AllowPopups = 'allow-popups';
+
+        /**
+         * Allows pop-ups to escape sandbox restrictions.
+         */
+        case AllowPopupsToEscapeSandbox = 'allow-popups-to-escape-sandbox';
+
+        /**
+         * Allows same-origin access in a sandboxed context.
+         */
+        case AllowSameOrigin = 'allow-same-origin';
+
+        /**
+         * Allows script execution in a sandboxed context.
+         */
+        case AllowScripts = 'allow-scripts';
+
+        /**
+         * Allows storage access via user activation in a sandbox.
+         */
+        case AllowStorageAccessByUserActivation = 'allow-storage-access-by-user-activation';
+
+        /**
+         * Allows top-level navigation via user activation.
+         */
+        case AllowTopNavigationByUserActivation = 'allow-top-navigation-by-user-activation';
+
+        /**
+         * Allows duplicate directives.
+         */
+        case AllowDuplicates = 'allow-duplicates';
+
+        /**
+         * Allows WebAssembly to use `eval()`.
+         */
+        case WasmUnsafeEval = 'wasm-unsafe-eval';
+
+        /**
+         * Enables inline speculation rules.
+         */
+        case InlineSpeculationRules = 'inline-speculation-rules';
+
+        /**
+         * Includes sample reports in violation reports.
+         */
+        case ReportSample = 'report-sample';
+    }
+
+    /**
+     * All the CSP directives you want to support.
+     * Supported Content Security Policy (CSP) directives.
+     *
+     * These correspond to the various directives you can set in a
+     * Content-Security-Policy header.
+     */
+    enum CspRule: string {
+        /**
+         * Fallback for other fetch directives.
+         */
+        case DefaultSrc = 'default-src';
+
+        /**
+         * Controls allowed sources for scripts.
+         */
+        case ScriptSrc = 'script-src';
+
+        /**
+         * Controls allowed sources for stylesheets.
+         */
+        case StyleSrc = 'style-src';
+
+        /**
+         * Controls allowed sources for images.
+         */
+        case ImgSrc = 'img-src';
+
+        /**
+         * Restricts which parent origins can embed this resource.
+         */
+        case FrameAncestors = 'frame-ancestors';
+
+        /**
+         * Controls allowed endpoints for fetch, XHR, WebSocket, etc.
+         */
+        case ConnectSrc = 'connect-src';
+
+        /**
+         * Controls allowed sources for font resources.
+         */
+        case FontSrc = 'font-src';
+
+        /**
+         * Alias for controlling allowed embedding contexts.
+         */
+        case ChildSrc = 'child-src';
+
+        /**
+         * Controls allowed sources for web app manifests.
+         */
+        case ManifestSrc = 'manifest-src';
+
+        /**
+         * Controls allowed sources for media elements.
+         */
+        case MediaSrc = 'media-src';
+
+        /**
+         * Controls allowed sources for plugin content.
+         */
+        case ObjectSrc = 'object-src';
+
+        /**
+         * Controls allowed sources for prefetch operations.
+         */
+        case PrefetchSrc = 'prefetch-src';
+
+        /**
+         * Controls allowed sources for script elements.
+         */
+        case ScriptSrcElem = 'script-src-elem';
+
+        /**
+         * Controls allowed sources for inline event handlers.
+         */
+        case ScriptSrcAttr = 'script-src-attr';
+
+        /**
+         * Controls allowed sources for style elements.
+         */
+        case StyleSrcElem = 'style-src-elem';
+
+        /**
+         * Controls allowed sources for inline style attributes.
+         */
+        case StyleSrcAttr = 'style-src-attr';
+
+        /**
+         * Controls allowed sources for worker scripts.
+         */
+        case WorkerSrc = 'worker-src';
+
+        /**
+         * Restricts the set of URLs usable in the document's base element.
+         */
+        case BaseUri = 'base-uri';
+
+        /**
+         * Restricts the URLs that forms can submit to.
+         */
+        case FormAction = 'form-action';
+
+        /**
+         * Applies sandboxing rules to the document.
+         */
+        case Sandbox = 'sandbox';
+
+        /**
+         * Restricts the types of plugins that may be loaded.
+         */
+        case PluginTypes = 'plugin-types';
+
+        /**
+         * Disallows all mixed HTTP content on secure pages.
+         */
+        case BlockAllMixedContent = 'block-all-mixed-content';
+
+        /**
+         * Instructs browsers to upgrade insecure requests to HTTPS.
+         */
+        case UpgradeInsecureRequests = 'upgrade-insecure-requests';
+
+        /**
+         * Specifies a URI to which policy violation reports are sent.
+         */
+        case ReportUri = 'report-uri';
+
+        /**
+         * Specifies a reporting group for violation reports.
+         */
+        case ReportTo = 'report-to';
+
+        /**
+         * Requires Subresource Integrity checks for specified resource types.
+         */
+        case RequireSriFor = 'require-sri-for';
+
+        /**
+         * Restricts creation of DOM sinks to a trusted-types policy.
+         */
+        case TrustedTypes = 'trusted-types';
+
+        /**
+         * Enforces Trusted Types for specified sinks.
+         */
+        case RequireTrustedTypesFor = 'require-trusted-types-for';
+    }
+
+    /**
+     * HTTP Strict Transport Security (HSTS) header builder.
+     */
+    class StrictTransportSecurity {
+        /**
+         * Sets the `max-age` directive (in seconds).
+         *
+         * # Parameters
+         * - `maxAge`: `int` number of seconds for `max-age`.
+         *
+         * # Returns
+         * - `void`
          */
-        public function stripComments(bool $strip): \Hardened\Sanitizers\SvgSanitizer {}
+        public function maxAge(int $max_age) {}
 
         /**
-         * Allow relative URLs
+         * Enable or disable the `includeSubDomains` flag.
+         *
+         * # Parameters
+         * - `enable`: `bool` `true` to include subdomains, `false` to omit.
+         *
+         * # Returns
+         * - `void`
          */
-        public function allowRelativeUrls(bool $allow): \Hardened\Sanitizers\SvgSanitizer {}
+        public function includeSubDomains(bool $enable) {}
+
+        /**
+         * Enable or disable the `preload` flag.
+         *
+         * # Parameters
+         * - `enable`: `bool` `true` to add `preload`, `false` to omit.
+         *
+         * # Returns
+         * - `void`
+         */
+        public function preload(bool $enable) {}
+
+        /**
+         * Lists the reasons the domain would be rejected by the HSTS preload list
+         * (<https://hstspreload.org>).
+         *
+         * # Returns
+         * - `string[]` Problems; empty if the header is eligible for preloading.
+         */
+        public function validateForPreload(): array {}
+
+        /**
+         * Builds the `Strict-Transport-Security` header value.
+         *
+         * # Returns
+         * - `string` e.g. `"max-age=31536000; includeSubDomains; preload"`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `preload` is set without `includeSubDomains`, which the
+         *   preload list always rejects.
+         */
+        public function build(): string {}
+
+        /**
+         * Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+         * headers to a worker runtime's response object.
+         *
+         * # Parameters
+         * - `emitter`: `callable(string $name, string $value): void`
+         *
+         * # Exceptions
+         * - Throws `Exception` if `emitter` is not callable.
+         */
+        public function setEmitter(mixed $emitter) {}
+
+        /**
+         * Builds the headers `send()` would emit.
+         *
+         * # Returns
+         * - `list<array{string, string}>` `[name, value]` pairs in sending order.
+         */
+        public function buildPairs(): array {}
+
+        /**
+         * Sends the `Strict-Transport-Security` header via PHP `header()` function.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the configuration is invalid (see `build()`).
+         * - Throws `Exception` if PHP `header()` cannot be invoked.
+         */
+        public function send() {}
 
+        /**
+         * Constructs a new HSTS builder with default settings.
+         *
+         * # Returns
+         * - `Hsts` New instance with `max-age=0`, no subdomains, no preload.
+         */
         public function __construct() {}
     }
-}
 
-namespace Hardened\Sanitizers\File {
     /**
-     * Engine for detecting "PNG bombs" (images with unreasonable dimensions).
+     * Builder for miscellaneous HTTP security headers:
+     * `X-Frame-Options`, `X-XSS-Protection`, `X-Content-Type-Options`,
+     * `X-Permitted-Cross-Domain-Policies`, `X-Robots-Tag`, `Report-To`, `Integrity-Policy`,
+     * `Integrity-Policy-Report-Only`, `NEL` and `Expect-CT`.
      */
-    class PngSanitizer {
+    class Whatnot {
+        /**
+         * Set `X-Frame-Options` header.
+         *
+         * # Parameters
+         * - `mode`: `FrameOptions::Deny`, `FrameOptions::SameOrigin`, or `FrameOptions::AllowFrom`.
+         * - `uri`: Optional URI, required if `mode` is `AllowFrom`.
+         *
+         * # Exceptions
+         * - Throws if `AllowFrom` is given without a URI.
+         */
+        public function setFrameOptions(\Hardened\SecurityHeaders\FrameOptions $mode, ?string $uri) {}
+
+        /**
+         * Set `X-XSS-Protection` header.
+         *
+         * # Parameters
+         * - `mode`: `XssProtection::Off`, `XssProtection::On`, or `XssProtection::Block`.
+         * - `report_uri`: Optional reporting URI, only allowed when `mode` is `On` or `Block`.
+         *
+         * # Exceptions
+         * - Throws if a `report_uri` is provided for `Off` mode.
+         */
+        public function setXssProtection(\Hardened\SecurityHeaders\XssProtection $mode, ?string $report_uri) {}
+
+        /**
+         * Enable or disable `X-Content-Type-Options: nosniff`.
+         */
+        public function setNosniff(bool $enable) {}
+
+        /**
+         * Set `X-Permitted-Cross-Domain-Policies` header.
+         *
+         * # Parameters
+         * - `policy`: `CrossDomainPolicy::None`, `MasterOnly`, `ByContentType`, or `All`.
+         */
+        public function setPermittedCrossDomainPolicies(\Hardened\SecurityHeaders\CrossDomainPolicy $policy) {}
+
+        /**
+         * Set the `X-Robots-Tag` header, e.g. to keep staging sites or private documents out of
+         * search engines. An empty array removes the header.
+         *
+         * # Parameters
+         * - `directives`: `string[]` e.g. `['noindex', 'nofollow', 'unavailable_after: 2030-12-31']`.
+         *   Supported: `all`, `noindex`, `nofollow`, `none`, `noarchive`, `nosnippet`,
+         *   `indexifembedded`, `notranslate`, `noimageindex`, `max-snippet:<n>`,
+         *   `max-video-preview:<n>`, `max-image-preview:<none|standard|large>` and
+         *   `unavailable_after:<date>` (ISO 8601 or RFC 822/850).
+         *
+         * # Exceptions
+         * - Throws if a directive is unknown or its value (e.g. the date) is invalid.
+         */
+        public function setRobotsTag(array $directives) {}
+
+        /**
+         * Configure a `Report-To` group from structured arguments. Each group is sent as its
+         * own `Report-To` header; setting a group name again replaces that group.
+         *
+         * # Parameters
+         * - `group`: report group name.
+         * - `max_age`: seconds to retain reports.
+         * - `include_subdomains`: whether to include subdomains.
+         * - `endpoints`: PHP array of endpoint names.
+         *
+         * # Exceptions
+         * - Throws if any argument is invalid.
+         */
+        public function setReportTo(string $group, int $max_age, bool $include_subdomains, array $endpoints) {}
+
+        /**
+         * Set a structured `Integrity-Policy` header.
+         *
+         * # Parameters
+         * - `blocked_destinations`: PHP array of destinations, e.g. `['script']`.
+         * - `sources`: Optional PHP array of sources, e.g. `['inline']`.
+         * - `endpoints`: Optional PHP array of reporting endpoint names.
+         *
+         * # Exceptions
+         * - Throws if any required array is missing or contains invalid entries.
+         */
+        public function setIntegrityPolicy(mixed $blocked_destinations, ?array $sources, ?array $endpoints) {}
+
+        /**
+         * Set a structured `Integrity-Policy-Report-Only` header.
+         *
+         * # Parameters
+         * - `blocked_destinations`: PHP array of destinations, e.g. `['script']`.
+         * - `sources`: Optional PHP array of sources, e.g. `['inline']`.
+         * - `endpoints`: Optional PHP array of reporting endpoint names.
+         *
+         * # Exceptions
+         * - Throws if any required array is missing or contains invalid entries.
+         */
+        public function setIntegrityPolicyReportOnly(mixed $blocked_destinations, ?array $sources, ?array $endpoints) {}
+
+        /**
+         * Set the `NEL` (Network Error Logging) header, so browsers report DNS, TLS and HTTP
+         * failures to a reporting endpoint. Pass `null` to remove the header.
+         *
+         * # Parameters
+         * - `report_to`: `?string` Endpoint name registered with `ReportingEndpoints` (or a
+         *   `Report-To` group for older browsers).
+         * - `max_age`: `int` Seconds the browser keeps the policy; `0` removes a cached policy.
+         * - `include_subdomains`: `?bool` Whether the policy also covers subdomains. Default: `false`.
+         * - `success_fraction`: `?float` Share of successful requests to report, `0.0`–`1.0`.
+         * - `failure_fraction`: `?float` Share of failed requests to report, `0.0`–`1.0`.
+         *
+         * # Exceptions
+         * - Throws if the endpoint name is invalid, `max_age` is negative or a fraction is out
+         *   of range.
+         */
+        public function setNel(?string $report_to, int $max_age, ?bool $include_subdomains, ?float $success_fraction, ?float $failure_fraction) {}
+
+        /**
+         * Set the legacy `Expect-CT` header for Certificate Transparency monitoring. Current
+         * browsers enforce CT without it; pass `null` as `max_age` to remove the header.
+         *
+         * # Parameters
+         * - `max_age`: `?int` Seconds the browser remembers the policy.
+         * - `enforce`: `?bool` Refuse connections that violate CT instead of only reporting.
+         *   Default: `false`.
+         * - `report_uri`: `?string` Absolute `https:` URL that receives violation reports.
+         *
+         * # Exceptions
+         * - Throws if `max_age` is negative or `report_uri` is not an absolute `https:` URL.
+         */
+        public function setExpectCt(?int $max_age, ?bool $enforce, ?string $report_uri) {}
+
+        /**
+         * Build an associative array of header names → values. Several `Report-To` groups are
+         * joined into one comma-separated value; `buildPairs()` keeps them as separate headers.
+         */
+        public function build(): array {}
+
+        /**
+         * Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+         * headers to a worker runtime's response object.
+         *
+         * # Parameters
+         * - `emitter`: `callable(string $name, string $value): void`
+         *
+         * # Exceptions
+         * - Throws `Exception` if `emitter` is not callable.
+         */
+        public function setEmitter(mixed $emitter) {}
+
+        /**
+         * Builds the headers `send()` would emit.
+         *
+         * # Returns
+         * - `list<array{string, string}>` `[name, value]` pairs in sending order.
+         */
+        public function buildPairs(): array {}
+
+        /**
+         * Emit all configured headers via PHP `header()` calls.
+         */
+        public function send() {}
+
+        /**
+         * Constructs a new builder with all headers disabled.
+         */
+        public function __construct() {}
+    }
+
+    /**
+     * Possible values for the `X-Frame-Options` header.
+     */
+    enum FrameOptions: string {
+        case Deny = 'DENY';
+
+        case SameOrigin = 'SAMEORIGIN';
+
+        case AllowFrom = 'ALLOW-FROM';
+    }
+
+    /**
+     * Possible values for the `X-XSS-Protection` header.
+     */
+    enum XssProtection: string {
+        case Off = 'off';
+
+        case On = 'on';
+
+        case Block = 'block';
+    }
+
+    /**
+     * Values for the `X-Permitted-Cross-Domain-Policies` header.
+     */
+    enum CrossDomainPolicy: string {
+        case None = 'none';
+
+        case MasterOnly = 'master-only';
+
+        case ByContentType = 'by-content-type';
+
+        case All = 'all';
+    }
+
+    /**
+     * Permissions-Policy header builder.
+     */
+    class PermissionsPolicy {
+        const ORIGIN_SELF = null;
+
+        const ORIGIN_ANY = null;
+
+        const ORIGIN_SRC = null;
+
+        /**
+         * Parses an existing `Permissions-Policy` header value into a builder, e.g. to audit
+         * or extend a policy set by another layer.
+         *
+         * Both `src` and `'src'` are accepted, bare `*`/`self` items are treated as
+         * single-entry allowlists, and the first `report-to` parameter is kept.
+         *
+         * # Parameters
+         * - `header`: `string` Header value, e.g. `camera=(), geolocation=(self "https://a.example")`.
+         *
+         * # Returns
+         * - `PermissionsPolicy` The parsed policy.
+         *
+         * # Exceptions
+         * - Throws an exception if a feature is unknown or an allowlist is malformed.
+         */
+        public static function parse(string $header): \Hardened\SecurityHeaders\PermissionsPolicy {}
+
+        /**
+         * Lists every feature token this builder knows about.
+         *
+         * # Returns
+         * - `string[]` Feature names, e.g. `["accelerometer", "ambient-light-sensor", ...]`.
+         */
+        public static function features(): array {}
+
+        /**
+         * Attaches a `report-to` parameter to every feature, so violations are reported to
+         * the named endpoint (see `ReportingEndpoints`).
+         *
+         * # Parameters
+         * - `endpoint`: `?string` Endpoint name, or `null` to stop reporting.
+         *
+         * # Exceptions
+         * - Throws an exception if the name is not a valid endpoint name.
+         */
+        public function reportTo(?string $endpoint) {}
+
+        /**
+         * Allow a feature for the given list of origins.
+         *
+         * # Parameters
+         * - `feature`: one of the defined `Feature` tokens.
+         * - `origins`: list of allowlist entries, e.g. `"self"`, `"*"`, `"src"`, or quoted origins.
+         *
+         * # Errors
+         * - if `feature` is not recognized.
+         */
+        public function allow(\Hardened\SecurityHeaders\PermissionsPolicyFeature $feature, array $origins) {}
+
         /**
-         * Scan a file at the given path and detect PNG bombs.
+         * Deny a feature entirely (empty allowlist).
          *
          * # Parameters
-         * - `path`: `string` Filesystem path to the PNG file.
-         *
-         * # Returns
-         * - `bool` `true` if the file is a PNG *and* has width or height > 10000,
-         *   or if it's invalid PNG with missing IHDR. Returns `false` if it's not
-         *   a PNG or has acceptable dimensions.
-         *
-         * # Exceptions
-         * - Throws an exception if the file cannot be opened, read, or the format
-         *   is malformed (e.g. missing IHDR).
+         * - `feature`: one of the defined `Feature` tokens.
          *
-         * ## Example
-         * ```php
-         * Hardened\Sanitizers\File\PngSanitizer::defuse('/tmp/image.png');
-         * ```
+         * # Errors
+         * - if `feature` is not recognized.
          */
-        public static function defuse(string $path): mixed {}
+        public function deny(\Hardened\SecurityHeaders\PermissionsPolicyFeature $feature) {}
 
-        public function __construct() {}
-    }
+        /**
+         * Builds the Permissions-Policy header value.
+         *
+         * # Returns
+         * - `String`, e.g.:
+         *   `geolocation=(self "https://api.example.com"), camera=()`
+         */
+        public function build(): string {}
 
-    /**
-     * Archive bomb detector for ZIP and RAR files.
-     *
-     * Provides two methods in PHP:
-     *   - `scan_zip(string $path): bool`
-     *   - `scan_rar(string $path, ?int $maxRatio = 1000): bool`
-     */
-    class ArchiveSanitizer {
         /**
-         * Perform archive‐bomb detection on a file.
+         * Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+         * headers to a worker runtime's response object.
          *
-         * This internal helper examines the file at `path` and returns an error if it
-         * appears to be a "bomb" (i.e. an archive whose reported uncompressed size
-         * far exceeds its on‐disk compressed size or mismatches the local header).
+         * # Parameters
+         * - `emitter`: `callable(string $name, string $value): void`
          *
-         * **ZIP**:
-         * - Reads the central directory to sum the uncompressed sizes of all entries.
-         * - Reads the 4‐byte little‐endian uncompressed size from the local file header at offset 22.
-         * - Fails if those two values differ.
+         * # Exceptions
+         * - Throws `Exception` if `emitter` is not callable.
+         */
+        public function setEmitter(mixed $emitter) {}
+
+        /**
+         * Builds the headers `send()` would emit.
          *
-         * **RAR**:
-         * - Computes the on‐disk file size.
-         * - Lists the first entry's `unpacked_size` and divides by the compressed size.
-         * - Fails if that ratio ≥ `max_ratio` (default 1000).
+         * # Returns
+         * - `list<array{string, string}>` `[name, value]` pairs in sending order.
+         */
+        public function buildPairs(): array {}
+
+        /**
+         * Sends the Permissions-Policy header via PHP `header()` function.
          *
-         * # Parameters
-         * - `path`: Filesystem path to the archive file to inspect.
-         * - `max_ratio`: Optional maximum unpacked/compressed ratio for RAR; Default is 1000
+         * # Errors
+         * - Returns an error if PHP `header()` cannot be invoked.
+         */
+        public function send() {}
+
+        /**
+         * Sends the policy as a `Permissions-Policy-Report-Only` header, to trial it: violations
+         * are reported (see `reportTo()`) but nothing is blocked.
          *
-         * # Exceptions
-         * - I/O errors opening, reading, or seeking the file.
-         * - ZIP archive mismatches (central-directory total vs. local-header size).
-         * - RAR archive exceeds the allowed unpacked/compressed ratio.
+         * # Errors
+         * - Returns an error if PHP `header()` cannot be invoked.
          */
-        public static function defuse(string $path, ?int $max_ratio): mixed {}
+        public function sendReportOnly() {}
 
+        /**
+         * Constructs a new Permissions-Policy builder with no features allowed.
+         *
+         * # Returns
+         * - `PermissionsPolicy` New instance with an empty feature map.
+         */
         public function __construct() {}
     }
-}
 
-namespace Hardened\SecurityHeaders {
     /**
-     * Your application’s CSP config.
+     * Supported Permissions-Policy features.
+     *
+     * Each variant corresponds to a feature name in the Permissions-Policy header
+     * (kebab-case). See: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Permissions-Policy
      */
-    class ContentSecurityPolicy {
-        /**
-         * Fallback for other fetch directives.
-         */
-        const DEFAULT_SRC = null;
-
+    enum PermissionsPolicyFeature: string {
         /**
-         * Controls allowed sources for scripts.
+         * Controls whether the current document is allowed to gather information
+         * about the acceleration of the device through the Accelerometer interface.
          */
-        const SCRIPT_SRC = null;
+        case Accelerometer = 'accelerometer';
 
         /**
-         * Controls allowed sources for stylesheets.
+         * Controls whether the current document is allowed to gather information
+         * about the amount of light in the environment around the device through
+         * the AmbientLightSensor interface.
          */
-        const STYLE_SRC = null;
+        case AmbientLightSensor = 'ambient-light-sensor';
 
         /**
-         * Controls allowed sources for images.
+         * Controls whether the current document is allowed to use the
+         * Attribution Reporting API.
          */
-        const IMG_SRC = null;
+        case AttributionReporting = 'attribution-reporting';
 
         /**
-         * Restricts which parent origins can embed this resource.
+         * Controls whether the current document is allowed to autoplay media
+         * requested through the HTMLMediaElement interface. When disabled without
+         * user gesture, play() will reject with NotAllowedError.
          */
-        const FRAME_ANCESTORS = null;
+        case Autoplay = 'autoplay';
 
         /**
-         * Controls allowed endpoints for fetch, XHR, WebSocket, etc.
+         * Controls whether the use of the Web Bluetooth API is allowed.
+         * When disabled, Bluetooth methods will either return false or reject.
          */
-        const CONNECT_SRC = null;
+        case Bluetooth = 'bluetooth';
 
         /**
-         * Controls allowed sources for font resources.
+         * Controls access to the Topics API. Disallowed calls to browsingTopics()
+         * or Sec-Browsing-Topics header will fail with NotAllowedError.
          */
-        const FONT_SRC = null;
+        case BrowsingTopics = 'browsing-topics';
 
         /**
-         * Alias for controlling allowed embedding contexts.
+         * Controls whether the current document is allowed to use video input devices.
+         * When disabled, getUserMedia() will reject with NotAllowedError.
          */
-        const CHILD_SRC = null;
+        case Camera = 'camera';
 
         /**
-         * Controls allowed sources for web app manifests.
+         * Controls whether the document may scroll and zoom captured tabs through the Captured
+         * Surface Control API.
          */
-        const MANIFEST_SRC = null;
+        case CapturedSurfaceControl = 'captured-surface-control';
 
         /**
-         * Controls allowed sources for media elements.
+         * Controls whether the document may read the clipboard through the Async Clipboard API.
          */
-        const MEDIA_SRC = null;
+        case ClipboardRead = 'clipboard-read';
 
         /**
-         * Controls allowed sources for plugin content.
+         * Controls whether the document may write to the clipboard through the Async Clipboard API.
          */
-        const OBJECT_SRC = null;
+        case ClipboardWrite = 'clipboard-write';
 
         /**
-         * Controls allowed sources for prefetch operations.
+         * Controls access to the Compute Pressure API.
          */
-        const PREFETCH_SRC = null;
+        case ComputePressure = 'compute-pressure';
 
         /**
-         * Controls allowed sources for script elements.
+         * Controls whether the current document can be treated as cross-origin isolated.
          */
-        const SCRIPT_SRC_ELEM = null;
+        case CrossOriginIsolated = 'cross-origin-isolated';
 
         /**
-         * Controls allowed sources for inline event handlers.
+         * Controls the allocation of the top-level origin's fetchLater() quota.
          */
-        const SCRIPT_SRC_ATTR = null;
+        case DeferredFetch = 'deferred-fetch';
 
         /**
-         * Controls allowed sources for style elements.
+         * Controls the allocation of the shared cross-origin subframe fetchLater() quota.
          */
-        const STYLE_SRC_ELEM = null;
+        case DeferredFetchMinimal = 'deferred-fetch-minimal';
 
         /**
-         * Controls allowed sources for inline style attributes.
+         * Controls whether the current document may capture display media via getDisplayMedia().
+         * When disabled, getDisplayMedia() will reject with NotAllowedError.
          */
-        const STYLE_SRC_ATTR = null;
+        case DisplayCapture = 'display-capture';
 
         /**
-         * Controls allowed sources for worker scripts.
+         * Controls whether the document may set `document.domain` to relax the same-origin policy.
          */
-        const WORKER_SRC = null;
+        case DocumentDomain = 'document-domain';
 
         /**
-         * Restricts the set of URLs usable in the document’s base element.
+         * Controls whether the current document is allowed to use the Encrypted Media
+         * Extensions API (EME). When disabled, requestMediaKeySystemAccess() will reject.
          */
-        const BASE_URI = null;
+        case EncryptedMedia = 'encrypted-media';
 
         /**
-         * Restricts the URLs that forms can submit to.
+         * Controls whether tasks may run in frames that are not rendered (e.g. `display: none`).
          */
-        const FORM_ACTION = null;
+        case ExecutionWhileNotRendered = 'execution-while-not-rendered';
 
         /**
-         * Applies sandboxing rules to the document.
+         * Controls whether tasks may run in frames that are outside the visible viewport.
          */
-        const SANDBOX = null;
+        case ExecutionWhileOutOfViewport = 'execution-while-out-of-viewport';
 
         /**
-         * Restricts the types of plugins that may be loaded.
+         * Controls whether the current document is allowed to use Element.requestFullscreen().
+         * When disabled, requestFullscreen() will reject with TypeError.
          */
-        const PLUGIN_TYPES = null;
+        case Fullscreen = 'fullscreen';
 
         /**
-         * Disallows all mixed HTTP content on secure pages.
+         * Controls whether the current document is allowed to use the Gamepad API.
+         * When disabled, getGamepads() will throw SecurityError and events won't fire.
          */
-        const BLOCK_ALL_MIXED_CONTENT = null;
+        case Gamepad = 'gamepad';
 
         /**
-         * Instructs browsers to upgrade insecure requests to HTTPS.
+         * Controls whether the current document is allowed to use the Geolocation Interface.
+         * When disabled, geolocation callbacks will error with PERMISSION_DENIED.
          */
-        const UPGRADE_INSECURE_REQUESTS = null;
+        case Geolocation = 'geolocation';
 
         /**
-         * Specifies a URI to which policy violation reports are sent.
+         * Controls whether the current document is allowed to gather information
+         * about device orientation through the Gyroscope interface.
          */
-        const REPORT_URI = null;
+        case Gyroscope = 'gyroscope';
 
         /**
-         * Specifies a reporting group for violation reports.
+         * Controls whether the current document is allowed to use the WebHID API.
+         * Allows communication with HID devices like gamepads or keyboards.
          */
-        const REPORT_TO = null;
+        case Hid = 'hid';
 
         /**
-         * Requires Subresource Integrity checks for specified resource types.
+         * Controls whether the document may use the Federated Credential Management API
+         * (FedCM) via navigator.credentials.get({identity:…}).
          */
-        const REQUIRE_SRI_FOR = null;
+        case IdentityCredentialsGet = 'identity-credentials-get';
 
         /**
-         * Restricts creation of DOM sinks to a trusted-types policy.
+         * Controls whether the document may use the Idle Detection API to detect user idle/active state.
          */
-        const TRUSTED_TYPES = null;
+        case IdleDetection = 'idle-detection';
 
         /**
-         * Enforces Trusted Types for specified sinks.
+         * Controls FLoC cohort calculation. Deprecated, but `interest-cohort=()` is still common.
          */
-        const REQUIRE_TRUSTED_TYPES_FOR = null;
+        case InterestCohort = 'interest-cohort';
 
         /**
-         * The `'self'` keyword, allowing the same origin.
+         * Controls whether the document may join interest groups of the Protected Audience API.
          */
-        const SELF = null;
+        case JoinAdInterestGroup = 'join-ad-interest-group';
 
         /**
-         * The `'unsafe-inline'` keyword, allowing inline scripts or styles.
+         * Controls whether the document may use `navigator.keyboard.getLayoutMap()`.
          */
-        const UNSAFE_INLINE = null;
+        case KeyboardMap = 'keyboard-map';
 
         /**
-         * The `'unsafe-eval'` keyword, allowing `eval()` and similar.
+         * Controls access to the language detection functionality of Translator & Language Detector APIs.
          */
-        const UNSAFE_EVAL = null;
+        case LanguageDetector = 'language-detector';
 
         /**
-         * The `'unsafe-hashes'` keyword, allowing hash-based inline resources.
+         * Controls whether the document may gather data on locally-installed fonts via queryLocalFonts().
          */
-        const UNSAFE_HASHES = null;
+        case LocalFonts = 'local-fonts';
 
         /**
-         * The `'strict-dynamic'` keyword, enabling strict dynamic loading.
+         * Controls whether the document may gather device orientation via the Magnetometer interface.
          */
-        const STRICT_DYNAMIC = null;
+        case Magnetometer = 'magnetometer';
 
         /**
-         * The `'nonce-…'` placeholder for single-use nonces.
+         * Controls whether the document is allowed to use audio input devices.
+         * When disabled, getUserMedia() will reject with NotAllowedError.
          */
-        const NONCE = null;
+        case Microphone = 'microphone';
 
         /**
-         * The `script` token for SRI or Trusted Types policies.
+         * Controls whether the document may use the Web MIDI API.
+         * When disabled, requestMIDIAccess() will reject with SecurityError.
          */
-        const SCRIPT = null;
+        case Midi = 'midi';
 
         /**
-         * The `style` token for SRI or Trusted Types policies.
+         * Controls whether the document may use the WebOTP API to retrieve one-time passwords.
          */
-        const STYLE = null;
+        case OtpCredentials = 'otp-credentials';
 
         /**
-         * Allows form submission in a sandboxed context.
+         * Controls whether the document may use the Payment Request API.
+         * When disabled, PaymentRequest() will throw SecurityError.
          */
-        const ALLOW_FORMS = null;
+        case Payment = 'payment';
 
         /**
-         * Allows modal dialogs in a sandboxed context.
+         * Controls whether the document may enter Picture-in-Picture mode via the API.
          */
-        const ALLOW_MODALS = null;
+        case PictureInPicture = 'picture-in-picture';
 
         /**
-         * Allows orientation lock in a sandboxed context.
+         * Controls whether the document may request Private State Tokens to be issued.
          */
-        const ALLOW_ORIENTATION_LOCK = null;
+        case PrivateStateTokenIssuance = 'private-state-token-issuance';
 
         /**
-         * Allows pointer lock in a sandboxed context.
+         * Controls whether the document may redeem Private State Tokens.
          */
-        const ALLOW_POINTER_LOCK = null;
+        case PrivateStateTokenRedemption = 'private-state-token-redemption';
 
         /**
-         * Allows presentation mode in a sandboxed context.
+         * Controls whether the document may use Web Authentication API to create new credentials.
          */
-        const ALLOW_PRESENTATION = null;
+        case PublickeyCredentialsCreate = 'publickey-credentials-create';
 
         /**
-         * Allows pop-ups in a sandboxed context.
+         * Controls whether the document may use Web Authentication API to retrieve stored credentials.
          */
-        const ALLOW_POPUPS = null;
+        case PublickeyCredentialsGet = 'publickey-credentials-get';
 
         /**
-         * Allows pop-ups to escape sandbox restrictions.
+         * Controls whether the document may run ad auctions of the Protected Audience API.
          */
-        const ALLOW_POPUPS_TO_ESCAPE_SANDBOX = null;
+        case RunAdAuction = 'run-ad-auction';
 
         /**
-         * Allows same-origin access in a sandboxed context.
+         * Controls whether the document may use the Screen Wake Lock API to keep the screen on.
          */
-        const ALLOW_SAME_ORIGIN = null;
+        case ScreenWakeLock = 'screen-wake-lock';
 
         /**
-         * Allows script execution in a sandboxed context.
+         * Controls whether the document may use the Web Serial API to communicate with serial devices.
          */
-        const ALLOW_SCRIPTS = null;
+        case Serial = 'serial';
 
         /**
-         * Allows storage access via user activation in a sandbox.
+         * Controls whether the document may use the Shared Storage API.
          */
-        const ALLOW_STORAGE_ACCESS_BY_USER_ACTIVATION = null;
+        case SharedStorage = 'shared-storage';
 
         /**
-         * Allows top-level navigation via user activation.
+         * Controls whether the document may use `sharedStorage.selectURL()`.
          */
-        const ALLOW_TOP_NAVIGATION_BY_USER_ACTIVATION = null;
+        case SharedStorageSelectUrl = 'shared-storage-select-url';
 
         /**
-         * Allows duplicate directives.
+         * Controls whether the document may list and select speakers via the Output Devices API.
          */
-        const ALLOW_DUPLICATES = null;
+        case SpeakerSelection = 'speaker-selection';
 
         /**
-         * Allows WebAssembly to use `eval()`.
+         * Controls whether an embedded document may use the Storage Access API for third-party cookies.
          */
-        const WASM_UNSAFE_EVAL = null;
+        case StorageAccess = 'storage-access';
 
         /**
-         * Enables inline speculation rules.
+         * Controls whether the document may make synchronous `XMLHttpRequest` requests.
          */
-        const INLINE_SPECULATION_RULES = null;
+        case SyncXhr = 'sync-xhr';
 
         /**
-         * Includes sample reports in violation reports.
+         * Controls access to the translation functionality of Translator & Language Detector APIs.
          */
-        const REPORT_SAMPLE = null;
+        case Translator = 'translator';
 
         /**
-         * Sets or replaces a CSP directive with the given keywords and host sources.
-         *
-         * # Parameters
-         * - `rule`: The directive name. One of `default-src`, `script-src`, `style-src`, `img-src`, `frame-ancestors`,
-         *   `connect-src`, `font-src`, `child-src`, `manifest-src`, `media-src`, `object-src`, `prefetch-src`,
-         *   `script-src-elem`, `script-src-attr`, `style-src-elem`, `style-src-attr`, `worker-src`,
-         *   `base-uri`, `form-action`, `sandbox`, `plugin-types`, `block-all-mixed-content`,
-         *   `upgrade-insecure-requests`, `report-uri`, `report-to`, `require-sri-for`,
-         *   `trusted-types`, `require-trusted-types-for`.
-         * - `keywords`: Slice of keyword tokens. One or more of `self`, `none`, `unsafe-inline`,
-         *   `unsafe-eval`, `unsafe-hashes`, `strict-dynamic`, `nonce`, `script`, `style`,
-         *   `allow-forms`, `allow-modals`, `allow-orientation-lock`, `allow-pointer-lock`,
-         *   `allow-presentation`, `allow-popups`, `allow-popups-to-escape-sandbox`,
-         *   `allow-same-origin`, `allow-scripts`, `allow-storage-access-by-user-activation`,
-         *   `allow-top-navigation-by-user-activation`, `allow-duplicates`, `wasm-unsafe-eval`,
-         *   `inline-speculation-rules`, `report-sample`.
-         * - `sources`: Optional list of host sources (e.g. `["example.com"]`)
-         *
-         * # Exceptions
-         * - Throws `Exception` if any array item in `keywords` is not a string.
-         * - Throws `Exception` if `rule` is not a valid CSP directive.
+         * Controls access to the Summarizer API.
          */
-        public function setRule(string $rule, array $keywords, ?array $sources): mixed {}
+        case Summarizer = 'summarizer';
 
         /**
-         * Builds the `Content-Security-Policy` header value from the configured directives.
-         *
-         * # Returns
-         * - `String` The full header value, for example:
-         *   `"default-src 'self'; script-src 'self' 'nonce-ABCD1234' example.com; …"`.
-         *
-         * # Exceptions
-         * - Throws `Exception` if formatting the header string fails.
+         * Controls whether the document may register `unload` event handlers.
          */
-        public function build(): string {}
+        case Unload = 'unload';
 
         /**
-         * Send the `Content-Security-Policy` header via PHP `header()`.
-         *
-         * # Exceptions
-         * - Throws `Exception` if the PHP `header()` function cannot be invoked.
+         * Controls whether the document may use the WebUSB API to connect to USB devices.
          */
-        public function send(): mixed {}
+        case Usb = 'usb';
 
         /**
-         * Returns the most recently generated nonce, if any.
-         *
-         * # Returns
-         * - `Option<&str>` The raw nonce string (without the `'nonce-'` prefix), or `None` if `build()` has not yet generated one.
+         * Controls whether the document may use the Web Share API (navigator.share()).
          */
-        public function getNonce(): ?string {}
+        case WebShare = 'web-share';
 
         /**
-         * Clears the generated nonce. The next call of `build()` or `send()` will generate a new one.
+         * Controls whether the document may use the Window Management API to manage windows.
          */
-        public function resetNonce() {}
+        case WindowManagement = 'window-management';
 
         /**
-         * Constructs a new `ContentSecurityPolicy` builder with no directives set.
-         *
-         * # Returns
-         * - `ContentSecurityPolicy` A fresh instance containing an empty rule map.
-         *
-         * # Notes
-         * - No errors are thrown.
+         * Controls whether the document may use the WebXR Device API to interact with XR sessions.
          */
-        public function __construct() {}
+        case XrSpatialTracking = 'xr-spatial-tracking';
     }
 
     /**
-     * HTTP Strict Transport Security (HSTS) header builder.
+     * Referrer-Policy header builder.
      */
-    class StrictTransportSecurity {
-        /**
-         * Sets the `max-age` directive (in seconds).
-         *
-         * # Parameters
-         * - `maxAge`: `int` number of seconds for `max-age`.
-         *
-         * # Returns
-         * - `void`
-         */
-        public function maxAge(int $max_age) {}
-
+    class ReferrerPolicy {
         /**
-         * Enable or disable the `includeSubDomains` flag.
+         * Update the active Referrer-Policy directive.
          *
          * # Parameters
-         * - `enable`: `bool` `true` to include subdomains, `false` to omit.
+         * - `policy`: Directive string. Must be one of the tokens listed above for `__construct`,
+         *   or a comma-separated fallback chain of them.
          *
-         * # Returns
-         * - `void`
+         * # Exceptions
+         * - Throws `Exception` if the provided token is invalid.
          */
-        public function includeSubDomains(bool $enable) {}
+        public function set(string $policy) {}
 
         /**
-         * Enable or disable the `preload` flag.
+         * Sets an ordered fallback chain, e.g. `['no-referrer', 'strict-origin-when-cross-origin']`.
+         *
+         * Browsers use the last token they support, so older values go first and the preferred
+         * (newest) one last. Repeated tokens are dropped.
          *
          * # Parameters
-         * - `enable`: `bool` `true` to add `preload`, `false` to omit.
+         * - `policies`: `string[]` Policy tokens, at least one.
          *
-         * # Returns
-         * - `void`
+         * # Exceptions
+         * - Throws `Exception` if a token is invalid or the list is empty.
          */
-        public function preload(bool $enable) {}
+        public function setChain(array $policies) {}
 
         /**
-         * Builds the `Strict-Transport-Security` header value.
+         * Returns the configured fallback chain.
          *
          * # Returns
-         * - `string` e.g. `"max-age=31536000; includeSubDomains; preload"`.
+         * - `string[]` Policy tokens in header order.
          */
-        public function build(): string {}
+        public function chain(): array {}
 
         /**
-         * Sends the `Strict-Transport-Security` header via PHP `header()` function.
+         * Get the current Referrer-Policy value.
          *
-         * # Exceptions
-         * - Throws `Exception` if PHP `header()` cannot be invoked.
+         * # Returns
+         * - `string` the active policy token, or the comma-separated chain.
          */
-        public function send(): mixed {}
+        public function get(): string {}
 
         /**
-         * Constructs a new HSTS builder with default settings.
+         * Build the `Referrer-Policy` header value.
          *
          * # Returns
-         * - `Hsts` New instance with `max-age=0`, no subdomains, no preload.
+         * - `string` the configured policy value suitable for sending as a header, e.g.
+         *   `"no-referrer, strict-origin-when-cross-origin"` for a fallback chain.
          */
-        public function __construct() {}
-    }
+        public function build(): string {}
 
-    /**
-     * Builder for miscellaneous HTTP security headers:
-     * `X-Frame-Options`, `X-XSS-Protection`, `X-Content-Type-Options`,
-     * `X-Permitted-Cross-Domain-Policies`, `Report-To`, `Integrity-Policy`,
-     * and `Integrity-Policy-Report-Only`.
-     */
-    class Whatnot {
         /**
-         * Set `X-Frame-Options` header.
+         * Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+         * headers to a worker runtime's response object.
          *
          * # Parameters
-         * - `mode`: `"DENY"`, `"SAMEORIGIN"`, or `"ALLOW-FROM"`.
-         * - `uri`: Optional URI, required if `mode` is `"ALLOW-FROM"`.
+         * - `emitter`: `callable(string $name, string $value): void`
          *
          * # Exceptions
-         * - Throws if `mode` is invalid or `"ALLOW-FROM"` is given without a URI.
+         * - Throws `Exception` if `emitter` is not callable.
          */
-        public function setFrameOptions(string $mode, ?string $uri): mixed {}
+        public function setEmitter(mixed $emitter) {}
 
         /**
-         * Set `X-XSS-Protection` header.
-         *
-         * # Parameters
-         * - `mode`: one of `"off"`, `"on"` or `"block"`.
-         * - `report_uri`: Optional reporting URI, only allowed when `mode` is `"1"`.
+         * Builds the headers `send()` would emit.
          *
-         * # Exceptions
-         * - Throws if `mode` is invalid or a `report_uri` is provided for 'off' mode.
-         */
-        public function setXssProtection(string $mode, ?string $report_uri): mixed {}
-
-        /**
-         * Enable or disable `X-Content-Type-Options: nosniff`.
+         * # Returns
+         * - `list<array{string, string}>` `[name, value]` pairs in sending order.
          */
-        public function setNosniff(bool $enable) {}
+        public function buildPairs(): array {}
 
         /**
-         * Set `X-Permitted-Cross-Domain-Policies` header.
-         *
-         * # Parameters
-         * - `mode`: one of `"none"`, `"master-only"`, `"by-content-type"`, or `"all"`.
+         * Send the `Referrer-Policy` header via PHP `header()` function.
          *
          * # Exceptions
-         * - Throws if `mode` is not a valid policy token.
+         * - Throws `Exception` if the PHP `header()` function cannot be invoked.
          */
-        public function setPermittedCrossDomainPolicies(string $mode): mixed {}
+        public function send() {}
 
         /**
-         * Configure the `Report-To` header from structured arguments.
+         * Create a new Referrer-Policy builder for PHP.
+         *
+         * By default, the referrer policy is set to `no-referrer`, which prevents
+         * the `Referer` header from being sent with any requests.
          *
          * # Parameters
-         * - `group`: report group name.
-         * - `max_age`: seconds to retain reports.
-         * - `include_subdomains`: whether to include subdomains.
-         * - `endpoints`: PHP array of endpoint names.
+         * - `policy`: Optional string. If provided, must match one of these tokens:
+         *   - `"no-referrer"`                          — never send the `Referer` header.
+         *   - `"no-referrer-when-downgrade"`           — send full URL except when downgrading HTTPS→HTTP.
+         *   - `"origin"`                               — send only the origin (`scheme://host[:port]`).
+         *   - `"origin-when-cross-origin"`             — send full URL same-origin; origin for cross-origin.
+         *   - `"same-origin"`                          — send full URL only for same-origin; omit for cross-origin.
+         *   - `"strict-origin"`                        — send origin except on HTTPS→HTTP downgrade (omit then).
+         *   - `"strict-origin-when-cross-origin"`      — full URL same-origin; origin cross-origin non-downgrade; omit on downgrade.
+         *   - `"unsafe-url"`                           — always send full URL, regardless of context.
+         *
+         *   A comma-separated fallback chain of these tokens is accepted too (see `setChain()`).
          *
          * # Exceptions
-         * - Throws if any argument is invalid.
+         * - Throws `Exception` if `policy` is not a recognized directive.
          */
-        public function setReportTo(string $group, int $max_age, bool $include_subdomains, array $endpoints): mixed {}
+        public function __construct(?string $policy) {}
+    }
 
+    /**
+     * Builder for the `Reporting-Endpoints` header and its legacy `Report-To` counterpart.
+     *
+     * Endpoints are registered once by name; `report-to` in CSP, COOP/COEP and NEL
+     * configuration then refers to those names, so every header points at the same URLs.
+     */
+    class ReportingEndpoints {
         /**
-         * Set a structured `Integrity-Policy` header.
+         * Registers a named endpoint, replacing an existing one with the same name.
          *
          * # Parameters
-         * - `blocked_destinations`: PHP array of destinations, e.g. `['script']`.
-         * - `sources`: Optional PHP array of sources, e.g. `['inline']`.
-         * - `endpoints`: Optional PHP array of reporting endpoint names.
+         * - `name`: `string` Endpoint name, e.g. `csp-endpoint`; lowercase letters, digits, `_`, `-`,
+         *   `.` and `*`, starting with a letter or `*`.
+         * - `url`: `string` An `https://` URL or a path on the same origin.
          *
          * # Exceptions
-         * - Throws if any required array is missing or contains invalid entries.
-         */
-        public function setIntegrityPolicy(mixed $blocked_destinations, ?array $sources, ?array $endpoints): mixed {}
-
-        /**
-         * Set `Integrity-Policy-Report-Only` header value.
-         */
-        public function setIntegrityPolicyReportOnly(string $policy): mixed {}
-
-        /**
-         * Build an associative array of header names → values.
+         * - Throws an exception if the name or URL is invalid.
          */
-        public function build(): array {}
+        public function add(string $name, string $url) {}
 
         /**
-         * Emit all configured headers via PHP `header()` calls.
+         * Returns true if an endpoint with this name is registered.
+         *
+         * # Parameters
+         * - `name`: `string` Endpoint name.
          */
-        public function send(): mixed {}
+        public function has(string $name): bool {}
 
         /**
-         * Constructs a new builder with all headers disabled.
+         * Returns the registered endpoint names, in registration order.
+         *
+         * # Returns
+         * - `string[]` Names to use in `report-to` directives.
          */
-        public function __construct() {}
-    }
+        public function names(): array {}
 
-    /**
-     * Permissions-Policy header builder.
-     */
-    class PermissionsPolicy {
         /**
-         * Controls whether the current document is allowed to gather information
-         * about the acceleration of the device through the Accelerometer interface.
+         * Also emit the legacy `Report-To` header, for browsers without `Reporting-Endpoints`.
+         *
+         * # Parameters
+         * - `maxAge`: `?int` Seconds browsers keep the endpoint groups. Default: `86400`.
+         * - `includeSubdomains`: `?bool` Apply the groups to subdomains. Default: `false`.
          */
-        const ACCELEROMETER = null;
+        public function legacyReportTo(?int $max_age, ?bool $include_subdomains) {}
 
         /**
-         * Controls whether the current document is allowed to gather information
-         * about the amount of light in the environment around the device through
-         * the AmbientLightSensor interface.
+         * Builds the `Reporting-Endpoints` header value.
+         *
+         * # Returns
+         * - `string` e.g. `csp-endpoint="https://example.com/csp", default="/reports"`.
          */
-        const AMBIENT_LIGHT_SENSOR = null;
+        public function build(): string {}
 
         /**
-         * Controls whether the current document is allowed to use the
-         * Attribution Reporting API.
+         * Builds the legacy `Report-To` header value: one JSON group per endpoint.
+         *
+         * # Returns
+         * - `string` Comma-separated JSON objects.
          */
-        const ATTRIBUTION_REPORTING = null;
+        public function buildReportTo(): string {}
 
         /**
-         * Controls whether the current document is allowed to autoplay media
-         * requested through the HTMLMediaElement interface. When disabled without
-         * user gesture, play() will reject with NotAllowedError.
+         * Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+         * headers to a worker runtime's response object.
+         *
+         * # Parameters
+         * - `emitter`: `callable(string $name, string $value): void`
+         *
+         * # Exceptions
+         * - Throws `Exception` if `emitter` is not callable.
          */
-        const AUTOPLAY = null;
+        public function setEmitter(mixed $emitter) {}
 
         /**
-         * Controls whether the use of the Web Bluetooth API is allowed.
-         * When disabled, Bluetooth methods will either return false or reject.
+         * Builds the headers `send()` would emit.
+         *
+         * # Returns
+         * - `list<array{string, string}>` `[name, value]` pairs in sending order.
          */
-        const BLUETOOTH = null;
+        public function buildPairs(): array {}
 
         /**
-         * Controls access to the Topics API. Disallowed calls to browsingTopics()
-         * or Sec-Browsing-Topics header will fail with NotAllowedError.
+         * Sends `Reporting-Endpoints` (and `Report-To` if enabled) via PHP `header()`.
+         * Nothing is sent if no endpoint is registered.
+         *
+         * # Exceptions
+         * - Throws `Exception` if PHP `header()` cannot be invoked.
          */
-        const BROWSING_TOPICS = null;
+        public function send() {}
 
         /**
-         * Controls whether the current document is allowed to use video input devices.
-         * When disabled, getUserMedia() will reject with NotAllowedError.
+         * Constructs a builder with no endpoints and the legacy `Report-To` header disabled.
          */
-        const CAMERA = null;
+        public function __construct() {}
+    }
 
+    /**
+     * Configures `Cross-Origin-Opener-Policy`, `Cross-Origin-Embedder-Policy` and
+     * `Cross-Origin-Resource-Policy` together, so the page ends up `crossOriginIsolated`
+     * (required for `SharedArrayBuffer` and high-resolution timers).
+     */
+    class CrossOriginIsolation {
         /**
-         * Controls access to the Compute Pressure API.
+         * Sets the enforced `Cross-Origin-Opener-Policy`.
+         *
+         * # Parameters
+         * - `policy`: `string` `unsafe-none`, `same-origin` or `same-origin-allow-popups`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the token is invalid.
          */
-        const COMPUTE_PRESSURE = null;
+        public function opener(string $policy) {}
 
         /**
-         * Controls whether the current document can be treated as cross-origin isolated.
+         * Sets (or with `null` removes) the `Cross-Origin-Opener-Policy-Report-Only` value.
+         *
+         * # Parameters
+         * - `policy`: `?string` Same tokens as `opener()`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the token is invalid.
          */
-        const CROSS_ORIGIN_ISOLATED = null;
+        public function openerReportOnly(?string $policy) {}
 
         /**
-         * Controls the allocation of the top-level origin’s fetchLater() quota.
+         * Sets the enforced `Cross-Origin-Embedder-Policy`.
+         *
+         * # Parameters
+         * - `policy`: `EmbedderPolicyValue` e.g. `EmbedderPolicyValue::Credentialless`.
          */
-        const DEFERRED_FETCH = null;
+        public function embedder(\Hardened\SecurityHeaders\CrossOrigin\EmbedderPolicyValue $policy) {}
 
         /**
-         * Controls the allocation of the shared cross-origin subframe fetchLater() quota.
+         * Sets (or with `null` removes) the `Cross-Origin-Embedder-Policy-Report-Only` value.
+         *
+         * # Parameters
+         * - `policy`: `?EmbedderPolicyValue`
          */
-        const DEFERRED_FETCH_MINIMAL = null;
+        public function embedderReportOnly(?\Hardened\SecurityHeaders\CrossOrigin\EmbedderPolicyValue $policy) {}
 
         /**
-         * Controls whether the current document may capture display media via getDisplayMedia().
-         * When disabled, getDisplayMedia() will reject with NotAllowedError.
+         * Sets the `Cross-Origin-Resource-Policy` sent with the response.
+         *
+         * # Parameters
+         * - `policy`: `string` `same-origin`, `same-site` or `cross-origin`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the token is invalid.
          */
-        const DISPLAY_CAPTURE = null;
+        public function resource(string $policy) {}
 
         /**
-         * Controls whether the current document is allowed to use the Encrypted Media
-         * Extensions API (EME). When disabled, requestMediaKeySystemAccess() will reject.
+         * Adds `report-to="<endpoint>"` to the COOP and COEP headers (enforced and report-only).
+         *
+         * # Parameters
+         * - `endpoint`: `?string` Name registered with `ReportingEndpoints`, or `null` to remove.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the name is not a valid endpoint name.
          */
-        const ENCRYPTED_MEDIA = null;
+        public function reportTo(?string $endpoint) {}
 
         /**
-         * Controls whether the current document is allowed to use Element.requestFullscreen().
-         * When disabled, requestFullscreen() will reject with TypeError.
+         * Lists the reasons the configuration would not make the page `crossOriginIsolated`.
+         *
+         * # Parameters
+         * - `reportOnly`: `?bool` Check the report-only values instead (falling back to the
+         *   enforced ones where unset), to see whether a trial would isolate. Default: `false`.
+         *
+         * # Returns
+         * - `string[]` Problems; empty if the page would be isolated.
          */
-        const FULLSCREEN = null;
+        public function validate(?bool $report_only): array {}
 
         /**
-         * Controls whether the current document is allowed to use the Gamepad API.
-         * When disabled, getGamepads() will throw SecurityError and events won’t fire.
+         * Returns true if the enforced headers make the page `crossOriginIsolated`.
          */
-        const GAMEPAD = null;
+        public function isIsolated(): bool {}
 
         /**
-         * Controls whether the current document is allowed to use the Geolocation Interface.
-         * When disabled, geolocation callbacks will error with PERMISSION_DENIED.
+         * Builds all configured headers.
+         *
+         * # Returns
+         * - `array<string,string>` Header names mapped to values, including the
+         *   `*-Report-Only` variants when set.
          */
-        const GEOLOCATION = null;
+        public function build(): array {}
 
         /**
-         * Controls whether the current document is allowed to gather information
-         * about device orientation through the Gyroscope interface.
+         * Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+         * headers to a worker runtime's response object.
+         *
+         * # Parameters
+         * - `emitter`: `callable(string $name, string $value): void`
+         *
+         * # Exceptions
+         * - Throws `Exception` if `emitter` is not callable.
          */
-        const GYROSCOPE = null;
+        public function setEmitter(mixed $emitter) {}
 
         /**
-         * Controls whether the current document is allowed to use the WebHID API.
-         * Allows communication with HID devices like gamepads or keyboards.
+         * Builds the headers `send()` would emit.
+         *
+         * # Returns
+         * - `list<array{string, string}>` `[name, value]` pairs in sending order.
          */
-        const HID = null;
+        public function buildPairs(): array {}
 
         /**
-         * Controls whether the document may use the Federated Credential Management API
-         * (FedCM) via navigator.credentials.get({identity:…}).
+         * Sends all configured headers via PHP `header()`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if PHP `header()` cannot be invoked.
          */
-        const IDENTITY_CREDENTIALS_GET = null;
+        public function send() {}
 
         /**
-         * Controls whether the document may use the Idle Detection API to detect user idle/active state.
+         * Constructs an isolating configuration: COOP `same-origin`, COEP `require-corp`
+         * and CORP `same-origin`, without report-only headers.
          */
-        const IDLE_DETECTION = null;
+        public function __construct() {}
+    }
 
+    /**
+     * `Set-Cookie` header builder that enforces secure attributes.
+     *
+     * Defaults to `Path=/; Secure; HttpOnly; SameSite=Lax`. `build()` rejects combinations
+     * browsers would silently drop or weaken: `__Host-` and `__Secure-` prefix rules,
+     * `SameSite=None` or `Partitioned` without `Secure`, and lifetimes over 400 days.
+     */
+    class SetCookie {
         /**
-         * Controls access to the language detection functionality of Translator & Language Detector APIs.
+         * Sets the `Path` attribute.
+         *
+         * # Parameters
+         * - `path`: `string` Must start with `/`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the path does not start with `/` or contains `;` or control characters.
          */
-        const LANGUAGE_DETECTOR = null;
+        public function path(string $path) {}
 
         /**
-         * Controls whether the document may gather data on locally-installed fonts via queryLocalFonts().
+         * Sets or clears the `Domain` attribute. Without it the cookie is host-only, which is safer.
+         *
+         * # Parameters
+         * - `domain`: `?string` e.g. `"example.com"` to share with subdomains, or `null`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the domain contains anything but letters, digits, `-` and `.`.
          */
-        const LOCAL_FONTS = null;
+        public function domain(?string $domain) {}
 
         /**
-         * Controls whether the document may gather device orientation via the Magnetometer interface.
+         * Sets the `Max-Age` attribute, or makes it a session cookie.
+         *
+         * # Parameters
+         * - `seconds`: `?int` Lifetime in seconds (`0` deletes the cookie), or `null` for a session cookie.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the lifetime exceeds 400 days.
          */
-        const MAGNETOMETER = null;
+        public function maxAge(?int $seconds) {}
 
         /**
-         * Controls whether the document is allowed to use audio input devices.
-         * When disabled, getUserMedia() will reject with NotAllowedError.
+         * Enables or disables the `Secure` attribute (enabled by default).
+         *
+         * # Parameters
+         * - `enable`: `bool`
          */
-        const MICROPHONE = null;
+        public function secure(bool $enable) {}
 
         /**
-         * Controls whether the document may use the Web MIDI API.
-         * When disabled, requestMIDIAccess() will reject with SecurityError.
+         * Enables or disables the `HttpOnly` attribute (enabled by default).
+         *
+         * # Parameters
+         * - `enable`: `bool`
          */
-        const MIDI = null;
+        public function httpOnly(bool $enable) {}
 
         /**
-         * Controls whether the document may use the WebOTP API to retrieve one-time passwords.
+         * Sets the `SameSite` attribute (`Lax` by default).
+         *
+         * # Parameters
+         * - `sameSite`: `string` `"Strict"`, `"Lax"` or `"None"`, case-insensitive.
+         *
+         * # Exceptions
+         * - Throws `Exception` for any other value.
          */
-        const OTP_CREDENTIALS = null;
+        public function sameSite(string $same_site) {}
 
         /**
-         * Controls whether the document may use the Payment Request API.
-         * When disabled, PaymentRequest() will throw SecurityError.
+         * Enables or disables the `Partitioned` attribute (CHIPS), for third-party cookies
+         * keyed by the top-level site.
+         *
+         * # Parameters
+         * - `enable`: `bool`
          */
-        const PAYMENT = null;
+        public function partitioned(bool $enable) {}
 
         /**
-         * Controls whether the document may enter Picture-in-Picture mode via the API.
+         * Builds the `Set-Cookie` header value.
+         *
+         * # Returns
+         * - `string` e.g. `"__Host-sid=abc; Path=/; Secure; HttpOnly; SameSite=Lax"`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the attributes break a prefix rule, or `SameSite=None` or
+         *   `Partitioned` is used without `Secure`.
          */
-        const PICTURE_IN_PICTURE = null;
+        public function build(): string {}
 
         /**
-         * Controls whether the document may use Web Authentication API to create new credentials.
+         * Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+         * headers to a worker runtime's response object.
+         *
+         * # Parameters
+         * - `emitter`: `callable(string $name, string $value): void`
+         *
+         * # Exceptions
+         * - Throws `Exception` if `emitter` is not callable.
          */
-        const PUBLICKEY_CREDENTIALS_CREATE = null;
+        public function setEmitter(mixed $emitter) {}
 
         /**
-         * Controls whether the document may use Web Authentication API to retrieve stored credentials.
+         * Builds the headers `send()` would emit.
+         *
+         * # Returns
+         * - `list<array{string, string}>` `[name, value]` pairs in sending order.
          */
-        const PUBLICKEY_CREDENTIALS_GET = null;
+        public function buildPairs(): array {}
 
         /**
-         * Controls whether the document may use the Screen Wake Lock API to keep the screen on.
+         * Sends the cookie via PHP `header()` function, keeping previously set cookies.
+         *
+         * # Exceptions
+         * - Throws `Exception` if `build()` fails or PHP `header()` cannot be invoked.
          */
-        const SCREEN_WAKE_LOCK = null;
+        public function send() {}
 
         /**
-         * Controls whether the document may use the Web Serial API to communicate with serial devices.
+         * Creates a cookie builder with the secure defaults.
+         *
+         * # Parameters
+         * - `name`: `string` Cookie name, optionally `__Host-` or `__Secure-` prefixed.
+         * - `value`: `string` Cookie value; encode arbitrary data (e.g. with `rawurlencode()`) first.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the name is not a valid token or the value contains
+         *   whitespace, control characters, `"`, `,`, `;` or `\`.
          */
-        const SERIAL = null;
+        public function __construct(string $name, string $value) {}
+    }
 
+    /**
+     * One object for the whole security header baseline.
+     *
+     * Composes CSP, HSTS, `Whatnot` (frame options, nosniff, ...), `Referrer-Policy`,
+     * `Permissions-Policy` and the cross-origin policies, starting from opinionated defaults.
+     * Each component can be replaced with an instance configured by hand, and single headers
+     * can be switched off with `disable()`.
+     */
+    class Manager {
         /**
-         * Controls whether the document may list and select speakers via the Output Devices API.
+         * Constructs a manager tuned for a deployment environment, starting from the defaults of
+         * `__construct()`:
+         *
+         * - `development`: no HSTS, and `script-src`/`connect-src` also allow a dev server on
+         *   `localhost` (`http:`, `ws:` and `wss:`, any port) for hot module replacement.
+         * - `staging`: HSTS for one day without `includeSubDomains`, so trying it out does not pin
+         *   sibling hosts, and `X-Robots-Tag: noindex, nofollow`.
+         * - `production`: the defaults, locked. Any later `set…()` or `disable()` call that would
+         *   make `Audit::grade()` report a finding, or make COOP, CORP,
+         *   `X-Permitted-Cross-Domain-Policies` or the denied `Permissions-Policy` features weaker
+         *   than the defaults, is refused.
+         *
+         * # Parameters
+         * - `profile`: `string` `development` (`dev`), `staging` (`stage`) or `production` (`prod`).
+         *
+         * # Exceptions
+         * - Throws `Exception` if the profile is unknown.
          */
-        const SPEAKER_SELECTION = null;
+        public static function forProfile(string $profile): \Hardened\SecurityHeaders\Manager {}
 
         /**
-         * Controls whether an embedded document may use the Storage Access API for third-party cookies.
+         * Returns the profile given to `forProfile()`, or `null` for `new Manager()`.
          */
-        const STORAGE_ACCESS = null;
+        public function profile(): ?string {}
 
         /**
-         * Controls access to the translation functionality of Translator & Language Detector APIs.
+         * Replaces the Content-Security-Policy (a copy of `$csp` is kept; configure it first,
+         * including `nonce()` if templates need the nonce).
+         *
+         * # Parameters
+         * - `csp`: `ContentSecurityPolicy`
+         *
+         * # Exceptions
+         * - Throws `Exception` under the production profile if the result would be weaker.
          */
-        const TRANSLATOR = null;
+        public function setCsp(\Hardened\SecurityHeaders\ContentSecurityPolicy $csp) {}
 
         /**
-         * Controls access to the Summarizer API.
+         * Replaces the Strict-Transport-Security builder.
+         *
+         * # Parameters
+         * - `hsts`: `StrictTransportSecurity`
+         *
+         * # Exceptions
+         * - Throws `Exception` under the production profile if the result would be weaker.
          */
-        const SUMMARIZER = null;
+        public function setHsts(\Hardened\SecurityHeaders\StrictTransportSecurity $hsts) {}
 
         /**
-         * Controls whether the document may use the WebUSB API to connect to USB devices.
+         * Replaces the `Whatnot` builder (frame options, nosniff, robots, integrity policy, ...).
+         *
+         * # Parameters
+         * - `whatnot`: `Whatnot`
+         *
+         * # Exceptions
+         * - Throws `Exception` under the production profile if the result would be weaker.
          */
-        const USB = null;
+        public function setWhatnot(\Hardened\SecurityHeaders\Whatnot $whatnot) {}
 
         /**
-         * Controls whether the document may use the Web Share API (navigator.share()).
+         * Replaces the Referrer-Policy builder.
+         *
+         * # Parameters
+         * - `policy`: `ReferrerPolicy`
+         *
+         * # Exceptions
+         * - Throws `Exception` under the production profile if the result would be weaker.
          */
-        const WEB_SHARE = null;
+        public function setReferrerPolicy(\Hardened\SecurityHeaders\ReferrerPolicy $policy) {}
 
         /**
-         * Controls whether the document may use the Window Management API to manage windows.
+         * Replaces the Permissions-Policy builder.
+         *
+         * # Parameters
+         * - `policy`: `PermissionsPolicy`
+         *
+         * # Exceptions
+         * - Throws `Exception` under the production profile if the result would be weaker.
          */
-        const WINDOW_MANAGEMENT = null;
+        public function setPermissionsPolicy(\Hardened\SecurityHeaders\PermissionsPolicy $policy) {}
 
         /**
-         * Controls whether the document may use the WebXR Device API to interact with XR sessions.
+         * Replaces the Cross-Origin-Opener-Policy builder.
+         *
+         * # Parameters
+         * - `policy`: `CrossOrigin\OpenerPolicy`
+         *
+         * # Exceptions
+         * - Throws `Exception` under the production profile if the result would be weaker.
          */
-        const XR_SPATIAL_TRACKING = null;
-
-        const ORIGIN_SELF = null;
-
-        const ORIGIN_ANY = null;
-
-        const ORIGIN_SRC = null;
+        public function setOpenerPolicy(\Hardened\SecurityHeaders\CrossOrigin\OpenerPolicy $policy) {}
 
         /**
-         * Allow a feature for the given list of origins.
+         * Sets the Cross-Origin-Embedder-Policy builder (not sent until set).
          *
          * # Parameters
-         * - `feature`: one of the defined `Feature` tokens.
-         * - `origins`: list of allowlist entries, e.g. `"self"`, `"*"`, `"src"`, or quoted origins.
+         * - `policy`: `CrossOrigin\EmbedderPolicy`
          *
-         * # Errors
-         * - if `feature` is not recognized.
+         * # Exceptions
+         * - Throws `Exception` under the production profile if the result would be weaker.
          */
-        public function allow(string $feature, array $origins): mixed {}
+        public function setEmbedderPolicy(\Hardened\SecurityHeaders\CrossOrigin\EmbedderPolicy $policy) {}
 
         /**
-         * Deny a feature entirely (empty allowlist).
+         * Replaces the Cross-Origin-Resource-Policy builder.
          *
          * # Parameters
-         * - `feature`: one of the defined `Feature` tokens.
+         * - `policy`: `CrossOrigin\ResourcePolicy`
          *
-         * # Errors
-         * - if `feature` is not recognized.
+         * # Exceptions
+         * - Throws `Exception` under the production profile if the result would be weaker.
          */
-        public function deny(string $feature): mixed {}
+        public function setResourcePolicy(\Hardened\SecurityHeaders\CrossOrigin\ResourcePolicy $policy) {}
 
         /**
-         * Builds the Permissions-Policy header value.
+         * Stops emitting a header, e.g. `disable('Strict-Transport-Security')` on plain-HTTP
+         * development hosts.
          *
-         * # Returns
-         * - `String`, e.g.:
-         *   `geolocation=(self "https://api.example.com"), camera=()`
+         * # Parameters
+         * - `header`: `string` Header name, case-insensitive.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the manager does not emit this header.
+         * - Throws `Exception` under the production profile if the header protects the response.
          */
-        public function build(): string {}
+        public function disable(string $header) {}
 
         /**
-         * Sends the Permissions-Policy header via PHP `header()` function.
+         * Emits a previously disabled header again.
          *
-         * # Errors
-         * - Returns an error if PHP `header()` cannot be invoked.
+         * # Parameters
+         * - `header`: `string` Header name, case-insensitive.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the manager does not emit this header.
          */
-        public function send(): mixed {}
+        public function enable(string $header) {}
 
         /**
-         * Constructs a new Permissions-Policy builder with no features allowed.
+         * Builds every enabled, non-empty header.
          *
          * # Returns
-         * - `PermissionsPolicy` New instance with an empty feature map.
+         * - `array<string,string>` Header names mapped to values.
+         *
+         * # Exceptions
+         * - Throws `Exception` if a component is misconfigured (e.g. HSTS `preload` without
+         *   `includeSubDomains`).
          */
-        public function __construct() {}
-    }
+        public function build(): array {}
 
-    /**
-     * Referrer-Policy header builder.
-     */
-    class ReferrerPolicy {
         /**
-         * Update the active Referrer-Policy directive.
+         * Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+         * headers to a worker runtime's response object. Emitters set on the component
+         * builders are not used by the manager.
          *
          * # Parameters
-         * - `policy`: Directive string. Must be one of the tokens listed above for `__construct`.
+         * - `emitter`: `callable(string $name, string $value): void`
          *
          * # Exceptions
-         * - Throws `Exception` if the provided token is invalid.
+         * - Throws `Exception` if `emitter` is not callable.
          */
-        public function set(string $policy): mixed {}
+        public function setEmitter(mixed $emitter) {}
 
         /**
-         * Get the current Referrer-Policy value.
+         * Builds every enabled, non-empty header.
          *
          * # Returns
-         * - `string` the active policy token.
+         * - `list<array{string, string}>` `[name, value]` pairs in sending order.
+         *
+         * # Exceptions
+         * - Throws `Exception` if a component is misconfigured.
          */
-        public function get(): string {}
+        public function buildPairs(): array {}
 
         /**
-         * Build the `Referrer-Policy` header value.
+         * Sends every enabled header via PHP `header()`, or the callable set with `setEmitter()`.
          *
-         * # Returns
-         * - `string` the configured policy value suitable for sending as a header.
+         * # Exceptions
+         * - Throws `Exception` if a component is misconfigured.
+         * - Throws `Exception` if PHP `header()` cannot be invoked or the emitter fails.
          */
-        public function build(): string {}
+        public function send() {}
 
         /**
-         * Send the `Referrer-Policy` header via PHP `header()` function.
+         * Constructs a manager with secure defaults:
+         *
+         * - `Content-Security-Policy: default-src 'self'; frame-ancestors 'none'; object-src 'none';
+         *   base-uri 'self'; form-action 'self'`
+         * - `Strict-Transport-Security: max-age=31536000; includeSubDomains`
+         * - `X-Frame-Options: DENY`, `X-Content-Type-Options: nosniff`, `X-XSS-Protection: 0`,
+         *   `X-Permitted-Cross-Domain-Policies: none`
+         * - `Referrer-Policy: strict-origin-when-cross-origin`
+         * - `Permissions-Policy` denying camera, microphone, geolocation, payment, USB, display
+         *   capture and interest-based advertising
+         * - `Cross-Origin-Opener-Policy: same-origin`, `Cross-Origin-Resource-Policy: same-origin`
+         *
+         * `Cross-Origin-Embedder-Policy` is not sent by default, since it blocks cross-origin
+         * embeds that do not opt in; set it with `setEmbedderPolicy()`.
+         *
+         * Unlike `forProfile('production')`, the result can be changed freely.
          *
          * # Exceptions
-         * - Throws `Exception` if the PHP `header()` function cannot be invoked.
+         * - Throws `Exception` if a default cannot be constructed (never expected).
          */
-        public function send(): mixed {}
+        public function __construct() {}
+    }
 
+    /**
+     * Grades response headers the way securityheaders.com does, for integration tests and
+     * health checks.
+     */
+    class Audit {
         /**
-         * Create a new Referrer-Policy builder for PHP.
-         *
-         * By default, the referrer policy is set to `no-referrer`, which prevents
-         * the `Referer` header from being sent with any requests.
+         * Inspects a response's headers and reports missing or misconfigured security headers,
+         * together with the builder calls that fix them.
          *
          * # Parameters
-         * - `policy`: Optional string. If provided, must match one of these tokens:
-         *   - `"no-referrer"`                          — never send the `Referer` header.
-         *   - `"no-referrer-when-downgrade"`           — send full URL except when downgrading HTTPS→HTTP.
-         *   - `"origin"`                               — send only the origin (`scheme://host[:port]`).
-         *   - `"origin-when-cross-origin"`             — send full URL same-origin; origin for cross-origin.
-         *   - `"same-origin"`                          — send full URL only for same-origin; omit for cross-origin.
-         *   - `"strict-origin"`                        — send origin except on HTTPS→HTTP downgrade (omit then).
-         *   - `"strict-origin-when-cross-origin"`      — full URL same-origin; origin cross-origin non-downgrade; omit on downgrade.
-         *   - `"unsafe-url"`                           — always send full URL, regardless of context.
+         * - `headers`: `array` Either a name => value map (values may be lists for repeated
+         *   headers), or a list of `"Name: value"` lines as returned by `headers_list()`.
+         *
+         * # Returns
+         * - `array` `['grade' => 'A+'..'F', 'score' => 0..100, 'findings' => [['severity' =>
+         *   'high'|'medium'|'low', 'header' => string, 'message' => string, 'fix' => string],
+         *   ...]]`, findings most severe first.
          *
          * # Exceptions
-         * - Throws `Exception` if `policy` is not a recognized directive.
+         * - Throws `Exception` if a header value is neither a string nor a list of strings.
          */
-        public function __construct(?string $policy) {}
+        public static function grade(array $headers): array {}
     }
 }
 
@@ -2379,7 +7522,7 @@ namespace Hardened\SecurityHeaders\CrossOrigin {
      * CORS policy builder for HTTP responses.
      */
     class ResourceSharing {
-        const SELF = null;
+        const ORIGIN_SELF = null;
 
         /**
          * Specify which origins are allowed to access the resource.
@@ -2401,6 +7544,33 @@ namespace Hardened\SecurityHeaders\CrossOrigin {
          */
         public function allowOrigins(array $origins) {}
 
+        /**
+         * Allow every origin matching a pattern, e.g. `*.example.com` or
+         * `http://**.example.com:8080`.
+         *
+         * The host is matched with `Hostname::matchesPattern()`: `*.` covers exactly one label,
+         * `**.` any depth, and the apex never matches. The scheme defaults to `https` and the port
+         * to the scheme's default, so `*.example.com` does not match `http://` or `:8443` origins.
+         * Matching origins are echoed back individually, so patterns work with credentials.
+         *
+         * # Parameters
+         * - `pattern`: `string` Origin pattern `[scheme://]host[:port]`.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the pattern is malformed.
+         */
+        public function allowOriginPattern(string $pattern) {}
+
+        /**
+         * Returns true if a request `Origin` is allowed, either by `allowOrigins()` or by a
+         * pattern. Origins are compared by scheme, host and port, so `https://Example.com:443`
+         * equals `https://example.com`. The `*` wildcard is not considered here.
+         *
+         * # Parameters
+         * - `origin`: `string` Value of the request's `Origin` header.
+         */
+        public function isOriginAllowed(string $origin): bool {}
+
         /**
          * Specify which HTTP methods may be used in cross-origin requests.
          *
@@ -2446,7 +7616,8 @@ namespace Hardened\SecurityHeaders\CrossOrigin {
          *   to omit the `Access-Control-Allow-Credentials` header.
          *
          * # Behavior
-         * - If enabled, you **cannot** use `"*"` for `allow_origins`.
+         * - If enabled, you **cannot** use `"*"` for `allow_origins`: `build()`, `send()` and
+         *   `handlePreflight()` throw instead of emitting both.
          *
          * # Returns
          * - `void`
@@ -2489,21 +7660,82 @@ namespace Hardened\SecurityHeaders\CrossOrigin {
         /**
          * Build an associative array of CORS headers and their values.
          *
+         * `Access-Control-Allow-Origin` names a single origin. Pass the request's `Origin` to
+         * have it echoed back when it is allowed (by `allowOrigins()` or a pattern), together
+         * with `Vary: Origin`; without it, the header is only sent for `*` or a single allowed
+         * origin.
+         *
+         * # Parameters
+         * - `origin`: `?string` Value of the request's `Origin` header, e.g.
+         *   `$_SERVER['HTTP_ORIGIN'] ?? null`.
+         *
          * # Returns
          * - `array<string,string>` Map of header names to header values.
+         *
+         * # Exceptions
+         * - Throws `Exception` if credentials are enabled together with the `*` origin.
          */
-        public function build(): array {}
+        public function build(?string $origin): array {}
+
+        /**
+         * Evaluate a CORS preflight (`OPTIONS`) request and compute the exact response headers.
+         *
+         * The allowed origin is echoed back (or `*` when any origin is allowed and credentials
+         * are disabled), the requested method and headers are confirmed individually, and `Vary`
+         * lists every request header the answer depends on, so caches never mix up responses.
+         *
+         * # Parameters
+         * - `requestHeaders`: `array<string,string>` Request headers, e.g. from `getallheaders()`
+         *   or `$_SERVER` (`HTTP_ORIGIN`, ...). Names are case-insensitive.
+         *
+         * # Returns
+         * - `array<string,string>` Response headers to send with a `204` status, or
+         * - `false` if the request is not an allowed preflight; respond with `403` and no CORS
+         *   headers.
+         *
+         * # Exceptions
+         * - Throws `Exception` if credentials are enabled together with the `*` origin.
+         * - Throws `Exception` if the result cannot be converted to a PHP value.
+         */
+        public function handlePreflight(array $request_headers): mixed {}
+
+        /**
+         * Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+         * headers to a worker runtime's response object.
+         *
+         * # Parameters
+         * - `emitter`: `callable(string $name, string $value): void`
+         *
+         * # Exceptions
+         * - Throws `Exception` if `emitter` is not callable.
+         */
+        public function setEmitter(mixed $emitter) {}
+
+        /**
+         * Builds the headers `send()` would emit.
+         *
+         * # Parameters
+         * - `origin`: `?string` Value of the request's `Origin` header; see `build()`.
+         *
+         * # Returns
+         * - `list<array{string, string}>` `[name, value]` pairs in sending order.
+         */
+        public function buildPairs(?string $origin): array {}
 
         /**
          * Send all configured CORS headers via PHP's `header()` function.
          *
+         * # Parameters
+         * - `origin`: `?string` Value of the request's `Origin` header; see `build()`.
+         *
          * # Returns
          * - `void`
          *
          * # Exceptions
+         * - Throws `Exception` if credentials are enabled together with the `*` origin.
          * - Throws `Exception` if PHP `header()` cannot be invoked.
          */
-        public function send(): mixed {}
+        public function send(?string $origin) {}
 
         /**
          * Constructs a new CORS policy with default settings (no restrictions).
@@ -2518,23 +7750,6 @@ namespace Hardened\SecurityHeaders\CrossOrigin {
      * Builder for `Cross-Origin-Embedder-Policy` header.
      */
     class EmbedderPolicy {
-        /**
-         * Allows the document to load cross-origin resources without giving explicit permission
-         * through CORS or `Cross-Origin-Resource-Policy`. This is the default.
-         */
-        const UNSAFE_NONE = null;
-
-        /**
-         * Only same-origin or resources explicitly marked via `Cross-Origin-Resource-Policy`
-         * or CORS may be loaded.
-         */
-        const REQUIRE_CORP = null;
-
-        /**
-         * Similar to `require-corp`, but drops credentials on no-CORS requests.
-         */
-        const CREDENTIALLESS = null;
-
         /**
          * Update the COEP directive.
          *
@@ -2544,7 +7759,7 @@ namespace Hardened\SecurityHeaders\CrossOrigin {
          * # Exceptions
          * - Throws an `Exception` if `policy` cannot be parsed into a valid directive.
          */
-        public function set(string $policy): mixed {}
+        public function set(\Hardened\SecurityHeaders\CrossOrigin\EmbedderPolicyValue $policy) {}
 
         /**
          * Get the current Embedder-Policy value.
@@ -2554,21 +7769,63 @@ namespace Hardened\SecurityHeaders\CrossOrigin {
          */
         public function get(): string {}
 
+        /**
+         * Adds `report-to="<endpoint>"`, so violations are reported to a `ReportingEndpoints`
+         * name (applies to `send()` and `sendReportOnly()`).
+         *
+         * # Parameters
+         * - `endpoint`: `?string` Endpoint name, or `null` to stop reporting.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the name is not a valid endpoint name.
+         */
+        public function reportTo(?string $endpoint) {}
+
         /**
          * Render the header value.
          *
          * # Returns
-         * - `string`: the currently configured policy token.
+         * - `string`: the currently configured policy token, followed by the `report-to`
+         *   parameter if an endpoint is set.
          */
         public function build(): string {}
 
+        /**
+         * Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+         * headers to a worker runtime's response object.
+         *
+         * # Parameters
+         * - `emitter`: `callable(string $name, string $value): void`
+         *
+         * # Exceptions
+         * - Throws `Exception` if `emitter` is not callable.
+         */
+        public function setEmitter(mixed $emitter) {}
+
+        /**
+         * Builds the headers `send()` would emit.
+         *
+         * # Returns
+         * - `list<array{string, string}>` `[name, value]` pairs in sending order.
+         */
+        public function buildPairs(): array {}
+
         /**
          * Send the `Cross-Origin-Embedder-Policy` header via PHP `header()`.
          *
          * # Errors
          * - Throws `Exception` if the PHP `header()` function cannot be invoked.
          */
-        public function send(): mixed {}
+        public function send() {}
+
+        /**
+         * Sends the policy as `Cross-Origin-Embedder-Policy-Report-Only` instead, to trial it: violations
+         * are reported (see `reportTo()`) but nothing is blocked.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the PHP `header()` function cannot be invoked.
+         */
+        public function sendReportOnly() {}
 
         /**
          * Create a new Cross-Origin-Embedder-Policy (COEP) builder for PHP.
@@ -2585,7 +7842,29 @@ namespace Hardened\SecurityHeaders\CrossOrigin {
          * # Exceptions
          * - Throws `Exception` if an invalid token is provided.
          */
-        public function __construct(?string $policy) {}
+        public function __construct(?\Hardened\SecurityHeaders\CrossOrigin\EmbedderPolicyValue $policy) {}
+    }
+
+    /**
+     * Allowed values for the `Cross-Origin-Embedder-Policy` header.
+     */
+    enum EmbedderPolicyValue: string {
+        /**
+         * Allows the document to load cross-origin resources without giving explicit permission
+         * through CORS or `Cross-Origin-Resource-Policy`. This is the default.
+         */
+        case UnsafeNone = 'unsafe-none';
+
+        /**
+         * Only same-origin or resources explicitly marked via `Cross-Origin-Resource-Policy`
+         * or CORS may be loaded.
+         */
+        case RequireCorp = 'require-corp';
+
+        /**
+         * Similar to `require-corp`, but drops credentials on no-CORS requests.
+         */
+        case Credentialless = 'credentialless';
     }
 
     /**
@@ -2603,7 +7882,7 @@ namespace Hardened\SecurityHeaders\CrossOrigin {
          * # Exceptions
          * - Throws an `Exception` if `policy` cannot be parsed into a valid directive.
          */
-        public function set(string $policy): mixed {}
+        public function set(string $policy) {}
 
         /**
          * Get the current Resource-Policy value.
@@ -2621,13 +7900,33 @@ namespace Hardened\SecurityHeaders\CrossOrigin {
          */
         public function build(): string {}
 
+        /**
+         * Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+         * headers to a worker runtime's response object.
+         *
+         * # Parameters
+         * - `emitter`: `callable(string $name, string $value): void`
+         *
+         * # Exceptions
+         * - Throws `Exception` if `emitter` is not callable.
+         */
+        public function setEmitter(mixed $emitter) {}
+
+        /**
+         * Builds the headers `send()` would emit.
+         *
+         * # Returns
+         * - `list<array{string, string}>` `[name, value]` pairs in sending order.
+         */
+        public function buildPairs(): array {}
+
         /**
          * Send the `Cross-Origin-Resource-Policy` header via PHP `header()`.
          *
          * # Exceptions
          * - Throws `Exception` if the PHP `header()` function cannot be invoked.
          */
-        public function send(): mixed {}
+        public function send() {}
 
         /**
          * Create a new Cross-Origin-Resource-Policy builder.
@@ -2661,23 +7960,65 @@ namespace Hardened\SecurityHeaders\CrossOrigin {
          * # Exceptions
          * - Throws `Exception` if the given token is invalid.
          */
-        public function set(string $policy): mixed {}
+        public function set(string $policy) {}
+
+        /**
+         * Adds `report-to="<endpoint>"`, so violations are reported to a `ReportingEndpoints`
+         * name (applies to `send()` and `sendReportOnly()`).
+         *
+         * # Parameters
+         * - `endpoint`: `?string` Endpoint name, or `null` to stop reporting.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the name is not a valid endpoint name.
+         */
+        public function reportTo(?string $endpoint) {}
 
         /**
          * Build the header value.
          *
          * # Returns
-         * - `string` the configured policy, e.g. `"same-origin"`.
+         * - `string` the configured policy, e.g. `"same-origin"` or
+         *   `"same-origin; report-to=\"coop\""`.
          */
         public function build(): string {}
 
+        /**
+         * Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+         * headers to a worker runtime's response object.
+         *
+         * # Parameters
+         * - `emitter`: `callable(string $name, string $value): void`
+         *
+         * # Exceptions
+         * - Throws `Exception` if `emitter` is not callable.
+         */
+        public function setEmitter(mixed $emitter) {}
+
+        /**
+         * Builds the headers `send()` would emit.
+         *
+         * # Returns
+         * - `list<array{string, string}>` `[name, value]` pairs in sending order.
+         */
+        public function buildPairs(): array {}
+
         /**
          * Send the `Cross-Origin-Opener-Policy` header via PHP `header()`.
          *
          * # Exceptions
          * - Throws `Exception` if the PHP `header()` function cannot be invoked.
          */
-        public function send(): mixed {}
+        public function send() {}
+
+        /**
+         * Sends the policy as `Cross-Origin-Opener-Policy-Report-Only` instead, to trial it: violations
+         * are reported (see `reportTo()`) but nothing is blocked.
+         *
+         * # Exceptions
+         * - Throws `Exception` if the PHP `header()` function cannot be invoked.
+         */
+        public function sendReportOnly() {}
 
         /**
          * Create a new Cross-Origin-Opener-Policy builder.
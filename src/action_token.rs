@@ -0,0 +1,310 @@
+//! Single-purpose, expiring tokens for password resets, e-mail confirmation and similar
+//! one-click actions.
+//!
+//! The user receives the token; the application stores only its keyed hash, bound to the
+//! purpose and subject, so a leaked database row can neither be replayed as a link nor reused
+//! for another action or account.
+
+use crate::constant_time;
+use crate::rng::random_bytes;
+use data_encoding::BASE64URL_NOPAD;
+use ext_php_rs::binary::Binary;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+// Error codes for ActionToken errors: 3500-3599
+pub mod error_codes {
+    pub const KEY_TOO_SHORT: i32 = 3500;
+    pub const INVALID_TTL: i32 = 3501;
+    pub const INVALID_PURPOSE: i32 = 3502;
+    pub const RANDOM: i32 = 3503;
+}
+
+/// Errors produced by `ActionToken`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Key must be at least 32 bytes, got {0}")]
+    KeyTooShort(usize),
+
+    #[error("TTL must be a positive number of seconds, got {0}")]
+    InvalidTtl(i64),
+
+    #[error("Purpose must not be empty")]
+    InvalidPurpose,
+
+    #[error("Random generator failed: {0}")]
+    Random(String),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::KeyTooShort(_) => error_codes::KEY_TOO_SHORT,
+            Error::InvalidTtl(_) => error_codes::INVALID_TTL,
+            Error::InvalidPurpose => error_codes::INVALID_PURPOSE,
+            Error::Random(_) => error_codes::RANDOM,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for action token operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const MIN_KEY_LEN: usize = 32;
+/// Random bytes per token.
+const TOKEN_BYTES: usize = 32;
+const DEFAULT_TTL: i64 = 3600;
+
+/// Current UNIX time in seconds.
+fn unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+/// Expiry of a `"<expires>.<random>"` token, if it is well-formed.
+fn expires_of(token: &str) -> Option<i64> {
+    let (expires, random) = token.split_once('.')?;
+    let random = BASE64URL_NOPAD.decode(random.as_bytes()).ok()?;
+    if random.len() != TOKEN_BYTES || !expires.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    expires.parse().ok()
+}
+
+/// Issuer and verifier of hashed, purpose-bound action tokens.
+#[php_class]
+#[php(name = "Hardened\\ActionToken")]
+pub struct ActionToken {
+    /// Key derived from the configured secret, so it is never shared with another primitive.
+    key: [u8; 32],
+    ttl: i64,
+}
+
+impl ActionToken {
+    /// Backs `__construct()`.
+    fn _new(key: &[u8], ttl: Option<i64>) -> Result<Self> {
+        if key.len() < MIN_KEY_LEN {
+            return Err(Error::KeyTooShort(key.len()));
+        }
+        let ttl = ttl.unwrap_or(DEFAULT_TTL);
+        if ttl <= 0 {
+            return Err(Error::InvalidTtl(ttl));
+        }
+        let mut mac =
+            <HmacSha256 as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(b"hardened-action-token-v1");
+        Ok(Self {
+            key: mac.finalize().into_bytes().into(),
+            ttl,
+        })
+    }
+
+    /// Keyed hash of a token; purpose and subject are length-prefixed so no choice of
+    /// either can collide with another pair.
+    fn _hash(&self, token: &str, purpose: &str, subject: &str) -> String {
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(&self.key)
+            .expect("HMAC accepts keys of any length");
+        for part in [purpose, subject] {
+            mac.update(&(part.len() as u64).to_be_bytes());
+            mac.update(part.as_bytes());
+        }
+        mac.update(token.as_bytes());
+        BASE64URL_NOPAD.encode(&mac.finalize().into_bytes())
+    }
+
+    /// Issues a token as of `now`; returns `(token, hash)`.
+    fn _issue_at(
+        &self,
+        purpose: &str,
+        subject: &str,
+        ttl: Option<i64>,
+        now: i64,
+    ) -> Result<(String, String)> {
+        if purpose.is_empty() {
+            return Err(Error::InvalidPurpose);
+        }
+        let ttl = ttl.unwrap_or(self.ttl);
+        if ttl <= 0 {
+            return Err(Error::InvalidTtl(ttl));
+        }
+        let expires = now.checked_add(ttl).ok_or(Error::InvalidTtl(ttl))?;
+        let random = random_bytes(TOKEN_BYTES).map_err(|err| Error::Random(err.to_string()))?;
+        let token = format!("{expires}.{}", BASE64URL_NOPAD.encode(&random));
+        let hash = self._hash(&token, purpose, subject);
+        Ok((token, hash))
+    }
+
+    /// Verifies a token against its stored hash as of `now`.
+    fn _verify_at(
+        &self,
+        token: &str,
+        stored: &str,
+        purpose: &str,
+        subject: &str,
+        now: i64,
+    ) -> bool {
+        let Some(expires) = expires_of(token) else {
+            return false;
+        };
+        let matches = constant_time::eq(
+            self._hash(token, purpose, subject).as_bytes(),
+            stored.as_bytes(),
+        );
+        matches && now <= expires
+    }
+}
+
+#[php_impl]
+impl ActionToken {
+    /// Constructs a token manager.
+    ///
+    /// # Parameters
+    /// - `key`: `string` Secret of at least 32 bytes. Changing it invalidates outstanding
+    ///   tokens.
+    /// - `ttl`: `?int` Default lifetime in seconds. Default: 3600.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the key is too short or `ttl` is not positive.
+    fn __construct(key: Binary<u8>, ttl: Option<i64>) -> Result<Self> {
+        Self::_new(&key, ttl)
+    }
+
+    /// Issue a token.
+    ///
+    /// # Parameters
+    /// - `purpose`: `string` What the token authorizes, e.g. `"password-reset"`.
+    /// - `subject`: `string` Who it is for, e.g. a user id.
+    /// - `ttl`: `?int` Override the default lifetime.
+    ///
+    /// # Returns
+    /// - `array` `token` (send it to the user, e.g. in a link) and `hash` (store it, never
+    ///   the token).
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `purpose` is empty or `ttl` is not positive.
+    fn issue(
+        &self,
+        purpose: &str,
+        subject: &str,
+        ttl: Option<i64>,
+    ) -> Result<HashMap<&'static str, String>> {
+        let (token, hash) = self._issue_at(purpose, subject, ttl, unix_time())?;
+        Ok(HashMap::from([("token", token), ("hash", hash)]))
+    }
+
+    /// Check a presented token against the stored hash.
+    ///
+    /// # Parameters
+    /// - `token`: `string` Token presented by the user.
+    /// - `storedHash`: `string` Hash returned by `issue()`.
+    /// - `purpose`: `string` Purpose it must have been issued for.
+    /// - `subject`: `string` Subject it must have been issued for.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the token is well-formed, unexpired and matches in constant time.
+    ///   Delete the stored hash once it has been used.
+    fn verify(&self, token: &str, stored_hash: &str, purpose: &str, subject: &str) -> bool {
+        self._verify_at(token, stored_hash, purpose, subject, unix_time())
+    }
+
+    /// Compute the hash of a presented token, to look up its stored row.
+    ///
+    /// # Parameters
+    /// - `token`: `string` Token presented by the user.
+    /// - `purpose`: `string`
+    /// - `subject`: `string`
+    ///
+    /// # Returns
+    /// - `?string` `null` if the token is malformed.
+    fn hash(&self, token: &str, purpose: &str, subject: &str) -> Option<String> {
+        expires_of(token).map(|_| self._hash(token, purpose, subject))
+    }
+
+    /// Read the expiry embedded in a token, e.g. to store alongside its hash for cleanup.
+    ///
+    /// The expiry is covered by the hash, so editing it makes the token invalid.
+    ///
+    /// # Parameters
+    /// - `token`: `string`
+    ///
+    /// # Returns
+    /// - `?int` UNIX timestamp, or `null` if the token is malformed.
+    fn expires_at(token: &str) -> Option<i64> {
+        expires_of(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActionToken, Error, expires_of};
+
+    fn manager() -> ActionToken {
+        ActionToken::_new(&[7; 32], None).unwrap()
+    }
+
+    #[test]
+    fn test_issue_and_verify() {
+        let manager = manager();
+        let (token, hash) = manager._issue_at("reset", "42", None, 1000).unwrap();
+        assert!(token.starts_with("4600."));
+        assert_eq!(expires_of(&token), Some(4600));
+        assert_ne!(token, hash);
+        assert_eq!(manager.hash(&token, "reset", "42").as_deref(), Some(&*hash));
+        assert!(manager._verify_at(&token, &hash, "reset", "42", 1000));
+        assert!(manager._verify_at(&token, &hash, "reset", "42", 4600));
+        assert!(!manager._verify_at(&token, &hash, "reset", "42", 4601));
+
+        let (other, _) = manager._issue_at("reset", "42", Some(60), 1000).unwrap();
+        assert_ne!(token, other);
+        assert_eq!(expires_of(&other), Some(1060));
+    }
+
+    #[test]
+    fn test_binding_and_tampering() {
+        let manager = manager();
+        let (token, hash) = manager._issue_at("reset", "42", None, 1000).unwrap();
+        assert!(!manager._verify_at(&token, &hash, "confirm-email", "42", 1000));
+        assert!(!manager._verify_at(&token, &hash, "reset", "43", 1000));
+        assert!(!manager._verify_at(&token, &hash, "reset4", "2", 1000));
+        assert!(!manager._verify_at(&token, &token, "reset", "42", 1000));
+        let extended = token.replacen("4600.", "9600.", 1);
+        assert!(!manager._verify_at(&extended, &hash, "reset", "42", 1000));
+        assert!(!manager._verify_at("garbage", &hash, "reset", "42", 1000));
+        assert_eq!(manager.hash("1.abc", "reset", "42"), None);
+        let other = ActionToken::_new(&[8; 32], None).unwrap();
+        assert!(!other._verify_at(&token, &hash, "reset", "42", 1000));
+
+        assert!(matches!(
+            manager._issue_at("", "42", None, 1000),
+            Err(Error::InvalidPurpose)
+        ));
+        assert!(matches!(
+            manager._issue_at("reset", "42", Some(0), 1000),
+            Err(Error::InvalidTtl(0))
+        ));
+        assert!(matches!(
+            ActionToken::_new(&[0; 16], None),
+            Err(Error::KeyTooShort(16))
+        ));
+    }
+}
@@ -0,0 +1,382 @@
+//! API keys in the style of modern secret-scanning-friendly tokens:
+//! `<prefix>_<id>_<secret><checksum>`.
+//!
+//! The prefix makes keys recognizable in leaked code and logs, the CRC-32 checksum lets a
+//! scanner tell real keys from random strings offline, and the public id lets the server find
+//! the stored row without the secret. Only a SHA-256 verifier of the key is stored, together
+//! with its creation time for rotation policies.
+
+use crate::constant_time;
+use data_encoding::BASE64URL_NOPAD;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use rand::distr::{Alphanumeric, SampleString};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+// Error codes for ApiKey errors: 3600-3699
+pub mod error_codes {
+    pub const INVALID_PREFIX: i32 = 3600;
+    pub const INVALID_OPTION: i32 = 3601;
+}
+
+/// Errors produced by `ApiKey`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Prefix must be 1-16 lowercase letters and digits, starting with a letter: {0:?}")]
+    InvalidPrefix(String),
+
+    #[error("Invalid option: {0}")]
+    InvalidOption(String),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::InvalidPrefix(_) => error_codes::INVALID_PREFIX,
+            Error::InvalidOption(_) => error_codes::INVALID_OPTION,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for API key operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+const MAX_PREFIX_LEN: usize = 16;
+/// Length of the public lookup id.
+const ID_LEN: usize = 12;
+/// Length of the secret part; 32 alphanumerics carry about 190 bits.
+const SECRET_LEN: usize = 32;
+/// Length of the base62 CRC-32; 62^6 exceeds 2^32.
+const CHECKSUM_LEN: usize = 6;
+const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+/// Version tag of stored verifiers.
+const HASH_VERSION: &str = "v1";
+
+/// Current UNIX time in seconds.
+fn unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+/// CRC-32 (IEEE 802.3), as used by zlib and PHP's `crc32()`.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// Fixed-width base62 CRC-32 of the key body.
+fn checksum(body: &str) -> String {
+    let mut value = crc32(body.as_bytes());
+    let mut digits = [b'0'; CHECKSUM_LEN];
+    for digit in digits.iter_mut().rev() {
+        *digit = BASE62[(value % 62) as usize];
+        value /= 62;
+    }
+    String::from_utf8_lossy(&digits).into_owned()
+}
+
+/// Stored verifier of a key.
+fn digest(key: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(b"hardened-api-key-v1\0")
+        .chain_update(key.as_bytes())
+        .finalize();
+    BASE64URL_NOPAD.encode(&digest)
+}
+
+/// Components of a well-formed key.
+#[derive(Debug, PartialEq, Eq)]
+struct Parsed<'a> {
+    prefix: &'a str,
+    id: &'a str,
+}
+
+/// Splits a key and checks its shape and checksum.
+fn parse(key: &str) -> Option<Parsed<'_>> {
+    let mut parts = key.split('_');
+    let (Some(prefix), Some(id), Some(rest), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    if !valid_prefix(prefix)
+        || id.len() != ID_LEN
+        || rest.len() != SECRET_LEN + CHECKSUM_LEN
+        || !id
+            .bytes()
+            .chain(rest.bytes())
+            .all(|b| b.is_ascii_alphanumeric())
+    {
+        return None;
+    }
+    let (body, sum) = key.split_at(key.len() - CHECKSUM_LEN);
+    (checksum(body) == sum).then_some(Parsed { prefix, id })
+}
+
+fn valid_prefix(prefix: &str) -> bool {
+    (1..=MAX_PREFIX_LEN).contains(&prefix.len())
+        && prefix.starts_with(|c: char| c.is_ascii_lowercase())
+        && prefix
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+}
+
+/// Splits a stored verifier into its creation time and digest.
+fn split_hash(stored: &str) -> Option<(i64, &str)> {
+    let mut parts = stored.splitn(3, '.');
+    let (Some(HASH_VERSION), Some(created_at), Some(digest)) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    Some((created_at.parse().ok()?, digest))
+}
+
+/// Generator and verifier of prefixed API keys.
+#[php_class]
+#[php(name = "Hardened\\ApiKey")]
+pub struct ApiKey {
+    prefix: String,
+    rotate_after: Option<i64>,
+}
+
+impl ApiKey {
+    /// Backs `__construct()`.
+    fn _new(prefix: &str, rotate_after: Option<i64>) -> Result<Self> {
+        if !valid_prefix(prefix) {
+            return Err(Error::InvalidPrefix(prefix.to_string()));
+        }
+        if let Some(seconds) = rotate_after.filter(|seconds| *seconds <= 0) {
+            return Err(Error::InvalidOption(format!(
+                "Rotation interval must be positive, got {seconds}"
+            )));
+        }
+        Ok(Self {
+            prefix: prefix.to_string(),
+            rotate_after,
+        })
+    }
+
+    /// Generates a key as of `now`; returns `(key, id, hash)`.
+    fn _generate_at(&self, now: i64) -> (String, String, String) {
+        let mut rng = rand::rng();
+        let id = Alphanumeric.sample_string(&mut rng, ID_LEN);
+        let secret = Alphanumeric.sample_string(&mut rng, SECRET_LEN);
+        let body = format!("{}_{id}_{secret}", self.prefix);
+        let key = format!("{body}{}", checksum(&body));
+        let hash = format!("{HASH_VERSION}.{now}.{}", digest(&key));
+        (key, id, hash)
+    }
+
+    fn _verify(&self, presented: &str, stored: &str) -> bool {
+        let presented = presented.trim();
+        let well_formed = parse(presented).is_some_and(|parsed| parsed.prefix == self.prefix);
+        let Some((_, stored)) = split_hash(stored) else {
+            return false;
+        };
+        well_formed && constant_time::eq(digest(presented).as_bytes(), stored.as_bytes())
+    }
+
+    fn _needs_rotation(&self, stored: &str, now: i64) -> bool {
+        match split_hash(stored) {
+            None => true,
+            Some((created_at, _)) => self
+                .rotate_after
+                .is_some_and(|interval| now.saturating_sub(created_at) >= interval),
+        }
+    }
+}
+
+#[php_impl]
+impl ApiKey {
+    /// Constructs a key generator.
+    ///
+    /// # Parameters
+    /// - `prefix`: `string` 1–16 lowercase letters and digits, starting with a letter,
+    ///   e.g. `"acme"` or `"sk"`. Register it with secret-scanning services.
+    /// - `rotateAfter`: `?int` Age in seconds after which `needsRotation()` reports a key.
+    ///   Default: never.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the prefix is invalid or `rotateAfter` is not positive.
+    fn __construct(prefix: &str, rotate_after: Option<i64>) -> Result<Self> {
+        Self::_new(prefix, rotate_after)
+    }
+
+    /// Generate a key.
+    ///
+    /// # Returns
+    /// - `array` `key` (show it to the user once), `id` (public, store it to look the key
+    ///   up) and `hash` (store it, never the key).
+    fn generate(&self) -> HashMap<&'static str, String> {
+        let (key, id, hash) = self._generate_at(unix_time());
+        HashMap::from([("key", key), ("id", id), ("hash", hash)])
+    }
+
+    /// Split a key into its parts without looking anything up.
+    ///
+    /// # Parameters
+    /// - `key`: `string` Presented key.
+    ///
+    /// # Returns
+    /// - `?array` `prefix` and `id`, or `null` if the key is malformed, has a wrong checksum
+    ///   or another prefix.
+    fn parse(&self, key: &str) -> Option<HashMap<&'static str, String>> {
+        parse(key.trim())
+            .filter(|parsed| parsed.prefix == self.prefix)
+            .map(|parsed| {
+                HashMap::from([
+                    ("prefix", parsed.prefix.to_string()),
+                    ("id", parsed.id.to_string()),
+                ])
+            })
+    }
+
+    /// Check whether a string looks like a key of any prefix, e.g. to scan logs or
+    /// commits for leaked keys.
+    ///
+    /// # Parameters
+    /// - `key`: `string`
+    ///
+    /// # Returns
+    /// - `bool` `true` if the shape and checksum are valid.
+    fn is_well_formed(key: &str) -> bool {
+        parse(key.trim()).is_some()
+    }
+
+    /// Check a presented key against its stored verifier.
+    ///
+    /// # Parameters
+    /// - `presented`: `string` Key from the request.
+    /// - `storedHash`: `string` Hash returned by `generate()`.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the key is well-formed, has this prefix and matches in constant
+    ///   time.
+    fn verify(&self, presented: &str, stored_hash: &str) -> bool {
+        self._verify(presented, stored_hash)
+    }
+
+    /// Read the creation time recorded in a stored verifier.
+    ///
+    /// # Parameters
+    /// - `storedHash`: `string`
+    ///
+    /// # Returns
+    /// - `?int` UNIX timestamp, or `null` if the hash is malformed.
+    fn created_at(stored_hash: &str) -> Option<i64> {
+        split_hash(stored_hash).map(|(created_at, _)| created_at)
+    }
+
+    /// Whether a key is older than the rotation interval.
+    ///
+    /// # Parameters
+    /// - `storedHash`: `string`
+    /// - `now`: `?int` Defaults to the current time.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the key should be replaced; also for malformed hashes.
+    fn needs_rotation(&self, stored_hash: &str, now: Option<i64>) -> bool {
+        self._needs_rotation(stored_hash, now.unwrap_or_else(unix_time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiKey, Error, Parsed, crc32, parse};
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_generate_and_verify() {
+        let keys = ApiKey::_new("acme", None).unwrap();
+        let (key, id, hash) = keys._generate_at(1000);
+        assert!(key.starts_with(&format!("acme_{id}_")));
+        assert_eq!(key.len(), 4 + 1 + 12 + 1 + 32 + 6);
+        assert_eq!(
+            parse(&key),
+            Some(Parsed {
+                prefix: "acme",
+                id: &id
+            })
+        );
+        assert!(hash.starts_with("v1.1000."));
+        assert!(!hash.contains(&key));
+        assert!(keys._verify(&key, &hash));
+        assert!(keys._verify(&format!(" {key}\n"), &hash));
+
+        let (other, _, other_hash) = keys._generate_at(1000);
+        assert!(!keys._verify(&other, &hash));
+        assert!(!keys._verify(&key, &other_hash));
+        assert!(!keys._verify(&key, "garbage"));
+        assert!(!ApiKey::_new("other", None).unwrap()._verify(&key, &hash));
+    }
+
+    #[test]
+    fn test_checksum() {
+        let keys = ApiKey::_new("sk", None).unwrap();
+        let (key, _, hash) = keys._generate_at(0);
+        let mut bytes = key.into_bytes();
+        let last = bytes.len() - 10;
+        bytes[last] = if bytes[last] == b'a' { b'b' } else { b'a' };
+        let typo = String::from_utf8(bytes).unwrap();
+        assert_eq!(parse(&typo), None);
+        assert!(!keys._verify(&typo, &hash));
+        assert_eq!(parse("sk_short_key"), None);
+        assert_eq!(parse("not a key"), None);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let keys = ApiKey::_new("acme", Some(86_400)).unwrap();
+        let (_, _, hash) = keys._generate_at(1000);
+        assert_eq!(ApiKey::created_at(&hash), Some(1000));
+        assert!(!keys._needs_rotation(&hash, 87_399));
+        assert!(keys._needs_rotation(&hash, 87_400));
+        assert!(keys._needs_rotation("garbage", 0));
+        assert!(
+            !ApiKey::_new("acme", None)
+                .unwrap()
+                ._needs_rotation(&hash, i64::MAX)
+        );
+
+        assert!(matches!(
+            ApiKey::_new("Acme", None),
+            Err(Error::InvalidPrefix(_))
+        ));
+        assert!(matches!(
+            ApiKey::_new("a_b", None),
+            Err(Error::InvalidPrefix(_))
+        ));
+        assert!(matches!(
+            ApiKey::_new("acme", Some(0)),
+            Err(Error::InvalidOption(_))
+        ));
+    }
+}
@@ -0,0 +1,333 @@
+//! Client IP resolution behind reverse proxies and load balancers.
+//!
+//! Forwarding headers are attacker-controlled except for the entries appended by proxies you
+//! run, so the chain is walked from the right, starting at the TCP peer, and each hop is only
+//! believed while the address that reported it is a trusted proxy.
+
+use crate::hostname::{self, Cidr};
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::types::{ZendHashTable, Zval};
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use thiserror::Error;
+
+// Error codes for ClientIp errors: 4200-4299
+pub mod error_codes {
+    pub const MISSING_REMOTE_ADDR: i32 = 4200;
+    pub const INVALID_REMOTE_ADDR: i32 = 4201;
+    pub const INVALID_HEADER: i32 = 4202;
+}
+
+/// Errors produced by `ClientIp`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("REMOTE_ADDR is missing from the server array")]
+    MissingRemoteAddr,
+
+    #[error("REMOTE_ADDR is not an IP address: {0}")]
+    InvalidRemoteAddr(String),
+
+    #[error("Invalid header name: {0}")]
+    InvalidHeader(String),
+
+    #[error(transparent)]
+    Hostname(#[from] hostname::Error),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::MissingRemoteAddr => error_codes::MISSING_REMOTE_ADDR,
+            Error::InvalidRemoteAddr(_) => error_codes::INVALID_REMOTE_ADDR,
+            Error::InvalidHeader(_) => error_codes::INVALID_HEADER,
+            Error::Hostname(err) => err.code(),
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for client IP resolution.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Header consulted when none are configured.
+const DEFAULT_HEADER: &str = "x-forwarded-for";
+
+/// Parses a node as written by proxies: a bare address, `[v6]`, `[v6]:port` or `v4:port`.
+/// IPv4-mapped IPv6 addresses are returned as IPv4.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim();
+    let ip = if let Some(rest) = node.strip_prefix('[') {
+        let (address, _) = rest.split_once(']')?;
+        IpAddr::V6(address.parse::<Ipv6Addr>().ok()?)
+    } else if let Ok(ip) = node.parse::<IpAddr>() {
+        ip
+    } else {
+        let (address, port) = node.rsplit_once(':')?;
+        port.parse::<u16>().ok()?;
+        IpAddr::V4(address.parse::<Ipv4Addr>().ok()?)
+    };
+    Some(match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+        v4 @ IpAddr::V4(_) => v4,
+    })
+}
+
+/// Splits `value` on `separator`, ignoring separators inside quoted strings.
+fn split_unquoted(value: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                parts.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+/// Hops of an RFC 7239 `Forwarded` header, left to right; `None` for an element without a
+/// usable `for=` address (`unknown`, an obfuscated identifier, or garbage).
+fn forwarded_hops(value: &str) -> Vec<Option<IpAddr>> {
+    split_unquoted(value, ',')
+        .into_iter()
+        .map(|element| {
+            split_unquoted(element, ';')
+                .into_iter()
+                .find_map(|pair| {
+                    let (name, value) = pair.split_once('=')?;
+                    name.trim()
+                        .eq_ignore_ascii_case("for")
+                        .then_some(value.trim())
+                })
+                .and_then(|value| {
+                    parse_node(
+                        value
+                            .strip_prefix('"')
+                            .and_then(|value| value.strip_suffix('"'))
+                            .unwrap_or(value),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Hops of an `X-Forwarded-For`-style comma-separated list, left to right.
+fn list_hops(value: &str) -> Vec<Option<IpAddr>> {
+    value.split(',').map(parse_node).collect()
+}
+
+/// Walks the chain from the peer leftwards while the current address is a trusted proxy.
+///
+/// A hop that is not an IP address stops the walk at the proxy that reported it, so garbage
+/// can never become the client address.
+fn walk(peer: IpAddr, hops: &[Option<IpAddr>], trusted: &[Cidr]) -> IpAddr {
+    let mut client = peer;
+    for hop in hops.iter().rev() {
+        if !trusted.iter().any(|cidr| cidr.contains(client)) {
+            break;
+        }
+        match hop {
+            Some(ip) => client = *ip,
+            None => break,
+        }
+    }
+    client
+}
+
+/// Resolves the client address from `REMOTE_ADDR` and a header lookup.
+fn resolve(
+    remote_addr: Option<&str>,
+    header: impl Fn(&str) -> Option<String>,
+    trusted: &[Cidr],
+    trusted_headers: &[String],
+) -> Result<IpAddr> {
+    let remote_addr = remote_addr.ok_or(Error::MissingRemoteAddr)?;
+    let peer =
+        parse_node(remote_addr).ok_or_else(|| Error::InvalidRemoteAddr(remote_addr.into()))?;
+    for name in trusted_headers {
+        if name.is_empty()
+            || !name
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_'))
+        {
+            return Err(Error::InvalidHeader(name.clone()));
+        }
+    }
+    // Headers are never consulted for a direct client, who can send anything
+    if !trusted.iter().any(|cidr| cidr.contains(peer)) {
+        return Ok(peer);
+    }
+    let found = trusted_headers.iter().find_map(|name| {
+        header(name)
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| (name, value))
+    });
+    let Some((name, value)) = found else {
+        return Ok(peer);
+    };
+    let hops = if name.eq_ignore_ascii_case("forwarded") {
+        forwarded_hops(&value)
+    } else {
+        list_hops(&value)
+    };
+    Ok(walk(peer, &hops, trusted))
+}
+
+/// Client address resolution with trusted proxies.
+#[php_class]
+#[php(name = "Hardened\\ClientIp")]
+pub struct ClientIp {}
+
+#[php_impl]
+impl ClientIp {
+    /// Determine the address of the client that sent the request.
+    ///
+    /// When `REMOTE_ADDR` is not a trusted proxy, it is the client and no header is read.
+    /// Otherwise the first configured header present is walked from the right: each address
+    /// is accepted as the next hop only while the previous one is a trusted proxy, so entries
+    /// a client prepends are never reached. An entry that is not an IP address stops the walk.
+    ///
+    /// # Parameters
+    /// - `server`: `array` Usually `$_SERVER`.
+    /// - `trustedProxyCidrs`: `string[]` Networks of your proxies and load balancers, e.g.
+    ///   `["10.0.0.0/8", "2001:db8::/32"]`; bare addresses are single hosts.
+    /// - `trustedHeaders`: `?string[]` Headers your proxies set, in order of preference:
+    ///   `forwarded` (RFC 7239) or any list header such as `x-forwarded-for`, `x-real-ip` or
+    ///   `cf-connecting-ip`. Default: `["x-forwarded-for"]`. List only headers every proxy
+    ///   overwrites or strips, or clients can supply the one consulted.
+    ///
+    /// # Returns
+    /// - `string` The client IP address; IPv4-mapped IPv6 addresses as IPv4.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `REMOTE_ADDR` is missing or invalid, a network is invalid, or
+    ///   a header name is invalid.
+    fn resolve(
+        server: &ZendHashTable,
+        trusted_proxy_cidrs: Vec<String>,
+        trusted_headers: Option<Vec<String>>,
+    ) -> Result<String> {
+        let trusted = Cidr::parse_list(&trusted_proxy_cidrs)?;
+        let trusted_headers = trusted_headers.unwrap_or_else(|| vec![DEFAULT_HEADER.into()]);
+        let remote_addr = server.get("REMOTE_ADDR").and_then(Zval::string);
+        let header = |name: &str| {
+            server
+                .get(format!("HTTP_{}", name.to_ascii_uppercase().replace('-', "_")).as_str())
+                .and_then(Zval::string)
+        };
+        Ok(resolve(remote_addr.as_deref(), header, &trusted, &trusted_headers)?.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, forwarded_hops, parse_node, resolve};
+    use crate::hostname::Cidr;
+    use std::collections::HashMap;
+    use std::net::IpAddr;
+
+    fn run(remote: &str, headers: &[(&str, &str)], names: &[&str]) -> String {
+        let trusted = Cidr::parse_list(&["10.0.0.0/8", "2001:db8::/32"]).unwrap();
+        let headers: HashMap<String, String> = headers
+            .iter()
+            .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+            .collect();
+        let names: Vec<String> = names.iter().map(|name| (*name).to_string()).collect();
+        resolve(
+            Some(remote),
+            |name| headers.get(name).cloned(),
+            &trusted,
+            &names,
+        )
+        .unwrap()
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_node() {
+        let ip = |s: &str| s.parse::<IpAddr>().ok();
+        assert_eq!(parse_node(" 192.0.2.1 "), ip("192.0.2.1"));
+        assert_eq!(parse_node("192.0.2.1:8080"), ip("192.0.2.1"));
+        assert_eq!(parse_node("[2001:db8::1]:443"), ip("2001:db8::1"));
+        assert_eq!(parse_node("::ffff:192.0.2.1"), ip("192.0.2.1"));
+        assert_eq!(parse_node("unknown"), None);
+        assert_eq!(parse_node("192.0.2.1:x"), None);
+    }
+
+    #[test]
+    fn test_untrusted_peer_ignores_headers() {
+        let xff = [("x-forwarded-for", "1.1.1.1")];
+        assert_eq!(
+            run("203.0.113.9", &xff, &["x-forwarded-for"]),
+            "203.0.113.9"
+        );
+    }
+
+    #[test]
+    fn test_walks_from_the_right() {
+        let names = ["x-forwarded-for"];
+        // The client prepended a spoofed entry; only the rightmost untrusted hop counts
+        let xff = [("x-forwarded-for", "1.1.1.1, 198.51.100.7, 10.0.0.2")];
+        assert_eq!(run("10.0.0.1", &xff, &names), "198.51.100.7");
+        // All hops trusted: the leftmost one is the client
+        let xff = [("x-forwarded-for", "10.1.1.1, 10.0.0.2")];
+        assert_eq!(run("10.0.0.1", &xff, &names), "10.1.1.1");
+        // Garbage stops the walk at the proxy that reported it
+        let xff = [("x-forwarded-for", "1.1.1.1, <script>")];
+        assert_eq!(run("10.0.0.1", &xff, &names), "10.0.0.1");
+        assert_eq!(run("10.0.0.1", &[], &names), "10.0.0.1");
+    }
+
+    #[test]
+    fn test_forwarded() {
+        let forwarded = [(
+            "forwarded",
+            "for=1.1.1.1, For=\"198.51.100.7:80\";by=x, for=\"[2001:db8:cafe::17]:4711\";proto=https",
+        )];
+        assert_eq!(
+            run("10.0.0.1", &forwarded, &["forwarded", "x-forwarded-for"]),
+            "198.51.100.7"
+        );
+        assert_eq!(forwarded_hops("for=unknown, by=x"), vec![None, None]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let trusted = Cidr::parse_list(&["10.0.0.0/8"]).unwrap();
+        assert!(matches!(
+            resolve(None, |_| None, &trusted, &[]),
+            Err(Error::MissingRemoteAddr)
+        ));
+        assert!(matches!(
+            resolve(Some("nope"), |_| None, &trusted, &[]),
+            Err(Error::InvalidRemoteAddr(_))
+        ));
+        assert!(matches!(
+            resolve(
+                Some("10.0.0.1"),
+                |_| None,
+                &trusted,
+                &["x forwarded".into()]
+            ),
+            Err(Error::InvalidHeader(_))
+        ));
+    }
+}
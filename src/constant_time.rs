@@ -0,0 +1,452 @@
+//! Side-channel-resistant comparison, selection and encoding.
+//!
+//! Comparisons take time that depends only on the lengths of their inputs, and the encoders
+//! use arithmetic instead of lookup tables, so secret bytes do not leak through cache
+//! timing. Decoders are strict: every byte string has exactly one accepted encoding.
+
+use ext_php_rs::binary::Binary;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use std::hint::black_box;
+use thiserror::Error;
+
+// Error codes for ConstantTime errors: 2200-2299
+pub mod error_codes {
+    pub const INVALID_ENCODING: i32 = 2200;
+    pub const LENGTH_MISMATCH: i32 = 2201;
+}
+
+/// Errors produced by `ConstantTime`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Invalid {0} input")]
+    InvalidEncoding(&'static str),
+
+    #[error("Both values must have the same length, got {0} and {1} bytes")]
+    LengthMismatch(usize, usize),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::InvalidEncoding(_) => error_codes::INVALID_ENCODING,
+            Error::LengthMismatch(..) => error_codes::LENGTH_MISMATCH,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for constant-time operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Compares two byte strings in time that depends only on their lengths.
+#[must_use]
+pub fn eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && black_box(a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y))) == 0
+}
+
+/// Returns `a` if `condition`, else `b`, without branching on `condition`.
+///
+/// # Errors
+/// - Returns `Error::LengthMismatch` if the inputs differ in length.
+pub fn select(condition: bool, a: &[u8], b: &[u8]) -> Result<Vec<u8>> {
+    if a.len() != b.len() {
+        return Err(Error::LengthMismatch(a.len(), b.len()));
+    }
+    let mask = black_box(0u8.wrapping_sub(u8::from(condition)));
+    Ok(a.iter()
+        .zip(b)
+        .map(|(x, y)| (x & mask) | (y & !mask))
+        .collect())
+}
+
+/// Lowercase hex digit of a nibble.
+fn encode_nibble(nibble: u8) -> char {
+    let n = i32::from(nibble);
+    // 'a' - 10 = 87; below 10, step down to '0' - 0 = 48 (87 - 39)
+    char::from((87 + n + (((n - 10) >> 8) & -39)) as u8)
+}
+
+/// Value of a lowercase hex digit, or a negative number.
+fn decode_nibble(c: u8) -> i32 {
+    let c = i32::from(c);
+    let digit = c - 48;
+    let letter = c - 87;
+    // -1 (all bits set) where the range check holds, 0 elsewhere
+    let is_digit = ((-1 - digit) & (digit - 10)) >> 8;
+    let is_letter = ((9 - letter) & (letter - 16)) >> 8;
+    ((digit & is_digit) | (letter & is_letter)) | !(is_digit | is_letter) << 8
+}
+
+/// Encodes bytes as lowercase hex.
+#[must_use]
+pub fn hex_encode(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .flat_map(|&byte| [encode_nibble(byte >> 4), encode_nibble(byte & 0x0F)])
+        .collect()
+}
+
+/// Decodes lowercase hex; uppercase digits are rejected so each value has one encoding.
+///
+/// # Errors
+/// - Returns `Error::InvalidEncoding` for an odd length or a character outside `0-9a-f`.
+pub fn hex_decode(input: &str) -> Result<Vec<u8>> {
+    let input = input.as_bytes();
+    if input.len() % 2 != 0 {
+        return Err(Error::InvalidEncoding("hex"));
+    }
+    let mut invalid = 0;
+    let bytes = input
+        .chunks_exact(2)
+        .map(|pair| {
+            let (high, low) = (decode_nibble(pair[0]), decode_nibble(pair[1]));
+            invalid |= high | low;
+            ((high << 4) | low) as u8
+        })
+        .collect();
+    if invalid < 0 {
+        return Err(Error::InvalidEncoding("hex"));
+    }
+    Ok(bytes)
+}
+
+/// The two base64 alphabets of RFC 4648.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Alphabet {
+    /// `+` and `/`, padded with `=`.
+    Standard,
+    /// `-` and `_`, unpadded.
+    UrlSafe,
+}
+
+impl Alphabet {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Standard => "base64",
+            Self::UrlSafe => "base64url",
+        }
+    }
+
+    /// Character for a 6-bit value.
+    fn encode(self, value: u8) -> char {
+        let v = i32::from(value);
+        // Start at 'A' and shift the offset at each range boundary
+        let mut offset = 65;
+        offset += ((25 - v) >> 8) & 6;
+        offset -= ((51 - v) >> 8) & 75;
+        offset -= ((61 - v) >> 8)
+            & match self {
+                Self::Standard => 15,
+                Self::UrlSafe => 13,
+            };
+        offset += ((62 - v) >> 8)
+            & match self {
+                Self::Standard => 3,
+                Self::UrlSafe => 49,
+            };
+        char::from((v + offset) as u8)
+    }
+
+    /// 6-bit value of a character, or -1.
+    fn decode(self, c: u8) -> i32 {
+        let c = i32::from(c);
+        let (c62, c63) = match self {
+            Self::Standard => (0x2B, 0x2F),
+            Self::UrlSafe => (0x2D, 0x5F),
+        };
+        let mut value = -1;
+        value += (((0x40 - c) & (c - 0x5B)) >> 8) & (c - 64);
+        value += (((0x60 - c) & (c - 0x7B)) >> 8) & (c - 70);
+        value += (((0x2F - c) & (c - 0x3A)) >> 8) & (c + 5);
+        value += (((c62 - 1 - c) & (c - c62 - 1)) >> 8) & 63;
+        value += (((c63 - 1 - c) & (c - c63 - 1)) >> 8) & 64;
+        value
+    }
+}
+
+fn base64_encode_with(bytes: &[u8], alphabet: Alphabet) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let symbols = [
+            b[0] >> 2,
+            ((b[0] & 0x03) << 4) | (b[1] >> 4),
+            ((b[1] & 0x0F) << 2) | (b[2] >> 6),
+            b[2] & 0x3F,
+        ];
+        for &symbol in &symbols[..=chunk.len()] {
+            out.push(alphabet.encode(symbol));
+        }
+        if alphabet == Alphabet::Standard {
+            for _ in chunk.len()..3 {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn base64_decode_with(input: &str, alphabet: Alphabet) -> Result<Vec<u8>> {
+    let invalid = || Error::InvalidEncoding(alphabet.name());
+    let mut input = input.as_bytes();
+    if alphabet == Alphabet::Standard {
+        if input.len() % 4 != 0 {
+            return Err(invalid());
+        }
+        // Padding depends only on the length of the data, not on its value
+        input = input.strip_suffix(b"=").unwrap_or(input);
+        input = input.strip_suffix(b"=").unwrap_or(input);
+    }
+    if input.len() % 4 == 1 {
+        return Err(invalid());
+    }
+    let mut error = 0;
+    let mut bytes = Vec::with_capacity(input.len() / 4 * 3 + 2);
+    for chunk in input.chunks(4) {
+        let mut values = [0i32; 4];
+        for (value, &c) in values.iter_mut().zip(chunk) {
+            *value = alphabet.decode(c);
+            error |= *value;
+        }
+        let [a, b, c, d] = values;
+        let decoded = [
+            ((a << 2) | (b >> 4)) as u8,
+            ((b << 4) | (c >> 2)) as u8,
+            ((c << 6) | d) as u8,
+        ];
+        bytes.extend_from_slice(&decoded[..chunk.len() - 1]);
+        // Unused low bits of the last symbol must be zero, or several inputs would decode
+        // to the same bytes
+        error |= match chunk.len() {
+            2 => -(b & 0x0F),
+            3 => -(c & 0x03),
+            _ => 0,
+        };
+    }
+    if error < 0 {
+        return Err(invalid());
+    }
+    Ok(bytes)
+}
+
+/// Encodes bytes as padded standard base64.
+#[must_use]
+pub fn base64_encode(bytes: &[u8]) -> String {
+    base64_encode_with(bytes, Alphabet::Standard)
+}
+
+/// Decodes padded standard base64.
+///
+/// # Errors
+/// - Returns `Error::InvalidEncoding` for missing or misplaced padding, characters outside
+///   the alphabet (including whitespace), or non-zero unused bits.
+pub fn base64_decode(input: &str) -> Result<Vec<u8>> {
+    base64_decode_with(input, Alphabet::Standard)
+}
+
+/// Encodes bytes as unpadded URL-safe base64.
+#[must_use]
+pub fn base64_url_encode(bytes: &[u8]) -> String {
+    base64_encode_with(bytes, Alphabet::UrlSafe)
+}
+
+/// Decodes unpadded URL-safe base64.
+///
+/// # Errors
+/// - Returns `Error::InvalidEncoding` for padding, characters outside the alphabet,
+///   an impossible length, or non-zero unused bits.
+pub fn base64_url_decode(input: &str) -> Result<Vec<u8>> {
+    base64_decode_with(input, Alphabet::UrlSafe)
+}
+
+/// Constant-time comparison, selection and strict encoding helpers.
+#[php_class]
+#[php(name = "Hardened\\ConstantTime")]
+pub struct ConstantTime {}
+
+#[php_impl]
+impl ConstantTime {
+    /// Compare two strings in time that depends only on their lengths, e.g. MACs or tokens.
+    ///
+    /// # Parameters
+    /// - `a`: `string` Known value.
+    /// - `b`: `string` Untrusted value.
+    ///
+    /// # Returns
+    /// - `bool` `true` if they are equal.
+    fn equals(a: Binary<u8>, b: Binary<u8>) -> bool {
+        eq(&a, &b)
+    }
+
+    /// Return `a` if `condition` is true, else `b`, without a secret-dependent branch.
+    ///
+    /// # Parameters
+    /// - `condition`: `bool` The selector.
+    /// - `a`: `string` Returned if `condition` is true.
+    /// - `b`: `string` Returned otherwise; must have the same length as `a`.
+    ///
+    /// # Returns
+    /// - `string` One of the inputs.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `a` and `b` differ in length.
+    fn select(condition: bool, a: Binary<u8>, b: Binary<u8>) -> Result<Binary<u8>> {
+        select(condition, &a, &b).map(Binary::from)
+    }
+
+    /// Encode bytes as lowercase hex without table lookups.
+    ///
+    /// # Parameters
+    /// - `bytes`: `string` Binary data.
+    ///
+    /// # Returns
+    /// - `string` Hex digits.
+    fn hex_encode(bytes: Binary<u8>) -> String {
+        hex_encode(&bytes)
+    }
+
+    /// Decode lowercase hex; uppercase, whitespace and odd lengths are rejected.
+    ///
+    /// # Parameters
+    /// - `hex`: `string` Hex digits.
+    ///
+    /// # Returns
+    /// - `string` Binary data.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the input is not canonical lowercase hex.
+    fn hex_decode(hex: &str) -> Result<Binary<u8>> {
+        hex_decode(hex).map(Binary::from)
+    }
+
+    /// Encode bytes as padded standard base64 without table lookups.
+    ///
+    /// # Parameters
+    /// - `bytes`: `string` Binary data.
+    ///
+    /// # Returns
+    /// - `string` Base64 text.
+    fn base64_encode(bytes: Binary<u8>) -> String {
+        base64_encode(&bytes)
+    }
+
+    /// Decode padded standard base64, rejecting anything but the canonical encoding.
+    ///
+    /// # Parameters
+    /// - `base64`: `string` Base64 text.
+    ///
+    /// # Returns
+    /// - `string` Binary data.
+    ///
+    /// # Exceptions
+    /// - Throws an exception on bad padding, whitespace, foreign characters or non-zero
+    ///   unused bits.
+    fn base64_decode(base64: &str) -> Result<Binary<u8>> {
+        base64_decode(base64).map(Binary::from)
+    }
+
+    /// Encode bytes as unpadded URL-safe base64 without table lookups.
+    ///
+    /// # Parameters
+    /// - `bytes`: `string` Binary data.
+    ///
+    /// # Returns
+    /// - `string` Base64url text.
+    fn base64_url_encode(bytes: Binary<u8>) -> String {
+        base64_url_encode(&bytes)
+    }
+
+    /// Decode unpadded URL-safe base64, rejecting anything but the canonical encoding.
+    ///
+    /// # Parameters
+    /// - `base64Url`: `string` Base64url text.
+    ///
+    /// # Returns
+    /// - `string` Binary data.
+    ///
+    /// # Exceptions
+    /// - Throws an exception on padding, foreign characters, an impossible length or
+    ///   non-zero unused bits.
+    fn base64_url_decode(base64_url: &str) -> Result<Binary<u8>> {
+        base64_url_decode(base64_url).map(Binary::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Error, base64_decode, base64_encode, base64_url_decode, base64_url_encode, eq, hex_decode,
+        hex_encode, select,
+    };
+
+    #[test]
+    fn test_eq_and_select() {
+        assert!(eq(b"secret", b"secret"));
+        assert!(!eq(b"secret", b"secreT"));
+        assert!(!eq(b"secret", b"secre"));
+        assert!(eq(b"", b""));
+
+        assert_eq!(select(true, b"aa", b"bb").unwrap(), b"aa");
+        assert_eq!(select(false, b"aa", b"bb").unwrap(), b"bb");
+        assert!(matches!(
+            select(true, b"a", b"bb"),
+            Err(Error::LengthMismatch(1, 2))
+        ));
+    }
+
+    #[test]
+    fn test_hex() {
+        let all: Vec<u8> = (0..=255).collect();
+        let hex = hex_encode(&all);
+        assert!(hex.starts_with("000102030405060708090a0b"));
+        assert!(hex.ends_with("fdfeff"));
+        assert_eq!(hex_decode(&hex).unwrap(), all);
+        for bad in ["0", "0g", "0A", "AB", " 0", "0/", "0:", "0`"] {
+            assert!(hex_decode(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_base64() {
+        for (bytes, standard, url) in [
+            (&b""[..], "", ""),
+            (b"f", "Zg==", "Zg"),
+            (b"fo", "Zm8=", "Zm8"),
+            (b"foo", "Zm9v", "Zm9v"),
+            (b"foob", "Zm9vYg==", "Zm9vYg"),
+            (b"\xfb\xff\xbf", "+/+/", "-_-_"),
+        ] {
+            assert_eq!(base64_encode(bytes), standard);
+            assert_eq!(base64_decode(standard).unwrap(), bytes);
+            assert_eq!(base64_url_encode(bytes), url);
+            assert_eq!(base64_url_decode(url).unwrap(), bytes);
+        }
+        let all: Vec<u8> = (0..=255).collect();
+        assert_eq!(base64_decode(&base64_encode(&all)).unwrap(), all);
+        assert_eq!(base64_url_decode(&base64_url_encode(&all)).unwrap(), all);
+
+        // Non-canonical or malformed input
+        for bad in [
+            "Zh==", "Zm9=", "Zg", "Zg=", "Z===", "Zm 9v", "Zm9v\n", "-_-_", "Zg=\0",
+        ] {
+            assert!(base64_decode(bad).is_err(), "{bad}");
+        }
+        for bad in ["Zh", "Zm-", "Zg==", "+/+/", "Z", "Zm9v\n"] {
+            assert!(base64_url_decode(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,334 @@
+//! Tamper-proof cookie values: signed with HMAC-SHA256, or encrypted with `Crypto\SecretBox`,
+//! under a rotatable key ring.
+//!
+//! Each value is bound to its cookie name, so it cannot be replayed under another cookie, and
+//! carries its own expiry, which holds even if the client keeps the cookie longer than asked.
+//! Layout before Base64URL encoding: version byte, mode byte (`s`igned or `e`ncrypted), then
+//! either `expires ‖ value ‖ tag` or a `SecretBox` ciphertext of `expires ‖ value`.
+
+use crate::crypto::kdf::{Algorithm, hkdf};
+use crate::crypto::secret_box::SecretBox;
+use crate::crypto::{self, decode_key, generate_key, keys_arg};
+use crate::security_headers::{self, set_cookie::SetCookie};
+use data_encoding::{BASE64URL, BASE64URL_NOPAD};
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::types::Zval;
+use ext_php_rs::zend::{ProcessGlobals, ce};
+use ext_php_rs::{php_class, php_impl};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+// Error codes for CookieGuard errors: 3100-3199
+pub mod error_codes {
+    pub const INVALID_TTL: i32 = 3100;
+}
+
+/// Errors produced by `CookieGuard`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("TTL must be a positive number of seconds, got {0}")]
+    InvalidTtl(i64),
+
+    #[error(transparent)]
+    Crypto(#[from] crypto::Error),
+
+    #[error(transparent)]
+    SetCookie(#[from] security_headers::Error),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::InvalidTtl(_) => error_codes::INVALID_TTL,
+            Error::Crypto(err) => err.code(),
+            Error::SetCookie(err) => err.code(),
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for cookie guard operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+const VERSION: u8 = 1;
+const MODE_SIGNED: u8 = b's';
+const MODE_ENCRYPTED: u8 = b'e';
+const EXPIRES_LEN: usize = 8;
+const TAG_LEN: usize = 32;
+
+/// Current UNIX time in seconds.
+fn unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+/// Signs, or encrypts, cookie values and checks them on the way back in.
+#[php_class]
+#[php(name = "Hardened\\CookieGuard")]
+pub struct CookieGuard {
+    /// Per-key HMAC keys, the first one signing.
+    macs: Vec<[u8; 32]>,
+    /// `Some` in encrypting mode.
+    secret_box: Option<SecretBox>,
+}
+
+impl CookieGuard {
+    /// Backs `__construct()`. Signing and encryption keys are derived separately from each key,
+    /// so the same key can safely be shared with other `Hardened\Crypto` classes.
+    fn _new<'a>(keys: impl IntoIterator<Item = &'a str>, encrypt: bool) -> Result<Self> {
+        let mut macs = Vec::new();
+        let mut encryption_keys = Vec::new();
+        for key in keys {
+            let key = decode_key(key)?;
+            let derive = |context: &[u8]| -> Result<[u8; 32]> {
+                let derived = hkdf(Algorithm::Sha256, &key, None, context, 32)?;
+                Ok(<[u8; 32]>::try_from(derived.as_slice()).expect("32-byte HKDF output"))
+            };
+            macs.push(derive(b"hardened-cookie-guard-v1:sign")?);
+            encryption_keys.push(derive(b"hardened-cookie-guard-v1:encrypt")?);
+        }
+        if macs.is_empty() {
+            return Err(crypto::Error::NoKeys.into());
+        }
+        let secret_box = if encrypt {
+            Some(SecretBox::_from_keys(&encryption_keys)?)
+        } else {
+            None
+        };
+        Ok(Self { macs, secret_box })
+    }
+
+    /// HMAC over the header, the cookie name and the body.
+    fn _tag(key: &[u8; 32], header: &[u8], name: &str, body: &[u8]) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+        mac.update(header);
+        mac.update(name.as_bytes());
+        mac.update(&[0]);
+        mac.update(body);
+        mac
+    }
+
+    /// Protects `value`; `expires` of 0 means no expiry of its own.
+    fn _seal(&self, name: &str, value: &[u8], expires: i64) -> Result<String> {
+        let mut body = expires.to_be_bytes().to_vec();
+        body.extend_from_slice(value);
+        let mut output = vec![VERSION];
+        if let Some(secret_box) = &self.secret_box {
+            output.push(MODE_ENCRYPTED);
+            let aad = [output.as_slice(), name.as_bytes()].concat();
+            output.extend(secret_box._seal(&body, &aad)?);
+        } else {
+            output.push(MODE_SIGNED);
+            let tag = Self::_tag(&self.macs[0], &output, name, &body).finalize();
+            output.extend(body);
+            output.extend_from_slice(&tag.into_bytes());
+        }
+        Ok(BASE64URL_NOPAD.encode(&output))
+    }
+
+    /// Checks a protected value as of `now`; `None` for anything forged, expired, made for
+    /// another cookie or in the other mode.
+    fn _open(&self, name: &str, sealed: &str, now: i64) -> Option<Vec<u8>> {
+        let raw = BASE64URL_NOPAD
+            .decode(sealed.as_bytes())
+            .or_else(|_| BASE64URL.decode(sealed.as_bytes()))
+            .ok()?;
+        if raw.len() < 2 || raw[0] != VERSION {
+            return None;
+        }
+        let (header, rest) = raw.split_at(2);
+        let body = match (header[1], &self.secret_box) {
+            (MODE_ENCRYPTED, Some(secret_box)) => secret_box
+                ._open(rest, &[header, name.as_bytes()].concat())
+                .ok()?,
+            (MODE_SIGNED, None) => {
+                let (body, tag) = rest.split_at(rest.len().checked_sub(TAG_LEN)?);
+                // Old keys stay valid until they are removed from the ring
+                self.macs
+                    .iter()
+                    .any(|key| {
+                        Self::_tag(key, header, name, body)
+                            .verify_slice(tag)
+                            .is_ok()
+                    })
+                    .then(|| body.to_vec())?
+            }
+            _ => return None,
+        };
+        if body.len() < EXPIRES_LEN {
+            return None;
+        }
+        let (expires, value) = body.split_at(EXPIRES_LEN);
+        let expires = i64::from_be_bytes(expires.try_into().ok()?);
+        if expires != 0 && now > expires {
+            return None;
+        }
+        Some(value.to_vec())
+    }
+
+    /// Expiry timestamp for a TTL.
+    fn _expires(ttl: Option<i64>, now: i64) -> Result<i64> {
+        match ttl {
+            None => Ok(0),
+            Some(ttl) if ttl > 0 => Ok(now.saturating_add(ttl)),
+            Some(ttl) => Err(Error::InvalidTtl(ttl)),
+        }
+    }
+}
+
+#[php_impl]
+impl CookieGuard {
+    /// Constructs a guard from one key or a key ring.
+    ///
+    /// # Parameters
+    /// - `keys`: `string|array` Base64URL-encoded 32-byte key, or a list of them. The first
+    ///   protects new cookies, all of them are accepted on read.
+    /// - `encrypt`: `?bool` Encrypt values instead of only signing them. Default: `true`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if no key is given or a key is not 32 bytes of Base64URL.
+    fn __construct(keys: &Zval, encrypt: Option<bool>) -> Result<Self> {
+        Self::_new(
+            keys_arg(keys)?.iter().map(String::as_str),
+            encrypt.unwrap_or(true),
+        )
+    }
+
+    /// Generate a new random key.
+    ///
+    /// # Returns
+    /// - `string` Base64URL-encoded 32-byte key.
+    fn generate_key() -> String {
+        generate_key()
+    }
+
+    /// Protect a cookie value.
+    ///
+    /// # Parameters
+    /// - `name`: `string` Cookie name the value is bound to, including any `__Host-` prefix.
+    /// - `value`: `string` The value.
+    /// - `ttl`: `?int` Seconds the value stays valid, enforced on read. Default: no limit.
+    ///
+    /// # Returns
+    /// - `string` Cookie-safe protected value.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `ttl` is not positive or encryption fails.
+    fn seal(&self, name: &str, value: &str, ttl: Option<i64>) -> Result<String> {
+        let now = unix_time();
+        self._seal(name, value.as_bytes(), Self::_expires(ttl, now)?)
+    }
+
+    /// Check a protected value.
+    ///
+    /// # Parameters
+    /// - `name`: `string` Cookie name given to `seal()`.
+    /// - `sealed`: `string` The protected value.
+    ///
+    /// # Returns
+    /// - `?string` The original value, or `null` if it was tampered with, has expired, was made
+    ///   for another cookie or with an unknown key.
+    fn open(&self, name: &str, sealed: &str) -> Option<String> {
+        self._open(name, sealed, unix_time())
+            .and_then(|value| String::from_utf8(value).ok())
+    }
+
+    /// Read and check a cookie from `$_COOKIE`.
+    ///
+    /// # Parameters
+    /// - `name`: `string` Cookie name.
+    ///
+    /// # Returns
+    /// - `?string` The original value, or `null` if the cookie is missing or invalid.
+    fn get(&self, name: &str) -> Option<String> {
+        let sealed = ProcessGlobals::get()
+            .http_cookie_vars()
+            .get(name)
+            .and_then(Zval::string)?;
+        self.open(name, &sealed)
+    }
+
+    /// Build a `SetCookie` carrying a protected value, to adjust and `send()`.
+    ///
+    /// # Parameters
+    /// - `name`: `string` Cookie name.
+    /// - `value`: `string` The value.
+    /// - `ttl`: `?int` Lifetime in seconds, used for both `Max-Age` and the embedded expiry.
+    ///   Default: a session cookie with no embedded expiry.
+    ///
+    /// # Returns
+    /// - `Hardened\SecurityHeaders\SetCookie` with the secure defaults.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the name is not a valid cookie name or `ttl` is out of range.
+    fn cookie(&self, name: &str, value: &str, ttl: Option<i64>) -> Result<SetCookie> {
+        let now = unix_time();
+        let sealed = self._seal(name, value.as_bytes(), Self::_expires(ttl, now)?)?;
+        let mut cookie = SetCookie::_new(name, &sealed)?;
+        cookie._max_age(ttl.and_then(|ttl| u64::try_from(ttl).ok()))?;
+        Ok(cookie)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CookieGuard, Error};
+    use crate::crypto::generate_key;
+
+    #[test]
+    fn test_signed() {
+        let key = generate_key();
+        let guard = CookieGuard::_new([key.as_str()], false).unwrap();
+        let sealed = guard._seal("prefs", b"theme=dark", 1000).unwrap();
+        assert_eq!(guard._open("prefs", &sealed, 1000).unwrap(), b"theme=dark");
+        assert!(guard._open("prefs", &sealed, 1001).is_none());
+        assert!(guard._open("session", &sealed, 1000).is_none());
+
+        let mut tampered = sealed.into_bytes();
+        tampered[4] = if tampered[4] == b'A' { b'B' } else { b'A' };
+        assert!(
+            guard
+                ._open("prefs", &String::from_utf8(tampered).unwrap(), 1000)
+                .is_none()
+        );
+
+        // Encrypting and signing guards do not accept each other's cookies
+        let encrypting = CookieGuard::_new([key.as_str()], true).unwrap();
+        let sealed = guard._seal("prefs", b"x", 0).unwrap();
+        assert!(encrypting._open("prefs", &sealed, 1000).is_none());
+        assert!(guard._open("prefs", &sealed, i64::MAX).is_some());
+    }
+
+    #[test]
+    fn test_encrypted_rotation() {
+        let (old, new) = (generate_key(), generate_key());
+        let before = CookieGuard::_new([old.as_str()], true).unwrap();
+        let sealed = before._seal("__Host-cart", b"42,43", 0).unwrap();
+        assert!(!sealed.contains("42"));
+
+        let after = CookieGuard::_new([new.as_str(), old.as_str()], true).unwrap();
+        assert_eq!(after._open("__Host-cart", &sealed, 0).unwrap(), b"42,43");
+        assert!(after._open("__Host-other", &sealed, 0).is_none());
+        let retired = CookieGuard::_new([new.as_str()], true).unwrap();
+        assert!(retired._open("__Host-cart", &sealed, 0).is_none());
+
+        assert!(matches!(
+            CookieGuard::_expires(Some(0), 0),
+            Err(Error::InvalidTtl(0))
+        ));
+        assert!(CookieGuard::_new(Vec::<&str>::new(), false).is_err());
+    }
+}
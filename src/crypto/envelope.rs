@@ -0,0 +1,299 @@
+//! Envelope encryption: every message gets its own random data key, and only that data key is
+//! encrypted with the master key.
+//!
+//! Rotating the master key then means re-wrapping 77-byte data keys rather than re-encrypting
+//! the data, and a leaked data key exposes a single record. Wrapped keys are `SecretBox`
+//! ciphertexts made under the master key ring, with their own associated-data label so they
+//! cannot be confused with ordinary `SecretBox` output of the same key.
+
+use super::secret_box::{OVERHEAD, SecretBox};
+use super::{Error, Result, decode_base64url, keys_arg};
+use data_encoding::BASE64URL;
+use ext_php_rs::binary::Binary;
+use ext_php_rs::types::Zval;
+use ext_php_rs::{php_class, php_impl};
+use std::collections::HashMap;
+
+const VERSION: u8 = 1;
+/// Length of a wrapped 32-byte data key.
+const WRAPPED_LEN: usize = OVERHEAD + 32;
+const WRAP_LABEL: &[u8] = b"hardened-envelope-key-v1\0";
+
+/// Envelope encryption under a rotatable master key ring.
+#[php_class]
+#[php(name = "Hardened\\Crypto\\Envelope")]
+pub struct Envelope {
+    master: SecretBox,
+}
+
+impl Envelope {
+    /// Backs `__construct()`.
+    fn _new<'a>(master_keys: impl IntoIterator<Item = &'a str>) -> Result<Self> {
+        Ok(Self {
+            master: SecretBox::_new(master_keys)?,
+        })
+    }
+
+    /// Wraps a data key under the primary master key.
+    fn _wrap(&self, data_key: &[u8; 32], context: &[u8]) -> Result<Vec<u8>> {
+        self.master._seal(data_key, &[WRAP_LABEL, context].concat())
+    }
+
+    /// Unwraps a data key with whichever master key made it.
+    fn _unwrap(&self, wrapped: &[u8], context: &[u8]) -> Result<[u8; 32]> {
+        if wrapped.len() != WRAPPED_LEN {
+            return Err(Error::Malformed);
+        }
+        let data_key = self
+            .master
+            ._open(wrapped, &[WRAP_LABEL, context].concat())?;
+        <[u8; 32]>::try_from(data_key.as_slice()).map_err(|_| Error::Malformed)
+    }
+
+    /// Encrypts with a fresh data key; returns version ‖ wrapped key ‖ `SecretBox` ciphertext.
+    fn _seal(&self, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        let data_key = rand::random::<[u8; 32]>();
+        let mut output = vec![VERSION];
+        output.extend(self._wrap(&data_key, aad)?);
+        output.extend(SecretBox::_from_keys(&[data_key])?._seal(plaintext, aad)?);
+        Ok(output)
+    }
+
+    /// Splits an envelope into the wrapped key and the data ciphertext.
+    fn _split(sealed: &[u8]) -> Result<(&[u8], &[u8])> {
+        let Some((&version, rest)) = sealed.split_first() else {
+            return Err(Error::Malformed);
+        };
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        if rest.len() < WRAPPED_LEN + OVERHEAD {
+            return Err(Error::Malformed);
+        }
+        Ok(rest.split_at(WRAPPED_LEN))
+    }
+
+    /// Decrypts an envelope.
+    fn _open(&self, sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        let (wrapped, ciphertext) = Self::_split(sealed)?;
+        let data_key = self._unwrap(wrapped, aad)?;
+        SecretBox::_from_keys(&[data_key])?._open(ciphertext, aad)
+    }
+
+    /// Re-wraps the data key of an envelope under the primary master key, leaving the data
+    /// ciphertext untouched.
+    fn _rewrap(&self, sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        let (wrapped, ciphertext) = Self::_split(sealed)?;
+        let data_key = self._unwrap(wrapped, aad)?;
+        let mut output = vec![VERSION];
+        output.extend(self._wrap(&data_key, aad)?);
+        output.extend_from_slice(ciphertext);
+        Ok(output)
+    }
+}
+
+#[php_impl]
+impl Envelope {
+    /// Constructs an envelope from one master key or a master key ring.
+    ///
+    /// # Parameters
+    /// - `masterKeys`: `string|array` Base64URL-encoded 32-byte key, or a list of them. The
+    ///   first wraps new data keys, all of them unwrap.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if no key is given or a key is not 32 bytes of Base64URL.
+    fn __construct(master_keys: &Zval) -> Result<Self> {
+        Self::_new(keys_arg(master_keys)?.iter().map(String::as_str))
+    }
+
+    /// Encrypt a message under a new data key.
+    ///
+    /// # Parameters
+    /// - `plaintext`: `string` The message.
+    /// - `aad`: `?string` Associated data, authenticated but not encrypted. Default: `''`.
+    ///
+    /// # Returns
+    /// - `string` Base64URL-encoded envelope holding the wrapped data key and the ciphertext.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if encryption fails.
+    fn encrypt(&self, plaintext: Binary<u8>, aad: Option<Binary<u8>>) -> Result<String> {
+        let aad = aad.map(Vec::from).unwrap_or_default();
+        Ok(BASE64URL.encode(&self._seal(&plaintext, &aad)?))
+    }
+
+    /// Decrypt an envelope.
+    ///
+    /// # Parameters
+    /// - `envelope`: `string` Output of `encrypt()` or `rewrap()`.
+    /// - `aad`: `?string` The associated data given to `encrypt()`. Default: `''`.
+    ///
+    /// # Returns
+    /// - `string` The plaintext.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the envelope is malformed, its master key is not configured, or
+    ///   it fails authentication.
+    fn decrypt(&self, envelope: &str, aad: Option<Binary<u8>>) -> Result<Binary<u8>> {
+        let aad = aad.map(Vec::from).unwrap_or_default();
+        Ok(Binary::from(
+            self._open(&decode_base64url(envelope)?, &aad)?,
+        ))
+    }
+
+    /// Re-wrap an envelope's data key under the current master key without re-encrypting
+    /// the data.
+    ///
+    /// # Parameters
+    /// - `envelope`: `string` Output of `encrypt()`.
+    /// - `aad`: `?string` The associated data given to `encrypt()`. Default: `''`.
+    ///
+    /// # Returns
+    /// - `string` The updated envelope.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the envelope cannot be unwrapped.
+    fn rewrap(&self, envelope: &str, aad: Option<Binary<u8>>) -> Result<String> {
+        let aad = aad.map(Vec::from).unwrap_or_default();
+        Ok(BASE64URL.encode(&self._rewrap(&decode_base64url(envelope)?, &aad)?))
+    }
+
+    /// Whether an envelope's data key is wrapped with a master key other than the first.
+    ///
+    /// # Parameters
+    /// - `envelope`: `string` Output of `encrypt()`.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the envelope should be passed to `rewrap()`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the envelope is malformed or its master key is not configured.
+    fn needs_rewrap(&self, envelope: &str) -> Result<bool> {
+        let (wrapped, _) = Self::_split(&decode_base64url(envelope)?)?;
+        self.master._needs_reencrypt(wrapped)
+    }
+
+    /// Generate a data key for use outside of `encrypt()`, e.g. with `SecretBox`.
+    ///
+    /// # Parameters
+    /// - `context`: `?string` Label the wrapped key is bound to. Default: `''`.
+    ///
+    /// # Returns
+    /// - `array` `key` (Base64URL-encoded data key, keep in memory only) and `wrapped`
+    ///   (Base64URL-encoded wrapped key, to store).
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if wrapping fails.
+    fn generate_data_key(
+        &self,
+        context: Option<Binary<u8>>,
+    ) -> Result<HashMap<&'static str, String>> {
+        let context = context.map(Vec::from).unwrap_or_default();
+        let data_key = rand::random::<[u8; 32]>();
+        let wrapped = self._wrap(&data_key, &context)?;
+        Ok(HashMap::from([
+            ("key", BASE64URL.encode(&data_key)),
+            ("wrapped", BASE64URL.encode(&wrapped)),
+        ]))
+    }
+
+    /// Unwrap a data key made by `generateDataKey()`.
+    ///
+    /// # Parameters
+    /// - `wrapped`: `string` The `wrapped` value.
+    /// - `context`: `?string` The context given to `generateDataKey()`. Default: `''`.
+    ///
+    /// # Returns
+    /// - `string` Base64URL-encoded data key.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the wrapped key is malformed, its master key is not
+    ///   configured, or it fails authentication.
+    fn unwrap_key(&self, wrapped: &str, context: Option<Binary<u8>>) -> Result<String> {
+        let context = context.map(Vec::from).unwrap_or_default();
+        let data_key = self._unwrap(&decode_base64url(wrapped)?, &context)?;
+        Ok(BASE64URL.encode(&data_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Error, generate_key};
+    use super::{Envelope, WRAPPED_LEN};
+
+    #[test]
+    fn test_round_trip() {
+        let key = generate_key();
+        let envelope = Envelope::_new([key.as_str()]).unwrap();
+        let sealed = envelope._seal(b"medical record", b"patients:7").unwrap();
+        assert_eq!(
+            envelope._open(&sealed, b"patients:7").unwrap(),
+            b"medical record"
+        );
+        assert!(matches!(
+            envelope._open(&sealed, b"patients:8"),
+            Err(Error::DecryptionFailed)
+        ));
+
+        // Data keys differ per message
+        let other = envelope._seal(b"medical record", b"patients:7").unwrap();
+        assert_ne!(sealed[1..=WRAPPED_LEN], other[1..=WRAPPED_LEN]);
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            envelope._open(&tampered, b"patients:7"),
+            Err(Error::DecryptionFailed)
+        ));
+        assert!(matches!(
+            envelope._open(&sealed[..WRAPPED_LEN], b"patients:7"),
+            Err(Error::Malformed)
+        ));
+    }
+
+    #[test]
+    fn test_rewrap() {
+        let (old, new) = (generate_key(), generate_key());
+        let before = Envelope::_new([old.as_str()]).unwrap();
+        let sealed = before._seal(b"data", b"").unwrap();
+
+        let after = Envelope::_new([new.as_str(), old.as_str()]).unwrap();
+        let rewrapped = after._rewrap(&sealed, b"").unwrap();
+        // Only the wrapped key changes
+        assert_eq!(sealed[1 + WRAPPED_LEN..], rewrapped[1 + WRAPPED_LEN..]);
+        assert_eq!(after._open(&rewrapped, b"").unwrap(), b"data");
+        assert!(
+            after
+                .master
+                ._needs_reencrypt(&sealed[1..=WRAPPED_LEN])
+                .unwrap()
+        );
+        assert!(
+            !after
+                .master
+                ._needs_reencrypt(&rewrapped[1..=WRAPPED_LEN])
+                .unwrap()
+        );
+
+        let retired = Envelope::_new([new.as_str()]).unwrap();
+        assert_eq!(retired._open(&rewrapped, b"").unwrap(), b"data");
+        assert!(matches!(
+            retired._open(&sealed, b""),
+            Err(Error::UnknownKey)
+        ));
+    }
+
+    #[test]
+    fn test_wrap_is_domain_separated() {
+        let key = generate_key();
+        let envelope = Envelope::_new([key.as_str()]).unwrap();
+        // A SecretBox ciphertext of 32 bytes under the master key is not a wrapped key
+        let forged = envelope.master._seal(&[7; 32], b"").unwrap();
+        assert!(matches!(
+            envelope._unwrap(&forged, b""),
+            Err(Error::DecryptionFailed)
+        ));
+        let wrapped = envelope._wrap(&[7; 32], b"ctx").unwrap();
+        assert_eq!(envelope._unwrap(&wrapped, b"ctx").unwrap(), [7; 32]);
+    }
+}
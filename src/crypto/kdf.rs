@@ -0,0 +1,251 @@
+//! Key derivation: HKDF for splitting a high-entropy key into per-purpose keys, and PBKDF2 for
+//! turning a password into a key.
+//!
+//! For storing password hashes use `Hardened\Password` instead; PBKDF2 is here for when a
+//! password has to become an encryption key or interoperate with an existing format.
+
+use super::{Error, Result, decode_key};
+use data_encoding::BASE64URL;
+use ext_php_rs::binary::Binary;
+use ext_php_rs::{php_class, php_impl};
+use hkdf::Hkdf;
+use sha2::{Sha256, Sha512};
+use std::str::FromStr;
+use strum::{Display, EnumString};
+
+/// Fewest PBKDF2 iterations accepted.
+const MIN_ITERATIONS: u32 = 100_000;
+/// Default PBKDF2 iterations, the OWASP recommendation for PBKDF2-HMAC-SHA256.
+const DEFAULT_ITERATIONS: u32 = 600_000;
+const MIN_SALT_LEN: usize = 16;
+/// Longest PBKDF2 output; anything longer only costs the defender time.
+const MAX_PBKDF2_LEN: usize = 64;
+const DEFAULT_LEN: usize = 32;
+
+/// Hash function behind HKDF or PBKDF2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Algorithm {
+    Sha256,
+    Sha512,
+}
+
+impl Algorithm {
+    fn parse(algorithm: Option<String>) -> Result<Self> {
+        match algorithm {
+            None => Ok(Algorithm::Sha256),
+            Some(algorithm) => Algorithm::from_str(algorithm.trim())
+                .map_err(|_| Error::UnsupportedAlgorithm(algorithm)),
+        }
+    }
+}
+
+/// Checks a requested output length and converts it.
+fn output_len(length: Option<i64>, max: usize) -> Result<usize> {
+    let Some(length) = length else {
+        return Ok(DEFAULT_LEN);
+    };
+    usize::try_from(length)
+        .ok()
+        .filter(|length| (1..=max).contains(length))
+        .ok_or(Error::InvalidLength(length))
+}
+
+/// HKDF (RFC 5869) extract-and-expand.
+///
+/// # Errors
+/// - Returns `Error::EmptyContext` if `context` is empty.
+/// - Returns `Error::InvalidLength` if `length` exceeds 255 hash blocks.
+pub fn hkdf(
+    algorithm: Algorithm,
+    key: &[u8],
+    salt: Option<&[u8]>,
+    context: &[u8],
+    length: usize,
+) -> Result<Vec<u8>> {
+    if context.is_empty() {
+        return Err(Error::EmptyContext);
+    }
+    let mut output = vec![0u8; length];
+    let expanded = match algorithm {
+        Algorithm::Sha256 => Hkdf::<Sha256>::new(salt, key).expand(context, &mut output),
+        Algorithm::Sha512 => Hkdf::<Sha512>::new(salt, key).expand(context, &mut output),
+    };
+    expanded.map_err(|_| Error::InvalidLength(i64::try_from(length).unwrap_or(i64::MAX)))?;
+    Ok(output)
+}
+
+/// PBKDF2-HMAC (RFC 8018) without any policy checks.
+#[must_use]
+pub fn pbkdf2(
+    algorithm: Algorithm,
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    length: usize,
+) -> Vec<u8> {
+    let mut output = vec![0u8; length];
+    match algorithm {
+        Algorithm::Sha256 => pbkdf2::pbkdf2_hmac::<Sha256>(password, salt, iterations, &mut output),
+        Algorithm::Sha512 => pbkdf2::pbkdf2_hmac::<Sha512>(password, salt, iterations, &mut output),
+    }
+    output
+}
+
+/// Key derivation functions.
+#[php_class]
+#[php(name = "Hardened\\Crypto\\Kdf")]
+pub struct Kdf {}
+
+#[php_impl]
+impl Kdf {
+    /// Derive key material from a high-entropy key with HKDF.
+    ///
+    /// # Parameters
+    /// - `key`: `string` Input key material (raw bytes). Not suitable for passwords.
+    /// - `context`: `string` Label saying what the output is for, e.g. `"invoices:encryption"`.
+    ///   Different labels give independent keys.
+    /// - `length`: `?int` Output length in bytes. Default: 32.
+    /// - `salt`: `?string` Optional salt. Default: none.
+    /// - `algorithm`: `?string` `"sha256"` or `"sha512"`. Default: `"sha256"`.
+    ///
+    /// # Returns
+    /// - `string` Raw derived bytes.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the context is empty, the length is out of range or the
+    ///   algorithm is unknown.
+    fn hkdf(
+        key: Binary<u8>,
+        context: Binary<u8>,
+        length: Option<i64>,
+        salt: Option<Binary<u8>>,
+        algorithm: Option<String>,
+    ) -> Result<Binary<u8>> {
+        let algorithm = Algorithm::parse(algorithm)?;
+        let length = output_len(length, 255 * 64)?;
+        Ok(Binary::from(hkdf(
+            algorithm,
+            &key,
+            salt.as_deref().map(Vec::as_slice),
+            &context,
+            length,
+        )?))
+    }
+
+    /// Derive a per-purpose key from a master key.
+    ///
+    /// # Parameters
+    /// - `masterKey`: `string` Base64URL-encoded 32-byte key.
+    /// - `context`: `string` Label for the derived key, e.g. `"users:42"`.
+    ///
+    /// # Returns
+    /// - `string` Base64URL-encoded 32-byte key, usable with `SecretBox`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the master key is invalid or the context is empty.
+    fn derive_key(master_key: &str, context: Binary<u8>) -> Result<String> {
+        let master_key = decode_key(master_key)?;
+        let key = hkdf(Algorithm::Sha256, &master_key, None, &context, 32)?;
+        Ok(BASE64URL.encode(&key))
+    }
+
+    /// Derive a key from a password with PBKDF2-HMAC.
+    ///
+    /// # Parameters
+    /// - `password`: `string` The password.
+    /// - `salt`: `string` Random salt of at least 16 bytes, stored alongside the result.
+    /// - `iterations`: `?int` Iteration count, at least 100000. Default: 600000.
+    /// - `length`: `?int` Output length in bytes, at most 64. Default: 32.
+    /// - `algorithm`: `?string` `"sha256"` or `"sha512"`. Default: `"sha256"`.
+    ///
+    /// # Returns
+    /// - `string` Raw derived bytes.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the salt is too short, the iteration count too low, the length
+    ///   out of range or the algorithm unknown.
+    fn pbkdf2(
+        password: Binary<u8>,
+        salt: Binary<u8>,
+        iterations: Option<i64>,
+        length: Option<i64>,
+        algorithm: Option<String>,
+    ) -> Result<Binary<u8>> {
+        let algorithm = Algorithm::parse(algorithm)?;
+        if salt.len() < MIN_SALT_LEN {
+            return Err(Error::SaltTooShort(salt.len()));
+        }
+        let iterations = match iterations {
+            None => DEFAULT_ITERATIONS,
+            Some(iterations) => u32::try_from(iterations)
+                .ok()
+                .filter(|iterations| *iterations >= MIN_ITERATIONS)
+                .ok_or(Error::InvalidIterations(iterations))?,
+        };
+        let length = output_len(length, MAX_PBKDF2_LEN)?;
+        Ok(Binary::from(pbkdf2(
+            algorithm, &password, &salt, iterations, length,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Algorithm, Error, hkdf, output_len, pbkdf2};
+    use data_encoding::HEXLOWER;
+
+    #[test]
+    fn test_hkdf() {
+        // RFC 5869, test case 1 (and the same inputs with SHA-512)
+        let key = [0x0b; 22];
+        let salt: Vec<u8> = (0x00..=0x0c).collect();
+        let info: Vec<u8> = (0xf0..=0xf9).collect();
+        assert_eq!(
+            HEXLOWER.encode(&hkdf(Algorithm::Sha256, &key, Some(&salt), &info, 42).unwrap()),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+        assert_eq!(
+            HEXLOWER.encode(&hkdf(Algorithm::Sha512, &key, Some(&salt), &info, 42).unwrap()),
+            "832390086cda71fb47625bb5ceb168e4c8e26a1a16ed34d9fc7fe92c1481579338da362cb8d9f925d7cb"
+        );
+        assert_ne!(
+            hkdf(Algorithm::Sha256, &key, None, b"users:1", 32).unwrap(),
+            hkdf(Algorithm::Sha256, &key, None, b"users:2", 32).unwrap()
+        );
+        assert!(matches!(
+            hkdf(Algorithm::Sha256, &key, None, b"", 32),
+            Err(Error::EmptyContext)
+        ));
+        assert!(matches!(
+            hkdf(Algorithm::Sha256, &key, None, b"x", 255 * 32 + 1),
+            Err(Error::InvalidLength(8161))
+        ));
+    }
+
+    #[test]
+    fn test_pbkdf2() {
+        assert_eq!(
+            HEXLOWER.encode(&pbkdf2(Algorithm::Sha256, b"password", b"salt", 1, 32)),
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"
+        );
+        assert_eq!(
+            HEXLOWER.encode(&pbkdf2(Algorithm::Sha256, b"password", b"salt", 4096, 32)),
+            "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"
+        );
+        assert_eq!(
+            HEXLOWER.encode(&pbkdf2(Algorithm::Sha512, b"password", b"salt", 1, 64)),
+            "867f70cf1ade02cff3752599a3a53dc4af34c7a669815ae5d513554e1c8cf252\
+             c02d470a285a0501bad999bfe943c08f050235d7d68b1da55e63f73b60a57fce"
+        );
+        assert_eq!(output_len(None, 64).unwrap(), 32);
+        assert!(matches!(
+            output_len(Some(0), 64),
+            Err(Error::InvalidLength(0))
+        ));
+        assert!(matches!(
+            output_len(Some(65), 64),
+            Err(Error::InvalidLength(65))
+        ));
+    }
+}
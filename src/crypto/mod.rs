@@ -0,0 +1,177 @@
+//! Misuse-resistant symmetric cryptography under `Hardened\Crypto`.
+//!
+//! Keys are exchanged as Base64URL strings of exactly 32 bytes, like `Hardened\CsrfProtection`
+//! keys, and every output carries a version byte so the format can evolve without breaking
+//! stored data.
+
+#[cfg(feature = "crypto")]
+use crate::to_str;
+#[cfg(feature = "crypto")]
+use data_encoding::{BASE64URL, BASE64URL_NOPAD};
+use ext_php_rs::builders::ModuleBuilder;
+use ext_php_rs::exception::PhpException;
+#[cfg(feature = "crypto")]
+use ext_php_rs::types::Zval;
+use ext_php_rs::zend::ce;
+use thiserror::Error;
+
+#[cfg(feature = "crypto")]
+pub mod envelope;
+#[cfg(feature = "crypto")]
+pub mod kdf;
+#[cfg(feature = "crypto")]
+pub mod secret_box;
+
+// Error codes for crypto errors: 3000-3099
+pub mod error_codes {
+    pub const KEY_DECODE: i32 = 3000;
+    pub const KEY_LENGTH: i32 = 3001;
+    pub const NO_KEYS: i32 = 3002;
+    pub const MALFORMED: i32 = 3003;
+    pub const UNSUPPORTED_VERSION: i32 = 3004;
+    pub const UNKNOWN_KEY: i32 = 3005;
+    pub const DECRYPTION_FAILED: i32 = 3006;
+    pub const ENCRYPTION_FAILED: i32 = 3007;
+    pub const STRING_CONVERSION: i32 = 3008;
+    pub const INVALID_LENGTH: i32 = 3009;
+    pub const UNSUPPORTED_ALGORITHM: i32 = 3010;
+    pub const INVALID_ITERATIONS: i32 = 3011;
+    pub const SALT_TOO_SHORT: i32 = 3012;
+    pub const EMPTY_CONTEXT: i32 = 3013;
+}
+
+/// Errors produced by the `Hardened\Crypto` classes.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Key must be valid base64url: {0}")]
+    KeyDecodeError(String),
+
+    #[error("Key must contain exactly 32 bytes, got {0}")]
+    KeyLengthError(usize),
+
+    #[error("At least one key is required")]
+    NoKeys,
+
+    #[error("Malformed ciphertext")]
+    Malformed,
+
+    #[error("Unsupported ciphertext version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("Ciphertext was encrypted with a key that is not configured")]
+    UnknownKey,
+
+    #[error("Decryption failed: wrong key, wrong associated data or tampered ciphertext")]
+    DecryptionFailed,
+
+    #[error("Encryption failed: {0}")]
+    EncryptionFailed(String),
+
+    #[error("Keys must be a string or an array of strings")]
+    StringConversion,
+
+    #[error("Invalid output length {0}")]
+    InvalidLength(i64),
+
+    #[error("Unsupported hash algorithm {0:?}, expected sha256 or sha512")]
+    UnsupportedAlgorithm(String),
+
+    #[error("PBKDF2 needs at least 100000 iterations, got {0}")]
+    InvalidIterations(i64),
+
+    #[error("Salt must be at least 16 bytes, got {0}")]
+    SaltTooShort(usize),
+
+    #[error("Context label must not be empty")]
+    EmptyContext,
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::KeyDecodeError(_) => error_codes::KEY_DECODE,
+            Error::KeyLengthError(_) => error_codes::KEY_LENGTH,
+            Error::NoKeys => error_codes::NO_KEYS,
+            Error::Malformed => error_codes::MALFORMED,
+            Error::UnsupportedVersion(_) => error_codes::UNSUPPORTED_VERSION,
+            Error::UnknownKey => error_codes::UNKNOWN_KEY,
+            Error::DecryptionFailed => error_codes::DECRYPTION_FAILED,
+            Error::EncryptionFailed(_) => error_codes::ENCRYPTION_FAILED,
+            Error::StringConversion => error_codes::STRING_CONVERSION,
+            Error::InvalidLength(_) => error_codes::INVALID_LENGTH,
+            Error::UnsupportedAlgorithm(_) => error_codes::UNSUPPORTED_ALGORITHM,
+            Error::InvalidIterations(_) => error_codes::INVALID_ITERATIONS,
+            Error::SaltTooShort(_) => error_codes::SALT_TOO_SHORT,
+            Error::EmptyContext => error_codes::EMPTY_CONTEXT,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for crypto operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Decodes a Base64URL key (padded or not) of exactly 32 bytes.
+///
+/// # Errors
+/// - Returns `Error::KeyDecodeError` or `Error::KeyLengthError`.
+#[cfg(feature = "crypto")]
+pub(crate) fn decode_key(key: &str) -> Result<[u8; 32]> {
+    let key = BASE64URL
+        .decode(key.as_bytes())
+        .or_else(|_| BASE64URL_NOPAD.decode(key.as_bytes()))
+        .map_err(|err| Error::KeyDecodeError(err.to_string()))?;
+    <[u8; 32]>::try_from(key.as_slice()).map_err(|_| Error::KeyLengthError(key.len()))
+}
+
+/// Decodes a Base64URL ciphertext, padded or not.
+///
+/// # Errors
+/// - Returns `Error::Malformed` if it is not Base64URL.
+#[cfg(feature = "crypto")]
+pub(crate) fn decode_base64url(ciphertext: &str) -> Result<Vec<u8>> {
+    BASE64URL
+        .decode(ciphertext.as_bytes())
+        .or_else(|_| BASE64URL_NOPAD.decode(ciphertext.as_bytes()))
+        .map_err(|_| Error::Malformed)
+}
+
+/// Reads a `string|array` of keys.
+///
+/// # Errors
+/// - Returns `Error::StringConversion` if a key is not a string.
+#[cfg(feature = "crypto")]
+pub(crate) fn keys_arg(keys: &Zval) -> Result<Vec<String>> {
+    if let Some(array) = keys.array() {
+        array
+            .values()
+            .map(|key| to_str(key).map_err(|_| Error::StringConversion))
+            .collect()
+    } else {
+        Ok(vec![to_str(keys).map_err(|_| Error::StringConversion)?])
+    }
+}
+
+/// A new random key, Base64URL-encoded.
+#[cfg(feature = "crypto")]
+pub(crate) fn generate_key() -> String {
+    BASE64URL.encode(&rand::random::<[u8; 32]>())
+}
+
+pub(crate) fn build(mut module: ModuleBuilder) -> ModuleBuilder {
+    #[cfg(feature = "crypto")]
+    {
+        module = module.class::<secret_box::SecretBox>();
+        module = module.class::<kdf::Kdf>();
+        module = module.class::<envelope::Envelope>();
+    }
+    module
+}
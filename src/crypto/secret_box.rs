@@ -0,0 +1,287 @@
+//! Authenticated symmetric encryption with XChaCha20-Poly1305 and key rotation.
+//!
+//! Ciphertext layout, Base64URL-encoded: version byte, 4-byte key id, 24-byte random nonce,
+//! then the encrypted message and its 16-byte tag. The version and key id are authenticated
+//! together with the caller's associated data. The 192-bit nonce makes random nonces safe for
+//! any realistic number of messages per key.
+
+use super::{Error, Result, decode_base64url, decode_key, generate_key, keys_arg};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use data_encoding::BASE64URL;
+use ext_php_rs::binary::Binary;
+use ext_php_rs::types::Zval;
+use ext_php_rs::{php_class, php_impl};
+use sha2::{Digest, Sha256};
+
+const VERSION: u8 = 1;
+const KEY_ID_LEN: usize = 4;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = 1 + KEY_ID_LEN;
+/// Size of everything but the message itself.
+pub(super) const OVERHEAD: usize = HEADER_LEN + NONCE_LEN + TAG_LEN;
+
+/// A key and the id stored in ciphertexts made with it.
+struct Key {
+    id: [u8; KEY_ID_LEN],
+    cipher: XChaCha20Poly1305,
+}
+
+impl Key {
+    fn new(key: &[u8; 32]) -> Self {
+        let digest = Sha256::new()
+            .chain_update(b"hardened-secretbox-key-id-v1")
+            .chain_update(key)
+            .finalize();
+        let mut id = [0u8; KEY_ID_LEN];
+        id.copy_from_slice(&digest[..KEY_ID_LEN]);
+        Self {
+            id,
+            cipher: XChaCha20Poly1305::new(key.into()),
+        }
+    }
+}
+
+/// Associated data actually authenticated: the ciphertext header, then the caller's data.
+fn full_aad(header: &[u8], aad: &[u8]) -> Vec<u8> {
+    [header, aad].concat()
+}
+
+/// XChaCha20-Poly1305 encryption with a key ring for rotation.
+#[php_class]
+#[php(name = "Hardened\\Crypto\\SecretBox")]
+pub struct SecretBox {
+    /// The first key encrypts; all of them decrypt.
+    keys: Vec<Key>,
+}
+
+impl SecretBox {
+    /// Backs `__construct()`.
+    pub(crate) fn _new<'a>(keys: impl IntoIterator<Item = &'a str>) -> Result<Self> {
+        let keys = keys
+            .into_iter()
+            .map(decode_key)
+            .collect::<Result<Vec<_>>>()?;
+        Self::_from_keys(&keys)
+    }
+
+    /// Builds a box from raw keys, the first being the primary one.
+    pub(crate) fn _from_keys(keys: &[[u8; 32]]) -> Result<Self> {
+        if keys.is_empty() {
+            return Err(Error::NoKeys);
+        }
+        Ok(Self {
+            keys: keys.iter().map(Key::new).collect(),
+        })
+    }
+
+    /// Encrypts under the primary key; returns the raw ciphertext.
+    pub(crate) fn _seal(&self, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        let key = &self.keys[0];
+        let mut output = Vec::with_capacity(OVERHEAD + plaintext.len());
+        output.push(VERSION);
+        output.extend_from_slice(&key.id);
+        let nonce = rand::random::<[u8; NONCE_LEN]>();
+        let ciphertext = key
+            .cipher
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: &full_aad(&output, aad),
+                },
+            )
+            .map_err(|err| Error::EncryptionFailed(err.to_string()))?;
+        output.extend_from_slice(&nonce);
+        output.extend_from_slice(&ciphertext);
+        Ok(output)
+    }
+
+    /// Validates the header of a raw ciphertext and returns the keys that may have made it.
+    fn _candidates(&self, sealed: &[u8]) -> Result<Vec<&Key>> {
+        if sealed.len() < OVERHEAD {
+            return Err(Error::Malformed);
+        }
+        if sealed[0] != VERSION {
+            return Err(Error::UnsupportedVersion(sealed[0]));
+        }
+        let id = &sealed[1..HEADER_LEN];
+        let candidates: Vec<_> = self.keys.iter().filter(|key| key.id == id).collect();
+        if candidates.is_empty() {
+            return Err(Error::UnknownKey);
+        }
+        Ok(candidates)
+    }
+
+    /// Decrypts a raw ciphertext.
+    pub(crate) fn _open(&self, sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        let candidates = self._candidates(sealed)?;
+        let (header, rest) = sealed.split_at(HEADER_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let aad = full_aad(header, aad);
+        // Key ids are only 32 bits, so more than one key may claim a ciphertext
+        candidates
+            .into_iter()
+            .find_map(|key| {
+                key.cipher
+                    .decrypt(
+                        XNonce::from_slice(nonce),
+                        Payload {
+                            msg: ciphertext,
+                            aad: &aad,
+                        },
+                    )
+                    .ok()
+            })
+            .ok_or(Error::DecryptionFailed)
+    }
+
+    /// Whether a raw ciphertext was made with a key other than the primary one.
+    pub(super) fn _needs_reencrypt(&self, sealed: &[u8]) -> Result<bool> {
+        self._candidates(sealed)?;
+        Ok(sealed[1..HEADER_LEN] != self.keys[0].id)
+    }
+}
+
+#[php_impl]
+impl SecretBox {
+    /// Constructs a box from one key or a key ring.
+    ///
+    /// # Parameters
+    /// - `keys`: `string|array` Base64URL-encoded 32-byte key, or a list of them. The first
+    ///   key encrypts, all of them decrypt, so a new key goes first and old ones stay until
+    ///   their data has been re-encrypted.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if no key is given or a key is not 32 bytes of Base64URL.
+    fn __construct(keys: &Zval) -> Result<Self> {
+        Self::_new(keys_arg(keys)?.iter().map(String::as_str))
+    }
+
+    /// Generate a new random key.
+    ///
+    /// # Returns
+    /// - `string` Base64URL-encoded 32-byte key.
+    fn generate_key() -> String {
+        generate_key()
+    }
+
+    /// Encrypt and authenticate a message.
+    ///
+    /// # Parameters
+    /// - `plaintext`: `string` The message.
+    /// - `aad`: `?string` Associated data that is authenticated but not encrypted, e.g. the
+    ///   table and row id, so a ciphertext cannot be moved to another record. Default: `''`.
+    ///
+    /// # Returns
+    /// - `string` Base64URL-encoded ciphertext.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if encryption fails.
+    fn encrypt(&self, plaintext: Binary<u8>, aad: Option<Binary<u8>>) -> Result<String> {
+        let aad = aad.map(Vec::from).unwrap_or_default();
+        Ok(BASE64URL.encode(&self._seal(&plaintext, &aad)?))
+    }
+
+    /// Decrypt and verify a message.
+    ///
+    /// # Parameters
+    /// - `ciphertext`: `string` Output of `encrypt()`.
+    /// - `aad`: `?string` The associated data given to `encrypt()`. Default: `''`.
+    ///
+    /// # Returns
+    /// - `string` The plaintext.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the ciphertext is malformed, was made with an unknown key,
+    ///   or fails authentication.
+    fn decrypt(&self, ciphertext: &str, aad: Option<Binary<u8>>) -> Result<Binary<u8>> {
+        let aad = aad.map(Vec::from).unwrap_or_default();
+        Ok(Binary::from(
+            self._open(&decode_base64url(ciphertext)?, &aad)?,
+        ))
+    }
+
+    /// Whether a ciphertext was made with a key other than the primary one.
+    ///
+    /// # Parameters
+    /// - `ciphertext`: `string` Output of `encrypt()`.
+    ///
+    /// # Returns
+    /// - `bool` `true` if it should be decrypted and encrypted again after a rotation.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the ciphertext is malformed or made with an unknown key.
+    fn needs_reencrypt(&self, ciphertext: &str) -> Result<bool> {
+        self._needs_reencrypt(&decode_base64url(ciphertext)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Error, generate_key};
+    use super::{OVERHEAD, SecretBox};
+
+    #[test]
+    fn test_round_trip() {
+        let key = generate_key();
+        let secret_box = SecretBox::_new([key.as_str()]).unwrap();
+        let sealed = secret_box._seal(b"card 4111", b"users:42").unwrap();
+        assert_eq!(sealed.len(), OVERHEAD + 9);
+        assert_eq!(
+            secret_box._open(&sealed, b"users:42").unwrap(),
+            b"card 4111"
+        );
+        assert_ne!(sealed, secret_box._seal(b"card 4111", b"users:42").unwrap());
+
+        assert!(matches!(
+            secret_box._open(&sealed, b"users:43"),
+            Err(Error::DecryptionFailed)
+        ));
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            secret_box._open(&tampered, b"users:42"),
+            Err(Error::DecryptionFailed)
+        ));
+        let mut future = sealed.clone();
+        future[0] = 2;
+        assert!(matches!(
+            secret_box._open(&future, b"users:42"),
+            Err(Error::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            secret_box._open(&sealed[..20], b""),
+            Err(Error::Malformed)
+        ));
+    }
+
+    #[test]
+    fn test_rotation() {
+        let (old, new) = (generate_key(), generate_key());
+        let before = SecretBox::_new([old.as_str()]).unwrap();
+        let sealed = before._seal(b"secret", b"").unwrap();
+
+        let after = SecretBox::_new([new.as_str(), old.as_str()]).unwrap();
+        assert_eq!(after._open(&sealed, b"").unwrap(), b"secret");
+        let resealed = after._seal(b"secret", b"").unwrap();
+        assert!(matches!(
+            before._open(&resealed, b""),
+            Err(Error::UnknownKey)
+        ));
+        assert!(matches!(
+            SecretBox::_new([new.as_str()]).unwrap()._open(&sealed, b""),
+            Err(Error::UnknownKey)
+        ));
+
+        assert!(matches!(
+            SecretBox::_new(Vec::<&str>::new()),
+            Err(Error::NoKeys)
+        ));
+        assert!(matches!(
+            SecretBox::_new(["c2hvcnQ"]),
+            Err(Error::KeyLengthError(5))
+        ));
+    }
+}
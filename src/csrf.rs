@@ -1,12 +1,24 @@
+use crate::constant_time;
+use crate::hostname::{self, Hostname};
+use crate::nonce_store::NonceStore;
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
 use csrf::{AesGcmCsrfProtection, CsrfCookie, CsrfProtection, CsrfToken};
-use data_encoding::{BASE64, BASE64URL};
+use data_encoding::{BASE64, BASE64URL, HEXLOWER};
 use ext_php_rs::exception::PhpException;
-use ext_php_rs::types::Zval;
+use ext_php_rs::prelude::ZendCallable;
+use ext_php_rs::types::{ZendHashTable, Zval};
 use ext_php_rs::zend::Function;
 use ext_php_rs::zend::ProcessGlobals;
 use ext_php_rs::zend::ce;
-use ext_php_rs::{php_class, php_impl};
+use ext_php_rs::{ZvalConvert, php_class, php_impl};
+use hmac::{Hmac, Mac};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error;
+use url::Url;
 
 // Error codes for CSRF errors: 1000-1099
 pub mod error_codes {
@@ -21,6 +33,19 @@ pub mod error_codes {
     pub const COOKIE_PARSE: i32 = 1008;
     pub const VERIFICATION: i32 = 1009;
     pub const SETCOOKIE_UNAVAILABLE: i32 = 1010;
+    pub const MALFORMED_TOKEN: i32 = 1011;
+    pub const INVALID_SIGNATURE: i32 = 1012;
+    pub const TOKEN_EXPIRED: i32 = 1013;
+    pub const TOKEN_NOT_YET_VALID: i32 = 1014;
+    pub const INVALID_COOKIE_OPTION: i32 = 1015;
+    pub const HEADER_UNAVAILABLE: i32 = 1016;
+    pub const ORIGIN_NOT_ALLOWED: i32 = 1017;
+    pub const ORIGIN_MISSING: i32 = 1018;
+    pub const TOKEN_MISSING: i32 = 1019;
+    pub const NEGATIVE_DURATION: i32 = 1020;
+    pub const REPLAY_STORE_NOT_SET: i32 = 1021;
+    pub const REPLAY_STORE_FAILED: i32 = 1022;
+    pub const EMPTY_SESSION_ID: i32 = 1023;
 }
 
 /// Errors that can occur during CSRF protection operations.
@@ -58,6 +83,48 @@ pub enum Error {
 
     #[error("Could not call setcookie()")]
     SetCookieUnavailable,
+
+    #[error("Malformed token")]
+    MalformedToken,
+
+    #[error("Token signature mismatch")]
+    InvalidSignature,
+
+    #[error("Token expired")]
+    TokenExpired,
+
+    #[error("Token is not valid yet")]
+    TokenNotYetValid,
+
+    #[error("Invalid cookie option: {0}")]
+    InvalidCookieOption(String),
+
+    #[error("Could not call header()")]
+    HeaderUnavailable,
+
+    #[error("Request origin is not allowed: {0}")]
+    OriginNotAllowed(String),
+
+    #[error("Request has neither an Origin nor a Referer header")]
+    OriginMissing,
+
+    #[error("CSRF token is missing")]
+    TokenMissing,
+
+    #[error("Duration must not be negative: {0}")]
+    NegativeDuration(i64),
+
+    #[error("No replay store is set, call setReplayStore() or setReplayNonceStore() first")]
+    ReplayStoreNotSet,
+
+    #[error("Replay store failed: {0}")]
+    ReplayStoreFailed(String),
+
+    #[error("Session id must not be empty")]
+    EmptySessionId,
+
+    #[error(transparent)]
+    Hostname(#[from] hostname::Error),
 }
 
 impl Error {
@@ -75,6 +142,20 @@ impl Error {
             Error::CookieParseError(_) => error_codes::COOKIE_PARSE,
             Error::VerificationError(_) => error_codes::VERIFICATION,
             Error::SetCookieUnavailable => error_codes::SETCOOKIE_UNAVAILABLE,
+            Error::MalformedToken => error_codes::MALFORMED_TOKEN,
+            Error::InvalidSignature => error_codes::INVALID_SIGNATURE,
+            Error::TokenExpired => error_codes::TOKEN_EXPIRED,
+            Error::TokenNotYetValid => error_codes::TOKEN_NOT_YET_VALID,
+            Error::InvalidCookieOption(_) => error_codes::INVALID_COOKIE_OPTION,
+            Error::HeaderUnavailable => error_codes::HEADER_UNAVAILABLE,
+            Error::OriginNotAllowed(_) => error_codes::ORIGIN_NOT_ALLOWED,
+            Error::OriginMissing => error_codes::ORIGIN_MISSING,
+            Error::TokenMissing => error_codes::TOKEN_MISSING,
+            Error::NegativeDuration(_) => error_codes::NEGATIVE_DURATION,
+            Error::ReplayStoreNotSet => error_codes::REPLAY_STORE_NOT_SET,
+            Error::ReplayStoreFailed(_) => error_codes::REPLAY_STORE_FAILED,
+            Error::EmptySessionId => error_codes::EMPTY_SESSION_ID,
+            Error::Hostname(err) => err.code(),
         }
     }
 }
@@ -90,6 +171,296 @@ impl From<Error> for PhpException {
 /// Result type alias for CSRF operations.
 pub type Result<T> = std::result::Result<T, Error>;
 
+type HmacSha256 = Hmac<Sha256>;
+
+/// Stateless token layout: version byte, issue time (big-endian seconds), random nonce, HMAC tag.
+const STATELESS_VERSION: u8 = 1;
+const STATELESS_NONCE_LEN: usize = 16;
+const STATELESS_TAG_LEN: usize = 32;
+const STATELESS_TOKEN_LEN: usize = 1 + 8 + STATELESS_NONCE_LEN + STATELESS_TAG_LEN;
+
+/// Current UNIX time in seconds.
+fn unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+/// HMAC-SHA256 keyed with `key` over the given parts.
+fn hmac_sha256(key: &[u8], parts: &[&[u8]]) -> HmacSha256 {
+    let mut mac =
+        <HmacSha256 as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for part in parts {
+        mac.update(part);
+    }
+    mac
+}
+
+/// Claims token layout: version byte, random nonce, AES-256-GCM ciphertext and tag.
+const CLAIMS_VERSION: u8 = 2;
+const CLAIMS_NONCE_LEN: usize = 12;
+
+/// A scalar claim value carried by `issueWithClaims()` tokens.
+#[derive(Debug, Clone, PartialEq, ZvalConvert)]
+pub enum Claim {
+    Bool(bool),
+    Long(i64),
+    Double(f64),
+    String(String),
+    None,
+}
+
+impl Claim {
+    fn to_json(&self) -> Value {
+        match self {
+            Claim::Bool(value) => Value::Bool(*value),
+            Claim::Long(value) => Value::from(*value),
+            Claim::Double(value) => Value::from(*value),
+            Claim::String(value) => Value::String(value.clone()),
+            Claim::None => Value::Null,
+        }
+    }
+
+    fn from_json(value: &Value) -> Self {
+        match value {
+            Value::Bool(value) => Claim::Bool(*value),
+            Value::Number(number) => number
+                .as_i64()
+                .map(Claim::Long)
+                .or_else(|| number.as_f64().map(Claim::Double))
+                .unwrap_or(Claim::None),
+            Value::String(value) => Claim::String(value.clone()),
+            _ => Claim::None,
+        }
+    }
+}
+
+/// What a stateless token is valid for. Each scope signs with its own derived key.
+#[derive(Debug, Clone, Copy)]
+enum Scope<'a> {
+    /// A free-form scope passed to `statelessToken()`.
+    Named(&'a str),
+    /// An action passed to `tokenFor()`.
+    Action(&'a str),
+    /// The double-submit cookie pattern.
+    DoubleSubmit,
+}
+
+impl Scope<'_> {
+    fn parts(&self) -> (&'static [u8], &[u8]) {
+        match self {
+            Scope::Named(name) => (b"scope", name.as_bytes()),
+            Scope::Action(action) => (b"action", action.as_bytes()),
+            Scope::DoubleSubmit => (b"double-submit", b""),
+        }
+    }
+}
+
+/// Form field read by `verifyFromRequest()` and `verifyRequest()`.
+const FORM_FIELD: &str = "csrf_token";
+
+/// Header read by `verifyRequest()` when the form field is absent.
+const TOKEN_HEADER: &str = "X-CSRF-Token";
+
+/// Methods `verifyRequest()` lets through, as they must not change state.
+const SAFE_METHODS: &[&str] = &["GET", "HEAD", "OPTIONS", "TRACE"];
+
+/// The parts of a request that `verifyRequest()` looks at.
+#[derive(Debug, Default)]
+struct RequestParts {
+    method: String,
+    host: Option<String>,
+    origin: Option<String>,
+    referer: Option<String>,
+    token: Option<String>,
+}
+
+impl RequestParts {
+    /// Collects the parts from `$_SERVER`, `$_POST` and a header array.
+    ///
+    /// Headers are looked up case-insensitively in `headers` (plain or PSR-7 `getHeaders()` style),
+    /// then as `HTTP_*` entries of `server`.
+    fn from_tables(server: &ZendHashTable, post: &ZendHashTable, headers: &ZendHashTable) -> Self {
+        let server_var = |name: &str| server.get(name).and_then(Zval::string);
+        let header = |name: &str| {
+            find_header(headers, name).or_else(|| {
+                server_var(&format!(
+                    "HTTP_{}",
+                    name.to_ascii_uppercase().replace('-', "_")
+                ))
+            })
+        };
+        Self {
+            method: server_var("REQUEST_METHOD")
+                .unwrap_or_default()
+                .to_ascii_uppercase(),
+            host: header("Host"),
+            origin: header("Origin"),
+            referer: header("Referer"),
+            token: post
+                .get(FORM_FIELD)
+                .and_then(Zval::string)
+                .or_else(|| header(TOKEN_HEADER)),
+        }
+    }
+}
+
+/// Finds a header by case-insensitive name; list values (PSR-7) yield their first entry.
+fn find_header(headers: &ZendHashTable, name: &str) -> Option<String> {
+    headers.iter().find_map(|(key, value)| {
+        if !key.to_string().eq_ignore_ascii_case(name) {
+            return None;
+        }
+        value.string().or_else(|| {
+            value
+                .array()
+                .and_then(|values| values.values().next())
+                .and_then(Zval::string)
+        })
+    })
+}
+
+/// Parses the host of an absolute URL.
+fn url_host(url: &str) -> Option<Hostname> {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| Hostname::_from_parsed_url(&url).ok())
+}
+
+/// Storage key for a used token: the hex SHA-256 of the token, so the store never sees
+/// a replayable value.
+fn token_hash(token: &str) -> String {
+    HEXLOWER.encode(&Sha256::digest(token.as_bytes()))
+}
+
+/// Calls a replay store callback that must return a `bool`.
+///
+/// # Errors
+/// - Returns `Err` if the callback is not callable, throws, or returns something else.
+fn call_store(callback: &Zval, hash: &str, ttl: Option<i64>) -> Result<bool> {
+    let callable =
+        ZendCallable::new(callback).map_err(|err| Error::ReplayStoreFailed(err.to_string()))?;
+    let result = match ttl {
+        Some(ttl) => callable.try_call(vec![&hash, &ttl]),
+        None => callable.try_call(vec![&hash]),
+    }
+    .map_err(|err| Error::ReplayStoreFailed(err.to_string()))?;
+    result
+        .bool()
+        .ok_or_else(|| Error::ReplayStoreFailed("callback must return a bool".into()))
+}
+
+/// Storage for single-use tokens.
+enum ReplayStore {
+    /// `(markUsed, isUsed)` callbacks set by `setReplayStore()`.
+    Callbacks(Zval, Zval),
+    /// Store set by `setReplayNonceStore()`, shared with the PHP `NonceStore` object.
+    Nonces(NonceStore),
+}
+
+/// Attributes of the double-submit cookie, using the keys of PHP's `setcookie()` options array.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CookieOptions {
+    expires: i64,
+    path: String,
+    domain: String,
+    secure: bool,
+    httponly: bool,
+    same_site: &'static str,
+}
+
+impl Default for CookieOptions {
+    /// The strictest attributes, which also satisfy the `__Host-` prefix rules.
+    fn default() -> Self {
+        Self {
+            expires: 0,
+            path: "/".into(),
+            domain: String::new(),
+            secure: true,
+            httponly: true,
+            same_site: "Strict",
+        }
+    }
+}
+
+impl CookieOptions {
+    /// Reads options over the defaults.
+    ///
+    /// # Errors
+    /// - Returns `Err` for a value of the wrong type or an unknown `samesite` value.
+    fn from_table(table: &ZendHashTable) -> Result<Self> {
+        let mut options = Self::default();
+        let invalid = |name: &str| Error::InvalidCookieOption(name.into());
+        if let Some(value) = table.get("expires") {
+            options.expires = value.long().ok_or_else(|| invalid("expires"))?;
+        }
+        if let Some(value) = table.get("path") {
+            options.path = value.string().ok_or_else(|| invalid("path"))?;
+        }
+        if let Some(value) = table.get("domain") {
+            options.domain = value.string().ok_or_else(|| invalid("domain"))?;
+        }
+        if let Some(value) = table.get("secure") {
+            options.secure = value.bool().ok_or_else(|| invalid("secure"))?;
+        }
+        if let Some(value) = table.get("httponly") {
+            options.httponly = value.bool().ok_or_else(|| invalid("httponly"))?;
+        }
+        if let Some(value) = table.get("samesite") {
+            let same_site = value.string().ok_or_else(|| invalid("samesite"))?;
+            options.same_site = ["Strict", "Lax", "None"]
+                .into_iter()
+                .find(|known| known.eq_ignore_ascii_case(&same_site))
+                .ok_or_else(|| invalid("samesite"))?;
+        }
+        // Browsers drop `SameSite=None` cookies without `Secure`
+        if options.same_site == "None" && !options.secure {
+            return Err(invalid("samesite"));
+        }
+        // These end up verbatim in the header
+        for (name, value) in [("path", &options.path), ("domain", &options.domain)] {
+            if value.contains([';', ',', '\r', '\n']) {
+                return Err(invalid(name));
+            }
+        }
+        Ok(options)
+    }
+
+    /// Returns the strongest name prefix these attributes allow.
+    fn prefix(&self) -> &'static str {
+        if self.secure && self.path == "/" && self.domain.is_empty() {
+            "__Host-"
+        } else if self.secure {
+            "__Secure-"
+        } else {
+            ""
+        }
+    }
+
+    /// Formats the `Set-Cookie` header value.
+    fn set_cookie(&self, name: &str, value: &str, now: i64) -> String {
+        let mut header = format!("{}{name}={value}; Path={}", self.prefix(), self.path);
+        if !self.domain.is_empty() {
+            header.push_str("; Domain=");
+            header.push_str(&self.domain);
+        }
+        if self.expires > 0 {
+            header.push_str(&format!("; Max-Age={}", (self.expires - now).max(0)));
+        }
+        if self.secure {
+            header.push_str("; Secure");
+        }
+        if self.httponly {
+            header.push_str("; HttpOnly");
+        }
+        header.push_str("; SameSite=");
+        header.push_str(self.same_site);
+        header
+    }
+}
+
 /// CSRF protection for your application.
 #[php_class]
 #[php(name = "Hardened\\CsrfProtection")]
@@ -98,7 +469,252 @@ pub struct Csrf {
     pub token: CsrfToken,
     pub cookie: CsrfCookie,
     pub cookie_name: String,
+    /// Key for stateless tokens, derived from the main key so it is never used for two primitives.
+    stateless_key: [u8; 32],
+    /// AES-256-GCM key for claims tokens, derived like `stateless_key`.
+    claims_key: [u8; 32],
+    ttl: i64,
+    /// Seconds by which clocks of different nodes may disagree.
+    clock_skew: i64,
+    allowed_hosts: Vec<Hostname>,
+    /// Store set by `setReplayStore()` or `setReplayNonceStore()`.
+    replay_store: Option<ReplayStore>,
+    /// Digest of the session set by `bindTo()`, mixed into every time-bound token.
+    binding: Option<[u8; 32]>,
+}
+
+impl Csrf {
+    fn _binding(&self) -> &[u8] {
+        match &self.binding {
+            Some(binding) => binding,
+            None => &[],
+        }
+    }
+
+    /// MAC of a stateless token body under a key derived for `scope` and the session binding,
+    /// so a token minted for one scope, action or session can never verify for another.
+    fn _stateless_mac(&self, scope: Scope, body: &[u8]) -> HmacSha256 {
+        let (kind, value) = scope.parts();
+        let scope_key = hmac_sha256(
+            &self.stateless_key,
+            &[
+                kind,
+                b"\0",
+                &(value.len() as u64).to_be_bytes(),
+                value,
+                self._binding(),
+            ],
+        )
+        .finalize()
+        .into_bytes();
+        hmac_sha256(&scope_key, &[body])
+    }
+
+    fn _stateless_token_at(&self, scope: Scope, issued_at: i64) -> String {
+        let mut token = Vec::with_capacity(STATELESS_TOKEN_LEN);
+        token.push(STATELESS_VERSION);
+        token.extend_from_slice(&issued_at.to_be_bytes());
+        token.extend_from_slice(&rand::random::<[u8; STATELESS_NONCE_LEN]>());
+        let tag = self._stateless_mac(scope, &token).finalize().into_bytes();
+        token.extend_from_slice(&tag);
+        BASE64URL.encode(&token)
+    }
+
+    /// Verifies a stateless token as of `now`, returning its issue time.
+    ///
+    /// # Errors
+    /// - Returns `Err` if the token is malformed, signed for another scope or key, or outside its lifetime.
+    fn _verify_stateless_at(
+        &self,
+        token: &str,
+        scope: Scope,
+        max_age: i64,
+        now: i64,
+    ) -> Result<i64> {
+        let token = BASE64URL
+            .decode(token.as_bytes())
+            .map_err(|err| Error::TokenDecodeError(err.to_string()))?;
+        if token.len() != STATELESS_TOKEN_LEN || token[0] != STATELESS_VERSION {
+            return Err(Error::MalformedToken);
+        }
+        let (body, tag) = token.split_at(STATELESS_TOKEN_LEN - STATELESS_TAG_LEN);
+        // The timestamp is only trusted once the tag checks out
+        self._stateless_mac(scope, body)
+            .verify_slice(tag)
+            .map_err(|_| Error::InvalidSignature)?;
+        let issued_at = i64::from_be_bytes(body[1..9].try_into().expect("8-byte timestamp"));
+        if issued_at > now.saturating_add(self.clock_skew) {
+            return Err(Error::TokenNotYetValid);
+        }
+        if now.saturating_sub(issued_at) > max_age.saturating_add(self.clock_skew) {
+            return Err(Error::TokenExpired);
+        }
+        Ok(issued_at)
+    }
+
+    /// Encrypts `claims` with an expiry of `now + ttl`.
+    ///
+    /// # Errors
+    /// - Returns `Err` if encryption fails.
+    fn _issue_with_claims_at(&self, claims: &HashMap<String, Claim>, now: i64) -> Result<String> {
+        let payload = Value::Object(Map::from_iter([
+            ("exp".to_string(), Value::from(now.saturating_add(self.ttl))),
+            (
+                "claims".to_string(),
+                Value::Object(
+                    claims
+                        .iter()
+                        .map(|(name, claim)| (name.clone(), claim.to_json()))
+                        .collect(),
+                ),
+            ),
+        ]))
+        .to_string();
+        let nonce = rand::random::<[u8; CLAIMS_NONCE_LEN]>();
+        let ciphertext = Aes256Gcm::new(&self.claims_key.into())
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: payload.as_bytes(),
+                    aad: &[&[CLAIMS_VERSION][..], self._binding()].concat(),
+                },
+            )
+            .map_err(|err| Error::TokenGenerationError(err.to_string()))?;
+        let mut token = Vec::with_capacity(1 + CLAIMS_NONCE_LEN + ciphertext.len());
+        token.push(CLAIMS_VERSION);
+        token.extend_from_slice(&nonce);
+        token.extend_from_slice(&ciphertext);
+        Ok(BASE64URL.encode(&token))
+    }
+
+    /// Decrypts a claims token, returning its payload without checking the expiry.
+    ///
+    /// # Errors
+    /// - Returns `Err` if the token is malformed or fails authentication.
+    fn _open_claims(&self, token: &str) -> Result<Map<String, Value>> {
+        let token = BASE64URL
+            .decode(token.as_bytes())
+            .map_err(|err| Error::TokenDecodeError(err.to_string()))?;
+        if token.len() <= 1 + CLAIMS_NONCE_LEN || token[0] != CLAIMS_VERSION {
+            return Err(Error::MalformedToken);
+        }
+        let (nonce, ciphertext) = token[1..].split_at(CLAIMS_NONCE_LEN);
+        let payload = Aes256Gcm::new(&self.claims_key.into())
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &[&[CLAIMS_VERSION][..], self._binding()].concat(),
+                },
+            )
+            .map_err(|_| Error::InvalidSignature)?;
+        match serde_json::from_slice(&payload) {
+            Ok(Value::Object(payload)) => Ok(payload),
+            _ => Err(Error::MalformedToken),
+        }
+    }
+
+    /// Reads the expiry of a stateless or claims token without checking it.
+    ///
+    /// Claims tokens are authenticated while decrypting; stateless tokens are not, as that
+    /// needs their scope, so their expiry is only informational until verified.
+    fn _expires_at(&self, token: &str) -> Option<i64> {
+        let bytes = BASE64URL.decode(token.as_bytes()).ok()?;
+        match bytes.first().copied()? {
+            STATELESS_VERSION if bytes.len() == STATELESS_TOKEN_LEN => {
+                let issued_at = i64::from_be_bytes(bytes[1..9].try_into().ok()?);
+                Some(issued_at.saturating_add(self.ttl))
+            }
+            CLAIMS_VERSION => self._open_claims(token).ok()?.get("exp")?.as_i64(),
+            _ => None,
+        }
+    }
+
+    /// How long a used token must be remembered as of `now`: until it expires, plus the clock skew.
+    fn _replay_ttl(&self, token: &str, now: i64) -> i64 {
+        self._expires_at(token)
+            .map_or(self.ttl, |expires_at| expires_at.saturating_sub(now))
+            .saturating_add(self.clock_skew)
+            .max(1)
+    }
+
+    /// Decrypts a claims token and checks its expiry as of `now`.
+    ///
+    /// # Errors
+    /// - Returns `Err` if the token is malformed, fails authentication or has expired.
+    fn _verify_and_decode_at(&self, token: &str, now: i64) -> Result<HashMap<String, Claim>> {
+        let payload = self._open_claims(token)?;
+        let expires_at = payload
+            .get("exp")
+            .and_then(Value::as_i64)
+            .ok_or(Error::MalformedToken)?;
+        if now > expires_at.saturating_add(self.clock_skew) {
+            return Err(Error::TokenExpired);
+        }
+        Ok(payload
+            .get("claims")
+            .and_then(Value::as_object)
+            .ok_or(Error::MalformedToken)?
+            .iter()
+            .map(|(name, value)| (name.clone(), Claim::from_json(value)))
+            .collect())
+    }
+
+    /// Checks that a request comes from an allowed host: the `Origin` header if present,
+    /// otherwise the `Referer`.
+    ///
+    /// # Errors
+    /// - Returns `Err` if both headers are missing or the host they name is not allowed.
+    fn _check_origin(&self, request: &RequestParts) -> Result<()> {
+        let source = request
+            .origin
+            .as_deref()
+            .or(request.referer.as_deref())
+            .ok_or(Error::OriginMissing)?;
+        // `Origin: null` (sandboxed frames, cross-origin redirects) has no host and is rejected here
+        let host = url_host(source).ok_or_else(|| Error::OriginNotAllowed(source.into()))?;
+        let allowed = if self.allowed_hosts.is_empty() {
+            request
+                .host
+                .as_deref()
+                .and_then(|host| url_host(&format!("http://{host}")))
+                .is_some_and(|request_host| request_host == host)
+        } else {
+            self.allowed_hosts.contains(&host)
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(Error::OriginNotAllowed(host.to_string()))
+        }
+    }
+
+    /// Verifies a request as of `now`, see `verifyRequest()`.
+    ///
+    /// # Errors
+    /// - Returns `Err` describing the first failed check.
+    fn _verify_request(&self, request: &RequestParts, now: i64) -> Result<()> {
+        if SAFE_METHODS.contains(&request.method.as_str()) {
+            return Ok(());
+        }
+        self._check_origin(request)?;
+        let token = request.token.as_deref().ok_or(Error::TokenMissing)?;
+        self._verify_stateless_at(token, Scope::Named(""), self.ttl, now)?;
+        Ok(())
+    }
+
+    /// Checks a double-submit cookie/form pair as of `now`.
+    fn _verify_double_submit(&self, cookie: Option<&str>, form: Option<&str>, now: i64) -> bool {
+        let (Some(cookie), Some(form)) = (cookie, form) else {
+            return false;
+        };
+        constant_time::eq(cookie.as_bytes(), form.as_bytes())
+            && self
+                ._verify_stateless_at(cookie, Scope::DoubleSubmit, self.ttl, now)
+                .is_ok()
+    }
 }
+
 #[php_impl]
 impl Csrf {
     /// Constructs a CSRF protection instance for PHP.
@@ -119,6 +735,14 @@ impl Csrf {
         )
         .map_err(|_| Error::KeyLengthError)?;
         let inner = AesGcmCsrfProtection::from_key(key);
+        let stateless_key = hmac_sha256(&key, &[b"hardened-csrf-stateless-v1"])
+            .finalize()
+            .into_bytes()
+            .into();
+        let claims_key = hmac_sha256(&key, &[b"hardened-csrf-claims-v1"])
+            .finalize()
+            .into_bytes()
+            .into();
 
         let previous_token_value = if let Some(previous_token_value) = previous_token_value {
             <[u8; 64]>::try_from(
@@ -139,6 +763,13 @@ impl Csrf {
             token,
             cookie,
             cookie_name: String::from("csrf"),
+            stateless_key,
+            claims_key,
+            ttl,
+            clock_skew: 0,
+            allowed_hosts: Vec::new(),
+            replay_store: None,
+            binding: None,
         })
     }
 
@@ -199,6 +830,378 @@ impl Csrf {
         Ok(())
     }
 
+    /// Sets the lifetime of stateless, per-action, double-submit and claims tokens.
+    ///
+    /// The constructor's token–cookie pair keeps the lifetime it was generated with.
+    ///
+    /// # Parameters
+    /// - `seconds`: `int` Token lifetime.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `seconds` is negative.
+    fn set_ttl(&mut self, seconds: i64) -> Result<()> {
+        if seconds < 0 {
+            return Err(Error::NegativeDuration(seconds));
+        }
+        self.ttl = seconds;
+        Ok(())
+    }
+
+    /// Returns the lifetime of time-bound tokens.
+    ///
+    /// # Returns
+    /// - `int` Seconds.
+    fn ttl(&self) -> i64 {
+        self.ttl
+    }
+
+    /// Sets how many seconds a token may be early or late, to tolerate clock drift between nodes.
+    ///
+    /// # Parameters
+    /// - `seconds`: `int` Tolerance, `0` by default.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `seconds` is negative.
+    fn set_clock_skew(&mut self, seconds: i64) -> Result<()> {
+        if seconds < 0 {
+            return Err(Error::NegativeDuration(seconds));
+        }
+        self.clock_skew = seconds;
+        Ok(())
+    }
+
+    /// Returns when a stateless, per-action, double-submit or claims token expires,
+    /// so an API can tell a SPA when to fetch a new one.
+    ///
+    /// This is an inspector, not a check: always verify the token before acting on it.
+    ///
+    /// # Parameters
+    /// - `token`: `string` The token.
+    ///
+    /// # Returns
+    /// - `?int` UNIX timestamp, or `null` if the token is not recognized.
+    fn expires_at(&self, token: &str) -> Option<i64> {
+        self._expires_at(token)
+    }
+
+    /// Binds time-bound tokens to a session: the session id (and optionally a user agent hash)
+    /// is mixed into their keys, so a token stolen from one session fails in any other,
+    /// even before it expires.
+    ///
+    /// Applies to tokens issued and verified after the call; the constructor's token–cookie
+    /// pair is not affected.
+    ///
+    /// # Parameters
+    /// - `sessionId`: `string` The session identifier, e.g. `session_id()`.
+    /// - `userAgentHash`: `?string` Optional digest of the user agent, to also tie tokens to the browser.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `sessionId` is empty.
+    fn bind_to(&mut self, session_id: &str, user_agent_hash: Option<String>) -> Result<()> {
+        if session_id.is_empty() {
+            return Err(Error::EmptySessionId);
+        }
+        let user_agent_hash = user_agent_hash.unwrap_or_default();
+        let mut digest = Sha256::new();
+        for part in [session_id, user_agent_hash.as_str()] {
+            digest.update((part.len() as u64).to_be_bytes());
+            digest.update(part.as_bytes());
+        }
+        self.binding = Some(digest.finalize().into());
+        Ok(())
+    }
+
+    /// Sets the storage used by `markUsed()` and `isUsed()` to enforce single-use tokens,
+    /// e.g. for payment confirmation.
+    ///
+    /// The callbacks receive the SHA-256 hex digest of a token, never the token itself:
+    /// - `markUsed(string $tokenHash, int $ttl): bool` must atomically record the hash for `$ttl`
+    ///   seconds and return `true` only if it was not recorded yet
+    ///   (e.g. `$redis->set("csrf:$tokenHash", 1, ['nx', 'ex' => $ttl])`).
+    /// - `isUsed(string $tokenHash): bool` returns whether the hash is recorded.
+    ///
+    /// # Parameters
+    /// - `markUsed`: `callable` Records a hash.
+    /// - `isUsed`: `callable` Looks a hash up.
+    fn set_replay_store(&mut self, mark_used: &Zval, is_used: &Zval) {
+        self.replay_store = Some(ReplayStore::Callbacks(
+            mark_used.shallow_clone(),
+            is_used.shallow_clone(),
+        ));
+    }
+
+    /// Uses a `NonceStore` for `markUsed()` and `isUsed()` instead of callbacks.
+    ///
+    /// The store is shared, not copied: tokens marked here are consumed nonces of `store`, and
+    /// a backend set on it later with `NonceStore::setBackend()` is used here too. Like any
+    /// nonce, a token only reaches the backend as its SHA-256 digest.
+    ///
+    /// # Parameters
+    /// - `store`: `NonceStore`
+    fn set_replay_nonce_store(&mut self, store: &NonceStore) {
+        self.replay_store = Some(ReplayStore::Nonces(store.clone()));
+    }
+
+    /// Marks a token as used. Call it after verifying the token and act only if it returns `true`.
+    ///
+    /// The hash is kept until the token expires, after which verification rejects it anyway.
+    ///
+    /// # Parameters
+    /// - `token`: `string` A verified token.
+    ///
+    /// # Returns
+    /// - `bool` `true` on first use, `false` if the token was already used (a replay).
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if no replay store is set or the store fails.
+    fn mark_used(&self, token: &str) -> Result<bool> {
+        let now = unix_time();
+        match self.replay_store.as_ref().ok_or(Error::ReplayStoreNotSet)? {
+            ReplayStore::Callbacks(mark_used, _) => call_store(
+                mark_used,
+                &token_hash(token),
+                Some(self._replay_ttl(token, now)),
+            ),
+            ReplayStore::Nonces(store) => store
+                ._consume_at(token, self._replay_ttl(token, now), now)
+                .map_err(|err| Error::ReplayStoreFailed(err.to_string())),
+        }
+    }
+
+    /// Returns true if `markUsed()` has already been called for this token.
+    ///
+    /// # Parameters
+    /// - `token`: `string` The token.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the token was used.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if no replay store is set or the store fails.
+    fn is_used(&self, token: &str) -> Result<bool> {
+        match self.replay_store.as_ref().ok_or(Error::ReplayStoreNotSet)? {
+            ReplayStore::Callbacks(_, is_used) => call_store(is_used, &token_hash(token), None),
+            ReplayStore::Nonces(store) => store
+                ._is_consumed_at(token, unix_time())
+                .map_err(|err| Error::ReplayStoreFailed(err.to_string())),
+        }
+    }
+
+    /// Issues a stateless token: a signed issue time and nonce, optionally bound to a scope.
+    ///
+    /// No cookie or session storage is involved, so any node that shares the key can verify it
+    /// (see `verifyStatelessToken()`), which suits deployments without sticky sessions.
+    ///
+    /// # Parameters
+    /// - `scope`: `?string` Optional scope (e.g. a form name) the token is only valid for.
+    ///
+    /// # Returns
+    /// - `string` Base64URL-encoded token.
+    fn stateless_token(&self, scope: Option<String>) -> String {
+        self._stateless_token_at(
+            Scope::Named(scope.as_deref().unwrap_or_default()),
+            unix_time(),
+        )
+    }
+
+    /// Verifies a token issued by `statelessToken()`.
+    ///
+    /// # Parameters
+    /// - `token`: `string` Token from the client.
+    /// - `maxAge`: `?int` Maximum token age in seconds (defaults to the constructor's `ttl`).
+    /// - `scope`: `?string` The scope the token must have been issued for.
+    ///
+    /// # Returns
+    /// - `void` on success.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the token is malformed, was signed for another scope or key, or has expired.
+    fn verify_stateless_token(
+        &self,
+        token: &str,
+        max_age: Option<i64>,
+        scope: Option<String>,
+    ) -> Result<()> {
+        self._verify_stateless_at(
+            token,
+            Scope::Named(scope.as_deref().unwrap_or_default()),
+            max_age.unwrap_or(self.ttl),
+            unix_time(),
+        )?;
+        Ok(())
+    }
+
+    /// Issues a token that is only valid for one action, e.g. `"delete-account"`.
+    ///
+    /// The action is mixed into the signing key, so a token leaked from one form cannot be
+    /// replayed against another.
+    ///
+    /// # Parameters
+    /// - `action`: `string` The action name.
+    ///
+    /// # Returns
+    /// - `string` Base64URL-encoded token.
+    fn token_for(&self, action: &str) -> String {
+        self._stateless_token_at(Scope::Action(action), unix_time())
+    }
+
+    /// Verifies a token issued by `tokenFor()` for the same action, no older than `ttl`.
+    ///
+    /// # Parameters
+    /// - `action`: `string` The action being performed.
+    /// - `token`: `string` Token from the client.
+    ///
+    /// # Returns
+    /// - `void` on success.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the token is malformed, was issued for another action, or has expired.
+    fn verify_for(&self, action: &str, token: &str) -> Result<()> {
+        self._verify_stateless_at(token, Scope::Action(action), self.ttl, unix_time())?;
+        Ok(())
+    }
+
+    /// Starts a double-submit cookie flow: sends a signed token as a cookie and returns the same
+    /// token to embed in the form or an `X-CSRF-Token` header.
+    ///
+    /// By default the cookie is named `"__Host-" . cookieName()` and is `Secure`, `HttpOnly`
+    /// and `SameSite=Strict`; the prefix keeps subdomains from planting their own token.
+    ///
+    /// # Parameters
+    /// - `cookieOptions`: `?array` Overrides in `setcookie()` format: `expires`, `path`, `domain`,
+    ///   `secure`, `httponly`, `samesite`. Changing `path`, `domain` or `secure` weakens the prefix
+    ///   to `__Secure-` or none.
+    ///
+    /// # Returns
+    /// - `string` The token to embed.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if an option is invalid or `header()` cannot be called.
+    fn issue_cookie_token(&self, cookie_options: Option<&ZendHashTable>) -> Result<String> {
+        let options = cookie_options
+            .map(CookieOptions::from_table)
+            .transpose()?
+            .unwrap_or_default();
+        let now = unix_time();
+        let token = self._stateless_token_at(Scope::DoubleSubmit, now);
+        let header = format!(
+            "Set-Cookie: {}",
+            options.set_cookie(&self.cookie_name, &token, now)
+        );
+        Function::try_from_function("header")
+            .ok_or(Error::HeaderUnavailable)?
+            .try_call(vec![&header, &false])
+            .map_err(|_| Error::HeaderUnavailable)?;
+        Ok(token)
+    }
+
+    /// Verifies a double-submit pair issued by `issueCookieToken()`: the values must be equal
+    /// and carry a valid signature no older than `ttl`.
+    ///
+    /// # Parameters
+    /// - `cookieValue`: `?string` Cookie value; defaults to the `__Host-` prefixed cookie from `$_COOKIE`.
+    ///   Pass it explicitly if the cookie was issued without the prefix (e.g. `secure => false`).
+    /// - `formValue`: `?string` Submitted token; defaults to `$_POST['csrf_token']`.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the pair is valid.
+    fn verify_from_request(
+        &self,
+        cookie_value: Option<String>,
+        form_value: Option<String>,
+    ) -> bool {
+        let globals = ProcessGlobals::get();
+        let cookie_value = cookie_value.or_else(|| {
+            globals
+                .http_cookie_vars()
+                .get(format!("__Host-{}", self.cookie_name).as_str())
+                .and_then(Zval::string)
+        });
+        let form_value = form_value.or_else(|| {
+            globals
+                .http_post_vars()
+                .get(FORM_FIELD)
+                .and_then(Zval::string)
+        });
+        self._verify_double_submit(cookie_value.as_deref(), form_value.as_deref(), unix_time())
+    }
+
+    /// Issues a token carrying encrypted, authenticated claims, e.g. a user id and form id,
+    /// that expires after `ttl` seconds.
+    ///
+    /// The server verifies intent from the token alone with `verifyAndDecode()`; the claims are not
+    /// readable by the client.
+    ///
+    /// # Parameters
+    /// - `claims`: `array` Scalar values keyed by name, e.g. `['uid' => 42, 'form' => 'profile']`.
+    ///
+    /// # Returns
+    /// - `string` Base64URL-encoded token.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if encryption fails.
+    fn issue_with_claims(&self, claims: HashMap<String, Claim>) -> Result<String> {
+        self._issue_with_claims_at(&claims, unix_time())
+    }
+
+    /// Decrypts a token issued by `issueWithClaims()` and returns its claims.
+    ///
+    /// # Parameters
+    /// - `token`: `string` Token from the client.
+    ///
+    /// # Returns
+    /// - `array` The claims.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the token is malformed, was tampered with or has expired.
+    fn verify_and_decode(&self, token: &str) -> Result<HashMap<String, Claim>> {
+        self._verify_and_decode_at(token, unix_time())
+    }
+
+    /// Sets the hosts whose pages may submit requests, checked by `verifyRequest()`.
+    /// By default only the request's own `Host` is allowed.
+    ///
+    /// # Parameters
+    /// - `hosts`: `array` Hostnames, e.g. `['example.com', 'admin.example.com']`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if an entry is not a valid hostname.
+    fn set_allowed_hosts(&mut self, hosts: Vec<String>) -> Result<()> {
+        self.allowed_hosts = hosts
+            .iter()
+            .map(|host| Hostname::_parse(host))
+            .collect::<hostname::Result<_>>()?;
+        Ok(())
+    }
+
+    /// Verifies a state-changing request in one call.
+    ///
+    /// `GET`, `HEAD`, `OPTIONS` and `TRACE` requests pass. For other methods the `Origin` header
+    /// (or, without it, the `Referer`) must name an allowed host (see `setAllowedHosts()`), and the
+    /// `csrf_token` form field or `X-CSRF-Token` header must hold a valid `statelessToken()`.
+    ///
+    /// # Parameters
+    /// - `server`: `array` Usually `$_SERVER`.
+    /// - `post`: `array` Usually `$_POST`.
+    /// - `headers`: `array` Request headers, e.g. `getallheaders()` or a PSR-7 `getHeaders()` array.
+    ///
+    /// # Returns
+    /// - `void` on success.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` whose code identifies the failed check.
+    fn verify_request(
+        &self,
+        server: &ZendHashTable,
+        post: &ZendHashTable,
+        headers: &ZendHashTable,
+    ) -> Result<()> {
+        self._verify_request(
+            &RequestParts::from_tables(server, post, headers),
+            unix_time(),
+        )
+    }
+
     /// Returns the CSRF cookie string to send in PHP.
     ///
     /// # Returns
@@ -274,9 +1277,11 @@ impl Csrf {
 
 #[cfg(test)]
 mod tests {
-    use super::Csrf;
+    use super::{Claim, CookieOptions, Csrf, Error, RequestParts, Scope, token_hash, unix_time};
+    use crate::nonce_store::NonceStore;
     use crate::run_php_example;
     use data_encoding::BASE64URL;
+    use std::collections::HashMap;
 
     /// Helper to generate a Base64URL-encoded 32-byte zero key.
     fn zero_key_b64() -> String {
@@ -349,6 +1354,289 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_stateless_token() -> crate::TestResult {
+        let csrf = Csrf::__construct(&zero_key_b64(), 60, None)?;
+        let token = csrf._stateless_token_at(Scope::Named("checkout"), 1_000);
+
+        assert_eq!(
+            csrf._verify_stateless_at(&token, Scope::Named("checkout"), 60, 1_030)?,
+            1_000
+        );
+        assert!(matches!(
+            csrf._verify_stateless_at(&token, Scope::Named("login"), 60, 1_030),
+            Err(Error::InvalidSignature)
+        ));
+        assert!(matches!(
+            csrf._verify_stateless_at(&token, Scope::Named("checkout"), 60, 1_061),
+            Err(Error::TokenExpired)
+        ));
+        assert!(matches!(
+            csrf._verify_stateless_at(&token, Scope::Named("checkout"), 60, 999),
+            Err(Error::TokenNotYetValid)
+        ));
+
+        // A different key, e.g. another deployment, must not accept the token
+        let other = Csrf::__construct(&Csrf::generate_key(), 60, None)?;
+        assert!(matches!(
+            other._verify_stateless_at(&token, Scope::Named("checkout"), 60, 1_030),
+            Err(Error::InvalidSignature)
+        ));
+        assert!(matches!(
+            csrf._verify_stateless_at("AAAA", Scope::Named("checkout"), 60, 1_030),
+            Err(Error::MalformedToken)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ttl_and_clock_skew() -> crate::TestResult {
+        let mut csrf = Csrf::__construct(&zero_key_b64(), 60, None)?;
+        csrf.set_ttl(120)?;
+        csrf.set_clock_skew(5)?;
+        assert!(matches!(csrf.set_ttl(-1), Err(Error::NegativeDuration(-1))));
+
+        let token = csrf._stateless_token_at(Scope::Action("pay"), 1_000);
+        assert_eq!(csrf._expires_at(&token), Some(1_120));
+        let verify = |now| csrf._verify_stateless_at(&token, Scope::Action("pay"), csrf.ttl, now);
+        verify(996)?;
+        verify(1_125)?;
+        assert!(matches!(verify(994), Err(Error::TokenNotYetValid)));
+        assert!(matches!(verify(1_126), Err(Error::TokenExpired)));
+
+        let claims = csrf._issue_with_claims_at(&HashMap::new(), 1_000)?;
+        assert_eq!(csrf._expires_at(&claims), Some(1_120));
+        csrf._verify_and_decode_at(&claims, 1_125)?;
+        assert!(csrf._verify_and_decode_at(&claims, 1_126).is_err());
+
+        assert_eq!(csrf._expires_at("not a token"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_protection() -> crate::TestResult {
+        let mut csrf = Csrf::__construct(&zero_key_b64(), 60, None)?;
+        csrf.set_clock_skew(5)?;
+        let token = csrf._stateless_token_at(Scope::Action("pay"), 1_000);
+
+        let hash = token_hash(&token);
+        assert_eq!(hash.len(), 64);
+        assert!(!hash.contains(&token));
+        assert_ne!(
+            hash,
+            token_hash(&csrf._stateless_token_at(Scope::Action("pay"), 1_000))
+        );
+
+        assert_eq!(csrf._replay_ttl(&token, 1_010), 55);
+        assert_eq!(csrf._replay_ttl(&token, 2_000), 1);
+        assert_eq!(csrf._replay_ttl("opaque", 1_010), 65);
+
+        assert!(matches!(
+            csrf.mark_used(&token),
+            Err(Error::ReplayStoreNotSet)
+        ));
+
+        let store = NonceStore::_new(None)?;
+        csrf.set_replay_nonce_store(&store);
+        assert!(!csrf.is_used(&token)?);
+        assert!(csrf.mark_used(&token)?);
+        assert!(!csrf.mark_used(&token)?);
+        assert!(csrf.is_used(&token)?);
+        // The token is a consumed nonce of the shared store
+        assert!(store._is_consumed_at(&token, unix_time())?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bind_to() -> crate::TestResult {
+        let mut csrf = Csrf::__construct(&zero_key_b64(), 60, None)?;
+        csrf.bind_to("session-a", None)?;
+        let token = csrf._stateless_token_at(Scope::Action("pay"), 1_000);
+        let claims = csrf._issue_with_claims_at(&HashMap::new(), 1_000)?;
+        let verify = |csrf: &Csrf| {
+            csrf._verify_stateless_at(&token, Scope::Action("pay"), 60, 1_010)
+                .is_ok()
+                && csrf._verify_and_decode_at(&claims, 1_010).is_ok()
+        };
+        assert!(verify(&csrf));
+
+        csrf.bind_to("session-b", None)?;
+        assert!(!verify(&csrf));
+        csrf.bind_to("session-a", Some("ua".into()))?;
+        assert!(!verify(&csrf));
+
+        let unbound = Csrf::__construct(&zero_key_b64(), 60, None)?;
+        assert!(!verify(&unbound));
+        assert!(matches!(csrf.bind_to("", None), Err(Error::EmptySessionId)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_for() -> crate::TestResult {
+        let csrf = Csrf::__construct(&zero_key_b64(), 60, None)?;
+        let token = csrf._stateless_token_at(Scope::Action("delete-account"), 1_000);
+
+        let verify = |scope| csrf._verify_stateless_at(&token, scope, 60, 1_010);
+        verify(Scope::Action("delete-account"))?;
+        assert!(matches!(
+            verify(Scope::Action("change-email")),
+            Err(Error::InvalidSignature)
+        ));
+        // Actions and free-form scopes are separate namespaces
+        assert!(matches!(
+            verify(Scope::Named("delete-account")),
+            Err(Error::InvalidSignature)
+        ));
+        assert!(matches!(
+            verify(Scope::DoubleSubmit),
+            Err(Error::InvalidSignature)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_request() -> crate::TestResult {
+        let mut csrf = Csrf::__construct(&zero_key_b64(), 60, None)?;
+        let token = csrf._stateless_token_at(Scope::Named(""), 1_000);
+        let request = || RequestParts {
+            method: "POST".into(),
+            host: Some("example.com:8443".into()),
+            origin: Some("https://example.com:8443".into()),
+            referer: None,
+            token: Some(token.clone()),
+        };
+        csrf._verify_request(&request(), 1_010)?;
+
+        let get = RequestParts {
+            method: "GET".into(),
+            ..RequestParts::default()
+        };
+        csrf._verify_request(&get, 1_010)?;
+
+        let no_token = RequestParts {
+            token: None,
+            ..request()
+        };
+        assert!(matches!(
+            csrf._verify_request(&no_token, 1_010),
+            Err(Error::TokenMissing)
+        ));
+
+        let cross_site = RequestParts {
+            origin: Some("https://evil.example".into()),
+            ..request()
+        };
+        assert!(matches!(
+            csrf._verify_request(&cross_site, 1_010),
+            Err(Error::OriginNotAllowed(_))
+        ));
+        let opaque = RequestParts {
+            origin: Some("null".into()),
+            ..request()
+        };
+        assert!(csrf._verify_request(&opaque, 1_010).is_err());
+        let referer_only = RequestParts {
+            origin: None,
+            referer: Some("https://example.com/form?step=2".into()),
+            ..request()
+        };
+        csrf._verify_request(&referer_only, 1_010)?;
+        let neither = RequestParts {
+            origin: None,
+            ..request()
+        };
+        assert!(matches!(
+            csrf._verify_request(&neither, 1_010),
+            Err(Error::OriginMissing)
+        ));
+
+        csrf.set_allowed_hosts(vec!["app.example.com".into()])?;
+        assert!(csrf._verify_request(&request(), 1_010).is_err());
+        let app = RequestParts {
+            origin: Some("https://app.example.com".into()),
+            ..request()
+        };
+        csrf._verify_request(&app, 1_010)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_claims() -> crate::TestResult {
+        let csrf = Csrf::__construct(&zero_key_b64(), 60, None)?;
+        let claims = HashMap::from([
+            ("uid".to_string(), Claim::Long(42)),
+            ("form".to_string(), Claim::String("profile".into())),
+            ("admin".to_string(), Claim::Bool(false)),
+            ("score".to_string(), Claim::Double(0.5)),
+            ("note".to_string(), Claim::None),
+        ]);
+        let token = csrf._issue_with_claims_at(&claims, 1_000)?;
+        assert!(!token.contains("profile"));
+        assert_eq!(csrf._verify_and_decode_at(&token, 1_060)?, claims);
+        assert!(matches!(
+            csrf._verify_and_decode_at(&token, 1_061),
+            Err(Error::TokenExpired)
+        ));
+
+        let other = Csrf::__construct(&Csrf::generate_key(), 60, None)?;
+        assert!(matches!(
+            other._verify_and_decode_at(&token, 1_010),
+            Err(Error::InvalidSignature)
+        ));
+        // A stateless token is not a claims token, and vice versa
+        let stateless = csrf._stateless_token_at(Scope::Named(""), 1_000);
+        assert!(csrf._verify_and_decode_at(&stateless, 1_010).is_err());
+        assert!(
+            csrf._verify_stateless_at(&token, Scope::Named(""), 60, 1_010)
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_double_submit() -> crate::TestResult {
+        let csrf = Csrf::__construct(&zero_key_b64(), 60, None)?;
+        let token = csrf._stateless_token_at(Scope::DoubleSubmit, 1_000);
+
+        assert!(csrf._verify_double_submit(Some(&token), Some(&token), 1_010));
+        assert!(!csrf._verify_double_submit(Some(&token), None, 1_010));
+        assert!(!csrf._verify_double_submit(None, Some(&token), 1_010));
+        assert!(!csrf._verify_double_submit(Some(&token), Some(&token), 1_100));
+
+        // Matching values are not enough, the token must be signed for the double-submit scope
+        let other = csrf._stateless_token_at(Scope::Named("checkout"), 1_000);
+        assert!(!csrf._verify_double_submit(Some(&other), Some(&other), 1_010));
+        let forged = "forged-by-subdomain";
+        assert!(!csrf._verify_double_submit(Some(forged), Some(forged), 1_010));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cookie_options() {
+        let options = CookieOptions::default();
+        assert_eq!(
+            options.set_cookie("csrf", "t0k", 1_000),
+            "__Host-csrf=t0k; Path=/; Secure; HttpOnly; SameSite=Strict"
+        );
+
+        let options = CookieOptions {
+            expires: 4_600,
+            domain: "example.com".into(),
+            same_site: "Lax",
+            ..CookieOptions::default()
+        };
+        assert_eq!(
+            options.set_cookie("csrf", "t0k", 1_000),
+            "__Secure-csrf=t0k; Path=/; Domain=example.com; Max-Age=3600; Secure; HttpOnly; SameSite=Lax"
+        );
+
+        let options = CookieOptions {
+            secure: false,
+            ..CookieOptions::default()
+        };
+        assert_eq!(options.prefix(), "");
+    }
+
     #[test]
     fn php_example() -> crate::TestResult {
         run_php_example("csrf-protection")?;
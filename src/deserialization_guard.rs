@@ -0,0 +1,205 @@
+//! Cheap detection of serialized PHP objects in untrusted input, as a pre-filter for legacy
+//! code that still passes cookies or form fields to `unserialize()`.
+//!
+//! Only constructs that instantiate a class are looked for: `O:` (objects), `C:` (custom
+//! `Serializable` objects) and `E:` (enum cases, which still trigger autoloading). Arrays and
+//! scalars are left alone, and so is the rest of the input: a match anywhere counts, including
+//! inside array payloads and inside base64-encoded layers.
+
+use ext_php_rs::binary::Binary;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use thiserror::Error;
+
+// Error codes for DeserializationGuard errors: 4600-4699
+pub mod error_codes {
+    pub const SERIALIZED_OBJECT: i32 = 4600;
+}
+
+/// Errors produced by `DeserializationGuard`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Input contains a serialized PHP object")]
+    SerializedObject,
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::SerializedObject => error_codes::SERIALIZED_OBJECT,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for deserialization guard operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// How many base64 layers are decoded below the input itself.
+const MAX_BASE64_LAYERS: usize = 3;
+
+/// Shortest base64 run worth decoding: 8 characters hold the 6 bytes of `O:1:"a`.
+const MIN_BASE64_RUN: usize = 8;
+
+/// Whether an object construct starts at `input[0]`: `O`, `C` or `E`, then `:`, an optional
+/// `+` (which `unserialize()` accepts and naive filters miss), digits, `:` and `"`.
+fn object_at(input: &[u8]) -> bool {
+    let [b'O' | b'C' | b'E', b':', rest @ ..] = input else {
+        return false;
+    };
+    let rest = rest.strip_prefix(b"+").unwrap_or(rest);
+    let digits = rest.iter().take_while(|byte| byte.is_ascii_digit()).count();
+    digits > 0 && rest[digits..].starts_with(b":\"")
+}
+
+/// Value of a base64 character in either the standard or the URL-safe alphabet.
+fn base64_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'A'..=b'Z' => Some(byte - b'A'),
+        b'a'..=b'z' => Some(byte - b'a' + 26),
+        b'0'..=b'9' => Some(byte - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+/// Decodes a run of base64 characters, padding optional; a dangling last character is ignored.
+fn decode_base64(run: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(run.len() / 4 * 3 + 2);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for value in run.iter().filter_map(|byte| base64_value(*byte)) {
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    out
+}
+
+/// Scans `input` and, up to `layers` deep, every base64 run in it.
+fn scan(input: &[u8], layers: usize) -> bool {
+    if (0..input.len()).any(|start| object_at(&input[start..])) {
+        return true;
+    }
+    layers > 0
+        && input
+            .split(|byte| base64_value(*byte).is_none())
+            .filter(|run| run.len() >= MIN_BASE64_RUN)
+            .any(|run| scan(&decode_base64(run), layers - 1))
+}
+
+/// Whether `input` contains a serialized object, directly or base64-encoded.
+#[must_use]
+pub fn contains_serialized_object(input: &[u8]) -> bool {
+    scan(input, MAX_BASE64_LAYERS)
+}
+
+/// Detection of serialized PHP objects in untrusted input.
+#[php_class]
+#[php(name = "Hardened\\DeserializationGuard")]
+pub struct DeserializationGuard {}
+
+#[php_impl]
+impl DeserializationGuard {
+    /// Check whether input contains a serialized object, class or enum construct.
+    ///
+    /// Looks for `O:`, `C:` and `E:` anywhere in the input, so constructs nested in arrays
+    /// are found, and in every base64 run (standard or URL-safe alphabet) up to three layers
+    /// deep. A match is not proof of an attack, only that `unserialize()` without
+    /// `allowed_classes => false` must not see the input.
+    ///
+    /// # Parameters
+    /// - `input`: `string` Cookie, form field or other untrusted value.
+    ///
+    /// # Returns
+    /// - `bool`
+    fn contains_serialized_object(input: Binary<u8>) -> bool {
+        contains_serialized_object(&input)
+    }
+
+    /// Throw if input contains a serialized object, class or enum construct.
+    ///
+    /// # Parameters
+    /// - `input`: `string` Value about to be passed to `unserialize()`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `containsSerializedObject()` would return `true`.
+    fn assert_scalar_only(input: Binary<u8>) -> Result<()> {
+        if contains_serialized_object(&input) {
+            return Err(Error::SerializedObject);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contains_serialized_object, decode_base64};
+
+    #[test]
+    fn test_plain() {
+        for input in [
+            &b"O:8:\"stdClass\":0:{}"[..],
+            b"O:+8:\"stdClass\":0:{}",
+            b"C:11:\"ArrayObject\":21:{x:i:0;a:0:{};m:a:0:{}}",
+            b"E:7:\"Foo:Bar\";",
+            b"a:1:{s:4:\"user\";a:1:{i:0;O:4:\"User\":1:{s:5:\"admin\";b:1;}}}",
+        ] {
+            assert!(
+                contains_serialized_object(input),
+                "{}",
+                String::from_utf8_lossy(input)
+            );
+        }
+        for input in [
+            &b""[..],
+            b"a:2:{i:0;s:3:\"foo\";i:1;d:1.5;}",
+            b"O: 5 apples",
+            b"O:x:\"a\"",
+            b"O::\"a\"",
+            b"O:8",
+            b"o:8:\"stdClass\":0:{}",
+        ] {
+            assert!(
+                !contains_serialized_object(input),
+                "{}",
+                String::from_utf8_lossy(input)
+            );
+        }
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(decode_base64(b"Tzo4Og"), b"O:8:");
+        assert_eq!(decode_base64(b"Tzo4Og=="), b"O:8:");
+        assert!(contains_serialized_object(
+            b"session=YToxOntzOjQ6InVzZXIiO086ODoic3RkQ2xhc3MiOjA6e319; path=/"
+        ));
+        // URL-safe alphabet
+        assert!(contains_serialized_object(
+            b"TzorODoic3RkQ2xhc3MiOjA6e30-Pj8="
+        ));
+        // Double encoding
+        assert!(contains_serialized_object(
+            b"UXpveE1Ub2lRWEp5WVhsUFltcGxZM1FpT2pBNmUzMD0="
+        ));
+        // An array whose string happens to contain "O:8:x"
+        assert!(!contains_serialized_object(
+            b"YToxOntpOjA7czo1OiJPOjg6eCI7fQ=="
+        ));
+        assert!(!contains_serialized_object(b"Hello, world! 0123456789"));
+    }
+}
@@ -0,0 +1,139 @@
+//! Blocking DNS lookups with a deadline.
+//!
+//! The system resolver has no per-call timeout, so each lookup runs on a helper thread
+//! and is abandoned (not cancelled) once the deadline passes.
+
+use std::net::{IpAddr, ToSocketAddrs};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Outcome of a lookup, telling a negative answer apart from no answer at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Lookup<T> {
+    /// The resolver found a record.
+    Found(T),
+    /// The resolver answered that there is no record.
+    NotFound,
+    /// The lookup failed or timed out, so the answer is unknown.
+    Failed,
+}
+
+/// Runs `f` on a helper thread and waits at most `timeout` for its result.
+fn with_timeout<T: Send + 'static>(
+    timeout: Duration,
+    f: impl FnOnce() -> T + Send + 'static,
+) -> Option<T> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(f());
+    });
+    receiver.recv_timeout(timeout).ok()
+}
+
+/// Resolves `host` to its addresses, or `None` on failure or timeout.
+pub(crate) fn forward_lookup(host: &str, timeout: Duration) -> Option<Vec<IpAddr>> {
+    let host = host.to_string();
+    with_timeout(timeout, move || {
+        (host.as_str(), 0)
+            .to_socket_addrs()
+            .ok()
+            .map(|addrs| addrs.map(|addr| addr.ip()).collect())
+    })
+    .flatten()
+}
+
+/// Looks up the PTR name of `ip`, lowercased and without the trailing dot.
+pub(crate) fn reverse_lookup(ip: IpAddr, timeout: Duration) -> Lookup<String> {
+    with_timeout(timeout, move || getnameinfo(ip)).unwrap_or(Lookup::Failed)
+}
+
+#[cfg(unix)]
+fn getnameinfo(ip: IpAddr) -> Lookup<String> {
+    use std::ffi::CStr;
+    use std::mem::{size_of, zeroed};
+
+    const NI_MAXHOST: usize = 1025;
+    let mut host = [0 as libc::c_char; NI_MAXHOST];
+    // SAFETY: the socket addresses are fully initialized (zeroed, then filled in), the lengths
+    // passed match the buffers, and getnameinfo() NUL-terminates `host` on success.
+    let ret = unsafe {
+        match ip {
+            IpAddr::V4(v4) => {
+                let mut addr: libc::sockaddr_in = zeroed();
+                addr.sin_family = libc::AF_INET as libc::sa_family_t;
+                addr.sin_addr = libc::in_addr {
+                    s_addr: u32::from_ne_bytes(v4.octets()),
+                };
+                libc::getnameinfo(
+                    (&raw const addr).cast(),
+                    size_of::<libc::sockaddr_in>() as libc::socklen_t,
+                    host.as_mut_ptr(),
+                    NI_MAXHOST as libc::socklen_t,
+                    std::ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+            IpAddr::V6(v6) => {
+                let mut addr: libc::sockaddr_in6 = zeroed();
+                addr.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+                addr.sin6_addr = libc::in6_addr {
+                    s6_addr: v6.octets(),
+                };
+                libc::getnameinfo(
+                    (&raw const addr).cast(),
+                    size_of::<libc::sockaddr_in6>() as libc::socklen_t,
+                    host.as_mut_ptr(),
+                    NI_MAXHOST as libc::socklen_t,
+                    std::ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+        }
+    };
+    match ret {
+        0 => {}
+        libc::EAI_NONAME => return Lookup::NotFound,
+        _ => return Lookup::Failed,
+    }
+    // SAFETY: see above
+    let name = unsafe { CStr::from_ptr(host.as_ptr()) };
+    name.to_str()
+        .ok()
+        .map(|name| name.trim_end_matches('.').to_ascii_lowercase())
+        .filter(|name| !name.is_empty())
+        .map_or(Lookup::NotFound, Lookup::Found)
+}
+
+#[cfg(not(unix))]
+fn getnameinfo(_ip: IpAddr) -> Lookup<String> {
+    Lookup::Failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{forward_lookup, with_timeout};
+    use std::time::Duration;
+
+    #[test]
+    fn test_with_timeout() {
+        assert_eq!(with_timeout(Duration::from_secs(1), || 42), Some(42));
+        assert_eq!(
+            with_timeout(Duration::from_millis(10), || {
+                std::thread::sleep(Duration::from_millis(200));
+                42
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_forward_lookup_ip_literal() {
+        assert_eq!(
+            forward_lookup("127.0.0.1", Duration::from_secs(1)),
+            Some(vec!["127.0.0.1".parse().unwrap()])
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! Context-aware output encoding following the OWASP XSS Prevention Cheat Sheet rules.
+//!
+//! Each encoder is for exactly one context: HTML text, quoted or unquoted attribute values,
+//! JavaScript string literals, CSS values, URL components and HTML comments. Invalid UTF-8 is
+//! replaced with U+FFFD before encoding, so no byte sequence can smuggle a delimiter through.
+
+use ext_php_rs::binary::Binary;
+use ext_php_rs::{php_class, php_impl};
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// Replacement for characters that are never valid in HTML: C0 controls other than
+/// whitespace, DEL, C1 controls and noncharacters.
+const REPLACEMENT: char = '\u{FFFD}';
+
+/// Whether a character is undefined in HTML and must be replaced, not encoded.
+fn is_html_undefined(c: char) -> bool {
+    matches!(c, '\0'..='\x08' | '\x0B' | '\x0E'..='\x1F' | '\x7F'..='\u{9F}')
+        || matches!(u32::from(c), 0xFDD0..=0xFDEF)
+        || u32::from(c) & 0xFFFE == 0xFFFE
+}
+
+/// Decodes bytes as UTF-8, replacing invalid sequences with U+FFFD.
+fn text(bytes: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(bytes)
+}
+
+/// Encodes text for an HTML element body.
+#[must_use]
+pub fn html(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#x27;"),
+            c if is_html_undefined(c) => out.push(REPLACEMENT),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Encodes text for an HTML attribute value, quoted or not: everything but alphanumerics
+/// and `,.-_` becomes an entity.
+#[must_use]
+pub fn html_attr(input: &str) -> String {
+    let mut out = String::with_capacity(input.len() * 2);
+    for c in input.chars() {
+        match c {
+            c if c.is_ascii_alphanumeric() || matches!(c, ',' | '.' | '-' | '_') => out.push(c),
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c if is_html_undefined(c) => out.push_str("&#xFFFD;"),
+            // Non-ASCII characters cannot break out of an attribute
+            c if !c.is_ascii() => out.push(c),
+            c => {
+                let _ = write!(out, "&#x{:02X};", u32::from(c));
+            }
+        }
+    }
+    out
+}
+
+/// Encodes text for a JavaScript string literal (single- or double-quoted, or a template
+/// literal), including inside an HTML `<script>` block or event handler attribute.
+#[must_use]
+pub fn js(input: &str) -> String {
+    let mut out = String::with_capacity(input.len() * 2);
+    for c in input.chars() {
+        if c.is_ascii_alphanumeric() || matches!(c, ',' | '.' | '_') {
+            out.push(c);
+            continue;
+        }
+        let mut units = [0u16; 2];
+        for unit in c.encode_utf16(&mut units) {
+            if *unit < 0x100 {
+                let _ = write!(out, "\\x{unit:02X}");
+            } else {
+                let _ = write!(out, "\\u{unit:04X}");
+            }
+        }
+    }
+    out
+}
+
+/// Encodes text for a CSS property value or string, using `\HEX ` escapes.
+#[must_use]
+pub fn css(input: &str) -> String {
+    let mut out = String::with_capacity(input.len() * 2);
+    for c in input.chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c);
+        } else if c == '\0' {
+            out.push_str("\\FFFD ");
+        } else {
+            // The trailing space ends the escape, so a following hex digit is not absorbed
+            let _ = write!(out, "\\{:X} ", u32::from(c));
+        }
+    }
+    out
+}
+
+/// Percent-encodes every byte except RFC 3986 unreserved characters, like `rawurlencode()`.
+#[must_use]
+pub fn url(input: &[u8]) -> String {
+    let mut out = String::with_capacity(input.len() * 3);
+    for &byte in input {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(char::from(byte));
+        } else {
+            let _ = write!(out, "%{byte:02X}");
+        }
+    }
+    out
+}
+
+/// Makes text safe inside `<!-- … -->`: no `--` run, no leading `>`/`->`, no trailing `-`,
+/// so the comment can neither close early nor be closed by what follows it.
+#[must_use]
+pub fn html_comment(input: &str) -> String {
+    let mut out = String::with_capacity(input.len() + 2);
+    if input.starts_with(['>', '-']) {
+        out.push(' ');
+    }
+    let mut previous = None;
+    for c in input.chars() {
+        if c == '-' && previous == Some('-') {
+            out.push(' ');
+        }
+        if is_html_undefined(c) {
+            out.push(REPLACEMENT);
+        } else {
+            out.push(c);
+        }
+        previous = Some(c);
+    }
+    if out.ends_with(['-', '<']) || out.ends_with("<!") {
+        out.push(' ');
+    }
+    out
+}
+
+/// Context-specific output encoders.
+#[php_class]
+#[php(name = "Hardened\\Escape")]
+pub struct Escape {}
+
+#[php_impl]
+impl Escape {
+    /// Encode text for an HTML element body, like `htmlspecialchars($s, ENT_QUOTES)`.
+    ///
+    /// # Parameters
+    /// - `input`: `string`
+    ///
+    /// # Returns
+    /// - `string` `&`, `<`, `>`, `"` and `'` as entities; control characters replaced
+    ///   with U+FFFD.
+    fn html(input: Binary<u8>) -> String {
+        html(&text(&input))
+    }
+
+    /// Encode text for an HTML attribute value.
+    ///
+    /// Safe in quoted and unquoted attributes alike, but not in `href`/`src` (validate the
+    /// URL first), `style` or event handler attributes (use `css()` or `js()`).
+    ///
+    /// # Parameters
+    /// - `input`: `string`
+    ///
+    /// # Returns
+    /// - `string` Everything but alphanumerics, `,.-_` and non-ASCII letters as entities.
+    fn html_attr(input: Binary<u8>) -> String {
+        html_attr(&text(&input))
+    }
+
+    /// Encode text for a quoted JavaScript string.
+    ///
+    /// # Parameters
+    /// - `input`: `string`
+    ///
+    /// # Returns
+    /// - `string` Everything but alphanumerics and `,._` as `\xHH` or `\uHHHH` escapes.
+    fn js(input: Binary<u8>) -> String {
+        js(&text(&input))
+    }
+
+    /// Encode text for a CSS property value or quoted CSS string.
+    ///
+    /// # Parameters
+    /// - `input`: `string`
+    ///
+    /// # Returns
+    /// - `string` Everything but alphanumerics as `\HEX ` escapes.
+    fn css(input: Binary<u8>) -> String {
+        css(&text(&input))
+    }
+
+    /// Encode a URL component (a path segment or query parameter), like `rawurlencode()`.
+    ///
+    /// # Parameters
+    /// - `input`: `string`
+    ///
+    /// # Returns
+    /// - `string` Everything but `A-Za-z0-9-._~` percent-encoded.
+    fn url(input: Binary<u8>) -> String {
+        url(&input)
+    }
+
+    /// Neutralize text for an HTML comment, e.g. debug output.
+    ///
+    /// # Parameters
+    /// - `input`: `string`
+    ///
+    /// # Returns
+    /// - `string` The text with `--` runs split and dangerous edges padded.
+    fn html_comment(input: Binary<u8>) -> String {
+        html_comment(&text(&input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{css, html, html_attr, html_comment, js, text, url};
+
+    #[test]
+    fn test_html() {
+        assert_eq!(
+            html("<a href=\"x\">Tom & 'Jerry'</a>"),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#x27;Jerry&#x27;&lt;/a&gt;"
+        );
+        assert_eq!(html("caf\u{e9}\n\u{1}"), "caf\u{e9}\n\u{FFFD}");
+        assert_eq!(text(b"a\xFFb"), "a\u{FFFD}b");
+    }
+
+    #[test]
+    fn test_html_attr() {
+        assert_eq!(
+            html_attr("x onmouseover=alert(1)"),
+            "x&#x20;onmouseover&#x3D;alert&#x28;1&#x29;"
+        );
+        assert_eq!(html_attr("a-b_c.d,e"), "a-b_c.d,e");
+        assert_eq!(html_attr("\"'&`"), "&quot;&#x27;&amp;&#x60;");
+        assert_eq!(html_attr("\u{e9}\u{7f}"), "\u{e9}&#xFFFD;");
+    }
+
+    #[test]
+    fn test_js() {
+        assert_eq!(
+            js("</script><script>alert('x')"),
+            "\\x3C\\x2Fscript\\x3E\\x3Cscript\\x3Ealert\\x28\\x27x\\x27\\x29"
+        );
+        assert_eq!(js("a\u{2028}\u{1F600}"), "a\\u2028\\uD83D\\uDE00");
+        assert_eq!(js("${x}`"), "\\x24\\x7Bx\\x7D\\x60");
+    }
+
+    #[test]
+    fn test_css() {
+        assert_eq!(css("red;} body{x"), "red\\3B \\7D \\20 body\\7B x");
+        assert_eq!(css("url(\"a\")"), "url\\28 \\22 a\\22 \\29 ");
+    }
+
+    #[test]
+    fn test_url() {
+        assert_eq!(url(b"a b/c?d=e&f"), "a%20b%2Fc%3Fd%3De%26f");
+        assert_eq!(url("\u{e9}-._~".as_bytes()), "%C3%A9-._~");
+    }
+
+    #[test]
+    fn test_html_comment() {
+        assert_eq!(html_comment("a --> b"), "a - -> b");
+        assert_eq!(html_comment("->x"), " ->x");
+        assert_eq!(html_comment(">x"), " >x");
+        assert_eq!(html_comment("x-"), "x- ");
+        assert_eq!(html_comment("x<!"), "x<! ");
+        assert_eq!(html_comment("a---!>"), "a- - -!>");
+        assert_eq!(html_comment("plain text"), "plain text");
+    }
+}
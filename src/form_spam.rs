@@ -0,0 +1,483 @@
+//! Bot protection for public forms: a randomly named honeypot field, a signed render time
+//! that enforces a minimum fill time, and a heuristic spam score.
+//!
+//! This complements CSRF protection: a bot posting from its own session passes CSRF checks,
+//! but tends to fill every field and to submit faster than a person can type.
+
+use crate::to_str;
+use data_encoding::{BASE64URL_NOPAD, HEXLOWER};
+use ext_php_rs::binary::Binary;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::types::ZendHashTable;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+// Error codes for FormSpam errors: 3300-3399
+pub mod error_codes {
+    pub const KEY_TOO_SHORT: i32 = 3300;
+    pub const INVALID_OPTION: i32 = 3301;
+}
+
+/// Errors produced by `FormSpam`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Key must be at least 32 bytes, got {0}")]
+    KeyTooShort(usize),
+
+    #[error("Invalid option: {0}")]
+    InvalidOption(String),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::KeyTooShort(_) => error_codes::KEY_TOO_SHORT,
+            Error::InvalidOption(_) => error_codes::INVALID_OPTION,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for form spam operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+const MIN_KEY_LEN: usize = 32;
+/// Name of the hidden field carrying the signed token.
+const TOKEN_FIELD: &str = "_form_token";
+/// Bases for honeypot names; autofilling bots go for plausible ones.
+const HONEYPOT_WORDS: &[&str] = &[
+    "website",
+    "homepage",
+    "url",
+    "company",
+    "fax",
+    "phone2",
+    "address2",
+    "nickname",
+    "middle_name",
+    "subject2",
+];
+/// How deep nested form arrays are read.
+const MAX_DEPTH: usize = 8;
+
+/// Current UNIX time in seconds.
+fn unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+/// Why a submission was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    Accepted,
+    InvalidToken,
+    Expired,
+    TooFast,
+    HoneypotMissing,
+    HoneypotFilled,
+}
+
+/// Request details used by `score()` besides the posted fields.
+#[derive(Debug, Default)]
+struct Meta {
+    form: String,
+    user_agent: Option<String>,
+    accept_language: Option<String>,
+}
+
+impl Meta {
+    fn from_table(table: &ZendHashTable) -> Self {
+        let get = |name: &str| table.get(name).and_then(|value| to_str(value).ok());
+        Self {
+            form: get("form").unwrap_or_default(),
+            user_agent: get("user_agent"),
+            accept_language: get("accept_language"),
+        }
+    }
+}
+
+/// Flattens posted fields into `name` / `name[key]` pairs with string values.
+fn flatten(
+    table: &ZendHashTable,
+    prefix: Option<&str>,
+    depth: usize,
+    out: &mut Vec<(String, String)>,
+) {
+    for (key, value) in table.iter() {
+        let name = match prefix {
+            Some(prefix) => format!("{prefix}[{key}]"),
+            None => key.to_string(),
+        };
+        if let Some(array) = value.array() {
+            if depth < MAX_DEPTH {
+                flatten(array, Some(&name), depth + 1, out);
+            }
+        } else if let Ok(value) = to_str(value) {
+            out.push((name, value));
+        }
+    }
+}
+
+/// Counts link-like substrings.
+fn count_links(text: &str) -> usize {
+    let lower = text.to_ascii_lowercase();
+    ["http://", "https://", "www."]
+        .iter()
+        .map(|needle| lower.matches(needle).count())
+        .sum()
+}
+
+/// Whether text is long and mostly uppercase letters.
+fn is_shouting(text: &str) -> bool {
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    letters.len() >= 20
+        && letters.iter().filter(|c| c.is_uppercase()).count() * 10 >= letters.len() * 7
+}
+
+/// Honeypot fields and signed render times for public forms.
+#[php_class]
+#[php(name = "Hardened\\FormSpam")]
+pub struct FormSpam {
+    key: Vec<u8>,
+    minimum_fill_time: i64,
+    max_age: i64,
+}
+
+impl FormSpam {
+    /// Backs `__construct()`.
+    fn _new(key: &[u8]) -> Result<Self> {
+        if key.len() < MIN_KEY_LEN {
+            return Err(Error::KeyTooShort(key.len()));
+        }
+        Ok(Self {
+            key: key.to_vec(),
+            minimum_fill_time: 3,
+            max_age: 7200,
+        })
+    }
+
+    fn _tag(&self, unsigned: &str, form: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(unsigned.as_bytes());
+        mac.update(&[0]);
+        mac.update(form.as_bytes());
+        mac
+    }
+
+    /// Picks a honeypot name and signs it with the render time; returns `(honeypot, token)`.
+    fn _issue(&self, form: &str, now: i64) -> (String, String) {
+        let random = rand::random::<[u8; 4]>();
+        let word = HONEYPOT_WORDS[usize::from(random[0]) % HONEYPOT_WORDS.len()];
+        let honeypot = format!("{word}_{}", HEXLOWER.encode(&random[1..]));
+        let unsigned = format!("v1.{now}.{honeypot}");
+        let tag = BASE64URL_NOPAD.encode(&self._tag(&unsigned, form).finalize().into_bytes());
+        (honeypot, format!("{unsigned}.{tag}"))
+    }
+
+    /// Checks the token and honeypot of a submission as of `now`.
+    fn _check(&self, post: &[(String, String)], form: &str, now: i64) -> Verdict {
+        let field = |name: &str| {
+            post.iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.as_str())
+        };
+        let Some(token) = field(TOKEN_FIELD) else {
+            return Verdict::InvalidToken;
+        };
+        let Some((unsigned, tag)) = token.rsplit_once('.') else {
+            return Verdict::InvalidToken;
+        };
+        let authentic = BASE64URL_NOPAD
+            .decode(tag.as_bytes())
+            .is_ok_and(|tag| self._tag(unsigned, form).verify_slice(&tag).is_ok());
+        let mut fields = unsigned.splitn(3, '.').skip(1);
+        let (true, Some(Ok(issued)), Some(honeypot)) = (
+            authentic,
+            fields.next().map(str::parse::<i64>),
+            fields.next(),
+        ) else {
+            return Verdict::InvalidToken;
+        };
+        let elapsed = now.saturating_sub(issued);
+        if elapsed > self.max_age {
+            return Verdict::Expired;
+        }
+        if elapsed < self.minimum_fill_time {
+            return Verdict::TooFast;
+        }
+        match field(honeypot) {
+            None => Verdict::HoneypotMissing,
+            Some(value) if !value.trim().is_empty() => Verdict::HoneypotFilled,
+            Some(_) => Verdict::Accepted,
+        }
+    }
+
+    /// Spam score from 0 (clean) to 100.
+    fn _score(&self, post: &[(String, String)], meta: &Meta, now: i64) -> i64 {
+        let mut score = match self._check(post, &meta.form, now) {
+            Verdict::Accepted => 0,
+            Verdict::InvalidToken | Verdict::HoneypotFilled => 100,
+            Verdict::TooFast => 60,
+            Verdict::HoneypotMissing => 30,
+            Verdict::Expired => 20,
+        };
+        let values: Vec<&str> = post
+            .iter()
+            .filter(|(name, _)| name != TOKEN_FIELD)
+            .map(|(_, value)| value.as_str())
+            .collect();
+
+        score += match values.iter().map(|value| count_links(value)).sum::<usize>() {
+            0 => 0,
+            1 => 5,
+            2..=3 => 20,
+            _ => 40,
+        };
+        let lower: Vec<String> = values
+            .iter()
+            .map(|value| value.to_ascii_lowercase())
+            .collect();
+        if lower
+            .iter()
+            .any(|value| value.contains("[url") || value.contains("[link"))
+        {
+            score += 25;
+        }
+        if lower
+            .iter()
+            .any(|value| value.contains("<a ") || value.contains("href="))
+        {
+            score += 25;
+        }
+        if values.iter().any(|value| is_shouting(value)) {
+            score += 10;
+        }
+        if values.iter().any(|value| value.len() > 5000) {
+            score += 10;
+        }
+        // Bots often paste the same text into every field
+        let repeated = values.iter().any(|value| {
+            value.trim().len() >= 3 && values.iter().filter(|other| *other == value).count() >= 3
+        });
+        if repeated {
+            score += 15;
+        }
+        if meta
+            .user_agent
+            .as_ref()
+            .is_some_and(|agent| agent.trim().is_empty())
+        {
+            score += 20;
+        }
+        if meta
+            .accept_language
+            .as_ref()
+            .is_some_and(|language| language.trim().is_empty())
+        {
+            score += 10;
+        }
+        score.min(100)
+    }
+}
+
+#[php_impl]
+impl FormSpam {
+    /// Constructs a form protector.
+    ///
+    /// # Parameters
+    /// - `key`: `string` Secret of at least 32 bytes.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the key is too short.
+    fn __construct(key: Binary<u8>) -> Result<Self> {
+        Self::_new(&key)
+    }
+
+    /// Set the least time a person needs to fill in the form.
+    ///
+    /// # Parameters
+    /// - `seconds`: `int` Default: `3`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `seconds` is negative.
+    fn minimum_fill_time(&mut self, seconds: i64) -> Result<()> {
+        if seconds < 0 {
+            return Err(Error::InvalidOption(format!(
+                "Minimum fill time must not be negative, got {seconds}"
+            )));
+        }
+        self.minimum_fill_time = seconds;
+        Ok(())
+    }
+
+    /// Set how long a rendered form stays valid.
+    ///
+    /// # Parameters
+    /// - `seconds`: `int` Default: `7200`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `seconds` is not positive.
+    fn max_age(&mut self, seconds: i64) -> Result<()> {
+        if seconds <= 0 {
+            return Err(Error::InvalidOption(format!(
+                "Maximum age must be positive, got {seconds}"
+            )));
+        }
+        self.max_age = seconds;
+        Ok(())
+    }
+
+    /// Prepare the hidden fields for a form.
+    ///
+    /// # Parameters
+    /// - `form`: `?string` Form identifier the token is bound to. Default: `''`.
+    ///
+    /// # Returns
+    /// - `array` `honeypot` (name of the text field to hide from people), `token_field`
+    ///   and `token` (the hidden input carrying the signed render time).
+    fn issue(&self, form: Option<String>) -> HashMap<&'static str, String> {
+        let (honeypot, token) = self._issue(form.as_deref().unwrap_or_default(), unix_time());
+        HashMap::from([
+            ("honeypot", honeypot),
+            ("token_field", TOKEN_FIELD.into()),
+            ("token", token),
+        ])
+    }
+
+    /// Render the hidden fields as HTML.
+    ///
+    /// # Parameters
+    /// - `form`: `?string` Form identifier the token is bound to. Default: `''`.
+    ///
+    /// # Returns
+    /// - `string` A hidden token input and an off-screen honeypot input.
+    fn fields(&self, form: Option<String>) -> String {
+        let (honeypot, token) = self._issue(form.as_deref().unwrap_or_default(), unix_time());
+        // Both values are alphanumerics, `_`, `-` and `.`, so no escaping is needed
+        format!(
+            "<input type=\"hidden\" name=\"{TOKEN_FIELD}\" value=\"{token}\">\
+             <input type=\"text\" name=\"{honeypot}\" value=\"\" autocomplete=\"off\" tabindex=\"-1\" \
+             aria-hidden=\"true\" style=\"position:absolute;left:-10000px\">"
+        )
+    }
+
+    /// Check a submission's token and honeypot.
+    ///
+    /// # Parameters
+    /// - `post`: `array` The submitted fields, e.g. `$_POST`.
+    /// - `form`: `?string` Form identifier given to `issue()`. Default: `''`.
+    ///
+    /// # Returns
+    /// - `bool` `false` if the token is forged or expired, the form was submitted faster
+    ///   than the minimum fill time, or the honeypot is missing or filled in.
+    fn check(&self, post: &ZendHashTable, form: Option<String>) -> bool {
+        let mut fields = Vec::new();
+        flatten(post, None, 0, &mut fields);
+        self._check(&fields, form.as_deref().unwrap_or_default(), unix_time()) == Verdict::Accepted
+    }
+
+    /// Estimate how likely a submission is spam.
+    ///
+    /// Combines `check()` with content heuristics: links, BBCode and HTML links, shouting,
+    /// oversized values, the same text in several fields, and empty request headers.
+    ///
+    /// # Parameters
+    /// - `post`: `array` The submitted fields, e.g. `$_POST`.
+    /// - `meta`: `array` Optional `form`, `user_agent` and `accept_language` values; the
+    ///   headers only count against the submission when passed and empty.
+    ///
+    /// # Returns
+    /// - `int` Score from 0 (looks clean) to 100 (certainly spam).
+    fn score(&self, post: &ZendHashTable, meta: &ZendHashTable) -> i64 {
+        let mut fields = Vec::new();
+        flatten(post, None, 0, &mut fields);
+        self._score(&fields, &Meta::from_table(meta), unix_time())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormSpam, Meta, TOKEN_FIELD, Verdict};
+
+    fn post(fields: &[(&str, &str)]) -> Vec<(String, String)> {
+        fields
+            .iter()
+            .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_check() {
+        let spam = FormSpam::_new(&[1; 32]).unwrap();
+        let (honeypot, token) = spam._issue("contact", 1000);
+        let human = post(&[(TOKEN_FIELD, &token), (&honeypot, ""), ("message", "Hi")]);
+        assert_eq!(spam._check(&human, "contact", 1010), Verdict::Accepted);
+        assert_eq!(spam._check(&human, "contact", 1001), Verdict::TooFast);
+        assert_eq!(spam._check(&human, "contact", 9000), Verdict::Expired);
+        assert_eq!(spam._check(&human, "signup", 1010), Verdict::InvalidToken);
+
+        let bot = post(&[(TOKEN_FIELD, &token), (&honeypot, "http://spam")]);
+        assert_eq!(spam._check(&bot, "contact", 1010), Verdict::HoneypotFilled);
+        let stripped = post(&[(TOKEN_FIELD, &token)]);
+        assert_eq!(
+            spam._check(&stripped, "contact", 1010),
+            Verdict::HoneypotMissing
+        );
+
+        // The honeypot name is signed too
+        let renamed = token.replace(&honeypot, "website_000000");
+        let forged = post(&[(TOKEN_FIELD, &renamed), ("website_000000", "")]);
+        assert_eq!(spam._check(&forged, "contact", 1010), Verdict::InvalidToken);
+    }
+
+    #[test]
+    fn test_score() {
+        let spam = FormSpam::_new(&[1; 32]).unwrap();
+        let (honeypot, token) = spam._issue("", 1000);
+        let meta = Meta::default();
+        let clean = post(&[
+            (TOKEN_FIELD, &token),
+            (&honeypot, ""),
+            (
+                "message",
+                "Do you ship to Norway? See https://example.com/order/1",
+            ),
+        ]);
+        assert_eq!(spam._score(&clean, &meta, 1010), 5);
+
+        let links = "BUY NOW <a href=\"http://a.example\">cheap</a> [url=http://b.example]pills[/url] \
+                     www.c.example";
+        let spammy = post(&[
+            (TOKEN_FIELD, &token),
+            (&honeypot, ""),
+            ("name", links),
+            ("message", links),
+            ("subject", links),
+        ]);
+        assert_eq!(spam._score(&spammy, &meta, 1010), 100);
+
+        let headless = Meta {
+            user_agent: Some(String::new()),
+            ..Meta::default()
+        };
+        assert_eq!(spam._score(&post(&[]), &headless, 1010), 100);
+        assert_eq!(spam._score(&clean, &headless, 1010), 25);
+    }
+}
@@ -1,9 +1,18 @@
+use crate::dns::Lookup;
 use crate::to_str;
 use ext_php_rs::exception::PhpException;
-use ext_php_rs::types::Zval;
+use ext_php_rs::types::{ZendHashTable, Zval};
 use ext_php_rs::zend::ce;
 use ext_php_rs::{php_class, php_impl};
+use psl::{Info, Psl, Type};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, ToSocketAddrs};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use unicode_normalization::UnicodeNormalization;
+use unicode_script::{Script, UnicodeScript};
 use url::quirks::hostname;
 use url::{Host, Url};
 
@@ -14,6 +23,14 @@ pub mod error_codes {
     pub const NO_HOST: i32 = 1102;
     pub const INVALID_HOSTNAME: i32 = 1103;
     pub const STRING_CONVERSION: i32 = 1104;
+    pub const INVALID_PUBLIC_SUFFIX_LIST: i32 = 1105;
+    pub const IDNA: i32 = 1106;
+    pub const INVALID_CIDR: i32 = 1107;
+    pub const DNS_RESOLUTION: i32 = 1108;
+    pub const NO_ALLOWED_ADDRESS: i32 = 1109;
+    pub const INVALID_OPTION: i32 = 1110;
+    pub const INVALID_PATTERN: i32 = 1111;
+    pub const OPAQUE_ORIGIN: i32 = 1112;
 }
 
 /// Errors that can occur during hostname operations.
@@ -33,6 +50,30 @@ pub enum Error {
 
     #[error("String conversion failed")]
     StringConversionError,
+
+    #[error("Invalid public suffix list: {0}")]
+    InvalidPublicSuffixList(String),
+
+    #[error("IDNA conversion failed: {0}")]
+    Idna(String),
+
+    #[error("Invalid CIDR: {0}")]
+    InvalidCidr(String),
+
+    #[error("DNS resolution failed: {0}")]
+    DnsResolution(String),
+
+    #[error("Host {0} has no address allowed by the policy")]
+    NoAllowedAddress(String),
+
+    #[error("Invalid option: {0}")]
+    InvalidOption(String),
+
+    #[error("Invalid hostname pattern: {0}")]
+    InvalidPattern(String),
+
+    #[error("URL has an opaque origin: {0}")]
+    OpaqueOrigin(String),
 }
 
 impl Error {
@@ -44,6 +85,14 @@ impl Error {
             Error::NoHost => error_codes::NO_HOST,
             Error::InvalidHostname(_) => error_codes::INVALID_HOSTNAME,
             Error::StringConversionError => error_codes::STRING_CONVERSION,
+            Error::InvalidPublicSuffixList(_) => error_codes::INVALID_PUBLIC_SUFFIX_LIST,
+            Error::Idna(_) => error_codes::IDNA,
+            Error::InvalidCidr(_) => error_codes::INVALID_CIDR,
+            Error::DnsResolution(_) => error_codes::DNS_RESOLUTION,
+            Error::NoAllowedAddress(_) => error_codes::NO_ALLOWED_ADDRESS,
+            Error::InvalidOption(_) => error_codes::INVALID_OPTION,
+            Error::InvalidPattern(_) => error_codes::INVALID_PATTERN,
+            Error::OpaqueOrigin(_) => error_codes::OPAQUE_ORIGIN,
         }
     }
 }
@@ -59,15 +108,555 @@ impl From<Error> for PhpException {
 /// Result type alias for hostname operations.
 pub type Result<T> = std::result::Result<T, Error>;
 
+thread_local! {
+    /// Public suffix list loaded at runtime; `None` means the embedded list is used.
+    static PUBLIC_SUFFIX_LIST: RefCell<Option<SuffixList>> = const { RefCell::new(None) };
+    /// Top-level domains loaded at runtime; `None` means the TLDs of the embedded PSL are used.
+    static KNOWN_TLDS: RefCell<Option<HashSet<String>>> = const { RefCell::new(None) };
+    /// Forward-confirmed PTR names by IP (`None` if confirmed absent), with the time they were
+    /// looked up.
+    static FCRDNS_CACHE: RefCell<HashMap<IpAddr, (Option<String>, Instant)>> =
+        RefCell::new(HashMap::new());
+}
+
+/// How long forward-confirmed reverse DNS results are cached.
+const FCRDNS_CACHE_TTL: Duration = Duration::from_secs(300);
+/// Maximum number of cached forward-confirmed reverse DNS results.
+const FCRDNS_CACHE_CAPACITY: usize = 10_000;
+
+/// Returns the PTR name of `ip` if it resolves back to `ip`, using the per-thread cache.
+fn forward_confirmed_name(ip: IpAddr, timeout: Duration) -> Option<String> {
+    let now = Instant::now();
+    if let Some(name) = FCRDNS_CACHE.with_borrow(|cache| {
+        cache
+            .get(&ip)
+            .filter(|(_, at)| now.duration_since(*at) < FCRDNS_CACHE_TTL)
+            .map(|(name, _)| name.clone())
+    }) {
+        return name;
+    }
+    // Only definitive answers are cached: a verified name, a name that resolves elsewhere, or
+    // no PTR record. A timeout or resolver failure is retried on the next call.
+    let name = match crate::dns::reverse_lookup(ip, timeout) {
+        Lookup::Found(name) => crate::dns::forward_lookup(&name, timeout)
+            .map(|addresses| addresses.contains(&ip).then_some(name)),
+        Lookup::NotFound => Some(None),
+        Lookup::Failed => None,
+    }?;
+    FCRDNS_CACHE.with_borrow_mut(|cache| {
+        if cache.len() >= FCRDNS_CACHE_CAPACITY {
+            cache.retain(|_, (_, at)| now.duration_since(*at) < FCRDNS_CACHE_TTL);
+            if cache.len() >= FCRDNS_CACHE_CAPACITY {
+                cache.clear();
+            }
+        }
+        cache.insert(ip, (name.clone(), now));
+    });
+    name
+}
+
+/// Returns the public suffix and the registrable domain of `domain` according to `list`.
+fn split_domain<L: Psl>(list: &L, domain: &str) -> (Option<String>, Option<String>) {
+    let bytes = domain.as_bytes();
+    let to_string = |b: &[u8]| String::from_utf8_lossy(b).into_owned();
+    (
+        list.suffix(bytes)
+            .map(|suffix| to_string(suffix.as_bytes())),
+        list.domain(bytes)
+            .map(|domain| to_string(domain.as_bytes())),
+    )
+}
+
+/// A public suffix list in the standard PSL format, parsed at runtime.
+///
+/// Rules before the `===BEGIN PRIVATE DOMAINS===` marker count as ICANN rules.
+#[derive(Debug, Default)]
+struct SuffixList {
+    /// Suffix rules by their ASCII form, wildcard rules like `*.ck` included.
+    rules: HashMap<String, Type>,
+    /// Exception rules like `!www.ck`, by their ASCII form without the `!`.
+    exceptions: HashMap<String, Type>,
+}
+
+impl FromStr for SuffixList {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut list = SuffixList::default();
+        let mut typ = Type::Icann;
+        for line in s.lines() {
+            if line.contains("===BEGIN PRIVATE DOMAINS===") {
+                typ = Type::Private;
+            }
+            if line.starts_with("//") {
+                continue;
+            }
+            let Some(rule) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (exception, rule) = match rule.strip_prefix('!') {
+                Some(rule) => (true, rule),
+                None => (false, rule),
+            };
+            let (wildcard, name) = match rule.strip_prefix("*.") {
+                Some(name) => (true, name),
+                None => (false, rule),
+            };
+            let ascii = idna::domain_to_ascii(name)
+                .ok()
+                .filter(|ascii| !ascii.is_empty())
+                .ok_or_else(|| Error::InvalidPublicSuffixList(format!("invalid rule: {rule}")))?;
+            let key = if wildcard {
+                format!("*.{ascii}")
+            } else {
+                ascii
+            };
+            if exception {
+                list.exceptions.insert(key, typ);
+            } else {
+                list.rules.insert(key, typ);
+            }
+        }
+        if list.rules.is_empty() {
+            return Err(Error::InvalidPublicSuffixList(
+                "the list has no rules".to_string(),
+            ));
+        }
+        Ok(list)
+    }
+}
+
+impl Psl for SuffixList {
+    /// Finds the longest matching rule, with exception rules taking precedence. The labels come
+    /// right to left; as the implicit `*` rule, the last label is a suffix of unknown type.
+    fn find<'a, T>(&self, labels: T) -> Info
+    where
+        T: Iterator<Item = &'a [u8]>,
+    {
+        let mut info = Info { len: 0, typ: None };
+        let mut suffix = String::new();
+        for label in labels {
+            let Ok(label) = std::str::from_utf8(label) else {
+                break;
+            };
+            let parent = suffix;
+            suffix = if parent.is_empty() {
+                label.to_string()
+            } else {
+                format!("{label}.{parent}")
+            };
+            if let Some(typ) = self.exceptions.get(&suffix) {
+                return Info {
+                    len: parent.len(),
+                    typ: Some(*typ),
+                };
+            }
+            let rule = self.rules.get(&suffix).or_else(|| {
+                (!parent.is_empty())
+                    .then(|| self.rules.get(&format!("*.{parent}")))
+                    .flatten()
+            });
+            match rule {
+                Some(typ) => {
+                    info = Info {
+                        len: suffix.len(),
+                        typ: Some(*typ),
+                    };
+                }
+                None if parent.is_empty() => info.len = suffix.len(),
+                None => {}
+            }
+        }
+        info
+    }
+}
+
+/// An IPv4 or IPv6 network in CIDR notation, e.g. `10.0.0.0/8` or `fd00::/8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl FromStr for Cidr {
+    type Err = Error;
+
+    /// Parses `address/prefix`; a bare address is treated as a single-host network.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let invalid = || Error::InvalidCidr(s.to_string());
+        let (address, prefix) = match s.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (s, None),
+        };
+        let network: IpAddr = address
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse()
+            .map_err(|_| invalid())?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= max)
+                .ok_or_else(invalid)?,
+            None => max,
+        };
+        Ok(Self { network, prefix })
+    }
+}
+
+impl Cidr {
+    /// Returns true if `ip` belongs to this network. An address is matched in its own
+    /// family first; IPv4-mapped IPv6 addresses (`::ffff:10.0.0.1`) are additionally matched
+    /// against IPv4 networks.
+    pub(crate) fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            (IpAddr::V4(_), IpAddr::V6(v6)) => v6
+                .to_ipv4_mapped()
+                .is_some_and(|v4| self.contains(IpAddr::V4(v4))),
+            (IpAddr::V6(_), IpAddr::V4(_)) => false,
+        }
+    }
+
+    /// Parses a list of networks; each entry may itself be a comma-separated list.
+    ///
+    /// # Errors
+    /// - Returns `Err` on the first invalid network.
+    pub(crate) fn parse_list<S: AsRef<str>>(list: &[S]) -> Result<Vec<Self>> {
+        list.iter()
+            .flat_map(|entry| entry.as_ref().split(','))
+            .filter(|entry| !entry.trim().is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+/// Networks that must not be reachable from server-side requests: loopback, private,
+/// link-local, carrier-grade NAT, documentation, benchmarking, multicast and reserved ranges,
+/// and the NAT64 prefixes, whose addresses embed an IPv4 address that may be any of those.
+const NON_PUBLIC_NETWORKS: &[&str] = &[
+    "0.0.0.0/8",
+    "10.0.0.0/8",
+    "100.64.0.0/10",
+    "127.0.0.0/8",
+    "169.254.0.0/16",
+    "172.16.0.0/12",
+    "192.0.0.0/24",
+    "192.0.2.0/24",
+    "192.88.99.0/24",
+    "192.168.0.0/16",
+    "198.18.0.0/15",
+    "198.51.100.0/24",
+    "203.0.113.0/24",
+    "224.0.0.0/4",
+    "240.0.0.0/4",
+    "::/128",
+    "::1/128",
+    "64:ff9b::/96",
+    "64:ff9b:1::/48",
+    "100::/64",
+    "2001::/23",
+    "2001:db8::/32",
+    "2002::/16",
+    "fc00::/7",
+    "fe80::/10",
+    "ff00::/8",
+];
+
+/// Returns true if `ip` is not a globally routable unicast address.
+pub(crate) fn is_non_public_ip(ip: IpAddr) -> bool {
+    NON_PUBLIC_NETWORKS.iter().any(|network| {
+        network
+            .parse::<Cidr>()
+            .is_ok_and(|network| network.contains(ip))
+    })
+}
+
+/// Formats a `CURLOPT_RESOLVE` entry, `host:port:ip1,[ip6]`, pinning `host` to `addresses`.
+pub(crate) fn curl_resolve_entry(host: &str, port: u16, addresses: &[IpAddr]) -> String {
+    let pinned = addresses
+        .iter()
+        .map(|ip| match ip {
+            IpAddr::V4(v4) => v4.to_string(),
+            IpAddr::V6(v6) => format!("[{v6}]"),
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{host}:{port}:{pinned}")
+}
+
+/// Options for `Hostname::resolvePinned()`.
+#[derive(Debug)]
+pub(crate) struct ResolveOptions {
+    /// Port the connection will be made to.
+    pub(crate) port: u16,
+    /// Whether non-public addresses are acceptable.
+    pub(crate) allow_private: bool,
+    /// Networks that are accepted even if non-public.
+    pub(crate) allowed_cidrs: Vec<Cidr>,
+    /// Networks that are always rejected.
+    pub(crate) denied_cidrs: Vec<Cidr>,
+}
+
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        Self {
+            port: 443,
+            allow_private: false,
+            allowed_cidrs: Vec::new(),
+            denied_cidrs: Vec::new(),
+        }
+    }
+}
+
+impl ResolveOptions {
+    /// Reads options from a PHP array, falling back to defaults for missing keys.
+    ///
+    /// # Errors
+    /// - Returns `Err` if an option has the wrong type or an invalid value.
+    fn from_table(table: &ZendHashTable) -> Result<Self> {
+        let cidrs = |key: &str| -> Result<Vec<Cidr>> {
+            let Some(value) = table.get(key) else {
+                return Ok(Vec::new());
+            };
+            let list = value
+                .array()
+                .ok_or_else(|| Error::InvalidOption(key.into()))?
+                .values()
+                .map(|entry| {
+                    entry
+                        .string()
+                        .ok_or_else(|| Error::InvalidOption(key.into()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Cidr::parse_list(&list)
+        };
+        let mut options = Self::default();
+        if let Some(value) = table.get("port") {
+            options.port = value
+                .long()
+                .and_then(|port| u16::try_from(port).ok())
+                .ok_or_else(|| Error::InvalidOption("port".into()))?;
+        }
+        if let Some(value) = table.get("allowPrivate") {
+            options.allow_private = value
+                .bool()
+                .ok_or_else(|| Error::InvalidOption("allowPrivate".into()))?;
+        }
+        options.allowed_cidrs = cidrs("allowedCidrs")?;
+        options.denied_cidrs = cidrs("deniedCidrs")?;
+        Ok(options)
+    }
+
+    /// Returns true if a connection to `ip` is permitted.
+    fn permits(&self, ip: IpAddr) -> bool {
+        if self.denied_cidrs.iter().any(|cidr| cidr.contains(ip)) {
+            return false;
+        }
+        self.allow_private
+            || self.allowed_cidrs.iter().any(|cidr| cidr.contains(ip))
+            || !is_non_public_ip(ip)
+    }
+}
+
+/// Non-Latin characters that are visually confusable with Latin letters, with the letter they
+/// imitate. A subset of the Unicode `confusables.txt` covering the characters seen in practice.
+const CONFUSABLES: &[(char, char)] = &[
+    // Cyrillic
+    ('а', 'a'),
+    ('в', 'b'),
+    ('с', 'c'),
+    ('ԁ', 'd'),
+    ('е', 'e'),
+    ('һ', 'h'),
+    ('і', 'i'),
+    ('ј', 'j'),
+    ('к', 'k'),
+    ('ӏ', 'l'),
+    ('м', 'm'),
+    ('н', 'h'),
+    ('о', 'o'),
+    ('р', 'p'),
+    ('ԛ', 'q'),
+    ('г', 'r'),
+    ('ѕ', 's'),
+    ('т', 't'),
+    ('ц', 'u'),
+    ('ѵ', 'v'),
+    ('ԝ', 'w'),
+    ('х', 'x'),
+    ('у', 'y'),
+    ('ү', 'y'),
+    ('з', '3'),
+    ('ъ', 'b'),
+    ('ь', 'b'),
+    ('ӓ', 'a'),
+    ('ё', 'e'),
+    ('ї', 'i'),
+    ('ӧ', 'o'),
+    // Greek
+    ('α', 'a'),
+    ('β', 'b'),
+    ('ε', 'e'),
+    ('η', 'n'),
+    ('ι', 'i'),
+    ('κ', 'k'),
+    ('ν', 'v'),
+    ('ο', 'o'),
+    ('ρ', 'p'),
+    ('τ', 't'),
+    ('υ', 'u'),
+    ('χ', 'x'),
+    ('γ', 'y'),
+    ('ω', 'w'),
+    // Armenian
+    ('ս', 'u'),
+    ('օ', 'o'),
+    ('հ', 'h'),
+    ('ո', 'n'),
+    ('ց', 'g'),
+    // Latin lookalikes outside ASCII
+    ('ɑ', 'a'),
+    ('ɡ', 'g'),
+    ('ɩ', 'i'),
+    ('ı', 'i'),
+    ('ʟ', 'l'),
+    ('ɴ', 'n'),
+    ('ʀ', 'r'),
+    // Digits
+    ('０', '0'),
+    ('１', '1'),
+    ('ⅼ', 'l'),
+];
+
+/// Maps a confusable character to the ASCII character it imitates.
+pub(crate) fn unconfuse(c: char) -> char {
+    CONFUSABLES
+        .iter()
+        .find(|(confusable, _)| *confusable == c)
+        .map_or(c, |(_, latin)| *latin)
+}
+
+/// Whether a character is a Default_Ignorable_Code_Point: zero-width characters, bidi
+/// controls, variation selectors, fillers and tags, which render as nothing.
+pub(crate) fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{AD}'
+            | '\u{34F}'
+            | '\u{61C}'
+            | '\u{115F}'..='\u{1160}'
+            | '\u{17B4}'..='\u{17B5}'
+            | '\u{180B}'..='\u{180F}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{206F}'
+            | '\u{3164}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FEFF}'
+            | '\u{FFA0}'
+            | '\u{FFF0}'..='\u{FFF8}'
+            | '\u{1BCA0}'..='\u{1BCA3}'
+            | '\u{1D173}'..='\u{1D17A}'
+            | '\u{E0000}'..='\u{E0FFF}'
+    )
+}
+
+/// Reduces a string to the form it visually resembles, after the UTS #39 skeleton:
+/// compatibility decomposition (NFKD, so fullwidth and mathematical letters become plain
+/// ones), invisible characters removed, case folded, and confusables mapped to the ASCII
+/// characters they imitate, including `1`/`|` to `l`, `0` to `o`, `rn` to `m` and `vv` to `w`.
+/// Capital `I` maps to `l` before case folding, as in the UTS #39 confusables data, since
+/// the two are indistinguishable in most sans-serif fonts.
+pub(crate) fn skeleton(s: &str) -> String {
+    let mapped: String = s
+        .nfkd()
+        .filter(|c| !is_invisible(*c))
+        .flat_map(|c| match c {
+            'I' | '1' | '|' => 'l'.to_lowercase(),
+            '0' => 'o'.to_lowercase(),
+            c => c.to_lowercase(),
+        })
+        .map(unconfuse)
+        .collect();
+    mapped.replace("rn", "m").replace("vv", "w")
+}
+
+/// Returns true if a single Unicode label mixes scripts in a way that is not normal for any
+/// language, or is written entirely in characters that imitate Latin letters.
+fn is_suspicious_label(label: &str) -> bool {
+    let mut scripts: Vec<Script> = Vec::new();
+    for c in label.chars() {
+        let script = c.script();
+        if !matches!(script, Script::Common | Script::Inherited | Script::Unknown)
+            && !scripts.contains(&script)
+        {
+            scripts.push(script);
+        }
+    }
+    // Combinations of Latin with CJK scripts are legitimate (UTS #39 "highly restrictive")
+    let cjk = |script: &Script| {
+        matches!(
+            script,
+            Script::Han | Script::Hiragana | Script::Katakana | Script::Hangul | Script::Bopomofo
+        )
+    };
+    let mixed = match scripts.as_slice() {
+        [] | [_] => false,
+        many => !many
+            .iter()
+            .all(|script| *script == Script::Latin || cjk(script)),
+    };
+    if mixed {
+        return true;
+    }
+    // Whole-script confusable, e.g. Cyrillic "аррӏе"
+    !label.is_ascii()
+        && label
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .all(|c| c.is_ascii() || unconfuse(c) != c)
+}
+
 /// A secured wrapper around `url::Host` for use in PHP extensions.
 /// Provides hostname parsing and normalization to prevent security issues.
 #[php_class]
 #[php(name = "Hardened\\Hostname")]
-#[derive(Debug)]
+#[php(implements(ce = crate::json_serializable, stub = "\\JsonSerializable"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Hostname {
     inner: Host,
 }
 
+impl Default for Hostname {
+    /// An empty placeholder, only observable while PHP restores a serialized instance.
+    fn default() -> Self {
+        Self {
+            inner: Host::Domain(String::new()),
+        }
+    }
+}
+
+impl std::fmt::Display for Hostname {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
 impl Hostname {
     /// Construct from a raw hostname string, handling domains and IPs (v4 or v6).
     ///
@@ -242,6 +831,164 @@ impl Hostname {
         self._subdomain_of(&other.inner.to_string())
     }
 
+    /// Look up the public suffix and registrable domain, using the runtime list if one was loaded.
+    ///
+    /// # Returns
+    /// - `(suffix, registrable domain)`, both `None` for IP addresses.
+    fn _split_domain(&self) -> (Option<String>, Option<String>) {
+        let Host::Domain(domain) = &self.inner else {
+            return (None, None);
+        };
+        PUBLIC_SUFFIX_LIST.with_borrow(|list| match list {
+            Some(list) => split_domain(list, domain),
+            None => split_domain(&psl::List, domain),
+        })
+    }
+
+    /// Match against a pattern that may start with a wildcard label.
+    ///
+    /// - `*.example.com` matches exactly one extra label: `a.example.com`, not `a.b.example.com`.
+    /// - `**.example.com` matches one or more extra labels.
+    /// - Anything else must match exactly.
+    ///
+    /// The apex (`example.com`) only matches a wildcard pattern if `include_apex` is set.
+    ///
+    /// # Errors
+    /// - Returns `Err` if the wildcard is not the entire leftmost label, or the rest is not a valid hostname.
+    pub(crate) fn _matches_pattern(&self, pattern: &str, include_apex: bool) -> Result<bool> {
+        let invalid = || Error::InvalidPattern(pattern.to_string());
+        let (wildcard, rest) = if let Some(rest) = pattern.strip_prefix("**.") {
+            (Some(true), rest)
+        } else if let Some(rest) = pattern.strip_prefix("*.") {
+            (Some(false), rest)
+        } else {
+            (None, pattern)
+        };
+        if rest.contains('*') {
+            return Err(invalid());
+        }
+        let base = Hostname::from_str(rest).map_err(|_| invalid())?;
+        let Some(multi_label) = wildcard else {
+            return Ok(self.inner == base.inner);
+        };
+        let (Host::Domain(domain), Host::Domain(base)) = (&self.inner, &base.inner) else {
+            return Err(invalid());
+        };
+        if domain == base {
+            return Ok(include_apex);
+        }
+        Ok(domain
+            .strip_suffix(base.as_str())
+            .and_then(|prefix| prefix.strip_suffix('.'))
+            .is_some_and(|prefix| !prefix.is_empty() && (multi_label || !prefix.contains('.'))))
+    }
+
+    /// Parses a hostname the same way as `Hostname::fromStr()`.
+    ///
+    /// # Errors
+    /// - Returns `Err` if the string is not a valid hostname.
+    pub(crate) fn _parse(hostname: &str) -> Result<Self> {
+        Self::from_str(hostname)
+    }
+
+    /// Parses the host of a URL the same way as `Hostname::fromUrl()`.
+    ///
+    /// # Errors
+    /// - Returns `Err` if the URL has no valid host.
+    pub(crate) fn _from_parsed_url(url: &Url) -> Result<Self> {
+        Self::from_str(hostname(url))
+    }
+
+    /// Returns true if this hostname equals `parent` or is a subdomain of it.
+    pub(crate) fn _within(&self, parent: &Hostname) -> bool {
+        match (&self.inner, &parent.inner) {
+            (Host::Domain(a), Host::Domain(b)) => {
+                a == b
+                    || a.strip_suffix(b.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Returns the IP address if this hostname is an IP literal.
+    pub(crate) fn _ip(&self) -> Option<IpAddr> {
+        match self.inner {
+            Host::Ipv4(v4) => Some(IpAddr::V4(v4)),
+            Host::Ipv6(v6) => Some(IpAddr::V6(v6)),
+            Host::Domain(_) => None,
+        }
+    }
+
+    /// Resolves the hostname and keeps only the addresses permitted by `options`.
+    ///
+    /// # Errors
+    /// - Returns `Err` if resolution fails or no address is permitted.
+    pub(crate) fn _resolve_pinned(
+        &self,
+        options: &ResolveOptions,
+    ) -> Result<(String, Vec<IpAddr>)> {
+        let addresses: Vec<IpAddr> = match &self.inner {
+            Host::Domain(domain) => {
+                let mut seen = HashSet::new();
+                (domain.as_str(), options.port)
+                    .to_socket_addrs()
+                    .map_err(|err| Error::DnsResolution(err.to_string()))?
+                    .map(|addr| addr.ip())
+                    .filter(|ip| seen.insert(*ip))
+                    .collect()
+            }
+            Host::Ipv4(v4) => vec![IpAddr::V4(*v4)],
+            Host::Ipv6(v6) => vec![IpAddr::V6(*v6)],
+        };
+        let host = self.inner.to_string();
+        let permitted: Vec<IpAddr> = addresses
+            .into_iter()
+            .filter(|ip| options.permits(*ip))
+            .collect();
+        if permitted.is_empty() {
+            return Err(Error::NoAllowedAddress(host));
+        }
+        Ok((host, permitted))
+    }
+
+    /// Converts the hostname to its Unicode form; IP addresses are returned unchanged.
+    ///
+    /// # Errors
+    /// - Returns `Err` if a punycode label cannot be decoded.
+    fn _to_unicode(&self) -> Result<String> {
+        match &self.inner {
+            Host::Domain(domain) => {
+                let (unicode, result) = idna::domain_to_unicode(domain);
+                result.map_err(|err| Error::Idna(err.to_string()))?;
+                Ok(unicode)
+            }
+            other => Ok(other.to_string()),
+        }
+    }
+
+    /// Check whether the hostname looks like a homograph of another one.
+    ///
+    /// # Parameters
+    /// - `protected`: Domains whose lookalikes must be flagged.
+    ///
+    /// # Errors
+    /// - Returns `Err` if a punycode label cannot be decoded.
+    fn _is_homograph_suspicious(&self, protected: &[&str]) -> Result<bool> {
+        if !self.is_domain() {
+            return Ok(false);
+        }
+        let unicode = self._to_unicode()?;
+        if unicode.split('.').any(is_suspicious_label) {
+            return Ok(true);
+        }
+        let unicode_skeleton = skeleton(&unicode);
+        Ok(protected.iter().any(|domain| {
+            let domain = domain.trim_end_matches('.').to_lowercase();
+            unicode_skeleton == skeleton(&domain) && unicode != domain
+        }))
+    }
+
     /// Check if this hostname is a subdomain of any hosts from a list of URLs.
     ///
     /// # Parameters
@@ -471,6 +1218,335 @@ impl Hostname {
         matches!(self.inner, Host::Domain(_))
     }
 
+    /// Returns true if this hostname is an IP address within any of the given networks.
+    ///
+    /// IPv4-mapped IPv6 addresses (e.g. `[::ffff:10.0.0.1]`) are matched against IPv4 networks.
+    /// Domain names never match; resolve them first.
+    ///
+    /// # Parameters
+    /// - `cidrs`: `string|array` Networks such as `"10.0.0.0/8, fd00::/8"` or `['10.0.0.0/8', 'fd00::/8']`.
+    ///   A bare address matches only itself.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the address is in one of the networks.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if a network is malformed.
+    fn in_cidr(&self, cidrs: &Zval) -> Result<bool> {
+        let list: Vec<String> = if let Some(array) = cidrs.array() {
+            array
+                .values()
+                .map(|value| to_str(value).map_err(|_| Error::StringConversionError))
+                .collect::<Result<_>>()?
+        } else {
+            vec![to_str(cidrs).map_err(|_| Error::StringConversionError)?]
+        };
+        let networks = Cidr::parse_list(&list)?;
+        Ok(self
+            ._ip()
+            .is_some_and(|ip| networks.iter().any(|network| network.contains(ip))))
+    }
+
+    /// Matches this hostname against a pattern with an optional leading wildcard label.
+    ///
+    /// `*.example.com` matches `api.example.com` but neither `a.b.example.com`, `example.com`
+    /// nor `evilexample.com`; `**.example.com` matches any depth of subdomains.
+    /// Patterns without a wildcard must match exactly.
+    ///
+    /// # Parameters
+    /// - `pattern`: `string` The pattern, e.g. `*.example.com`.
+    /// - `includeApex`: `?bool` Whether a wildcard pattern also matches `example.com` itself (default `false`).
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the hostname matches.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the pattern is malformed (e.g. `a*.example.com`).
+    fn matches_pattern(&self, pattern: &str, include_apex: Option<bool>) -> Result<bool> {
+        self._matches_pattern(pattern, include_apex.unwrap_or(false))
+    }
+
+    /// Returns true if this hostname equals, or is a subdomain of, any of the given hosts.
+    ///
+    /// Entries starting with `*.` or `**.` are treated as patterns (see `matchesPattern()`).
+    ///
+    /// # Parameters
+    /// - `hosts`: `array` Hostnames and/or patterns.
+    ///
+    /// # Returns
+    /// - `bool`: `true` on the first match.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if an entry is not a valid hostname or pattern.
+    fn equals_or_subdomain_of_any(&self, hosts: Vec<String>) -> Result<bool> {
+        for host in &hosts {
+            let matched = if host.starts_with('*') {
+                self._matches_pattern(host, false)?
+            } else {
+                self._subdomain_of(host)?
+            };
+            if matched {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Resolves the hostname and pins the connection to addresses that pass the network policy.
+    ///
+    /// Validating a hostname and then letting the HTTP client resolve it again leaves a window
+    /// for DNS rebinding: the second lookup can return `127.0.0.1`. The returned `resolve` entry
+    /// makes curl connect only to the addresses checked here.
+    ///
+    /// ```php
+    /// $pinned = Hostname::fromUrl($url)->resolvePinned(['port' => 443]);
+    /// curl_setopt($ch, CURLOPT_RESOLVE, $pinned['resolve']);
+    /// ```
+    ///
+    /// # Parameters
+    /// - `options`: `?array` Optional settings:
+    ///   - `port`: `int` port of the connection (default `443`).
+    ///   - `allowPrivate`: `bool` accept loopback, private and other non-public addresses (default `false`).
+    ///   - `allowedCidrs`: `array` networks accepted even if non-public.
+    ///   - `deniedCidrs`: `array` networks that are always rejected.
+    ///
+    /// # Returns
+    /// - `array`: `['ips' => string[], 'resolve' => ['host:port:ip1,ip2']]`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if resolution fails, an option is invalid, or no address is permitted.
+    fn resolve_pinned(
+        &self,
+        options: Option<&ZendHashTable>,
+    ) -> Result<HashMap<&'static str, Vec<String>>> {
+        let options = options
+            .map(ResolveOptions::from_table)
+            .transpose()?
+            .unwrap_or_default();
+        let (host, addresses) = self._resolve_pinned(&options)?;
+        let ips: Vec<String> = addresses.iter().map(IpAddr::to_string).collect();
+        Ok(HashMap::from([
+            (
+                "resolve",
+                vec![curl_resolve_entry(&host, options.port, &addresses)],
+            ),
+            ("ips", ips),
+        ]))
+    }
+
+    /// Parses many hostnames in one call, e.g. a column of an uploaded CSV file.
+    ///
+    /// # Parameters
+    /// - `hosts`: `array` Raw hostname strings.
+    ///
+    /// # Returns
+    /// - `array` One entry per input, in order: `['hostname' => ?string, 'error' => ?string]`,
+    ///   where `hostname` is the normalized form if valid, and `error` the reason otherwise.
+    fn validate_all(hosts: Vec<String>) -> Vec<HashMap<&'static str, Option<String>>> {
+        hosts
+            .iter()
+            .map(|host| {
+                let (hostname, error) = match Self::from_str(host.trim()) {
+                    Ok(hostname) => (Some(hostname.to_string()), None),
+                    Err(err) => (None, Some(err.to_string())),
+                };
+                HashMap::from([("hostname", hostname), ("error", error)])
+            })
+            .collect()
+    }
+
+    /// Verifies a crawler's identity with forward-confirmed reverse DNS.
+    ///
+    /// Looks up the PTR name of `ip`, requires it to be within one of `expectedSuffixes`
+    /// (e.g. `googlebot.com`), and requires the name to resolve back to `ip`. This is the check
+    /// recommended by Google and Bing to tell genuine crawlers from spoofed user agents.
+    ///
+    /// Definitive results are cached per worker thread for 5 minutes; timeouts and resolver
+    /// failures are not, so a DNS hiccup does not lock a genuine crawler out.
+    ///
+    /// # Parameters
+    /// - `ip`: `string` The client IP address.
+    /// - `expectedSuffixes`: `array` Allowed parent domains, e.g. `['googlebot.com', 'google.com']`.
+    /// - `timeoutMs`: `?int` Timeout of each DNS lookup in milliseconds (default `2000`).
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the IP belongs to one of the expected domains.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `ip` is not an IP address or a suffix is not a valid hostname.
+    fn verify_fcrdns(
+        ip: &str,
+        expected_suffixes: Vec<String>,
+        timeout_ms: Option<u64>,
+    ) -> Result<bool> {
+        let ip: IpAddr = ip
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse()
+            .map_err(|_| Error::InvalidHostname(ip.to_string()))?;
+        let suffixes = expected_suffixes
+            .iter()
+            .map(|suffix| Hostname::from_str(suffix.trim_start_matches('.')))
+            .collect::<Result<Vec<_>>>()?;
+        let timeout = Duration::from_millis(timeout_ms.unwrap_or(2000));
+        let Some(name) = forward_confirmed_name(ip, timeout) else {
+            return Ok(false);
+        };
+        let Ok(name) = Hostname::from_str(&name) else {
+            return Ok(false);
+        };
+        Ok(suffixes.iter().any(|suffix| name._within(suffix)))
+    }
+
+    /// Returns the ASCII (punycode) form of this hostname, e.g. `xn--80ak6aa92e.com`.
+    ///
+    /// # Returns
+    /// - `string`: The ASCII hostname; IP addresses are returned unchanged.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the hostname is not a valid IDN.
+    fn to_ascii(&self) -> Result<String> {
+        match &self.inner {
+            Host::Domain(domain) => {
+                idna::domain_to_ascii(domain).map_err(|err| Error::Idna(err.to_string()))
+            }
+            other => Ok(other.to_string()),
+        }
+    }
+
+    /// Returns the Unicode form of this hostname, e.g. `аррӏе.com` for `xn--80ak6aa92e.com`.
+    ///
+    /// Use this for display only; compare and store the ASCII form.
+    ///
+    /// # Returns
+    /// - `string`: The Unicode hostname; IP addresses are returned unchanged.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if a punycode label cannot be decoded.
+    fn to_unicode(&self) -> Result<String> {
+        self._to_unicode()
+    }
+
+    /// Returns true if the hostname looks like a lookalike (homograph) of another host.
+    ///
+    /// A label is suspicious if it mixes scripts (e.g. Latin and Cyrillic in `аpple`), or is written
+    /// entirely in non-Latin characters that imitate Latin letters (e.g. Cyrillic `аррӏе`).
+    /// Additionally, if `protected` domains are given, the hostname is flagged when it
+    /// visually resembles one of them without being equal to it.
+    ///
+    /// # Parameters
+    /// - `protected`: `?array` Domains to protect, e.g. `['apple.com', 'paypal.com']`.
+    ///
+    /// # Returns
+    /// - `bool`: `true` if the hostname should be treated as a spoofing attempt.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if a punycode label cannot be decoded.
+    fn is_homograph_suspicious(&self, protected: Option<Vec<String>>) -> Result<bool> {
+        let protected = protected.unwrap_or_default();
+        self._is_homograph_suspicious(&protected.iter().map(String::as_str).collect::<Vec<_>>())
+    }
+
+    /// Returns the public suffix (eTLD) of this hostname, e.g. `co.uk` for `www.example.co.uk`.
+    ///
+    /// Unknown TLDs are treated as public suffixes (the PSL `*` rule).
+    ///
+    /// # Returns
+    /// - `?string`: The public suffix, or `null` for IP addresses.
+    fn public_suffix(&self) -> Option<String> {
+        self._split_domain().0
+    }
+
+    /// Returns the registrable domain (eTLD+1) of this hostname, e.g. `example.co.uk`
+    /// for `www.example.co.uk`. This is the widest domain a cookie may be scoped to,
+    /// and the unit of "same site" comparisons.
+    ///
+    /// # Returns
+    /// - `?string`: The registrable domain, or `null` for IP addresses and public suffixes themselves.
+    fn registrable_domain(&self) -> Option<String> {
+        self._split_domain().1
+    }
+
+    /// Returns true if this hostname is exactly a registrable domain (eTLD+1).
+    ///
+    /// # Returns
+    /// - `bool`: `true` for `example.co.uk`; `false` for `co.uk`, `www.example.co.uk` and IPs.
+    fn is_registrable_domain(&self) -> bool {
+        match (&self.inner, self._split_domain().1) {
+            (Host::Domain(domain), Some(registrable)) => *domain == registrable,
+            _ => false,
+        }
+    }
+
+    /// Replaces the embedded public suffix list, e.g. with a freshly downloaded copy of
+    /// <https://publicsuffix.org/list/public_suffix_list.dat> or a list with private additions.
+    ///
+    /// The list applies to the current PHP worker thread until `resetPublicSuffixList()`.
+    ///
+    /// # Parameters
+    /// - `list`: `string` The list in the standard PSL format.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the list cannot be parsed.
+    fn set_public_suffix_list(list: &str) -> Result<()> {
+        let list = list.parse::<SuffixList>()?;
+        PUBLIC_SUFFIX_LIST.set(Some(list));
+        Ok(())
+    }
+
+    /// Reverts to the public suffix list embedded at build time.
+    fn reset_public_suffix_list() {
+        PUBLIC_SUFFIX_LIST.set(None);
+    }
+
+    /// Returns true if the top-level domain is delegated in the root zone, e.g. `com`.
+    ///
+    /// By default, the ICANN section of the embedded public suffix list is used, which tracks the
+    /// IANA root zone. Use `loadTldList()` to check against a fresh copy of the IANA list instead.
+    ///
+    /// # Returns
+    /// - `bool`: `true` for known TLDs; `false` for unknown TLDs like `notatld`, and for IP addresses.
+    fn has_known_tld(&self) -> bool {
+        let Host::Domain(domain) = &self.inner else {
+            return false;
+        };
+        let tld = domain.rsplit('.').next().unwrap_or_default();
+        if tld.is_empty() {
+            return false;
+        }
+        KNOWN_TLDS.with_borrow(|tlds| match tlds {
+            Some(tlds) => tlds.contains(tld),
+            None => psl::List
+                .suffix(tld.as_bytes())
+                .is_some_and(|suffix| suffix.is_known()),
+        })
+    }
+
+    /// Replaces the TLD list used by `hasKnownTld()`.
+    ///
+    /// The list applies to the current PHP worker thread until `resetTldList()`.
+    ///
+    /// # Parameters
+    /// - `list`: `string` The list in the format of
+    ///   <https://data.iana.org/TLD/tlds-alpha-by-domain.txt>: one TLD per line, `#` comments.
+    fn load_tld_list(list: &str) {
+        let tlds = list
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|tld| {
+                let tld = tld.trim_start_matches('.').to_lowercase();
+                idna::domain_to_ascii(&tld).unwrap_or(tld)
+            })
+            .collect();
+        KNOWN_TLDS.set(Some(tlds));
+    }
+
+    /// Reverts `hasKnownTld()` to the TLDs of the embedded public suffix list.
+    fn reset_tld_list() {
+        KNOWN_TLDS.set(None);
+    }
+
     /// Returns the string representation of this hostname.
     ///
     /// # Returns
@@ -478,12 +1554,71 @@ impl Hostname {
     fn __to_string(&self) -> String {
         self.inner.to_string()
     }
+
+    /// Serializes to the normalized hostname string for `json_encode()`.
+    ///
+    /// # Returns
+    /// - `string`: The normalized hostname.
+    fn json_serialize(&self) -> String {
+        self.inner.to_string()
+    }
+
+    /// Returns the data stored by `serialize()`.
+    ///
+    /// # Returns
+    /// - `array`: `['hostname' => string]`.
+    fn __serialize(&self) -> HashMap<&'static str, String> {
+        HashMap::from([("hostname", self.inner.to_string())])
+    }
+
+    /// Restores an instance from `unserialize()` data.
+    ///
+    /// The stored hostname is parsed again, so tampered cache entries cannot produce
+    /// a Hostname that would not pass `Hostname::from()`.
+    ///
+    /// # Parameters
+    /// - `data`: `array` The array returned by `__serialize()`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the data is malformed or the hostname is invalid.
+    fn __unserialize(&mut self, data: &ZendHashTable) -> Result<()> {
+        let hostname = data
+            .get("hostname")
+            .and_then(Zval::string)
+            .ok_or(Error::StringConversionError)?;
+        *self = Self::from_str(&hostname)?;
+        Ok(())
+    }
+
+    /// The normalized hostname, exposed as the read-only `hostname` property so that
+    /// `var_export()` output carries it to `__set_state()`.
+    #[php(getter)]
+    fn get_hostname(&self) -> String {
+        self.inner.to_string()
+    }
+
+    /// Restores an instance exported with `var_export()`.
+    ///
+    /// # Parameters
+    /// - `properties`: `array` `['hostname' => string]`, the `hostname` property.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the data is malformed or the hostname is invalid.
+    fn __set_state(properties: &ZendHashTable) -> Result<Self> {
+        let mut hostname = Self::default();
+        hostname.__unserialize(properties)?;
+        Ok(hostname)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Hostname;
-    use crate::run_php_example;
+    use super::{
+        Cidr, FCRDNS_CACHE, Hostname, ResolveOptions, SuffixList, forward_confirmed_name,
+        is_non_public_ip,
+    };
+    use crate::{run_php_example, run_php_test};
+    use psl::{Psl, Type};
 
     #[test]
     fn test_from_str_and_equals() {
@@ -544,9 +1679,264 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_public_suffix() {
+        let h = Hostname::_from_str("www.Example.co.uk").unwrap();
+        assert_eq!(h.public_suffix().as_deref(), Some("co.uk"));
+        assert_eq!(h.registrable_domain().as_deref(), Some("example.co.uk"));
+        assert!(!h.is_registrable_domain());
+        assert!(
+            Hostname::_from_str("example.co.uk")
+                .unwrap()
+                .is_registrable_domain()
+        );
+
+        let suffix = Hostname::_from_str("co.uk").unwrap();
+        assert_eq!(suffix.registrable_domain(), None);
+        assert!(!suffix.is_registrable_domain());
+
+        let ip = Hostname::_from_str("127.0.0.1").unwrap();
+        assert_eq!(ip.public_suffix(), None);
+        assert_eq!(ip.registrable_domain(), None);
+    }
+
+    #[test]
+    fn test_public_suffix_override() {
+        let h = Hostname::_from_str("tenant.apps.example.com").unwrap();
+        assert_eq!(h.registrable_domain().as_deref(), Some("example.com"));
+
+        Hostname::set_public_suffix_list("// private\ncom\napps.example.com\n").unwrap();
+        assert_eq!(h.public_suffix().as_deref(), Some("apps.example.com"));
+        assert_eq!(
+            h.registrable_domain().as_deref(),
+            Some("tenant.apps.example.com")
+        );
+
+        Hostname::reset_public_suffix_list();
+        assert_eq!(h.registrable_domain().as_deref(), Some("example.com"));
+    }
+
+    #[test]
+    fn test_suffix_list() {
+        let list: SuffixList = "// ===BEGIN ICANN DOMAINS===\nuk\nco.uk\n*.ck\n!www.ck\n\
+            // ===BEGIN PRIVATE DOMAINS===\nblogspot.com\n"
+            .parse()
+            .unwrap();
+        let suffix = |name: &str| {
+            list.suffix(name.as_bytes())
+                .map(|s| (String::from_utf8_lossy(s.as_bytes()).into_owned(), s.typ()))
+        };
+        assert_eq!(
+            suffix("www.example.co.uk"),
+            Some(("co.uk".into(), Some(Type::Icann)))
+        );
+        assert_eq!(suffix("a.b.ck"), Some(("b.ck".into(), Some(Type::Icann))));
+        assert_eq!(suffix("www.ck"), Some(("ck".into(), Some(Type::Icann))));
+        assert_eq!(
+            suffix("me.blogspot.com"),
+            Some(("blogspot.com".into(), Some(Type::Private)))
+        );
+        assert_eq!(suffix("example.zz"), Some(("zz".into(), None)));
+
+        assert!("// comments only\n".parse::<SuffixList>().is_err());
+        assert!("xn--a.com\n".parse::<SuffixList>().is_err());
+    }
+
+    #[test]
+    fn test_cidr() {
+        let networks = Cidr::parse_list(&["10.0.0.0/8, fd00::/8", "192.168.1.1"]).unwrap();
+        let matches = |ip: &str| {
+            let ip = ip.parse().unwrap();
+            networks.iter().any(|network| network.contains(ip))
+        };
+        assert!(matches("10.1.2.3"));
+        assert!(!matches("11.0.0.1"));
+        assert!(matches("fd12:3456::1"));
+        assert!(!matches("fe80::1"));
+        assert!(matches("192.168.1.1"));
+        assert!(!matches("192.168.1.2"));
+        assert!(matches("::ffff:10.0.0.1"));
+
+        let any: Cidr = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains("8.8.8.8".parse().unwrap()));
+        assert!(!any.contains("2001:db8::1".parse().unwrap()));
+
+        // IPv6 networks see mapped addresses as they are
+        let mapped: Cidr = "::ffff:0:0/96".parse().unwrap();
+        assert!(mapped.contains("::ffff:10.0.0.1".parse().unwrap()));
+        assert!(mapped.contains("::ffff:8.8.8.8".parse().unwrap()));
+        assert!(!mapped.contains("10.0.0.1".parse().unwrap()));
+        assert!(!mapped.contains("2001:db8::1".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+        assert!("fd00::/129".parse::<Cidr>().is_err());
+        assert!("example.com/8".parse::<Cidr>().is_err());
+    }
+
+    #[test]
+    fn test_fcrdns_failure_not_cached() {
+        let ip = "192.0.2.1".parse().unwrap();
+        assert_eq!(forward_confirmed_name(ip, std::time::Duration::ZERO), None);
+        assert!(FCRDNS_CACHE.with_borrow(|cache| !cache.contains_key(&ip)));
+    }
+
+    #[test]
+    fn test_in_cidr_host() {
+        let h = Hostname::_from_str("[fd00::1]").unwrap();
+        assert_eq!(h._ip(), Some("fd00::1".parse().unwrap()));
+        assert_eq!(Hostname::_from_str("example.com").unwrap()._ip(), None);
+    }
+
+    #[test]
+    fn test_non_public_ip() {
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.31.255.255",
+            "192.168.0.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "64:ff9b::7f00:1",
+            "64:ff9b::a00:1",
+        ] {
+            assert!(is_non_public_ip(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in ["8.8.8.8", "1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(!is_non_public_ip(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[test]
+    fn test_resolve_pinned_ip_literal() {
+        let options = ResolveOptions::default();
+        let loopback = Hostname::_from_str("127.0.0.1").unwrap();
+        assert!(loopback._resolve_pinned(&options).is_err());
+        let public = Hostname::_from_str("8.8.8.8").unwrap();
+        assert_eq!(
+            public._resolve_pinned(&options).unwrap().1,
+            vec!["8.8.8.8".parse::<std::net::IpAddr>().unwrap()]
+        );
+
+        let internal = ResolveOptions {
+            allowed_cidrs: Cidr::parse_list(&["127.0.0.0/8"]).unwrap(),
+            ..ResolveOptions::default()
+        };
+        assert!(loopback._resolve_pinned(&internal).is_ok());
+
+        let denied = ResolveOptions {
+            allow_private: true,
+            denied_cidrs: Cidr::parse_list(&["8.8.0.0/16"]).unwrap(),
+            ..ResolveOptions::default()
+        };
+        assert!(public._resolve_pinned(&denied).is_err());
+    }
+
+    #[test]
+    fn test_matches_pattern() -> crate::TestResult {
+        let api = Hostname::_from_str("api.example.com")?;
+        let deep = Hostname::_from_str("a.b.example.com")?;
+        let apex = Hostname::_from_str("example.com")?;
+        let evil = Hostname::_from_str("evilexample.com")?;
+
+        assert!(api._matches_pattern("*.example.com", false)?);
+        assert!(!deep._matches_pattern("*.example.com", false)?);
+        assert!(deep._matches_pattern("**.example.com", false)?);
+        assert!(!apex._matches_pattern("*.example.com", false)?);
+        assert!(apex._matches_pattern("*.example.com", true)?);
+        assert!(!evil._matches_pattern("*.example.com", true)?);
+        assert!(!evil._matches_pattern("**.example.com", false)?);
+        assert!(apex._matches_pattern("Example.COM.", false)?);
+
+        assert!(api._matches_pattern("a*.example.com", false).is_err());
+        assert!(api._matches_pattern("*.*.com", false).is_err());
+        assert!(api._matches_pattern("*.127.0.0.1", false).is_err());
+
+        assert!(api.equals_or_subdomain_of_any(vec!["foo.com".into(), "example.com".into()])?);
+        assert!(api.equals_or_subdomain_of_any(vec!["*.example.com".into()])?);
+        assert!(!evil.equals_or_subdomain_of_any(vec!["example.com".into()])?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_all() {
+        let results = Hostname::validate_all(vec![
+            " Example.COM. ".into(),
+            "bad host".into(),
+            "[::1]".into(),
+        ]);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["hostname"].as_deref(), Some("example.com"));
+        assert_eq!(results[0]["error"], None);
+        assert_eq!(results[1]["hostname"], None);
+        assert!(results[1]["error"].is_some());
+        assert_eq!(results[2]["hostname"].as_deref(), Some("[::1]"));
+    }
+
+    #[test]
+    fn test_has_known_tld() -> crate::TestResult {
+        assert!(Hostname::from_str("example.com")?.has_known_tld());
+        assert!(Hostname::from_str("example.co.uk")?.has_known_tld());
+        assert!(!Hostname::from_str("example.notatld")?.has_known_tld());
+        assert!(!Hostname::from_str("127.0.0.1")?.has_known_tld());
+
+        Hostname::load_tld_list("# Version 2026101400\nCOM\nNOTATLD\n");
+        assert!(Hostname::from_str("example.notatld")?.has_known_tld());
+        assert!(!Hostname::from_str("example.org")?.has_known_tld());
+        Hostname::reset_tld_list();
+        assert!(Hostname::from_str("example.org")?.has_known_tld());
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize() -> crate::TestResult {
+        let h = Hostname::from_str("Example.COM.")?;
+        assert_eq!(h.json_serialize(), "example.com");
+        assert_eq!(h.__serialize()["hostname"], "example.com");
+        Ok(())
+    }
+
+    #[test]
+    fn test_idna() -> crate::TestResult {
+        let h = Hostname::from_str("Bücher.example")?;
+        assert_eq!(h.to_ascii()?, "xn--bcher-kva.example");
+        assert_eq!(h.to_unicode()?, "bücher.example");
+        assert_eq!(Hostname::from_str("[::1]")?.to_unicode()?, "[::1]");
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_homograph_suspicious() -> crate::TestResult {
+        // Latin "a" replaced by Cyrillic "а"
+        assert!(Hostname::from_str("\u{430}pple.com")?._is_homograph_suspicious(&[])?);
+        // Entirely Cyrillic lookalike of "apple"
+        assert!(Hostname::from_str("xn--80ak6aa92e.com")?._is_homograph_suspicious(&[])?);
+        assert!(!Hostname::from_str("apple.com")?._is_homograph_suspicious(&["apple.com"])?);
+        assert!(!Hostname::from_str("bücher.example")?._is_homograph_suspicious(&[])?);
+        assert!(!Hostname::from_str("пример.рф")?._is_homograph_suspicious(&[])?);
+        assert!(!Hostname::from_str("日本語.jp")?._is_homograph_suspicious(&[])?);
+        assert!(!Hostname::from_str("127.0.0.1")?._is_homograph_suspicious(&[])?);
+        // Greek omicron imitating "o"
+        assert!(
+            Hostname::from_str("g\u{3bf}\u{3bf}gle.com")?
+                ._is_homograph_suspicious(&["google.com"])?
+        );
+        Ok(())
+    }
+
     #[test]
     fn php_example() -> crate::TestResult {
         run_php_example("hostname")?;
         Ok(())
     }
+
+    #[test]
+    fn php_test() -> crate::TestResult {
+        run_php_test("hostname")?;
+        Ok(())
+    }
 }
@@ -0,0 +1,298 @@
+use crate::hostname::{self, Cidr, Hostname};
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use thiserror::Error;
+use url::Url;
+
+// Error codes for HostnamePolicy errors: 2000-2099
+pub mod error_codes {
+    pub const HOST_NOT_ALLOWED: i32 = 2000;
+    pub const SCHEME_NOT_ALLOWED: i32 = 2001;
+    pub const PORT_NOT_ALLOWED: i32 = 2002;
+    pub const ADDRESS_DENIED: i32 = 2003;
+    pub const URL_PARSE: i32 = 2004;
+}
+
+/// Errors produced when a host or URL violates a `HostnamePolicy`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Host is not allowed: {0}")]
+    HostNotAllowed(String),
+
+    #[error("Scheme is not allowed: {0}")]
+    SchemeNotAllowed(String),
+
+    #[error("Port is not allowed: {0}")]
+    PortNotAllowed(u16),
+
+    #[error("Address is in a denied network: {0}")]
+    AddressDenied(String),
+
+    #[error("URL parse error: {0}")]
+    UrlParseError(String),
+
+    #[error(transparent)]
+    Hostname(#[from] hostname::Error),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::HostNotAllowed(_) => error_codes::HOST_NOT_ALLOWED,
+            Error::SchemeNotAllowed(_) => error_codes::SCHEME_NOT_ALLOWED,
+            Error::PortNotAllowed(_) => error_codes::PORT_NOT_ALLOWED,
+            Error::AddressDenied(_) => error_codes::ADDRESS_DENIED,
+            Error::UrlParseError(_) => error_codes::URL_PARSE,
+            Error::Hostname(err) => err.code(),
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for hostname policy operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A reusable allow/deny policy for hosts and URLs.
+///
+/// Configure it once (e.g. at bootstrap) and call `check()`/`assert()` per request,
+/// instead of passing the same lists to `Hostname::equalsAny()` every time.
+#[php_class]
+#[php(name = "Hardened\\HostnamePolicy")]
+#[derive(Default)]
+pub struct HostnamePolicy {
+    allow_hosts: Vec<Hostname>,
+    allow_suffixes: Vec<Hostname>,
+    deny_cidrs: Vec<Cidr>,
+    allow_schemes: Vec<String>,
+    allow_ports: Vec<u16>,
+}
+
+impl HostnamePolicy {
+    /// Checks a parsed host against the host and network rules.
+    ///
+    /// # Errors
+    /// - Returns `Err` describing the first violated rule.
+    fn _check_host(&self, host: &Hostname) -> Result<()> {
+        if let Some(ip) = host._ip()
+            && self.deny_cidrs.iter().any(|cidr| cidr.contains(ip))
+        {
+            return Err(Error::AddressDenied(ip.to_string()));
+        }
+        let unrestricted = self.allow_hosts.is_empty() && self.allow_suffixes.is_empty();
+        if unrestricted
+            || self.allow_hosts.iter().any(|allowed| allowed == host)
+            || self
+                .allow_suffixes
+                .iter()
+                .any(|suffix| host._within(suffix))
+        {
+            Ok(())
+        } else {
+            Err(Error::HostNotAllowed(host.to_string()))
+        }
+    }
+
+    /// Networks that IP literal hosts must not belong to.
+    pub(crate) fn _denied_cidrs(&self) -> &[Cidr] {
+        &self.deny_cidrs
+    }
+
+    /// Checks a full URL against the scheme, port, host and network rules.
+    ///
+    /// # Errors
+    /// - Returns `Err` describing the first violated rule.
+    pub(crate) fn _check_url(&self, url: &Url) -> Result<()> {
+        let scheme = url.scheme();
+        if !self.allow_schemes.is_empty()
+            && !self
+                .allow_schemes
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(scheme))
+        {
+            return Err(Error::SchemeNotAllowed(scheme.to_string()));
+        }
+        if !self.allow_ports.is_empty() {
+            let port = url.port_or_known_default().unwrap_or(0);
+            if !self.allow_ports.contains(&port) {
+                return Err(Error::PortNotAllowed(port));
+            }
+        }
+        self._check_host(&Hostname::_from_parsed_url(url)?)
+    }
+
+    /// Checks a bare host or, if it contains `://`, a URL.
+    ///
+    /// # Errors
+    /// - Returns `Err` describing the first violated rule or the parse failure.
+    fn _assert(&self, host_or_url: &str) -> Result<()> {
+        if host_or_url.contains("://") {
+            let url =
+                Url::parse(host_or_url).map_err(|err| Error::UrlParseError(err.to_string()))?;
+            self._check_url(&url)
+        } else {
+            self._check_host(&Hostname::_parse(host_or_url)?)
+        }
+    }
+}
+
+#[php_impl]
+impl HostnamePolicy {
+    /// Constructs an empty policy, which allows every host, scheme and port.
+    fn __construct() -> Self {
+        Self::default()
+    }
+
+    /// Sets the hosts that are allowed exactly.
+    ///
+    /// # Parameters
+    /// - `hosts`: `array` Hostnames or IP literals, e.g. `['api.example.com', '[::1]']`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if an entry is not a valid hostname.
+    pub(crate) fn allow_hosts(&mut self, hosts: Vec<String>) -> Result<()> {
+        self.allow_hosts = hosts
+            .iter()
+            .map(|host| Hostname::_parse(host))
+            .collect::<hostname::Result<_>>()?;
+        Ok(())
+    }
+
+    /// Sets the domains whose subdomains (and the domain itself) are allowed.
+    ///
+    /// Matching is label-based: `example.com` allows `api.example.com`, but not `evilexample.com`.
+    ///
+    /// # Parameters
+    /// - `suffixes`: `array` Parent domains, e.g. `['example.com']`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if an entry is not a valid hostname.
+    fn allow_suffixes(&mut self, suffixes: Vec<String>) -> Result<()> {
+        self.allow_suffixes = suffixes
+            .iter()
+            .map(|suffix| Hostname::_parse(suffix.trim_start_matches('.')))
+            .collect::<hostname::Result<_>>()?;
+        Ok(())
+    }
+
+    /// Sets networks that IP literal hosts must not belong to, even if otherwise allowed.
+    ///
+    /// # Parameters
+    /// - `cidrs`: `array` Networks, e.g. `['127.0.0.0/8', '10.0.0.0/8', 'fc00::/7']`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if a network is malformed.
+    pub(crate) fn deny_cidrs(&mut self, cidrs: Vec<String>) -> Result<()> {
+        self.deny_cidrs = Cidr::parse_list(&cidrs)?;
+        Ok(())
+    }
+
+    /// Sets the URL schemes that are allowed. An empty list allows any scheme.
+    ///
+    /// # Parameters
+    /// - `schemes`: `array` Schemes, e.g. `['https']`.
+    fn allow_schemes(&mut self, schemes: Vec<String>) {
+        self.allow_schemes = schemes;
+    }
+
+    /// Sets the ports that are allowed; URLs without an explicit port use the scheme's default.
+    /// An empty list allows any port.
+    ///
+    /// # Parameters
+    /// - `ports`: `array` Ports, e.g. `[443, 8443]`.
+    pub(crate) fn allow_ports(&mut self, ports: Vec<u16>) {
+        self.allow_ports = ports;
+    }
+
+    /// Returns true if the host or URL satisfies the policy.
+    ///
+    /// Scheme and port rules only apply to URLs (inputs containing `://`).
+    /// Unparseable input is rejected.
+    ///
+    /// # Parameters
+    /// - `hostOrUrl`: `string` A hostname, IP literal or URL.
+    ///
+    /// # Returns
+    /// - `bool` `true` if allowed.
+    fn check(&self, host_or_url: &str) -> bool {
+        self._assert(host_or_url).is_ok()
+    }
+
+    /// Like `check()`, but throws an exception describing the violated rule.
+    ///
+    /// # Parameters
+    /// - `hostOrUrl`: `string` A hostname, IP literal or URL.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the input cannot be parsed or is not allowed.
+    fn assert(&self, host_or_url: &str) -> Result<()> {
+        self._assert(host_or_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, HostnamePolicy};
+
+    fn policy() -> HostnamePolicy {
+        let mut policy = HostnamePolicy::__construct();
+        policy.allow_hosts(vec!["partner.org".into()]).unwrap();
+        policy.allow_suffixes(vec!["example.com".into()]).unwrap();
+        policy
+            .deny_cidrs(vec!["127.0.0.0/8".into(), "::1".into()])
+            .unwrap();
+        policy.allow_schemes(vec!["https".into()]);
+        policy.allow_ports(vec![443]);
+        policy
+    }
+
+    #[test]
+    fn test_hosts() {
+        let policy = policy();
+        assert!(policy.check("example.com"));
+        assert!(policy.check("API.example.com."));
+        assert!(policy.check("partner.org"));
+        assert!(!policy.check("www.partner.org"));
+        assert!(!policy.check("evilexample.com"));
+        assert!(!policy.check("not a host"));
+        assert!(matches!(
+            policy._assert("evilexample.com"),
+            Err(Error::HostNotAllowed(_))
+        ));
+    }
+
+    #[test]
+    fn test_urls() {
+        let policy = policy();
+        assert!(policy.check("https://api.example.com/hook"));
+        assert!(policy.check("https://api.example.com:443/hook"));
+        assert!(matches!(
+            policy._assert("http://api.example.com/"),
+            Err(Error::SchemeNotAllowed(_))
+        ));
+        assert!(matches!(
+            policy._assert("https://api.example.com:8443/"),
+            Err(Error::PortNotAllowed(8443))
+        ));
+        assert!(!policy.check("https://example.com.evil.net/"));
+    }
+
+    #[test]
+    fn test_denied_cidrs() {
+        let mut policy = HostnamePolicy::__construct();
+        policy.deny_cidrs(vec!["127.0.0.0/8".into()]).unwrap();
+        assert!(policy.check("8.8.8.8"));
+        assert!(matches!(
+            policy._assert("https://127.0.0.2/"),
+            Err(Error::AddressDenied(_))
+        ));
+    }
+}
@@ -0,0 +1,155 @@
+//! `Content-Disposition` header values for downloads (RFC 6266), with the file name in both
+//! an ASCII `filename` fallback and an RFC 5987 `filename*` parameter.
+//!
+//! File names usually come from uploads or the database, so they are cleaned first: control
+//! characters (including CR/LF), invisible bidirectional overrides and path separators never
+//! reach the header or the user's disk.
+
+use crate::path::{is_bidi_or_zero_width, truncate_file_name};
+use ext_php_rs::binary::Binary;
+use ext_php_rs::{php_class, php_impl};
+use std::fmt::Write;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+/// Name used when nothing is left of the given one.
+const DEFAULT_FILENAME: &str = "download";
+
+/// Longest file name in bytes, the limit of common filesystems.
+const MAX_FILENAME_LEN: usize = 255;
+
+/// Cleans a user-supplied file name into a single, visible, NFC-normalized path component.
+///
+/// Invisible formatting such as U+202E, which turns `invoice_fdp.exe` into `invoice_exe.pdf` on
+/// screen, is removed along with control characters.
+fn sanitize(filename: &str) -> String {
+    let cleaned: String = filename
+        .nfc()
+        .filter(|c| !c.is_control() && !is_bidi_or_zero_width(*c))
+        .map(|c| if matches!(c, '/' | '\\') { '_' } else { c })
+        .collect();
+    // Leading dots hide files; trailing dots and spaces are dropped by Windows
+    let name = cleaned.trim_matches(|c: char| c == '.' || c.is_whitespace());
+    if name.is_empty() {
+        return DEFAULT_FILENAME.into();
+    }
+    truncate_file_name(name, MAX_FILENAME_LEN)
+}
+
+/// ASCII approximation of a clean name for the `filename` parameter: accents are stripped,
+/// other non-ASCII characters and `"`, `\` and `%` become `_`.
+fn ascii_fallback(name: &str) -> String {
+    name.nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .map(|c| {
+            if (c.is_ascii_graphic() && !matches!(c, '"' | '\\' | '%')) || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Percent-encodes a name as an RFC 5987 `ext-value`.
+fn ext_value(name: &str) -> String {
+    let mut out = String::from("UTF-8''");
+    for &byte in name.as_bytes() {
+        if byte.is_ascii_alphanumeric()
+            || matches!(
+                byte,
+                b'!' | b'#' | b'$' | b'&' | b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~'
+            )
+        {
+            out.push(char::from(byte));
+        } else {
+            let _ = write!(out, "%{byte:02X}");
+        }
+    }
+    out
+}
+
+/// Builds the header value; `filename*` is added only when the fallback lost something.
+#[must_use]
+pub fn header_value(disposition: &str, filename: &str) -> String {
+    let name = sanitize(filename);
+    let fallback = ascii_fallback(&name);
+    let mut out = format!("{disposition}; filename=\"{fallback}\"");
+    if fallback != name {
+        out.push_str("; filename*=");
+        out.push_str(&ext_value(&name));
+    }
+    out
+}
+
+/// `Content-Disposition` header values.
+#[php_class]
+#[php(name = "Hardened\\Http\\ContentDisposition")]
+pub struct ContentDisposition {}
+
+#[php_impl]
+impl ContentDisposition {
+    /// Build a header value that makes the browser download the response.
+    ///
+    /// # Parameters
+    /// - `filename`: `string` Suggested file name; cleaned of control characters, invisible
+    ///   bidirectional overrides and path separators.
+    ///
+    /// # Returns
+    /// - `string` e.g. `attachment; filename="resume.pdf"; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf`
+    ///   for `résumé.pdf`.
+    fn attachment(filename: Binary<u8>) -> String {
+        header_value("attachment", &String::from_utf8_lossy(&filename))
+    }
+
+    /// Build a header value that lets the browser display the response, with a file name for
+    /// when the user saves it.
+    ///
+    /// # Parameters
+    /// - `filename`: `string` Suggested file name, cleaned as by `attachment()`.
+    ///
+    /// # Returns
+    /// - `string` e.g. `inline; filename="report.pdf"`.
+    fn inline(filename: Binary<u8>) -> String {
+        header_value("inline", &String::from_utf8_lossy(&filename))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{header_value, sanitize};
+
+    #[test]
+    fn test_header_value() {
+        assert_eq!(
+            header_value("attachment", "report.pdf"),
+            "attachment; filename=\"report.pdf\""
+        );
+        assert_eq!(
+            header_value("attachment", "r\u{e9}sum\u{e9}.pdf"),
+            "attachment; filename=\"resume.pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf"
+        );
+        assert_eq!(
+            header_value("inline", "\u{4e2d}\u{6587} \"50%\".txt"),
+            "inline; filename=\"__ _50__.txt\"; filename*=UTF-8''%E4%B8%AD%E6%96%87%20%2250%25%22.txt"
+        );
+    }
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(sanitize("a.txt\r\nSet-Cookie: x=1"), "a.txtSet-Cookie: x=1");
+        assert_eq!(sanitize("../../etc/passwd"), "_.._etc_passwd");
+        assert_eq!(sanitize("C:\\boot.ini"), "C:_boot.ini");
+        assert_eq!(sanitize("invoice_\u{202e}fdp.exe"), "invoice_fdp.exe");
+        assert_eq!(sanitize("a\u{61c}b\u{180e}c.txt"), "abc.txt");
+        assert_eq!(sanitize(" .hidden. "), "hidden");
+        assert_eq!(sanitize("\r\n.."), "download");
+        // Decomposed input is composed, so the fallback and `filename*` agree
+        assert_eq!(sanitize("e\u{301}.txt"), "\u{e9}.txt");
+
+        let long = format!("{}.pdf", "\u{e9}".repeat(200));
+        let short = sanitize(&long);
+        assert!(short.len() <= 255);
+        assert!(short.ends_with("\u{e9}.pdf"));
+    }
+}
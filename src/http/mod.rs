@@ -0,0 +1,14 @@
+//! HTTP header builders under `Hardened\Http`.
+
+use ext_php_rs::builders::ModuleBuilder;
+
+#[cfg(feature = "http")]
+pub mod content_disposition;
+
+pub(crate) fn build(mut module: ModuleBuilder) -> ModuleBuilder {
+    #[cfg(feature = "http")]
+    {
+        module = module.class::<content_disposition::ContentDisposition>();
+    }
+    module
+}
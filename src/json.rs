@@ -0,0 +1,524 @@
+//! Strict JSON decoding and HTML-safe JSON encoding.
+//!
+//! `json_decode()` silently keeps the last of duplicate keys, which lets two parsers of the same
+//! document disagree (a classic signature and authorization bypass), and only limits depth.
+//! `json_encode()` leaves `</script>` and U+2028/U+2029 intact unless every flag is remembered.
+
+use crate::zval_json::is_list;
+use ext_php_rs::binary::Binary;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::types::{ArrayKey, ZendHashTable, Zval};
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde_json::Number;
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::fmt::{self, Write};
+use thiserror::Error;
+
+// Error codes for Json errors: 4500-4599
+pub mod error_codes {
+    pub const TOO_LARGE: i32 = 4500;
+    pub const TOO_DEEP: i32 = 4501;
+    pub const DUPLICATE_KEY: i32 = 4502;
+    pub const NOT_UTF8: i32 = 4503;
+    pub const PARSE: i32 = 4504;
+    pub const INVALID_OPTION: i32 = 4505;
+    pub const UNSUPPORTED_VALUE: i32 = 4506;
+    pub const CONVERSION: i32 = 4507;
+}
+
+/// Errors produced by `Json`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("JSON document is {0} bytes long, the limit is {1}")]
+    TooLarge(usize, usize),
+
+    #[error("JSON is nested deeper than {0} levels")]
+    TooDeep(usize),
+
+    #[error("Duplicate object key: {0:?}")]
+    DuplicateKey(String),
+
+    #[error("JSON must be valid UTF-8")]
+    NotUtf8,
+
+    #[error("JSON parse error: {0}")]
+    Parse(String),
+
+    #[error("Invalid option: {0}")]
+    InvalidOption(String),
+
+    #[error("{0} cannot be represented in JSON")]
+    UnsupportedValue(String),
+
+    #[error("Failed to convert JSON to a PHP value: {0}")]
+    Conversion(String),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::TooLarge(..) => error_codes::TOO_LARGE,
+            Error::TooDeep(_) => error_codes::TOO_DEEP,
+            Error::DuplicateKey(_) => error_codes::DUPLICATE_KEY,
+            Error::NotUtf8 => error_codes::NOT_UTF8,
+            Error::Parse(_) => error_codes::PARSE,
+            Error::InvalidOption(_) => error_codes::INVALID_OPTION,
+            Error::UnsupportedValue(_) => error_codes::UNSUPPORTED_VALUE,
+            Error::Conversion(_) => error_codes::CONVERSION,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for JSON operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Ceiling for `maxDepth`, below the 127 levels `serde_json` itself stops at.
+const MAX_DEPTH: usize = 100;
+
+/// Limits for `Json::decodeStrict()`.
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    /// Largest accepted document in bytes.
+    max_bytes: usize,
+    /// Deepest accepted array/object nesting; a top-level container is level 1.
+    max_depth: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 10 * 1024 * 1024,
+            max_depth: 64,
+        }
+    }
+}
+
+impl Limits {
+    /// Reads limits from a PHP array; unknown keys are rejected.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidOption` for an unknown key or an out-of-range value.
+    fn from_table(table: &ZendHashTable) -> Result<Self> {
+        let mut limits = Self::default();
+        for (key, value) in table {
+            let key = key.to_string();
+            let invalid = || Error::InvalidOption(key.clone());
+            let positive = || {
+                value
+                    .long()
+                    .and_then(|value| usize::try_from(value).ok())
+                    .filter(|value| *value > 0)
+                    .ok_or_else(invalid)
+            };
+            match key.as_str() {
+                "maxBytes" => limits.max_bytes = positive()?,
+                "maxDepth" => {
+                    limits.max_depth = Some(positive()?)
+                        .filter(|depth| *depth <= MAX_DEPTH)
+                        .ok_or_else(invalid)?;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(limits)
+    }
+}
+
+/// A decoded JSON value; unlike `serde_json::Value`, objects keep their key order.
+#[derive(Debug, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+/// Deserializes one value at `depth`, recording why it failed in `failure` so the error keeps
+/// its own code instead of becoming a generic parse error.
+#[derive(Clone, Copy)]
+struct Seed<'a> {
+    depth: usize,
+    max_depth: usize,
+    failure: &'a Cell<Option<Error>>,
+}
+
+impl Seed<'_> {
+    /// Seed for the elements of a container opened at this level.
+    fn child<E: de::Error>(self) -> std::result::Result<Self, E> {
+        if self.depth >= self.max_depth {
+            self.failure.set(Some(Error::TooDeep(self.max_depth)));
+            return Err(E::custom("nesting limit exceeded"));
+        }
+        Ok(Self {
+            depth: self.depth + 1,
+            ..self
+        })
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Seed<'_> {
+    type Value = Value;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Seed<'_> {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> std::result::Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> std::result::Result<Value, E> {
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> std::result::Result<Value, E> {
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> std::result::Result<Value, E> {
+        Number::from_f64(value)
+            .map(Value::Number)
+            .ok_or_else(|| E::custom("number out of range"))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<Value, E> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> std::result::Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<Value, A::Error> {
+        let child = self.child()?;
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element_seed(child)? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Value, A::Error> {
+        let child = self.child()?;
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if !seen.insert(key.clone()) {
+                self.failure.set(Some(Error::DuplicateKey(key)));
+                return Err(de::Error::custom("duplicate key"));
+            }
+            let value = map.next_value_seed(child)?;
+            entries.push((key, value));
+        }
+        Ok(Value::Object(entries))
+    }
+}
+
+/// Parses `json` within `limits`, rejecting duplicate keys.
+///
+/// # Errors
+/// - Returns `Err` if a limit is exceeded, a key repeats, or the input is not valid UTF-8 JSON.
+fn decode(json: &[u8], limits: &Limits) -> Result<Value> {
+    if json.len() > limits.max_bytes {
+        return Err(Error::TooLarge(json.len(), limits.max_bytes));
+    }
+    std::str::from_utf8(json).map_err(|_| Error::NotUtf8)?;
+    let failure = Cell::new(None);
+    let seed = Seed {
+        depth: 0,
+        max_depth: limits.max_depth,
+        failure: &failure,
+    };
+    let mut deserializer = serde_json::Deserializer::from_slice(json);
+    seed.deserialize(&mut deserializer)
+        .and_then(|value| deserializer.end().map(|()| value))
+        .map_err(|err| {
+            failure
+                .take()
+                .unwrap_or_else(|| Error::Parse(err.to_string()))
+        })
+}
+
+/// Array key for an object key: canonical integers become integer keys, as in `json_decode()`.
+fn array_key(key: &str) -> ArrayKey<'_> {
+    match key.parse::<i64>() {
+        Ok(index) if index.to_string() == key => ArrayKey::Long(index),
+        _ => ArrayKey::Str(key),
+    }
+}
+
+/// Converts decoded JSON to a PHP value; objects become associative arrays.
+fn to_zval(value: &Value) -> ext_php_rs::error::Result<Zval> {
+    let mut zval = Zval::new();
+    match value {
+        Value::Null => zval.set_null(),
+        Value::Bool(value) => zval.set_bool(*value),
+        Value::Number(number) => match number.as_i64() {
+            Some(long) => zval.set_long(long),
+            None => zval.set_double(number.as_f64().unwrap_or_default()),
+        },
+        Value::String(value) => zval.set_string(value, false)?,
+        Value::Array(items) => {
+            let mut array = ZendHashTable::new();
+            for item in items {
+                array.push(to_zval(item)?)?;
+            }
+            zval.set_hashtable(array);
+        }
+        Value::Object(entries) => {
+            let mut array = ZendHashTable::new();
+            for (key, value) in entries {
+                array.insert(array_key(key), to_zval(value)?)?;
+            }
+            zval.set_hashtable(array);
+        }
+    }
+    Ok(zval)
+}
+
+/// Appends `value` as a JSON string in which no character can end a `<script>` block, an
+/// HTML attribute or a JavaScript line: `<>&'"` and U+2028/U+2029 are `\u` escapes.
+fn push_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '<' | '>' | '&' | '\'' | '"' | '\u{2028}' | '\u{2029}' | '\0'..='\u{1f}' => {
+                let _ = write!(out, "\\u{:04X}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Appends the JSON encoding of a PHP value.
+///
+/// # Errors
+/// - Returns `Err` for objects, resources, non-finite floats, non-UTF-8 strings, and nesting
+///   deeper than `MAX_DEPTH`.
+fn encode(zval: &Zval, out: &mut String, depth: usize) -> Result<()> {
+    if zval.is_null() {
+        out.push_str("null");
+    } else if zval.is_bool() {
+        out.push_str(if zval.bool().unwrap_or_default() {
+            "true"
+        } else {
+            "false"
+        });
+    } else if zval.is_long() {
+        let _ = write!(out, "{}", zval.long().unwrap_or_default());
+    } else if zval.is_double() {
+        let double = zval.double().unwrap_or_default();
+        let number =
+            Number::from_f64(double).ok_or_else(|| Error::UnsupportedValue(double.to_string()))?;
+        let _ = write!(out, "{number}");
+    } else if zval.is_string() {
+        push_string(out, &zval.string().ok_or(Error::NotUtf8)?);
+    } else if let Some(array) = zval.array() {
+        if depth >= MAX_DEPTH {
+            return Err(Error::TooDeep(MAX_DEPTH));
+        }
+        let list = is_list(array);
+        out.push(if list { '[' } else { '{' });
+        for (index, (key, value)) in array.iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+            if !list {
+                push_string(out, &key.to_string());
+                out.push(':');
+            }
+            encode(value, out, depth + 1)?;
+        }
+        out.push(if list { ']' } else { '}' });
+    } else {
+        return Err(Error::UnsupportedValue(format!(
+            "{} value",
+            zval.get_type()
+        )));
+    }
+    Ok(())
+}
+
+/// Strict JSON decoding and HTML-safe encoding.
+#[php_class]
+#[php(name = "Hardened\\Json")]
+pub struct Json {}
+
+#[php_impl]
+impl Json {
+    /// Decode JSON, rejecting anything `json_decode()` would accept ambiguously.
+    ///
+    /// Objects become associative arrays with their key order preserved. Integers that do
+    /// not fit in an `int` become floats, as with `json_decode()`.
+    ///
+    /// # Parameters
+    /// - `json`: `string`
+    /// - `limits`: `?array` Optional settings:
+    ///   - `maxBytes`: `int` largest document (default 10 MiB).
+    ///   - `maxDepth`: `int` deepest array/object nesting, at most `100` (default `64`).
+    ///
+    /// # Returns
+    /// - `mixed` The decoded value.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if an option is invalid, a limit is exceeded, an object repeats a
+    ///   key, or the input is not valid UTF-8 JSON (lone surrogate escapes included).
+    fn decode_strict(json: Binary<u8>, limits: Option<&ZendHashTable>) -> Result<Zval> {
+        let limits = limits
+            .map(Limits::from_table)
+            .transpose()?
+            .unwrap_or_default();
+        to_zval(&decode(&json, &limits)?).map_err(|err| Error::Conversion(err.to_string()))
+    }
+
+    /// Encode a value as JSON that is safe to embed in HTML.
+    ///
+    /// Equivalent to `json_encode()` with `JSON_HEX_TAG | JSON_HEX_AMP | JSON_HEX_APOS |
+    /// JSON_HEX_QUOT | JSON_UNESCAPED_SLASHES | JSON_UNESCAPED_UNICODE |
+    /// JSON_THROW_ON_ERROR`, plus escaped U+2028/U+2029. The result can go inside
+    /// `<script>`, and inside a single-quoted HTML attribute.
+    ///
+    /// # Parameters
+    /// - `value`: `mixed` Scalars and arrays; lists become JSON arrays, other arrays objects.
+    ///
+    /// # Returns
+    /// - `string`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` for objects, resources, `NAN`/`INF`, non-UTF-8 strings or
+    ///   nesting deeper than 100 levels.
+    fn encode_safe(value: &Zval) -> Result<String> {
+        let mut out = String::new();
+        encode(value, &mut out, 0)?;
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Limits, Value, array_key, decode, push_string};
+    use ext_php_rs::types::ArrayKey;
+
+    fn decode_default(json: &str) -> super::Result<Value> {
+        decode(json.as_bytes(), &Limits::default())
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            decode_default(r#"{"b": [1, -2, 1.5, "\u00e9"], "a": null}"#).unwrap(),
+            Value::Object(vec![
+                (
+                    "b".into(),
+                    Value::Array(vec![
+                        Value::Number(1.into()),
+                        Value::Number((-2).into()),
+                        Value::Number(serde_json::Number::from_f64(1.5).unwrap()),
+                        Value::String("\u{e9}".into()),
+                    ])
+                ),
+                ("a".into(), Value::Null),
+            ])
+        );
+        assert_eq!(decode_default(" true ").unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_decode_rejects() {
+        assert!(matches!(
+            decode_default(r#"{"role": "user", "role": "admin"}"#),
+            Err(Error::DuplicateKey(key)) if key == "role"
+        ));
+        assert!(matches!(
+            decode_default(r#"{"a": {"x": 1, "x": 2}}"#),
+            Err(Error::DuplicateKey(_))
+        ));
+        assert!(matches!(
+            decode(b"\"\xff\"", &Limits::default()),
+            Err(Error::NotUtf8)
+        ));
+        assert!(matches!(
+            decode_default(r#""\ud800""#),
+            Err(Error::Parse(_))
+        ));
+        assert!(matches!(decode_default("[1,]"), Err(Error::Parse(_))));
+        assert!(matches!(decode_default("[1] x"), Err(Error::Parse(_))));
+        assert!(matches!(decode_default(""), Err(Error::Parse(_))));
+
+        let shallow = Limits {
+            max_depth: 2,
+            ..Limits::default()
+        };
+        assert!(decode(b"[[1]]", &shallow).is_ok());
+        assert!(matches!(
+            decode(b"[[[1]]]", &shallow),
+            Err(Error::TooDeep(2))
+        ));
+        let small = Limits {
+            max_bytes: 4,
+            ..Limits::default()
+        };
+        assert!(matches!(
+            decode(b"[1,2]", &small),
+            Err(Error::TooLarge(5, 4))
+        ));
+    }
+
+    #[test]
+    fn test_array_key() {
+        assert!(matches!(array_key("12"), ArrayKey::Long(12)));
+        assert!(matches!(array_key("-3"), ArrayKey::Long(-3)));
+        assert!(matches!(array_key("012"), ArrayKey::Str("012")));
+        assert!(matches!(array_key("+1"), ArrayKey::Str("+1")));
+        assert!(matches!(array_key("a"), ArrayKey::Str("a")));
+    }
+
+    #[test]
+    fn test_push_string() {
+        let mut out = String::new();
+        push_string(
+            &mut out,
+            "</script><b a='x' & \"y\">\u{2028}\\\n\u{1}/\u{e9}",
+        );
+        assert_eq!(
+            out,
+            "\"\\u003C/script\\u003E\\u003Cb a=\\u0027x\\u0027 \\u0026 \\u0022y\\u0022\\u003E\\u2028\\\\\\n\\u0001/\u{e9}\""
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Escaping of untrusted values for LDAP search filters (RFC 4515) and distinguished names
+//! (RFC 4514), the LDAP counterparts of SQL injection.
+
+use ext_php_rs::binary::Binary;
+use ext_php_rs::{php_class, php_impl};
+use std::fmt::Write;
+
+/// Escapes a value for an LDAP search filter assertion, e.g. `(uid=…)`.
+///
+/// Every byte outside printable ASCII, and the filter metacharacters `*()\`, becomes a `\hh`
+/// escape, so the result is plain ASCII that cannot add wildcards or close the filter.
+#[must_use]
+pub fn filter_value(input: &[u8]) -> String {
+    let mut out = String::with_capacity(input.len() * 3);
+    for &byte in input {
+        if matches!(byte, b' '..=b'~') && !matches!(byte, b'*' | b'(' | b')' | b'\\') {
+            out.push(char::from(byte));
+        } else {
+            let _ = write!(out, "\\{byte:02x}");
+        }
+    }
+    out
+}
+
+/// Escapes a value for one attribute of a distinguished name, e.g. `cn=…,ou=people`.
+///
+/// Special characters get a backslash, a leading `#` or space and a trailing space are
+/// escaped, and control characters and invalid UTF-8 bytes become `\hh`.
+#[must_use]
+pub fn dn_value(input: &[u8]) -> String {
+    let mut out = String::with_capacity(input.len() * 2);
+    let last = input.len().saturating_sub(1);
+    for chunk in input.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '"' | '+' | ',' | ';' | '<' | '>' | '=' | '\\' => {
+                    out.push('\\');
+                    out.push(c);
+                }
+                c if c.is_control() => {
+                    let mut buf = [0u8; 4];
+                    for byte in c.encode_utf8(&mut buf).bytes() {
+                        let _ = write!(out, "\\{byte:02x}");
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+        for byte in chunk.invalid() {
+            let _ = write!(out, "\\{byte:02x}");
+        }
+    }
+    // Position checks use the input, where only ASCII can be a leading `#` or an edge space
+    if input
+        .first()
+        .is_some_and(|byte| matches!(byte, b'#' | b' '))
+    {
+        out.insert(0, '\\');
+    }
+    if last > 0 && input[last] == b' ' {
+        out.insert(out.len() - 1, '\\');
+    }
+    out
+}
+
+/// LDAP filter and DN escaping.
+#[php_class]
+#[php(name = "Hardened\\LdapEscape")]
+pub struct LdapEscape {}
+
+#[php_impl]
+impl LdapEscape {
+    /// Escape a value for an LDAP search filter, like `ldap_escape($v, '', LDAP_ESCAPE_FILTER)`
+    /// but also escaping every non-printable and non-ASCII byte.
+    ///
+    /// # Parameters
+    /// - `value`: `string`
+    ///
+    /// # Returns
+    /// - `string` e.g. `"\2a)(uid=\2a"` for `"*)(uid=*"`.
+    fn filter_value(value: Binary<u8>) -> String {
+        filter_value(&value)
+    }
+
+    /// Escape a value for a distinguished name attribute, like
+    /// `ldap_escape($v, '', LDAP_ESCAPE_DN)` but also covering leading `#` and edge spaces.
+    ///
+    /// # Parameters
+    /// - `value`: `string`
+    ///
+    /// # Returns
+    /// - `string` e.g. `"Smith\, John"` for `"Smith, John"`.
+    fn dn_value(value: Binary<u8>) -> String {
+        dn_value(&value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dn_value, filter_value};
+
+    #[test]
+    fn test_filter_value() {
+        assert_eq!(filter_value(b"*)(uid=*"), "\\2a\\29\\28uid=\\2a");
+        assert_eq!(filter_value(b"a\\b\0"), "a\\5cb\\00");
+        assert_eq!(
+            filter_value("Lu\u{e7}i\u{e3}".as_bytes()),
+            "Lu\\c3\\a7i\\c3\\a3"
+        );
+        assert_eq!(filter_value(b"John Smith"), "John Smith");
+    }
+
+    #[test]
+    fn test_dn_value() {
+        assert_eq!(dn_value(b"Smith, John"), "Smith\\, John");
+        assert_eq!(dn_value(b"a+b=c;<d>\"e\\"), "a\\+b\\=c\\;\\<d\\>\\\"e\\\\");
+        assert_eq!(dn_value(b"#admin"), "\\#admin");
+        assert_eq!(dn_value(b" padded "), "\\ padded\\ ");
+        assert_eq!(dn_value(b" "), "\\ ");
+        assert_eq!(dn_value(b"a\0b\xff"), "a\\00b\\ff");
+        assert_eq!(dn_value("Jos\u{e9}".as_bytes()), "Jos\u{e9}");
+        assert_eq!(dn_value(b""), "");
+    }
+}
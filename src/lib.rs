@@ -1,32 +1,104 @@
+pub mod action_token;
+pub mod api_key;
+pub mod client_ip;
+pub mod constant_time;
+pub mod cookie_guard;
+pub mod crypto;
 #[warn(clippy::pedantic)]
 #[allow(clippy::used_underscore_items)]
 pub mod csrf;
+pub mod deserialization_guard;
+mod dns;
+pub mod escape;
+pub mod form_spam;
 pub mod hostname;
+pub mod hostname_policy;
+pub mod http;
+pub mod json;
+pub mod jwt;
+pub mod ldap_escape;
+pub mod mime;
+pub mod nonce_store;
+pub mod origin;
+pub mod password;
 pub mod path;
+pub mod proof_of_work;
+pub mod pwned_passwords;
+pub mod redactor;
+pub mod request_signer;
 pub mod rng;
+pub mod safe_fetch;
 pub mod sanitizers;
 pub mod security_headers;
+pub mod security_txt;
+pub mod session;
 pub mod shell_command;
+pub mod shell_escape;
+pub mod signed_url;
+pub mod sql_escape;
+pub mod sri;
+pub mod totp;
+pub mod unicode_security;
+pub mod url_safety;
+pub mod utf8;
+pub mod xml;
+mod zval_json;
 
+use crate::action_token::ActionToken;
+use crate::api_key::ApiKey;
+use crate::client_ip::ClientIp;
+use crate::constant_time::ConstantTime;
+use crate::cookie_guard::CookieGuard;
 use crate::csrf::Csrf;
+use crate::deserialization_guard::DeserializationGuard;
+use crate::escape::Escape;
+use crate::form_spam::FormSpam;
 pub use crate::hostname::Hostname;
+use crate::hostname_policy::HostnamePolicy;
+use crate::json::Json;
+use crate::jwt::Jwt;
+use crate::ldap_escape::LdapEscape;
+use crate::nonce_store::NonceStore;
+use crate::origin::Origin;
+use crate::password::Password;
 use crate::path::PathObj;
-use crate::rng::Rng;
+use crate::proof_of_work::ProofOfWork;
+use crate::pwned_passwords::PwnedPasswords;
+use crate::redactor::Redactor;
+use crate::request_signer::RequestSigner;
+use crate::rng::{Rng, SeededRng};
+use crate::safe_fetch::SafeFetch;
+use crate::security_headers::audit::Audit as SecurityHeadersAudit;
 use crate::security_headers::cross_origin::embedder_policy::{
     EmbedderPolicy, Policy as EmbedderPolicyValue,
 };
+use crate::security_headers::cross_origin::isolation::CrossOriginIsolation;
 use crate::security_headers::cross_origin::opener_policy::OpenerPolicy;
 use crate::security_headers::cross_origin::resource_policy::ResourcePolicy;
 use crate::security_headers::cross_origin::resource_sharing::ResourceSharing;
 use crate::security_headers::csp::{ContentSecurityPolicy, Keyword as CspKeyword, Rule as CspRule};
 use crate::security_headers::hsts::StrictTransportSecurity;
+use crate::security_headers::manager::Manager as SecurityHeadersManager;
 use crate::security_headers::permissions::{
     Feature as PermissionsPolicyFeature, PermissionsPolicy,
 };
 use crate::security_headers::referrer_policy::ReferrerPolicy;
+use crate::security_headers::reporting::ReportingEndpoints;
+use crate::security_headers::set_cookie::SetCookie;
 use crate::security_headers::whatnot::{
     FrameOptions, PermittedCrossDomainPolicies as CrossDomainPolicy, Whatnot, XssProtection,
 };
+use crate::security_txt::SecurityTxt;
+use crate::session::Session;
+use crate::shell_escape::ShellEscape;
+use crate::signed_url::SignedUrl;
+use crate::sql_escape::SqlEscape;
+use crate::sri::Sri;
+use crate::totp::Totp;
+use crate::unicode_security::UnicodeSecurity;
+use crate::url_safety::UrlSafety;
+use crate::utf8::Utf8;
+use crate::xml::Xml;
 use ext_php_rs::prelude::*;
 use ext_php_rs::types::Zval;
 use thiserror::Error;
@@ -64,6 +136,12 @@ impl From<ConversionError> for ext_php_rs::exception::PhpException {
         ext_php_rs::exception::PhpException::new(message, code, ext_php_rs::zend::ce::exception())
     }
 }
+/// Class entry of PHP's built-in `JsonSerializable` interface, for `#[php(implements(...))]`.
+pub(crate) fn json_serializable() -> &'static ext_php_rs::zend::ClassEntry {
+    ext_php_rs::zend::ClassEntry::try_find("JsonSerializable")
+        .expect("JsonSerializable is always available since PHP 8.0")
+}
+
 #[cfg(test)]
 use std::path::{Path, PathBuf};
 
@@ -75,7 +153,15 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 #[php_module]
 fn get_module(mut module: ModuleBuilder) -> ModuleBuilder {
     module = sanitizers::build(module);
+    module = crypto::build(module);
+    module = http::build(module);
     module = module.name("hardened").version(env!("CARGO_PKG_VERSION"));
+    module = module.class::<ConstantTime>();
+    module = module.class::<DeserializationGuard>();
+    module = module.class::<Escape>();
+    module = module.class::<LdapEscape>();
+    module = module.class::<ShellEscape>();
+    module = module.class::<SqlEscape>();
     #[cfg(feature = "shell_command")]
     {
         module = shell_command::build(module);
@@ -83,6 +169,9 @@ fn get_module(mut module: ModuleBuilder) -> ModuleBuilder {
     #[cfg(feature = "hostname")]
     {
         module = module.class::<Hostname>();
+        module = module.class::<HostnamePolicy>();
+        module = module.class::<Origin>();
+        module = module.class::<UrlSafety>();
     }
     #[cfg(feature = "path")]
     {
@@ -91,11 +180,100 @@ fn get_module(mut module: ModuleBuilder) -> ModuleBuilder {
     #[cfg(feature = "rng")]
     {
         module = module.class::<Rng>();
+        module = module.class::<SeededRng>();
     }
     #[cfg(feature = "csrf")]
     {
         module = module.class::<Csrf>();
     }
+    #[cfg(feature = "sri")]
+    {
+        module = module.class::<Sri>();
+    }
+    #[cfg(feature = "security_txt")]
+    {
+        module = module.class::<SecurityTxt>();
+    }
+    #[cfg(feature = "signed_url")]
+    {
+        module = module.class::<SignedUrl>();
+    }
+    #[cfg(feature = "totp")]
+    {
+        module = module.class::<Totp>();
+    }
+    #[cfg(feature = "jwt")]
+    {
+        module = module.class::<Jwt>();
+    }
+    #[cfg(feature = "password")]
+    {
+        module = module.class::<Password>();
+    }
+    #[cfg(feature = "pwned_passwords")]
+    {
+        module = module.class::<PwnedPasswords>();
+    }
+    #[cfg(feature = "cookie_guard")]
+    {
+        module = module.class::<CookieGuard>();
+    }
+    #[cfg(feature = "proof_of_work")]
+    {
+        module = module.class::<ProofOfWork>();
+    }
+    #[cfg(feature = "form_spam")]
+    {
+        module = module.class::<FormSpam>();
+    }
+    #[cfg(feature = "session")]
+    {
+        module = module.class::<Session>();
+    }
+    #[cfg(feature = "action_token")]
+    {
+        module = module.class::<ActionToken>();
+    }
+    #[cfg(feature = "api_key")]
+    {
+        module = module.class::<ApiKey>();
+    }
+    #[cfg(feature = "nonce_store")]
+    {
+        module = module.class::<NonceStore>();
+    }
+    #[cfg(feature = "request_signer")]
+    {
+        module = module.class::<RequestSigner>();
+    }
+    #[cfg(feature = "redactor")]
+    {
+        module = module.class::<Redactor>();
+    }
+    #[cfg(feature = "client_ip")]
+    {
+        module = module.class::<ClientIp>();
+    }
+    #[cfg(feature = "safe_fetch")]
+    {
+        module = module.class::<SafeFetch>();
+    }
+    #[cfg(feature = "xml")]
+    {
+        module = module.class::<Xml>();
+    }
+    #[cfg(feature = "json")]
+    {
+        module = module.class::<Json>();
+    }
+    #[cfg(feature = "unicode_security")]
+    {
+        module = module.class::<UnicodeSecurity>();
+    }
+    #[cfg(feature = "utf8")]
+    {
+        module = module.class::<Utf8>();
+    }
     #[cfg(feature = "headers")]
     {
         module = module.class::<ContentSecurityPolicy>();
@@ -109,11 +287,16 @@ fn get_module(mut module: ModuleBuilder) -> ModuleBuilder {
         module = module.class::<PermissionsPolicy>();
         module = module.enumeration::<PermissionsPolicyFeature>();
         module = module.class::<ReferrerPolicy>();
+        module = module.class::<ReportingEndpoints>();
         module = module.class::<ResourceSharing>();
         module = module.class::<EmbedderPolicy>();
         module = module.enumeration::<EmbedderPolicyValue>();
         module = module.class::<ResourcePolicy>();
         module = module.class::<OpenerPolicy>();
+        module = module.class::<CrossOriginIsolation>();
+        module = module.class::<SetCookie>();
+        module = module.class::<SecurityHeadersManager>();
+        module = module.class::<SecurityHeadersAudit>();
     }
     module
 }
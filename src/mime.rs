@@ -0,0 +1,236 @@
+//! Content type detection from magic bytes.
+//!
+//! Only the leading bytes of a file are inspected, so detection is cheap and never parses
+//! the whole document. The result is meant for cross-checking a claimed file extension,
+//! not for deciding whether a file is safe to process.
+
+/// Number of leading bytes needed by [`sniff`].
+pub const SNIFF_LEN: usize = 4096;
+
+/// A detected content type and the file extensions commonly used for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileType {
+    pub mime: &'static str,
+    pub extensions: &'static [&'static str],
+}
+
+impl FileType {
+    const fn new(mime: &'static str, extensions: &'static [&'static str]) -> Self {
+        Self { mime, extensions }
+    }
+
+    /// Returns true if `extension` (without leading dot) is usual for this type, case-insensitively.
+    #[must_use]
+    pub fn matches_extension(&self, extension: &str) -> bool {
+        self.extensions
+            .iter()
+            .any(|ext| ext.eq_ignore_ascii_case(extension))
+    }
+}
+
+pub const OCTET_STREAM: FileType = FileType::new("application/octet-stream", &[]);
+const TEXT: FileType = FileType::new(
+    "text/plain",
+    &[
+        "txt", "csv", "tsv", "md", "log", "json", "xml", "yml", "yaml", "ini",
+    ],
+);
+
+/// Signatures matched at offset 0.
+const PREFIXES: &[(&[u8], FileType)] = &[
+    (b"\x89PNG\r\n\x1a\n", FileType::new("image/png", &["png"])),
+    (
+        b"\xFF\xD8\xFF",
+        FileType::new("image/jpeg", &["jpg", "jpeg"]),
+    ),
+    (b"GIF87a", FileType::new("image/gif", &["gif"])),
+    (b"GIF89a", FileType::new("image/gif", &["gif"])),
+    (b"II*\0", FileType::new("image/tiff", &["tif", "tiff"])),
+    (b"MM\0*", FileType::new("image/tiff", &["tif", "tiff"])),
+    (b"%PDF-", FileType::new("application/pdf", &["pdf"])),
+    (
+        b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1",
+        FileType::new("application/x-ole-storage", &["doc", "xls", "ppt", "msi"]),
+    ),
+    (
+        b"OggS",
+        FileType::new("audio/ogg", &["ogg", "oga", "ogv", "opus"]),
+    ),
+    (b"fLaC", FileType::new("audio/flac", &["flac"])),
+    (b"ID3", FileType::new("audio/mpeg", &["mp3"])),
+    (b"FLV", FileType::new("video/x-flv", &["flv"])),
+    (
+        b"\x1F\x8B",
+        FileType::new("application/gzip", &["gz", "tgz"]),
+    ),
+    (
+        b"Rar!\x1A\x07",
+        FileType::new("application/vnd.rar", &["rar"]),
+    ),
+    (
+        b"7z\xBC\xAF\x27\x1C",
+        FileType::new("application/x-7z-compressed", &["7z"]),
+    ),
+    (
+        b"\x7FELF",
+        FileType::new("application/x-executable", &["so", "bin"]),
+    ),
+    (
+        b"MZ",
+        FileType::new(
+            "application/vnd.microsoft.portable-executable",
+            &["exe", "dll"],
+        ),
+    ),
+];
+
+const ZIP: FileType = FileType::new(
+    "application/zip",
+    &["zip", "jar", "apk", "epub", "odt", "ods", "odp"],
+);
+const DOCX: FileType = FileType::new(
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    &["docx"],
+);
+const XLSX: FileType = FileType::new(
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    &["xlsx"],
+);
+const PPTX: FileType = FileType::new(
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    &["pptx"],
+);
+
+/// Detects the content type of `head`, the leading bytes of a file (up to [`SNIFF_LEN`]).
+#[must_use]
+pub fn sniff(head: &[u8]) -> FileType {
+    if let Some((_, file_type)) = PREFIXES.iter().find(|(magic, _)| head.starts_with(magic)) {
+        return *file_type;
+    }
+    // "BM" alone is too common at the start of text; also require the zeroed reserved fields
+    if head.len() >= 14 && head.starts_with(b"BM") && head[6..10] == [0; 4] {
+        return FileType::new("image/bmp", &["bmp"]);
+    }
+    if head.starts_with(b"PK\x03\x04") {
+        return sniff_zip(head);
+    }
+    if head.len() >= 12 && head.starts_with(b"RIFF") {
+        match &head[8..12] {
+            b"WEBP" => return FileType::new("image/webp", &["webp"]),
+            b"WAVE" => return FileType::new("audio/wav", &["wav"]),
+            b"AVI " => return FileType::new("video/x-msvideo", &["avi"]),
+            _ => {}
+        }
+    }
+    if head.len() >= 12 && &head[4..8] == b"ftyp" {
+        return match &head[8..12] {
+            b"qt  " => FileType::new("video/quicktime", &["mov"]),
+            b"M4A " => FileType::new("audio/mp4", &["m4a"]),
+            _ => FileType::new("video/mp4", &["mp4", "m4v"]),
+        };
+    }
+    if head.starts_with(b"\x1A\x45\xDF\xA3") {
+        return if contains(head, b"webm") {
+            FileType::new("video/webm", &["webm"])
+        } else {
+            FileType::new("video/x-matroska", &["mkv", "mka"])
+        };
+    }
+    if head.len() >= 2 && head[0] == 0xFF && matches!(head[1], 0xF1 | 0xF9) {
+        return FileType::new("audio/aac", &["aac"]);
+    }
+    if head.len() >= 2 && head[0] == 0xFF && head[1] & 0xE0 == 0xE0 {
+        return FileType::new("audio/mpeg", &["mp3"]);
+    }
+    sniff_text(head)
+}
+
+/// Tells OOXML documents apart from plain archives by the entry names in the first headers.
+fn sniff_zip(head: &[u8]) -> FileType {
+    if contains(head, b"word/") {
+        DOCX
+    } else if contains(head, b"xl/") {
+        XLSX
+    } else if contains(head, b"ppt/") {
+        PPTX
+    } else {
+        ZIP
+    }
+}
+
+/// Recognizes markup and plain text, which have no magic number.
+fn sniff_text(head: &[u8]) -> FileType {
+    if head.contains(&0) {
+        return OCTET_STREAM;
+    }
+    // The head may end in the middle of a multi-byte character
+    let text = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        Err(err) if err.error_len().is_none() => {
+            std::str::from_utf8(&head[..err.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return OCTET_STREAM,
+    };
+    let trimmed = text.trim_start_matches('\u{FEFF}').trim_start();
+    let lower = trimmed
+        .get(..trimmed.len().min(512))
+        .unwrap_or(trimmed)
+        .to_ascii_lowercase();
+    if lower.starts_with("<svg") || (lower.starts_with("<?xml") && lower.contains("<svg")) {
+        FileType::new("image/svg+xml", &["svg"])
+    } else if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+        FileType::new("text/html", &["html", "htm"])
+    } else if lower.starts_with("<?php") {
+        FileType::new("application/x-httpd-php", &["php"])
+    } else if lower.starts_with("<?xml") {
+        FileType::new("application/xml", &["xml"])
+    } else {
+        TEXT
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sniff;
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").mime, "image/png");
+        assert_eq!(sniff(b"\xFF\xD8\xFF\xE0\0\x10JFIF").mime, "image/jpeg");
+        assert_eq!(sniff(b"RIFF\0\0\0\0WEBPVP8 ").mime, "image/webp");
+        assert_eq!(sniff(b"\0\0\0\x18ftypmp42").mime, "video/mp4");
+        assert_eq!(sniff(b"%PDF-1.7\n").mime, "application/pdf");
+        assert_eq!(
+            sniff(b"PK\x03\x04\x14\0\0\0\x08\0[Content_Types].xmlword/document.xml").extensions,
+            ["docx"]
+        );
+        assert_eq!(
+            sniff(b"PK\x03\x04\x14\0\0\0\x08\0data.csv").mime,
+            "application/zip"
+        );
+        assert_eq!(
+            sniff(
+                b"\xEF\xBB\xBF<?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\">"
+            )
+            .mime,
+            "image/svg+xml"
+        );
+        assert_eq!(sniff(b"<?php echo 1;").mime, "application/x-httpd-php");
+        assert_eq!(sniff("name,price\nкофе,1\n".as_bytes()).mime, "text/plain");
+        assert_eq!(sniff(b"\x00\x01\x02\x03").mime, "application/octet-stream");
+    }
+
+    #[test]
+    fn test_matches_extension() {
+        let png = sniff(b"\x89PNG\r\n\x1a\n");
+        assert!(png.matches_extension("PNG"));
+        assert!(!png.matches_extension("jpg"));
+        assert!(!sniff(b"MZ\x90\0").matches_extension("pdf"));
+    }
+}
@@ -0,0 +1,355 @@
+//! Single-use nonces: one-time CSRF tokens, webhook delivery ids, OAuth/OIDC `nonce` values.
+//!
+//! `consume()` records a nonce for a TTL and reports whether this was its first use. Nonces
+//! are kept in process memory by default, which suits tests and long-running workers; shared
+//! deployments plug in Redis, Memcached or a database through `setBackend()`.
+
+use data_encoding::HEXLOWER;
+use ext_php_rs::convert::IntoZvalDyn;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::prelude::ZendCallable;
+use ext_php_rs::types::Zval;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+// Error codes for NonceStore errors: 3700-3799
+pub mod error_codes {
+    pub const INVALID_NONCE: i32 = 3700;
+    pub const INVALID_TTL: i32 = 3701;
+    pub const INVALID_OPTION: i32 = 3702;
+    pub const BACKEND_NOT_CALLABLE: i32 = 3703;
+    pub const BACKEND_FAILED: i32 = 3704;
+    pub const FULL: i32 = 3705;
+}
+
+/// Errors produced by `NonceStore`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Nonce must not be empty")]
+    InvalidNonce,
+
+    #[error("TTL must be a positive number of seconds, got {0}")]
+    InvalidTtl(i64),
+
+    #[error("Invalid option: {0}")]
+    InvalidOption(String),
+
+    #[error("Backend `{0}` callback is not callable")]
+    BackendNotCallable(&'static str),
+
+    #[error("Backend callback failed: {0}")]
+    BackendFailed(String),
+
+    #[error("In-memory nonce store is full ({0} live entries)")]
+    Full(usize),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::InvalidNonce => error_codes::INVALID_NONCE,
+            Error::InvalidTtl(_) => error_codes::INVALID_TTL,
+            Error::InvalidOption(_) => error_codes::INVALID_OPTION,
+            Error::BackendNotCallable(_) => error_codes::BACKEND_NOT_CALLABLE,
+            Error::BackendFailed(_) => error_codes::BACKEND_FAILED,
+            Error::Full(_) => error_codes::FULL,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for nonce store operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+const DEFAULT_CAPACITY: usize = 100_000;
+
+/// Current UNIX time in seconds.
+fn unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+/// Storage key for a nonce: its hex SHA-256, so keys have a fixed length and the backend
+/// never holds the nonce itself.
+fn nonce_key(nonce: &str) -> Result<String> {
+    if nonce.is_empty() {
+        return Err(Error::InvalidNonce);
+    }
+    Ok(HEXLOWER.encode(&Sha256::digest(nonce.as_bytes())))
+}
+
+/// Calls a backend callback with the given arguments.
+fn call(callback: &Zval, args: Vec<&dyn IntoZvalDyn>) -> Result<Zval> {
+    ZendCallable::new(callback)
+        .map_err(|err| Error::BackendFailed(err.to_string()))?
+        .try_call(args)
+        .map_err(|err| Error::BackendFailed(err.to_string()))
+}
+
+/// Calls a backend callback that must return a `bool`.
+fn call_bool(callback: &Zval, args: Vec<&dyn IntoZvalDyn>) -> Result<bool> {
+    call(callback, args)?
+        .bool()
+        .ok_or_else(|| Error::BackendFailed("callback must return a bool".into()))
+}
+
+/// Where nonces are kept.
+enum Backend {
+    /// Key to expiry, pruned when the capacity is reached.
+    Memory {
+        entries: HashMap<String, i64>,
+        capacity: usize,
+    },
+    /// `(get, set, delete)` callbacks.
+    Callbacks { get: Zval, set: Zval, delete: Zval },
+}
+
+impl Backend {
+    /// The `(get, set, delete)` callbacks, cloned so that they can be called without holding a
+    /// borrow of the backend.
+    fn callbacks(&self) -> Option<(Zval, Zval, Zval)> {
+        match self {
+            Backend::Memory { .. } => None,
+            Backend::Callbacks { get, set, delete } => Some((
+                get.shallow_clone(),
+                set.shallow_clone(),
+                delete.shallow_clone(),
+            )),
+        }
+    }
+}
+
+/// Replay-protection store for single-use values.
+///
+/// Clones share the backend, so `CsrfProtection::setReplayNonceStore()` and the PHP object
+/// see the same nonces.
+#[php_class]
+#[php(name = "Hardened\\NonceStore")]
+#[derive(Clone)]
+pub struct NonceStore {
+    backend: Rc<RefCell<Backend>>,
+}
+
+impl NonceStore {
+    /// Backs `__construct()`.
+    pub(crate) fn _new(capacity: Option<i64>) -> Result<Self> {
+        let capacity = match capacity {
+            None => DEFAULT_CAPACITY,
+            Some(capacity) => usize::try_from(capacity)
+                .ok()
+                .filter(|capacity| *capacity > 0)
+                .ok_or_else(|| {
+                    Error::InvalidOption(format!("Capacity must be positive, got {capacity}"))
+                })?,
+        };
+        Ok(Self {
+            backend: Rc::new(RefCell::new(Backend::Memory {
+                entries: HashMap::new(),
+                capacity,
+            })),
+        })
+    }
+
+    pub(crate) fn _consume_at(&self, nonce: &str, ttl: i64, now: i64) -> Result<bool> {
+        if ttl <= 0 {
+            return Err(Error::InvalidTtl(ttl));
+        }
+        let key = nonce_key(nonce)?;
+        let callbacks = self.backend.borrow().callbacks();
+        if let Some((_, set, _)) = callbacks {
+            return call_bool(&set, vec![&key, &ttl]);
+        }
+        if let Backend::Memory { entries, capacity } = &mut *self.backend.borrow_mut() {
+            if entries.get(&key).is_some_and(|expires| *expires > now) {
+                return Ok(false);
+            }
+            if entries.len() >= *capacity {
+                entries.retain(|_, expires| *expires > now);
+                // Evicting live nonces would reopen them to replay
+                if entries.len() >= *capacity {
+                    return Err(Error::Full(entries.len()));
+                }
+            }
+            entries.insert(key, now.saturating_add(ttl));
+        }
+        Ok(true)
+    }
+
+    pub(crate) fn _is_consumed_at(&self, nonce: &str, now: i64) -> Result<bool> {
+        let key = nonce_key(nonce)?;
+        let callbacks = self.backend.borrow().callbacks();
+        if let Some((get, _, _)) = callbacks {
+            return call_bool(&get, vec![&key]);
+        }
+        Ok(matches!(
+            &*self.backend.borrow(),
+            Backend::Memory { entries, .. } if entries.get(&key).is_some_and(|expires| *expires > now)
+        ))
+    }
+}
+
+#[php_impl]
+impl NonceStore {
+    /// Constructs a store that keeps nonces in process memory.
+    ///
+    /// Memory is per process: under PHP-FPM each worker has its own, so use `setBackend()`
+    /// with a shared store there.
+    ///
+    /// # Parameters
+    /// - `capacity`: `?int` Most live nonces kept in memory. Default: 100000.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `capacity` is not positive.
+    fn __construct(capacity: Option<i64>) -> Result<Self> {
+        Self::_new(capacity)
+    }
+
+    /// Keep nonces in an external store instead of memory.
+    ///
+    /// The callbacks receive the SHA-256 hex digest of a nonce, never the nonce itself:
+    /// - `get(string $key): bool` returns whether the key is recorded.
+    /// - `set(string $key, int $ttl): bool` must atomically record the key for `$ttl` seconds
+    ///   and return `true` only if it was not recorded yet
+    ///   (e.g. `$redis->set("nonce:$key", 1, ['nx', 'ex' => $ttl])` or `Memcached::add()`).
+    /// - `delete(string $key): mixed` forgets the key.
+    ///
+    /// # Parameters
+    /// - `get`: `callable`
+    /// - `set`: `callable`
+    /// - `delete`: `callable`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if an argument is not callable.
+    fn set_backend(&mut self, get: &Zval, set: &Zval, delete: &Zval) -> Result<()> {
+        for (name, callback) in [("get", get), ("set", set), ("delete", delete)] {
+            if !callback.is_callable() {
+                return Err(Error::BackendNotCallable(name));
+            }
+        }
+        *self.backend.borrow_mut() = Backend::Callbacks {
+            get: get.shallow_clone(),
+            set: set.shallow_clone(),
+            delete: delete.shallow_clone(),
+        };
+        Ok(())
+    }
+
+    /// Record a nonce as used.
+    ///
+    /// Check the nonce's signature or origin first, then act only if this returns `true`.
+    ///
+    /// # Parameters
+    /// - `nonce`: `string`
+    /// - `ttl`: `int` Seconds to remember it; at least as long as the nonce is otherwise
+    ///   accepted (token lifetime, webhook timestamp tolerance…).
+    ///
+    /// # Returns
+    /// - `bool` `true` on first use, `false` on a replay.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the nonce is empty, `ttl` is not positive, the memory store is
+    ///   full of live nonces, or a backend callback fails.
+    fn consume(&self, nonce: &str, ttl: i64) -> Result<bool> {
+        self._consume_at(nonce, ttl, unix_time())
+    }
+
+    /// Check whether a nonce has been consumed, without recording it.
+    ///
+    /// # Parameters
+    /// - `nonce`: `string`
+    ///
+    /// # Returns
+    /// - `bool`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the nonce is empty or a backend callback fails.
+    fn is_consumed(&self, nonce: &str) -> Result<bool> {
+        self._is_consumed_at(nonce, unix_time())
+    }
+
+    /// Forget a nonce, e.g. when the action it guarded failed and may be retried.
+    ///
+    /// # Parameters
+    /// - `nonce`: `string`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the nonce is empty or a backend callback fails.
+    fn release(&self, nonce: &str) -> Result<()> {
+        let key = nonce_key(nonce)?;
+        let callbacks = self.backend.borrow().callbacks();
+        match callbacks {
+            Some((_, _, delete)) => {
+                call(&delete, vec![&key])?;
+            }
+            None => {
+                if let Backend::Memory { entries, .. } = &mut *self.backend.borrow_mut() {
+                    entries.remove(&key);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, NonceStore, nonce_key};
+
+    #[test]
+    fn test_consume() {
+        let store = NonceStore::_new(None).unwrap();
+        assert!(store._consume_at("abc", 60, 1000).unwrap());
+        assert!(!store._consume_at("abc", 60, 1030).unwrap());
+        assert!(store._is_consumed_at("abc", 1059).unwrap());
+        assert!(!store._is_consumed_at("abc", 1060).unwrap());
+        // Expired nonces may be used again
+        assert!(store._consume_at("abc", 60, 1060).unwrap());
+        assert!(store._consume_at("def", 60, 1060).unwrap());
+
+        // Clones share the nonces
+        let shared = store.clone();
+        assert!(!shared._consume_at("def", 60, 1061).unwrap());
+
+        store.release("abc").unwrap();
+        assert!(store._consume_at("abc", 60, 1061).unwrap());
+
+        assert!(matches!(
+            store._consume_at("", 60, 0),
+            Err(Error::InvalidNonce)
+        ));
+        assert!(matches!(
+            store._consume_at("x", 0, 0),
+            Err(Error::InvalidTtl(0))
+        ));
+        assert_eq!(nonce_key("abc").unwrap().len(), 64);
+    }
+
+    #[test]
+    fn test_capacity() {
+        let store = NonceStore::_new(Some(2)).unwrap();
+        assert!(store._consume_at("a", 10, 0).unwrap());
+        assert!(store._consume_at("b", 100, 0).unwrap());
+        assert!(matches!(store._consume_at("c", 10, 5), Err(Error::Full(2))));
+        // Pruning the expired `a` makes room
+        assert!(store._consume_at("c", 10, 10).unwrap());
+        assert!(!store._consume_at("b", 10, 10).unwrap());
+        assert!(NonceStore::_new(Some(0)).is_err());
+    }
+}
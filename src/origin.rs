@@ -0,0 +1,232 @@
+use crate::hostname::{Error, Hostname, Result};
+use crate::to_str;
+use ext_php_rs::types::Zval;
+use ext_php_rs::{php_class, php_impl};
+use url::Url;
+
+/// A web origin: scheme, host and port, as used by CORS, `postMessage` and redirect checks.
+///
+/// Default ports are normalized away, so `https://example.com` and `https://example.com:443`
+/// are the same origin, while `http://example.com` and `https://example.com` are not.
+#[php_class]
+#[php(name = "Hardened\\Origin")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    scheme: String,
+    host: Hostname,
+    port: u16,
+}
+
+/// An origin pattern of the form `[scheme://]host[:port]`, where the host may start with a
+/// `*.` (one label) or `**.` (any depth) wildcard, e.g. `https://*.example.com:8443`.
+///
+/// The scheme defaults to `https` and the port to the scheme's default, so a pattern never
+/// matches a different scheme or port than it spells out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OriginPattern {
+    scheme: String,
+    host: String,
+    port: u16,
+}
+
+impl OriginPattern {
+    /// Parses an origin pattern.
+    ///
+    /// # Errors
+    /// - Returns `InvalidPattern` if the scheme has no tuple origin, the port is invalid, or
+    ///   the host is not a valid hostname pattern.
+    pub(crate) fn parse(pattern: &str) -> Result<Self> {
+        let invalid = || Error::InvalidPattern(pattern.to_string());
+        let (scheme, rest) = pattern
+            .split_once("://")
+            .map_or(("https".to_string(), pattern), |(scheme, rest)| {
+                (scheme.to_ascii_lowercase(), rest)
+            });
+        let (host, port) = match rest.rsplit_once(':') {
+            Some((host, port)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => {
+                (host, Some(port.parse::<u16>().map_err(|_| invalid())?))
+            }
+            _ => (rest, None),
+        };
+        let port = port.unwrap_or(match scheme.as_str() {
+            "https" | "wss" => 443,
+            "http" | "ws" => 80,
+            "ftp" => 21,
+            _ => return Err(invalid()),
+        });
+        let base = host
+            .strip_prefix("**.")
+            .or_else(|| host.strip_prefix("*."))
+            .unwrap_or(host);
+        if base.contains('*') || Hostname::_parse(base).is_err() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            scheme,
+            host: host.to_ascii_lowercase(),
+            port,
+        })
+    }
+
+    /// Returns true if the origin has the pattern's scheme and port and its host matches the
+    /// host pattern. The apex never matches a wildcard, so `*.example.com` excludes `example.com`.
+    pub(crate) fn matches(&self, origin: &Origin) -> bool {
+        origin.scheme == self.scheme
+            && origin.port == self.port
+            && origin
+                .host
+                ._matches_pattern(&self.host, false)
+                .unwrap_or(false)
+    }
+}
+
+impl Origin {
+    /// Builds the origin of a URL string.
+    ///
+    /// # Errors
+    /// - Returns `Err` if the URL cannot be parsed or has an opaque origin (e.g. `data:`, `file:`).
+    pub(crate) fn _from_url(url: &str) -> Result<Self> {
+        let parsed = Url::parse(url).map_err(|err| Error::UrlParseError(err.to_string()))?;
+        // Only these schemes have tuple origins; `port_or_known_default()` knows all of them
+        if !matches!(parsed.scheme(), "http" | "https" | "ws" | "wss" | "ftp") {
+            return Err(Error::OpaqueOrigin(url.to_string()));
+        }
+        Ok(Self {
+            scheme: parsed.scheme().to_string(),
+            host: Hostname::_from_parsed_url(&parsed)?,
+            port: parsed.port_or_known_default().ok_or(Error::NoHost)?,
+        })
+    }
+
+    /// Returns the default port of the scheme.
+    fn _default_port(&self) -> u16 {
+        match self.scheme.as_str() {
+            "https" | "wss" => 443,
+            "ftp" => 21,
+            _ => 80,
+        }
+    }
+}
+
+#[php_impl]
+impl Origin {
+    /// Parses the origin of a URL.
+    ///
+    /// # Parameters
+    /// - `url`: `string` A URL, e.g. `https://Example.com:443/path`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the URL is invalid or has an opaque origin (`data:`, `file:`, ...).
+    fn from(url: &Zval) -> Result<Self> {
+        Self::_from_url(&to_str(url).map_err(|_| Error::StringConversionError)?)
+    }
+
+    /// Constructs a new Origin instance (alias for `from`).
+    ///
+    /// # Parameters
+    /// - `url`: `string` A URL.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the URL is invalid or has an opaque origin.
+    fn __construct(url: &Zval) -> Result<Self> {
+        Self::from(url)
+    }
+
+    /// Returns the lowercase scheme, e.g. `https`.
+    fn scheme(&self) -> String {
+        self.scheme.clone()
+    }
+
+    /// Returns the normalized host.
+    fn host(&self) -> Hostname {
+        self.host.clone()
+    }
+
+    /// Returns the effective port, with the scheme's default filled in (e.g. `443` for `https`).
+    fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns true if the URL belongs to this origin.
+    ///
+    /// # Parameters
+    /// - `url`: `string` The URL to compare, e.g. a redirect target or an `Origin` header.
+    ///
+    /// # Returns
+    /// - `bool` `true` if scheme, host and port are all equal; URLs with opaque origins never match.
+    fn same_origin(&self, url: &Zval) -> Result<bool> {
+        let url = to_str(url).map_err(|_| Error::StringConversionError)?;
+        match Self::_from_url(&url) {
+            Ok(other) => Ok(*self == other),
+            Err(Error::OpaqueOrigin(_)) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Serializes the origin as in the `Origin` header, omitting the default port.
+    ///
+    /// # Returns
+    /// - `string` E.g. `https://example.com` or `http://example.com:8080`.
+    fn __to_string(&self) -> String {
+        if self._default_port() == self.port {
+            format!("{}://{}", self.scheme, self.host)
+        } else {
+            format!("{}://{}:{}", self.scheme, self.host, self.port)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Origin, OriginPattern};
+    use crate::hostname::Error;
+
+    #[test]
+    fn test_origin() -> crate::TestResult {
+        let origin = Origin::_from_url("HTTPS://Example.COM.:443/path?q")?;
+        assert_eq!(origin.scheme(), "https");
+        assert_eq!(origin.port(), 443);
+        assert_eq!(origin.__to_string(), "https://example.com");
+        assert_eq!(
+            Origin::_from_url("http://example.com:8080/")?.__to_string(),
+            "http://example.com:8080"
+        );
+
+        assert_eq!(origin, Origin::_from_url("https://example.com/other")?);
+        assert_ne!(origin, Origin::_from_url("http://example.com/")?);
+        assert_ne!(origin, Origin::_from_url("https://example.com:8443/")?);
+        assert_ne!(origin, Origin::_from_url("https://www.example.com/")?);
+
+        assert!(matches!(
+            Origin::_from_url("data:text/html,hi"),
+            Err(Error::OpaqueOrigin(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_origin_pattern() -> crate::TestResult {
+        let pattern = OriginPattern::parse("*.Example.com")?;
+        assert!(pattern.matches(&Origin::_from_url("https://api.example.com")?));
+        assert!(pattern.matches(&Origin::_from_url("https://api.example.com:443")?));
+        assert!(!pattern.matches(&Origin::_from_url("http://api.example.com")?));
+        assert!(!pattern.matches(&Origin::_from_url("https://api.example.com:8443")?));
+        assert!(!pattern.matches(&Origin::_from_url("https://example.com")?));
+        assert!(!pattern.matches(&Origin::_from_url("https://a.b.example.com")?));
+        assert!(!pattern.matches(&Origin::_from_url("https://evilexample.com")?));
+
+        let pattern = OriginPattern::parse("http://**.example.com:8080")?;
+        assert!(pattern.matches(&Origin::_from_url("http://a.b.example.com:8080")?));
+        assert!(!pattern.matches(&Origin::_from_url("http://a.b.example.com")?));
+
+        for bad in [
+            "a*.example.com",
+            "data://*.example.com",
+            "*.example.com:99999",
+            "*.",
+        ] {
+            assert!(OriginPattern::parse(bad).is_err(), "{bad}");
+        }
+        Ok(())
+    }
+}
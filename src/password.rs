@@ -0,0 +1,395 @@
+//! Password hashing with Argon2id, or scrypt where Argon2 is not an option.
+//!
+//! Hashes are PHC strings (`$argon2id$v=19$m=…,t=…,p=…$salt$hash`), the same format PHP's
+//! `password_hash(…, PASSWORD_ARGON2ID)` produces, so existing hashes verify here and new ones
+//! verify with `password_verify()`. An instance is a policy: `needsRehash()` reports hashes made
+//! with any other algorithm or cost, so they can be upgraded at the next login.
+
+use crate::rng::random_bytes;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Argon2, Version};
+use ext_php_rs::binary::Binary;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use scrypt::Scrypt;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+// Error codes for Password errors: 2800-2899
+pub mod error_codes {
+    pub const INVALID_PARAMS: i32 = 2800;
+    pub const HASH_FAILED: i32 = 2801;
+    pub const RANDOM: i32 = 2802;
+}
+
+/// Errors produced by `Password`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Invalid password hashing parameters: {0}")]
+    InvalidParams(String),
+
+    #[error("Password hashing failed: {0}")]
+    HashFailed(String),
+
+    #[error("Failed to generate salt: {0}")]
+    Random(String),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::InvalidParams(_) => error_codes::INVALID_PARAMS,
+            Error::HashFailed(_) => error_codes::HASH_FAILED,
+            Error::Random(_) => error_codes::RANDOM,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for password hashing operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// OWASP's recommended Argon2id configuration: 19 MiB, 2 passes, 1 lane.
+const DEFAULT_MEMORY_COST: u32 = 19 * 1024;
+const DEFAULT_TIME_COST: u32 = 2;
+const DEFAULT_PARALLELISM: u32 = 1;
+/// Memory of OWASP's cheapest acceptable Argon2id configuration (7 MiB with 5 passes).
+const MIN_MEMORY_COST: u32 = 7 * 1024;
+/// Memory `benchmark()` holds fixed while searching for the time cost: PHP's default, 64 MiB.
+const BENCHMARK_MEMORY_COST: u32 = 64 * 1024;
+/// OWASP's recommended scrypt configuration: N = 2^17, r = 8, p = 1.
+const DEFAULT_SCRYPT_LOG_N: u8 = 17;
+const DEFAULT_SCRYPT_R: u32 = 8;
+const DEFAULT_SCRYPT_P: u32 = 1;
+/// scrypt cost below which a hash is too cheap to slow down an offline attack (N = 2^13).
+const MIN_SCRYPT_LOG_N: u8 = 13;
+const SALT_LEN: usize = 16;
+const OUTPUT_LEN: usize = 32;
+
+/// Algorithm and cost a `Password` instance hashes with.
+#[derive(Debug, Clone)]
+enum Policy {
+    Argon2id(argon2::Params),
+    Scrypt(scrypt::Params),
+}
+
+/// Reads an optional PHP integer as `u32`, falling back to `default`.
+fn cost(name: &str, value: Option<i64>, default: u32) -> Result<u32> {
+    value.map_or(Ok(default), |value| {
+        u32::try_from(value)
+            .map_err(|_| Error::InvalidParams(format!("{name} out of range: {value}")))
+    })
+}
+
+/// Argon2id parameters, rejecting memory costs below OWASP's minimum.
+fn argon2_params(memory_cost: u32, time_cost: u32, parallelism: u32) -> Result<argon2::Params> {
+    if memory_cost < MIN_MEMORY_COST {
+        return Err(Error::InvalidParams(format!(
+            "memory cost must be at least {MIN_MEMORY_COST} KiB, got {memory_cost}"
+        )));
+    }
+    argon2::Params::new(memory_cost, time_cost, parallelism, Some(OUTPUT_LEN))
+        .map_err(|err| Error::InvalidParams(err.to_string()))
+}
+
+/// Argon2id hasher for `params`.
+fn argon2id(params: argon2::Params) -> Argon2<'static> {
+    Argon2::new(argon2::Algorithm::Argon2id, Version::V0x13, params)
+}
+
+/// Password hashing policy.
+#[php_class]
+#[php(name = "Hardened\\Password")]
+pub struct Password {
+    policy: Policy,
+}
+
+impl Password {
+    /// Backs `hash()`.
+    fn _hash(&self, password: &[u8]) -> Result<String> {
+        let salt = random_bytes(SALT_LEN).map_err(|err| Error::Random(err.to_string()))?;
+        let salt = SaltString::encode_b64(&salt).map_err(|err| Error::Random(err.to_string()))?;
+        let hash = match &self.policy {
+            Policy::Argon2id(params) => argon2id(params.clone()).hash_password(password, &salt),
+            Policy::Scrypt(params) => {
+                Scrypt.hash_password_customized(password, None, None, *params, &salt)
+            }
+        };
+        hash.map(|hash| hash.to_string())
+            .map_err(|err| Error::HashFailed(err.to_string()))
+    }
+
+    /// Backs `needsRehash()`.
+    fn _needs_rehash(&self, hash: &str) -> bool {
+        let Ok(hash) = PasswordHash::new(hash) else {
+            return true;
+        };
+        match &self.policy {
+            Policy::Argon2id(policy) => {
+                hash.algorithm != argon2::Algorithm::Argon2id.ident()
+                    || hash.version != Some(Version::V0x13.into())
+                    || argon2::Params::try_from(&hash).ok().is_none_or(|params| {
+                        (params.m_cost(), params.t_cost(), params.p_cost())
+                            != (policy.m_cost(), policy.t_cost(), policy.p_cost())
+                    })
+            }
+            Policy::Scrypt(policy) => {
+                hash.algorithm.as_str() != "scrypt"
+                    || scrypt::Params::try_from(&hash).ok().is_none_or(|params| {
+                        (params.log_n(), params.r(), params.p())
+                            != (policy.log_n(), policy.r(), policy.p())
+                    })
+            }
+        }
+    }
+
+    /// Backs `benchmark()`: the largest time cost whose hash takes at most `target`
+    /// (but at least one pass), and how long that hash took.
+    fn _benchmark(
+        target: Duration,
+        memory_cost: u32,
+        parallelism: u32,
+    ) -> Result<(argon2::Params, Duration)> {
+        let measure = |params: argon2::Params| -> Result<Duration> {
+            let mut output = [0u8; OUTPUT_LEN];
+            let start = Instant::now();
+            argon2id(params)
+                .hash_password_into(b"benchmark", &[0u8; SALT_LEN], &mut output)
+                .map_err(|err| Error::HashFailed(err.to_string()))?;
+            Ok(start.elapsed())
+        };
+        // Argon2 run time is linear in the number of passes
+        let single_pass = measure(argon2_params(memory_cost, 1, parallelism)?)?;
+        let passes = (target.as_secs_f64() / single_pass.as_secs_f64().max(f64::EPSILON))
+            .floor()
+            .clamp(1.0, 1000.0) as u32;
+        let params = argon2_params(memory_cost, passes, parallelism)?;
+        let duration = measure(params.clone())?;
+        Ok((params, duration))
+    }
+}
+
+#[php_impl]
+impl Password {
+    /// Constructs an Argon2id policy.
+    ///
+    /// # Parameters
+    /// - `memoryCost`: `?int` Memory in KiB, at least 7168. Default: `19456` (19 MiB).
+    /// - `timeCost`: `?int` Number of passes. Default: `2`.
+    /// - `parallelism`: `?int` Number of lanes. Default: `1`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if a parameter is out of range.
+    fn __construct(
+        memory_cost: Option<i64>,
+        time_cost: Option<i64>,
+        parallelism: Option<i64>,
+    ) -> Result<Self> {
+        Ok(Self {
+            policy: Policy::Argon2id(argon2_params(
+                cost("memory cost", memory_cost, DEFAULT_MEMORY_COST)?,
+                cost("time cost", time_cost, DEFAULT_TIME_COST)?,
+                cost("parallelism", parallelism, DEFAULT_PARALLELISM)?,
+            )?),
+        })
+    }
+
+    /// Constructs a scrypt policy, for platforms where Argon2 is not acceptable.
+    ///
+    /// # Parameters
+    /// - `logN`: `?int` CPU/memory cost as a power of two, at least 13. Default: `17`.
+    /// - `r`: `?int` Block size. Default: `8`.
+    /// - `p`: `?int` Parallelism. Default: `1`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if a parameter is out of range.
+    fn scrypt(log_n: Option<i64>, r: Option<i64>, p: Option<i64>) -> Result<Self> {
+        let log_n = u8::try_from(cost("log N", log_n, u32::from(DEFAULT_SCRYPT_LOG_N))?)
+            .map_err(|_| Error::InvalidParams(format!("log N out of range: {log_n:?}")))?;
+        if log_n < MIN_SCRYPT_LOG_N {
+            return Err(Error::InvalidParams(format!(
+                "log N must be at least {MIN_SCRYPT_LOG_N}, got {log_n}"
+            )));
+        }
+        let params = scrypt::Params::new(
+            log_n,
+            cost("r", r, DEFAULT_SCRYPT_R)?,
+            cost("p", p, DEFAULT_SCRYPT_P)?,
+            OUTPUT_LEN,
+        )
+        .map_err(|err| Error::InvalidParams(err.to_string()))?;
+        Ok(Self {
+            policy: Policy::Scrypt(params),
+        })
+    }
+
+    /// Hash a password with a fresh random salt.
+    ///
+    /// # Parameters
+    /// - `password`: `string` The password.
+    ///
+    /// # Returns
+    /// - `string` PHC string, e.g. `$argon2id$v=19$m=19456,t=2,p=1$…$…`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if hashing fails.
+    fn hash(&self, password: Binary<u8>) -> Result<String> {
+        self._hash(&password)
+    }
+
+    /// Check a password against a hash.
+    ///
+    /// Any Argon2 variant or scrypt hash is accepted, whatever its cost, so hashes keep
+    /// verifying after the policy changes.
+    ///
+    /// # Parameters
+    /// - `password`: `string` The password.
+    /// - `hash`: `string` PHC string from `hash()` or `password_hash()`.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the password matches; `false` also for malformed or unsupported hashes.
+    fn verify(password: Binary<u8>, hash: &str) -> bool {
+        let Ok(hash) = PasswordHash::new(hash) else {
+            return false;
+        };
+        match hash.algorithm.as_str() {
+            "argon2id" | "argon2i" | "argon2d" => {
+                Argon2::default().verify_password(&password, &hash).is_ok()
+            }
+            "scrypt" => Scrypt.verify_password(&password, &hash).is_ok(),
+            _ => false,
+        }
+    }
+
+    /// Whether a hash was made with another algorithm or cost than this policy.
+    ///
+    /// # Parameters
+    /// - `hash`: `string` Stored hash.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the hash should be replaced by `hash()` of the same password.
+    fn needs_rehash(&self, hash: &str) -> bool {
+        self._needs_rehash(hash)
+    }
+
+    /// Pick the Argon2id time cost that takes about `targetMs` on this host.
+    ///
+    /// Memory stays fixed and the number of passes is raised as far as the target allows.
+    /// Run it on production hardware and keep the result in configuration; do not call it
+    /// per request.
+    ///
+    /// # Parameters
+    /// - `targetMs`: `int` Time budget for one hash in milliseconds, e.g. `500`.
+    /// - `memoryCost`: `?int` Memory in KiB. Default: `65536` (64 MiB).
+    /// - `parallelism`: `?int` Number of lanes. Default: `1`.
+    ///
+    /// # Returns
+    /// - `array` `memory_cost`, `time_cost` and `threads` (the keys `password_hash()` uses),
+    ///   plus the measured `duration_ms`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if a parameter is out of range.
+    fn benchmark(
+        target_ms: i64,
+        memory_cost: Option<i64>,
+        parallelism: Option<i64>,
+    ) -> Result<HashMap<&'static str, i64>> {
+        let target = u64::try_from(target_ms)
+            .ok()
+            .filter(|target| *target > 0)
+            .ok_or_else(|| {
+                Error::InvalidParams(format!("target must be positive, got {target_ms}"))
+            })?;
+        let (params, duration) = Self::_benchmark(
+            Duration::from_millis(target),
+            cost("memory cost", memory_cost, BENCHMARK_MEMORY_COST)?,
+            cost("parallelism", parallelism, DEFAULT_PARALLELISM)?,
+        )?;
+        Ok(HashMap::from([
+            ("memory_cost", i64::from(params.m_cost())),
+            ("time_cost", i64::from(params.t_cost())),
+            ("threads", i64::from(params.p_cost())),
+            (
+                "duration_ms",
+                i64::try_from(duration.as_millis()).unwrap_or(i64::MAX),
+            ),
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Password};
+    use std::time::Duration;
+
+    fn cheap() -> Password {
+        Password::__construct(Some(7168), Some(1), None).unwrap()
+    }
+
+    #[test]
+    fn test_hash_and_verify() {
+        let password = cheap();
+        let hash = password._hash(b"correct horse").unwrap();
+        assert!(hash.starts_with("$argon2id$v=19$m=7168,t=1,p=1$"));
+        assert!(Password::verify(b"correct horse".to_vec().into(), &hash));
+        assert!(!Password::verify(b"battery staple".to_vec().into(), &hash));
+        assert_ne!(hash, password._hash(b"correct horse").unwrap());
+        assert!(!Password::verify(b"".to_vec().into(), "not a hash"));
+        // Made with Python's hashlib.scrypt
+        let scrypt = "$scrypt$ln=10,r=8,p=1$c2FsdHNhbHRzYWx0c2FsdA\
+                      $A9lBa6RTbfBovWqamVIqXKovIl4Vk6OZyVojLJmYmSI";
+        assert!(Password::verify(b"correct horse".to_vec().into(), scrypt));
+        assert!(!Password::verify(b"correct horsE".to_vec().into(), scrypt));
+
+        let scrypt_policy = Password::scrypt(Some(13), None, None).unwrap();
+        let hash = scrypt_policy._hash(b"correct horse").unwrap();
+        assert!(hash.starts_with("$scrypt$ln=13,r=8,p=1$"));
+        assert!(Password::verify(b"correct horse".to_vec().into(), &hash));
+    }
+
+    #[test]
+    fn test_needs_rehash() {
+        let password = cheap();
+        let hash = password._hash(b"secret").unwrap();
+        assert!(!password._needs_rehash(&hash));
+        let stronger = Password::__construct(Some(7168), Some(2), None).unwrap();
+        assert!(stronger._needs_rehash(&hash));
+        assert!(
+            Password::scrypt(Some(13), None, None)
+                .unwrap()
+                ._needs_rehash(&hash)
+        );
+        assert!(
+            password._needs_rehash("$2y$10$abcdefghijklmnopqrstuu5Mq5Jg4BXpNDAoN0N7.Sb.CXb2OkA.")
+        );
+        assert!(password._needs_rehash(""));
+    }
+
+    #[test]
+    fn test_params() {
+        assert!(matches!(
+            Password::__construct(Some(1024), None, None),
+            Err(Error::InvalidParams(_))
+        ));
+        assert!(matches!(
+            Password::__construct(None, Some(-1), None),
+            Err(Error::InvalidParams(_))
+        ));
+        assert!(matches!(
+            Password::scrypt(Some(10), None, None),
+            Err(Error::InvalidParams(_))
+        ));
+        let (params, _) = Password::_benchmark(Duration::from_millis(1), 7168, 1).unwrap();
+        assert_eq!((params.m_cost(), params.t_cost()), (7168, 1));
+    }
+}
@@ -1,18 +1,41 @@
+use crate::mime::{self, FileType};
 use crate::to_str;
 use ext_php_rs::exception::PhpException;
-use ext_php_rs::types::Zval;
+use ext_php_rs::types::{ZendHashTable, Zval};
 use ext_php_rs::zend::ce;
 use ext_php_rs::{php_class, php_impl};
+use glob::{MatchOptions, Pattern};
+use rand::distr::{Alphanumeric, SampleString};
+use rand::rng;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::ffi::OsStr;
+use std::fs::{DirBuilder, Metadata, OpenOptions};
+use std::io::{self, Read};
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
 use std::path::Component;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
 use thiserror::Error;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
 
 // Error codes for Path errors: 1200-1299
 pub mod error_codes {
     pub const SUBPATH_ESCAPING: i32 = 1200;
     pub const PATH_TO_STRING: i32 = 1201;
     pub const STRING_CONVERSION: i32 = 1202;
+    pub const INVALID_OPTION: i32 = 1203;
+    pub const TEMP_CREATION: i32 = 1204;
+    pub const INVALID_PATTERN: i32 = 1205;
+    pub const METADATA: i32 = 1206;
+    pub const PATH_TOO_LONG: i32 = 1207;
+    pub const COMPONENT_TOO_LONG: i32 = 1208;
+    pub const PATH_TOO_DEEP: i32 = 1209;
+    pub const UNKNOWN_EXTENSION_CATEGORY: i32 = 1210;
+    pub const READ: i32 = 1211;
 }
 
 /// Errors that can occur during path operations.
@@ -26,6 +49,33 @@ pub enum Error {
 
     #[error("String conversion failed")]
     StringConversionError,
+
+    #[error("Invalid option: {0}")]
+    InvalidOption(String),
+
+    #[error("Failed to create temporary entry: {0}")]
+    TempCreation(String),
+
+    #[error("Invalid glob pattern: {0}")]
+    InvalidPattern(String),
+
+    #[error("Failed to read metadata: {0}")]
+    Metadata(String),
+
+    #[error("Path is {0} bytes long, the limit is {1}")]
+    PathTooLong(usize, usize),
+
+    #[error("Path component is {0} bytes long, the limit is {1}")]
+    ComponentTooLong(usize, usize),
+
+    #[error("Path has {0} components, the limit is {1}")]
+    PathTooDeep(usize, usize),
+
+    #[error("Unknown extension category: {0}")]
+    UnknownExtensionCategory(String),
+
+    #[error("Failed to read file: {0}")]
+    Read(String),
 }
 
 impl Error {
@@ -35,6 +85,15 @@ impl Error {
             Error::SubpathEscaping => error_codes::SUBPATH_ESCAPING,
             Error::PathToStringError => error_codes::PATH_TO_STRING,
             Error::StringConversionError => error_codes::STRING_CONVERSION,
+            Error::InvalidOption(_) => error_codes::INVALID_OPTION,
+            Error::TempCreation(_) => error_codes::TEMP_CREATION,
+            Error::InvalidPattern(_) => error_codes::INVALID_PATTERN,
+            Error::Metadata(_) => error_codes::METADATA,
+            Error::PathTooLong(..) => error_codes::PATH_TOO_LONG,
+            Error::ComponentTooLong(..) => error_codes::COMPONENT_TOO_LONG,
+            Error::PathTooDeep(..) => error_codes::PATH_TOO_DEEP,
+            Error::UnknownExtensionCategory(_) => error_codes::UNKNOWN_EXTENSION_CATEGORY,
+            Error::Read(_) => error_codes::READ,
         }
     }
 }
@@ -52,9 +111,191 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 type HasEscaped = bool;
 
+/// Unicode normalization forms accepted by `Path` helpers.
+#[derive(EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "UPPERCASE", ascii_case_insensitive)]
+pub enum NormalizationForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+    None,
+}
+
+impl NormalizationForm {
+    fn parse(form: &str) -> Result<Self> {
+        Self::from_str(form).map_err(|_| Error::InvalidOption(format!("normalization: {form}")))
+    }
+
+    pub(crate) fn apply(self, s: &str) -> String {
+        match self {
+            NormalizationForm::Nfc => s.nfc().collect(),
+            NormalizationForm::Nfd => s.nfd().collect(),
+            NormalizationForm::Nfkc => s.nfkc().collect(),
+            NormalizationForm::Nfkd => s.nfkd().collect(),
+            NormalizationForm::None => s.to_string(),
+        }
+    }
+}
+
+/// Size limits enforced whenever a `Path` is constructed or joined. `0` disables a limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PathLimits {
+    /// Maximum length of the normalized path in bytes.
+    max_length: usize,
+    /// Maximum length of a single component in bytes.
+    max_component_length: usize,
+    /// Maximum number of components.
+    max_depth: usize,
+}
+
+impl PathLimits {
+    const DEFAULT: Self = Self {
+        max_length: 4096,
+        max_component_length: 255,
+        max_depth: 256,
+    };
+
+    /// Checks a normalized path against the limits.
+    ///
+    /// # Errors
+    /// - Returns `Err` naming the first exceeded limit.
+    fn check(&self, path: &Path) -> Result<()> {
+        let length = path.as_os_str().len();
+        if self.max_length > 0 && length > self.max_length {
+            return Err(Error::PathTooLong(length, self.max_length));
+        }
+        let mut depth = 0;
+        for component in path.components() {
+            depth += 1;
+            let length = component.as_os_str().len();
+            if self.max_component_length > 0 && length > self.max_component_length {
+                return Err(Error::ComponentTooLong(length, self.max_component_length));
+            }
+        }
+        if self.max_depth > 0 && depth > self.max_depth {
+            return Err(Error::PathTooDeep(depth, self.max_depth));
+        }
+        Ok(())
+    }
+}
+
+/// Built-in extension categories, used until redefined with `Path::defineExtensionCategory()`.
+const DEFAULT_EXTENSION_CATEGORIES: &[(&str, &[&str])] = &[
+    (
+        "image",
+        &["png", "jpg", "jpeg", "gif", "webp", "bmp", "tiff", "svg"],
+    ),
+    ("video", &["mp4", "mov", "avi", "mkv", "webm", "flv"]),
+    ("audio", &["mp3", "wav", "ogg", "flac", "aac"]),
+    (
+        "document",
+        &["pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx"],
+    ),
+];
+
+fn default_extension_categories() -> HashMap<String, Vec<String>> {
+    DEFAULT_EXTENSION_CATEGORIES
+        .iter()
+        .map(|(name, extensions)| {
+            (
+                (*name).to_string(),
+                extensions.iter().map(|ext| (*ext).to_string()).collect(),
+            )
+        })
+        .collect()
+}
+
+thread_local! {
+    static LIMITS: Cell<PathLimits> = const { Cell::new(PathLimits::DEFAULT) };
+    static EXTENSION_CATEGORIES: RefCell<HashMap<String, Vec<String>>> =
+        RefCell::new(default_extension_categories());
+}
+
+/// Options for `Path::sanitizeFileName()`.
+#[derive(Debug, Clone)]
+struct FileNameOptions {
+    /// Maximum length of the result in bytes.
+    max_length: usize,
+    /// String substituted for directory separators and forbidden characters.
+    replacement: String,
+    /// Unicode normalization form applied before any other processing.
+    normalization: NormalizationForm,
+    /// Whether Windows naming rules (reserved names, `<>:"|?*`, trailing dots) are enforced.
+    windows_safe: bool,
+    /// Name returned when nothing is left after sanitization.
+    fallback: String,
+}
+
+impl Default for FileNameOptions {
+    fn default() -> Self {
+        Self {
+            max_length: 255,
+            replacement: String::from("_"),
+            normalization: NormalizationForm::Nfc,
+            windows_safe: true,
+            fallback: String::from("file"),
+        }
+    }
+}
+
+impl FileNameOptions {
+    /// Reads options from a PHP array, falling back to defaults for missing keys.
+    ///
+    /// # Errors
+    /// - Returns `Err` if an option has the wrong type or an invalid value.
+    fn from_table(table: &ZendHashTable) -> Result<Self> {
+        let mut options = Self::default();
+        if let Some(value) = table.get("maxLength") {
+            options.max_length = value
+                .long()
+                .and_then(|n| usize::try_from(n).ok())
+                .filter(|&n| n > 0)
+                .ok_or_else(|| Error::InvalidOption("maxLength".into()))?;
+        }
+        if let Some(value) = table.get("replacement") {
+            let replacement = value
+                .string()
+                .ok_or_else(|| Error::InvalidOption("replacement".into()))?;
+            // The replacement must never reintroduce what we are stripping
+            if replacement.chars().any(|c| {
+                c == '/' || c == '\\' || c == '.' || c.is_control() || is_windows_forbidden(c)
+            }) {
+                return Err(Error::InvalidOption("replacement".into()));
+            }
+            options.replacement = replacement;
+        }
+        if let Some(value) = table.get("normalization") {
+            options.normalization = NormalizationForm::parse(
+                &value
+                    .string()
+                    .ok_or_else(|| Error::InvalidOption("normalization".into()))?,
+            )?;
+        }
+        if let Some(value) = table.get("windowsSafe") {
+            options.windows_safe = value
+                .bool()
+                .ok_or_else(|| Error::InvalidOption("windowsSafe".into()))?;
+        }
+        if let Some(value) = table.get("fallback") {
+            options.fallback = value
+                .string()
+                .ok_or_else(|| Error::InvalidOption("fallback".into()))?;
+            // The fallback is returned as is, so it must already be a sanitized name
+            if options.fallback.is_empty()
+                || PathObj::_sanitize_file_name(&options.fallback, &options) != options.fallback
+            {
+                return Err(Error::InvalidOption("fallback".into()));
+            }
+        }
+        Ok(options)
+    }
+}
+
 #[php_class]
 #[php(name = "Hardened\\Path")]
-#[derive(Debug)]
+#[php(implements(ce = crate::json_serializable, stub = "\\JsonSerializable"))]
+#[derive(Debug, Default)]
 pub struct PathObj {
     inner: PathBuf,
     escaped: HasEscaped,
@@ -85,6 +326,215 @@ impl PathObj {
     fn _starts_with(&self, path: &str) -> bool {
         self.inner.starts_with(path)
     }
+
+    fn _validate_extension_category(&self, name: &str) -> Result<bool> {
+        EXTENSION_CATEGORIES.with_borrow(|categories| {
+            let allowed = categories
+                .get(name)
+                .ok_or_else(|| Error::UnknownExtensionCategory(name.to_string()))?;
+            Ok(self
+                .inner
+                .extension()
+                .and_then(OsStr::to_str)
+                .is_some_and(|ext| allowed.iter().any(|a| a.eq_ignore_ascii_case(ext))))
+        })
+    }
+
+    fn _relative_to(&self, base: &str) -> Option<Self> {
+        let (base, _) = normalize_lexically(base);
+        self.inner.strip_prefix(base).ok().map(Self::_from)
+    }
+
+    fn _is_ancestor_of(&self, other: &str) -> bool {
+        let (other, _) = normalize_lexically(other);
+        other != self.inner && other.starts_with(&self.inner)
+    }
+
+    fn _common_prefix(&self, other: &str) -> Self {
+        let (other, _) = normalize_lexically(other);
+        let prefix: PathBuf = self
+            .inner
+            .components()
+            .zip(other.components())
+            .take_while(|(a, b)| a == b)
+            .map(|(a, _)| a)
+            .collect();
+        Self::_from(prefix)
+    }
+
+    /// Creates a new, uniquely named temporary entry in `dir` using `create`.
+    ///
+    /// Names are `prefix` followed by random alphanumeric characters. Creation is exclusive,
+    /// so a name that already exists (including a planted symlink) is never reused; a few
+    /// fresh names are tried before giving up.
+    fn _create_temp(
+        dir: Option<&str>,
+        prefix: &str,
+        create: impl Fn(&Path) -> io::Result<()>,
+    ) -> Result<Self> {
+        if prefix.contains(['/', '\\', '\0']) || prefix == "." || prefix == ".." {
+            return Err(Error::InvalidOption("prefix".into()));
+        }
+        let dir = dir.map_or_else(std::env::temp_dir, PathBuf::from);
+        let mut last_err = None;
+        for _ in 0..TEMP_ATTEMPTS {
+            let candidate = dir.join(format!(
+                "{prefix}{}",
+                Alphanumeric.sample_string(&mut rng(), TEMP_RANDOM_LEN)
+            ));
+            match create(&candidate) {
+                Ok(()) => return Ok(Self::_from(candidate)),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => last_err = Some(err),
+                Err(err) => return Err(Error::TempCreation(err.to_string())),
+            }
+        }
+        Err(Error::TempCreation(
+            last_err.map_or_else(String::new, |err| err.to_string()),
+        ))
+    }
+
+    fn _temp_file(dir: Option<&str>, prefix: &str) -> Result<Self> {
+        Self::_create_temp(dir, prefix, |path| {
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            options.mode(0o600);
+            options.open(path).map(drop)
+        })
+    }
+
+    fn _temp_dir(dir: Option<&str>, prefix: &str) -> Result<Self> {
+        Self::_create_temp(dir, prefix, |path| {
+            let mut builder = DirBuilder::new();
+            #[cfg(unix)]
+            builder.mode(0o700);
+            builder.create(path)
+        })
+    }
+
+    /// Expands `pattern` beneath this path, never returning anything outside of it.
+    ///
+    /// The pattern must be relative and must not contain `..` components. Matches are
+    /// canonicalized and dropped if a symlink leads them out of the base directory.
+    /// Hidden entries are only matched by patterns that spell out the leading dot.
+    fn _glob(&self, pattern: &str) -> Result<Vec<Self>> {
+        let relative = Path::new(pattern);
+        if pattern.is_empty()
+            || pattern.contains('\0')
+            || pattern.split(['/', '\\']).any(|part| part == "..")
+            || relative.is_absolute()
+            || relative.has_root()
+        {
+            return Err(Error::InvalidPattern(pattern.to_string()));
+        }
+        let base = self.inner.to_str().ok_or(Error::PathToStringError)?;
+        let full = Path::new(&Pattern::escape(base)).join(relative);
+        let full = full.to_str().ok_or(Error::PathToStringError)?;
+        let options = MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: true,
+        };
+        let paths =
+            glob::glob_with(full, options).map_err(|err| Error::InvalidPattern(err.to_string()))?;
+
+        // A base that does not exist has no entries, and nothing can escape it
+        let Ok(jail) = self.inner.canonicalize() else {
+            return Ok(Vec::new());
+        };
+        Ok(paths
+            .filter_map(std::result::Result::ok)
+            .filter(|path| {
+                path.canonicalize()
+                    .is_ok_and(|resolved| resolved.starts_with(&jail))
+            })
+            .map(Self::_from)
+            .collect())
+    }
+
+    /// Stats the path, either following a final symlink (`stat`) or not (`lstat`).
+    fn _metadata(&self, follow_symlinks: bool) -> std::io::Result<Metadata> {
+        if follow_symlinks {
+            std::fs::metadata(&self.inner)
+        } else {
+            std::fs::symlink_metadata(&self.inner)
+        }
+    }
+
+    /// Reads the leading bytes of the file and detects its content type.
+    fn _sniff(&self) -> Result<FileType> {
+        let mut head = Vec::with_capacity(mime::SNIFF_LEN);
+        std::fs::File::open(&self.inner)
+            .and_then(|file| file.take(mime::SNIFF_LEN as u64).read_to_end(&mut head))
+            .map_err(|err| Error::Read(err.to_string()))?;
+        Ok(mime::sniff(&head))
+    }
+
+    /// Turns an arbitrary user-supplied file name into a single, storage-safe path component.
+    ///
+    /// # Parameters
+    /// - `name`: The untrusted file name.
+    /// - `options`: Sanitization options.
+    ///
+    /// # Returns
+    /// A non-empty file name without separators, control characters or leading dots,
+    /// no longer than `options.max_length` bytes.
+    fn _sanitize_file_name(name: &str, options: &FileNameOptions) -> String {
+        let normalized = options.normalization.apply(name);
+
+        // Replace separators and forbidden characters, drop control characters,
+        // and collapse runs of whitespace into a single space.
+        let mut cleaned = String::with_capacity(normalized.len());
+        let mut pending_space = false;
+        for c in normalized.chars() {
+            if c.is_whitespace() {
+                pending_space = true;
+                continue;
+            }
+            if c.is_control() || is_bidi_or_zero_width(c) {
+                continue;
+            }
+            if pending_space {
+                cleaned.push(' ');
+                pending_space = false;
+            }
+            if c == '/' || c == '\\' || (options.windows_safe && is_windows_forbidden(c)) {
+                cleaned.push_str(&options.replacement);
+            } else {
+                cleaned.push(c);
+            }
+        }
+
+        // Leading dots create hidden files (and `..` components)
+        let mut cleaned = cleaned.trim_start_matches(['.', ' ']).to_string();
+
+        if cleaned.len() > options.max_length {
+            cleaned = truncate_file_name(&cleaned, options.max_length);
+        }
+        if options.windows_safe {
+            cleaned.truncate(cleaned.trim_end_matches(['.', ' ']).len());
+            if !cleaned.is_empty() && !is_windows_safe_component(&cleaned) {
+                let prefix = if options.replacement.is_empty() {
+                    "_"
+                } else {
+                    &options.replacement
+                };
+                cleaned.insert_str(0, prefix);
+                if cleaned.len() > options.max_length {
+                    cleaned = truncate_file_name(&cleaned, options.max_length);
+                }
+            }
+        } else {
+            cleaned.truncate(cleaned.trim_end().len());
+        }
+
+        if cleaned.is_empty() {
+            // A custom fallback fits already; the default one may not fit a small `maxLength`
+            truncate_file_name(&options.fallback, options.max_length)
+        } else {
+            cleaned
+        }
+    }
 }
 
 impl PartialEq<Self> for PathObj {
@@ -119,6 +569,7 @@ impl PathObj {
         let (inner, escaped) = normalize_lexically(Path::new(
             &to_str(path).map_err(|_| Error::StringConversionError)?,
         ));
+        LIMITS.get().check(&inner)?;
         Ok(Self { inner, escaped })
     }
 
@@ -160,7 +611,9 @@ impl PathObj {
     /// # Exceptions
     /// - Throws an exception if conversion from Zval to string fails.
     fn join(&self, path: &Zval) -> Result<Self> {
-        Ok(self._join(&to_str(path).map_err(|_| Error::StringConversionError)?))
+        let joined = self._join(&to_str(path).map_err(|_| Error::StringConversionError)?);
+        LIMITS.get().check(&joined.inner)?;
+        Ok(joined)
     }
 
     /// Joins the given path onto this path, normalizes it, and ensures it's a subpath.
@@ -171,7 +624,10 @@ impl PathObj {
     /// # Exceptions
     /// - Throws an exception if `$path` is not a string nor Path
     fn join_subpath(&self, path: &Zval) -> Result<Self> {
-        self._join_subpath(&to_str(path).map_err(|_| Error::StringConversionError)?)
+        let joined =
+            self._join_subpath(&to_str(path).map_err(|_| Error::StringConversionError)?)?;
+        LIMITS.get().check(&joined.inner)?;
+        Ok(joined)
     }
 
     /// Set the file name component of the path.
@@ -238,6 +694,69 @@ impl PathObj {
             .ok_or(Error::PathToStringError)
     }
 
+    /// Serializes to the normalized path string for `json_encode()`.
+    ///
+    /// # Returns
+    /// - `string` The normalized path.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the path is not valid UTF-8.
+    fn json_serialize(&self) -> Result<String> {
+        self.path()
+    }
+
+    /// Returns the data stored by `serialize()`.
+    ///
+    /// # Returns
+    /// - `array` `['path' => string]`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the path is not valid UTF-8.
+    fn __serialize(&self) -> Result<HashMap<&'static str, String>> {
+        Ok(HashMap::from([("path", self.path()?)]))
+    }
+
+    /// Restores an instance from `unserialize()` data.
+    ///
+    /// The stored path is normalized and checked against the limits again, so a tampered
+    /// cache entry cannot produce a Path that `Path::from()` would reject.
+    ///
+    /// # Parameters
+    /// - `data`: `array` The array returned by `__serialize()`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the data is malformed or exceeds the limits.
+    fn __unserialize(&mut self, data: &ZendHashTable) -> Result<()> {
+        let path = data
+            .get("path")
+            .and_then(Zval::string)
+            .ok_or(Error::StringConversionError)?;
+        let restored = Self::_from(path);
+        LIMITS.get().check(&restored.inner)?;
+        *self = restored;
+        Ok(())
+    }
+
+    /// The normalized path, exposed as the read-only `path` property so that `var_export()`
+    /// output carries it to `__set_state()`; `null` if it is not valid UTF-8.
+    #[php(getter)]
+    fn get_path(&self) -> Option<String> {
+        self.inner.to_str().map(str::to_string)
+    }
+
+    /// Restores an instance exported with `var_export()`.
+    ///
+    /// # Parameters
+    /// - `properties`: `array` `['path' => string]`, the `path` property.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the data is malformed or exceeds the limits.
+    fn __set_state(properties: &ZendHashTable) -> Result<Self> {
+        let mut path = Self::default();
+        path.__unserialize(properties)?;
+        Ok(path)
+    }
+
     /// Check if the path's extension is in the allowed list.
     ///
     /// # Parameters
@@ -255,35 +774,73 @@ impl PathObj {
     /// Check if the path's extension is a common image type.
     ///
     /// # Returns
-    /// - `bool` `true` if extension is one of `["png","jpg","jpeg","gif","webp","bmp","tiff","svg"]`.
+    /// - `bool` `true` if extension is in the `image` category
+    ///   (by default `["png","jpg","jpeg","gif","webp","bmp","tiff","svg"]`).
     fn validate_extension_image(&self) -> bool {
-        self.validate_extension(vec![
-            "png", "jpg", "jpeg", "gif", "webp", "bmp", "tiff", "svg",
-        ])
+        self._validate_extension_category("image").unwrap_or(false)
     }
 
     /// Check if the path's extension is a common video type.
     ///
     /// # Returns
-    /// - `bool` `true` if extension is one of `["mp4","mov","avi","mkv","webm","flv"]`.
+    /// - `bool` `true` if extension is in the `video` category
+    ///   (by default `["mp4","mov","avi","mkv","webm","flv"]`).
     fn validate_extension_video(&self) -> bool {
-        self.validate_extension(vec!["mp4", "mov", "avi", "mkv", "webm", "flv"])
+        self._validate_extension_category("video").unwrap_or(false)
     }
 
     /// Check if the path's extension is a common audio type.
     ///
     /// # Returns
-    /// - `bool` `true` if extension is one of `["mp3","wav","ogg","flac","aac"]`.
+    /// - `bool` `true` if extension is in the `audio` category
+    ///   (by default `["mp3","wav","ogg","flac","aac"]`).
     fn validate_extension_audio(&self) -> bool {
-        self.validate_extension(vec!["mp3", "wav", "ogg", "flac", "aac"])
+        self._validate_extension_category("audio").unwrap_or(false)
     }
 
     /// Check if the path's extension is a common document type.
     ///
     /// # Returns
-    /// - `bool` `true` if extension is one of `["pdf","doc","docx","xls","xlsx","ppt","pptx"]`.
+    /// - `bool` `true` if extension is in the `document` category
+    ///   (by default `["pdf","doc","docx","xls","xlsx","ppt","pptx"]`).
     fn validate_extension_document(&self) -> bool {
-        self.validate_extension(vec!["pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx"])
+        self._validate_extension_category("document")
+            .unwrap_or(false)
+    }
+
+    /// Defines (or redefines) a named extension category.
+    ///
+    /// Redefining a built-in category (`image`, `video`, `audio`, `document`) also changes the
+    /// corresponding `validateExtension*()` method, e.g. to exclude `svg` from `image`.
+    ///
+    /// # Parameters
+    /// - `name`: `string` Category name.
+    /// - `extensions`: `array` Allowed extensions, without leading dot (case-insensitive).
+    fn define_extension_category(name: String, extensions: Vec<String>) {
+        let extensions = extensions
+            .into_iter()
+            .map(|ext| ext.trim_start_matches('.').to_ascii_lowercase())
+            .collect();
+        EXTENSION_CATEGORIES.with_borrow_mut(|categories| categories.insert(name, extensions));
+    }
+
+    /// Check if the path's extension belongs to a named category.
+    ///
+    /// # Parameters
+    /// - `name`: `string` Category name (built-in or defined with `defineExtensionCategory()`).
+    ///
+    /// # Returns
+    /// - `bool` `true` if the file extension is in the category.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the category is not defined.
+    fn validate_extension_category(&self, name: &str) -> Result<bool> {
+        self._validate_extension_category(name)
+    }
+
+    /// Removes all custom extension categories and restores the built-in ones.
+    fn reset_extension_categories() {
+        EXTENSION_CATEGORIES.set(default_extension_categories());
     }
 
     /// Returns true if the path is absolute (starts with root or drive prefix).
@@ -324,6 +881,430 @@ impl PathObj {
             .and_then(OsStr::to_str)
             .map(str::to_string)
     }
+
+    /// Configures the limits enforced by `from()`, `__construct()`, `join()` and `joinSubpath()`.
+    ///
+    /// Limits apply to the normalized path. Keys that are omitted keep their current value;
+    /// `0` disables a limit.
+    ///
+    /// # Parameters
+    /// - `limits`: `array` Any of:
+    ///   - `maxLength`: `int` maximum path length in bytes (default `4096`).
+    ///   - `maxComponentLength`: `int` maximum component length in bytes (default `255`).
+    ///   - `maxDepth`: `int` maximum number of components (default `256`).
+    ///
+    /// # Exceptions
+    /// - Throws an exception if a value is not a non-negative integer.
+    fn set_limits(limits: &ZendHashTable) -> Result<()> {
+        let mut current = LIMITS.get();
+        for (key, field) in [
+            ("maxLength", &mut current.max_length),
+            ("maxComponentLength", &mut current.max_component_length),
+            ("maxDepth", &mut current.max_depth),
+        ] {
+            if let Some(value) = limits.get(key) {
+                *field = value
+                    .long()
+                    .and_then(|n| usize::try_from(n).ok())
+                    .ok_or_else(|| Error::InvalidOption(key.into()))?;
+            }
+        }
+        LIMITS.set(current);
+        Ok(())
+    }
+
+    /// Returns the currently enforced limits.
+    ///
+    /// # Returns
+    /// - `array` `['maxLength' => int, 'maxComponentLength' => int, 'maxDepth' => int]`.
+    fn get_limits() -> HashMap<&'static str, i64> {
+        let limits = LIMITS.get();
+        [
+            ("maxLength", limits.max_length),
+            ("maxComponentLength", limits.max_component_length),
+            ("maxDepth", limits.max_depth),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key, i64::try_from(value).unwrap_or(i64::MAX)))
+        .collect()
+    }
+
+    /// Produces a storage-safe file name from an arbitrary user-supplied one.
+    ///
+    /// Directory separators and characters forbidden on Windows are replaced, control, bidi and
+    /// zero-width characters and leading dots are stripped, whitespace runs are collapsed, Unicode is normalized, and the
+    /// result is truncated (preserving the extension) to the maximum length.
+    ///
+    /// # Parameters
+    /// - `name`: `string` The untrusted file name, e.g. from `$_FILES['upload']['name']`.
+    /// - `options`: `?array` Optional settings:
+    ///   - `maxLength`: `int` maximum length in bytes (default `255`).
+    ///   - `replacement`: `string` substitute for separators and forbidden characters (default `"_"`).
+    ///   - `normalization`: `string` one of `NFC`, `NFD`, `NFKC`, `NFKD`, `none` (default `NFC`).
+    ///   - `windowsSafe`: `bool` enforce Windows naming rules (default `true`).
+    ///   - `fallback`: `string` name returned when nothing is left (default `"file"`, shortened
+    ///     to `maxLength`); it must come out of sanitization unchanged.
+    ///
+    /// # Returns
+    /// - `string` The sanitized file name.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if an option has the wrong type or an invalid value.
+    fn sanitize_file_name(name: &str, options: Option<&ZendHashTable>) -> Result<String> {
+        let options = options
+            .map(FileNameOptions::from_table)
+            .transpose()?
+            .unwrap_or_default();
+        Ok(Self::_sanitize_file_name(name, &options))
+    }
+
+    /// Returns true if every component of the path is safe to store on Windows/SMB filesystems.
+    ///
+    /// A component is rejected if it is a reserved device name (`CON`, `PRN`, `AUX`, `NUL`,
+    /// `COM1`–`COM9`, `LPT1`–`LPT9`, with or without an extension), ends with a dot or a space,
+    /// contains a `:` (NTFS alternate data stream, e.g. `file.txt:stream`), or contains
+    /// a character Windows does not allow in file names (`<>"|?*` and control characters).
+    /// Both `/` and `\` are treated as separators, regardless of the host platform.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the path can be safely created on Windows.
+    fn is_windows_safe(&self) -> bool {
+        self.inner.components().all(|component| match component {
+            Component::Normal(name) => name.to_str().is_some_and(|name| {
+                name.split('\\')
+                    .filter(|part| !part.is_empty())
+                    .all(is_windows_safe_component)
+            }),
+            _ => true,
+        })
+    }
+
+    /// Computes this path relative to `base`, without any string manipulation.
+    ///
+    /// Both paths are compared component by component after lexical normalization,
+    /// so `/var/www-data` is not considered to be inside `/var/www`.
+    ///
+    /// # Parameters
+    /// - `base`: `string|Path` The base directory.
+    ///
+    /// # Returns
+    /// - `?Path` The relative path (empty if both paths are equal), or `null` if this path is not under `$base`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if conversion of `$base` to string fails.
+    fn relative_to(&self, base: &Zval) -> Result<Option<Self>> {
+        Ok(self._relative_to(&to_str(base).map_err(|_| Error::StringConversionError)?))
+    }
+
+    /// Returns true if this path is a strict ancestor of `other`.
+    ///
+    /// # Parameters
+    /// - `other`: `string|Path` The path to test.
+    ///
+    /// # Returns
+    /// - `bool` `true` if `$other` lies beneath this path (a path is not its own ancestor).
+    ///
+    /// # Exceptions
+    /// - Throws an exception if conversion of `$other` to string fails.
+    fn is_ancestor_of(&self, other: &Zval) -> Result<bool> {
+        Ok(self._is_ancestor_of(&to_str(other).map_err(|_| Error::StringConversionError)?))
+    }
+
+    /// Returns the longest leading sequence of components shared with `other`.
+    ///
+    /// # Parameters
+    /// - `other`: `string|Path` The path to compare against.
+    ///
+    /// # Returns
+    /// - `Path` The common prefix (empty if the paths have nothing in common).
+    ///
+    /// # Exceptions
+    /// - Throws an exception if conversion of `$other` to string fails.
+    fn common_prefix(&self, other: &Zval) -> Result<Self> {
+        Ok(self._common_prefix(&to_str(other).map_err(|_| Error::StringConversionError)?))
+    }
+
+    /// Securely creates a new, empty temporary file and returns its path.
+    ///
+    /// Unlike `tempnam()`, the file is created with `O_CREAT | O_EXCL` and mode `0600`,
+    /// so it can neither be pre-created by an attacker nor read by other users.
+    ///
+    /// # Parameters
+    /// - `dir`: `?string` Directory to create the file in (defaults to the system temporary directory).
+    /// - `prefix`: `string` File name prefix; must not contain directory separators.
+    ///
+    /// # Returns
+    /// - `Path` Path of the created file.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the prefix is invalid or the file cannot be created.
+    fn temp_file(dir: Option<String>, prefix: &str) -> Result<Self> {
+        Self::_temp_file(dir.as_deref(), prefix)
+    }
+
+    /// Securely creates a new, empty temporary directory with mode `0700` and returns its path.
+    ///
+    /// # Parameters
+    /// - `dir`: `?string` Parent directory (defaults to the system temporary directory).
+    /// - `prefix`: `string` Directory name prefix; must not contain directory separators.
+    ///
+    /// # Returns
+    /// - `Path` Path of the created directory.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the prefix is invalid or the directory cannot be created.
+    fn temp_dir(dir: Option<String>, prefix: &str) -> Result<Self> {
+        Self::_temp_dir(dir.as_deref(), prefix)
+    }
+
+    /// Lists entries beneath this path that match a glob pattern.
+    ///
+    /// Supports `*`, `?`, `[...]` and `**` (recursive). The pattern is always anchored at this
+    /// path: absolute patterns and `..` components are rejected, and matches reached through
+    /// symlinks pointing outside the base directory are skipped.
+    ///
+    /// # Parameters
+    /// - `pattern`: `string` Relative glob pattern, e.g. `"*.jpg"` or `"**/*.pdf"`.
+    ///
+    /// # Returns
+    /// - `Path[]` Matching paths, in alphabetical order.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the pattern is absolute, contains `..`, or is malformed.
+    fn glob(&self, pattern: &str) -> Result<Vec<Self>> {
+        self._glob(pattern)
+    }
+
+    /// Returns true if the path exists on the filesystem.
+    ///
+    /// # Parameters
+    /// - `followSymlinks`: `?bool` Whether a final symlink is followed (default `true`). When `false`,
+    ///   a dangling symlink counts as existing.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the entry exists.
+    fn exists(&self, follow_symlinks: Option<bool>) -> bool {
+        self._metadata(follow_symlinks.unwrap_or(true)).is_ok()
+    }
+
+    /// Returns true if the path itself is a symbolic link (never follows it).
+    ///
+    /// # Returns
+    /// - `bool` `true` if the entry is a symlink.
+    fn is_symlink(&self) -> bool {
+        self._metadata(false)
+            .is_ok_and(|metadata| metadata.file_type().is_symlink())
+    }
+
+    /// Returns true if the file name starts with a dot (a hidden entry on Unix-like systems).
+    ///
+    /// This is a lexical check and does not touch the filesystem.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the last component is hidden.
+    fn is_hidden(&self) -> bool {
+        self.inner
+            .file_name()
+            .and_then(OsStr::to_str)
+            .is_some_and(|name| name.starts_with('.'))
+    }
+
+    /// Returns the size of the entry in bytes.
+    ///
+    /// # Parameters
+    /// - `followSymlinks`: `?bool` Whether a final symlink is followed (default `true`). When `false`,
+    ///   the size of the link itself is returned.
+    ///
+    /// # Returns
+    /// - `int` Size in bytes.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the entry cannot be stat'ed.
+    fn size(&self, follow_symlinks: Option<bool>) -> Result<i64> {
+        let metadata = self
+            ._metadata(follow_symlinks.unwrap_or(true))
+            .map_err(|err| Error::Metadata(err.to_string()))?;
+        i64::try_from(metadata.len()).map_err(|err| Error::Metadata(err.to_string()))
+    }
+
+    /// Returns the permission bits of the entry (e.g. `0o644`), including setuid/setgid/sticky.
+    ///
+    /// On non-Unix platforms only the read-only flag is available: `0o444` or `0o666`.
+    ///
+    /// # Parameters
+    /// - `followSymlinks`: `?bool` Whether a final symlink is followed (default `true`).
+    ///
+    /// # Returns
+    /// - `int` Permission bits.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the entry cannot be stat'ed.
+    fn permissions(&self, follow_symlinks: Option<bool>) -> Result<i64> {
+        let permissions = self
+            ._metadata(follow_symlinks.unwrap_or(true))
+            .map_err(|err| Error::Metadata(err.to_string()))?
+            .permissions();
+        #[cfg(unix)]
+        let mode = permissions.mode() & 0o7777;
+        #[cfg(not(unix))]
+        let mode = if permissions.readonly() { 0o444 } else { 0o666 };
+        Ok(i64::from(mode))
+    }
+
+    /// Detects the MIME type of the file from its magic bytes, ignoring the extension.
+    ///
+    /// Only the first 4 KiB are read. Unrecognized binary content is reported as
+    /// `application/octet-stream`, unrecognized text as `text/plain`.
+    ///
+    /// # Returns
+    /// - `string` The detected MIME type, e.g. `image/png`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the file cannot be read.
+    fn detect_mime(&self) -> Result<String> {
+        Ok(self._sniff()?.mime.to_string())
+    }
+
+    /// Checks that the file's content agrees with its extension.
+    ///
+    /// Catches e.g. a PHP script or an executable uploaded as `avatar.jpg`.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the extension is one usually used for the detected content type;
+    ///   `false` if there is no extension or the content is unrecognized binary.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the file cannot be read.
+    fn matches_extension(&self) -> Result<bool> {
+        let Some(extension) = self.inner.extension().and_then(OsStr::to_str) else {
+            return Ok(false);
+        };
+        Ok(self._sniff()?.matches_extension(extension))
+    }
+
+    /// Applies Unicode normalization to the whole path.
+    ///
+    /// Visually identical names can have different byte sequences (e.g. `é` as U+00E9 vs.
+    /// `e` + U+0301), which defeats comparisons and allow-lists. Normalizing first makes them equal.
+    ///
+    /// # Parameters
+    /// - `form`: `string` One of `NFC`, `NFD`, `NFKC`, `NFKD` (case-insensitive).
+    ///
+    /// # Returns
+    /// - `Path` A new, normalized Path.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `$form` is not a known normalization form, or the path is not valid UTF-8.
+    fn normalize_unicode(&self, form: &str) -> Result<Self> {
+        let form = NormalizationForm::parse(form)?;
+        let path = self.inner.to_str().ok_or(Error::PathToStringError)?;
+        let (inner, escaped) = normalize_lexically(Path::new(&form.apply(path)));
+        Ok(Self {
+            inner,
+            escaped: self.escaped || escaped,
+        })
+    }
+
+    /// Returns true if any path component contains bidirectional control or zero-width characters.
+    ///
+    /// Such characters are invisible, and are used to disguise file names: `invoice\u{202E}fdp.exe`
+    /// is displayed as `invoiceexe.pdf`.
+    ///
+    /// # Returns
+    /// - `bool` `true` if an RTL/LTR override, embedding, isolate, mark, or zero-width character is present.
+    fn contains_bidi_or_zero_width(&self) -> bool {
+        self.inner
+            .to_string_lossy()
+            .chars()
+            .any(is_bidi_or_zero_width)
+    }
+}
+
+/// Number of fresh names tried by `Path::tempFile()`/`Path::tempDir()` before giving up.
+const TEMP_ATTEMPTS: usize = 16;
+/// Number of random characters appended to temporary entry prefixes.
+const TEMP_RANDOM_LEN: usize = 12;
+
+/// Returns true for invisible characters that alter text direction or have no width.
+pub(crate) fn is_bidi_or_zero_width(c: char) -> bool {
+    matches!(
+        c,
+        '\u{061C}'
+            | '\u{180E}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{2069}'
+            | '\u{FEFF}'
+    )
+}
+
+/// Device names reserved by Windows, regardless of extension.
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "COM¹", "COM²", "COM³", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8",
+    "LPT9", "LPT¹", "LPT²", "LPT³", "CONIN$", "CONOUT$",
+];
+
+/// Checks a single path component against Windows naming rules.
+///
+/// # Parameters
+/// - `name`: A single path component (must not contain separators).
+///
+/// # Returns
+/// `true` if the component is not a reserved device name, has no trailing dot or space,
+/// and contains no forbidden characters (including the `:` used by alternate data streams).
+fn is_windows_safe_component(name: &str) -> bool {
+    if name == "." || name == ".." {
+        return true;
+    }
+    if name.ends_with('.') || name.ends_with(' ') {
+        return false;
+    }
+    if name
+        .chars()
+        .any(|c| c.is_control() || is_windows_forbidden(c))
+    {
+        return false;
+    }
+    // `CON.txt` and `con .tar.gz` refer to the device as well
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    !WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+}
+
+/// Returns true for printable characters that Windows does not allow in file names.
+fn is_windows_forbidden(c: char) -> bool {
+    matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*')
+}
+
+/// Truncates a file name to at most `max` bytes on a grapheme boundary, keeping the extension
+/// when it is reasonably short and some of the stem fits next to it.
+pub(crate) fn truncate_file_name(name: &str, max: usize) -> String {
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && ext.len() < max / 2 => (stem, Some(ext)),
+        _ => (name, None),
+    };
+    let budget = max - extension.map_or(0, |ext| ext.len() + 1);
+    match (truncate_graphemes(stem, budget).trim_end(), extension) {
+        // The extension alone would make a hidden file, so drop it instead
+        ("", Some(_)) => truncate_graphemes(stem, max).trim_end().to_string(),
+        (truncated, Some(ext)) => format!("{truncated}.{ext}"),
+        (truncated, None) => truncated.to_string(),
+    }
+}
+
+/// Returns the longest prefix of `s` made of whole graphemes that fits in `max` bytes.
+fn truncate_graphemes(s: &str, max: usize) -> &str {
+    let mut end = 0;
+    for (idx, grapheme) in s.grapheme_indices(true) {
+        if idx + grapheme.len() > max {
+            break;
+        }
+        end = idx + grapheme.len();
+    }
+    &s[..end]
 }
 
 /// Performs a purely lexical normalization of a path:
@@ -381,10 +1362,10 @@ fn normalize_lexically<P: AsRef<Path>>(path: P) -> (PathBuf, HasEscaped) {
 }
 #[cfg(test)]
 mod tests {
-    use super::{PathObj, normalize_lexically};
-    use crate::run_php_example;
+    use super::{Error, FileNameOptions, PathLimits, PathObj, normalize_lexically};
+    use crate::{run_php_example, run_php_test};
     use std::ffi::OsStr;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     fn canon(s: &str) -> String {
         normalize_lexically(s).0.to_str().unwrap().to_owned()
@@ -639,9 +1620,370 @@ mod tests {
         assert!(p.parent().is_none());
     }
 
+    #[test]
+    fn test_is_windows_safe() {
+        assert!(PathObj::_from("uploads/report.pdf").is_windows_safe());
+        assert!(PathObj::_from("/var/www/console.log").is_windows_safe());
+        assert!(PathObj::_from("uploads/.htaccess").is_windows_safe());
+
+        // Reserved device names, with or without extension
+        assert!(!PathObj::_from("uploads/CON").is_windows_safe());
+        assert!(!PathObj::_from("uploads/nul.txt").is_windows_safe());
+        assert!(!PathObj::_from("uploads/Com1.tar.gz").is_windows_safe());
+        assert!(!PathObj::_from("LPT9/file.txt").is_windows_safe());
+        assert!(!PathObj::_from("uploads\\aux\\file.txt").is_windows_safe());
+
+        // Trailing dots and spaces
+        assert!(!PathObj::_from("uploads/file.txt.").is_windows_safe());
+        assert!(!PathObj::_from("uploads/file.txt ").is_windows_safe());
+        assert!(!PathObj::_from("dir /file.txt").is_windows_safe());
+
+        // Alternate data streams and forbidden characters
+        assert!(!PathObj::_from("uploads/file.txt:stream").is_windows_safe());
+        assert!(!PathObj::_from("uploads/file?.txt").is_windows_safe());
+        assert!(!PathObj::_from("uploads/file\u{1}.txt").is_windows_safe());
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        let options = FileNameOptions::default();
+        let sanitize = |name: &str| PathObj::_sanitize_file_name(name, &options);
+
+        assert_eq!(sanitize("report.pdf"), "report.pdf");
+        assert_eq!(sanitize("../../etc/passwd"), "_.._etc_passwd");
+        assert_eq!(sanitize("..\\..\\boot.ini"), "_.._boot.ini");
+        assert_eq!(sanitize(".htaccess"), "htaccess");
+        assert_eq!(
+            sanitize("  my   holiday\tphoto .JPG "),
+            "my holiday photo .JPG"
+        );
+        assert_eq!(sanitize("in\u{0}valid\u{7}.txt"), "invalid.txt");
+        assert_eq!(sanitize("what?.txt"), "what_.txt");
+        assert_eq!(sanitize("invoice.pdf..."), "invoice.pdf");
+        assert_eq!(sanitize("CON.txt"), "_CON.txt");
+        assert_eq!(sanitize("..."), "file");
+        assert_eq!(sanitize(""), "file");
+        // NFD input is recomposed to NFC
+        assert_eq!(sanitize("cafe\u{301}.txt"), "caf\u{e9}.txt");
+    }
+
+    #[test]
+    fn test_relative_to() {
+        let path = PathObj::_from("/var/www/uploads/1/avatar.png");
+        assert_eq!(
+            path._relative_to("/var/www").unwrap().inner,
+            PathBuf::from("uploads/1/avatar.png")
+        );
+        assert_eq!(
+            path._relative_to("/var/www/./uploads/2/../1/")
+                .unwrap()
+                .inner,
+            PathBuf::from("avatar.png")
+        );
+        assert_eq!(
+            path._relative_to("/var/www/uploads/1/avatar.png")
+                .unwrap()
+                .inner,
+            PathBuf::new()
+        );
+        assert!(path._relative_to("/var/ww").is_none());
+        assert!(path._relative_to("/etc").is_none());
+        assert!(path._relative_to("var/www").is_none());
+    }
+
+    #[test]
+    fn test_ancestry() {
+        let base = PathObj::_from("/var/www");
+        assert!(base._is_ancestor_of("/var/www/uploads"));
+        assert!(base._is_ancestor_of("/var/www/uploads/../index.php"));
+        assert!(!base._is_ancestor_of("/var/www"));
+        assert!(!base._is_ancestor_of("/var/www-data/secret"));
+        assert!(!base._is_ancestor_of("/var/www/../../etc/passwd"));
+
+        assert_eq!(
+            base._common_prefix("/var/www-data").inner,
+            PathBuf::from("/var")
+        );
+        assert_eq!(
+            PathObj::_from("/var/www/a/b")
+                ._common_prefix("/var/www/a/c")
+                .inner,
+            PathBuf::from("/var/www/a")
+        );
+        assert_eq!(
+            PathObj::_from("a/b")._common_prefix("c/d").inner,
+            PathBuf::new()
+        );
+    }
+
+    #[test]
+    fn test_temp_file_and_dir() -> crate::TestResult {
+        let dir = PathObj::_temp_dir(None, "hardened-test-")?;
+        let dir_path = dir.path()?;
+        assert!(dir.inner.is_dir());
+        assert!(dir.file_name().unwrap().starts_with("hardened-test-"));
+
+        let file = PathObj::_temp_file(Some(&dir_path), "upload-")?;
+        assert!(file.inner.is_file());
+        assert!(dir._is_ancestor_of(&file.path()?));
+        assert_ne!(
+            file.inner,
+            PathObj::_temp_file(Some(&dir_path), "upload-")?.inner
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(
+                std::fs::metadata(&dir.inner)?.permissions().mode() & 0o777,
+                0o700
+            );
+            assert_eq!(
+                std::fs::metadata(&file.inner)?.permissions().mode() & 0o777,
+                0o600
+            );
+        }
+
+        assert!(PathObj::_temp_file(Some(&dir_path), "../escape").is_err());
+        assert!(PathObj::_temp_file(Some(&format!("{dir_path}/missing")), "x").is_err());
+
+        std::fs::remove_dir_all(&dir.inner)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_glob() -> crate::TestResult {
+        let base = PathObj::_temp_dir(None, "hardened-glob-")?;
+        std::fs::create_dir(base.inner.join("sub"))?;
+        for file in ["a.jpg", "b.png", ".hidden.jpg", "sub/c.jpg"] {
+            std::fs::write(base.inner.join(file), b"")?;
+        }
+        let names = |pattern: &str| -> crate::TestResult<Vec<String>> {
+            Ok(base
+                ._glob(pattern)?
+                .iter()
+                .filter_map(|path| path._relative_to(base.inner.to_str()?))
+                .map(|path| path.inner.to_string_lossy().into_owned())
+                .collect())
+        };
+
+        assert_eq!(names("*.jpg")?, vec!["a.jpg"]);
+        assert_eq!(names("**/*.jpg")?, vec!["a.jpg", "sub/c.jpg"]);
+        assert_eq!(names(".*.jpg")?, vec![".hidden.jpg"]);
+        assert!(names("*.gif")?.is_empty());
+
+        assert!(base._glob("../*").is_err());
+        assert!(base._glob("sub/../../*").is_err());
+        assert!(base._glob("/etc/*").is_err());
+        assert!(base._glob("").is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("/etc", base.inner.join("etc"))?;
+            assert!(names("etc/*")?.is_empty());
+        }
+
+        std::fs::remove_dir_all(&base.inner)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata() -> crate::TestResult {
+        let dir = PathObj::_temp_dir(None, "hardened-meta-")?;
+        let file = dir._join("data.bin");
+        std::fs::write(&file.inner, b"hello")?;
+
+        assert!(file.exists(None));
+        assert!(!dir._join("missing").exists(None));
+        assert!(!file.is_symlink());
+        assert!(!file.is_hidden());
+        assert!(dir._join(".env").is_hidden());
+        assert_eq!(file.size(None)?, 5);
+        assert!(dir._join("missing").size(None).is_err());
+
+        #[cfg(unix)]
+        {
+            assert_eq!(dir.permissions(None)?, 0o700);
+
+            let link = dir._join("link");
+            std::os::unix::fs::symlink(dir.inner.join("missing"), &link.inner)?;
+            assert!(link.is_symlink());
+            assert!(!link.exists(None));
+            assert!(link.exists(Some(false)));
+            assert!(link.size(None).is_err());
+            assert!(link.size(Some(false)).is_ok());
+        }
+
+        std::fs::remove_dir_all(&dir.inner)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_limits() {
+        let limits = PathLimits {
+            max_length: 32,
+            max_component_length: 8,
+            max_depth: 4,
+        };
+        assert!(limits.check(Path::new("/var/www/a.txt")).is_ok());
+        assert!(matches!(
+            limits.check(Path::new("/var/www/uploaded-files")),
+            Err(Error::ComponentTooLong(14, 8))
+        ));
+        assert!(matches!(
+            limits.check(Path::new("/a/b/c/d")),
+            Err(Error::PathTooDeep(5, 4))
+        ));
+        assert!(matches!(
+            limits.check(Path::new(&"abcdefg/".repeat(5))),
+            Err(Error::PathTooLong(_, 32))
+        ));
+
+        let unlimited = PathLimits {
+            max_length: 0,
+            max_component_length: 0,
+            max_depth: 0,
+        };
+        assert!(unlimited.check(Path::new(&"a/".repeat(10_000))).is_ok());
+        assert!(
+            PathLimits::DEFAULT
+                .check(Path::new(&"a".repeat(256)))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_extension_categories() -> crate::TestResult {
+        let logo = PathObj::_from("/uploads/logo.SVG");
+        assert!(logo.validate_extension_image());
+        assert!(logo.validate_extension_category("image")?);
+        assert!(logo.validate_extension_category("vector").is_err());
+
+        PathObj::define_extension_category("image".into(), vec!["png".into(), ".JPG".into()]);
+        PathObj::define_extension_category("vector".into(), vec!["svg".into()]);
+        assert!(!logo.validate_extension_image());
+        assert!(logo.validate_extension_category("vector")?);
+        assert!(PathObj::_from("a.jpg").validate_extension_image());
+
+        PathObj::reset_extension_categories();
+        assert!(logo.validate_extension_image());
+        assert!(logo.validate_extension_category("vector").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_mime() -> crate::TestResult {
+        let dir = PathObj::_temp_dir(None, "hardened-mime-")?;
+        let png = dir._join("avatar.png");
+        std::fs::write(&png.inner, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR")?;
+        let disguised = dir._join("avatar.jpg");
+        std::fs::write(&disguised.inner, b"<?php system($_GET['c']);")?;
+
+        assert_eq!(png.detect_mime()?, "image/png");
+        assert!(png.matches_extension()?);
+        assert_eq!(disguised.detect_mime()?, "application/x-httpd-php");
+        assert!(!disguised.matches_extension()?);
+        assert!(dir._join("missing.png").detect_mime().is_err());
+
+        std::fs::remove_dir_all(&dir.inner)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize() -> crate::TestResult {
+        let path = PathObj::_from("/var/www/./uploads/../index.php");
+        assert_eq!(path.json_serialize()?, "/var/www/index.php");
+        assert_eq!(path.__serialize()?["path"], "/var/www/index.php");
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_unicode() -> crate::TestResult {
+        let path = PathObj::_from("/uploads/cafe\u{301}.txt");
+        assert_eq!(
+            path.normalize_unicode("NFC")?.path()?,
+            "/uploads/caf\u{e9}.txt"
+        );
+        assert_eq!(
+            path.normalize_unicode("nfd")?.path()?,
+            "/uploads/cafe\u{301}.txt"
+        );
+        assert_eq!(
+            PathObj::_from("/uploads/\u{fb01}le.txt")
+                .normalize_unicode("NFKC")?
+                .path()?,
+            "/uploads/file.txt"
+        );
+        assert!(path.normalize_unicode("NFX").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_contains_bidi_or_zero_width() {
+        assert!(!PathObj::_from("/uploads/invoice.pdf").contains_bidi_or_zero_width());
+        assert!(PathObj::_from("/uploads/invoice\u{202E}fdp.exe").contains_bidi_or_zero_width());
+        assert!(PathObj::_from("/uploads/in\u{200B}voice.pdf").contains_bidi_or_zero_width());
+        assert!(PathObj::_from("/up\u{2066}loads/invoice.pdf").contains_bidi_or_zero_width());
+        assert_eq!(
+            PathObj::_sanitize_file_name("invoice\u{202E}fdp.exe", &FileNameOptions::default()),
+            "invoicefdp.exe"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_file_name_max_length() {
+        let options = FileNameOptions {
+            max_length: 10,
+            ..FileNameOptions::default()
+        };
+        assert_eq!(
+            PathObj::_sanitize_file_name("averyveryverylongname.pdf", &options),
+            "averyv.pdf"
+        );
+        assert_eq!(
+            PathObj::_sanitize_file_name("привет-мир.txt", &options),
+            "при.txt"
+        );
+        let options = FileNameOptions {
+            max_length: 5,
+            ..FileNameOptions::default()
+        };
+        assert_eq!(
+            PathObj::_sanitize_file_name("archive.tar", &options),
+            "archi"
+        );
+        // When not even the first grapheme fits next to the extension, the extension goes
+        let options = FileNameOptions {
+            max_length: 10,
+            ..FileNameOptions::default()
+        };
+        assert_eq!(
+            PathObj::_sanitize_file_name("\u{1F44D}\u{1F3FD}.txt", &options),
+            "\u{1F44D}\u{1F3FD}"
+        );
+        assert_eq!(
+            PathObj::_sanitize_file_name(
+                "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}.txt",
+                &options
+            ),
+            "file"
+        );
+        let options = FileNameOptions {
+            max_length: 2,
+            ..FileNameOptions::default()
+        };
+        assert_eq!(PathObj::_sanitize_file_name("...", &options), "fi");
+    }
+
     #[test]
     fn php_example() -> crate::TestResult {
         run_php_example("path")?;
         Ok(())
     }
+
+    #[test]
+    fn php_test() -> crate::TestResult {
+        run_php_test("path")?;
+        Ok(())
+    }
 }
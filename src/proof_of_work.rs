@@ -0,0 +1,289 @@
+//! Stateless proof-of-work challenges, a CAPTCHA alternative that costs bots CPU time
+//! instead of costing people patience.
+//!
+//! A challenge reads `v1.{difficulty}.{expires}.{salt}.{tag}`, where the tag is an
+//! HMAC-SHA256 of everything before it and of an optional scope (a form name, a client IP…).
+//! The client looks for a nonce such that SHA-256(challenge ‖ nonce) starts with `difficulty`
+//! zero bits; checking it takes a single hash.
+
+use data_encoding::BASE64URL_NOPAD;
+use ext_php_rs::binary::Binary;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+// Error codes for ProofOfWork errors: 3200-3299
+pub mod error_codes {
+    pub const KEY_TOO_SHORT: i32 = 3200;
+    pub const INVALID_DIFFICULTY: i32 = 3201;
+    pub const INVALID_TTL: i32 = 3202;
+}
+
+/// Errors produced by `ProofOfWork`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Key must be at least 32 bytes, got {0}")]
+    KeyTooShort(usize),
+
+    #[error("Difficulty must be between 1 and 32 bits, got {0}")]
+    InvalidDifficulty(i64),
+
+    #[error("TTL must be a positive number of seconds, got {0}")]
+    InvalidTtl(i64),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::KeyTooShort(_) => error_codes::KEY_TOO_SHORT,
+            Error::InvalidDifficulty(_) => error_codes::INVALID_DIFFICULTY,
+            Error::InvalidTtl(_) => error_codes::INVALID_TTL,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for proof-of-work operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+const MIN_KEY_LEN: usize = 32;
+/// 2^32 hashes on average is already minutes of browser time.
+const MAX_DIFFICULTY: u32 = 32;
+const DEFAULT_DIFFICULTY: u32 = 18;
+const DEFAULT_TTL: i64 = 300;
+const SALT_LEN: usize = 16;
+/// Longest nonce accepted, so verification cost stays constant.
+const MAX_NONCE_LEN: usize = 64;
+
+/// Current UNIX time in seconds.
+fn unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+fn parse_difficulty(difficulty: i64) -> Result<u32> {
+    u32::try_from(difficulty)
+        .ok()
+        .filter(|difficulty| (1..=MAX_DIFFICULTY).contains(difficulty))
+        .ok_or(Error::InvalidDifficulty(difficulty))
+}
+
+/// Number of leading zero bits of `hash`.
+fn leading_zero_bits(hash: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in hash {
+        bits += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+/// Issues and verifies HMAC-bound proof-of-work challenges.
+#[php_class]
+#[php(name = "Hardened\\ProofOfWork")]
+pub struct ProofOfWork {
+    key: Vec<u8>,
+    difficulty: u32,
+    ttl: i64,
+}
+
+impl ProofOfWork {
+    /// Backs `__construct()`.
+    fn _new(key: &[u8], difficulty: Option<i64>, ttl: Option<i64>) -> Result<Self> {
+        if key.len() < MIN_KEY_LEN {
+            return Err(Error::KeyTooShort(key.len()));
+        }
+        let difficulty = difficulty.map_or(Ok(DEFAULT_DIFFICULTY), parse_difficulty)?;
+        let ttl = match ttl {
+            None => DEFAULT_TTL,
+            Some(ttl) if ttl > 0 => ttl,
+            Some(ttl) => return Err(Error::InvalidTtl(ttl)),
+        };
+        Ok(Self {
+            key: key.to_vec(),
+            difficulty,
+            ttl,
+        })
+    }
+
+    /// Tag over the unsigned part of a challenge and the scope.
+    fn _tag(&self, unsigned: &str, scope: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(unsigned.as_bytes());
+        mac.update(&[0]);
+        mac.update(scope.as_bytes());
+        mac
+    }
+
+    /// Issues a challenge as of `now`.
+    fn _challenge(&self, scope: &str, difficulty: u32, now: i64) -> String {
+        let salt = BASE64URL_NOPAD.encode(&rand::random::<[u8; SALT_LEN]>());
+        let unsigned = format!("v1.{difficulty}.{}.{salt}", now.saturating_add(self.ttl));
+        let tag = BASE64URL_NOPAD.encode(&self._tag(&unsigned, scope).finalize().into_bytes());
+        format!("{unsigned}.{tag}")
+    }
+
+    /// Checks a solution as of `now`.
+    fn _verify(&self, challenge: &str, nonce: &str, scope: &str, now: i64) -> bool {
+        if nonce.is_empty() || nonce.len() > MAX_NONCE_LEN {
+            return false;
+        }
+        let Some((unsigned, tag)) = challenge.rsplit_once('.') else {
+            return false;
+        };
+        let Ok(tag) = BASE64URL_NOPAD.decode(tag.as_bytes()) else {
+            return false;
+        };
+        if self._tag(unsigned, scope).verify_slice(&tag).is_err() {
+            return false;
+        }
+        // Authentic from here on, so the fields are ours
+        let mut fields = unsigned.split('.').skip(1);
+        let (Some(Ok(difficulty)), Some(Ok(expires))) = (
+            fields.next().map(str::parse::<u32>),
+            fields.next().map(str::parse::<i64>),
+        ) else {
+            return false;
+        };
+        if now > expires {
+            return false;
+        }
+        let hash = Sha256::new()
+            .chain_update(challenge.as_bytes())
+            .chain_update(nonce.as_bytes())
+            .finalize();
+        leading_zero_bits(&hash) >= difficulty
+    }
+}
+
+#[php_impl]
+impl ProofOfWork {
+    /// Constructs a challenge issuer.
+    ///
+    /// # Parameters
+    /// - `key`: `string` Secret of at least 32 bytes.
+    /// - `difficulty`: `?int` Default number of leading zero bits required, 1–32. Each extra
+    ///   bit doubles the average work. Default: 18.
+    /// - `ttl`: `?int` Seconds a challenge stays solvable. Default: 300.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the key is too short, or the difficulty or TTL is out of range.
+    fn __construct(key: Binary<u8>, difficulty: Option<i64>, ttl: Option<i64>) -> Result<Self> {
+        Self::_new(&key, difficulty, ttl)
+    }
+
+    /// Issue a challenge.
+    ///
+    /// # Parameters
+    /// - `scope`: `?string` What the challenge is for (form name, client IP, account…); the
+    ///   same scope must be passed to `verify()`. Default: `''`.
+    /// - `difficulty`: `?int` Override the default difficulty, e.g. to raise it for clients
+    ///   that already failed several times.
+    ///
+    /// # Returns
+    /// - `string` e.g. `"v1.18.1760000000.<salt>.<tag>"`; the difficulty is the second field.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the difficulty is out of range.
+    fn challenge(&self, scope: Option<String>, difficulty: Option<i64>) -> Result<String> {
+        let difficulty = difficulty.map_or(Ok(self.difficulty), parse_difficulty)?;
+        Ok(self._challenge(
+            scope.as_deref().unwrap_or_default(),
+            difficulty,
+            unix_time(),
+        ))
+    }
+
+    /// Verify a solution.
+    ///
+    /// Challenges are stateless, so one solution can be replayed until the challenge expires;
+    /// keep short TTLs, or record used challenges when that matters.
+    ///
+    /// # Parameters
+    /// - `challenge`: `string` Challenge returned by `challenge()`.
+    /// - `nonce`: `string` The client's solution, at most 64 bytes.
+    /// - `scope`: `?string` Scope given to `challenge()`. Default: `''`.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the challenge is authentic, unexpired and solved.
+    fn verify(&self, challenge: &str, nonce: &str, scope: Option<String>) -> bool {
+        self._verify(
+            challenge,
+            nonce,
+            scope.as_deref().unwrap_or_default(),
+            unix_time(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, ProofOfWork, leading_zero_bits};
+    use sha2::{Digest, Sha256};
+
+    fn solve(challenge: &str, difficulty: u32) -> String {
+        (0u64..)
+            .map(|nonce| nonce.to_string())
+            .find(|nonce| {
+                let hash = Sha256::new()
+                    .chain_update(challenge.as_bytes())
+                    .chain_update(nonce.as_bytes())
+                    .finalize();
+                leading_zero_bits(&hash) >= difficulty
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_leading_zero_bits() {
+        assert_eq!(leading_zero_bits(&[0, 0, 0x10, 0xff]), 19);
+        assert_eq!(leading_zero_bits(&[0x80]), 0);
+        assert_eq!(leading_zero_bits(&[0, 0]), 16);
+    }
+
+    #[test]
+    fn test_challenge() {
+        let pow = ProofOfWork::_new(&[7; 32], Some(8), Some(60)).unwrap();
+        let challenge = pow._challenge("signup", 8, 1000);
+        assert!(challenge.starts_with("v1.8.1060."));
+        let nonce = solve(&challenge, 8);
+        assert!(pow._verify(&challenge, &nonce, "signup", 1060));
+        assert!(!pow._verify(&challenge, &nonce, "signup", 1061));
+        assert!(!pow._verify(&challenge, &nonce, "login", 1000));
+        assert!(!pow._verify(&challenge, "", "signup", 1000));
+
+        // Lowering the difficulty breaks the tag
+        let easier = challenge.replacen("v1.8.", "v1.1.", 1);
+        assert!(!pow._verify(&easier, &solve(&easier, 1), "signup", 1000));
+
+        let other = ProofOfWork::_new(&[8; 32], None, None).unwrap();
+        assert!(!other._verify(&challenge, &nonce, "signup", 1000));
+
+        assert!(matches!(
+            ProofOfWork::_new(&[7; 16], None, None),
+            Err(Error::KeyTooShort(16))
+        ));
+        assert!(matches!(
+            ProofOfWork::_new(&[7; 32], Some(33), None),
+            Err(Error::InvalidDifficulty(33))
+        ));
+    }
+}
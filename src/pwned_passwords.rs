@@ -0,0 +1,161 @@
+//! Helpers for the Have I Been Pwned "Pwned Passwords" range API.
+//!
+//! The API is queried with the first five hex digits of a password's SHA-1 (k-anonymity), and
+//! answers with every known hash suffix in that range. This module computes the prefix/suffix
+//! pair and searches the response; the HTTP request itself is left to the application.
+
+use data_encoding::HEXUPPER;
+use ext_php_rs::binary::Binary;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use thiserror::Error;
+
+// Error codes for PwnedPasswords errors: 2900-2999
+pub mod error_codes {
+    pub const INVALID_SUFFIX: i32 = 2900;
+}
+
+/// Errors produced by `PwnedPasswords`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Hash suffix must be 35 hex digits, or the full 40-digit SHA-1: {0:?}")]
+    InvalidSuffix(String),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::InvalidSuffix(_) => error_codes::INVALID_SUFFIX,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for Pwned Passwords operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Hex digits of the hash sent to the API.
+const PREFIX_LEN: usize = 5;
+
+/// Uppercase hex SHA-1 of `password`, split into the range prefix and the suffix.
+#[must_use]
+pub fn split_hash(password: &[u8]) -> (String, String) {
+    let mut hash = HEXUPPER.encode(&Sha1::digest(password));
+    let suffix = hash.split_off(PREFIX_LEN);
+    (hash, suffix)
+}
+
+/// Finds `suffix` in a range response and returns its breach count.
+///
+/// Lines look like `SUFFIX:COUNT`. Entries with a count of 0 are the fake rows the API adds
+/// when `Add-Padding` is requested, so they count as not found.
+///
+/// # Errors
+/// - Returns `Error::InvalidSuffix` unless `suffix` is 35 hex digits or a full 40-digit hash.
+pub fn find_suffix(suffix: &str, body: &str) -> Result<Option<i64>> {
+    let suffix = suffix.trim();
+    if !suffix.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(Error::InvalidSuffix(suffix.to_string()));
+    }
+    let suffix = match suffix.len() {
+        40 => &suffix[PREFIX_LEN..],
+        35 => suffix,
+        _ => return Err(Error::InvalidSuffix(suffix.to_string())),
+    };
+    Ok(body
+        .lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(suffix))
+        .and_then(|(_, count)| count.trim().parse::<i64>().ok())
+        .filter(|count| *count > 0))
+}
+
+/// Have I Been Pwned k-anonymity helpers.
+#[php_class]
+#[php(name = "Hardened\\PwnedPasswords")]
+pub struct PwnedPasswords {}
+
+#[php_impl]
+impl PwnedPasswords {
+    /// Hash a password for a range query.
+    ///
+    /// # Parameters
+    /// - `password`: `string` The candidate password.
+    ///
+    /// # Returns
+    /// - `array` `prefix` (5 hex digits, for `https://api.pwnedpasswords.com/range/{prefix}`)
+    ///   and `suffix` (the remaining 35, to look up in the response). Both uppercase.
+    fn prefix(password: Binary<u8>) -> HashMap<&'static str, String> {
+        let (prefix, suffix) = split_hash(&password);
+        HashMap::from([("prefix", prefix), ("suffix", suffix)])
+    }
+
+    /// Look up a suffix in a range response body.
+    ///
+    /// # Parameters
+    /// - `suffix`: `string` Suffix from `prefix()` (or the full hash).
+    /// - `rangeBody`: `string` Body returned by the range endpoint.
+    ///
+    /// # Returns
+    /// - `?int` How many times the password appears in breaches, or `null` if it does not.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `suffix` is not a hex hash suffix.
+    fn match_in_range_response(suffix: &str, range_body: &str) -> Result<Option<i64>> {
+        find_suffix(suffix, range_body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, find_suffix, split_hash};
+
+    #[test]
+    fn test_split_hash() {
+        // SHA-1("password") = 5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8
+        let (prefix, suffix) = split_hash(b"password");
+        assert_eq!(prefix, "5BAA6");
+        assert_eq!(suffix, "1E4C9B93F3F0682250B6CF8331B7EE68FD8");
+    }
+
+    #[test]
+    fn test_find_suffix() {
+        let body = "003D68EB55068C33ACE09247EE4C639306B:3\r\n\
+                    1E4C9B93F3F0682250B6CF8331B7EE68FD8:10434004\r\n\
+                    1E4C9B93F3F0682250B6CF8331B7EE68FDA:0\r\n";
+        let (_, suffix) = split_hash(b"password");
+        assert_eq!(find_suffix(&suffix, body).unwrap(), Some(10_434_004));
+        assert_eq!(
+            find_suffix("5baa61e4c9b93f3f0682250b6cf8331b7ee68fd8", body).unwrap(),
+            Some(10_434_004)
+        );
+        // Padding rows have a zero count
+        assert_eq!(
+            find_suffix("1E4C9B93F3F0682250B6CF8331B7EE68FDA", body).unwrap(),
+            None
+        );
+        assert_eq!(
+            find_suffix("0000000000000000000000000000000000A", body).unwrap(),
+            None
+        );
+        assert!(matches!(
+            find_suffix("1E4C9", body),
+            Err(Error::InvalidSuffix(_))
+        ));
+        assert!(matches!(
+            find_suffix("ZZZC9B93F3F0682250B6CF8331B7EE68FD8", body),
+            Err(Error::InvalidSuffix(_))
+        ));
+    }
+}
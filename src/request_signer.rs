@@ -0,0 +1,809 @@
+//! Signing and verification of HTTP requests between services, following
+//! [RFC 9421](https://www.rfc-editor.org/rfc/rfc9421) (HTTP Message Signatures) with
+//! [RFC 9530](https://www.rfc-editor.org/rfc/rfc9530) `Content-Digest` for the body.
+//!
+//! The signer covers the method, authority, path, query, any configured headers and the
+//! body digest, and emits `Signature-Input` and `Signature` headers with `hmac-sha256` or
+//! `ed25519` signatures, so it interoperates with other RFC 9421 implementations.
+
+use crate::to_str;
+use data_encoding::BASE64;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use ext_php_rs::binary::Binary;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::types::ZendHashTable;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use url::Url;
+
+// Error codes for RequestSigner errors: 3800-3899
+pub mod error_codes {
+    pub const UNSUPPORTED_ALGORITHM: i32 = 3800;
+    pub const INVALID_KEY: i32 = 3801;
+    pub const INVALID_OPTION: i32 = 3802;
+    pub const URL_PARSE: i32 = 3803;
+    pub const INVALID_HEADER: i32 = 3804;
+    pub const MISSING_COMPONENT: i32 = 3805;
+    pub const VERIFY_ONLY: i32 = 3806;
+}
+
+/// Errors produced by `RequestSigner`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unsupported algorithm {0:?}, expected \"hmac-sha256\" or \"ed25519\"")]
+    UnsupportedAlgorithm(String),
+
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+
+    #[error("Invalid option: {0}")]
+    InvalidOption(String),
+
+    #[error("URL parse error: {0}")]
+    UrlParseError(String),
+
+    #[error("Header {0:?} must be a string or a list of strings")]
+    InvalidHeader(String),
+
+    #[error("Covered component {0:?} is missing from the request")]
+    MissingComponent(String),
+
+    #[error("An Ed25519 public key can only verify signatures")]
+    VerifyOnly,
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::UnsupportedAlgorithm(_) => error_codes::UNSUPPORTED_ALGORITHM,
+            Error::InvalidKey(_) => error_codes::INVALID_KEY,
+            Error::InvalidOption(_) => error_codes::INVALID_OPTION,
+            Error::UrlParseError(_) => error_codes::URL_PARSE,
+            Error::InvalidHeader(_) => error_codes::INVALID_HEADER,
+            Error::MissingComponent(_) => error_codes::MISSING_COMPONENT,
+            Error::VerifyOnly => error_codes::VERIFY_ONLY,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for request signing operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const MIN_HMAC_KEY_LEN: usize = 32;
+/// Label of the signature this signer emits.
+const LABEL: &str = "sig1";
+const DEFAULT_COMPONENTS: &[&str] = &["@method", "@authority", "@path", "@query"];
+const DERIVED_COMPONENTS: &[&str] = &[
+    "@method",
+    "@target-uri",
+    "@authority",
+    "@scheme",
+    "@request-target",
+    "@path",
+    "@query",
+];
+const DEFAULT_MAX_AGE: i64 = 300;
+/// How far in the future a `created` time may lie, for clock drift between hosts.
+const FUTURE_SKEW: i64 = 60;
+
+/// Current UNIX time in seconds.
+fn unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+/// `Content-Digest` value of a body.
+fn content_digest(body: &[u8]) -> String {
+    format!("sha-256=:{}:", BASE64.encode(&Sha256::digest(body)))
+}
+
+/// Whether `name` is a header field name as RFC 9110 defines it.
+fn is_token(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+/// Splits a structured field dictionary into `(key, value)` members, ignoring commas inside
+/// strings and inner lists.
+fn dictionary(field: &str) -> Vec<(&str, &str)> {
+    let mut members = Vec::new();
+    let (mut start, mut depth, mut quoted, mut escaped) = (0, 0u32, false, false);
+    for (i, c) in field.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth = depth.saturating_sub(1),
+            ',' if !quoted && depth == 0 => {
+                members.push(&field[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    members.push(&field[start..]);
+    members
+        .into_iter()
+        .filter_map(|member| {
+            let (key, value) = member.trim().split_once('=')?;
+            Some((key.trim(), value.trim()))
+        })
+        .collect()
+}
+
+/// A parsed `Signature-Input` member: the covered components and the parameters.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SignatureInput {
+    components: Vec<String>,
+    created: Option<i64>,
+    key_id: Option<String>,
+    alg: Option<String>,
+}
+
+impl SignatureInput {
+    /// Parses `("a" "b");created=1;keyid="k"`; `None` for anything else.
+    fn parse(value: &str) -> Option<Self> {
+        let rest = value.strip_prefix('(')?;
+        let (list, params) = rest.split_once(')')?;
+        let mut input = Self::default();
+        for item in list.split_ascii_whitespace() {
+            let name = item.strip_prefix('"')?.strip_suffix('"')?;
+            if name.contains(['"', '\\', ';']) {
+                return None;
+            }
+            input.components.push(name.to_string());
+        }
+        for param in params.split(';').skip(1) {
+            let (name, value) = param.split_once('=')?;
+            let string = || {
+                value
+                    .strip_prefix('"')
+                    .and_then(|value| value.strip_suffix('"'))
+                    .map(str::to_string)
+            };
+            match name {
+                "created" => input.created = Some(value.parse().ok()?),
+                "keyid" => input.key_id = Some(string()?),
+                "alg" => input.alg = Some(string()?),
+                _ => {}
+            }
+        }
+        Some(input)
+    }
+}
+
+/// The parts of a request a signature can cover.
+struct Request {
+    method: String,
+    url: Url,
+    /// Lowercased names with their values, in order.
+    headers: Vec<(String, String)>,
+}
+
+impl Request {
+    fn new(method: &str, url: &str, headers: &ZendHashTable) -> Result<Self> {
+        let url = Url::parse(url).map_err(|err| Error::UrlParseError(err.to_string()))?;
+        let mut pairs = Vec::new();
+        for (name, value) in headers {
+            let name = name.to_string();
+            let invalid = || Error::InvalidHeader(name.clone());
+            match value.array() {
+                Some(list) => {
+                    for item in list.values() {
+                        pairs.push((
+                            name.to_ascii_lowercase(),
+                            to_str(item).map_err(|_| invalid())?,
+                        ));
+                    }
+                }
+                None => pairs.push((
+                    name.to_ascii_lowercase(),
+                    to_str(value).map_err(|_| invalid())?,
+                )),
+            }
+        }
+        Ok(Self {
+            method: method.to_string(),
+            url,
+            headers: pairs,
+        })
+    }
+
+    fn header(&self, name: &str) -> Option<String> {
+        let values: Vec<&str> = self
+            .headers
+            .iter()
+            .filter(|(header, _)| header == name)
+            .map(|(_, value)| value.trim())
+            .collect();
+        (!values.is_empty()).then(|| values.join(", "))
+    }
+
+    /// Value of a covered component, per RFC 9421 §2.
+    fn component(&self, name: &str) -> Option<String> {
+        let authority = || {
+            let host = self.url.host_str()?;
+            Some(match self.url.port() {
+                Some(port) => format!("{host}:{port}"),
+                None => host.to_string(),
+            })
+        };
+        let query = || format!("?{}", self.url.query().unwrap_or_default());
+        match name {
+            "@method" => Some(self.method.to_ascii_uppercase()),
+            "@target-uri" => {
+                let mut url = self.url.clone();
+                url.set_fragment(None);
+                Some(url.into())
+            }
+            "@authority" => authority(),
+            "@scheme" => Some(self.url.scheme().to_string()),
+            "@request-target" => Some(match self.url.query() {
+                Some(query) => format!("{}?{query}", self.url.path()),
+                None => self.url.path().to_string(),
+            }),
+            "@path" => Some(self.url.path().to_string()),
+            "@query" => Some(query()),
+            _ if name.starts_with('@') => None,
+            _ => self.header(name),
+        }
+    }
+
+    /// The signature base of RFC 9421 §2.5, or `None` if a component is missing.
+    fn signature_base(&self, components: &[String], params: &str) -> Option<String> {
+        let mut base = String::new();
+        for name in components {
+            let _ = writeln!(base, "\"{name}\": {}", self.component(name)?);
+        }
+        let _ = write!(base, "\"@signature-params\": {params}");
+        Some(base)
+    }
+}
+
+/// Key material and algorithm.
+enum Key {
+    Hmac(Vec<u8>),
+    Ed25519(SigningKey),
+    Ed25519Public(VerifyingKey),
+}
+
+impl Key {
+    fn alg(&self) -> &'static str {
+        match self {
+            Key::Hmac(_) => "hmac-sha256",
+            Key::Ed25519(_) | Key::Ed25519Public(_) => "ed25519",
+        }
+    }
+}
+
+/// RFC 9421 request signer and verifier.
+#[php_class]
+#[php(name = "Hardened\\RequestSigner")]
+pub struct RequestSigner {
+    key_id: String,
+    key: Key,
+    /// Components every signature must cover, besides `content-digest` when there is a body.
+    components: Vec<String>,
+    max_age: i64,
+}
+
+impl RequestSigner {
+    /// Backs `__construct()`.
+    fn _new(key_id: &str, algorithm: &str, key: &[u8]) -> Result<Self> {
+        if key_id.is_empty() || key_id.contains(['"', '\\']) || !key_id.is_ascii() {
+            return Err(Error::InvalidOption(format!("Invalid key id {key_id:?}")));
+        }
+        let key = match algorithm.to_ascii_lowercase().as_str() {
+            "hmac-sha256" => {
+                if key.len() < MIN_HMAC_KEY_LEN {
+                    return Err(Error::InvalidKey(format!(
+                        "HMAC keys need at least 32 bytes, got {}",
+                        key.len()
+                    )));
+                }
+                Key::Hmac(key.to_vec())
+            }
+            "ed25519" => match key.len() {
+                64 => Key::Ed25519(
+                    SigningKey::from_keypair_bytes(key.try_into().expect("length checked"))
+                        .map_err(|err| Error::InvalidKey(err.to_string()))?,
+                ),
+                32 => Key::Ed25519Public(
+                    VerifyingKey::from_bytes(key.try_into().expect("length checked"))
+                        .map_err(|err| Error::InvalidKey(err.to_string()))?,
+                ),
+                len => {
+                    return Err(Error::InvalidKey(format!(
+                        "Ed25519 keys are 64-byte secret keys or 32-byte public keys, got {len} bytes"
+                    )));
+                }
+            },
+            _ => return Err(Error::UnsupportedAlgorithm(algorithm.to_string())),
+        };
+        Ok(Self {
+            key_id: key_id.to_string(),
+            key,
+            components: DEFAULT_COMPONENTS
+                .iter()
+                .map(|c| (*c).to_string())
+                .collect(),
+            max_age: DEFAULT_MAX_AGE,
+        })
+    }
+
+    fn _signature(&self, base: &str) -> Result<Vec<u8>> {
+        Ok(match &self.key {
+            Key::Hmac(key) => {
+                let mut mac = <HmacSha256 as Mac>::new_from_slice(key)
+                    .expect("HMAC accepts keys of any length");
+                mac.update(base.as_bytes());
+                mac.finalize().into_bytes().to_vec()
+            }
+            Key::Ed25519(key) => key.sign(base.as_bytes()).to_bytes().to_vec(),
+            Key::Ed25519Public(_) => return Err(Error::VerifyOnly),
+        })
+    }
+
+    fn _check(&self, base: &str, signature: &[u8]) -> bool {
+        match &self.key {
+            Key::Hmac(key) => {
+                let mut mac = <HmacSha256 as Mac>::new_from_slice(key)
+                    .expect("HMAC accepts keys of any length");
+                mac.update(base.as_bytes());
+                mac.verify_slice(signature).is_ok()
+            }
+            Key::Ed25519(key) => Self::_check_ed25519(&key.verifying_key(), base, signature),
+            Key::Ed25519Public(key) => Self::_check_ed25519(key, base, signature),
+        }
+    }
+
+    fn _check_ed25519(key: &VerifyingKey, base: &str, signature: &[u8]) -> bool {
+        Signature::from_slice(signature)
+            .is_ok_and(|signature| key.verify_strict(base.as_bytes(), &signature).is_ok())
+    }
+
+    /// Signs `request` as of `now`; returns the headers to add.
+    fn _sign_at(
+        &self,
+        request: &mut Request,
+        body: Option<&[u8]>,
+        now: i64,
+    ) -> Result<Vec<(&'static str, String)>> {
+        let mut headers = Vec::new();
+        let mut components = self.components.clone();
+        if let Some(body) = body {
+            let digest = content_digest(body);
+            request.headers.retain(|(name, _)| name != "content-digest");
+            request
+                .headers
+                .push(("content-digest".into(), digest.clone()));
+            components.push("content-digest".into());
+            headers.push(("Content-Digest", digest));
+        }
+        if let Some(missing) = components
+            .iter()
+            .find(|name| request.component(name).is_none())
+        {
+            return Err(Error::MissingComponent(missing.clone()));
+        }
+        let list: Vec<String> = components
+            .iter()
+            .map(|name| format!("\"{name}\""))
+            .collect();
+        let params = format!(
+            "({});created={now};keyid=\"{}\";alg=\"{}\"",
+            list.join(" "),
+            self.key_id,
+            self.key.alg()
+        );
+        let base = request
+            .signature_base(&components, &params)
+            .ok_or_else(|| Error::MissingComponent("signature base".into()))?;
+        let signature = self._signature(&base)?;
+        headers.push(("Signature-Input", format!("{LABEL}={params}")));
+        headers.push((
+            "Signature",
+            format!("{LABEL}=:{}:", BASE64.encode(&signature)),
+        ));
+        Ok(headers)
+    }
+
+    /// Verifies `request` as of `now`.
+    fn _verify_at(&self, request: &Request, body: Option<&[u8]>, now: i64) -> bool {
+        let (Some(inputs), Some(signatures)) = (
+            request.header("signature-input"),
+            request.header("signature"),
+        ) else {
+            return false;
+        };
+        let inputs = dictionary(&inputs);
+        let signatures = dictionary(&signatures);
+        let Some((label, params, input)) = inputs.iter().find_map(|(label, value)| {
+            SignatureInput::parse(value)
+                .filter(|input| input.key_id.as_deref() == Some(self.key_id.as_str()))
+                .map(|input| (*label, *value, input))
+        }) else {
+            return false;
+        };
+        let Some(signature) = signatures
+            .iter()
+            .find(|(name, _)| *name == label)
+            .and_then(|(_, value)| value.strip_prefix(':')?.strip_suffix(':'))
+            .and_then(|value| BASE64.decode(value.as_bytes()).ok())
+        else {
+            return false;
+        };
+        if input
+            .alg
+            .as_deref()
+            .is_some_and(|alg| alg != self.key.alg())
+        {
+            return false;
+        }
+        let Some(created) = input.created else {
+            return false;
+        };
+        if now.saturating_sub(created) > self.max_age || created.saturating_sub(now) > FUTURE_SKEW {
+            return false;
+        }
+        let covers = |name: &str| input.components.iter().any(|component| component == name);
+        if !self.components.iter().all(|name| covers(name)) {
+            return false;
+        }
+        if let Some(body) = body {
+            let expected = content_digest(body);
+            let matches = request.header("content-digest").is_some_and(|header| {
+                dictionary(&header)
+                    .iter()
+                    .any(|(alg, value)| format!("{alg}={value}") == expected)
+            });
+            if !matches || !covers("content-digest") {
+                return false;
+            }
+        }
+        request
+            .signature_base(&input.components, params)
+            .is_some_and(|base| self._check(&base, &signature))
+    }
+}
+
+#[php_impl]
+impl RequestSigner {
+    /// Constructs a signer.
+    ///
+    /// # Parameters
+    /// - `keyId`: `string` Identifier the receiver uses to pick the key (`keyid` parameter).
+    /// - `algorithm`: `string` `"hmac-sha256"` or `"ed25519"`.
+    /// - `key`: `string` Raw key bytes: an HMAC secret of at least 32 bytes, a 64-byte
+    ///   `sodium_crypto_sign_secretkey()` (signs and verifies), or a 32-byte
+    ///   `sodium_crypto_sign_publickey()` (verifies only).
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the algorithm is unknown or the key does not fit it.
+    fn __construct(key_id: &str, algorithm: &str, key: Binary<u8>) -> Result<Self> {
+        Self::_new(key_id, algorithm, &key)
+    }
+
+    /// Set the components every signature must cover.
+    ///
+    /// # Parameters
+    /// - `components`: `string[]` Derived components (`@method`, `@target-uri`, `@authority`,
+    ///   `@scheme`, `@request-target`, `@path`, `@query`) and header names. Default:
+    ///   `["@method", "@authority", "@path", "@query"]`. `content-digest` is added
+    ///   automatically whenever there is a body.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` on an unknown derived component or an invalid header name.
+    fn set_components(&mut self, components: Vec<String>) -> Result<()> {
+        let mut covered = Vec::with_capacity(components.len());
+        for component in components {
+            let component = component.trim().to_ascii_lowercase();
+            let valid = if component.starts_with('@') {
+                DERIVED_COMPONENTS.contains(&component.as_str())
+            } else {
+                is_token(&component)
+            };
+            if !valid {
+                return Err(Error::InvalidOption(format!(
+                    "Invalid component {component:?}"
+                )));
+            }
+            if !covered.contains(&component) {
+                covered.push(component);
+            }
+        }
+        self.components = covered;
+        Ok(())
+    }
+
+    /// Set how old a signature may be.
+    ///
+    /// # Parameters
+    /// - `seconds`: `int` Default: 300.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `seconds` is not positive.
+    fn max_age(&mut self, seconds: i64) -> Result<()> {
+        if seconds <= 0 {
+            return Err(Error::InvalidOption(format!(
+                "Maximum age must be positive, got {seconds}"
+            )));
+        }
+        self.max_age = seconds;
+        Ok(())
+    }
+
+    /// Sign a request.
+    ///
+    /// # Parameters
+    /// - `method`: `string` e.g. `"POST"`.
+    /// - `url`: `string` Absolute URL.
+    /// - `headers`: `array` Header name => value (or list of values); must contain every
+    ///   covered header.
+    /// - `body`: `?string` Request body; when given, a `Content-Digest` is computed and
+    ///   covered.
+    ///
+    /// # Returns
+    /// - `array` Headers to add: `Content-Digest` (with a body), `Signature-Input` and
+    ///   `Signature`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the URL is invalid, a covered header is missing, or the key is
+    ///   an Ed25519 public key.
+    fn sign(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &ZendHashTable,
+        body: Option<Binary<u8>>,
+    ) -> Result<HashMap<&'static str, String>> {
+        let mut request = Request::new(method, url, headers)?;
+        Ok(self
+            ._sign_at(
+                &mut request,
+                body.as_deref().map(Vec::as_slice),
+                unix_time(),
+            )?
+            .into_iter()
+            .collect())
+    }
+
+    /// Verify a signed request.
+    ///
+    /// The signature is the one whose `keyid` matches this signer; it must be recent, use
+    /// this algorithm and cover the configured components.
+    ///
+    /// # Parameters
+    /// - `method`: `string` e.g. `$_SERVER['REQUEST_METHOD']`.
+    /// - `url`: `string` Absolute URL as requested.
+    /// - `headers`: `array` Request headers, e.g. `getallheaders()`.
+    /// - `body`: `?string` Request body, e.g. `file_get_contents('php://input')`; when given,
+    ///   `Content-Digest` must match it and be covered.
+    ///
+    /// # Returns
+    /// - `bool`
+    fn verify(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &ZendHashTable,
+        body: Option<Binary<u8>>,
+    ) -> bool {
+        Request::new(method, url, headers).is_ok_and(|request| {
+            self._verify_at(&request, body.as_deref().map(Vec::as_slice), unix_time())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Request, RequestSigner, SignatureInput, content_digest, dictionary};
+    use data_encoding::BASE64;
+    use ed25519_dalek::SigningKey;
+    use url::Url;
+
+    fn request(method: &str, url: &str, headers: &[(&str, &str)]) -> Request {
+        Request {
+            method: method.into(),
+            url: Url::parse(url).unwrap(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_ascii_lowercase(), (*value).to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_rfc9421_hmac_vector() {
+        // RFC 9421 §B.2.5
+        let key = BASE64
+            .decode(b"uzvJfB4u3N0Jy4T7NZ75MDVcr8zSTInedJtkgcu46YW4XByzNJjxBdtjUkdJPBtbmHhIDi6pcl8jsasjlTMtDQ==")
+            .unwrap();
+        let mut signer = RequestSigner::_new("test-shared-secret", "hmac-sha256", &key).unwrap();
+        signer
+            .set_components(vec![
+                "date".into(),
+                "@authority".into(),
+                "content-type".into(),
+            ])
+            .unwrap();
+        let request = request(
+            "POST",
+            "https://example.com/foo?param=Value&Pet=dog",
+            &[
+                ("Date", "Tue, 20 Apr 2021 02:07:55 GMT"),
+                ("Content-Type", "application/json"),
+                (
+                    "Signature-Input",
+                    "sig-b25=(\"date\" \"@authority\" \"content-type\");created=1618884473;keyid=\"test-shared-secret\"",
+                ),
+                (
+                    "Signature",
+                    "sig-b25=:pxcQw6G3AjtMBQjwo8XzkZf/bws5LelbaMk5rGIGtE8=:",
+                ),
+            ],
+        );
+        assert!(signer._verify_at(&request, None, 1_618_884_473));
+        assert!(!signer._verify_at(&request, None, 1_618_884_473 + 301));
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = RequestSigner::_new("svc", "hmac-sha256", &[7; 32]).unwrap();
+        let url = "https://api.example.com:8443/orders?id=5";
+        let mut outgoing = request("post", url, &[("Content-Type", "application/json")]);
+        let headers = signer
+            ._sign_at(
+                &mut outgoing,
+                Some(b"{\"hello\": \"world\"}".as_slice()),
+                1000,
+            )
+            .unwrap();
+        assert_eq!(
+            headers[0],
+            (
+                "Content-Digest",
+                "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:".to_string()
+            )
+        );
+        assert_eq!(
+            headers[1].1,
+            "sig1=(\"@method\" \"@authority\" \"@path\" \"@query\" \"content-digest\");created=1000;keyid=\"svc\";alg=\"hmac-sha256\""
+        );
+        let mut received: Vec<(&str, &str)> = vec![("Content-Type", "application/json")];
+        received.extend(headers.iter().map(|(name, value)| (*name, value.as_str())));
+
+        let body: &[u8] = b"{\"hello\": \"world\"}";
+        assert!(signer._verify_at(&request("POST", url, &received), Some(body), 1000));
+        assert!(!signer._verify_at(&request("PUT", url, &received), Some(body), 1000));
+        assert!(!signer._verify_at(
+            &request(
+                "POST",
+                "https://api.example.com:8443/orders?id=6",
+                &received
+            ),
+            Some(body),
+            1000
+        ));
+        assert!(!signer._verify_at(
+            &request(
+                "POST",
+                "https://evil.example.com:8443/orders?id=5",
+                &received
+            ),
+            Some(body),
+            1000
+        ));
+        assert!(!signer._verify_at(
+            &request("POST", url, &received),
+            Some(b"{}".as_slice()),
+            1000
+        ));
+        assert!(!signer._verify_at(&request("POST", url, &received), Some(body), 1301));
+        assert!(!signer._verify_at(&request("POST", url, &received), Some(body), 900));
+        let other = RequestSigner::_new("svc", "hmac-sha256", &[8; 32]).unwrap();
+        assert!(!other._verify_at(&request("POST", url, &received), Some(body), 1000));
+        let other_id = RequestSigner::_new("other", "hmac-sha256", &[7; 32]).unwrap();
+        assert!(!other_id._verify_at(&request("POST", url, &received), Some(body), 1000));
+
+        let mut strict = RequestSigner::_new("svc", "hmac-sha256", &[7; 32]).unwrap();
+        strict
+            .set_components(vec!["@method".into(), "x-tenant".into()])
+            .unwrap();
+        assert!(!strict._verify_at(&request("POST", url, &received), Some(body), 1000));
+        assert!(matches!(
+            strict._sign_at(&mut request("POST", url, &[]), None, 1000),
+            Err(super::Error::MissingComponent(name)) if name == "x-tenant"
+        ));
+    }
+
+    #[test]
+    fn test_ed25519() {
+        let secret = SigningKey::from_bytes(&[3; 32]);
+        let signer = RequestSigner::_new("ed", "ed25519", &secret.to_keypair_bytes()).unwrap();
+        let verifier =
+            RequestSigner::_new("ed", "ed25519", secret.verifying_key().as_bytes()).unwrap();
+        let url = "https://example.com/hook";
+        let headers = signer
+            ._sign_at(&mut request("GET", url, &[]), None, 1000)
+            .unwrap();
+        let received: Vec<(&str, &str)> = headers
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect();
+        assert!(verifier._verify_at(&request("GET", url, &received), None, 1000));
+        assert!(!verifier._verify_at(
+            &request("GET", "https://example.com/x", &received),
+            None,
+            1000
+        ));
+        assert!(
+            verifier
+                ._sign_at(&mut request("GET", url, &[]), None, 1000)
+                .is_err()
+        );
+        // An HMAC signer with the same key id must not accept an `ed25519` signature
+        let hmac = RequestSigner::_new("ed", "hmac-sha256", &[3; 32]).unwrap();
+        assert!(!hmac._verify_at(&request("GET", url, &received), None, 1000));
+        assert!(RequestSigner::_new("ed", "ed25519", &[0; 16]).is_err());
+        assert!(RequestSigner::_new("ed", "rsa-pss-sha512", &[0; 32]).is_err());
+    }
+
+    #[test]
+    fn test_parsing() {
+        assert_eq!(
+            dictionary("a=(\"x\" \"y\");keyid=\"k,1\", b=:AA==:"),
+            vec![("a", "(\"x\" \"y\");keyid=\"k,1\""), ("b", ":AA==:")]
+        );
+        assert_eq!(
+            SignatureInput::parse("(\"@method\" \"date\");created=5;keyid=\"k\";alg=\"ed25519\""),
+            Some(SignatureInput {
+                components: vec!["@method".into(), "date".into()],
+                created: Some(5),
+                key_id: Some("k".into()),
+                alg: Some("ed25519".into()),
+            })
+        );
+        assert_eq!(SignatureInput::parse("@method;created=5"), None);
+        assert_eq!(
+            content_digest(b""),
+            "sha-256=:47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=:"
+        );
+        let request = request(
+            "get",
+            "http://Example.COM:80/a%20b",
+            &[("X-A", "1"), ("x-a", " 2 ")],
+        );
+        assert_eq!(
+            request.component("@authority").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(request.component("@query").as_deref(), Some("?"));
+        assert_eq!(request.component("@path").as_deref(), Some("/a%20b"));
+        assert_eq!(request.component("x-a").as_deref(), Some("1, 2"));
+        assert_eq!(request.component("@status"), None);
+    }
+}
@@ -1,10 +1,26 @@
+use crate::constant_time;
+use data_encoding::{BASE32_NOPAD, BASE64URL_NOPAD, HEXLOWER};
 use ext_php_rs::binary::Binary;
 use ext_php_rs::exception::PhpException;
-use ext_php_rs::types::Zval;
+use ext_php_rs::types::{ArrayKey, ZendHashTable, Zval};
 use ext_php_rs::zend::ce;
 use ext_php_rs::{php_class, php_impl};
 use rand::distr::{Alphabetic, Alphanumeric, SampleString, Uniform};
-use rand::{RngExt, rng, seq::IndexedRandom};
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng, rng, seq::IndexedRandom};
+use sha2::{Digest, Sha256};
+use std::cell::Cell;
+use std::fmt::Write;
+use std::fs::{self, OpenOptions};
+use std::hint::black_box;
+use std::io::Write as _;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::LazyLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use strum_macros::{Display, EnumString};
 use thiserror::Error;
 use unicode_segmentation::UnicodeSegmentation;
 
@@ -18,6 +34,16 @@ pub mod error_codes {
     pub const ZVAL_CONVERSION: i32 = 1405;
     pub const DISTRIBUTION_ERROR: i32 = 1406;
     pub const WEIGHT_ERROR: i32 = 1407;
+    pub const ULID_OVERFLOW: i32 = 1408;
+    pub const INVALID_ULID: i32 = 1409;
+    pub const INVALID_PASSWORD_POLICY: i32 = 1410;
+    pub const INVALID_WORDLIST: i32 = 1411;
+    pub const INVALID_ENCODING: i32 = 1412;
+    pub const INVALID_PROBABILITY: i32 = 1413;
+    pub const SAMPLE_TOO_LARGE: i32 = 1414;
+    pub const INVALID_DIGITS: i32 = 1415;
+    pub const INVALID_FILE_MODE: i32 = 1416;
+    pub const FILE_WRITE: i32 = 1417;
 }
 
 /// Errors that can occur during random number generation operations.
@@ -46,6 +72,36 @@ pub enum Error {
 
     #[error("Weighted selection error: {0}")]
     WeightError(String),
+
+    #[error("ULID random component overflowed within one millisecond")]
+    UlidOverflow,
+
+    #[error("Invalid ULID: {0}")]
+    InvalidUlid(String),
+
+    #[error("Invalid password policy: {0}")]
+    InvalidPasswordPolicy(String),
+
+    #[error("Wordlist must contain at least two distinct non-empty words")]
+    InvalidWordlist,
+
+    #[error("Unsupported token encoding: {0}")]
+    InvalidEncoding(String),
+
+    #[error("Probability must be between 0 and 1, got {0}")]
+    InvalidProbability(f64),
+
+    #[error("Cannot sample {0} items from {1}")]
+    SampleTooLarge(usize, usize),
+
+    #[error("Code length must be between 1 and 19 digits, got {0}")]
+    InvalidDigits(usize),
+
+    #[error("File mode must only contain permission bits (0 to 0777), got {0:#o}")]
+    InvalidFileMode(u32),
+
+    #[error("Failed to write random bytes to file: {0}")]
+    FileWrite(String),
 }
 
 impl Error {
@@ -60,6 +116,16 @@ impl Error {
             Error::ZvalConversionError(_) => error_codes::ZVAL_CONVERSION,
             Error::DistributionError(_) => error_codes::DISTRIBUTION_ERROR,
             Error::WeightError(_) => error_codes::WEIGHT_ERROR,
+            Error::UlidOverflow => error_codes::ULID_OVERFLOW,
+            Error::InvalidUlid(_) => error_codes::INVALID_ULID,
+            Error::InvalidPasswordPolicy(_) => error_codes::INVALID_PASSWORD_POLICY,
+            Error::InvalidWordlist => error_codes::INVALID_WORDLIST,
+            Error::InvalidEncoding(_) => error_codes::INVALID_ENCODING,
+            Error::InvalidProbability(_) => error_codes::INVALID_PROBABILITY,
+            Error::SampleTooLarge(..) => error_codes::SAMPLE_TOO_LARGE,
+            Error::InvalidDigits(_) => error_codes::INVALID_DIGITS,
+            Error::InvalidFileMode(_) => error_codes::INVALID_FILE_MODE,
+            Error::FileWrite(_) => error_codes::FILE_WRITE,
         }
     }
 }
@@ -75,12 +141,704 @@ impl From<Error> for PhpException {
 /// Result type alias for RNG operations.
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// `len` bytes from the thread-local CSPRNG, for other modules that need raw key material.
+///
+/// # Errors
+/// - Returns `Error::DistributionError` if the uniform distribution for `u8` cannot be created.
+pub(crate) fn random_bytes(len: usize) -> Result<Vec<u8>> {
+    Ok(rng()
+        .sample_iter(
+            Uniform::new_inclusive(u8::MIN, u8::MAX)
+                .map_err(|e| Error::DistributionError(e.to_string()))?,
+        )
+        .take(len)
+        .collect())
+}
+
+/// Milliseconds since the UNIX epoch.
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX)
+        })
+}
+
+/// Formats 16 bytes as a lowercase `8-4-4-4-12` UUID string.
+fn format_uuid(bytes: &[u8; 16]) -> String {
+    let mut uuid = String::with_capacity(36);
+    for (i, byte) in bytes.iter().enumerate() {
+        if matches!(i, 4 | 6 | 8 | 10) {
+            uuid.push('-');
+        }
+        let _ = write!(uuid, "{byte:02x}");
+    }
+    uuid
+}
+
+/// Crockford's base32 alphabet, as used by ULIDs.
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Crockford's check symbols, appended to the base32 alphabet for values 32-36.
+const CROCKFORD_CHECK: &[u8; 5] = b"*~$=U";
+
+/// Bitcoin's base58 alphabet, without `0`, `O`, `I` and `l`.
+const BASE58: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Text encodings supported by `Rng::token()`.
+#[derive(EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+enum TokenEncoding {
+    /// Lowercase hexadecimal.
+    Hex,
+    /// URL-safe base64 without padding (RFC 4648 §5).
+    Base64url,
+    /// Uppercase base32 without padding (RFC 4648 §6).
+    Base32,
+    /// Bitcoin base58; no look-alike characters and no punctuation.
+    Base58,
+    /// Crockford base32; case-insensitive and free of `I`, `L`, `O` and `U`.
+    Crockford,
+}
+
+impl TokenEncoding {
+    fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Hex => HEXLOWER.encode(bytes),
+            Self::Base64url => BASE64URL_NOPAD.encode(bytes),
+            Self::Base32 => BASE32_NOPAD.encode(bytes),
+            Self::Base58 => encode_base58(bytes),
+            Self::Crockford => encode_crockford(bytes),
+        }
+    }
+}
+
+/// Encodes bytes as Crockford base32, most significant bits first, zero-padding the last symbol.
+fn encode_crockford(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 8 / 5 + 1);
+    let (mut buffer, mut bits) = (0u16, 0);
+    for &byte in bytes {
+        buffer = (buffer << 8) | u16::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(CROCKFORD[usize::from((buffer >> bits) & 0x1F)] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(CROCKFORD[usize::from((buffer << (5 - bits)) & 0x1F)] as char);
+    }
+    out
+}
+
+/// Crockford's check symbol: the encoded value modulo 37.
+fn crockford_check_symbol(encoded: &str) -> char {
+    let remainder = encoded.bytes().fold(0usize, |acc, c| {
+        let digit = CROCKFORD.iter().position(|&d| d == c).unwrap_or(0);
+        (acc * 32 + digit) % 37
+    });
+    if remainder < 32 {
+        CROCKFORD[remainder] as char
+    } else {
+        CROCKFORD_CHECK[remainder - 32] as char
+    }
+}
+
+/// Encodes bytes as base58; every leading zero byte becomes a leading `1`.
+fn encode_base58(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+    // Little-endian base58 digits of the big-endian number
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in &bytes[zeros..] {
+        let mut carry = u32::from(byte);
+        for digit in &mut digits {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    std::iter::repeat_n('1', zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|&digit| BASE58[usize::from(digit)] as char),
+        )
+        .collect()
+}
+
+/// Returns true for codes of 3 or more digits that are one repeated digit (`000000`)
+/// or a run up or down (`123456`, `987654`) — the first guesses of an attacker.
+fn is_trivial_code(code: &str) -> bool {
+    let digits = code.as_bytes();
+    if digits.len() < 3 {
+        return false;
+    }
+    let steps = |step: i16| {
+        digits
+            .windows(2)
+            .all(|pair| i16::from(pair[1]) - i16::from(pair[0]) == step)
+    };
+    steps(0) || steps(1) || steps(-1)
+}
+
+/// Mask of the 80-bit random component of a ULID.
+const ULID_RANDOM_MASK: u128 = (1 << 80) - 1;
+
+thread_local! {
+    /// Timestamp and random component of the last ULID, for monotonic generation.
+    static LAST_ULID: Cell<Option<(u64, u128)>> = const { Cell::new(None) };
+}
+
+/// Encodes a 128-bit ULID as 26 Crockford base32 characters.
+fn encode_ulid(ulid: u128) -> String {
+    (0..26)
+        .rev()
+        .map(|i| CROCKFORD[((ulid >> (i * 5)) & 0x1F) as usize] as char)
+        .collect()
+}
+
+/// Decodes a 26-character ULID, case-insensitively.
+///
+/// # Errors
+/// - Returns `Error::InvalidUlid` on a wrong length, a character outside the alphabet,
+///   or a value that does not fit in 128 bits.
+fn decode_ulid(input: &str) -> Result<u128> {
+    let invalid = || Error::InvalidUlid(input.to_string());
+    if input.len() != 26 {
+        return Err(invalid());
+    }
+    let mut ulid = 0u128;
+    for (i, c) in input.bytes().enumerate() {
+        let digit = CROCKFORD
+            .iter()
+            .position(|&d| d == c.to_ascii_uppercase())
+            .ok_or_else(invalid)?;
+        // 26 characters carry 130 bits; the first one may only use the low 3
+        if i == 0 && digit > 7 {
+            return Err(invalid());
+        }
+        ulid = (ulid << 5) | digit as u128;
+    }
+    Ok(ulid)
+}
+
+/// Symbols used by `Rng::password()` unless the policy sets its own.
+const DEFAULT_SYMBOLS: &str = "!#$%&*+-=?@^_";
+
+/// Characters easily confused with one another when read or typed.
+const AMBIGUOUS: &str = "0Oo1lI|";
+
+/// Which characters `Rng::password()` draws from; every enabled class appears at least once.
+struct PasswordPolicy {
+    lower: bool,
+    upper: bool,
+    digits: bool,
+    symbols: bool,
+    symbol_set: String,
+    exclude_ambiguous: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            lower: true,
+            upper: true,
+            digits: true,
+            symbols: false,
+            symbol_set: DEFAULT_SYMBOLS.into(),
+            exclude_ambiguous: false,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Reads a policy from a PHP array; unknown keys are rejected so typos do not
+    /// silently weaken the policy.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidPasswordPolicy` naming the offending key.
+    fn from_table(table: &ZendHashTable) -> Result<Self> {
+        let mut policy = Self::default();
+        let invalid = |name: &str| Error::InvalidPasswordPolicy(name.into());
+        for (key, value) in table {
+            let key = key.to_string();
+            let flag = || value.bool().ok_or_else(|| invalid(&key));
+            match key.as_str() {
+                "lower" => policy.lower = flag()?,
+                "upper" => policy.upper = flag()?,
+                "digits" => policy.digits = flag()?,
+                "symbols" => policy.symbols = flag()?,
+                "excludeAmbiguous" => policy.exclude_ambiguous = flag()?,
+                "symbolSet" => policy.symbol_set = value.string().ok_or_else(|| invalid(&key))?,
+                _ => return Err(invalid(&key)),
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Reads an optional policy array; `None` gives the defaults.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidPasswordPolicy` naming the offending key.
+    fn from_option(table: Option<&ZendHashTable>) -> Result<Self> {
+        table.map_or_else(|| Ok(Self::default()), Self::from_table)
+    }
+
+    /// The enabled character classes, without ambiguous characters if requested.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidPasswordPolicy` if no class is enabled or one ends up empty.
+    fn classes(&self) -> Result<Vec<Vec<char>>> {
+        let classes: Vec<Vec<char>> = [
+            (self.lower, ('a'..='z').collect::<Vec<_>>()),
+            (self.upper, ('A'..='Z').collect()),
+            (self.digits, ('0'..='9').collect()),
+            (self.symbols, self.symbol_set.chars().collect()),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, mut class)| {
+            class.sort_unstable();
+            class.dedup();
+            if self.exclude_ambiguous {
+                class.retain(|c| !AMBIGUOUS.contains(*c));
+            }
+            class
+        })
+        .collect();
+        if classes.is_empty() {
+            return Err(Error::InvalidPasswordPolicy(
+                "no character class enabled".into(),
+            ));
+        }
+        if classes.iter().any(Vec::is_empty) {
+            return Err(Error::InvalidPasswordPolicy(
+                "a character class is empty".into(),
+            ));
+        }
+        Ok(classes)
+    }
+}
+
+/// The EFF large wordlist: 7776 words (12.9 bits each), one `dice<TAB>word` pair per line.
+static EFF_LARGE_WORDLIST: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
+    include_str!("rng/eff_large_wordlist.txt")
+        .lines()
+        .filter_map(|line| line.split_whitespace().last())
+        .collect()
+});
+
 #[php_class]
 #[php(name = "Hardened\\Rng")]
 pub struct Rng {}
 
+impl Rng {
+    /// Sets the version nibble and the RFC 9562 variant bits.
+    fn _set_uuid_version(bytes: &mut [u8; 16], version: u8) {
+        bytes[6] = (bytes[6] & 0x0F) | (version << 4);
+        bytes[8] = (bytes[8] & 0x3F) | 0x80;
+    }
+
+    /// A random (version 4) UUID: 122 random bits.
+    fn _uuid4<R: RngExt + ?Sized>(rng: &mut R) -> [u8; 16] {
+        let mut bytes: [u8; 16] = rng.random();
+        Self::_set_uuid_version(&mut bytes, 4);
+        bytes
+    }
+
+    /// A time-ordered (version 7) UUID: 48-bit UNIX milliseconds, then 74 random bits.
+    fn _uuid7<R: RngExt + ?Sized>(rng: &mut R, unix_ms: u64) -> [u8; 16] {
+        let mut bytes: [u8; 16] = rng.random();
+        bytes[..6].copy_from_slice(&unix_ms.to_be_bytes()[2..]);
+        Self::_set_uuid_version(&mut bytes, 7);
+        bytes
+    }
+
+    /// A ULID: 48-bit UNIX milliseconds, then 80 random bits.
+    ///
+    /// Within the same millisecond as `last` (or if the clock went backwards) the random
+    /// component of `last` is incremented instead, so ULIDs from one thread sort strictly.
+    ///
+    /// # Errors
+    /// - Returns `Error::UlidOverflow` if the random component cannot be incremented.
+    fn _ulid<R: RngExt + ?Sized>(
+        rng: &mut R,
+        unix_ms: u64,
+        last: &mut Option<(u64, u128)>,
+    ) -> Result<u128> {
+        let unix_ms = unix_ms & ((1 << 48) - 1);
+        let (unix_ms, random) = match *last {
+            Some((last_ms, last_random)) if unix_ms <= last_ms => {
+                let random = last_random + 1;
+                if random > ULID_RANDOM_MASK {
+                    return Err(Error::UlidOverflow);
+                }
+                (last_ms, random)
+            }
+            _ => (unix_ms, rng.random::<u128>() & ULID_RANDOM_MASK),
+        };
+        *last = Some((unix_ms, random));
+        Ok((u128::from(unix_ms) << 80) | random)
+    }
+
+    /// A uniform integer in `[min, max]`.
+    ///
+    /// Draws 64 random bits and rejects the top `2^64 mod span` values, so that the final
+    /// modulo is free of bias.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidRange` if `min > max`.
+    fn _int_between<R: RngExt + ?Sized>(rng: &mut R, min: i64, max: i64) -> Result<i64> {
+        if min > max {
+            return Err(Error::InvalidRange);
+        }
+        let span = max.abs_diff(min).wrapping_add(1);
+        if span == 0 {
+            // The full i64 range: every 64-bit value is fine
+            return Ok(rng.random());
+        }
+        Ok(min.wrapping_add_unsigned(Self::_below(rng, span)))
+    }
+
+    /// A uniform integer in `[0, bound)`; `bound` must not be zero.
+    fn _below<R: RngExt + ?Sized>(rng: &mut R, bound: u64) -> u64 {
+        let rejected = (u64::MAX % bound + 1) % bound;
+        loop {
+            let value: u64 = rng.random();
+            if value <= u64::MAX - rejected {
+                return value % bound;
+            }
+        }
+    }
+
+    /// The index of a weight, chosen with probability `weight / sum(weights)`.
+    ///
+    /// Works on exact integers throughout, so no weight is lost to float rounding.
+    ///
+    /// # Errors
+    /// - Returns `Error::WeightError` if all weights are zero or their sum overflows.
+    fn _weighted_index<R: RngExt + ?Sized>(rng: &mut R, weights: &[u64]) -> Result<usize> {
+        let total = weights
+            .iter()
+            .try_fold(0u64, |total, &weight| total.checked_add(weight))
+            .ok_or_else(|| Error::WeightError("sum of weights overflows".into()))?;
+        if total == 0 {
+            return Err(Error::WeightError("no positive weight".into()));
+        }
+        let mut target = Self::_below(rng, total);
+        for (index, &weight) in weights.iter().enumerate() {
+            if target < weight {
+                return Ok(index);
+            }
+            target -= weight;
+        }
+        unreachable!("target is below the sum of weights")
+    }
+
+    /// A uniform float in `[0, 1)` with 53 bits of precision.
+    fn _float<R: RngExt + ?Sized>(rng: &mut R) -> f64 {
+        (rng.random::<u64>() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A uniform float in `[min, max)`, or `min` if both bounds are equal.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidRange` if a bound is not finite, `min > max`, or the range
+    ///   is wider than the largest float.
+    fn _float_between<R: RngExt + ?Sized>(rng: &mut R, min: f64, max: f64) -> Result<f64> {
+        let width = max - min;
+        if !min.is_finite() || !max.is_finite() || min > max || !width.is_finite() {
+            return Err(Error::InvalidRange);
+        }
+        if width == 0.0 {
+            return Ok(min);
+        }
+        loop {
+            // Rounding can land exactly on `max`
+            let value = min + width * Self::_float(rng);
+            if value < max {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Moves `k` uniformly chosen items, in random order, to the front of `items`
+    /// (the first `k` steps of a Fisher–Yates shuffle).
+    ///
+    /// # Errors
+    /// - Returns `Error::SampleTooLarge` if `k` exceeds the number of items.
+    fn _sample<T, R: RngExt + ?Sized>(rng: &mut R, items: &mut [T], k: usize) -> Result<()> {
+        if k > items.len() {
+            return Err(Error::SampleTooLarge(k, items.len()));
+        }
+        for i in 0..k {
+            items.swap(i, rng.random_range(i..items.len()));
+        }
+        Ok(())
+    }
+
+    /// `words` words drawn independently and uniformly from `wordlist`, joined by `separator`.
+    fn _pick_words<R: RngExt + ?Sized>(
+        rng: &mut R,
+        words: usize,
+        separator: &str,
+        wordlist: &[&str],
+    ) -> String {
+        (0..words)
+            .map(|_| wordlist[rng.random_range(0..wordlist.len())])
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    /// A password of `length` characters containing every class of `policy`.
+    ///
+    /// Characters are drawn uniformly from the union of the classes and candidates missing
+    /// a class are discarded, so the result is uniform over all passwords satisfying the
+    /// policy (forcing one character per class would make positions predictable).
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidPasswordPolicy` if the policy is unusable or `length` is
+    ///   shorter than the number of required classes.
+    fn _password<R: RngExt + ?Sized>(
+        rng: &mut R,
+        length: usize,
+        policy: &PasswordPolicy,
+    ) -> Result<String> {
+        let classes = policy.classes()?;
+        if length < classes.len() {
+            return Err(Error::InvalidPasswordPolicy(format!(
+                "length {length} cannot cover {} character classes",
+                classes.len()
+            )));
+        }
+        let mut alphabet = classes.concat();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+        loop {
+            let password: Vec<char> = (0..length)
+                .map(|_| alphabet[rng.random_range(0..alphabet.len())])
+                .collect();
+            if classes
+                .iter()
+                .all(|class| password.iter().any(|c| class.contains(c)))
+            {
+                return Ok(password.into_iter().collect());
+            }
+        }
+    }
+
+    /// Backs `numericCode()`.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidDigits` unless `digits` is between 1 and 19.
+    fn _numeric_code<R: RngExt + ?Sized>(
+        rng: &mut R,
+        digits: usize,
+        exclude_trivial: bool,
+    ) -> Result<String> {
+        if !(1..=19).contains(&digits) {
+            return Err(Error::InvalidDigits(digits));
+        }
+        let bound = 10u64.pow(digits as u32);
+        loop {
+            let code = format!("{:0digits$}", Self::_below(rng, bound));
+            if !exclude_trivial || !is_trivial_code(&code) {
+                return Ok(code);
+            }
+        }
+    }
+
+    /// Backs `bytes()`.
+    fn _bytes<R: RngExt + ?Sized>(rng: &mut R, len: usize) -> Vec<u8> {
+        (0..len).map(|_| rng.random()).collect()
+    }
+
+    /// Backs `bytesToFile()`.
+    ///
+    /// The file is created exclusively, so an existing file or a planted symlink is never
+    /// written through, and a partially written file is removed on failure. Bytes pass
+    /// through a small buffer that is wiped afterwards.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidFileMode` if `mode` has bits beyond `0o777`.
+    /// - Returns `Error::FileWrite` if the file already exists or cannot be written.
+    fn _bytes_to_file<R: RngExt + ?Sized>(
+        rng: &mut R,
+        path: &Path,
+        length: usize,
+        mode: u32,
+    ) -> Result<()> {
+        if mode & !0o777 != 0 {
+            return Err(Error::InvalidFileMode(mode));
+        }
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(mode);
+        let mut file = options
+            .open(path)
+            .map_err(|err| Error::FileWrite(err.to_string()))?;
+
+        let mut buffer = [0u8; 4096];
+        let mut remaining = length;
+        let mut written = Ok(());
+        while remaining > 0 && written.is_ok() {
+            let chunk = &mut buffer[..remaining.min(4096)];
+            for byte in chunk.iter_mut() {
+                *byte = rng.random();
+            }
+            written = file.write_all(chunk);
+            remaining -= chunk.len();
+        }
+        buffer.fill(0);
+        black_box(&buffer);
+
+        if let Err(err) = written.and_then(|()| file.sync_all()) {
+            drop(file);
+            let _ = fs::remove_file(path);
+            return Err(Error::FileWrite(err.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Backs `bool()`.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidProbability` if `probability` is outside `[0, 1]`.
+    fn _bool<R: RngExt + ?Sized>(rng: &mut R, probability: Option<f64>) -> Result<bool> {
+        let probability = probability.unwrap_or(0.5);
+        if !(0.0..=1.0).contains(&probability) {
+            return Err(Error::InvalidProbability(probability));
+        }
+        Ok(Self::_float(rng) < probability)
+    }
+
+    /// Backs `token()`.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidEncoding` for an unknown encoding, or a checksum requested
+    ///   for an encoding other than Crockford.
+    fn _token<R: RngExt + ?Sized>(
+        rng: &mut R,
+        bytes: usize,
+        encoding: Option<&str>,
+        checksum: Option<bool>,
+    ) -> Result<String> {
+        let name = encoding.unwrap_or("base64url");
+        let encoding =
+            TokenEncoding::from_str(name).map_err(|_| Error::InvalidEncoding(name.into()))?;
+        let checksum = checksum.unwrap_or(false);
+        if checksum && encoding != TokenEncoding::Crockford {
+            return Err(Error::InvalidEncoding(format!("{encoding} with checksum")));
+        }
+        let mut token = encoding.encode(&Self::_bytes(rng, bytes));
+        if checksum {
+            token.push(crockford_check_symbol(&token));
+        }
+        Ok(token)
+    }
+
+    /// Backs `passphrase()`.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidWordlist` if a custom wordlist has fewer than two distinct words.
+    fn _passphrase<R: RngExt + ?Sized>(
+        rng: &mut R,
+        words: usize,
+        separator: Option<&str>,
+        wordlist: Option<Vec<String>>,
+    ) -> Result<String> {
+        let separator = separator.unwrap_or("-");
+        let Some(wordlist) = wordlist else {
+            return Ok(Self::_pick_words(
+                rng,
+                words,
+                separator,
+                &EFF_LARGE_WORDLIST,
+            ));
+        };
+        // Duplicates would make some words more likely than others
+        let mut wordlist: Vec<&str> = wordlist
+            .iter()
+            .map(String::as_str)
+            .filter(|word| !word.is_empty())
+            .collect();
+        wordlist.sort_unstable();
+        wordlist.dedup();
+        if wordlist.len() < 2 {
+            return Err(Error::InvalidWordlist);
+        }
+        Ok(Self::_pick_words(rng, words, separator, &wordlist))
+    }
+
+    /// Backs `shuffle()`.
+    fn _shuffle_values<R: RngExt + ?Sized>(rng: &mut R, items: Vec<&Zval>) -> Vec<Zval> {
+        let mut items = items;
+        let len = items.len();
+        // Cannot fail: k equals the length
+        let _ = Self::_sample(rng, &mut items, len);
+        items.into_iter().map(Zval::shallow_clone).collect()
+    }
+
+    /// Backs `sample()`.
+    ///
+    /// # Errors
+    /// - Returns `Error::SampleTooLarge` if `k` exceeds the number of items.
+    fn _sample_values<R: RngExt + ?Sized>(
+        rng: &mut R,
+        items: Vec<&Zval>,
+        k: usize,
+    ) -> Result<Vec<Zval>> {
+        let mut items = items;
+        Self::_sample(rng, &mut items, k)?;
+        Ok(items[..k].iter().map(|item| item.shallow_clone()).collect())
+    }
+
+    /// Backs `weightedChoice()`.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidWeight` for a weight that is not a non-negative integer,
+    ///   or `Error::WeightError` if no weight is positive or their sum overflows.
+    fn _weighted_choice<R: RngExt + ?Sized>(rng: &mut R, weights: &ZendHashTable) -> Result<Zval> {
+        let mut keys = Vec::with_capacity(weights.len());
+        let mut values = Vec::with_capacity(weights.len());
+        for (key, weight) in weights {
+            let weight = weight
+                .long()
+                .and_then(|weight| u64::try_from(weight).ok())
+                .ok_or(Error::InvalidWeight)?;
+            keys.push(key);
+            values.push(weight);
+        }
+        let mut zval = Zval::new();
+        match &keys[Self::_weighted_index(rng, &values)?] {
+            ArrayKey::Long(index) => zval.set_long(*index),
+            key => zval
+                .set_string(&key.to_string(), false)
+                .map_err(|err| Error::ZvalConversionError(format!("{err:?}")))?,
+        }
+        Ok(zval)
+    }
+}
+
 #[php_impl]
 impl Rng {
+    /// Create a deterministic generator for tests; see `SeededRng`.
+    ///
+    /// # Parameters
+    /// - `seed`: `string` Any string; equal seeds give equal sequences.
+    ///
+    /// # Returns
+    /// - `SeededRng` A **non-cryptographic** generator. Never use it for real secrets.
+    fn seeded(seed: &str) -> SeededRng {
+        SeededRng::__construct(seed)
+    }
+
     /// Generate a random ASCII alphanumeric string of the specified length.
     ///
     /// # Parameters
@@ -114,15 +872,25 @@ impl Rng {
     /// # Exceptions
     /// - Throws an exception if the uniform distribution for `u8` cannot be created.
     fn bytes(len: usize) -> Result<Binary<u8>> {
-        Ok(Binary::from(
-            rng()
-                .sample_iter(
-                    Uniform::new_inclusive(u8::MIN, u8::MAX)
-                        .map_err(|e| Error::DistributionError(e.to_string()))?,
-                )
-                .take(len)
-                .collect::<Vec<_>>(),
-        ))
+        Ok(Binary::from(random_bytes(len)?))
+    }
+
+    /// Write random bytes straight to a new file, e.g. a key file, without returning them
+    /// as a PHP string that could end up in logs, core dumps or swap.
+    ///
+    /// The file must not exist yet; it is created with `mode` (further restricted by the
+    /// umask) and synced to disk before returning. On failure no partial file is left behind.
+    ///
+    /// # Parameters
+    /// - `path`: `string` Path of the file to create.
+    /// - `length`: `int` Number of bytes to write.
+    /// - `mode`: `int` Permission bits of the new file. Default: `0600`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `mode` is not a permission mask, the file already exists,
+    ///   or it cannot be written.
+    fn bytes_to_file(path: &str, length: usize, mode: Option<u32>) -> Result<()> {
+        Self::_bytes_to_file(&mut rng(), Path::new(path), length, mode.unwrap_or(0o600))
     }
 
     /// Generate a vector of random integers in the inclusive range `[low, high]`.
@@ -168,6 +936,62 @@ impl Rng {
         ))
     }
 
+    /// Generate a uniform random integer in the inclusive range `[min, max]`.
+    ///
+    /// Uses rejection sampling, so unlike `mt_rand() % n` or `rand($min, $max)` it has no
+    /// modulo bias and draws from the CSPRNG.
+    ///
+    /// # Parameters
+    /// - `min`: `int` Lower bound (inclusive).
+    /// - `max`: `int` Upper bound (inclusive).
+    ///
+    /// # Returns
+    /// - `int` A value within bounds.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `min > max`.
+    fn int_between(min: i64, max: i64) -> Result<i64> {
+        Self::_int_between(&mut rng(), min, max)
+    }
+
+    /// Generate a uniform random float in `[0, 1)`.
+    ///
+    /// # Returns
+    /// - `float` A value with 53 bits of randomness.
+    fn float() -> f64 {
+        Self::_float(&mut rng())
+    }
+
+    /// Generate a uniform random float in `[min, max)`.
+    ///
+    /// # Parameters
+    /// - `min`: `float` Lower bound (inclusive).
+    /// - `max`: `float` Upper bound (exclusive); equal bounds return `min`.
+    ///
+    /// # Returns
+    /// - `float` A value within bounds.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if a bound is `INF` or `NAN`, or `min > max`.
+    fn float_between(min: f64, max: f64) -> Result<f64> {
+        Self::_float_between(&mut rng(), min, max)
+    }
+
+    /// Return `true` with the given probability.
+    ///
+    /// # Parameters
+    /// - `probability`: `?float` Chance of `true`, from `0.0` (never) to `1.0` (always);
+    ///   default `0.5`.
+    ///
+    /// # Returns
+    /// - `bool`
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `probability` is outside `[0, 1]`.
+    fn bool(probability: Option<f64>) -> Result<bool> {
+        Self::_bool(&mut rng(), probability)
+    }
+
     /// Sample random Unicode characters (code points) from the given string.
     ///
     /// # Parameters
@@ -246,6 +1070,232 @@ impl Rng {
             .collect())
     }
 
+    /// Generate a random (version 4) UUID.
+    ///
+    /// # Returns
+    /// - `string` Canonical form, e.g. `"3f9c1e2a-7b4d-4c8e-9a1f-0d2e3c4b5a69"`.
+    fn uuid4() -> String {
+        format_uuid(&Self::_uuid4(&mut rng()))
+    }
+
+    /// Generate a random (version 4) UUID in binary form.
+    ///
+    /// # Returns
+    /// - `string` 16 bytes.
+    fn uuid4_binary() -> Binary<u8> {
+        Binary::from(Self::_uuid4(&mut rng()).to_vec())
+    }
+
+    /// Generate a time-ordered (version 7) UUID, which sorts by creation time and makes a
+    /// better database key than version 4.
+    ///
+    /// # Returns
+    /// - `string` Canonical form, e.g. `"01928f3a-6c1e-7d2b-8f4a-1b2c3d4e5f60"`.
+    fn uuid7() -> String {
+        format_uuid(&Self::_uuid7(&mut rng(), unix_millis()))
+    }
+
+    /// Generate a time-ordered (version 7) UUID in binary form.
+    ///
+    /// # Returns
+    /// - `string` 16 bytes.
+    fn uuid7_binary() -> Binary<u8> {
+        Binary::from(Self::_uuid7(&mut rng(), unix_millis()).to_vec())
+    }
+
+    /// Generate a ULID: a 26-character, lexicographically sortable identifier.
+    ///
+    /// ULIDs generated in the same millisecond by the same thread are still strictly
+    /// increasing.
+    ///
+    /// # Returns
+    /// - `string` e.g. `"01J9ZQ3XK5V7T2M8N4P6R0S1W3"`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the random component overflows within one millisecond.
+    fn ulid() -> Result<String> {
+        LAST_ULID.with(|cell| {
+            let mut last = cell.get();
+            let ulid = Self::_ulid(&mut rng(), unix_millis(), &mut last)?;
+            cell.set(last);
+            Ok(encode_ulid(ulid))
+        })
+    }
+
+    /// Check whether a string is a well-formed ULID (case-insensitive).
+    ///
+    /// # Parameters
+    /// - `ulid`: `string` The value to check.
+    ///
+    /// # Returns
+    /// - `bool` `true` if it is a valid ULID.
+    fn is_valid_ulid(ulid: &str) -> bool {
+        decode_ulid(ulid).is_ok()
+    }
+
+    /// Extract the creation time of a ULID.
+    ///
+    /// # Parameters
+    /// - `ulid`: `string` The ULID.
+    ///
+    /// # Returns
+    /// - `int` Milliseconds since the UNIX epoch.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the ULID is malformed.
+    fn ulid_timestamp(ulid: &str) -> Result<i64> {
+        Ok((decode_ulid(ulid)? >> 80) as i64)
+    }
+
+    /// Convert a ULID to its 16-byte binary form.
+    ///
+    /// # Parameters
+    /// - `ulid`: `string` The ULID.
+    ///
+    /// # Returns
+    /// - `string` 16 bytes, big-endian.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the ULID is malformed.
+    fn ulid_to_binary(ulid: &str) -> Result<Binary<u8>> {
+        Ok(Binary::from(decode_ulid(ulid)?.to_be_bytes().to_vec()))
+    }
+
+    /// Generate a password that satisfies a character-class policy.
+    ///
+    /// # Parameters
+    /// - `length`: `int` Number of characters.
+    /// - `policy`: `?array` Options, all optional:
+    ///   - `lower`, `upper`, `digits`: `bool` Require that class (default `true`).
+    ///   - `symbols`: `bool` Require a symbol (default `false`).
+    ///   - `symbolSet`: `string` Symbols to use (default `!#$%&*+-=?@^_`).
+    ///   - `excludeAmbiguous`: `bool` Leave out `0 O o 1 l I |` (default `false`).
+    ///
+    /// # Returns
+    /// - `string` A password containing at least one character of every enabled class.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the policy has unknown keys, enables no class, or cannot be
+    ///   satisfied in `length` characters.
+    fn password(length: usize, policy: Option<&ZendHashTable>) -> Result<String> {
+        Self::_password(&mut rng(), length, &PasswordPolicy::from_option(policy)?)
+    }
+
+    /// Generate a random token as text, e.g. for session IDs, API keys or short codes.
+    ///
+    /// # Parameters
+    /// - `bytes`: `int` Bytes of randomness (16 or more for secrets).
+    /// - `encoding`: `?string` One of `"hex"`, `"base64url"` (default), `"base32"`, `"base58"`
+    ///   or `"crockford"`.
+    /// - `checksum`: `?bool` Append a Crockford check symbol, so typos in hand-typed codes are
+    ///   detected (`"crockford"` only; default `false`).
+    ///
+    /// # Returns
+    /// - `string` The encoded token.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the encoding is unknown, or a checksum is requested for an
+    ///   encoding other than `"crockford"`.
+    fn token(bytes: usize, encoding: Option<String>, checksum: Option<bool>) -> Result<String> {
+        Self::_token(&mut rng(), bytes, encoding.as_deref(), checksum)
+    }
+
+    /// Generate a zero-padded numeric one-time code, e.g. for SMS or email verification.
+    ///
+    /// Every code of the given length is equally likely (no modulo bias).
+    ///
+    /// # Parameters
+    /// - `digits`: `int` Code length, 1 to 19.
+    /// - `excludeTrivial`: `?bool` Never return a repeated digit or a run such as `123456` or
+    ///   `987654` (codes of 3 or more digits; default `false`).
+    ///
+    /// # Returns
+    /// - `string` e.g. `"049731"`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `digits` is out of range.
+    fn numeric_code(digits: usize, exclude_trivial: Option<bool>) -> Result<String> {
+        Self::_numeric_code(&mut rng(), digits, exclude_trivial.unwrap_or(false))
+    }
+
+    /// Compare a submitted code with the expected one in constant time, so response timing
+    /// does not reveal how many leading digits were right.
+    ///
+    /// # Parameters
+    /// - `expected`: `string` The code that was sent.
+    /// - `given`: `string` The code the user entered.
+    ///
+    /// # Returns
+    /// - `bool` `true` if they are equal.
+    fn code_equals(expected: &str, given: &str) -> bool {
+        constant_time::eq(expected.as_bytes(), given.as_bytes())
+    }
+
+    /// Generate a diceware-style passphrase.
+    ///
+    /// With the built-in EFF large wordlist every word adds about 12.9 bits of entropy,
+    /// so 6 words give roughly 77 bits.
+    ///
+    /// # Parameters
+    /// - `words`: `int` Number of words.
+    /// - `separator`: `?string` Placed between words (default `"-"`).
+    /// - `wordlist`: `?array` Custom list of words; duplicates and empty strings are ignored.
+    ///
+    /// # Returns
+    /// - `string` e.g. `"exhale-gravy-nutmeg-unsaid-wobbly-tulip"`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the custom wordlist has fewer than two distinct words.
+    fn passphrase(
+        words: usize,
+        separator: Option<String>,
+        wordlist: Option<Vec<String>>,
+    ) -> Result<String> {
+        Self::_passphrase(&mut rng(), words, separator.as_deref(), wordlist)
+    }
+
+    /// Shuffle a list with Fisher–Yates and the CSPRNG, unlike PHP's `shuffle()`.
+    ///
+    /// # Parameters
+    /// - `items`: `array` Values to shuffle; keys are discarded.
+    ///
+    /// # Returns
+    /// - `array` The same values as a list, in uniformly random order.
+    fn shuffle(items: Vec<&Zval>) -> Vec<Zval> {
+        Self::_shuffle_values(&mut rng(), items)
+    }
+
+    /// Draw `k` distinct items without replacement, e.g. for raffles or audit sampling.
+    ///
+    /// # Parameters
+    /// - `items`: `array` Values to draw from; keys are discarded.
+    /// - `k`: `int` Number of items to draw.
+    ///
+    /// # Returns
+    /// - `array` `k` values as a list, in random order.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `k` exceeds the number of items.
+    fn sample(items: Vec<&Zval>, k: usize) -> Result<Vec<Zval>> {
+        Self::_sample_values(&mut rng(), items, k)
+    }
+
+    /// Pick a key with probability proportional to its weight, e.g. for A/B assignment.
+    ///
+    /// # Parameters
+    /// - `weights`: `array<int|string, int>` Non-negative integer weights by key,
+    ///   e.g. `['control' => 90, 'variant' => 10]`.
+    ///
+    /// # Returns
+    /// - `int|string` The chosen key.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if a weight is not a non-negative integer, all weights are
+    ///   zero, or their sum overflows.
+    fn weighted_choice(weights: &ZendHashTable) -> Result<Zval> {
+        Self::_weighted_choice(&mut rng(), weights)
+    }
+
     /// Randomly selects one element from the given list.
     ///
     /// # Parameters
@@ -350,9 +1400,152 @@ impl Rng {
     }
 }
 
+/// A deterministic, **non-cryptographic** generator for reproducible tests.
+///
+/// The seed is hashed with SHA-256 into the key of a ChaCha12 stream (`StdRng`), so equal
+/// seeds produce equal outputs with the same build of the extension. Anyone who knows the
+/// seed can predict every value: never use it for real passwords, tokens or keys.
+#[php_class]
+#[php(name = "Hardened\\SeededRng")]
+pub struct SeededRng {
+    rng: StdRng,
+}
+
+#[php_impl]
+impl SeededRng {
+    /// Constructs a generator from `seed`.
+    ///
+    /// # Parameters
+    /// - `seed`: `string` Any string; equal seeds give equal sequences.
+    fn __construct(seed: &str) -> Self {
+        Self {
+            rng: StdRng::from_seed(Sha256::digest(seed.as_bytes()).into()),
+        }
+    }
+
+    /// Always `false`: outputs are reproducible from the seed.
+    ///
+    /// # Returns
+    /// - `bool`
+    fn is_cryptographic(&self) -> bool {
+        false
+    }
+
+    /// Like `Rng::alphanumeric()`.
+    fn alphanumeric(&mut self, len: usize) -> String {
+        Alphanumeric.sample_string(&mut self.rng, len)
+    }
+
+    /// Like `Rng::bytes()`.
+    fn bytes(&mut self, len: usize) -> Binary<u8> {
+        Binary::from(Rng::_bytes(&mut self.rng, len))
+    }
+
+    /// Like `Rng::intBetween()`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `min > max`.
+    fn int_between(&mut self, min: i64, max: i64) -> Result<i64> {
+        Rng::_int_between(&mut self.rng, min, max)
+    }
+
+    /// Like `Rng::float()`.
+    fn float(&mut self) -> f64 {
+        Rng::_float(&mut self.rng)
+    }
+
+    /// Like `Rng::floatBetween()`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if a bound is `INF` or `NAN`, or `min > max`.
+    fn float_between(&mut self, min: f64, max: f64) -> Result<f64> {
+        Rng::_float_between(&mut self.rng, min, max)
+    }
+
+    /// Like `Rng::bool()`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `probability` is outside `[0, 1]`.
+    fn bool(&mut self, probability: Option<f64>) -> Result<bool> {
+        Rng::_bool(&mut self.rng, probability)
+    }
+
+    /// Like `Rng::numericCode()`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `digits` is out of range.
+    fn numeric_code(&mut self, digits: usize, exclude_trivial: Option<bool>) -> Result<String> {
+        Rng::_numeric_code(&mut self.rng, digits, exclude_trivial.unwrap_or(false))
+    }
+
+    /// Like `Rng::uuid4()`.
+    fn uuid4(&mut self) -> String {
+        format_uuid(&Rng::_uuid4(&mut self.rng))
+    }
+
+    /// Like `Rng::password()`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the policy is invalid or cannot be satisfied.
+    fn password(&mut self, length: usize, policy: Option<&ZendHashTable>) -> Result<String> {
+        Rng::_password(&mut self.rng, length, &PasswordPolicy::from_option(policy)?)
+    }
+
+    /// Like `Rng::passphrase()`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the custom wordlist has fewer than two distinct words.
+    fn passphrase(
+        &mut self,
+        words: usize,
+        separator: Option<String>,
+        wordlist: Option<Vec<String>>,
+    ) -> Result<String> {
+        Rng::_passphrase(&mut self.rng, words, separator.as_deref(), wordlist)
+    }
+
+    /// Like `Rng::token()`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the encoding is unknown or does not support a checksum.
+    fn token(
+        &mut self,
+        bytes: usize,
+        encoding: Option<String>,
+        checksum: Option<bool>,
+    ) -> Result<String> {
+        Rng::_token(&mut self.rng, bytes, encoding.as_deref(), checksum)
+    }
+
+    /// Like `Rng::shuffle()`.
+    fn shuffle(&mut self, items: Vec<&Zval>) -> Vec<Zval> {
+        Rng::_shuffle_values(&mut self.rng, items)
+    }
+
+    /// Like `Rng::sample()`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `k` exceeds the number of items.
+    fn sample(&mut self, items: Vec<&Zval>, k: usize) -> Result<Vec<Zval>> {
+        Rng::_sample_values(&mut self.rng, items, k)
+    }
+
+    /// Like `Rng::weightedChoice()`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if a weight is invalid or none is positive.
+    fn weighted_choice(&mut self, weights: &ZendHashTable) -> Result<Zval> {
+        Rng::_weighted_choice(&mut self.rng, weights)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Rng;
+    use super::{
+        AMBIGUOUS, DEFAULT_SYMBOLS, EFF_LARGE_WORDLIST, Error, PasswordPolicy, Rng, SeededRng,
+        TokenEncoding, ULID_RANDOM_MASK, crockford_check_symbol, decode_ulid, encode_ulid,
+        format_uuid, is_trivial_code,
+    };
     use crate::run_php_example;
     use unicode_segmentation::UnicodeSegmentation;
 
@@ -416,6 +1609,360 @@ mod tests {
         assert!(Rng::custom_ascii(4, "").is_err());
     }
 
+    #[test]
+    fn test_uuid() {
+        let uuid = Rng::uuid4();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert!(matches!(&uuid[19..20], "8" | "9" | "a" | "b"));
+        assert_eq!(uuid.chars().filter(|&c| c == '-').count(), 4, "{uuid}");
+        assert_ne!(uuid, Rng::uuid4());
+
+        let bytes = Rng::_uuid7(&mut rand::rng(), 0x0192_8F3A_6C1E);
+        assert!(format_uuid(&bytes).starts_with("01928f3a-6c1e-7"));
+        assert_eq!(bytes[8] & 0xC0, 0x80);
+        // Later timestamps sort later
+        assert!(Rng::_uuid7(&mut rand::rng(), 0x0192_8F3A_6C1F) > bytes);
+    }
+
+    #[test]
+    fn test_ulid() {
+        let mut last = None;
+        let first = Rng::_ulid(&mut rand::rng(), 1_700_000_000_000, &mut last).unwrap();
+        let second = Rng::_ulid(&mut rand::rng(), 1_700_000_000_000, &mut last).unwrap();
+        assert_eq!(second, first + 1);
+        // A clock going backwards keeps the order
+        let third = Rng::_ulid(&mut rand::rng(), 1_699_999_999_999, &mut last).unwrap();
+        assert!(third > second);
+        assert_eq!(third >> 80, 1_700_000_000_000);
+
+        let encoded = encode_ulid(first);
+        assert_eq!(encoded.len(), 26);
+        assert!(encoded.starts_with("01HF"), "{encoded}");
+        assert_eq!(decode_ulid(&encoded.to_ascii_lowercase()).unwrap(), first);
+        assert_eq!(Rng::ulid_timestamp(&encoded).unwrap(), 1_700_000_000_000);
+        assert!(encode_ulid(second) > encoded);
+
+        assert!(Rng::is_valid_ulid("7ZZZZZZZZZZZZZZZZZZZZZZZZZ"));
+        assert!(!Rng::is_valid_ulid("8ZZZZZZZZZZZZZZZZZZZZZZZZZ"));
+        assert!(!Rng::is_valid_ulid("01HFUI00000000000000000000"));
+        assert!(!Rng::is_valid_ulid("01HF"));
+
+        let mut last = Some((1, ULID_RANDOM_MASK));
+        assert!(matches!(
+            Rng::_ulid(&mut rand::rng(), 1, &mut last),
+            Err(Error::UlidOverflow)
+        ));
+
+        assert!(Rng::ulid().unwrap() < Rng::ulid().unwrap());
+    }
+
+    #[test]
+    fn test_password() {
+        let policy = PasswordPolicy {
+            symbols: true,
+            exclude_ambiguous: true,
+            ..PasswordPolicy::default()
+        };
+        for _ in 0..100 {
+            let password = Rng::_password(&mut rand::rng(), 4, &policy).unwrap();
+            assert_eq!(password.chars().count(), 4);
+            assert!(
+                password.chars().any(|c| c.is_ascii_lowercase()),
+                "{password}"
+            );
+            assert!(
+                password.chars().any(|c| c.is_ascii_uppercase()),
+                "{password}"
+            );
+            assert!(password.chars().any(|c| c.is_ascii_digit()), "{password}");
+            assert!(
+                password.chars().any(|c| DEFAULT_SYMBOLS.contains(c)),
+                "{password}"
+            );
+            assert!(
+                !password.chars().any(|c| AMBIGUOUS.contains(c)),
+                "{password}"
+            );
+        }
+
+        let digits_only = PasswordPolicy {
+            lower: false,
+            upper: false,
+            ..PasswordPolicy::default()
+        };
+        let pin = Rng::_password(&mut rand::rng(), 6, &digits_only).unwrap();
+        assert!(pin.chars().all(|c| c.is_ascii_digit()), "{pin}");
+
+        assert!(matches!(
+            Rng::_password(&mut rand::rng(), 2, &PasswordPolicy::default()),
+            Err(Error::InvalidPasswordPolicy(_))
+        ));
+        let nothing = PasswordPolicy {
+            digits: false,
+            ..digits_only
+        };
+        assert!(nothing.classes().is_err());
+        let ambiguous_only = PasswordPolicy {
+            symbol_set: "|".into(),
+            ..policy
+        };
+        assert!(ambiguous_only.classes().is_err());
+    }
+
+    #[test]
+    fn test_passphrase() {
+        assert_eq!(EFF_LARGE_WORDLIST.len(), 7776);
+        assert_eq!(EFF_LARGE_WORDLIST.first(), Some(&"abacus"));
+        assert_eq!(EFF_LARGE_WORDLIST.last(), Some(&"zoom"));
+
+        let phrase = Rng::passphrase(6, None, None).unwrap();
+        let words: Vec<&str> = phrase.split('-').collect();
+        assert_eq!(words.len(), 6, "{phrase}");
+        assert!(words.iter().all(|word| EFF_LARGE_WORDLIST.contains(word)));
+
+        let phrase = Rng::passphrase(
+            4,
+            Some(" ".into()),
+            Some(vec![
+                "red".into(),
+                "blue".into(),
+                "red".into(),
+                String::new(),
+            ]),
+        )
+        .unwrap();
+        assert!(
+            phrase
+                .split(' ')
+                .all(|word| word == "red" || word == "blue"),
+            "{phrase}"
+        );
+        assert!(matches!(
+            Rng::passphrase(4, None, Some(vec!["red".into(), "red".into()])),
+            Err(Error::InvalidWordlist)
+        ));
+    }
+
+    #[test]
+    fn test_token() {
+        assert_eq!(TokenEncoding::Hex.encode(b"\x00\xff"), "00ff");
+        assert_eq!(TokenEncoding::Base64url.encode(b"\xfb\xff"), "-_8");
+        assert_eq!(TokenEncoding::Base32.encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(
+            TokenEncoding::Base58.encode(b"hello world"),
+            "StV1DL6CwTryKyV"
+        );
+        assert_eq!(TokenEncoding::Base58.encode(b"\0\0\x01"), "112");
+        assert_eq!(TokenEncoding::Crockford.encode(b"\xff"), "ZW");
+        assert_eq!(TokenEncoding::Crockford.encode(b"\x01\x23"), "04HG");
+        // "16J" is 1234, and 1234 % 37 = 13; "11" is 33
+        assert_eq!(crockford_check_symbol("16J"), 'D');
+        assert_eq!(crockford_check_symbol("11"), '~');
+
+        assert_eq!(Rng::token(32, Some("hex".into()), None).unwrap().len(), 64);
+        assert_eq!(Rng::token(32, None, None).unwrap().len(), 43);
+        let code = Rng::token(5, Some("Crockford".into()), Some(true)).unwrap();
+        assert_eq!(code.len(), 9);
+        assert_eq!(
+            code.chars().last(),
+            Some(crockford_check_symbol(&code[..8]))
+        );
+        assert!(matches!(
+            Rng::token(16, Some("base36".into()), None),
+            Err(Error::InvalidEncoding(_))
+        ));
+        assert!(matches!(
+            Rng::token(16, Some("hex".into()), Some(true)),
+            Err(Error::InvalidEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut generator = rand::rng();
+        for _ in 0..1000 {
+            let value = Rng::_int_between(&mut generator, -3, 3).unwrap();
+            assert!((-3..=3).contains(&value));
+        }
+        assert_eq!(Rng::int_between(7, 7).unwrap(), 7);
+        assert!(Rng::int_between(i64::MIN, i64::MAX).is_ok());
+        assert!(Rng::int_between(i64::MIN, i64::MIN + 1).unwrap() <= i64::MIN + 1);
+        assert!(matches!(Rng::int_between(2, 1), Err(Error::InvalidRange)));
+
+        // Every value of a small range shows up
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            seen[Rng::_int_between(&mut generator, 0, 5).unwrap() as usize] = true;
+        }
+        assert!(seen.iter().all(|&seen| seen));
+
+        for _ in 0..1000 {
+            let value = Rng::float();
+            assert!((0.0..1.0).contains(&value));
+            let value = Rng::float_between(-2.5, 2.5).unwrap();
+            assert!((-2.5..2.5).contains(&value));
+        }
+        assert_eq!(Rng::float_between(1.5, 1.5).unwrap(), 1.5);
+        assert!(Rng::float_between(f64::NAN, 1.0).is_err());
+        assert!(Rng::float_between(-f64::MAX, f64::MAX).is_err());
+        assert!(Rng::float_between(2.0, 1.0).is_err());
+
+        assert!(!Rng::bool(Some(0.0)).unwrap());
+        assert!(Rng::bool(Some(1.0)).unwrap());
+        assert!(Rng::bool(None).is_ok());
+        assert!(matches!(
+            Rng::bool(Some(1.5)),
+            Err(Error::InvalidProbability(_))
+        ));
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut generator = rand::rng();
+        let mut items: Vec<u32> = (0..10).collect();
+        Rng::_sample(&mut generator, &mut items, 10).unwrap();
+        let mut sorted = items.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..10).collect::<Vec<_>>());
+
+        Rng::_sample(&mut generator, &mut items, 3).unwrap();
+        assert_eq!(items.len(), 10);
+        let mut drawn = items[..3].to_vec();
+        drawn.sort_unstable();
+        drawn.dedup();
+        assert_eq!(drawn.len(), 3);
+
+        assert!(Rng::_sample(&mut generator, &mut items, 0).is_ok());
+        assert!(matches!(
+            Rng::_sample(&mut generator, &mut items, 11),
+            Err(Error::SampleTooLarge(11, 10))
+        ));
+
+        // Each item reaches the front about equally often
+        let mut firsts = [0u32; 4];
+        for _ in 0..4000 {
+            let mut items = [0, 1, 2, 3];
+            Rng::_sample(&mut generator, &mut items, 4).unwrap();
+            firsts[items[0]] += 1;
+        }
+        assert!(firsts.iter().all(|&count| count > 800), "{firsts:?}");
+    }
+
+    #[test]
+    fn test_weighted_index() {
+        let mut generator = rand::rng();
+        let mut counts = [0u32; 3];
+        for _ in 0..4000 {
+            counts[Rng::_weighted_index(&mut generator, &[1, 0, 3]).unwrap()] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        assert!((800..1200).contains(&counts[0]), "{counts:?}");
+
+        assert_eq!(
+            Rng::_weighted_index(&mut generator, &[0, u64::MAX]).unwrap(),
+            1
+        );
+        assert!(matches!(
+            Rng::_weighted_index(&mut generator, &[0, 0]),
+            Err(Error::WeightError(_))
+        ));
+        assert!(matches!(
+            Rng::_weighted_index(&mut generator, &[u64::MAX, 1]),
+            Err(Error::WeightError(_))
+        ));
+        assert!(Rng::_weighted_index(&mut generator, &[]).is_err());
+    }
+
+    #[test]
+    fn test_numeric_code() {
+        let mut generator = rand::rng();
+        for digits in [1, 6, 19] {
+            let code = Rng::_numeric_code(&mut generator, digits, false).unwrap();
+            assert_eq!(code.len(), digits);
+            assert!(code.bytes().all(|c| c.is_ascii_digit()), "{code}");
+        }
+        assert!(matches!(
+            Rng::numeric_code(0, None),
+            Err(Error::InvalidDigits(0))
+        ));
+        assert!(Rng::numeric_code(20, None).is_err());
+
+        for code in ["000000", "123456", "987654", "789"] {
+            assert!(is_trivial_code(code), "{code}");
+        }
+        for code in ["049731", "123457", "11", "890123"] {
+            assert!(!is_trivial_code(code), "{code}");
+        }
+        for _ in 0..1000 {
+            let code = Rng::_numeric_code(&mut generator, 3, true).unwrap();
+            assert!(!is_trivial_code(&code), "{code}");
+        }
+        // Single digits have no trivial form to exclude
+        assert!(Rng::numeric_code(1, Some(true)).is_ok());
+
+        assert!(Rng::code_equals("049731", "049731"));
+        assert!(!Rng::code_equals("049731", "049732"));
+        assert!(!Rng::code_equals("049731", "04973"));
+    }
+
+    #[test]
+    fn test_bytes_to_file() -> crate::TestResult {
+        let path = std::env::temp_dir().join(format!("hardened-key-{}", Rng::alphanumeric(12)));
+        let mut generator = rand::rng();
+        Rng::_bytes_to_file(&mut generator, &path, 10_000, 0o600)?;
+        assert_eq!(std::fs::read(&path)?.len(), 10_000);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(
+                std::fs::metadata(&path)?.permissions().mode() & 0o777,
+                0o600
+            );
+        }
+
+        // Existing files are never overwritten
+        assert!(matches!(
+            Rng::_bytes_to_file(&mut generator, &path, 32, 0o600),
+            Err(Error::FileWrite(_))
+        ));
+        assert_eq!(std::fs::read(&path)?.len(), 10_000);
+        std::fs::remove_file(&path)?;
+
+        assert!(matches!(
+            Rng::_bytes_to_file(&mut generator, &path, 32, 0o4600),
+            Err(Error::InvalidFileMode(0o4600))
+        ));
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_seeded() {
+        let mut first = Rng::seeded("test suite");
+        let mut second = SeededRng::__construct("test suite");
+        assert!(!first.is_cryptographic());
+        assert_eq!(
+            first.token(16, None, None).unwrap(),
+            second.token(16, None, None).unwrap()
+        );
+        assert_eq!(
+            first.password(12, None).unwrap(),
+            second.password(12, None).unwrap()
+        );
+        assert_eq!(first.uuid4(), second.uuid4());
+        assert_eq!(
+            first.int_between(0, 1_000_000).unwrap(),
+            second.int_between(0, 1_000_000).unwrap()
+        );
+
+        let mut other = SeededRng::__construct("other seed");
+        assert_ne!(
+            SeededRng::__construct("test suite").alphanumeric(32),
+            other.alphanumeric(32)
+        );
+    }
+
     #[test]
     fn php_example() -> crate::TestResult {
         run_php_example("rng")?;
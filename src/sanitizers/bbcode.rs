@@ -0,0 +1,438 @@
+use crate::hostname_policy::HostnamePolicy;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::types::ZendClassObject;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use std::collections::HashSet;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+use thiserror::Error;
+use url::Url;
+
+// Error codes for BBCode Sanitizer errors: 4800-4899
+pub mod error_codes {
+    pub const UNKNOWN_TAG: i32 = 4800;
+}
+
+/// Errors that can occur when configuring the BBCode sanitizer.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown BBCode tag: {0}")]
+    UnknownTag(String),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::UnknownTag(_) => error_codes::UNKNOWN_TAG,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for BBCode sanitizer operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Deepest nesting of tags; further opening tags are kept as text.
+const MAX_DEPTH: usize = 32;
+
+/// `rel` of generated links: user content, not endorsed, no `window.opener`.
+const LINK_REL: &str = "nofollow ugc noopener";
+
+/// Supported BBCode tags.
+#[derive(EnumString, EnumIter, Display, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Tag {
+    B,
+    I,
+    U,
+    S,
+    Url,
+    Img,
+    Quote,
+    Code,
+}
+
+impl Tag {
+    /// HTML emitted for a simple formatting tag: opening and closing.
+    fn html(self) -> (&'static str, &'static str) {
+        match self {
+            Tag::B => ("<strong>", "</strong>"),
+            Tag::I => ("<em>", "</em>"),
+            Tag::U => ("<u>", "</u>"),
+            Tag::S => ("<s>", "</s>"),
+            Tag::Quote => ("<blockquote>", "</blockquote>"),
+            Tag::Url => ("", "</a>"),
+            Tag::Img | Tag::Code => ("", ""),
+        }
+    }
+}
+
+/// A tag as written: `[name]`, `[name=value]` or `[/name]`.
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Open(Tag, Option<&'a str>),
+    Close(Tag),
+}
+
+/// Parses a tag at the start of `input` (which starts with `[`), returning it and its length.
+fn parse_token(input: &str) -> Option<(Token<'_>, usize)> {
+    let end = input.find(']')?;
+    let inner = &input[1..end];
+    if inner.contains(['[', '\n']) {
+        return None;
+    }
+    let token = if let Some(name) = inner.strip_prefix('/') {
+        Token::Close(Tag::from_str(name).ok()?)
+    } else {
+        let (name, value) = match inner.split_once('=') {
+            Some((name, value)) => {
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|value| value.strip_suffix('"'))
+                    .unwrap_or(value);
+                (name, Some(value))
+            }
+            None => (inner, None),
+        };
+        Token::Open(Tag::from_str(name).ok()?, value)
+    };
+    Some((token, end + 1))
+}
+
+/// Finds `[/name]` in `input`, ignoring ASCII case, returning the content before it and the
+/// input after it.
+fn split_at_close(input: &str, tag: Tag) -> Option<(&str, &str)> {
+    let close = format!("[/{tag}]");
+    let start = input
+        .as_bytes()
+        .windows(close.len())
+        .position(|window| window.eq_ignore_ascii_case(close.as_bytes()))?;
+    Some((&input[..start], &input[start + close.len()..]))
+}
+
+/// Appends HTML-escaped text.
+fn push_escaped(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// Appends escaped text with line breaks as `<br>`.
+fn push_text(out: &mut String, text: &str) {
+    for (index, line) in text.split('\n').enumerate() {
+        if index > 0 {
+            out.push_str("<br>\n");
+        }
+        push_escaped(out, line);
+    }
+}
+
+/// Validates a link or image URL: absolute `http(s)` without credentials, allowed by `policy`.
+fn check_url(raw: &str, policy: Option<&HostnamePolicy>) -> Option<Url> {
+    let raw = raw.trim();
+    if raw
+        .chars()
+        .any(|c| c.is_control() || c.is_whitespace() || c == '\\')
+    {
+        return None;
+    }
+    let url = Url::parse(raw).ok()?;
+    if !matches!(url.scheme(), "http" | "https")
+        || !url.username().is_empty()
+        || url.password().is_some()
+    {
+        return None;
+    }
+    if let Some(policy) = policy {
+        policy._check_url(&url).ok()?;
+    }
+    Some(url)
+}
+
+/// Appends `<a href="...">`.
+fn push_link_open(out: &mut String, url: &Url) {
+    out.push_str("<a href=\"");
+    push_escaped(out, url.as_str());
+    out.push_str("\" rel=\"");
+    out.push_str(LINK_REL);
+    out.push_str("\">");
+}
+
+/// An open tag and the HTML that closes it.
+struct Frame {
+    tag: Tag,
+    close: &'static str,
+}
+
+/// BBCode to safe HTML for content that cannot be migrated away from BBCode.
+///
+/// Every piece of output is generated from escaped text and a fixed set of elements, so the
+/// result needs no further sanitization. Tags that are unknown, disabled, unbalanced or carry
+/// a rejected URL are shown as text.
+#[php_class]
+#[php(name = "Hardened\\Sanitizers\\BbcodeSanitizer")]
+pub struct BbcodeSanitizer {
+    tags: HashSet<Tag>,
+}
+
+impl BbcodeSanitizer {
+    /// Renders BBCode to HTML.
+    pub(crate) fn render(&self, bbcode: &str, policy: Option<&HostnamePolicy>) -> String {
+        let input = bbcode.replace("\r\n", "\n");
+        let mut out = String::with_capacity(input.len() * 5 / 4);
+        let mut stack: Vec<Frame> = Vec::new();
+        let mut rest = input.as_str();
+        while let Some(start) = rest.find('[') {
+            push_text(&mut out, &rest[..start]);
+            rest = &rest[start..];
+            let Some((token, len)) = parse_token(rest).filter(|(token, _)| match token {
+                Token::Open(tag, _) | Token::Close(tag) => self.tags.contains(tag),
+            }) else {
+                push_text(&mut out, "[");
+                rest = &rest[1..];
+                continue;
+            };
+            let (literal, after) = rest.split_at(len);
+            match token {
+                Token::Open(Tag::Code, _) => {
+                    if let Some((content, after)) = split_at_close(after, Tag::Code) {
+                        out.push_str("<pre><code>");
+                        push_escaped(&mut out, content.strip_prefix('\n').unwrap_or(content));
+                        out.push_str("</code></pre>");
+                        rest = after;
+                        continue;
+                    }
+                }
+                Token::Open(Tag::Img, _) => {
+                    if let Some((content, after)) = split_at_close(after, Tag::Img) {
+                        match check_url(content, policy) {
+                            Some(url) => {
+                                out.push_str("<img src=\"");
+                                push_escaped(&mut out, url.as_str());
+                                out.push_str("\" alt=\"\">");
+                            }
+                            None => push_text(&mut out, content),
+                        }
+                        rest = after;
+                        continue;
+                    }
+                }
+                Token::Open(Tag::Url, None) if !stack.iter().any(|f| f.tag == Tag::Url) => {
+                    if let Some((content, after)) = split_at_close(after, Tag::Url) {
+                        if let Some(url) = check_url(content, policy) {
+                            push_link_open(&mut out, &url);
+                            push_escaped(&mut out, content.trim());
+                            out.push_str("</a>");
+                        } else {
+                            push_text(&mut out, content);
+                        }
+                        rest = after;
+                        continue;
+                    }
+                }
+                Token::Open(tag, value)
+                    if stack.len() < MAX_DEPTH
+                        && (tag != Tag::Url || !stack.iter().any(|f| f.tag == Tag::Url)) =>
+                {
+                    let (open, mut close) = tag.html();
+                    if tag == Tag::Url {
+                        // A rejected URL keeps the label as plain text
+                        match value.and_then(|value| check_url(value, policy)) {
+                            Some(url) => push_link_open(&mut out, &url),
+                            None => close = "",
+                        }
+                    } else {
+                        out.push_str(open);
+                        if let (Tag::Quote, Some(author)) = (tag, value) {
+                            out.push_str("<cite>");
+                            push_escaped(&mut out, author);
+                            out.push_str("</cite>");
+                        }
+                    }
+                    stack.push(Frame { tag, close });
+                    rest = after;
+                    continue;
+                }
+                Token::Close(tag) => {
+                    if let Some(index) = stack.iter().rposition(|frame| frame.tag == tag) {
+                        // Closing an outer tag closes the ones opened inside it
+                        for frame in stack.drain(index..).rev() {
+                            out.push_str(frame.close);
+                        }
+                        rest = after;
+                        continue;
+                    }
+                }
+                Token::Open(..) => {}
+            }
+            push_text(&mut out, literal);
+            rest = after;
+        }
+        push_text(&mut out, rest);
+        for frame in stack.into_iter().rev() {
+            out.push_str(frame.close);
+        }
+        out
+    }
+}
+
+#[php_impl]
+impl BbcodeSanitizer {
+    /// Constructs a sanitizer with every supported tag enabled: `b`, `i`, `u`, `s`, `url`,
+    /// `img`, `quote` and `code`.
+    ///
+    /// # Returns
+    /// - BbcodeSanitizer A new sanitizer instance.
+    fn __construct() -> Self {
+        Self {
+            tags: Tag::iter().collect(),
+        }
+    }
+
+    /// Sets the enabled tags; other tags are shown as text.
+    ///
+    /// # Parameters
+    /// - `tags`: `string[]` e.g. `['b', 'i', 'url', 'quote']`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if a tag is not supported.
+    fn tags(
+        self_: &mut ZendClassObject<BbcodeSanitizer>,
+        tags: Vec<String>,
+    ) -> Result<&mut ZendClassObject<BbcodeSanitizer>> {
+        self_.tags = tags
+            .iter()
+            .map(|tag| Tag::from_str(tag).map_err(|_| Error::UnknownTag(tag.clone())))
+            .collect::<Result<_>>()?;
+        Ok(self_)
+    }
+
+    /// Converts BBCode to HTML.
+    ///
+    /// `[url]` and `[img]` accept only absolute `http`/`https` URLs without credentials, which
+    /// must also pass `urlPolicy` if given. Links get `rel="nofollow ugc noopener"`. Line
+    /// breaks become `<br>`, except inside `[code]`.
+    ///
+    /// # Parameters
+    /// - `bbcode`: `string` Untrusted BBCode.
+    /// - `urlPolicy`: `?HostnamePolicy` Hosts, schemes and ports allowed for links and images.
+    ///
+    /// # Returns
+    /// - `string` Safe HTML.
+    fn clean(&self, bbcode: &str, url_policy: Option<&HostnamePolicy>) -> String {
+        self.render(bbcode, url_policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BbcodeSanitizer, Tag, Token, parse_token};
+    use crate::hostname_policy::HostnamePolicy;
+
+    fn render(bbcode: &str) -> String {
+        BbcodeSanitizer::__construct().render(bbcode, None)
+    }
+
+    #[test]
+    fn test_parse_token() {
+        assert_eq!(parse_token("[b]x"), Some((Token::Open(Tag::B, None), 3)));
+        assert_eq!(
+            parse_token("[QUOTE=\"Bob\"]"),
+            Some((Token::Open(Tag::Quote, Some("Bob")), 13))
+        );
+        assert_eq!(parse_token("[/Url]"), Some((Token::Close(Tag::Url), 6)));
+        assert_eq!(parse_token("[script]"), None);
+        assert_eq!(parse_token("[b"), None);
+    }
+
+    #[test]
+    fn test_formatting() {
+        assert_eq!(
+            render("[b]bold [i]both[/i][/b]\n<script>"),
+            "<strong>bold <em>both</em></strong><br>\n&lt;script&gt;"
+        );
+        // Misnested and unclosed tags are balanced
+        assert_eq!(
+            render("[b][i]x[/b]y[/i][u]z"),
+            "<strong><em>x</em></strong>y[/i]<u>z</u>"
+        );
+        assert_eq!(render("[size=9]x[/size] [b"), "[size=9]x[/size] [b");
+        assert_eq!(
+            render("[quote=\"A<b>\"]hi[/quote]"),
+            "<blockquote><cite>A&lt;b&gt;</cite>hi</blockquote>"
+        );
+        assert_eq!(
+            render("[code]\n[b]<x>[/b]\n[/CODE]"),
+            "<pre><code>[b]&lt;x&gt;[/b]\n</code></pre>"
+        );
+
+        let deep = "[quote]".repeat(40);
+        assert_eq!(render(&deep).matches("<blockquote>").count(), 32);
+    }
+
+    #[test]
+    fn test_urls() {
+        assert_eq!(
+            render("[url]https://example.com/a?b=1&c=2[/url]"),
+            "<a href=\"https://example.com/a?b=1&amp;c=2\" rel=\"nofollow ugc noopener\">https://example.com/a?b=1&amp;c=2</a>"
+        );
+        assert_eq!(
+            render("[url=https://example.com][b]x[/b][/url]"),
+            "<a href=\"https://example.com/\" rel=\"nofollow ugc noopener\"><strong>x</strong></a>"
+        );
+        assert_eq!(render("[url=javascript:alert(1)]x[/url]"), "x");
+        assert_eq!(
+            render("[url]javascript:alert(1)[/url]"),
+            "javascript:alert(1)"
+        );
+        assert_eq!(render("[url=https://a:b@example.com]x[/url]"), "x");
+        assert_eq!(
+            render("[img]https://example.com/a.png\" onerror=\"x[/img]"),
+            "https://example.com/a.png&quot; onerror=&quot;x"
+        );
+        assert_eq!(
+            render("[img]https://example.com/a.png[/img]"),
+            "<img src=\"https://example.com/a.png\" alt=\"\">"
+        );
+
+        let mut policy = HostnamePolicy::default();
+        policy.allow_hosts(vec!["example.com".into()]).unwrap();
+        let sanitizer = BbcodeSanitizer::__construct();
+        assert_eq!(
+            sanitizer.render("[url=https://evil.test/]x[/url]", Some(&policy)),
+            "x"
+        );
+        assert!(
+            sanitizer
+                .render("[url=https://example.com/]x[/url]", Some(&policy))
+                .starts_with("<a ")
+        );
+    }
+
+    #[test]
+    fn test_disabled_tags() {
+        let mut sanitizer = BbcodeSanitizer::__construct();
+        sanitizer.tags = [Tag::B].into();
+        assert_eq!(
+            sanitizer.render("[b]x[/b] [img]https://example.com/a.png[/img]", None),
+            "<strong>x</strong> [img]https://example.com/a.png[/img]"
+        );
+    }
+}
@@ -0,0 +1,1286 @@
+//! Sanitization of whole user-supplied stylesheets, e.g. theme customization.
+//!
+//! The input is tokenized as CSS Syntax Level 3 does, so escapes and comments are decoded
+//! before anything is checked (`expr\65ssion(`, `u\rl(`, `-moz-/**/binding` are seen for what
+//! they are), and the output is serialized from the tokens rather than copied from the input.
+
+use crate::hostname_policy::HostnamePolicy;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::types::ZendClassObject;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use std::collections::HashSet;
+use std::fmt::Write;
+use thiserror::Error;
+use url::Url;
+
+// Error codes for CSS Sanitizer errors: 4900-4999
+pub mod error_codes {
+    pub const TOO_LARGE: i32 = 4900;
+    pub const TOO_MANY_RULES: i32 = 4901;
+}
+
+/// Errors that can occur during CSS sanitization.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Stylesheet is {0} bytes, more than the limit of {1}")]
+    TooLarge(usize, usize),
+
+    #[error("Stylesheet has more than {0} rules")]
+    TooManyRules(usize),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::TooLarge(..) => error_codes::TOO_LARGE,
+            Error::TooManyRules(_) => error_codes::TOO_MANY_RULES,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for CSS sanitizer operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Properties allowed by default: colors, typography, box model and layout within the
+/// element's own flow. `position`, `z-index` and friends are left out, as they let a theme
+/// overlay the rest of the page.
+const DEFAULT_PROPERTIES: &[&str] = &[
+    "align-content",
+    "align-items",
+    "align-self",
+    "animation",
+    "animation-delay",
+    "animation-direction",
+    "animation-duration",
+    "animation-fill-mode",
+    "animation-iteration-count",
+    "animation-name",
+    "animation-timing-function",
+    "aspect-ratio",
+    "background",
+    "background-attachment",
+    "background-clip",
+    "background-color",
+    "background-image",
+    "background-origin",
+    "background-position",
+    "background-repeat",
+    "background-size",
+    "border",
+    "border-bottom",
+    "border-bottom-color",
+    "border-bottom-left-radius",
+    "border-bottom-right-radius",
+    "border-bottom-style",
+    "border-bottom-width",
+    "border-collapse",
+    "border-color",
+    "border-left",
+    "border-left-color",
+    "border-left-style",
+    "border-left-width",
+    "border-radius",
+    "border-right",
+    "border-right-color",
+    "border-right-style",
+    "border-right-width",
+    "border-spacing",
+    "border-style",
+    "border-top",
+    "border-top-color",
+    "border-top-left-radius",
+    "border-top-right-radius",
+    "border-top-style",
+    "border-top-width",
+    "border-width",
+    "box-shadow",
+    "box-sizing",
+    "caption-side",
+    "clear",
+    "color",
+    "column-count",
+    "column-gap",
+    "column-width",
+    "columns",
+    "content",
+    "cursor",
+    "display",
+    "filter",
+    "flex",
+    "flex-basis",
+    "flex-direction",
+    "flex-flow",
+    "flex-grow",
+    "flex-shrink",
+    "flex-wrap",
+    "float",
+    "font",
+    "font-family",
+    "font-size",
+    "font-stretch",
+    "font-style",
+    "font-variant",
+    "font-weight",
+    "gap",
+    "grid",
+    "grid-area",
+    "grid-auto-columns",
+    "grid-auto-flow",
+    "grid-auto-rows",
+    "grid-column",
+    "grid-gap",
+    "grid-row",
+    "grid-template",
+    "grid-template-areas",
+    "grid-template-columns",
+    "grid-template-rows",
+    "height",
+    "justify-content",
+    "justify-items",
+    "justify-self",
+    "letter-spacing",
+    "line-height",
+    "list-style",
+    "list-style-position",
+    "list-style-type",
+    "margin",
+    "margin-bottom",
+    "margin-left",
+    "margin-right",
+    "margin-top",
+    "max-height",
+    "max-width",
+    "min-height",
+    "min-width",
+    "object-fit",
+    "object-position",
+    "opacity",
+    "order",
+    "outline",
+    "outline-color",
+    "outline-offset",
+    "outline-style",
+    "outline-width",
+    "overflow",
+    "overflow-wrap",
+    "overflow-x",
+    "overflow-y",
+    "padding",
+    "padding-bottom",
+    "padding-left",
+    "padding-right",
+    "padding-top",
+    "row-gap",
+    "table-layout",
+    "text-align",
+    "text-decoration",
+    "text-decoration-color",
+    "text-decoration-line",
+    "text-decoration-style",
+    "text-indent",
+    "text-overflow",
+    "text-shadow",
+    "text-transform",
+    "transform",
+    "transform-origin",
+    "transition",
+    "transition-delay",
+    "transition-duration",
+    "transition-property",
+    "transition-timing-function",
+    "vertical-align",
+    "visibility",
+    "white-space",
+    "width",
+    "word-break",
+    "word-spacing",
+    "word-wrap",
+];
+
+/// Descriptors allowed inside `@font-face`.
+const FONT_FACE_DESCRIPTORS: &[&str] = &[
+    "font-display",
+    "font-family",
+    "font-stretch",
+    "font-style",
+    "font-weight",
+    "src",
+    "unicode-range",
+];
+
+/// Properties dropped even if allowed: both attach script to elements in old browsers.
+const BLOCKED_PROPERTIES: &[&str] = &["behavior", "-moz-binding"];
+
+/// Functions allowed in values, besides `url()` and `src()`, which are checked separately.
+/// Everything that can fetch a resource by other means (`image-set()`, `image()`,
+/// `cross-fade()`, `element()`) or run script (`expression()`) is absent.
+const ALLOWED_FUNCTIONS: &[&str] = &[
+    "blur",
+    "brightness",
+    "calc",
+    "circle",
+    "clamp",
+    "color",
+    "color-mix",
+    "conic-gradient",
+    "contrast",
+    "counter",
+    "counters",
+    "cubic-bezier",
+    "drop-shadow",
+    "ellipse",
+    "fit-content",
+    "format",
+    "grayscale",
+    "hsl",
+    "hsla",
+    "hue-rotate",
+    "hwb",
+    "inset",
+    "invert",
+    "lab",
+    "lch",
+    "linear-gradient",
+    "local",
+    "matrix",
+    "matrix3d",
+    "max",
+    "min",
+    "minmax",
+    "oklab",
+    "oklch",
+    "opacity",
+    "perspective",
+    "polygon",
+    "radial-gradient",
+    "repeat",
+    "repeating-conic-gradient",
+    "repeating-linear-gradient",
+    "repeating-radial-gradient",
+    "rgb",
+    "rgba",
+    "rotate",
+    "rotate3d",
+    "rotatex",
+    "rotatey",
+    "rotatez",
+    "saturate",
+    "scale",
+    "scale3d",
+    "scalex",
+    "scaley",
+    "sepia",
+    "skew",
+    "skewx",
+    "skewy",
+    "steps",
+    "translate",
+    "translate3d",
+    "translatex",
+    "translatey",
+    "var",
+];
+
+const VENDOR_PREFIXES: &[&str] = &["-webkit-", "-moz-", "-ms-", "-o-"];
+
+/// How deep `@media`, `@supports` and `@keyframes` blocks may nest.
+const MAX_NESTING: usize = 8;
+
+/// A CSS token; names and strings hold their decoded values.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Function(String),
+    AtKeyword(String),
+    Hash(String),
+    String(String),
+    /// Unquoted `url(...)`
+    Url(String),
+    BadString,
+    BadUrl,
+    /// Number, percentage or dimension, already serialized
+    Numeric(String),
+    Whitespace,
+    Delim(char),
+    Colon,
+    Semicolon,
+    Comma,
+    OpenParen,
+    CloseParen,
+    OpenBracket,
+    CloseBracket,
+    OpenBrace,
+    CloseBrace,
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+fn is_name(c: char) -> bool {
+    is_name_start(c) || c.is_ascii_digit() || c == '-'
+}
+
+fn is_valid_escape(first: Option<char>, second: Option<char>) -> bool {
+    first == Some('\\') && second != Some('\n')
+}
+
+fn starts_ident(first: Option<char>, second: Option<char>, third: Option<char>) -> bool {
+    match first {
+        Some('-') => {
+            second.is_some_and(|c| is_name_start(c) || c == '-') || is_valid_escape(second, third)
+        }
+        Some(c) if is_name_start(c) => true,
+        _ => is_valid_escape(first, second),
+    }
+}
+
+fn starts_number(first: Option<char>, second: Option<char>, third: Option<char>) -> bool {
+    let digit = |c: Option<char>| c.is_some_and(|c| c.is_ascii_digit());
+    match first {
+        Some('+' | '-') => digit(second) || (second == Some('.') && digit(third)),
+        Some('.') => digit(second),
+        c => digit(c),
+    }
+}
+
+/// Tokenizer following CSS Syntax Level 3; comments become whitespace.
+struct Tokenizer {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Tokenizer {
+    fn new(css: &str) -> Self {
+        let css = css
+            .replace("\r\n", "\n")
+            .replace(['\r', '\x0c'], "\n")
+            .replace('\0', "\u{FFFD}");
+        Self {
+            chars: css.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    /// Consumes an escape whose backslash has already been consumed.
+    fn consume_escape(&mut self) -> char {
+        let Some(c) = self.peek(0) else {
+            return '\u{FFFD}';
+        };
+        self.pos += 1;
+        if !c.is_ascii_hexdigit() {
+            return c;
+        }
+        let mut value = c.to_digit(16).unwrap_or(0);
+        for _ in 0..5 {
+            match self.peek(0).and_then(|c| c.to_digit(16)) {
+                Some(digit) => {
+                    value = value * 16 + digit;
+                    self.pos += 1;
+                }
+                None => break,
+            }
+        }
+        if matches!(self.peek(0), Some(' ' | '\t' | '\n')) {
+            self.pos += 1;
+        }
+        char::from_u32(value)
+            .filter(|c| *c != '\0')
+            .unwrap_or('\u{FFFD}')
+    }
+
+    fn consume_name(&mut self) -> String {
+        let mut name = String::new();
+        loop {
+            match self.peek(0) {
+                Some(c) if is_name(c) => {
+                    name.push(c);
+                    self.pos += 1;
+                }
+                first if is_valid_escape(first, self.peek(1)) => {
+                    self.pos += 1;
+                    name.push(self.consume_escape());
+                }
+                _ => return name,
+            }
+        }
+    }
+
+    fn consume_numeric(&mut self) -> Token {
+        let start = self.pos;
+        let digits = |this: &mut Self| {
+            while this.peek(0).is_some_and(|c| c.is_ascii_digit()) {
+                this.pos += 1;
+            }
+        };
+        if matches!(self.peek(0), Some('+' | '-')) {
+            self.pos += 1;
+        }
+        digits(self);
+        if self.peek(0) == Some('.') && self.peek(1).is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+            digits(self);
+        }
+        if matches!(self.peek(0), Some('e' | 'E')) {
+            let sign = usize::from(matches!(self.peek(1), Some('+' | '-')));
+            if self.peek(1 + sign).is_some_and(|c| c.is_ascii_digit()) {
+                self.pos += 1 + sign;
+                digits(self);
+            }
+        }
+        let mut number: String = self.chars[start..self.pos].iter().collect();
+        if starts_ident(self.peek(0), self.peek(1), self.peek(2)) {
+            let unit = self.consume_name();
+            push_name(&mut number, &unit, false);
+        } else if self.peek(0) == Some('%') {
+            self.pos += 1;
+            number.push('%');
+        }
+        Token::Numeric(number)
+    }
+
+    fn consume_ident_like(&mut self) -> Token {
+        let name = self.consume_name();
+        if self.peek(0) != Some('(') {
+            return Token::Ident(name);
+        }
+        self.pos += 1;
+        if !name.eq_ignore_ascii_case("url") {
+            return Token::Function(name);
+        }
+        while matches!(self.peek(0), Some(' ' | '\t' | '\n')) {
+            self.pos += 1;
+        }
+        if matches!(self.peek(0), Some('"' | '\'')) {
+            return Token::Function(name);
+        }
+        self.consume_url()
+    }
+
+    /// Consumes an unquoted `url(...)` after the opening parenthesis and whitespace.
+    fn consume_url(&mut self) -> Token {
+        let mut url = String::new();
+        loop {
+            let Some(c) = self.peek(0) else {
+                return Token::Url(url);
+            };
+            self.pos += 1;
+            match c {
+                ')' => return Token::Url(url),
+                ' ' | '\t' | '\n' => {
+                    while matches!(self.peek(0), Some(' ' | '\t' | '\n')) {
+                        self.pos += 1;
+                    }
+                    match self.peek(0) {
+                        None => return Token::Url(url),
+                        Some(')') => {
+                            self.pos += 1;
+                            return Token::Url(url);
+                        }
+                        Some(_) => break,
+                    }
+                }
+                '\\' if is_valid_escape(Some(c), self.peek(0)) => {
+                    url.push(self.consume_escape());
+                }
+                '"' | '\'' | '(' | '\\' => break,
+                '\0'..='\x08' | '\x0b' | '\x0e'..='\x1f' | '\x7f' => break,
+                c => url.push(c),
+            }
+        }
+        // Skip the remnants of a bad URL
+        while let Some(c) = self.peek(0) {
+            self.pos += 1;
+            match c {
+                ')' => break,
+                '\\' if is_valid_escape(Some(c), self.peek(0)) => {
+                    self.consume_escape();
+                }
+                _ => {}
+            }
+        }
+        Token::BadUrl
+    }
+
+    fn consume_string(&mut self, quote: char) -> Token {
+        let mut string = String::new();
+        while let Some(c) = self.peek(0) {
+            match c {
+                c if c == quote => {
+                    self.pos += 1;
+                    return Token::String(string);
+                }
+                '\n' => return Token::BadString,
+                '\\' => {
+                    self.pos += 1;
+                    match self.peek(0) {
+                        None => {}
+                        Some('\n') => self.pos += 1,
+                        Some(_) => string.push(self.consume_escape()),
+                    }
+                }
+                c => {
+                    self.pos += 1;
+                    string.push(c);
+                }
+            }
+        }
+        Token::String(string)
+    }
+
+    fn next_token(&mut self) -> Option<Token> {
+        let c = self.peek(0)?;
+        let (next, third) = (self.peek(1), self.peek(2));
+        if c == '/' && next == Some('*') {
+            self.pos += 2;
+            while self.pos < self.chars.len()
+                && !(self.peek(0) == Some('*') && self.peek(1) == Some('/'))
+            {
+                self.pos += 1;
+            }
+            self.pos = (self.pos + 2).min(self.chars.len());
+            return Some(Token::Whitespace);
+        }
+        let token = match c {
+            ' ' | '\t' | '\n' => {
+                while matches!(self.peek(0), Some(' ' | '\t' | '\n')) {
+                    self.pos += 1;
+                }
+                return Some(Token::Whitespace);
+            }
+            '"' | '\'' => {
+                self.pos += 1;
+                return Some(self.consume_string(c));
+            }
+            '#' if next.is_some_and(is_name) || is_valid_escape(next, third) => {
+                self.pos += 1;
+                return Some(Token::Hash(self.consume_name()));
+            }
+            '+' | '-' | '.' if starts_number(Some(c), next, third) => {
+                return Some(self.consume_numeric());
+            }
+            c if c.is_ascii_digit() => return Some(self.consume_numeric()),
+            '-' if next == Some('-') && third == Some('>') => {
+                self.pos += 3;
+                return Some(Token::Whitespace);
+            }
+            '<' if next == Some('!') && third == Some('-') && self.peek(3) == Some('-') => {
+                self.pos += 4;
+                return Some(Token::Whitespace);
+            }
+            '-' | '\\' if starts_ident(Some(c), next, third) => {
+                return Some(self.consume_ident_like());
+            }
+            c if is_name_start(c) => return Some(self.consume_ident_like()),
+            '@' if starts_ident(next, third, self.peek(3)) => {
+                self.pos += 1;
+                return Some(Token::AtKeyword(self.consume_name()));
+            }
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            '[' => Token::OpenBracket,
+            ']' => Token::CloseBracket,
+            '{' => Token::OpenBrace,
+            '}' => Token::CloseBrace,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            ';' => Token::Semicolon,
+            c => Token::Delim(c),
+        };
+        self.pos += 1;
+        Some(token)
+    }
+}
+
+fn tokenize(css: &str) -> Vec<Token> {
+    let mut tokenizer = Tokenizer::new(css);
+    std::iter::from_fn(|| tokenizer.next_token()).collect()
+}
+
+/// Appends a name, escaping whatever would not read back as the same identifier (or, for
+/// `#hash` names, the same hash).
+fn push_name(out: &mut String, name: &str, hash: bool) {
+    for (index, c) in name.chars().enumerate() {
+        let leading_digit =
+            !hash && c.is_ascii_digit() && (index == 0 || (index == 1 && name.starts_with('-')));
+        if is_name(c) && !leading_digit {
+            out.push(c);
+        } else {
+            let _ = write!(out, "\\{:x} ", c as u32);
+        }
+    }
+}
+
+/// Appends a double-quoted string. `<` and `>` are escaped as well, so the stylesheet cannot
+/// close the `<style>` element it is embedded in.
+fn push_string(out: &mut String, string: &str) {
+    out.push('"');
+    for c in string.chars() {
+        match c {
+            '"' | '\\' | '<' | '>' | '\0'..='\x1f' | '\x7f' => {
+                let _ = write!(out, "\\{:x} ", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Serializes tokens, collapsing whitespace and trimming it at both ends.
+fn serialize(tokens: &[Token]) -> String {
+    let mut out = String::new();
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Ident(name) => push_name(&mut out, name, false),
+            Token::Function(name) => {
+                push_name(&mut out, name, false);
+                out.push('(');
+            }
+            Token::AtKeyword(name) => {
+                out.push('@');
+                push_name(&mut out, name, false);
+            }
+            Token::Hash(name) => {
+                out.push('#');
+                push_name(&mut out, name, true);
+            }
+            Token::String(string) => push_string(&mut out, string),
+            Token::Url(url) => {
+                out.push_str("url(");
+                push_string(&mut out, url);
+                out.push(')');
+            }
+            Token::Numeric(number) => out.push_str(number),
+            Token::Whitespace => {
+                if index > 0
+                    && index + 1 < tokens.len()
+                    && !matches!(tokens[index + 1], Token::Whitespace)
+                    && !out.ends_with(' ')
+                {
+                    out.push(' ');
+                }
+            }
+            Token::Delim(c) => out.push(*c),
+            Token::Colon => out.push(':'),
+            Token::Semicolon => out.push(';'),
+            Token::Comma => out.push(','),
+            Token::OpenParen => out.push('('),
+            Token::CloseParen => out.push(')'),
+            Token::OpenBracket => out.push('['),
+            Token::CloseBracket => out.push(']'),
+            Token::OpenBrace => out.push('{'),
+            Token::CloseBrace => out.push('}'),
+            Token::BadString | Token::BadUrl => {}
+        }
+    }
+    out.trim_end().to_string()
+}
+
+fn trim(mut tokens: &[Token]) -> &[Token] {
+    while let [Token::Whitespace, rest @ ..] = tokens {
+        tokens = rest;
+    }
+    while let [rest @ .., Token::Whitespace] = tokens {
+        tokens = rest;
+    }
+    tokens
+}
+
+/// Tokens that are never kept, wherever they appear.
+fn is_forbidden(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::BadString
+            | Token::BadUrl
+            | Token::AtKeyword(_)
+            | Token::Semicolon
+            | Token::OpenBrace
+            | Token::CloseBrace
+            | Token::Delim('<' | '\\' | '@')
+    )
+}
+
+/// Whether a token starts a `url()` or `src()`.
+fn is_url(token: &Token) -> bool {
+    match token {
+        Token::Url(_) => true,
+        Token::Function(name) => {
+            name.eq_ignore_ascii_case("url") || name.eq_ignore_ascii_case("src")
+        }
+        _ => false,
+    }
+}
+
+/// Whether parentheses and brackets pair up, counting functions as an opening parenthesis.
+fn is_balanced(tokens: &[Token]) -> bool {
+    let mut stack = Vec::new();
+    for token in tokens {
+        match token {
+            Token::Function(_) | Token::OpenParen => stack.push(Token::CloseParen),
+            Token::OpenBracket => stack.push(Token::CloseBracket),
+            Token::CloseParen | Token::CloseBracket if stack.pop().as_ref() != Some(token) => {
+                return false;
+            }
+            _ => {}
+        }
+    }
+    stack.is_empty()
+}
+
+/// Splits a rule off `tokens[start..]`: the prelude, the block if the rule has one, and
+/// where the next rule starts. A top-level `;` ends a rule without a block.
+fn split_rule(tokens: &[Token], start: usize) -> (&[Token], Option<&[Token]>, usize) {
+    let mut nesting = 0usize;
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::Function(_) | Token::OpenParen | Token::OpenBracket => nesting += 1,
+            Token::CloseParen | Token::CloseBracket => nesting = nesting.saturating_sub(1),
+            Token::Semicolon if nesting == 0 => {
+                return (&tokens[start..index], None, index + 1);
+            }
+            Token::OpenBrace => {
+                let mut depth = 0usize;
+                for (end, token) in tokens.iter().enumerate().skip(index + 1) {
+                    match token {
+                        Token::OpenBrace => depth += 1,
+                        Token::CloseBrace if depth == 0 => {
+                            return (
+                                &tokens[start..index],
+                                Some(&tokens[index + 1..end]),
+                                end + 1,
+                            );
+                        }
+                        Token::CloseBrace => depth -= 1,
+                        _ => {}
+                    }
+                }
+                return (
+                    &tokens[start..index],
+                    Some(&tokens[index + 1..]),
+                    tokens.len(),
+                );
+            }
+            _ => {}
+        }
+    }
+    (&tokens[start..], None, tokens.len())
+}
+
+/// Splits a declaration block at top-level semicolons.
+fn split_declarations(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut declarations = Vec::new();
+    let mut nesting = 0usize;
+    let mut start = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Function(_) | Token::OpenParen | Token::OpenBracket | Token::OpenBrace => {
+                nesting += 1;
+            }
+            Token::CloseParen | Token::CloseBracket | Token::CloseBrace => {
+                nesting = nesting.saturating_sub(1);
+            }
+            Token::Semicolon if nesting == 0 => {
+                declarations.push(&tokens[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    declarations.push(&tokens[start..]);
+    declarations
+}
+
+/// Strips a vendor prefix such as `-webkit-`.
+fn unprefixed(name: &str) -> &str {
+    VENDOR_PREFIXES
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(name)
+}
+
+/// Whether a `url()` may stay: an absolute `http`/`https` URL without credentials that
+/// passes the policy. Without a policy, no URL is allowed.
+fn is_allowed_url(raw: &str, policy: Option<&HostnamePolicy>) -> bool {
+    let Some(policy) = policy else {
+        return false;
+    };
+    let raw = raw.trim();
+    if raw.chars().any(|c| c.is_control() || c.is_whitespace()) {
+        return false;
+    }
+    let Ok(url) = Url::parse(raw) else {
+        return false;
+    };
+    matches!(url.scheme(), "http" | "https")
+        && url.username().is_empty()
+        && url.password().is_none()
+        && policy._check_url(&url).is_ok()
+}
+
+/// Stylesheet sanitizer for untrusted CSS such as user themes.
+///
+/// Keeps style rules whose selectors are well-formed, `@media`, `@supports`, `@keyframes` and
+/// `@font-face`; every other at-rule (`@import`, `@charset`, `@namespace`, ...) is removed.
+/// Declarations are kept only if the property is allowed and every value token is safe:
+/// functions come from a fixed allowlist (no `expression()`), and `url()` must pass the URL
+/// policy given to `clean()`.
+#[php_class]
+#[php(name = "Hardened\\Sanitizers\\CssSanitizer")]
+pub struct CssSanitizer {
+    properties: HashSet<String>,
+    max_bytes: usize,
+    max_rules: usize,
+}
+
+/// State of a single `clean()` run.
+struct Cleaner<'a> {
+    sanitizer: &'a CssSanitizer,
+    policy: Option<&'a HostnamePolicy>,
+    rules: usize,
+    out: String,
+}
+
+impl Cleaner<'_> {
+    fn count_rule(&mut self) -> Result<()> {
+        self.rules += 1;
+        if self.rules > self.sanitizer.max_rules {
+            return Err(Error::TooManyRules(self.sanitizer.max_rules));
+        }
+        Ok(())
+    }
+
+    fn rules(&mut self, tokens: &[Token], depth: usize) -> Result<()> {
+        let mut index = 0;
+        while index < tokens.len() {
+            match &tokens[index] {
+                Token::Whitespace | Token::Semicolon | Token::CloseBrace => index += 1,
+                Token::AtKeyword(name) => {
+                    let (prelude, block, next) = split_rule(tokens, index + 1);
+                    index = next;
+                    if let Some(block) = block {
+                        self.at_rule(&name.to_ascii_lowercase(), trim(prelude), block, depth)?;
+                    }
+                }
+                _ => {
+                    let (prelude, block, next) = split_rule(tokens, index);
+                    index = next;
+                    if let Some(block) = block {
+                        self.style_rule(trim(prelude), block)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn at_rule(
+        &mut self,
+        name: &str,
+        prelude: &[Token],
+        block: &[Token],
+        depth: usize,
+    ) -> Result<()> {
+        let valid = match name {
+            "media" | "supports" => {
+                depth < MAX_NESTING
+                    && is_balanced(prelude)
+                    && !prelude
+                        .iter()
+                        .any(|token| is_forbidden(token) || is_url(token))
+            }
+            "keyframes" | "-webkit-keyframes" | "-moz-keyframes" => {
+                depth < MAX_NESTING && matches!(prelude, [Token::Ident(_) | Token::String(_)])
+            }
+            "font-face" => {
+                if prelude.is_empty() {
+                    let declarations = self.declarations(block, true);
+                    if !declarations.is_empty() {
+                        self.count_rule()?;
+                        self.out.push_str("@font-face{");
+                        self.out.push_str(&declarations);
+                        self.out.push_str("}\n");
+                    }
+                }
+                return Ok(());
+            }
+            _ => false,
+        };
+        if !valid {
+            return Ok(());
+        }
+        self.count_rule()?;
+        self.out.push('@');
+        self.out.push_str(name);
+        self.out.push(' ');
+        self.out.push_str(&serialize(prelude));
+        self.out.push_str("{\n");
+        self.rules(block, depth + 1)?;
+        self.out.push_str("}\n");
+        Ok(())
+    }
+
+    fn style_rule(&mut self, selector: &[Token], block: &[Token]) -> Result<()> {
+        if selector.is_empty()
+            || !is_balanced(selector)
+            || selector
+                .iter()
+                .any(|token| is_forbidden(token) || is_url(token))
+        {
+            return Ok(());
+        }
+        let declarations = self.declarations(block, false);
+        if declarations.is_empty() {
+            return Ok(());
+        }
+        self.count_rule()?;
+        self.out.push_str(&serialize(selector));
+        self.out.push('{');
+        self.out.push_str(&declarations);
+        self.out.push_str("}\n");
+        Ok(())
+    }
+
+    /// Serializes the allowed declarations of a block, each followed by `;`.
+    fn declarations(&self, block: &[Token], font_face: bool) -> String {
+        let mut out = String::new();
+        for declaration in split_declarations(block) {
+            let [Token::Ident(name), rest @ ..] = trim(declaration) else {
+                continue;
+            };
+            let [Token::Colon, value @ ..] = trim(rest) else {
+                continue;
+            };
+            let name = name.to_ascii_lowercase();
+            let value = trim(value);
+            if value.is_empty()
+                || !self.is_allowed_property(&name, font_face)
+                || !self.is_safe_value(value)
+            {
+                continue;
+            }
+            push_name(&mut out, &name, false);
+            out.push(':');
+            out.push_str(&serialize(value));
+            out.push(';');
+        }
+        out
+    }
+
+    fn is_allowed_property(&self, name: &str, font_face: bool) -> bool {
+        if font_face {
+            return FONT_FACE_DESCRIPTORS.contains(&name);
+        }
+        !BLOCKED_PROPERTIES.contains(&name)
+            && (self.sanitizer.properties.contains(name)
+                || self.sanitizer.properties.contains(unprefixed(name)))
+    }
+
+    fn is_safe_value(&self, value: &[Token]) -> bool {
+        if !is_balanced(value) {
+            return false;
+        }
+        let mut index = 0;
+        while index < value.len() {
+            match &value[index] {
+                token if is_forbidden(token) => return false,
+                Token::Url(url) if !is_allowed_url(url, self.policy) => return false,
+                Token::Function(name) => {
+                    let name = name.to_ascii_lowercase();
+                    if name == "url" || name == "src" {
+                        // Only a single string argument, checked like an unquoted url()
+                        let mut argument = value[index + 1..]
+                            .iter()
+                            .filter(|token| !matches!(token, Token::Whitespace));
+                        let (Some(Token::String(url)), Some(Token::CloseParen)) =
+                            (argument.next(), argument.next())
+                        else {
+                            return false;
+                        };
+                        if !is_allowed_url(url, self.policy) {
+                            return false;
+                        }
+                    } else if !ALLOWED_FUNCTIONS.contains(&unprefixed(&name)) {
+                        return false;
+                    }
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+        true
+    }
+}
+
+impl CssSanitizer {
+    /// Sanitizes a stylesheet.
+    pub(crate) fn sanitize(&self, css: &str, policy: Option<&HostnamePolicy>) -> Result<String> {
+        if css.len() > self.max_bytes {
+            return Err(Error::TooLarge(css.len(), self.max_bytes));
+        }
+        let mut cleaner = Cleaner {
+            sanitizer: self,
+            policy,
+            rules: 0,
+            out: String::with_capacity(css.len()),
+        };
+        cleaner.rules(&tokenize(css), 0)?;
+        Ok(cleaner.out)
+    }
+}
+
+#[php_impl]
+impl CssSanitizer {
+    /// Constructs a sanitizer with the default property allowlist (colors, typography, box
+    /// model, flex and grid, transitions and animations; no `position` or `z-index`), a limit
+    /// of 256 KiB and 2000 rules.
+    ///
+    /// # Returns
+    /// - CssSanitizer A new sanitizer instance.
+    fn __construct() -> Self {
+        Self {
+            properties: DEFAULT_PROPERTIES.iter().map(|s| s.to_string()).collect(),
+            max_bytes: 256 * 1024,
+            max_rules: 2000,
+        }
+    }
+
+    /// Sets the allowed properties, replacing the default list. Vendor-prefixed variants
+    /// (`-webkit-transform`) follow their unprefixed property; `behavior` and `-moz-binding`
+    /// are never allowed.
+    ///
+    /// # Parameters
+    /// - `properties`: `string[]` e.g. `['color', 'background-color', 'font-family']`.
+    fn allowed_properties(
+        self_: &mut ZendClassObject<CssSanitizer>,
+        properties: Vec<String>,
+    ) -> &mut ZendClassObject<CssSanitizer> {
+        self_.properties = properties
+            .iter()
+            .map(|property| property.to_ascii_lowercase())
+            .collect();
+        self_
+    }
+
+    /// Sets the largest stylesheet accepted, in bytes.
+    ///
+    /// # Parameters
+    /// - `bytes`: `int` Default 262144.
+    fn max_bytes(
+        self_: &mut ZendClassObject<CssSanitizer>,
+        bytes: usize,
+    ) -> &mut ZendClassObject<CssSanitizer> {
+        self_.max_bytes = bytes;
+        self_
+    }
+
+    /// Sets the most rules (style rules and at-rules) the output may contain.
+    ///
+    /// # Parameters
+    /// - `rules`: `int` Default 2000.
+    fn max_rules(
+        self_: &mut ZendClassObject<CssSanitizer>,
+        rules: usize,
+    ) -> &mut ZendClassObject<CssSanitizer> {
+        self_.max_rules = rules;
+        self_
+    }
+
+    /// Sanitizes a stylesheet.
+    ///
+    /// `url()` accepts only absolute `http`/`https` URLs without credentials that pass
+    /// `urlPolicy`; without a policy, declarations using `url()` are dropped. Strings are
+    /// re-escaped so the result cannot close a `<style>` element.
+    ///
+    /// # Parameters
+    /// - `css`: `string` Untrusted stylesheet.
+    /// - `urlPolicy`: `?HostnamePolicy` Hosts, schemes and ports allowed in `url()`.
+    ///
+    /// # Returns
+    /// - `string` Sanitized stylesheet, one rule per line.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the stylesheet exceeds the size or rule limit.
+    fn clean(&self, css: &str, url_policy: Option<&HostnamePolicy>) -> Result<String> {
+        self.sanitize(css, url_policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CssSanitizer, Error, Token, tokenize};
+    use crate::hostname_policy::HostnamePolicy;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("a{b:expr\\65ssion(1)}"),
+            [
+                Token::Ident("a".into()),
+                Token::OpenBrace,
+                Token::Ident("b".into()),
+                Token::Colon,
+                Token::Function("expression".into()),
+                Token::Numeric("1".into()),
+                Token::CloseParen,
+                Token::CloseBrace,
+            ]
+        );
+        assert_eq!(
+            tokenize("u\\rl( x.png )/**/\"a\\\"b\""),
+            [
+                Token::Url("x.png".into()),
+                Token::Whitespace,
+                Token::String("a\"b".into()),
+            ]
+        );
+        assert_eq!(tokenize("url(a\"b)"), [Token::BadUrl]);
+        assert_eq!(
+            tokenize("-1.5e3px 50%"),
+            [
+                Token::Numeric("-1.5e3px".into()),
+                Token::Whitespace,
+                Token::Numeric("50%".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_properties() {
+        let sanitizer = CssSanitizer::__construct();
+        assert_eq!(
+            sanitizer
+                .sanitize(
+                    "a.b > c:hover { color: RED; position: fixed; z-index: 9 }",
+                    None
+                )
+                .unwrap(),
+            "a.b > c:hover{color:RED;}\n"
+        );
+        assert_eq!(
+            sanitizer
+                .sanitize(
+                    "p { width: expression(alert(1)); -moz-binding: url(x.xml#x); \
+                     behavior: url(x.htc); -moz-bind\\ing: x; color: red !important }",
+                    None
+                )
+                .unwrap(),
+            "p{color:red !important;}\n"
+        );
+        assert_eq!(
+            sanitizer
+                .sanitize("p { w\\idth: expr/**/\\65ssion(alert(1)) }", None)
+                .unwrap(),
+            ""
+        );
+        assert_eq!(
+            sanitizer
+                .sanitize(
+                    "p { -webkit-transform: rotate(1deg); color: rgb(1, 2 }",
+                    None
+                )
+                .unwrap(),
+            "p{-webkit-transform:rotate(1deg);}\n"
+        );
+    }
+
+    #[test]
+    fn test_at_rules() {
+        let sanitizer = CssSanitizer::__construct();
+        assert_eq!(
+            sanitizer
+                .sanitize(
+                    "@import url(https://evil.test/x.css); @charset \"utf-8\";\n\
+                     @media screen and (max-width: 600px) { a { color: red } @import 'x'; }\n\
+                     @keyframes spin { from { transform: rotate(0deg) } 50% { opacity: .5 } }\n\
+                     @page { margin: 0 } b { color: blue }",
+                    None
+                )
+                .unwrap(),
+            "@media screen and (max-width: 600px){\na{color:red;}\n}\n\
+             @keyframes spin{\nfrom{transform:rotate(0deg);}\n50%{opacity:.5;}\n}\n\
+             b{color:blue;}\n"
+        );
+    }
+
+    #[test]
+    fn test_breakout() {
+        let sanitizer = CssSanitizer::__construct();
+        assert_eq!(
+            sanitizer
+                .sanitize("p::before { content: \"</style><script>\" }", None)
+                .unwrap(),
+            "p::before{content:\"\\3c /style\\3e \\3c script\\3e \";}\n"
+        );
+        assert_eq!(
+            sanitizer
+                .sanitize("</style><script>alert(1)</script> p { color: red }", None)
+                .unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_urls() {
+        let sanitizer = CssSanitizer::__construct();
+        let css = "p { background: url(https://cdn.example.com/a.png) }";
+        assert_eq!(sanitizer.sanitize(css, None).unwrap(), "");
+
+        let mut policy = HostnamePolicy::default();
+        policy.allow_hosts(vec!["cdn.example.com".into()]).unwrap();
+        assert_eq!(
+            sanitizer.sanitize(css, Some(&policy)).unwrap(),
+            "p{background:url(\"https://cdn.example.com/a.png\");}\n"
+        );
+        for css in [
+            "p { background: url(javascript:alert(1)) }",
+            "p { background: url('https://evil.test/a.png') }",
+            "p { background: u\\rl(https://evil.test/a.png) }",
+            "p { background: url(https://user@cdn.example.com/a.png) }",
+            "p { background: url(/a.png) }",
+            "p { background: image-set('https://cdn.example.com/a.png' 1x) }",
+        ] {
+            assert_eq!(sanitizer.sanitize(css, Some(&policy)).unwrap(), "", "{css}");
+        }
+        assert_eq!(
+            sanitizer
+                .sanitize(
+                    "@font-face { font-family: X; src: url('https://cdn.example.com/x.woff2') \
+                     format('woff2'); color: red }",
+                    Some(&policy)
+                )
+                .unwrap(),
+            "@font-face{font-family:X;src:url(\"https://cdn.example.com/x.woff2\") \
+             format(\"woff2\");}\n"
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let mut sanitizer = CssSanitizer::__construct();
+        sanitizer.max_bytes = 10;
+        assert!(matches!(
+            sanitizer.sanitize("a { color: red }", None),
+            Err(Error::TooLarge(16, 10))
+        ));
+        sanitizer.max_bytes = 1024;
+        sanitizer.max_rules = 2;
+        assert!(matches!(
+            sanitizer.sanitize("a{color:red} b{color:red} c{color:red}", None),
+            Err(Error::TooManyRules(2))
+        ));
+    }
+}
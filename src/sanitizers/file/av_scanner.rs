@@ -0,0 +1,375 @@
+use super::{Error, Result};
+use ext_php_rs::boxed::ZBox;
+use ext_php_rs::types::{ZendHashTable, Zval};
+use ext_php_rs::zend::Function;
+use ext_php_rs::{php_class, php_impl};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Bytes sent per `INSTREAM` chunk; well below clamd's default `StreamMaxLength` of 25 MiB.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Longest reply accepted from clamd.
+const MAX_REPLY_LEN: usize = 4096;
+
+/// Default connect, read and write timeout.
+const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+
+/// Where clamd listens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Address {
+    #[cfg(unix)]
+    Unix(PathBuf),
+    Tcp(String),
+}
+
+impl Address {
+    /// Parses `unix:///path`, `/path`, `tcp://host:port` or `host:port`.
+    fn parse(address: &str) -> Result<Self> {
+        let invalid = || Error::ClamdAddress(address.to_string());
+        if let Some(path) = address
+            .strip_prefix("unix://")
+            .or_else(|| address.starts_with('/').then_some(address))
+        {
+            if !path.starts_with('/') {
+                return Err(invalid());
+            }
+            #[cfg(unix)]
+            return Ok(Address::Unix(PathBuf::from(path)));
+            #[cfg(not(unix))]
+            return Err(invalid());
+        }
+        let target = address.strip_prefix("tcp://").unwrap_or(address);
+        match target.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => {
+                Ok(Address::Tcp(target.to_string()))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// A connection to clamd.
+trait Connection: Read + Write {}
+
+impl<T: Read + Write> Connection for T {}
+
+/// Outcome of a scan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Verdict {
+    Clean,
+    Infected(String),
+}
+
+impl Verdict {
+    /// Converts to `['infected' => bool, 'signature' => ?string]`.
+    fn to_array(&self) -> Result<ZBox<ZendHashTable>> {
+        let conversion = |err: ext_php_rs::error::Error| Error::Conversion(err.to_string());
+        let signature = match self {
+            Verdict::Clean => None,
+            Verdict::Infected(signature) => Some(signature.as_str()),
+        };
+        let mut array = ZendHashTable::new();
+        array
+            .insert("infected", signature.is_some())
+            .map_err(conversion)?;
+        array.insert("signature", signature).map_err(conversion)?;
+        Ok(array)
+    }
+}
+
+/// Interprets a reply such as `stream: OK` or `stream: Eicar-Signature FOUND`.
+fn parse_reply(reply: &str) -> Result<Verdict> {
+    let reply = reply.trim_end_matches(['\0', '\n']).trim();
+    let result = reply.strip_prefix("stream: ").unwrap_or(reply);
+    if result == "OK" {
+        Ok(Verdict::Clean)
+    } else if let Some(signature) = result.strip_suffix(" FOUND") {
+        Ok(Verdict::Infected(signature.to_string()))
+    } else if let Some(message) = result.strip_suffix(" ERROR") {
+        Err(Error::ClamdError(message.to_string()))
+    } else {
+        Err(Error::ClamdResponse(reply.to_string()))
+    }
+}
+
+/// Reads a reply up to its NUL terminator or the end of the connection.
+fn read_reply(connection: &mut dyn Connection) -> Result<String> {
+    let mut reply = Vec::new();
+    let mut buffer = [0u8; 256];
+    while !reply.contains(&0) && reply.len() < MAX_REPLY_LEN {
+        let read = connection
+            .read(&mut buffer)
+            .map_err(|err| Error::ClamdIo(err.to_string()))?;
+        if read == 0 {
+            break;
+        }
+        reply.extend_from_slice(&buffer[..read]);
+    }
+    if let Some(end) = reply.iter().position(|byte| *byte == 0) {
+        reply.truncate(end);
+    }
+    Ok(String::from_utf8_lossy(&reply).into_owned())
+}
+
+/// Sends `next_chunk()` output as an `INSTREAM` scan until it yields an empty chunk.
+fn instream(
+    connection: &mut dyn Connection,
+    mut next_chunk: impl FnMut(&mut Vec<u8>) -> Result<()>,
+) -> Result<Verdict> {
+    let io = |err: std::io::Error| Error::ClamdIo(err.to_string());
+    connection.write_all(b"zINSTREAM\0").map_err(io)?;
+    let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+    loop {
+        next_chunk(&mut chunk)?;
+        let length = u32::try_from(chunk.len()).map_err(|err| Error::ClamdIo(err.to_string()))?;
+        let sent = connection
+            .write_all(&length.to_be_bytes())
+            .and_then(|()| connection.write_all(&chunk));
+        if let Err(err) = sent {
+            // clamd replies and hangs up once a stream exceeds `StreamMaxLength`
+            return match read_reply(connection) {
+                Ok(reply) if !reply.is_empty() => parse_reply(&reply),
+                _ => Err(io(err)),
+            };
+        }
+        if chunk.is_empty() {
+            break;
+        }
+    }
+    parse_reply(&read_reply(connection)?)
+}
+
+/// Client for the ClamAV daemon.
+///
+/// Content is always streamed with `INSTREAM`, so clamd needs no access to the file system of
+/// the PHP process.
+#[php_class]
+#[php(name = "Hardened\\Sanitizers\\File\\AvScanner")]
+pub struct AvScanner {
+    address: Address,
+    timeout: Duration,
+}
+
+impl AvScanner {
+    /// Opens a connection with timeouts applied.
+    fn connect(&self) -> Result<Box<dyn Connection>> {
+        let failed = |err: std::io::Error| Error::ClamdConnect(err.to_string());
+        match &self.address {
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                let stream = UnixStream::connect(path).map_err(failed)?;
+                stream
+                    .set_read_timeout(Some(self.timeout))
+                    .map_err(failed)?;
+                stream
+                    .set_write_timeout(Some(self.timeout))
+                    .map_err(failed)?;
+                Ok(Box::new(stream))
+            }
+            Address::Tcp(target) => {
+                let mut last_error = None;
+                for address in target.to_socket_addrs().map_err(failed)? {
+                    match TcpStream::connect_timeout(&address, self.timeout) {
+                        Ok(stream) => {
+                            stream
+                                .set_read_timeout(Some(self.timeout))
+                                .map_err(failed)?;
+                            stream
+                                .set_write_timeout(Some(self.timeout))
+                                .map_err(failed)?;
+                            return Ok(Box::new(stream));
+                        }
+                        Err(err) => last_error = Some(err),
+                    }
+                }
+                Err(Error::ClamdConnect(last_error.map_or_else(
+                    || format!("{target} did not resolve"),
+                    |err| err.to_string(),
+                )))
+            }
+        }
+    }
+}
+
+#[php_impl]
+impl AvScanner {
+    /// Constructs a scanner.
+    ///
+    /// # Parameters
+    /// - `address`: `string` clamd socket: `unix:///var/run/clamav/clamd.ctl`, `/path/to/socket`,
+    ///   `tcp://127.0.0.1:3310` or `127.0.0.1:3310`.
+    /// - `timeoutMs`: `?int` Connect, read and write timeout in milliseconds (default `30000`).
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the address is malformed or the timeout is not positive.
+    fn __construct(address: &str, timeout_ms: Option<i64>) -> Result<Self> {
+        let timeout_ms = match timeout_ms {
+            None => DEFAULT_TIMEOUT_MS,
+            Some(timeout_ms) => u64::try_from(timeout_ms)
+                .ok()
+                .filter(|timeout_ms| *timeout_ms > 0)
+                .ok_or(Error::ClamdTimeout(timeout_ms))?,
+        };
+        Ok(Self {
+            address: Address::parse(address)?,
+            timeout: Duration::from_millis(timeout_ms),
+        })
+    }
+
+    /// Scan a file by streaming its contents to clamd.
+    ///
+    /// # Parameters
+    /// - `path`: `string` File to scan, e.g. an upload's `tmp_name`.
+    ///
+    /// # Returns
+    /// - `array` `['infected' => bool, 'signature' => ?string]`, the signature being the name
+    ///   of the detected malware.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the file cannot be read, clamd cannot be reached or times out,
+    ///   or clamd reports an error (e.g. the file exceeds its `StreamMaxLength`).
+    fn scan_file(&self, path: &str) -> Result<ZBox<ZendHashTable>> {
+        let mut file = File::open(path).map_err(|err| Error::FileOpenError {
+            path: path.to_string(),
+            reason: err.to_string(),
+        })?;
+        let mut connection = self.connect()?;
+        instream(connection.as_mut(), |chunk| {
+            chunk.resize(CHUNK_SIZE, 0);
+            let read = file
+                .read(chunk)
+                .map_err(|err| Error::StreamRead(err.to_string()))?;
+            chunk.truncate(read);
+            Ok(())
+        })?
+        .to_array()
+    }
+
+    /// Scan the remaining contents of a PHP stream.
+    ///
+    /// Reads with `fread()` until it returns an empty string, so the stream is left at its end.
+    ///
+    /// # Parameters
+    /// - `stream`: `resource` Readable stream, e.g. from `fopen()` or `php://input`.
+    ///
+    /// # Returns
+    /// - `array` As for `scanFile()`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if reading fails, clamd cannot be reached or times out, or clamd
+    ///   reports an error.
+    fn scan_stream(&self, stream: &Zval) -> Result<ZBox<ZendHashTable>> {
+        let fread = Function::try_from_function("fread")
+            .ok_or_else(|| Error::StreamRead("fread() is unavailable".into()))?;
+        let length = CHUNK_SIZE as i64;
+        let mut connection = self.connect()?;
+        instream(connection.as_mut(), |chunk| {
+            *chunk = fread
+                .try_call(vec![stream, &length])
+                .map_err(|err| Error::StreamRead(err.to_string()))?
+                .binary::<u8>()
+                .ok_or_else(|| Error::StreamRead("fread() failed".into()))?;
+            Ok(())
+        })?
+        .to_array()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Address, CHUNK_SIZE, Error, Verdict, instream, parse_reply};
+    use std::io::{Cursor, Read, Write};
+
+    /// clamd stand-in that replies with a canned answer.
+    struct Mock {
+        reply: Cursor<Vec<u8>>,
+        sent: Vec<u8>,
+    }
+
+    impl Read for Mock {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.reply.read(buf)
+        }
+    }
+
+    impl Write for Mock {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.sent.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_instream() {
+        let mut mock = Mock {
+            reply: Cursor::new(b"stream: Win.Test.EICAR_HDB-1 FOUND\0".to_vec()),
+            sent: Vec::new(),
+        };
+        let mut data = Cursor::new(vec![7u8; CHUNK_SIZE + 3]);
+        let verdict = instream(&mut mock, |chunk| {
+            chunk.resize(CHUNK_SIZE, 0);
+            let read = data.read(chunk).unwrap();
+            chunk.truncate(read);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(verdict, Verdict::Infected("Win.Test.EICAR_HDB-1".into()));
+
+        let mut expected = b"zINSTREAM\0".to_vec();
+        expected.extend_from_slice(&u32::try_from(CHUNK_SIZE).unwrap().to_be_bytes());
+        expected.extend_from_slice(&[7; CHUNK_SIZE]);
+        expected.extend_from_slice(&[0, 0, 0, 3, 7, 7, 7, 0, 0, 0, 0]);
+        assert_eq!(mock.sent, expected);
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        assert_eq!(parse_reply("stream: OK\0").unwrap(), Verdict::Clean);
+        assert_eq!(
+            parse_reply("stream: Eicar-Signature FOUND").unwrap(),
+            Verdict::Infected("Eicar-Signature".into())
+        );
+        assert!(matches!(
+            parse_reply("INSTREAM size limit exceeded. ERROR"),
+            Err(Error::ClamdError(message)) if message == "INSTREAM size limit exceeded."
+        ));
+        assert!(matches!(parse_reply(""), Err(Error::ClamdResponse(_))));
+    }
+
+    #[test]
+    fn test_address() {
+        #[cfg(unix)]
+        {
+            assert_eq!(
+                Address::parse("unix:///var/run/clamav/clamd.ctl").unwrap(),
+                Address::Unix("/var/run/clamav/clamd.ctl".into())
+            );
+            assert_eq!(
+                Address::parse("/tmp/clamd.sock").unwrap(),
+                Address::Unix("/tmp/clamd.sock".into())
+            );
+        }
+        assert_eq!(
+            Address::parse("tcp://127.0.0.1:3310").unwrap(),
+            Address::Tcp("127.0.0.1:3310".into())
+        );
+        assert_eq!(
+            Address::parse("[::1]:3310").unwrap(),
+            Address::Tcp("[::1]:3310".into())
+        );
+        for address in ["unix://relative", "127.0.0.1", "tcp://:3310", "clamd:99999"] {
+            assert!(Address::parse(address).is_err(), "{address}");
+        }
+    }
+}
@@ -3,6 +3,7 @@ use ext_php_rs::zend::ce;
 use thiserror::Error;
 
 pub mod archive;
+pub mod av_scanner;
 pub mod png;
 
 // Error codes for file sanitizer errors: 1600-1699
@@ -17,6 +18,14 @@ pub mod error_codes {
     pub const PNG_BOMB: i32 = 1607;
     pub const ZIP_BOMB: i32 = 1608;
     pub const RAR_BOMB: i32 = 1609;
+    pub const CLAMD_ADDRESS: i32 = 1610;
+    pub const CLAMD_CONNECT: i32 = 1611;
+    pub const CLAMD_IO: i32 = 1612;
+    pub const CLAMD_ERROR: i32 = 1613;
+    pub const CLAMD_RESPONSE: i32 = 1614;
+    pub const STREAM_READ: i32 = 1615;
+    pub const CONVERSION: i32 = 1616;
+    pub const CLAMD_TIMEOUT: i32 = 1617;
 }
 
 /// Errors that can occur during file sanitization operations.
@@ -51,6 +60,30 @@ pub enum Error {
 
     #[error("RAR archive looks like a bomb")]
     RarBomb,
+
+    #[error("Invalid clamd address: {0}")]
+    ClamdAddress(String),
+
+    #[error("Failed to connect to clamd: {0}")]
+    ClamdConnect(String),
+
+    #[error("clamd communication failed: {0}")]
+    ClamdIo(String),
+
+    #[error("clamd reported an error: {0}")]
+    ClamdError(String),
+
+    #[error("Unexpected clamd response: {0:?}")]
+    ClamdResponse(String),
+
+    #[error("Failed to read stream: {0}")]
+    StreamRead(String),
+
+    #[error("Failed to build scan result: {0}")]
+    Conversion(String),
+
+    #[error("Timeout must be positive, got {0} ms")]
+    ClamdTimeout(i64),
 }
 
 impl Error {
@@ -67,6 +100,14 @@ impl Error {
             Error::PngBomb { .. } => error_codes::PNG_BOMB,
             Error::ZipBomb => error_codes::ZIP_BOMB,
             Error::RarBomb => error_codes::RAR_BOMB,
+            Error::ClamdAddress(_) => error_codes::CLAMD_ADDRESS,
+            Error::ClamdConnect(_) => error_codes::CLAMD_CONNECT,
+            Error::ClamdIo(_) => error_codes::CLAMD_IO,
+            Error::ClamdError(_) => error_codes::CLAMD_ERROR,
+            Error::ClamdResponse(_) => error_codes::CLAMD_RESPONSE,
+            Error::StreamRead(_) => error_codes::STREAM_READ,
+            Error::Conversion(_) => error_codes::CONVERSION,
+            Error::ClamdTimeout(_) => error_codes::CLAMD_TIMEOUT,
         }
     }
 }
@@ -1,3 +1,4 @@
+use crate::security_headers::csp::ContentSecurityPolicy;
 use ammonia::{Builder, UrlRelative};
 use ext_php_rs::exception::PhpException;
 use ext_php_rs::zend::ce;
@@ -95,6 +96,8 @@ thread_local! {
 pub struct HtmlSanitizer {
     inner: Option<Builder>,
     attribute_filter: Option<Zval>,
+    /// Set by `useCspNonce()`: `nonce` attributes from the input are dropped.
+    strip_nonces: bool,
     pub truncation_is_safe: bool,
 }
 
@@ -107,6 +110,17 @@ impl HtmlSanitizer {
         let inner = self.inner.as_ref().ok_or(Error::InvalidState)?;
         Ok(inner.clean(html).to_string())
     }
+
+    /// Puts `nonce` on every `<script>` and `<style>` tag that survives sanitization and
+    /// drops `nonce` attributes found in the input.
+    pub(crate) fn _use_csp_nonce(&mut self, nonce: &str) -> Result<()> {
+        let inner = self.inner.as_mut().ok_or(Error::InvalidState)?;
+        for tag in ["script", "style"] {
+            inner.set_tag_attribute_value(tag.to_string(), "nonce".to_string(), nonce.to_string());
+        }
+        self.strip_nonces = true;
+        Ok(())
+    }
 }
 
 #[php_impl]
@@ -121,6 +135,7 @@ impl HtmlSanitizer {
             inner: Some(Builder::default()),
             truncation_is_safe: true,
             attribute_filter: None,
+            strip_nonces: false,
         }
     }
 
@@ -539,22 +554,31 @@ impl HtmlSanitizer {
     ///
     /// # Notes
     /// - If an attribute filter is set, it will be invoked for each attribute.
+    /// - After `useCspNonce()`, `nonce` attributes from the input never reach the filter.
     pub fn clean(&mut self, html: String) -> Result<String> {
-        let Some(filter) = self.attribute_filter.take() else {
+        let filter = self.attribute_filter.take();
+        if filter.is_none() && !self.strip_nonces {
             // Fast path: no attribute filter
             let inner = self.inner.as_ref().ok_or(Error::InvalidState)?;
             return Ok(inner.clean(&html).to_string());
-        };
+        }
 
         // Store callable in thread-local for the filter closure to access
-        ATTRIBUTE_FILTER.with(|f| *f.borrow_mut() = Some(filter.shallow_clone()));
+        ATTRIBUTE_FILTER.with(|f| *f.borrow_mut() = filter.as_ref().map(Zval::shallow_clone));
 
         // Configure the builder with the attribute filter
+        let strip_nonces = self.strip_nonces;
         let inner = self.inner.as_mut().ok_or(Error::InvalidState)?;
-        inner.attribute_filter(|element, attribute, value| {
+        inner.attribute_filter(move |element, attribute, value| {
+            // The trusted nonce is applied after filtering, so any nonce seen here is forged
+            if strip_nonces && attribute.eq_ignore_ascii_case("nonce") {
+                return None;
+            }
             ATTRIBUTE_FILTER.with(|f| {
                 let binding = f.borrow();
-                let filter = binding.as_ref()?;
+                let Some(filter) = binding.as_ref() else {
+                    return Some(value.to_string());
+                };
                 let callable = ZendCallable::new(filter).ok()?;
                 callable
                     .try_call(vec![&element, &attribute, &value])
@@ -566,7 +590,7 @@ impl HtmlSanitizer {
         let result = inner.clean(&html).to_string();
 
         // Restore the callable and clear thread-local
-        self.attribute_filter = Some(filter);
+        self.attribute_filter = filter;
         ATTRIBUTE_FILTER.with(|f| *f.borrow_mut() = None);
 
         Ok(result)
@@ -885,6 +909,28 @@ impl HtmlSanitizer {
         Ok(self_)
     }
 
+    /// Lets trusted `<script>` and `<style>` blocks run under a nonce-based CSP.
+    ///
+    /// Every `<script>` and `<style>` tag that the sanitizer allows gets `nonce="…"` with the
+    /// policy's nonce (generating it if needed), and `nonce` attributes in the input are
+    /// dropped everywhere, so an attacker cannot smuggle in a guessed or leaked nonce.
+    /// Only allow these tags for input you trust, e.g. embed snippets from your own CMS.
+    ///
+    /// The nonce is copied: call this again after `$csp->resetNonce()`.
+    ///
+    /// # Parameters
+    /// - `csp`: `ContentSecurityPolicy` The policy sent with this response.
+    ///
+    /// # Exceptions
+    /// - `Exception` if the sanitizer is not in a valid state.
+    fn use_csp_nonce<'a>(
+        self_: &'a mut ZendClassObject<HtmlSanitizer>,
+        csp: &mut ContentSecurityPolicy,
+    ) -> Result<&'a mut ZendClassObject<HtmlSanitizer>> {
+        self_._use_csp_nonce(csp.nonce())?;
+        Ok(self_)
+    }
+
     /// Sanitize and truncate the given HTML by extended grapheme clusters.
     ///
     /// This is a convenience wrapper that ensures no user-perceived character
@@ -1077,6 +1123,7 @@ mod tests {
     use super::HtmlSanitizer;
     use crate::run_php_example;
     use crate::sanitizers::html::Flag::{Ascii, Graphemes, PreserveWords};
+    use crate::security_headers::csp::ContentSecurityPolicy;
     use ammonia::UrlRelative;
     use assertables::{assert_contains, assert_le, assert_not_contains};
     use std::collections::HashSet;
@@ -1253,6 +1300,31 @@ Excepteur sint occaecat cupidatat non proident, sunt in culpa qui \
         Ok(())
     }
 
+    #[test]
+    fn test_use_csp_nonce() -> crate::TestResult {
+        let mut csp = ContentSecurityPolicy::default();
+        let nonce = csp.nonce().to_string();
+        let mut s = HtmlSanitizer::new_default();
+        s._rm_clean_content_tags(vec![String::from("script"), String::from("style")])?;
+        s._tags(vec![
+            String::from("p"),
+            String::from("script"),
+            String::from("style"),
+        ])?;
+        s._add_tag_attributes(String::from("p"), vec![String::from("nonce")])?;
+        let html =
+            r#"<script nonce="forged">run()</script><style>p{}</style><p nonce="forged">x</p>"#;
+        assert_contains!(s.clean(html.to_string())?, r#"nonce="forged""#);
+
+        s._use_csp_nonce(&nonce)?;
+        let out = s.clean(html.to_string())?;
+        assert_not_contains!(out, "forged");
+        assert_contains!(out, format!(r#"<script nonce="{nonce}">"#));
+        assert_contains!(out, format!(r#"<style nonce="{nonce}">"#));
+        assert_contains!(out, "<p>x</p>");
+        Ok(())
+    }
+
     #[test]
     fn php_example() -> crate::TestResult {
         run_php_example("sanitizers/html")?;
@@ -0,0 +1,338 @@
+use super::html::{self, HtmlSanitizer};
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::types::ZendClassObject;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag, TagEnd};
+use std::collections::HashSet;
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+use thiserror::Error;
+
+// Error codes for Markdown Sanitizer errors: 4700-4799
+pub mod error_codes {
+    pub const UNKNOWN_IMAGE_POLICY: i32 = 4700;
+}
+
+/// Errors that can occur during Markdown sanitization.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown image policy: {0}")]
+    UnknownImagePolicy(String),
+
+    #[error(transparent)]
+    Html(#[from] html::Error),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::UnknownImagePolicy(_) => error_codes::UNKNOWN_IMAGE_POLICY,
+            Error::Html(err) => err.code(),
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for Markdown sanitizer operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// What becomes of Markdown images.
+#[derive(EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum ImagePolicy {
+    /// Render as `<img>`.
+    Allow,
+    /// Render as a link to the image, so nothing loads (or tracks) until it is clicked.
+    Link,
+    /// Render only the alt text.
+    Alt,
+}
+
+/// Markdown to sanitized HTML: CommonMark with GFM tables and strikethrough, rendered by
+/// pulldown-cmark and cleaned by an `HtmlSanitizer`.
+#[php_class]
+#[php(name = "Hardened\\Sanitizers\\MarkdownSanitizer")]
+pub struct MarkdownSanitizer {
+    raw_html: bool,
+    link_schemes: HashSet<String>,
+    images: ImagePolicy,
+}
+
+impl MarkdownSanitizer {
+    /// Whether a link or image destination may be kept: relative URLs always, absolute ones
+    /// only with an allowed scheme.
+    fn allows(&self, link_type: LinkType, url: &str) -> bool {
+        if link_type == LinkType::Email {
+            // Rendered with an implicit `mailto:`
+            return self.link_schemes.contains("mailto");
+        }
+        match url.split_once(':') {
+            Some((scheme, _)) if !scheme.contains(['/', '?', '#']) => {
+                self.link_schemes.contains(&scheme.to_ascii_lowercase())
+            }
+            _ => true,
+        }
+    }
+
+    /// Renders Markdown to HTML, applying the raw HTML, link and image policies.
+    pub(crate) fn render(&self, markdown: &str) -> String {
+        let parser = Parser::new_ext(
+            markdown,
+            Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+        );
+        let mut events = Vec::new();
+        // Inside a link that is rendered as `<a>`, or one reduced to its text
+        let mut in_link = false;
+        let mut dropped_link = false;
+        // What closes each open image, if anything
+        let mut image_ends = Vec::new();
+        for event in parser {
+            match event {
+                Event::Html(html) | Event::InlineHtml(html) if !self.raw_html => {
+                    events.push(Event::Text(html));
+                }
+                Event::Start(Tag::Link {
+                    link_type,
+                    ref dest_url,
+                    ..
+                }) => {
+                    if self.allows(link_type, dest_url) {
+                        in_link = true;
+                        events.push(event);
+                    } else {
+                        dropped_link = true;
+                    }
+                }
+                Event::End(TagEnd::Link) => {
+                    if dropped_link {
+                        dropped_link = false;
+                    } else {
+                        in_link = false;
+                        events.push(event);
+                    }
+                }
+                Event::Start(Tag::Image {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }) => {
+                    let allowed = self.allows(link_type, &dest_url);
+                    match self.images {
+                        ImagePolicy::Allow if allowed => {
+                            events.push(Event::Start(Tag::Image {
+                                link_type,
+                                dest_url,
+                                title,
+                                id,
+                            }));
+                            image_ends.push(Some(Event::End(TagEnd::Image)));
+                        }
+                        // Anchors cannot nest, so an image inside a link keeps only its text
+                        ImagePolicy::Link if allowed && !in_link => {
+                            events.push(Event::Start(Tag::Link {
+                                link_type,
+                                dest_url,
+                                title,
+                                id,
+                            }));
+                            image_ends.push(Some(Event::End(TagEnd::Link)));
+                        }
+                        _ => image_ends.push(None),
+                    }
+                }
+                Event::End(TagEnd::Image) => {
+                    if let Some(end) = image_ends.pop().flatten() {
+                        events.push(end);
+                    }
+                }
+                event => events.push(event),
+            }
+        }
+        let mut out = String::with_capacity(markdown.len() * 3 / 2);
+        pulldown_cmark::html::push_html(&mut out, events.into_iter());
+        out
+    }
+}
+
+#[php_impl]
+impl MarkdownSanitizer {
+    /// Constructs a sanitizer: raw HTML is escaped, links may use `http`, `https` and
+    /// `mailto`, and images are allowed.
+    ///
+    /// # Returns
+    /// - MarkdownSanitizer A new sanitizer instance.
+    fn __construct() -> Self {
+        Self {
+            raw_html: false,
+            link_schemes: ["http", "https", "mailto"].map(String::from).into(),
+            images: ImagePolicy::Allow,
+        }
+    }
+
+    /// Enables or disables raw HTML in the Markdown.
+    ///
+    /// When disabled (the default), HTML in the input is shown as text. When enabled, it is
+    /// passed on to the `HtmlSanitizer`, which still removes anything it does not allow.
+    ///
+    /// # Parameters
+    /// - `allow`: `bool`
+    fn raw_html(
+        self_: &mut ZendClassObject<MarkdownSanitizer>,
+        allow: bool,
+    ) -> &mut ZendClassObject<MarkdownSanitizer> {
+        self_.raw_html = allow;
+        self_
+    }
+
+    /// Sets the URL schemes links, autolinks and images may use; other absolute URLs are
+    /// reduced to their text. Relative URLs are always kept.
+    ///
+    /// # Parameters
+    /// - `schemes`: `string[]` e.g. `['https', 'mailto']`. Default `http`, `https`, `mailto`.
+    fn link_schemes(
+        self_: &mut ZendClassObject<MarkdownSanitizer>,
+        schemes: Vec<String>,
+    ) -> &mut ZendClassObject<MarkdownSanitizer> {
+        self_.link_schemes = schemes
+            .iter()
+            .map(|scheme| scheme.to_ascii_lowercase())
+            .collect();
+        self_
+    }
+
+    /// Sets what becomes of images.
+    ///
+    /// # Parameters
+    /// - `policy`: `string` `allow` (render `<img>`, the default), `link` (a link to the image)
+    ///   or `alt` (only the alt text).
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the policy is unknown.
+    fn image_policy(
+        self_: &mut ZendClassObject<MarkdownSanitizer>,
+        policy: &str,
+    ) -> Result<&mut ZendClassObject<MarkdownSanitizer>> {
+        self_.images = ImagePolicy::from_str(policy)
+            .map_err(|_| Error::UnknownImagePolicy(policy.to_string()))?;
+        Ok(self_)
+    }
+
+    /// Renders Markdown to HTML and sanitizes the result.
+    ///
+    /// # Parameters
+    /// - `markdown`: `string` Untrusted Markdown.
+    /// - `sanitizer`: `?HtmlSanitizer` Policy for the rendered HTML; a default
+    ///   `HtmlSanitizer` if omitted.
+    ///
+    /// # Returns
+    /// - `string` Sanitized HTML.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the `HtmlSanitizer` is not in a valid state.
+    fn clean(&self, markdown: &str, sanitizer: Option<&mut HtmlSanitizer>) -> Result<String> {
+        let html = self.render(markdown);
+        Ok(match sanitizer {
+            Some(sanitizer) => sanitizer.clean(html)?,
+            None => HtmlSanitizer::new_default().clean(html)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImagePolicy, MarkdownSanitizer};
+    use crate::sanitizers::html::HtmlSanitizer;
+
+    #[test]
+    fn test_render() {
+        let sanitizer = MarkdownSanitizer::__construct();
+        assert_eq!(
+            sanitizer.render("**a** ~~b~~ [c](https://example.com/)"),
+            "<p><strong>a</strong> <del>b</del> <a href=\"https://example.com/\">c</a></p>\n"
+        );
+        assert!(
+            sanitizer
+                .render("| a |\n|---|\n| b |\n")
+                .contains("<td>b</td>")
+        );
+    }
+
+    #[test]
+    fn test_raw_html() {
+        let mut sanitizer = MarkdownSanitizer::__construct();
+        let html = sanitizer.render("<script>alert(1)</script>\n\nx <b>y</b>");
+        assert!(!html.contains("<script") && !html.contains("<b>"));
+        assert!(html.contains("&lt;script&gt;") && html.contains("&lt;b&gt;"));
+
+        sanitizer.raw_html = true;
+        let html = sanitizer.render("x <b>y</b>");
+        assert_eq!(html, "<p>x <b>y</b></p>\n");
+        let cleaned = HtmlSanitizer::new_default()
+            .clean(sanitizer.render("<img src=x onerror=alert(1)>"))
+            .unwrap();
+        assert!(!cleaned.contains("onerror"));
+    }
+
+    #[test]
+    fn test_links() {
+        let mut sanitizer = MarkdownSanitizer::__construct();
+        for markdown in [
+            "[x](javascript:alert(1))",
+            "[x](JavaScript:alert(1))",
+            "[x](javascript&#58;alert(1))",
+            "<javascript:alert(1)>",
+        ] {
+            assert!(!sanitizer.render(markdown).contains("href"), "{markdown}");
+        }
+        assert_eq!(
+            sanitizer.render("[x](/a?b:c)"),
+            "<p><a href=\"/a?b:c\">x</a></p>\n"
+        );
+        assert!(sanitizer.render("<a@example.com>").contains("mailto:"));
+
+        sanitizer.link_schemes = ["https".to_string()].into();
+        assert_eq!(sanitizer.render("[x](http://example.com/)"), "<p>x</p>\n");
+        assert_eq!(
+            sanitizer.render("<a@example.com>"),
+            "<p>a@example.com</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_images() {
+        let mut sanitizer = MarkdownSanitizer::__construct();
+        let markdown = "![cat](https://example.com/cat.png)";
+        assert!(
+            sanitizer
+                .render(markdown)
+                .contains("<img src=\"https://example.com/cat.png\" alt=\"cat\"")
+        );
+        assert_eq!(
+            sanitizer.render("![cat](data:image/png;base64,AAAA)"),
+            "<p>cat</p>\n"
+        );
+
+        sanitizer.images = ImagePolicy::Link;
+        assert_eq!(
+            sanitizer.render(markdown),
+            "<p><a href=\"https://example.com/cat.png\">cat</a></p>\n"
+        );
+        assert_eq!(
+            sanitizer.render("[![cat](https://example.com/cat.png)](https://example.com/)"),
+            "<p><a href=\"https://example.com/\">cat</a></p>\n"
+        );
+
+        sanitizer.images = ImagePolicy::Alt;
+        assert_eq!(sanitizer.render(markdown), "<p>cat</p>\n");
+    }
+}
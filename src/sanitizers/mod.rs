@@ -1,8 +1,14 @@
 use ext_php_rs::builders::ModuleBuilder;
+#[cfg(feature = "bbcode_sanitizer")]
+pub mod bbcode;
+#[cfg(feature = "css_sanitizer")]
+pub mod css;
 #[cfg(feature = "file_sanitizers")]
 pub mod file;
 #[cfg(feature = "html_sanitizer")]
 pub mod html;
+#[cfg(feature = "markdown_sanitizer")]
+pub mod markdown;
 #[cfg(feature = "svg_sanitizer")]
 pub mod svg;
 
@@ -12,10 +18,23 @@ pub(crate) fn build(mut module: ModuleBuilder) -> ModuleBuilder {
         module = module.class::<html::HtmlSanitizer>();
         module = module.enumeration::<html::Flag>();
     }
+    #[cfg(feature = "markdown_sanitizer")]
+    {
+        module = module.class::<markdown::MarkdownSanitizer>();
+    }
+    #[cfg(feature = "bbcode_sanitizer")]
+    {
+        module = module.class::<bbcode::BbcodeSanitizer>();
+    }
+    #[cfg(feature = "css_sanitizer")]
+    {
+        module = module.class::<css::CssSanitizer>();
+    }
     #[cfg(feature = "file_sanitizers")]
     {
         module = module.class::<file::png::PngSanitizer>();
         module = module.class::<file::archive::ArchiveSanitizer>();
+        module = module.class::<file::av_scanner::AvScanner>();
     }
     #[cfg(feature = "svg_sanitizer")]
     {
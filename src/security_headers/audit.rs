@@ -0,0 +1,413 @@
+use super::csp::{ContentSecurityPolicy, Severity};
+use super::{Error as SecurityHeaderError, Result};
+use ext_php_rs::boxed::ZBox;
+use ext_php_rs::types::{ArrayKey, ZendHashTable};
+use ext_php_rs::{php_class, php_impl};
+use std::collections::HashMap;
+
+/// HSTS `max-age` below which the header is graded as too short (one year).
+const HSTS_MIN_MAX_AGE: u64 = 31_536_000;
+
+/// Grade thresholds on the 0–100 score, best first.
+const GRADES: [(u32, &str); 6] = [
+    (100, "A+"),
+    (90, "A"),
+    (75, "B"),
+    (60, "C"),
+    (45, "D"),
+    (30, "E"),
+];
+
+/// A single problem found in a response's headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub severity: Severity,
+    pub header: &'static str,
+    pub message: String,
+    pub fix: String,
+}
+
+/// The outcome of grading a response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub score: u32,
+    pub grade: &'static str,
+    pub issues: Vec<Issue>,
+}
+
+/// Points deducted for one issue.
+fn penalty(severity: Severity) -> u32 {
+    match severity {
+        Severity::High => 25,
+        Severity::Medium => 10,
+        Severity::Low => 5,
+    }
+}
+
+/// Reads a `key=value` parameter (case-insensitive key) from a `;`-separated header value.
+fn parameter<'a>(value: &'a str, key: &str) -> Option<&'a str> {
+    value.split(';').find_map(|part| {
+        let (name, value) = part.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case(key)
+            .then(|| value.trim().trim_matches('"'))
+    })
+}
+
+/// Returns true if a `;`-separated header value contains the flag `name`.
+fn has_flag(value: &str, name: &str) -> bool {
+    value
+        .split(';')
+        .any(|part| part.trim().eq_ignore_ascii_case(name))
+}
+
+/// Grades a response's headers. Names are matched case-insensitively; repeated headers
+/// are expected to be joined with `, ` already.
+#[must_use]
+pub fn grade(headers: &[(String, String)]) -> Report {
+    let get = |name: &str| {
+        headers
+            .iter()
+            .find(|(header, _)| header.trim().eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+    };
+    let mut issues = Vec::new();
+    let mut report = |severity, header, message: &str, fix: &str| {
+        issues.push(Issue {
+            severity,
+            header,
+            message: message.into(),
+            fix: fix.into(),
+        });
+    };
+
+    let mut frame_ancestors = false;
+    match get("Content-Security-Policy") {
+        None => report(
+            Severity::High,
+            "Content-Security-Policy",
+            if get("Content-Security-Policy-Report-Only").is_some() {
+                "only a report-only policy is sent, so nothing is enforced"
+            } else {
+                "missing, so injected scripts run unrestricted"
+            },
+            "send a ContentSecurityPolicy, e.g. new Manager() for a strict baseline",
+        ),
+        Some(value) => match ContentSecurityPolicy::parse(value) {
+            Err(err) => report(
+                Severity::Medium,
+                "Content-Security-Policy",
+                &format!("could not be parsed ({err}), so browsers may ignore parts of it"),
+                "rebuild it with ContentSecurityPolicy::parse() and build()",
+            ),
+            Ok(csp) => {
+                frame_ancestors = value.to_ascii_lowercase().contains("frame-ancestors");
+                for finding in csp._audit() {
+                    report(
+                        finding.severity,
+                        "Content-Security-Policy",
+                        &finding.message,
+                        &format!(
+                            "adjust {} with ContentSecurityPolicy::setRule()",
+                            finding.directive
+                        ),
+                    );
+                }
+            }
+        },
+    }
+
+    match get("Strict-Transport-Security") {
+        None => report(
+            Severity::High,
+            "Strict-Transport-Security",
+            "missing, so the first request can be downgraded to plain HTTP",
+            "StrictTransportSecurity::maxAge(31536000) and includeSubDomains(true)",
+        ),
+        Some(value) => match parameter(value, "max-age").and_then(|age| age.parse::<u64>().ok()) {
+            None | Some(0) => report(
+                Severity::High,
+                "Strict-Transport-Security",
+                "max-age is missing or 0, which disables HSTS",
+                "StrictTransportSecurity::maxAge(31536000)",
+            ),
+            Some(max_age) => {
+                if max_age < HSTS_MIN_MAX_AGE {
+                    report(
+                        Severity::Low,
+                        "Strict-Transport-Security",
+                        &format!("max-age={max_age} is shorter than one year"),
+                        "StrictTransportSecurity::maxAge(31536000)",
+                    );
+                }
+                if !has_flag(value, "includeSubDomains") {
+                    report(
+                        Severity::Low,
+                        "Strict-Transport-Security",
+                        "includeSubDomains is not set, so subdomains can be downgraded",
+                        "StrictTransportSecurity::includeSubDomains(true)",
+                    );
+                }
+            }
+        },
+    }
+
+    if !get("X-Content-Type-Options").is_some_and(|value| value.eq_ignore_ascii_case("nosniff")) {
+        report(
+            Severity::Medium,
+            "X-Content-Type-Options",
+            "nosniff is not set, so browsers may guess content types and run uploads as scripts",
+            "Whatnot::setNosniff(true)",
+        );
+    }
+
+    match get("X-Frame-Options") {
+        None if !frame_ancestors => report(
+            Severity::Medium,
+            "X-Frame-Options",
+            "neither X-Frame-Options nor CSP frame-ancestors is set, so the page can be framed \
+             (clickjacking)",
+            "Whatnot::setFrameOptions(FrameOptions::Deny) or CSP frame-ancestors 'none'",
+        ),
+        Some(value)
+            if !frame_ancestors
+                && !value.eq_ignore_ascii_case("DENY")
+                && !value.eq_ignore_ascii_case("SAMEORIGIN") =>
+        {
+            report(
+                Severity::Medium,
+                "X-Frame-Options",
+                &format!("{value} is not supported by current browsers"),
+                "Whatnot::setFrameOptions(FrameOptions::Deny) or CSP frame-ancestors",
+            );
+        }
+        _ => {}
+    }
+
+    match get("Referrer-Policy") {
+        None => report(
+            Severity::Medium,
+            "Referrer-Policy",
+            "missing; browsers default to strict-origin-when-cross-origin, older ones leak \
+             full URLs",
+            "ReferrerPolicy::set('strict-origin-when-cross-origin')",
+        ),
+        Some(value) => {
+            // With a fallback list, the last token the browser understands wins.
+            let policy = value.rsplit(',').next().unwrap_or_default().trim();
+            if policy.eq_ignore_ascii_case("unsafe-url")
+                || policy.eq_ignore_ascii_case("no-referrer-when-downgrade")
+            {
+                report(
+                    Severity::Medium,
+                    "Referrer-Policy",
+                    &format!("{policy} sends full URLs, including paths and queries, cross-origin"),
+                    "ReferrerPolicy::set('strict-origin-when-cross-origin')",
+                );
+            }
+        }
+    }
+
+    if get("Permissions-Policy").is_none() {
+        report(
+            Severity::Low,
+            "Permissions-Policy",
+            "missing, so embedded content may request powerful features",
+            "PermissionsPolicy::deny() for unused features such as camera and geolocation",
+        );
+    }
+
+    if get("Cross-Origin-Opener-Policy").is_none() {
+        report(
+            Severity::Low,
+            "Cross-Origin-Opener-Policy",
+            "missing, so cross-origin windows keep a reference to this one",
+            "OpenerPolicy::set('same-origin')",
+        );
+    }
+
+    if let Some(value) = get("X-XSS-Protection")
+        && value != "0"
+    {
+        report(
+            Severity::Low,
+            "X-XSS-Protection",
+            "the XSS auditor is gone from browsers and could be abused to leak data",
+            "Whatnot::setXssProtection(XssProtection::Off), or omit the header",
+        );
+    }
+
+    if get("X-Powered-By").is_some() {
+        report(
+            Severity::Low,
+            "X-Powered-By",
+            "discloses the server-side technology",
+            "header_remove('X-Powered-By') or expose_php = Off",
+        );
+    }
+    if let Some(value) = get("Server")
+        && value.chars().any(|c| c.is_ascii_digit())
+    {
+        report(
+            Severity::Low,
+            "Server",
+            "discloses the server software version",
+            "configure the web server to omit its version",
+        );
+    }
+
+    issues.sort_by_key(|issue| issue.severity);
+    let score = 100u32.saturating_sub(issues.iter().map(|issue| penalty(issue.severity)).sum());
+    let grade = GRADES
+        .iter()
+        .find(|(min, _)| score >= *min)
+        .map_or("F", |(_, grade)| *grade);
+    Report {
+        score,
+        grade,
+        issues,
+    }
+}
+
+/// Grades response headers the way securityheaders.com does, for integration tests and
+/// health checks.
+#[php_class]
+#[php(name = "Hardened\\SecurityHeaders\\Audit")]
+pub struct Audit {}
+
+#[php_impl]
+impl Audit {
+    /// Inspects a response's headers and reports missing or misconfigured security headers,
+    /// together with the builder calls that fix them.
+    ///
+    /// # Parameters
+    /// - `headers`: `array` Either a name => value map (values may be lists for repeated
+    ///   headers), or a list of `"Name: value"` lines as returned by `headers_list()`.
+    ///
+    /// # Returns
+    /// - `array` `['grade' => 'A+'..'F', 'score' => 0..100, 'findings' => [['severity' =>
+    ///   'high'|'medium'|'low', 'header' => string, 'message' => string, 'fix' => string],
+    ///   ...]]`, findings most severe first.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if a header value is neither a string nor a list of strings.
+    fn grade(headers: &ZendHashTable) -> Result<ZBox<ZendHashTable>> {
+        let mut pairs = Vec::new();
+        for (key, value) in headers {
+            let invalid = || SecurityHeaderError::InvalidValue {
+                header_type: "audited header".into(),
+                value: key.to_string(),
+            };
+            let value = match value.array() {
+                Some(list) => list
+                    .values()
+                    .map(|item| item.string().ok_or_else(invalid))
+                    .collect::<Result<Vec<_>>>()?
+                    .join(", "),
+                None => value.string().ok_or_else(invalid)?,
+            };
+            if let ArrayKey::Long(_) = key {
+                let (name, value) = value.split_once(':').ok_or_else(invalid)?;
+                pairs.push((name.to_string(), value.trim().to_string()));
+            } else {
+                pairs.push((key.to_string(), value));
+            }
+        }
+
+        let report = grade(&pairs);
+        let findings: Vec<HashMap<&'static str, String>> = report
+            .issues
+            .into_iter()
+            .map(|issue| {
+                HashMap::from([
+                    ("severity", issue.severity.to_string()),
+                    ("header", issue.header.to_string()),
+                    ("message", issue.message),
+                    ("fix", issue.fix),
+                ])
+            })
+            .collect();
+        let format_error =
+            |err: ext_php_rs::error::Error| SecurityHeaderError::FormatError(err.to_string());
+        let mut result = ZendHashTable::new();
+        result.insert("grade", report.grade).map_err(format_error)?;
+        result
+            .insert("score", i64::from(report.score))
+            .map_err(format_error)?;
+        result.insert("findings", findings).map_err(format_error)?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::grade;
+    use crate::security_headers::csp::Severity;
+
+    fn headers(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn empty_response_fails() {
+        let report = grade(&[]);
+        assert_eq!(report.grade, "F");
+        assert_eq!(report.score, 0);
+        assert_eq!(report.issues[0].severity, Severity::High);
+    }
+
+    #[test]
+    fn strong_response_gets_a_plus() {
+        let report = grade(&headers(&[
+            (
+                "content-security-policy",
+                "default-src 'self'; object-src 'none'; base-uri 'none'; frame-ancestors 'none'",
+            ),
+            (
+                "Strict-Transport-Security",
+                "max-age=63072000; includeSubDomains; preload",
+            ),
+            ("X-Content-Type-Options", "nosniff"),
+            (
+                "Referrer-Policy",
+                "no-referrer, strict-origin-when-cross-origin",
+            ),
+            ("Permissions-Policy", "camera=()"),
+            ("Cross-Origin-Opener-Policy", "same-origin"),
+            ("X-XSS-Protection", "0"),
+        ]));
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+        assert_eq!((report.score, report.grade), (100, "A+"));
+    }
+
+    #[test]
+    fn weak_values_are_reported() {
+        let report = grade(&headers(&[
+            (
+                "Content-Security-Policy",
+                "script-src 'self' 'unsafe-inline'",
+            ),
+            ("Strict-Transport-Security", "max-age=600"),
+            ("X-Content-Type-Options", "nosniff"),
+            ("X-Frame-Options", "ALLOW-FROM https://example.com"),
+            ("Referrer-Policy", "unsafe-url"),
+            ("X-Powered-By", "PHP/8.4.0"),
+        ]));
+        let flagged: Vec<&str> = report.issues.iter().map(|issue| issue.header).collect();
+        for header in [
+            "Content-Security-Policy",
+            "Strict-Transport-Security",
+            "X-Frame-Options",
+            "Referrer-Policy",
+            "X-Powered-By",
+        ] {
+            assert!(flagged.contains(&header), "{header}");
+        }
+        assert!(!flagged.contains(&"X-Content-Type-Options"));
+        assert_eq!(report.issues[0].severity, Severity::High);
+        assert_eq!(report.grade, "F");
+    }
+}
@@ -1,5 +1,7 @@
+use super::super::emitter::{Emitter, HeaderPair, to_php};
+use super::super::reporting::is_valid_name;
 use super::super::{Error as SecurityHeaderError, Result};
-use ext_php_rs::zend::Function;
+use ext_php_rs::types::Zval;
 use ext_php_rs::{php_class, php_enum, php_impl};
 use strum_macros::Display;
 
@@ -27,8 +29,18 @@ pub enum Policy {
 /// Builder for `Cross-Origin-Embedder-Policy` header.
 #[php_class]
 #[php(name = "Hardened\\SecurityHeaders\\CrossOrigin\\EmbedderPolicy")]
+#[derive(Clone)]
 pub struct EmbedderPolicy {
     policy: Policy,
+    report_to: Option<String>,
+    emitter: Emitter,
+}
+
+impl EmbedderPolicy {
+    /// Headers emitted by `send()`, in order.
+    fn _pairs(&self) -> Vec<HeaderPair> {
+        vec![("Cross-Origin-Embedder-Policy", self.build())]
+    }
 }
 
 #[php_impl]
@@ -49,6 +61,8 @@ impl EmbedderPolicy {
     fn __construct(policy: Option<Policy>) -> Self {
         Self {
             policy: policy.unwrap_or(Policy::UnsafeNone),
+            report_to: None,
+            emitter: Emitter::default(),
         }
     }
 
@@ -71,12 +85,57 @@ impl EmbedderPolicy {
         self.policy.to_string()
     }
 
+    /// Adds `report-to="<endpoint>"`, so violations are reported to a `ReportingEndpoints`
+    /// name (applies to `send()` and `sendReportOnly()`).
+    ///
+    /// # Parameters
+    /// - `endpoint`: `?string` Endpoint name, or `null` to stop reporting.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the name is not a valid endpoint name.
+    fn report_to(&mut self, endpoint: Option<String>) -> Result<()> {
+        if let Some(name) = &endpoint
+            && !is_valid_name(name)
+        {
+            return Err(SecurityHeaderError::InvalidValue {
+                header_type: "reporting endpoint name".into(),
+                value: name.clone(),
+            });
+        }
+        self.report_to = endpoint;
+        Ok(())
+    }
+
     /// Render the header value.
     ///
     /// # Returns
-    /// - `string`: the currently configured policy token.
-    fn build(&self) -> String {
-        self.policy.to_string()
+    /// - `string`: the currently configured policy token, followed by the `report-to`
+    ///   parameter if an endpoint is set.
+    pub(crate) fn build(&self) -> String {
+        match &self.report_to {
+            Some(endpoint) => format!("{}; report-to=\"{endpoint}\"", self.policy),
+            None => self.policy.to_string(),
+        }
+    }
+
+    /// Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+    /// headers to a worker runtime's response object.
+    ///
+    /// # Parameters
+    /// - `emitter`: `callable(string $name, string $value): void`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `emitter` is not callable.
+    fn set_emitter(&mut self, emitter: &Zval) -> Result<()> {
+        self.emitter.set(emitter)
+    }
+
+    /// Builds the headers `send()` would emit.
+    ///
+    /// # Returns
+    /// - `list<array{string, string}>` `[name, value]` pairs in sending order.
+    fn build_pairs(&self) -> Vec<Vec<String>> {
+        to_php(self._pairs())
     }
 
     /// Send the `Cross-Origin-Embedder-Policy` header via PHP `header()`.
@@ -84,14 +143,17 @@ impl EmbedderPolicy {
     /// # Errors
     /// - Throws `Exception` if the PHP `header()` function cannot be invoked.
     fn send(&self) -> Result<()> {
-        Function::try_from_function("header")
-            .ok_or(SecurityHeaderError::HeaderUnavailable)?
-            .try_call(vec![&format!(
-                "Cross-Origin-Embedder-Policy: {}",
-                self.policy
-            )])
-            .map_err(|err| SecurityHeaderError::HeaderCallFailed(err.to_string()))?;
-        Ok(())
+        self.emitter.emit(&self._pairs())
+    }
+
+    /// Sends the policy as `Cross-Origin-Embedder-Policy-Report-Only` instead, to trial it: violations
+    /// are reported (see `reportTo()`) but nothing is blocked.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the PHP `header()` function cannot be invoked.
+    fn send_report_only(&self) -> Result<()> {
+        self.emitter
+            .emit(&[("Cross-Origin-Embedder-Policy-Report-Only", self.build())])
     }
 }
 
@@ -136,6 +198,16 @@ mod tests {
         assert_eq!(Policy::Credentialless.to_string(), "credentialless");
     }
 
+    #[test]
+    fn report_to() -> crate::TestResult {
+        let mut coep = EmbedderPolicy::__construct(Some(Policy::Credentialless));
+        coep.report_to(Some("coep".into()))?;
+        assert_eq!(coep.build(), r#"credentialless; report-to="coep""#);
+        assert!(coep.report_to(Some("Upper".into())).is_err());
+        assert_eq!(coep.get(), "credentialless");
+        Ok(())
+    }
+
     #[test]
     fn php_example() -> crate::TestResult {
         run_php_example("security-headers/cross-origin/embedder-policy")?;
@@ -0,0 +1,326 @@
+use super::super::emitter::{Emitter, HeaderPair, to_map, to_php};
+use super::super::reporting::is_valid_name;
+use super::super::{Error as SecurityHeaderError, Result};
+use super::embedder_policy::Policy as EmbedderPolicyValue;
+use super::opener_policy::Policy as OpenerPolicyValue;
+use super::resource_policy::ResourcePolicyDirective;
+use ext_php_rs::types::Zval;
+use ext_php_rs::{php_class, php_impl};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Configures `Cross-Origin-Opener-Policy`, `Cross-Origin-Embedder-Policy` and
+/// `Cross-Origin-Resource-Policy` together, so the page ends up `crossOriginIsolated`
+/// (required for `SharedArrayBuffer` and high-resolution timers).
+#[php_class]
+#[php(name = "Hardened\\SecurityHeaders\\CrossOriginIsolation")]
+pub struct CrossOriginIsolation {
+    opener: OpenerPolicyValue,
+    opener_report_only: Option<OpenerPolicyValue>,
+    embedder: EmbedderPolicyValue,
+    embedder_report_only: Option<EmbedderPolicyValue>,
+    resource: ResourcePolicyDirective,
+    report_to: Option<String>,
+    emitter: Emitter,
+}
+
+impl CrossOriginIsolation {
+    /// Lists the reasons the given COOP/COEP pair does not yield `crossOriginIsolated`.
+    fn _problems(opener: OpenerPolicyValue, embedder: EmbedderPolicyValue) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !matches!(opener, OpenerPolicyValue::SameOrigin) {
+            problems.push(format!(
+                "Cross-Origin-Opener-Policy must be same-origin, got {opener}"
+            ));
+        }
+        if embedder == EmbedderPolicyValue::UnsafeNone {
+            problems.push(format!(
+                "Cross-Origin-Embedder-Policy must be require-corp or credentialless, got {embedder}"
+            ));
+        }
+        problems
+    }
+
+    /// Appends the `report-to` parameter, if an endpoint is configured.
+    fn _with_report_to(&self, value: String) -> String {
+        match &self.report_to {
+            Some(endpoint) => format!("{value}; report-to=\"{endpoint}\""),
+            None => value,
+        }
+    }
+}
+
+impl CrossOriginIsolation {
+    /// Headers emitted by `send()`, in order.
+    fn _pairs(&self) -> Vec<HeaderPair> {
+        let mut pairs = vec![
+            (
+                "Cross-Origin-Opener-Policy",
+                self._with_report_to(self.opener.to_string()),
+            ),
+            (
+                "Cross-Origin-Embedder-Policy",
+                self._with_report_to(self.embedder.to_string()),
+            ),
+            ("Cross-Origin-Resource-Policy", self.resource.to_string()),
+        ];
+        if let Some(opener) = self.opener_report_only {
+            pairs.push((
+                "Cross-Origin-Opener-Policy-Report-Only",
+                self._with_report_to(opener.to_string()),
+            ));
+        }
+        if let Some(embedder) = self.embedder_report_only {
+            pairs.push((
+                "Cross-Origin-Embedder-Policy-Report-Only",
+                self._with_report_to(embedder.to_string()),
+            ));
+        }
+        pairs
+    }
+}
+
+#[php_impl]
+impl CrossOriginIsolation {
+    /// Constructs an isolating configuration: COOP `same-origin`, COEP `require-corp`
+    /// and CORP `same-origin`, without report-only headers.
+    fn __construct() -> Self {
+        Self {
+            opener: OpenerPolicyValue::SameOrigin,
+            opener_report_only: None,
+            embedder: EmbedderPolicyValue::RequireCorp,
+            embedder_report_only: None,
+            resource: ResourcePolicyDirective::SameOrigin,
+            report_to: None,
+            emitter: Emitter::default(),
+        }
+    }
+
+    /// Sets the enforced `Cross-Origin-Opener-Policy`.
+    ///
+    /// # Parameters
+    /// - `policy`: `string` `unsafe-none`, `same-origin` or `same-origin-allow-popups`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the token is invalid.
+    fn opener(&mut self, policy: &str) -> Result<()> {
+        self.opener = parse_opener(policy)?;
+        Ok(())
+    }
+
+    /// Sets (or with `null` removes) the `Cross-Origin-Opener-Policy-Report-Only` value.
+    ///
+    /// # Parameters
+    /// - `policy`: `?string` Same tokens as `opener()`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the token is invalid.
+    fn opener_report_only(&mut self, policy: Option<String>) -> Result<()> {
+        self.opener_report_only = policy.as_deref().map(parse_opener).transpose()?;
+        Ok(())
+    }
+
+    /// Sets the enforced `Cross-Origin-Embedder-Policy`.
+    ///
+    /// # Parameters
+    /// - `policy`: `EmbedderPolicyValue` e.g. `EmbedderPolicyValue::Credentialless`.
+    fn embedder(&mut self, policy: EmbedderPolicyValue) {
+        self.embedder = policy;
+    }
+
+    /// Sets (or with `null` removes) the `Cross-Origin-Embedder-Policy-Report-Only` value.
+    ///
+    /// # Parameters
+    /// - `policy`: `?EmbedderPolicyValue`
+    fn embedder_report_only(&mut self, policy: Option<EmbedderPolicyValue>) {
+        self.embedder_report_only = policy;
+    }
+
+    /// Sets the `Cross-Origin-Resource-Policy` sent with the response.
+    ///
+    /// # Parameters
+    /// - `policy`: `string` `same-origin`, `same-site` or `cross-origin`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the token is invalid.
+    fn resource(&mut self, policy: &str) -> Result<()> {
+        self.resource = ResourcePolicyDirective::from_str(policy).map_err(|_| {
+            SecurityHeaderError::InvalidValue {
+                header_type: "Cross-Origin-Resource-Policy".into(),
+                value: policy.to_string(),
+            }
+        })?;
+        Ok(())
+    }
+
+    /// Adds `report-to="<endpoint>"` to the COOP and COEP headers (enforced and report-only).
+    ///
+    /// # Parameters
+    /// - `endpoint`: `?string` Name registered with `ReportingEndpoints`, or `null` to remove.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the name is not a valid endpoint name.
+    fn report_to(&mut self, endpoint: Option<String>) -> Result<()> {
+        if let Some(name) = &endpoint
+            && !is_valid_name(name)
+        {
+            return Err(SecurityHeaderError::InvalidValue {
+                header_type: "reporting endpoint name".into(),
+                value: name.clone(),
+            });
+        }
+        self.report_to = endpoint;
+        Ok(())
+    }
+
+    /// Lists the reasons the configuration would not make the page `crossOriginIsolated`.
+    ///
+    /// # Parameters
+    /// - `reportOnly`: `?bool` Check the report-only values instead (falling back to the
+    ///   enforced ones where unset), to see whether a trial would isolate. Default: `false`.
+    ///
+    /// # Returns
+    /// - `string[]` Problems; empty if the page would be isolated.
+    fn validate(&self, report_only: Option<bool>) -> Vec<String> {
+        if report_only.unwrap_or(false) {
+            Self::_problems(
+                self.opener_report_only.unwrap_or(self.opener),
+                self.embedder_report_only.unwrap_or(self.embedder),
+            )
+        } else {
+            Self::_problems(self.opener, self.embedder)
+        }
+    }
+
+    /// Returns true if the enforced headers make the page `crossOriginIsolated`.
+    fn is_isolated(&self) -> bool {
+        self.validate(None).is_empty()
+    }
+
+    /// Builds all configured headers.
+    ///
+    /// # Returns
+    /// - `array<string,string>` Header names mapped to values, including the
+    ///   `*-Report-Only` variants when set.
+    fn build(&self) -> HashMap<&'static str, String> {
+        to_map(self._pairs())
+    }
+
+    /// Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+    /// headers to a worker runtime's response object.
+    ///
+    /// # Parameters
+    /// - `emitter`: `callable(string $name, string $value): void`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `emitter` is not callable.
+    fn set_emitter(&mut self, emitter: &Zval) -> Result<()> {
+        self.emitter.set(emitter)
+    }
+
+    /// Builds the headers `send()` would emit.
+    ///
+    /// # Returns
+    /// - `list<array{string, string}>` `[name, value]` pairs in sending order.
+    fn build_pairs(&self) -> Vec<Vec<String>> {
+        to_php(self._pairs())
+    }
+
+    /// Sends all configured headers via PHP `header()`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if PHP `header()` cannot be invoked.
+    fn send(&self) -> Result<()> {
+        self.emitter.emit(&self._pairs())
+    }
+}
+
+/// Parses a `Cross-Origin-Opener-Policy` token.
+fn parse_opener(policy: &str) -> Result<OpenerPolicyValue> {
+    OpenerPolicyValue::from_str(policy).map_err(|_| SecurityHeaderError::InvalidValue {
+        header_type: "Cross-Origin-Opener-Policy".into(),
+        value: policy.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CrossOriginIsolation, EmbedderPolicyValue};
+
+    #[test]
+    fn default_is_isolated() {
+        let isolation = CrossOriginIsolation::__construct();
+        assert!(isolation.is_isolated());
+        let headers = isolation.build();
+        assert_eq!(headers.len(), 3);
+        assert_eq!(
+            headers
+                .get("Cross-Origin-Opener-Policy")
+                .map(String::as_str),
+            Some("same-origin")
+        );
+        assert_eq!(
+            headers
+                .get("Cross-Origin-Embedder-Policy")
+                .map(String::as_str),
+            Some("require-corp")
+        );
+        assert_eq!(
+            headers
+                .get("Cross-Origin-Resource-Policy")
+                .map(String::as_str),
+            Some("same-origin")
+        );
+    }
+
+    #[test]
+    fn validate_combinations() {
+        let mut isolation = CrossOriginIsolation::__construct();
+        isolation.opener("same-origin-allow-popups").unwrap();
+        isolation.embedder(EmbedderPolicyValue::UnsafeNone);
+        assert_eq!(isolation.validate(None).len(), 2);
+        assert!(!isolation.is_isolated());
+
+        // Trial isolation in report-only mode while enforcing nothing.
+        isolation
+            .opener_report_only(Some("same-origin".into()))
+            .unwrap();
+        isolation.embedder_report_only(Some(EmbedderPolicyValue::Credentialless));
+        assert!(isolation.validate(Some(true)).is_empty());
+        assert!(!isolation.is_isolated());
+
+        assert!(isolation.opener("bogus").is_err());
+        assert!(isolation.opener_report_only(Some("bogus".into())).is_err());
+        assert!(isolation.resource("bogus").is_err());
+    }
+
+    #[test]
+    fn report_only_and_report_to() {
+        let mut isolation = CrossOriginIsolation::__construct();
+        isolation.embedder_report_only(Some(EmbedderPolicyValue::Credentialless));
+        isolation.resource("same-site").unwrap();
+        isolation.report_to(Some("coi".into())).unwrap();
+        assert!(isolation.report_to(Some("Not Valid".into())).is_err());
+
+        let headers = isolation.build();
+        assert_eq!(headers.len(), 4);
+        assert_eq!(
+            headers
+                .get("Cross-Origin-Opener-Policy")
+                .map(String::as_str),
+            Some("same-origin; report-to=\"coi\"")
+        );
+        assert_eq!(
+            headers
+                .get("Cross-Origin-Embedder-Policy-Report-Only")
+                .map(String::as_str),
+            Some("credentialless; report-to=\"coi\"")
+        );
+        assert_eq!(
+            headers
+                .get("Cross-Origin-Resource-Policy")
+                .map(String::as_str),
+            Some("same-site")
+        );
+    }
+}
@@ -1,4 +1,5 @@
 pub mod embedder_policy;
+pub mod isolation;
 pub mod opener_policy;
 pub mod resource_policy;
 pub mod resource_sharing;
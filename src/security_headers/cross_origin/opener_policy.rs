@@ -1,5 +1,7 @@
+use super::super::emitter::{Emitter, HeaderPair, to_php};
+use super::super::reporting::is_valid_name;
 use super::super::{Error as SecurityHeaderError, Result};
-use ext_php_rs::zend::Function;
+use ext_php_rs::types::Zval;
 use ext_php_rs::{php_class, php_impl};
 use std::str::FromStr;
 use strum_macros::{Display, EnumString};
@@ -22,8 +24,18 @@ pub enum Policy {
 /// Builder for `Cross-Origin-Opener-Policy` header.
 #[php_class]
 #[php(name = "Hardened\\SecurityHeaders\\CrossOrigin\\OpenerPolicy")]
+#[derive(Clone)]
 pub struct OpenerPolicy {
     policy: Policy,
+    report_to: Option<String>,
+    emitter: Emitter,
+}
+
+impl OpenerPolicy {
+    /// Headers emitted by `send()`, in order.
+    fn _pairs(&self) -> Vec<HeaderPair> {
+        vec![("Cross-Origin-Opener-Policy", self.build())]
+    }
 }
 
 #[php_impl]
@@ -42,7 +54,7 @@ impl OpenerPolicy {
     ///
     /// # Exceptions
     /// - Throws `Exception` if the provided token is not one of the allowed values.
-    fn __construct(policy: Option<String>) -> Result<Self> {
+    pub(crate) fn __construct(policy: Option<String>) -> Result<Self> {
         let policy = if let Some(p) = policy {
             Policy::from_str(&p).map_err(|_| SecurityHeaderError::InvalidValue {
                 header_type: "Cross-Origin-Opener-Policy".into(),
@@ -51,7 +63,11 @@ impl OpenerPolicy {
         } else {
             Policy::UnsafeNone
         };
-        Ok(Self { policy })
+        Ok(Self {
+            policy,
+            report_to: None,
+            emitter: Emitter::default(),
+        })
     }
 
     /// Use this if you need to change the policy after construction.
@@ -70,12 +86,57 @@ impl OpenerPolicy {
         Ok(())
     }
 
+    /// Adds `report-to="<endpoint>"`, so violations are reported to a `ReportingEndpoints`
+    /// name (applies to `send()` and `sendReportOnly()`).
+    ///
+    /// # Parameters
+    /// - `endpoint`: `?string` Endpoint name, or `null` to stop reporting.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the name is not a valid endpoint name.
+    fn report_to(&mut self, endpoint: Option<String>) -> Result<()> {
+        if let Some(name) = &endpoint
+            && !is_valid_name(name)
+        {
+            return Err(SecurityHeaderError::InvalidValue {
+                header_type: "reporting endpoint name".into(),
+                value: name.clone(),
+            });
+        }
+        self.report_to = endpoint;
+        Ok(())
+    }
+
     /// Build the header value.
     ///
     /// # Returns
-    /// - `string` the configured policy, e.g. `"same-origin"`.
-    fn build(&self) -> String {
-        self.policy.to_string()
+    /// - `string` the configured policy, e.g. `"same-origin"` or
+    ///   `"same-origin; report-to=\"coop\""`.
+    pub(crate) fn build(&self) -> String {
+        match &self.report_to {
+            Some(endpoint) => format!("{}; report-to=\"{endpoint}\"", self.policy),
+            None => self.policy.to_string(),
+        }
+    }
+
+    /// Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+    /// headers to a worker runtime's response object.
+    ///
+    /// # Parameters
+    /// - `emitter`: `callable(string $name, string $value): void`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `emitter` is not callable.
+    fn set_emitter(&mut self, emitter: &Zval) -> Result<()> {
+        self.emitter.set(emitter)
+    }
+
+    /// Builds the headers `send()` would emit.
+    ///
+    /// # Returns
+    /// - `list<array{string, string}>` `[name, value]` pairs in sending order.
+    fn build_pairs(&self) -> Vec<Vec<String>> {
+        to_php(self._pairs())
     }
 
     /// Send the `Cross-Origin-Opener-Policy` header via PHP `header()`.
@@ -83,14 +144,17 @@ impl OpenerPolicy {
     /// # Exceptions
     /// - Throws `Exception` if the PHP `header()` function cannot be invoked.
     fn send(&self) -> Result<()> {
-        Function::try_from_function("header")
-            .ok_or(SecurityHeaderError::HeaderUnavailable)?
-            .try_call(vec![&format!(
-                "Cross-Origin-Opener-Policy: {}",
-                self.build()
-            )])
-            .map_err(|e| SecurityHeaderError::HeaderCallFailed(e.to_string()))?;
-        Ok(())
+        self.emitter.emit(&self._pairs())
+    }
+
+    /// Sends the policy as `Cross-Origin-Opener-Policy-Report-Only` instead, to trial it: violations
+    /// are reported (see `reportTo()`) but nothing is blocked.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the PHP `header()` function cannot be invoked.
+    fn send_report_only(&self) -> Result<()> {
+        self.emitter
+            .emit(&[("Cross-Origin-Opener-Policy-Report-Only", self.build())])
     }
 }
 
@@ -128,6 +192,17 @@ mod tests {
         assert!(c.set("invalid").is_err());
     }
 
+    #[test]
+    fn report_to() -> TestResult {
+        let mut c = OpenerPolicy::__construct(Some("same-origin".into()))?;
+        c.report_to(Some("coop".into()))?;
+        assert_eq!(c.build(), r#"same-origin; report-to="coop""#);
+        assert!(c.report_to(Some("not valid".into())).is_err());
+        c.report_to(None)?;
+        assert_eq!(c.build(), "same-origin");
+        Ok(())
+    }
+
     #[test]
     fn php_example() -> TestResult {
         run_php_example("security-headers/cross-origin/opener-policy")?;
@@ -1,5 +1,6 @@
+use super::super::emitter::{Emitter, HeaderPair, to_php};
 use super::super::{Error as SecurityHeaderError, Result};
-use ext_php_rs::zend::Function;
+use ext_php_rs::types::Zval;
 use ext_php_rs::{php_class, php_impl};
 use std::str::FromStr;
 use strum_macros::{Display, EnumString};
@@ -21,8 +22,17 @@ pub enum ResourcePolicyDirective {
 /// Builder for the `Cross-Origin-Resource-Policy` header.
 #[php_class]
 #[php(name = "Hardened\\SecurityHeaders\\CrossOrigin\\ResourcePolicy")]
+#[derive(Clone)]
 pub struct ResourcePolicy {
     policy: ResourcePolicyDirective,
+    emitter: Emitter,
+}
+
+impl ResourcePolicy {
+    /// Headers emitted by `send()`, in order.
+    fn _pairs(&self) -> Vec<HeaderPair> {
+        vec![("Cross-Origin-Resource-Policy", self.build())]
+    }
 }
 
 #[php_impl]
@@ -40,7 +50,7 @@ impl ResourcePolicy {
     ///
     /// # Exceptions
     /// - Throws an `Exception` if `policy` cannot be parsed into a valid directive.
-    fn __construct(policy: Option<String>) -> Result<Self> {
+    pub(crate) fn __construct(policy: Option<String>) -> Result<Self> {
         let directive = if let Some(s) = policy {
             ResourcePolicyDirective::from_str(&s).map_err(|_| {
                 SecurityHeaderError::InvalidValue {
@@ -51,7 +61,10 @@ impl ResourcePolicy {
         } else {
             ResourcePolicyDirective::SameOrigin
         };
-        Ok(Self { policy: directive })
+        Ok(Self {
+            policy: directive,
+            emitter: Emitter::default(),
+        })
     }
 
     /// Change the active Cross-Origin-Resource-Policy directive.
@@ -85,23 +98,36 @@ impl ResourcePolicy {
     ///
     /// # Returns
     /// - `string` the configured directive token.
-    fn build(&self) -> String {
+    pub(crate) fn build(&self) -> String {
         self.policy.to_string()
     }
 
+    /// Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+    /// headers to a worker runtime's response object.
+    ///
+    /// # Parameters
+    /// - `emitter`: `callable(string $name, string $value): void`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `emitter` is not callable.
+    fn set_emitter(&mut self, emitter: &Zval) -> Result<()> {
+        self.emitter.set(emitter)
+    }
+
+    /// Builds the headers `send()` would emit.
+    ///
+    /// # Returns
+    /// - `list<array{string, string}>` `[name, value]` pairs in sending order.
+    fn build_pairs(&self) -> Vec<Vec<String>> {
+        to_php(self._pairs())
+    }
+
     /// Send the `Cross-Origin-Resource-Policy` header via PHP `header()`.
     ///
     /// # Exceptions
     /// - Throws `Exception` if the PHP `header()` function cannot be invoked.
     fn send(&self) -> Result<()> {
-        Function::try_from_function("header")
-            .ok_or(SecurityHeaderError::HeaderUnavailable)?
-            .try_call(vec![&format!(
-                "Cross-Origin-Resource-Policy: {}",
-                self.build()
-            )])
-            .map_err(|err| SecurityHeaderError::HeaderCallFailed(format!("{err:?}")))?;
-        Ok(())
+        self.emitter.emit(&self._pairs())
     }
 }
 #[cfg(test)]
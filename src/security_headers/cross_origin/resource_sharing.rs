@@ -1,19 +1,93 @@
+use super::super::emitter::{Emitter, HeaderPair, to_map, to_php};
 use super::super::{Error as SecurityHeaderError, Result};
-use ext_php_rs::zend::Function;
+use crate::origin::{Origin, OriginPattern};
+use ext_php_rs::convert::IntoZval;
+use ext_php_rs::types::Zval;
 use ext_php_rs::{php_class, php_const, php_impl};
 use std::collections::HashMap;
 
+/// Methods a browser may always use cross-origin, without being listed.
+const SAFELISTED_METHODS: [&str; 3] = ["GET", "HEAD", "POST"];
+
+/// Looks up a request header case-insensitively. Accepts both header names
+/// (`Access-Control-Request-Method`) and `$_SERVER` keys (`HTTP_ACCESS_CONTROL_REQUEST_METHOD`).
+fn request_header<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    headers.iter().find_map(|(key, value)| {
+        let key = key.strip_prefix("HTTP_").unwrap_or(key);
+        (key.len() == name.len()
+            && key
+                .bytes()
+                .zip(name.bytes())
+                .all(|(a, b)| a.eq_ignore_ascii_case(&b) || (a == b'_' && b == b'-')))
+        .then_some(value.trim())
+    })
+}
+
 /// CORS policy builder for HTTP responses.
 #[derive(Default)]
 #[php_class]
 #[php(name = "Hardened\\SecurityHeaders\\CrossOrigin\\ResourceSharing")]
 pub struct ResourceSharing {
     allow_origins: Vec<String>,
+    allow_origin_patterns: Vec<OriginPattern>,
     allow_methods: Vec<String>,
     allow_headers: Vec<String>,
     allow_credentials: bool,
     expose_headers: Vec<String>,
     max_age: u64,
+    emitter: Emitter,
+}
+
+impl ResourceSharing {
+    /// Headers emitted by `send()`, in order.
+    ///
+    /// `Access-Control-Allow-Origin` holds a single origin or `*`. With the request `Origin`,
+    /// it is echoed back if allowed and `Vary: Origin` is added; without it, only `*` or a
+    /// lone allowed origin (and no patterns) can be emitted.
+    fn _pairs(&self, origin: Option<&str>) -> Result<Vec<HeaderPair>> {
+        self._check_credentials()?;
+        let mut headers = Vec::new();
+
+        if self.allow_origins.iter().any(|allowed| allowed == "*") {
+            headers.push(("Access-Control-Allow-Origin", "*".into()));
+        } else if let Some(origin) = origin {
+            if self.is_origin_allowed(origin) {
+                headers.push(("Access-Control-Allow-Origin", origin.into()));
+            }
+            headers.push(("Vary", "Origin".into()));
+        } else if let ([allowed], []) = (
+            self.allow_origins.as_slice(),
+            self.allow_origin_patterns.as_slice(),
+        ) {
+            headers.push(("Access-Control-Allow-Origin", allowed.clone()));
+        }
+        if !self.allow_methods.is_empty() {
+            headers.push((
+                "Access-Control-Allow-Methods",
+                self.allow_methods.join(", "),
+            ));
+        }
+        if !self.allow_headers.is_empty() {
+            headers.push((
+                "Access-Control-Allow-Headers",
+                self.allow_headers.join(", "),
+            ));
+        }
+        if self.allow_credentials {
+            headers.push(("Access-Control-Allow-Credentials", "true".into()));
+        }
+        if !self.expose_headers.is_empty() {
+            headers.push((
+                "Access-Control-Expose-Headers",
+                self.expose_headers.join(", "),
+            ));
+        }
+        if self.max_age > 0 {
+            headers.push(("Access-Control-Max-Age", self.max_age.to_string()));
+        }
+
+        Ok(headers)
+    }
 }
 
 #[php_impl]
@@ -28,11 +102,13 @@ impl ResourceSharing {
     fn __construct() -> Self {
         Self {
             allow_origins: Vec::new(),
+            allow_origin_patterns: Vec::new(),
             allow_methods: Vec::new(),
             allow_headers: Vec::new(),
             allow_credentials: false,
             expose_headers: Vec::new(),
             max_age: 0,
+            emitter: Emitter::default(),
         }
     }
 
@@ -56,6 +132,62 @@ impl ResourceSharing {
         self.allow_origins = origins;
     }
 
+    /// Allow every origin matching a pattern, e.g. `*.example.com` or
+    /// `http://**.example.com:8080`.
+    ///
+    /// The host is matched with `Hostname::matchesPattern()`: `*.` covers exactly one label,
+    /// `**.` any depth, and the apex never matches. The scheme defaults to `https` and the port
+    /// to the scheme's default, so `*.example.com` does not match `http://` or `:8443` origins.
+    /// Matching origins are echoed back individually, so patterns work with credentials.
+    ///
+    /// # Parameters
+    /// - `pattern`: `string` Origin pattern `[scheme://]host[:port]`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the pattern is malformed.
+    fn allow_origin_pattern(&mut self, pattern: &str) -> Result<()> {
+        let pattern =
+            OriginPattern::parse(pattern).map_err(|_| SecurityHeaderError::InvalidValue {
+                header_type: "CORS origin pattern".into(),
+                value: pattern.into(),
+            })?;
+        self.allow_origin_patterns.push(pattern);
+        Ok(())
+    }
+
+    /// Returns true if a request `Origin` is allowed, either by `allowOrigins()` or by a
+    /// pattern. Origins are compared by scheme, host and port, so `https://Example.com:443`
+    /// equals `https://example.com`. The `*` wildcard is not considered here.
+    ///
+    /// # Parameters
+    /// - `origin`: `string` Value of the request's `Origin` header.
+    fn is_origin_allowed(&self, origin: &str) -> bool {
+        let parsed = Origin::_from_url(origin).ok();
+        self.allow_origins.iter().any(|allowed| {
+            match (&parsed, Origin::_from_url(allowed)) {
+                (Some(parsed), Ok(allowed)) => *parsed == allowed,
+                // Opaque origins such as `null` can only be allowed literally.
+                (None, Err(_)) => allowed != "*" && allowed.eq_ignore_ascii_case(origin),
+                _ => false,
+            }
+        }) || parsed.is_some_and(|parsed| {
+            self.allow_origin_patterns
+                .iter()
+                .any(|pattern| pattern.matches(&parsed))
+        })
+    }
+
+    /// Errors if credentials are enabled together with the `*` origin wildcard.
+    fn _check_credentials(&self) -> Result<()> {
+        if self.allow_credentials && self.allow_origins.iter().any(|origin| origin == "*") {
+            return Err(SecurityHeaderError::InvalidValue {
+                header_type: "Access-Control-Allow-Origin".into(),
+                value: "* cannot be combined with Access-Control-Allow-Credentials".into(),
+            });
+        }
+        Ok(())
+    }
+
     /// Specify which HTTP methods may be used in cross-origin requests.
     ///
     /// During a CORS preflight (`OPTIONS`) request, the browser checks
@@ -100,7 +232,8 @@ impl ResourceSharing {
     ///   to omit the `Access-Control-Allow-Credentials` header.
     ///
     /// # Behavior
-    /// - If enabled, you **cannot** use `"*"` for `allow_origins`.
+    /// - If enabled, you **cannot** use `"*"` for `allow_origins`: `build()`, `send()` and
+    ///   `handlePreflight()` throw instead of emitting both.
     ///
     /// # Returns
     /// - `void`
@@ -143,59 +276,173 @@ impl ResourceSharing {
 
     /// Build an associative array of CORS headers and their values.
     ///
+    /// `Access-Control-Allow-Origin` names a single origin. Pass the request's `Origin` to
+    /// have it echoed back when it is allowed (by `allowOrigins()` or a pattern), together
+    /// with `Vary: Origin`; without it, the header is only sent for `*` or a single allowed
+    /// origin.
+    ///
+    /// # Parameters
+    /// - `origin`: `?string` Value of the request's `Origin` header, e.g.
+    ///   `$_SERVER['HTTP_ORIGIN'] ?? null`.
+    ///
     /// # Returns
     /// - `array<string,string>` Map of header names to header values.
-    fn build(&self) -> HashMap<&'static str, String> {
-        let mut headers = HashMap::new();
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if credentials are enabled together with the `*` origin.
+    fn build(&self, origin: Option<&str>) -> Result<HashMap<&'static str, String>> {
+        Ok(to_map(self._pairs(origin)?))
+    }
+
+    /// Evaluates a preflight request against the policy and returns the headers to answer it with.
+    ///
+    /// # Returns
+    /// - `Some(headers)` if the origin, method and every requested header are allowed.
+    /// - `None` if the request is not a preflight or is not allowed.
+    ///
+    /// # Errors
+    /// - Returns `InvalidValue` if credentials are enabled together with the `*` origin.
+    fn _handle_preflight(
+        &self,
+        request_headers: &HashMap<String, String>,
+    ) -> Result<Option<HashMap<&'static str, String>>> {
+        self._check_credentials()?;
+        Ok(self._evaluate_preflight(request_headers))
+    }
 
-        if !self.allow_origins.is_empty() {
-            headers.insert("Access-Control-Allow-Origin", self.allow_origins.join(", "));
+    /// Computes the preflight response headers; see `_handle_preflight()`.
+    fn _evaluate_preflight(
+        &self,
+        request_headers: &HashMap<String, String>,
+    ) -> Option<HashMap<&'static str, String>> {
+        let origin = request_header(request_headers, "Origin")?;
+        let method = request_header(request_headers, "Access-Control-Request-Method")?;
+        let requested_headers: Vec<&str> =
+            request_header(request_headers, "Access-Control-Request-Headers")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .collect();
+
+        let any_origin = self.allow_origins.iter().any(|allowed| allowed == "*");
+        if !(any_origin || self.is_origin_allowed(origin)) {
+            return None;
         }
-        if !self.allow_methods.is_empty() {
+
+        // The `*` wildcard is only valid for requests without credentials.
+        let wildcard = |list: &[String]| !self.allow_credentials && list.iter().any(|v| v == "*");
+        if !(SAFELISTED_METHODS.contains(&method)
+            || wildcard(&self.allow_methods)
+            || self.allow_methods.iter().any(|allowed| allowed == method))
+        {
+            return None;
+        }
+        if !requested_headers.iter().all(|name| {
+            // `Authorization` is never covered by the wildcard.
+            (wildcard(&self.allow_headers) && !name.eq_ignore_ascii_case("authorization"))
+                || self
+                    .allow_headers
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(name))
+        }) {
+            return None;
+        }
+
+        let mut headers = HashMap::new();
+        if any_origin {
+            headers.insert("Access-Control-Allow-Origin", "*".into());
             headers.insert(
-                "Access-Control-Allow-Methods",
-                self.allow_methods.join(", "),
+                "Vary",
+                "Access-Control-Request-Method, Access-Control-Request-Headers".into(),
             );
-        }
-        if !self.allow_headers.is_empty() {
+        } else {
+            headers.insert("Access-Control-Allow-Origin", origin.into());
             headers.insert(
-                "Access-Control-Allow-Headers",
-                self.allow_headers.join(", "),
+                "Vary",
+                "Origin, Access-Control-Request-Method, Access-Control-Request-Headers".into(),
             );
         }
+        headers.insert("Access-Control-Allow-Methods", method.into());
+        if !requested_headers.is_empty() {
+            headers.insert("Access-Control-Allow-Headers", requested_headers.join(", "));
+        }
         if self.allow_credentials {
             headers.insert("Access-Control-Allow-Credentials", "true".into());
         }
-        if !self.expose_headers.is_empty() {
-            headers.insert(
-                "Access-Control-Expose-Headers",
-                self.expose_headers.join(", "),
-            );
-        }
         if self.max_age > 0 {
             headers.insert("Access-Control-Max-Age", self.max_age.to_string());
         }
+        Some(headers)
+    }
+
+    /// Evaluate a CORS preflight (`OPTIONS`) request and compute the exact response headers.
+    ///
+    /// The allowed origin is echoed back (or `*` when any origin is allowed and credentials
+    /// are disabled), the requested method and headers are confirmed individually, and `Vary`
+    /// lists every request header the answer depends on, so caches never mix up responses.
+    ///
+    /// # Parameters
+    /// - `requestHeaders`: `array<string,string>` Request headers, e.g. from `getallheaders()`
+    ///   or `$_SERVER` (`HTTP_ORIGIN`, ...). Names are case-insensitive.
+    ///
+    /// # Returns
+    /// - `array<string,string>` Response headers to send with a `204` status, or
+    /// - `false` if the request is not an allowed preflight; respond with `403` and no CORS
+    ///   headers.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if credentials are enabled together with the `*` origin.
+    /// - Throws `Exception` if the result cannot be converted to a PHP value.
+    fn handle_preflight(&self, request_headers: HashMap<String, String>) -> Result<Zval> {
+        match self._handle_preflight(&request_headers)? {
+            Some(headers) => headers
+                .into_zval(false)
+                .map_err(|err| SecurityHeaderError::FormatError(format!("{err:?}"))),
+            None => {
+                let mut zval = Zval::new();
+                zval.set_bool(false);
+                Ok(zval)
+            }
+        }
+    }
 
-        headers
+    /// Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+    /// headers to a worker runtime's response object.
+    ///
+    /// # Parameters
+    /// - `emitter`: `callable(string $name, string $value): void`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `emitter` is not callable.
+    fn set_emitter(&mut self, emitter: &Zval) -> Result<()> {
+        self.emitter.set(emitter)
+    }
+
+    /// Builds the headers `send()` would emit.
+    ///
+    /// # Parameters
+    /// - `origin`: `?string` Value of the request's `Origin` header; see `build()`.
+    ///
+    /// # Returns
+    /// - `list<array{string, string}>` `[name, value]` pairs in sending order.
+    fn build_pairs(&self, origin: Option<&str>) -> Result<Vec<Vec<String>>> {
+        Ok(to_php(self._pairs(origin)?))
     }
 
     /// Send all configured CORS headers via PHP's `header()` function.
     ///
+    /// # Parameters
+    /// - `origin`: `?string` Value of the request's `Origin` header; see `build()`.
+    ///
     /// # Returns
     /// - `void`
     ///
     /// # Exceptions
+    /// - Throws `Exception` if credentials are enabled together with the `*` origin.
     /// - Throws `Exception` if PHP `header()` cannot be invoked.
-    fn send(&self) -> Result<()> {
-        let header_fn =
-            Function::try_from_function("header").ok_or(SecurityHeaderError::HeaderUnavailable)?;
-        for (name, value) in self.build() {
-            let hdr = format!("{name}: {value}");
-            header_fn
-                .try_call(vec![&hdr])
-                .map_err(|e| SecurityHeaderError::HeaderCallFailed(e.to_string()))?;
-        }
-        Ok(())
+    fn send(&self, origin: Option<&str>) -> Result<()> {
+        self.emitter.emit(&self._pairs(origin)?)
     }
 }
 
@@ -203,11 +450,12 @@ impl ResourceSharing {
 mod tests {
     use super::ResourceSharing;
     use crate::run_php_example;
+    use std::collections::HashMap;
 
     #[test]
     fn test_default_policy_empty() {
         let cp = ResourceSharing::default();
-        let headers = cp.build();
+        let headers = cp.build(None).unwrap();
         assert!(headers.is_empty(), "Expected no headers by default");
     }
 
@@ -215,21 +463,54 @@ mod tests {
     fn test_allow_origins_only() {
         let mut cp = ResourceSharing::default();
         cp.allow_origins(vec!["https://example.com".to_string(), "*".to_string()]);
-        let headers = cp.build();
+        let headers = cp.build(None).unwrap();
         assert_eq!(
             headers
                 .get("Access-Control-Allow-Origin")
                 .map(String::as_str),
-            Some("https://example.com, *")
+            Some("*")
         );
         assert_eq!(headers.len(), 1);
+
+        // Several origins cannot share the header without knowing the request's origin.
+        cp.allow_origins(vec![
+            "https://example.com".to_string(),
+            "https://api.example.com".to_string(),
+        ]);
+        assert!(cp.build(None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_echo_request_origin() {
+        let mut cp = ResourceSharing::default();
+        cp.allow_origins(vec![
+            "https://example.com".to_string(),
+            "https://api.example.com".to_string(),
+        ]);
+        cp.allow_origin_pattern("*.example.org").unwrap();
+        cp.allow_credentials(true);
+
+        for origin in ["https://api.example.com", "https://app.example.org"] {
+            let headers = cp.build(Some(origin)).unwrap();
+            assert_eq!(
+                headers
+                    .get("Access-Control-Allow-Origin")
+                    .map(String::as_str),
+                Some(origin)
+            );
+            assert_eq!(headers.get("Vary").map(String::as_str), Some("Origin"));
+        }
+
+        let headers = cp.build(Some("https://evil.example.net")).unwrap();
+        assert!(!headers.contains_key("Access-Control-Allow-Origin"));
+        assert_eq!(headers.get("Vary").map(String::as_str), Some("Origin"));
     }
 
     #[test]
     fn test_allow_methods_only() {
         let mut cp = ResourceSharing::default();
         cp.allow_methods(vec!["GET".to_string(), "POST".to_string()]);
-        let headers = cp.build();
+        let headers = cp.build(None).unwrap();
         assert_eq!(
             headers
                 .get("Access-Control-Allow-Methods")
@@ -243,7 +524,7 @@ mod tests {
     fn test_allow_headers_only() {
         let mut cp = ResourceSharing::default();
         cp.allow_headers(vec!["Content-Type".to_string(), "X-Custom".to_string()]);
-        let headers = cp.build();
+        let headers = cp.build(None).unwrap();
         assert_eq!(
             headers
                 .get("Access-Control-Allow-Headers")
@@ -257,7 +538,7 @@ mod tests {
     fn test_allow_credentials_only() {
         let mut cp = ResourceSharing::default();
         cp.allow_credentials(true);
-        let headers = cp.build();
+        let headers = cp.build(None).unwrap();
         assert_eq!(
             headers
                 .get("Access-Control-Allow-Credentials")
@@ -271,7 +552,7 @@ mod tests {
     fn test_expose_headers_only() {
         let mut cp = ResourceSharing::default();
         cp.expose_headers(vec!["X-Exposed".to_string()]);
-        let headers = cp.build();
+        let headers = cp.build(None).unwrap();
         assert_eq!(
             headers
                 .get("Access-Control-Expose-Headers")
@@ -285,7 +566,7 @@ mod tests {
     fn test_max_age_only() {
         let mut cp = ResourceSharing::default();
         cp.max_age(3600);
-        let headers = cp.build();
+        let headers = cp.build(None).unwrap();
         assert_eq!(
             headers.get("Access-Control-Max-Age").map(String::as_str),
             Some("3600")
@@ -303,7 +584,7 @@ mod tests {
         cp.expose_headers(vec!["X-Exp".to_string()]);
         cp.max_age(1200);
 
-        let headers = cp.build();
+        let headers = cp.build(None).unwrap();
         assert_eq!(
             headers
                 .get("Access-Control-Allow-Origin")
@@ -341,6 +622,181 @@ mod tests {
         assert_eq!(headers.len(), 6);
     }
 
+    fn preflight_request(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_handle_preflight() {
+        let mut cp = ResourceSharing::default();
+        cp.allow_origins(vec!["https://app.example.com".to_string()]);
+        cp.allow_methods(vec!["PUT".to_string()]);
+        cp.allow_headers(vec!["Content-Type".to_string(), "X-Token".to_string()]);
+        cp.allow_credentials(true);
+        cp.max_age(600);
+
+        let headers = cp
+            ._handle_preflight(&preflight_request(&[
+                ("origin", "https://app.example.com"),
+                ("Access-Control-Request-Method", "PUT"),
+                (
+                    "HTTP_ACCESS_CONTROL_REQUEST_HEADERS",
+                    "x-token, content-type",
+                ),
+            ]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            headers
+                .get("Access-Control-Allow-Origin")
+                .map(String::as_str),
+            Some("https://app.example.com")
+        );
+        assert_eq!(
+            headers.get("Vary").map(String::as_str),
+            Some("Origin, Access-Control-Request-Method, Access-Control-Request-Headers")
+        );
+        assert_eq!(
+            headers
+                .get("Access-Control-Allow-Methods")
+                .map(String::as_str),
+            Some("PUT")
+        );
+        assert_eq!(
+            headers
+                .get("Access-Control-Allow-Headers")
+                .map(String::as_str),
+            Some("x-token, content-type")
+        );
+        assert_eq!(
+            headers
+                .get("Access-Control-Allow-Credentials")
+                .map(String::as_str),
+            Some("true")
+        );
+        assert_eq!(
+            headers.get("Access-Control-Max-Age").map(String::as_str),
+            Some("600")
+        );
+
+        for request in [
+            // Not a preflight.
+            vec![("Origin", "https://app.example.com")],
+            vec![
+                ("Origin", "https://evil.example.com"),
+                ("Access-Control-Request-Method", "PUT"),
+            ],
+            vec![
+                ("Origin", "https://app.example.com"),
+                ("Access-Control-Request-Method", "DELETE"),
+            ],
+            vec![
+                ("Origin", "https://app.example.com"),
+                ("Access-Control-Request-Method", "PUT"),
+                ("Access-Control-Request-Headers", "X-Other"),
+            ],
+        ] {
+            assert!(
+                cp._handle_preflight(&preflight_request(&request))
+                    .unwrap()
+                    .is_none()
+            );
+        }
+    }
+
+    #[test]
+    fn test_handle_preflight_wildcard() {
+        let mut cp = ResourceSharing::default();
+        cp.allow_origins(vec!["*".to_string()]);
+        cp.allow_headers(vec!["*".to_string()]);
+        let request = preflight_request(&[
+            ("Origin", "https://any.example.com"),
+            ("Access-Control-Request-Method", "GET"),
+            ("Access-Control-Request-Headers", "X-Anything"),
+        ]);
+        let headers = cp._handle_preflight(&request).unwrap().unwrap();
+        assert_eq!(
+            headers
+                .get("Access-Control-Allow-Origin")
+                .map(String::as_str),
+            Some("*")
+        );
+        assert_eq!(
+            headers.get("Vary").map(String::as_str),
+            Some("Access-Control-Request-Method, Access-Control-Request-Headers")
+        );
+        assert!(
+            cp._handle_preflight(&preflight_request(&[
+                ("Origin", "https://any.example.com"),
+                ("Access-Control-Request-Method", "GET"),
+                ("Access-Control-Request-Headers", "Authorization"),
+            ]))
+            .unwrap()
+            .is_none()
+        );
+
+        // Credentials are never combined with `*`.
+        cp.allow_credentials(true);
+        assert!(cp._handle_preflight(&request).is_err());
+        assert!(cp.build(None).is_err());
+    }
+
+    #[test]
+    fn test_origin_patterns() {
+        let mut cp = ResourceSharing::default();
+        cp.allow_origins(vec![
+            "https://Example.com:443".to_string(),
+            "null".to_string(),
+        ]);
+        cp.allow_origin_pattern("*.example.com").unwrap();
+        cp.allow_origin_pattern("http://localhost:3000").unwrap();
+        assert!(cp.allow_origin_pattern("*.*.example.com").is_err());
+
+        for origin in [
+            "https://example.com",
+            "https://api.example.com",
+            "http://localhost:3000",
+            "null",
+        ] {
+            assert!(cp.is_origin_allowed(origin), "{origin}");
+        }
+        for origin in [
+            "http://example.com",
+            "https://example.com:8443",
+            "http://api.example.com",
+            "https://a.b.example.com",
+            "https://example.com.evil.com",
+            "http://localhost:3001",
+            "*",
+        ] {
+            assert!(!cp.is_origin_allowed(origin), "{origin}");
+        }
+
+        cp.allow_credentials(true);
+        let headers = cp
+            ._handle_preflight(&preflight_request(&[
+                ("Origin", "https://api.example.com"),
+                ("Access-Control-Request-Method", "GET"),
+            ]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            headers
+                .get("Access-Control-Allow-Origin")
+                .map(String::as_str),
+            Some("https://api.example.com")
+        );
+        assert_eq!(
+            headers
+                .get("Access-Control-Allow-Credentials")
+                .map(String::as_str),
+            Some("true")
+        );
+    }
+
     #[test]
     fn php_example() -> crate::TestResult {
         run_php_example("security-headers/cross-origin/resource-sharing")?;
@@ -1,12 +1,16 @@
+use super::emitter::{Emitter, HeaderPair, to_php};
 use super::{Error as SecurityHeaderError, Result};
-use ext_php_rs::zend::Function;
+use data_encoding::BASE64;
+use ext_php_rs::types::Zval;
 use ext_php_rs::{php_class, php_enum, php_impl};
 use fmt::Write;
 use rand::distr::Alphanumeric;
 use rand::{RngExt, rng};
-use std::collections::BTreeMap;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
-use strum_macros::Display;
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
 use trim_in_place::TrimInPlace;
 
 /// All the CSP directives you want to support.
@@ -16,8 +20,8 @@ use trim_in_place::TrimInPlace;
 /// Content-Security-Policy header.
 #[php_enum]
 #[php(name = "Hardened\\SecurityHeaders\\CspRule")]
-#[derive(Debug, Eq, PartialEq, Hash, Display, Ord, PartialOrd, Clone)]
-#[strum(serialize_all = "kebab-case")]
+#[derive(Debug, Eq, PartialEq, Hash, Display, EnumString, Ord, PartialOrd, Clone)]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
 pub enum Rule {
     /// Fallback for other fetch directives.
     #[php(value = "default-src")]
@@ -141,8 +145,8 @@ pub enum Rule {
 /// and sandbox flags that can appear after a directive name.
 #[php_enum]
 #[php(name = "Hardened\\SecurityHeaders\\CspKeyword")]
-#[derive(Clone, Display, Debug, PartialEq, Eq)]
-#[strum(serialize_all = "kebab-case")]
+#[derive(Clone, Display, EnumString, Debug, PartialEq, Eq)]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
 pub enum Keyword {
     /// The `'self'` keyword, allowing the same origin.
     #[strum(serialize = "self")]
@@ -241,17 +245,387 @@ pub enum Keyword {
     ReportSample,
 }
 
+/// Generates a fresh nonce value.
+fn generate_nonce() -> String {
+    rng()
+        .sample_iter(Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect()
+}
+
+/// Serializes directives into a header value, generating the nonce on first use.
+fn build_directives(
+    map: &BTreeMap<Rule, CspSettings>,
+    nonce: &mut Option<String>,
+) -> Result<String> {
+    let mut header = String::new();
+
+    let mut it = map.iter().peekable();
+    while let Some((src, (keywords, sources))) = it.next() {
+        header.push_str(src.to_string().as_str());
+        if keywords.is_empty() && sources.is_empty() {
+            header.push_str(" 'none'");
+        } else {
+            for keyword in keywords {
+                match keyword {
+                    Keyword::Nonce => {
+                        let nonce = nonce.get_or_insert_with(generate_nonce);
+                        write!(header, " 'nonce-{nonce}'")
+                            .map_err(|err| SecurityHeaderError::FormatError(err.to_string()))?;
+                    }
+                    _ => {
+                        write!(header, " '{keyword}'")
+                            .map_err(|err| SecurityHeaderError::FormatError(err.to_string()))?;
+                    }
+                }
+            }
+
+            for source in sources {
+                write!(header, " {source}")
+                    .map_err(|err| SecurityHeaderError::FormatError(err.to_string()))?;
+            }
+        }
+        if it.peek().is_some() {
+            header.push(';');
+        }
+    }
+
+    Ok(header)
+}
+
 pub type Source = String;
 pub type CspSettings = (Vec<Keyword>, Vec<Source>);
 
 /// Your application's CSP config.
-#[derive(Default)]
+#[derive(Default, Clone)]
 #[php_class]
 #[php(name = "Hardened\\SecurityHeaders\\ContentSecurityPolicy")]
 pub struct ContentSecurityPolicy {
     pub src_map: BTreeMap<Rule, CspSettings>,
     pub nonce: Option<String>,
+    pub report_only_map: BTreeMap<Rule, CspSettings>,
+    emitter: Emitter,
+}
+
+/// How serious an audit finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Severity {
+    /// Defeats the policy's protection against XSS.
+    High,
+    /// Weakens the policy or leaves a known bypass open.
+    Medium,
+    /// Deprecated or redundant configuration.
+    Low,
+}
+
+/// A single problem reported by `audit()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub directive: String,
+    pub message: String,
+}
+
+/// How `merge()` combines directives present in both policies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum MergeStrategy {
+    /// Allow what either policy allows.
+    Union,
+    /// Allow only what both policies allow.
+    Intersection,
+}
+
+/// Directives a fetch directive falls back to when it is not set, nearest first; `None` for
+/// directives without fallback (document, navigation and reporting directives).
+fn fallbacks(rule: &Rule) -> Option<&'static [Rule]> {
+    match rule {
+        Rule::DefaultSrc => Some(&[]),
+        Rule::ScriptSrcElem | Rule::ScriptSrcAttr => Some(&[Rule::ScriptSrc, Rule::DefaultSrc]),
+        Rule::StyleSrcElem | Rule::StyleSrcAttr => Some(&[Rule::StyleSrc, Rule::DefaultSrc]),
+        Rule::WorkerSrc => Some(&[Rule::ChildSrc, Rule::ScriptSrc, Rule::DefaultSrc]),
+        Rule::ScriptSrc
+        | Rule::StyleSrc
+        | Rule::ImgSrc
+        | Rule::ConnectSrc
+        | Rule::FontSrc
+        | Rule::ChildSrc
+        | Rule::ManifestSrc
+        | Rule::MediaSrc
+        | Rule::ObjectSrc
+        | Rule::PrefetchSrc => Some(&[Rule::DefaultSrc]),
+        _ => None,
+    }
+}
+
+/// What a policy enforces for a fetch directive: its own value, else the nearest fallback,
+/// else `*` (the policy does not restrict it).
+fn effective_fetch(map: &BTreeMap<Rule, CspSettings>, rule: &Rule, chain: &[Rule]) -> CspSettings {
+    std::iter::once(rule)
+        .chain(chain)
+        .find_map(|rule| map.get(rule))
+        .cloned()
+        .unwrap_or_else(|| (Vec::new(), vec!["*".into()]))
+}
+
+/// Whether `source` is allowed by a source list containing `*`, which matches every host and
+/// network scheme, but not `data:`, `blob:`, `filesystem:` or quoted tokens such as hashes.
+fn wildcard_covers(sources: &[Source], source: &str) -> bool {
+    let lower = source.to_ascii_lowercase();
+    sources.iter().any(|source| source == "*")
+        && !lower.starts_with('\'')
+        && !["data:", "blob:", "filesystem:"].contains(&lower.as_str())
+}
+
+/// Combines two directive values.
+fn merge_settings(
+    (our_keywords, our_sources): &CspSettings,
+    (their_keywords, their_sources): &CspSettings,
+    strategy: MergeStrategy,
+) -> CspSettings {
+    let mut keywords = Vec::new();
+    let mut sources = Vec::new();
+    match strategy {
+        MergeStrategy::Union => {
+            keywords.extend(our_keywords.iter().chain(their_keywords).cloned());
+            sources.extend(our_sources.iter().chain(their_sources).cloned());
+        }
+        MergeStrategy::Intersection => {
+            // Whatever one side allows that the other allows too, literally or through `*`
+            let keyword_allowed =
+                |keyword: &Keyword, (keywords, sources): (&[Keyword], &[Source])| {
+                    keywords.contains(keyword)
+                        || (*keyword == Keyword::SelfOrigin && sources.iter().any(|s| s == "*"))
+                };
+            let source_allowed = |source: &Source, sources: &[Source]| {
+                sources.contains(source) || wildcard_covers(sources, source)
+            };
+            keywords.extend(
+                our_keywords
+                    .iter()
+                    .filter(|keyword| keyword_allowed(keyword, (their_keywords, their_sources)))
+                    .chain(
+                        their_keywords.iter().filter(|keyword| {
+                            keyword_allowed(keyword, (our_keywords, our_sources))
+                        }),
+                    )
+                    .cloned(),
+            );
+            sources.extend(
+                our_sources
+                    .iter()
+                    .filter(|source| source_allowed(source, their_sources))
+                    .chain(
+                        their_sources
+                            .iter()
+                            .filter(|source| source_allowed(source, our_sources)),
+                    )
+                    .cloned(),
+            );
+        }
+    }
+    let mut unique_keywords = Vec::with_capacity(keywords.len());
+    for keyword in keywords {
+        if !unique_keywords.contains(&keyword) {
+            unique_keywords.push(keyword);
+        }
+    }
+    let mut unique_sources = Vec::with_capacity(sources.len());
+    for source in sources {
+        if !unique_sources.contains(&source) {
+            unique_sources.push(source);
+        }
+    }
+    (unique_keywords, unique_sources)
 }
+
+/// Merges `theirs` into `ours` directive by directive, keeping the order of first appearance.
+///
+/// A fetch directive missing from one policy stands for what that policy enforces instead:
+/// its `default-src` (or nearer fallback), or `*` if it has none. Other directives missing
+/// from one policy are copied from the other.
+fn merge_directives(
+    ours: &mut BTreeMap<Rule, CspSettings>,
+    theirs: &BTreeMap<Rule, CspSettings>,
+    strategy: MergeStrategy,
+) {
+    let original = ours.clone();
+    let rules: Vec<Rule> = original.keys().chain(theirs.keys()).cloned().collect();
+    for rule in rules {
+        let merged = match (fallbacks(&rule), original.get(&rule), theirs.get(&rule)) {
+            (Some(chain), _, _) => merge_settings(
+                &effective_fetch(&original, &rule, chain),
+                &effective_fetch(theirs, &rule, chain),
+                strategy,
+            ),
+            (None, Some(our_settings), Some(their_settings)) => {
+                merge_settings(our_settings, their_settings, strategy)
+            }
+            (None, None, Some(their_settings)) => their_settings.clone(),
+            (None, _, None) => continue,
+        };
+        ours.insert(rule, merged);
+    }
+}
+
+/// Directives that browsers no longer support or that have been superseded.
+const DEPRECATED: &[(Rule, &str)] = &[
+    (Rule::BlockAllMixedContent, "use upgrade-insecure-requests"),
+    (Rule::PluginTypes, "use object-src 'none'"),
+    (
+        Rule::PrefetchSrc,
+        "it has been removed from the specification",
+    ),
+    (
+        Rule::RequireSriFor,
+        "it has been removed from the specification",
+    ),
+    (Rule::ReportUri, "add report-to alongside it"),
+];
+
+impl ContentSecurityPolicy {
+    /// Returns the settings that apply to `rule`, falling back to `default-src`.
+    fn _effective(&self, rule: &Rule) -> Option<(&Rule, &CspSettings)> {
+        self.src_map
+            .get_key_value(rule)
+            .or_else(|| self.src_map.get_key_value(&Rule::DefaultSrc))
+    }
+
+    /// Checks the policy for common weaknesses, most severe first.
+    pub(crate) fn _audit(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let mut report = |severity, directive: &Rule, message: String| {
+            findings.push(Finding {
+                severity,
+                directive: directive.to_string(),
+                message,
+            });
+        };
+
+        match self._effective(&Rule::ScriptSrc) {
+            None => report(
+                Severity::High,
+                &Rule::ScriptSrc,
+                "script-src is missing, so any script may run; set script-src or default-src"
+                    .into(),
+            ),
+            Some((rule, (keywords, sources))) => {
+                let has_nonce = keywords.contains(&Keyword::Nonce);
+                let has_hash = sources.iter().any(|source| source.starts_with("'sha"));
+                let strict_dynamic = keywords.contains(&Keyword::StrictDynamic);
+                if keywords.contains(&Keyword::UnsafeInline) {
+                    if has_nonce || has_hash {
+                        report(
+                            Severity::Low,
+                            rule,
+                            "'unsafe-inline' is ignored when a nonce or hash is present; \
+                             keep it only as a fallback for old browsers"
+                                .into(),
+                        );
+                    } else {
+                        report(
+                            Severity::High,
+                            rule,
+                            "'unsafe-inline' allows injected inline scripts; use a nonce or hashes"
+                                .into(),
+                        );
+                    }
+                }
+                if keywords.contains(&Keyword::UnsafeEval) {
+                    report(
+                        Severity::Medium,
+                        rule,
+                        "'unsafe-eval' allows eval() and similar string-to-code APIs".into(),
+                    );
+                }
+                for source in sources {
+                    let lower = source.to_ascii_lowercase();
+                    if lower == "data:" {
+                        report(
+                            Severity::High,
+                            rule,
+                            "data: URIs allow arbitrary scripts".into(),
+                        );
+                    } else if !strict_dynamic && matches!(lower.as_str(), "*" | "http:" | "https:")
+                    {
+                        report(
+                            Severity::High,
+                            rule,
+                            format!("{source} allows scripts from any host"),
+                        );
+                    }
+                }
+            }
+        }
+
+        match self._effective(&Rule::ObjectSrc) {
+            None => report(
+                Severity::High,
+                &Rule::ObjectSrc,
+                "object-src is missing, so plugins may load scripts; set it to 'none'".into(),
+            ),
+            Some((rule, (keywords, sources))) if !(keywords.is_empty() && sources.is_empty()) => {
+                report(
+                    Severity::Medium,
+                    rule,
+                    "object-src should be 'none' unless plugins are required".into(),
+                );
+            }
+            Some(_) => {}
+        }
+
+        if !self.src_map.contains_key(&Rule::BaseUri) {
+            report(
+                Severity::Medium,
+                &Rule::BaseUri,
+                "base-uri is missing, so an injected <base> tag can redirect relative scripts; \
+                 set it to 'none' or 'self'"
+                    .into(),
+            );
+        }
+
+        for (rule, (_, sources)) in &self.src_map {
+            if !matches!(rule, Rule::ScriptSrc | Rule::ObjectSrc)
+                && !(rule == &Rule::DefaultSrc && !self.src_map.contains_key(&Rule::ScriptSrc))
+                && sources.iter().any(|source| source == "*")
+            {
+                report(
+                    Severity::Low,
+                    rule,
+                    "* allows any host; list the hosts you need".into(),
+                );
+            }
+        }
+
+        for (rule, advice) in DEPRECATED {
+            if self.src_map.contains_key(rule) {
+                report(
+                    Severity::Low,
+                    rule,
+                    format!("{rule} is deprecated; {advice}"),
+                );
+            }
+        }
+
+        findings.sort_by_key(|finding| finding.severity);
+        findings
+    }
+
+    /// Headers emitted by `send()`, in order.
+    fn _pairs(&mut self) -> Result<Vec<HeaderPair>> {
+        let mut pairs = vec![("Content-Security-Policy", self.build()?)];
+        if !self.report_only_map.is_empty() {
+            pairs.push((
+                "Content-Security-Policy-Report-Only",
+                self.build_report_only()?,
+            ));
+        }
+        Ok(pairs)
+    }
+}
+
 #[php_impl]
 impl ContentSecurityPolicy {
     /// Constructs a new `ContentSecurityPolicy` builder with no directives set.
@@ -262,10 +636,12 @@ impl ContentSecurityPolicy {
     /// # Notes
     /// - No errors are thrown.
     #[php(constructor)]
-    fn __construct() -> Self {
+    pub(crate) fn __construct() -> Self {
         Self {
             src_map: Default::default(),
             nonce: None,
+            report_only_map: BTreeMap::new(),
+            emitter: Emitter::default(),
         }
     }
 
@@ -290,7 +666,12 @@ impl ContentSecurityPolicy {
     /// # Exceptions
     /// - Throws `Exception` if any array item in `keywords` is not a string.
     /// - Throws `Exception` if `rule` is not a valid CSP directive.
-    fn set_rule(&mut self, rule: Rule, keywords: Vec<Keyword>, mut sources: Option<Vec<String>>) {
+    pub(crate) fn set_rule(
+        &mut self,
+        rule: Rule,
+        keywords: Vec<Keyword>,
+        mut sources: Option<Vec<String>>,
+    ) {
         if let Some(vec_sources) = sources.as_mut() {
             for source in vec_sources {
                 source.trim_in_place();
@@ -300,6 +681,51 @@ impl ContentSecurityPolicy {
             .insert(rule, (keywords, sources.unwrap_or_default()));
     }
 
+    /// Sets or replaces a directive of the report-only policy, which is sent alongside the
+    /// enforced one as `Content-Security-Policy-Report-Only`.
+    ///
+    /// Use it to trial a stricter policy: violations are reported but nothing is blocked.
+    /// Both policies share the same nonce.
+    ///
+    /// # Parameters
+    /// - `rule`: `CspRule` The directive.
+    /// - `keywords`: `CspKeyword[]` Keyword tokens.
+    /// - `sources`: `?string[]` Host sources.
+    fn set_report_only_rule(
+        &mut self,
+        rule: Rule,
+        keywords: Vec<Keyword>,
+        sources: Option<Vec<String>>,
+    ) {
+        let sources = sources
+            .unwrap_or_default()
+            .into_iter()
+            .map(|source| source.trim().to_string())
+            .collect();
+        self.report_only_map.insert(rule, (keywords, sources));
+    }
+
+    /// Builds the `Content-Security-Policy-Report-Only` header value.
+    ///
+    /// # Returns
+    /// - `string` The header value; empty if no report-only directive is set.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if formatting the header string fails.
+    pub(crate) fn build_report_only(&mut self) -> Result<String> {
+        build_directives(&self.report_only_map, &mut self.nonce)
+    }
+
+    /// Send only the `Content-Security-Policy-Report-Only` header via PHP `header()`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the PHP `header()` function cannot be invoked.
+    fn send_report_only(&mut self) -> Result<()> {
+        let value = self.build_report_only()?;
+        self.emitter
+            .emit(&[("Content-Security-Policy-Report-Only", value)])
+    }
+
     /// Builds the `Content-Security-Policy` header value from the configured directives.
     ///
     /// # Returns
@@ -308,61 +734,37 @@ impl ContentSecurityPolicy {
     ///
     /// # Exceptions
     /// - Throws `Exception` if formatting the header string fails.
-    fn build(&mut self) -> Result<String> {
-        let mut header = String::new();
-
-        let mut it = self.src_map.iter().peekable();
-        while let Some((src, (keywords, sources))) = it.next() {
-            header.push_str(src.to_string().as_str());
-            if keywords.is_empty() && sources.is_empty() {
-                header.push_str(" 'none'");
-            } else {
-                for keyword in keywords {
-                    match keyword {
-                        Keyword::Nonce => {
-                            let nonce = if let Some(x) = self.nonce.as_ref() {
-                                x
-                            } else {
-                                self.nonce.insert(
-                                    rng()
-                                        .sample_iter(Alphanumeric)
-                                        .take(16)
-                                        .map(char::from)
-                                        .collect(),
-                                )
-                            };
-                            write!(header, " 'nonce-{nonce}'")
-                                .map_err(|err| SecurityHeaderError::FormatError(err.to_string()))?;
-                        }
-                        _ => {
-                            write!(header, " '{keyword}'")
-                                .map_err(|err| SecurityHeaderError::FormatError(err.to_string()))?;
-                        }
-                    }
-                }
+    pub(crate) fn build(&mut self) -> Result<String> {
+        build_directives(&self.src_map, &mut self.nonce)
+    }
 
-                for source in sources {
-                    write!(header, " {source}")
-                        .map_err(|err| SecurityHeaderError::FormatError(err.to_string()))?;
-                }
-            }
-            if it.peek().is_some() {
-                header.push(';');
-            }
-        }
+    /// Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+    /// headers to a worker runtime's response object.
+    ///
+    /// # Parameters
+    /// - `emitter`: `callable(string $name, string $value): void`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `emitter` is not callable.
+    fn set_emitter(&mut self, emitter: &Zval) -> Result<()> {
+        self.emitter.set(emitter)
+    }
 
-        Ok(header)
+    /// Builds the headers `send()` would emit.
+    ///
+    /// # Returns
+    /// - `list<array{string, string}>` `[name, value]` pairs in sending order.
+    fn build_pairs(&mut self) -> Result<Vec<Vec<String>>> {
+        Ok(to_php(self._pairs()?))
     }
 
-    /// Send the `Content-Security-Policy` header via PHP `header()`.
+    /// Send the `Content-Security-Policy` header via PHP `header()`, followed by
+    /// `Content-Security-Policy-Report-Only` if a report-only policy is configured.
     ///
     /// # Exceptions
     /// - Throws `Exception` if the PHP `header()` function cannot be invoked.
     fn send(&mut self) -> Result<()> {
-        let _ = Function::try_from_function("header")
-            .ok_or(SecurityHeaderError::HeaderUnavailable)?
-            .try_call(vec![&format!("content-security-policy: {}", self.build()?)]);
-        Ok(())
+        self.emitter.emit(&self._pairs()?)
     }
 
     /// Returns the most recently generated nonce, if any.
@@ -377,11 +779,254 @@ impl ContentSecurityPolicy {
     fn reset_nonce(&mut self) {
         self.nonce = None;
     }
+
+    /// Creates the strict policy recommended by Google as a starting point.
+    ///
+    /// `script-src 'nonce-…' 'strict-dynamic' 'unsafe-inline' https:; object-src 'none';
+    /// base-uri 'none'`. Browsers supporting CSP3 only trust nonced scripts (and those they
+    /// load); `https:` and `'unsafe-inline'` are fallbacks that are ignored by such browsers.
+    /// Put `nonce()` on every `<script>` tag.
+    ///
+    /// # Parameters
+    /// - `reportEndpoint`: `?string` URL for violation reports, emitted as `report-uri`.
+    ///
+    /// # Returns
+    /// - `ContentSecurityPolicy` The preset, ready to be extended.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `reportEndpoint` contains whitespace, `;` or `,`.
+    fn strict_preset(report_endpoint: Option<&str>) -> Result<Self> {
+        let mut policy = Self::__construct();
+        policy.src_map.insert(
+            Rule::ScriptSrc,
+            (
+                vec![
+                    Keyword::Nonce,
+                    Keyword::StrictDynamic,
+                    Keyword::UnsafeInline,
+                ],
+                vec!["https:".into()],
+            ),
+        );
+        policy
+            .src_map
+            .insert(Rule::ObjectSrc, CspSettings::default());
+        policy.src_map.insert(Rule::BaseUri, CspSettings::default());
+        if let Some(endpoint) = report_endpoint {
+            if endpoint.is_empty()
+                || endpoint.contains(|c: char| c.is_ascii_whitespace() || c == ';' || c == ',')
+            {
+                return Err(SecurityHeaderError::InvalidValue {
+                    header_type: "report endpoint".into(),
+                    value: endpoint.into(),
+                });
+            }
+            policy
+                .src_map
+                .insert(Rule::ReportUri, (Vec::new(), vec![endpoint.into()]));
+        }
+        Ok(policy)
+    }
+
+    /// Parses an existing `Content-Security-Policy` header value into a policy object.
+    ///
+    /// Directive names and keywords are case-insensitive. As browsers do, a repeated
+    /// directive is ignored after its first occurrence. `'none'` becomes an empty directive,
+    /// hash sources are kept as sources, and `'nonce-…'` becomes the `nonce` keyword, so a
+    /// fresh nonce is generated by `build()` instead of replaying the parsed one.
+    ///
+    /// # Parameters
+    /// - `header`: `string` The header value, e.g. `"default-src 'self'; img-src *"`.
+    ///
+    /// # Returns
+    /// - `ContentSecurityPolicy` A policy that can be modified and rebuilt.
+    ///
+    /// # Exceptions
+    /// - Throws an exception on an unknown directive or keyword.
+    pub(crate) fn parse(header: &str) -> Result<Self> {
+        let mut policy = Self::__construct();
+        for directive in header.split(';') {
+            let mut tokens = directive.split_ascii_whitespace();
+            let Some(name) = tokens.next() else {
+                continue;
+            };
+            let rule = Rule::from_str(name)
+                .map_err(|_| SecurityHeaderError::InvalidRule(name.to_string()))?;
+            let mut keywords = Vec::new();
+            let mut sources = Vec::new();
+            for token in tokens {
+                let Some(quoted) = token
+                    .strip_prefix('\'')
+                    .and_then(|token| token.strip_suffix('\''))
+                else {
+                    sources.push(token.to_string());
+                    continue;
+                };
+                let lower = quoted.to_ascii_lowercase();
+                if lower == "none" {
+                    continue;
+                }
+                if lower.starts_with("nonce-") {
+                    if !keywords.contains(&Keyword::Nonce) {
+                        keywords.push(Keyword::Nonce);
+                    }
+                } else if ["sha256-", "sha384-", "sha512-"]
+                    .iter()
+                    .any(|prefix| lower.starts_with(prefix))
+                {
+                    sources.push(token.to_string());
+                } else {
+                    keywords.push(
+                        Keyword::from_str(quoted)
+                            .map_err(|_| SecurityHeaderError::InvalidKeyword(token.to_string()))?,
+                    );
+                }
+            }
+            policy.src_map.entry(rule).or_insert((keywords, sources));
+        }
+        Ok(policy)
+    }
+
+    /// Checks the policy for common weaknesses, in the spirit of Google's CSP Evaluator.
+    ///
+    /// Checks include `'unsafe-inline'`/`'unsafe-eval'`, wildcard and `data:` script sources,
+    /// missing `object-src`/`base-uri` and deprecated directives. Intended for CI, e.g.
+    /// failing the build when any `high` finding appears.
+    ///
+    /// # Returns
+    /// - `array` Findings, most severe first: `['severity' => 'high'|'medium'|'low',
+    ///   'directive' => string, 'message' => string]`. Empty if nothing was found.
+    fn audit(&self) -> Vec<HashMap<&'static str, String>> {
+        self._audit()
+            .into_iter()
+            .map(|finding| {
+                HashMap::from([
+                    ("severity", finding.severity.to_string()),
+                    ("directive", finding.directive),
+                    ("message", finding.message),
+                ])
+            })
+            .collect()
+    }
+
+    /// Merges another policy into this one, e.g. a per-page addition into a base platform policy.
+    ///
+    /// `union` keeps every keyword and source of either policy, while `intersection` keeps
+    /// only what both allow (an empty result becomes `'none'`). A fetch directive set in only
+    /// one policy is merged with what the other enforces for it instead: its `default-src`, or
+    /// `*` without one. Other directives set in only one policy are copied as they are.
+    /// Sources are compared literally, except that `*` covers host and scheme sources, and
+    /// keep the order of first appearance, so the result is deterministic.
+    /// Report-only policies are merged the same way; this policy's nonce is kept if it
+    /// already has one.
+    ///
+    /// # Parameters
+    /// - `other`: `ContentSecurityPolicy` The policy to merge in.
+    /// - `strategy`: `string` `union` or `intersection`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `strategy` is unknown.
+    fn merge(&mut self, other: &ContentSecurityPolicy, strategy: &str) -> Result<()> {
+        let strategy =
+            MergeStrategy::from_str(strategy).map_err(|_| SecurityHeaderError::InvalidValue {
+                header_type: "CSP merge strategy".into(),
+                value: strategy.into(),
+            })?;
+        merge_directives(&mut self.src_map, &other.src_map, strategy);
+        merge_directives(&mut self.report_only_map, &other.report_only_map, strategy);
+        if self.nonce.is_none() {
+            self.nonce.clone_from(&other.nonce);
+        }
+        Ok(())
+    }
+
+    /// Adds a per-request nonce to the given directives and returns it.
+    ///
+    /// The nonce is generated once and reused by every later `build()`/`send()` until
+    /// `resetNonce()`. Directives that are not set yet are created with just the nonce.
+    ///
+    /// # Parameters
+    /// - `directives`: `CspRule[]` Directives to protect, typically `script-src` and `style-src`.
+    ///
+    /// # Returns
+    /// - `string` The nonce (without the `'nonce-'` prefix), for `nonce="…"` attributes.
+    fn enable_nonce(&mut self, directives: Vec<Rule>) -> String {
+        for rule in directives {
+            let (keywords, _) = self.src_map.entry(rule).or_default();
+            if !keywords.contains(&Keyword::Nonce) {
+                keywords.push(Keyword::Nonce);
+            }
+        }
+        self.nonce().to_string()
+    }
+
+    /// Computes a CSP hash source for an inline `<script>` or `<style>` block.
+    ///
+    /// The content must be exactly what appears between the opening and closing tags,
+    /// including whitespace, or the browser will compute a different hash.
+    ///
+    /// # Parameters
+    /// - `inlineContent`: `string` The inline block's text.
+    /// - `algo`: `string` One of `sha256`, `sha384`, `sha512`. Default: `sha256`.
+    ///
+    /// # Returns
+    /// - `string` The quoted source, e.g. `'sha256-…'`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if `algo` is not supported.
+    fn hash_source(inline_content: &str, algo: Option<&str>) -> Result<String> {
+        let algo = algo.unwrap_or("sha256");
+        let digest = match algo.to_ascii_lowercase().as_str() {
+            "sha256" => Sha256::digest(inline_content).to_vec(),
+            "sha384" => Sha384::digest(inline_content).to_vec(),
+            "sha512" => Sha512::digest(inline_content).to_vec(),
+            _ => {
+                return Err(SecurityHeaderError::InvalidValue {
+                    header_type: "CSP hash algorithm".into(),
+                    value: algo.into(),
+                });
+            }
+        };
+        Ok(format!(
+            "'{}-{}'",
+            algo.to_ascii_lowercase(),
+            BASE64.encode(&digest)
+        ))
+    }
+
+    /// Allows one specific inline `<script>` block by adding its SHA-256 hash to `script-src`.
+    ///
+    /// This permits inline scripts without nonces, so pages can be cached. `script-src` is
+    /// created if it is not set yet.
+    ///
+    /// # Parameters
+    /// - `content`: `string` The script's text, exactly as it appears between the tags.
+    ///
+    /// # Returns
+    /// - `string` The hash source that was added.
+    fn allow_inline_script(&mut self, content: &str) -> Result<String> {
+        let source = Self::hash_source(content, None)?;
+        let (_, sources) = self.src_map.entry(Rule::ScriptSrc).or_default();
+        if !sources.contains(&source) {
+            sources.push(source.clone());
+        }
+        Ok(source)
+    }
+
+    /// Returns the nonce for templates, generating it if none has been generated yet.
+    ///
+    /// Unlike `getNonce()` this never returns `null`, so it can be called before `build()`.
+    ///
+    /// # Returns
+    /// - `string` The nonce (without the `'nonce-'` prefix).
+    pub(crate) fn nonce(&mut self) -> &str {
+        self.nonce.get_or_insert_with(generate_nonce)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ContentSecurityPolicy, Keyword, Rule};
+    use super::{ContentSecurityPolicy, Keyword, Rule, Severity};
     use crate::run_php_example;
 
     #[test]
@@ -457,6 +1102,206 @@ mod tests {
         assert_ne!(nonce1, nonce2, "nonce after reset should differ");
     }
 
+    #[test]
+    fn enable_nonce_injects_into_directives() {
+        let mut csp = ContentSecurityPolicy::default();
+        csp.set_rule(Rule::ScriptSrc, vec![Keyword::SelfOrigin], None);
+        let nonce = csp.enable_nonce(vec![Rule::ScriptSrc, Rule::StyleSrc]);
+        assert_eq!(nonce.len(), 16);
+        assert_eq!(csp.nonce(), nonce);
+        // enabling again keeps the nonce and does not duplicate the source
+        assert_eq!(csp.enable_nonce(vec![Rule::ScriptSrc]), nonce);
+        assert_eq!(
+            csp.build().unwrap(),
+            format!("script-src 'self' 'nonce-{nonce}';style-src 'nonce-{nonce}'")
+        );
+    }
+
+    #[test]
+    fn hash_sources() {
+        // echo -n "alert('Hello, world.');" | openssl sha256 -binary | base64
+        assert_eq!(
+            ContentSecurityPolicy::hash_source("alert('Hello, world.');", None).unwrap(),
+            "'sha256-qznLcsROx4GACP2dm0UCKCzCG+HiZ1guq6ZZDob/Tng='"
+        );
+        assert_eq!(
+            ContentSecurityPolicy::hash_source("", Some("SHA384")).unwrap(),
+            "'sha384-OLBgp1GsljhM2TJ+sbHjaiH9txEUvgdDTAzHv2P24donTt6/529l+9Ua0vFImLlb'"
+        );
+        assert!(ContentSecurityPolicy::hash_source("", Some("md5")).is_err());
+
+        let mut csp = ContentSecurityPolicy::default();
+        let source = csp.allow_inline_script("init();").unwrap();
+        csp.allow_inline_script("init();").unwrap();
+        assert_eq!(csp.build().unwrap(), format!("script-src {source}"));
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let mut csp = ContentSecurityPolicy::parse(
+            "Default-Src 'SELF' https://cdn.example.com; object-src 'none';; \
+             script-src 'self' 'nonce-stale' 'sha256-abc='; default-src *",
+        )
+        .unwrap();
+        assert_eq!(
+            csp.src_map.get(&Rule::DefaultSrc),
+            Some(&(
+                vec![Keyword::SelfOrigin],
+                vec!["https://cdn.example.com".to_string()]
+            ))
+        );
+        let header = csp.build().unwrap();
+        let nonce = csp.get_nonce().unwrap();
+        assert_ne!(nonce, "stale");
+        assert_eq!(
+            header,
+            format!(
+                "default-src 'self' https://cdn.example.com;script-src 'self' 'nonce-{nonce}' \
+                 'sha256-abc=';object-src 'none'"
+            )
+        );
+        assert!(ContentSecurityPolicy::parse("default-src 'selfish'").is_err());
+        assert!(ContentSecurityPolicy::parse("made-up-src 'self'").is_err());
+    }
+
+    #[test]
+    fn audit_findings() {
+        let weak = ContentSecurityPolicy::parse(
+            "default-src *; script-src 'self' 'unsafe-inline' https: data:; report-uri /csp",
+        )
+        .unwrap();
+        let findings = weak._audit();
+        let summary: Vec<_> = findings
+            .iter()
+            .map(|finding| (finding.severity, finding.directive.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Severity::High, "script-src"),
+                (Severity::High, "script-src"),
+                (Severity::High, "script-src"),
+                (Severity::Medium, "default-src"),
+                (Severity::Medium, "base-uri"),
+                (Severity::Low, "default-src"),
+                (Severity::Low, "report-uri"),
+            ]
+        );
+
+        let strict = ContentSecurityPolicy::parse(
+            "script-src 'nonce-x' 'strict-dynamic' 'unsafe-inline' https:; \
+             object-src 'none'; base-uri 'none'",
+        )
+        .unwrap();
+        let findings = strict._audit();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Low);
+
+        let empty = ContentSecurityPolicy::default()._audit();
+        assert_eq!(empty.len(), 3);
+        assert!(empty[..2].iter().all(|f| f.severity == Severity::High));
+    }
+
+    #[test]
+    fn strict_preset() {
+        let mut csp = ContentSecurityPolicy::strict_preset(Some("/csp-report")).unwrap();
+        let header = csp.build().unwrap();
+        let nonce = csp.nonce().to_string();
+        assert_eq!(
+            header,
+            format!(
+                "script-src 'nonce-{nonce}' 'strict-dynamic' 'unsafe-inline' https:;\
+                 object-src 'none';base-uri 'none';report-uri /csp-report"
+            )
+        );
+        assert!(
+            csp._audit()
+                .iter()
+                .all(|finding| finding.severity == Severity::Low)
+        );
+        assert!(ContentSecurityPolicy::strict_preset(Some("/a; script-src *")).is_err());
+        assert!(
+            !ContentSecurityPolicy::strict_preset(None)
+                .unwrap()
+                .src_map
+                .contains_key(&Rule::ReportUri)
+        );
+    }
+
+    #[test]
+    fn report_only_policy() {
+        let mut csp = ContentSecurityPolicy::default();
+        csp.set_rule(
+            Rule::ScriptSrc,
+            vec![Keyword::SelfOrigin, Keyword::Nonce],
+            None,
+        );
+        assert_eq!(csp.build_report_only().unwrap(), "");
+        csp.set_report_only_rule(
+            Rule::ScriptSrc,
+            vec![Keyword::Nonce, Keyword::StrictDynamic],
+            None,
+        );
+        csp.set_report_only_rule(Rule::ReportUri, vec![], Some(vec![" /csp ".into()]));
+        let enforced = csp.build().unwrap();
+        let report_only = csp.build_report_only().unwrap();
+        let nonce = csp.nonce().to_string();
+        assert_eq!(enforced, format!("script-src 'self' 'nonce-{nonce}'"));
+        assert_eq!(
+            report_only,
+            format!("script-src 'nonce-{nonce}' 'strict-dynamic';report-uri /csp")
+        );
+    }
+
+    #[test]
+    fn build_pairs() {
+        let mut csp = ContentSecurityPolicy::default();
+        csp.set_rule(Rule::DefaultSrc, vec![Keyword::SelfOrigin], None);
+        assert_eq!(
+            csp.build_pairs().unwrap(),
+            [["Content-Security-Policy", "default-src 'self'"]]
+        );
+        csp.set_report_only_rule(Rule::ObjectSrc, vec![], None);
+        assert_eq!(
+            csp.build_pairs().unwrap(),
+            [
+                ["Content-Security-Policy", "default-src 'self'"],
+                ["Content-Security-Policy-Report-Only", "object-src 'none'"],
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_policies() {
+        let base = || {
+            ContentSecurityPolicy::parse(
+                "default-src 'self'; script-src 'self' https://cdn.example.com; child-src 'none'",
+            )
+            .unwrap()
+        };
+        let page = ContentSecurityPolicy::parse(
+            "script-src 'self' https://www.youtube.com; child-src https://www.youtube.com; img-src *",
+        )
+        .unwrap();
+
+        let mut union = base();
+        union.merge(&page, "union").unwrap();
+        assert_eq!(
+            union.build().unwrap(),
+            "default-src 'self' *;script-src 'self' https://cdn.example.com \
+             https://www.youtube.com;img-src 'self' *;child-src https://www.youtube.com"
+        );
+
+        let mut intersection = base();
+        intersection.merge(&page, "Intersection").unwrap();
+        assert_eq!(
+            intersection.build().unwrap(),
+            "default-src 'self';script-src 'self';img-src 'self';child-src 'none'"
+        );
+
+        assert!(base().merge(&page, "xor").is_err());
+    }
+
     #[test]
     fn php_example() -> crate::TestResult {
         run_php_example("security-headers/content-security-policy")?;
@@ -0,0 +1,107 @@
+use super::{Error, Result};
+use ext_php_rs::prelude::ZendCallable;
+use ext_php_rs::types::Zval;
+use ext_php_rs::zend::Function;
+use std::collections::HashMap;
+
+/// A header name and value, in the order they are sent.
+pub type HeaderPair = (&'static str, String);
+
+/// Delivers headers on behalf of `send()`.
+///
+/// By default headers go through PHP `header()`. Under worker runtimes (Swoole, RoadRunner,
+/// FrankenPHP workers) a callable set with `setEmitter()` receives each `(name, value)` pair
+/// instead, so it can attach them to the framework's response object.
+#[derive(Default, Debug)]
+pub struct Emitter {
+    callable: Option<Zval>,
+}
+
+impl Clone for Emitter {
+    fn clone(&self) -> Self {
+        Self {
+            callable: self.callable.as_ref().map(Zval::shallow_clone),
+        }
+    }
+}
+
+impl Emitter {
+    /// Replaces PHP `header()` with `callable`, invoked as `callable(string $name, string $value)`.
+    pub(crate) fn set(&mut self, callable: &Zval) -> Result<()> {
+        if !callable.is_callable() {
+            return Err(Error::EmitterNotCallable);
+        }
+        self.callable = Some(callable.shallow_clone());
+        Ok(())
+    }
+
+    /// Emits `pairs` in order.
+    ///
+    /// With PHP `header()`, the first header of each name replaces one sent earlier (so
+    /// calling `send()` twice does not duplicate it); repeated names within `pairs` and
+    /// `Set-Cookie` are appended.
+    pub(crate) fn emit(&self, pairs: &[HeaderPair]) -> Result<()> {
+        if let Some(callable) = &self.callable {
+            let callable =
+                ZendCallable::new(callable).map_err(|err| Error::EmitterFailed(err.to_string()))?;
+            for (name, value) in pairs {
+                let name = (*name).to_string();
+                callable
+                    .try_call(vec![&name, value])
+                    .map_err(|err| Error::EmitterFailed(err.to_string()))?;
+            }
+            return Ok(());
+        }
+        let header_fn = Function::try_from_function("header").ok_or(Error::HeaderUnavailable)?;
+        let mut sent: Vec<&str> = Vec::new();
+        for (name, value) in pairs {
+            let replace = !name.eq_ignore_ascii_case("Set-Cookie") && !sent.contains(name);
+            sent.push(name);
+            header_fn
+                .try_call(vec![&format!("{name}: {value}"), &replace])
+                .map_err(|err| Error::HeaderCallFailed(err.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts header pairs into the `list<array{0: string, 1: string}>` returned by `buildPairs()`.
+pub(crate) fn to_php(pairs: Vec<HeaderPair>) -> Vec<Vec<String>> {
+    pairs
+        .into_iter()
+        .map(|(name, value)| vec![name.to_string(), value])
+        .collect()
+}
+
+/// Collapses pairs into the name => value map returned by `build()`. Repeated headers are
+/// joined with `, `, as HTTP does for list-valued headers; use `buildPairs()` where that
+/// is not possible (`Set-Cookie`).
+pub(crate) fn to_map(pairs: Vec<HeaderPair>) -> HashMap<&'static str, String> {
+    let mut headers: HashMap<&'static str, String> = HashMap::new();
+    for (name, value) in pairs {
+        headers
+            .entry(name)
+            .and_modify(|joined| {
+                joined.push_str(", ");
+                joined.push_str(&value);
+            })
+            .or_insert(value);
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_map;
+
+    #[test]
+    fn to_map_joins_repeated_headers() {
+        let headers = to_map(vec![
+            ("Report-To", "{\"group\":\"a\"}".into()),
+            ("X-Frame-Options", "DENY".into()),
+            ("Report-To", "{\"group\":\"b\"}".into()),
+        ]);
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["Report-To"], r#"{"group":"a"}, {"group":"b"}"#);
+    }
+}
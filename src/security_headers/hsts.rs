@@ -1,14 +1,28 @@
 use super::Error as SecurityHeaderError;
 use super::Result;
-use ext_php_rs::zend::Function;
+use super::emitter::{Emitter, HeaderPair, to_php};
+use ext_php_rs::types::Zval;
 use ext_php_rs::{php_class, php_impl};
+
+/// Minimum `max-age` accepted by the HSTS preload list (one year).
+const PRELOAD_MIN_MAX_AGE: u64 = 31_536_000;
+
 /// HTTP Strict Transport Security (HSTS) header builder.
 #[php_class]
 #[php(name = "Hardened\\SecurityHeaders\\StrictTransportSecurity")]
+#[derive(Clone)]
 pub struct StrictTransportSecurity {
     max_age: u64,
     include_subdomains: bool,
     preload: bool,
+    emitter: Emitter,
+}
+
+impl StrictTransportSecurity {
+    /// Headers emitted by `send()`, in order.
+    fn _pairs(&self) -> Result<Vec<HeaderPair>> {
+        Ok(vec![("Strict-Transport-Security", self.build()?)])
+    }
 }
 
 #[php_impl]
@@ -17,11 +31,12 @@ impl StrictTransportSecurity {
     ///
     /// # Returns
     /// - `Hsts` New instance with `max-age=0`, no subdomains, no preload.
-    fn __construct() -> Self {
+    pub(crate) fn __construct() -> Self {
         Self {
             max_age: 0,
             include_subdomains: false,
             preload: false,
+            emitter: Emitter::default(),
         }
     }
 
@@ -32,7 +47,7 @@ impl StrictTransportSecurity {
     ///
     /// # Returns
     /// - `void`
-    fn max_age(&mut self, max_age: u64) {
+    pub(crate) fn max_age(&mut self, max_age: u64) {
         self.max_age = max_age;
     }
 
@@ -43,7 +58,7 @@ impl StrictTransportSecurity {
     ///
     /// # Returns
     /// - `void`
-    fn include_sub_domains(&mut self, enable: bool) {
+    pub(crate) fn include_sub_domains(&mut self, enable: bool) {
         self.include_subdomains = enable;
     }
 
@@ -58,11 +73,43 @@ impl StrictTransportSecurity {
         self.preload = enable;
     }
 
+    /// Lists the reasons the domain would be rejected by the HSTS preload list
+    /// (<https://hstspreload.org>).
+    ///
+    /// # Returns
+    /// - `string[]` Problems; empty if the header is eligible for preloading.
+    fn validate_for_preload(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.max_age < PRELOAD_MIN_MAX_AGE {
+            problems.push(format!(
+                "max-age must be at least {PRELOAD_MIN_MAX_AGE} seconds, got {}",
+                self.max_age
+            ));
+        }
+        if !self.include_subdomains {
+            problems.push("includeSubDomains must be set".into());
+        }
+        if !self.preload {
+            problems.push("preload must be set".into());
+        }
+        problems
+    }
+
     /// Builds the `Strict-Transport-Security` header value.
     ///
     /// # Returns
     /// - `string` e.g. `"max-age=31536000; includeSubDomains; preload"`.
-    fn build(&self) -> String {
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `preload` is set without `includeSubDomains`, which the
+    ///   preload list always rejects.
+    pub(crate) fn build(&self) -> Result<String> {
+        if self.preload && !self.include_subdomains {
+            return Err(SecurityHeaderError::InvalidValue {
+                header_type: "Strict-Transport-Security".into(),
+                value: "preload requires includeSubDomains".into(),
+            });
+        }
         let mut header = format!("max-age={}", self.max_age);
         if self.include_subdomains {
             header.push_str("; includeSubDomains");
@@ -70,23 +117,36 @@ impl StrictTransportSecurity {
         if self.preload {
             header.push_str("; preload");
         }
-        header
+        Ok(header)
+    }
+
+    /// Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+    /// headers to a worker runtime's response object.
+    ///
+    /// # Parameters
+    /// - `emitter`: `callable(string $name, string $value): void`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `emitter` is not callable.
+    fn set_emitter(&mut self, emitter: &Zval) -> Result<()> {
+        self.emitter.set(emitter)
+    }
+
+    /// Builds the headers `send()` would emit.
+    ///
+    /// # Returns
+    /// - `list<array{string, string}>` `[name, value]` pairs in sending order.
+    fn build_pairs(&self) -> Result<Vec<Vec<String>>> {
+        Ok(to_php(self._pairs()?))
     }
 
     /// Sends the `Strict-Transport-Security` header via PHP `header()` function.
     ///
     /// # Exceptions
+    /// - Throws `Exception` if the configuration is invalid (see `build()`).
     /// - Throws `Exception` if PHP `header()` cannot be invoked.
     fn send(&self) -> Result<()> {
-        Function::try_from_function("header")
-            .ok_or(SecurityHeaderError::HeaderUnavailable)?
-            .try_call(vec![&format!(
-                "Strict-Transport-Security: {}",
-                self.build()
-            )])
-            .map_err(|err| SecurityHeaderError::HeaderCallFailed(format!("{err:?}")))?;
-
-        Ok(())
+        self.emitter.emit(&self._pairs()?)
     }
 }
 
@@ -98,28 +158,28 @@ mod tests {
     #[test]
     fn test_default_build() {
         let h = StrictTransportSecurity::__construct();
-        assert_eq!(h.build(), "max-age=0");
+        assert_eq!(h.build().unwrap(), "max-age=0");
     }
 
     #[test]
     fn test_max_age_only() {
         let mut h = StrictTransportSecurity::__construct();
         h.max_age(31536000);
-        assert_eq!(h.build(), "max-age=31536000");
+        assert_eq!(h.build().unwrap(), "max-age=31536000");
     }
 
     #[test]
     fn test_include_subdomains_only() {
         let mut h = StrictTransportSecurity::__construct();
         h.include_sub_domains(true);
-        assert_eq!(h.build(), "max-age=0; includeSubDomains");
+        assert_eq!(h.build().unwrap(), "max-age=0; includeSubDomains");
     }
 
     #[test]
-    fn test_preload_only() {
+    fn test_preload_requires_subdomains() {
         let mut h = StrictTransportSecurity::__construct();
         h.preload(true);
-        assert_eq!(h.build(), "max-age=0; preload");
+        assert!(h.build().is_err());
     }
 
     #[test]
@@ -128,7 +188,25 @@ mod tests {
         h.max_age(86400);
         h.include_sub_domains(true);
         h.preload(true);
-        assert_eq!(h.build(), "max-age=86400; includeSubDomains; preload");
+        assert_eq!(
+            h.build().unwrap(),
+            "max-age=86400; includeSubDomains; preload"
+        );
+    }
+
+    #[test]
+    fn test_validate_for_preload() {
+        let mut h = StrictTransportSecurity::__construct();
+        assert_eq!(h.validate_for_preload().len(), 3);
+        h.max_age(86400);
+        h.include_sub_domains(true);
+        h.preload(true);
+        assert_eq!(
+            h.validate_for_preload(),
+            ["max-age must be at least 31536000 seconds, got 86400"]
+        );
+        h.max_age(63072000);
+        assert!(h.validate_for_preload().is_empty());
     }
 
     #[test]
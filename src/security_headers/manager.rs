@@ -0,0 +1,680 @@
+use super::audit;
+use super::cross_origin::embedder_policy::EmbedderPolicy;
+use super::cross_origin::opener_policy::OpenerPolicy;
+use super::cross_origin::resource_policy::ResourcePolicy;
+use super::csp::{ContentSecurityPolicy, Keyword, Rule};
+use super::emitter::{Emitter, HeaderPair, to_map, to_php};
+use super::hsts::StrictTransportSecurity;
+use super::permissions::{Feature, PermissionsPolicy};
+use super::referrer_policy::ReferrerPolicy;
+use super::whatnot::{FrameOptions, PermittedCrossDomainPolicies, Whatnot, XssProtection};
+use super::{Error as SecurityHeaderError, Result};
+use ext_php_rs::types::Zval;
+use ext_php_rs::{php_class, php_impl};
+use std::collections::HashMap;
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+
+/// Every header the manager can emit, for `disable()`/`enable()`.
+const MANAGED_HEADERS: [&str; 16] = [
+    "Content-Security-Policy",
+    "Content-Security-Policy-Report-Only",
+    "Strict-Transport-Security",
+    "X-Frame-Options",
+    "X-XSS-Protection",
+    "X-Content-Type-Options",
+    "X-Permitted-Cross-Domain-Policies",
+    "X-Robots-Tag",
+    "Report-To",
+    "Integrity-Policy",
+    "Integrity-Policy-Report-Only",
+    "Referrer-Policy",
+    "Permissions-Policy",
+    "Cross-Origin-Opener-Policy",
+    "Cross-Origin-Embedder-Policy",
+    "Cross-Origin-Resource-Policy",
+];
+
+/// Features denied by the default `Permissions-Policy`.
+const DENIED_FEATURES: [Feature; 8] = [
+    Feature::Camera,
+    Feature::Microphone,
+    Feature::Geolocation,
+    Feature::Payment,
+    Feature::Usb,
+    Feature::BrowsingTopics,
+    Feature::InterestCohort,
+    Feature::DisplayCapture,
+];
+
+/// Sources a local dev server (Vite, webpack) needs for scripts and hot module replacement.
+const DEV_SERVER_SOURCES: [&str; 3] = [
+    "http://localhost:*",
+    "ws://localhost:*",
+    "wss://localhost:*",
+];
+
+/// Values of the headers the production lock compares with its baseline, weakest first.
+/// `Audit::grade()` does not judge these.
+const RANKED_HEADERS: [(&str, &[&str]); 3] = [
+    (
+        "Cross-Origin-Opener-Policy",
+        &["unsafe-none", "same-origin-allow-popups", "same-origin"],
+    ),
+    (
+        "Cross-Origin-Resource-Policy",
+        &["cross-origin", "same-site", "same-origin"],
+    ),
+    (
+        "X-Permitted-Cross-Domain-Policies",
+        &["all", "by-content-type", "master-only", "none"],
+    ),
+];
+
+/// Features a `Permissions-Policy` value denies, i.e. lists with an empty allowlist.
+fn denied_features(policy: &str) -> Vec<&str> {
+    policy
+        .split(',')
+        .filter_map(|entry| entry.trim().split_once('='))
+        .filter(|(_, allowlist)| allowlist.trim() == "()")
+        .map(|(feature, _)| feature.trim())
+        .collect()
+}
+
+/// Describes the first header in `candidate` that is missing or weaker than in `baseline`,
+/// among those `Audit::grade()` does not judge: the cross-origin policies,
+/// `X-Permitted-Cross-Domain-Policies` and the features `Permissions-Policy` denies.
+fn weakened(baseline: &[HeaderPair], candidate: &[HeaderPair]) -> Option<String> {
+    let get = |pairs: &[HeaderPair], name: &str| {
+        pairs
+            .iter()
+            .find(|(header, _)| *header == name)
+            .map(|(_, value)| value.trim().to_ascii_lowercase())
+    };
+    for (header, order) in RANKED_HEADERS {
+        let Some(required) = get(baseline, header) else {
+            continue;
+        };
+        let rank = |value: &str| order.iter().position(|known| *known == value);
+        let weaker = match get(candidate, header) {
+            Some(value) => rank(&value) < rank(&required),
+            None => true,
+        };
+        if weaker {
+            return Some(format!("{header}: weaker than {required}"));
+        }
+    }
+    if let Some(required) = get(baseline, "Permissions-Policy") {
+        let policy = get(candidate, "Permissions-Policy").unwrap_or_default();
+        let denied = denied_features(&policy);
+        if let Some(feature) = denied_features(&required)
+            .into_iter()
+            .find(|feature| !denied.contains(feature))
+        {
+            return Some(format!("Permissions-Policy: {feature} is no longer denied"));
+        }
+    }
+    None
+}
+
+/// Deployment environments with their own defaults, see `Manager::forProfile()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[strum(ascii_case_insensitive)]
+pub enum Profile {
+    #[strum(to_string = "development", serialize = "dev")]
+    Development,
+    #[strum(to_string = "staging", serialize = "stage")]
+    Staging,
+    #[strum(to_string = "production", serialize = "prod")]
+    Production,
+}
+
+/// One object for the whole security header baseline.
+///
+/// Composes CSP, HSTS, `Whatnot` (frame options, nosniff, ...), `Referrer-Policy`,
+/// `Permissions-Policy` and the cross-origin policies, starting from opinionated defaults.
+/// Each component can be replaced with an instance configured by hand, and single headers
+/// can be switched off with `disable()`.
+#[php_class]
+#[php(name = "Hardened\\SecurityHeaders\\Manager")]
+#[derive(Clone)]
+pub struct Manager {
+    csp: ContentSecurityPolicy,
+    hsts: StrictTransportSecurity,
+    whatnot: Whatnot,
+    referrer_policy: ReferrerPolicy,
+    permissions_policy: PermissionsPolicy,
+    opener_policy: OpenerPolicy,
+    embedder_policy: Option<EmbedderPolicy>,
+    resource_policy: ResourcePolicy,
+    disabled: Vec<&'static str>,
+    profile: Option<Profile>,
+    emitter: Emitter,
+}
+
+impl Manager {
+    /// Resolves a header name case-insensitively to its canonical spelling.
+    ///
+    /// # Errors
+    /// - Returns `InvalidValue` if the manager does not emit this header.
+    fn _managed_header(name: &str) -> Result<&'static str> {
+        MANAGED_HEADERS
+            .into_iter()
+            .find(|header| header.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| SecurityHeaderError::InvalidValue {
+                header_type: "managed header".into(),
+                value: name.into(),
+            })
+    }
+
+    /// Every enabled, non-empty header, in sending order.
+    fn _pairs(&mut self) -> Result<Vec<HeaderPair>> {
+        let mut pairs = vec![
+            ("Content-Security-Policy", self.csp.build()?),
+            (
+                "Content-Security-Policy-Report-Only",
+                self.csp.build_report_only()?,
+            ),
+            ("Strict-Transport-Security", self.hsts.build()?),
+        ];
+        pairs.extend(self.whatnot._pairs());
+        pairs.extend([
+            ("Referrer-Policy", self.referrer_policy.build()),
+            ("Permissions-Policy", self.permissions_policy.build()),
+            ("Cross-Origin-Opener-Policy", self.opener_policy.build()),
+        ]);
+        if let Some(policy) = &self.embedder_policy {
+            pairs.push(("Cross-Origin-Embedder-Policy", policy.build()));
+        }
+        pairs.push(("Cross-Origin-Resource-Policy", self.resource_policy.build()));
+        pairs.retain(|(name, value)| !value.is_empty() && !self.disabled.contains(name));
+        Ok(pairs)
+    }
+
+    /// Applies `change`. Under the production profile the change is refused if the resulting
+    /// headers would no longer pass `Audit::grade()` without findings, or if a header the audit
+    /// does not judge would be weaker than in the production baseline.
+    ///
+    /// # Errors
+    /// - Returns `ProfileViolation` naming the first finding the change would introduce.
+    fn _change(&mut self, change: impl FnOnce(&mut Self)) -> Result<()> {
+        if self.profile != Some(Profile::Production) {
+            change(self);
+            return Ok(());
+        }
+        let mut candidate = self.clone();
+        change(&mut candidate);
+        let pairs = candidate._pairs()?;
+        let headers: Vec<(String, String)> = pairs
+            .iter()
+            .map(|(name, value)| ((*name).to_string(), value.clone()))
+            .collect();
+        if let Some(issue) = audit::grade(&headers).issues.first() {
+            return Err(SecurityHeaderError::ProfileViolation(format!(
+                "{}: {}",
+                issue.header, issue.message
+            )));
+        }
+        if let Some(violation) = weakened(&Self::__construct()?._pairs()?, &pairs) {
+            return Err(SecurityHeaderError::ProfileViolation(violation));
+        }
+        *self = candidate;
+        Ok(())
+    }
+}
+
+#[php_impl]
+impl Manager {
+    /// Constructs a manager with secure defaults:
+    ///
+    /// - `Content-Security-Policy: default-src 'self'; frame-ancestors 'none'; object-src 'none';
+    ///   base-uri 'self'; form-action 'self'`
+    /// - `Strict-Transport-Security: max-age=31536000; includeSubDomains`
+    /// - `X-Frame-Options: DENY`, `X-Content-Type-Options: nosniff`, `X-XSS-Protection: 0`,
+    ///   `X-Permitted-Cross-Domain-Policies: none`
+    /// - `Referrer-Policy: strict-origin-when-cross-origin`
+    /// - `Permissions-Policy` denying camera, microphone, geolocation, payment, USB, display
+    ///   capture and interest-based advertising
+    /// - `Cross-Origin-Opener-Policy: same-origin`, `Cross-Origin-Resource-Policy: same-origin`
+    ///
+    /// `Cross-Origin-Embedder-Policy` is not sent by default, since it blocks cross-origin
+    /// embeds that do not opt in; set it with `setEmbedderPolicy()`.
+    ///
+    /// Unlike `forProfile('production')`, the result can be changed freely.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if a default cannot be constructed (never expected).
+    fn __construct() -> Result<Self> {
+        let mut csp = ContentSecurityPolicy::__construct();
+        for (rule, keywords) in [
+            (Rule::DefaultSrc, vec![Keyword::SelfOrigin]),
+            (Rule::ObjectSrc, vec![]),
+            (Rule::BaseUri, vec![Keyword::SelfOrigin]),
+            (Rule::FormAction, vec![Keyword::SelfOrigin]),
+            (Rule::FrameAncestors, vec![]),
+        ] {
+            csp.set_rule(rule, keywords, None);
+        }
+
+        let mut hsts = StrictTransportSecurity::__construct();
+        hsts.max_age(31_536_000);
+        hsts.include_sub_domains(true);
+
+        let mut whatnot = Whatnot::__construct();
+        whatnot.set_frame_options(FrameOptions::Deny, None)?;
+        whatnot.set_nosniff(true);
+        whatnot.set_xss_protection(XssProtection::Off, None)?;
+        whatnot.set_permitted_cross_domain_policies(PermittedCrossDomainPolicies::None);
+
+        let mut permissions_policy = PermissionsPolicy::__construct();
+        for feature in DENIED_FEATURES {
+            permissions_policy.deny(feature);
+        }
+
+        Ok(Self {
+            csp,
+            hsts,
+            whatnot,
+            referrer_policy: ReferrerPolicy::__construct(Some(
+                "strict-origin-when-cross-origin".into(),
+            ))?,
+            permissions_policy,
+            opener_policy: OpenerPolicy::__construct(Some("same-origin".into()))?,
+            embedder_policy: None,
+            resource_policy: ResourcePolicy::__construct(Some("same-origin".into()))?,
+            disabled: Vec::new(),
+            profile: None,
+            emitter: Emitter::default(),
+        })
+    }
+
+    /// Constructs a manager tuned for a deployment environment, starting from the defaults of
+    /// `__construct()`:
+    ///
+    /// - `development`: no HSTS, and `script-src`/`connect-src` also allow a dev server on
+    ///   `localhost` (`http:`, `ws:` and `wss:`, any port) for hot module replacement.
+    /// - `staging`: HSTS for one day without `includeSubDomains`, so trying it out does not pin
+    ///   sibling hosts, and `X-Robots-Tag: noindex, nofollow`.
+    /// - `production`: the defaults, locked. Any later `set…()` or `disable()` call that would
+    ///   make `Audit::grade()` report a finding, or make COOP, CORP,
+    ///   `X-Permitted-Cross-Domain-Policies` or the denied `Permissions-Policy` features weaker
+    ///   than the defaults, is refused.
+    ///
+    /// # Parameters
+    /// - `profile`: `string` `development` (`dev`), `staging` (`stage`) or `production` (`prod`).
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the profile is unknown.
+    fn for_profile(profile: &str) -> Result<Self> {
+        let profile =
+            Profile::from_str(profile.trim()).map_err(|_| SecurityHeaderError::InvalidValue {
+                header_type: "profile".into(),
+                value: profile.into(),
+            })?;
+        let mut manager = Self::__construct()?;
+        match profile {
+            Profile::Development => {
+                manager.disabled.push("Strict-Transport-Security");
+                let dev_server: Vec<String> = DEV_SERVER_SOURCES
+                    .iter()
+                    .map(|s| (*s).to_string())
+                    .collect();
+                manager.csp.set_rule(
+                    Rule::ScriptSrc,
+                    vec![Keyword::SelfOrigin],
+                    Some(dev_server[..1].to_vec()),
+                );
+                manager.csp.set_rule(
+                    Rule::ConnectSrc,
+                    vec![Keyword::SelfOrigin],
+                    Some(dev_server),
+                );
+            }
+            Profile::Staging => {
+                manager.hsts.max_age(86_400);
+                manager.hsts.include_sub_domains(false);
+                manager
+                    .whatnot
+                    .set_robots_tag(vec!["noindex".into(), "nofollow".into()])?;
+            }
+            Profile::Production => {}
+        }
+        manager.profile = Some(profile);
+        Ok(manager)
+    }
+
+    /// Returns the profile given to `forProfile()`, or `null` for `new Manager()`.
+    fn profile(&self) -> Option<String> {
+        self.profile.map(|profile| profile.to_string())
+    }
+
+    /// Replaces the Content-Security-Policy (a copy of `$csp` is kept; configure it first,
+    /// including `nonce()` if templates need the nonce).
+    ///
+    /// # Parameters
+    /// - `csp`: `ContentSecurityPolicy`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` under the production profile if the result would be weaker.
+    fn set_csp(&mut self, csp: &ContentSecurityPolicy) -> Result<()> {
+        self._change(|manager| manager.csp = csp.clone())
+    }
+
+    /// Replaces the Strict-Transport-Security builder.
+    ///
+    /// # Parameters
+    /// - `hsts`: `StrictTransportSecurity`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` under the production profile if the result would be weaker.
+    fn set_hsts(&mut self, hsts: &StrictTransportSecurity) -> Result<()> {
+        self._change(|manager| manager.hsts = hsts.clone())
+    }
+
+    /// Replaces the `Whatnot` builder (frame options, nosniff, robots, integrity policy, ...).
+    ///
+    /// # Parameters
+    /// - `whatnot`: `Whatnot`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` under the production profile if the result would be weaker.
+    fn set_whatnot(&mut self, whatnot: &Whatnot) -> Result<()> {
+        self._change(|manager| manager.whatnot = whatnot.clone())
+    }
+
+    /// Replaces the Referrer-Policy builder.
+    ///
+    /// # Parameters
+    /// - `policy`: `ReferrerPolicy`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` under the production profile if the result would be weaker.
+    fn set_referrer_policy(&mut self, policy: &ReferrerPolicy) -> Result<()> {
+        self._change(|manager| manager.referrer_policy = policy.clone())
+    }
+
+    /// Replaces the Permissions-Policy builder.
+    ///
+    /// # Parameters
+    /// - `policy`: `PermissionsPolicy`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` under the production profile if the result would be weaker.
+    fn set_permissions_policy(&mut self, policy: &PermissionsPolicy) -> Result<()> {
+        self._change(|manager| manager.permissions_policy = policy.clone())
+    }
+
+    /// Replaces the Cross-Origin-Opener-Policy builder.
+    ///
+    /// # Parameters
+    /// - `policy`: `CrossOrigin\OpenerPolicy`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` under the production profile if the result would be weaker.
+    fn set_opener_policy(&mut self, policy: &OpenerPolicy) -> Result<()> {
+        self._change(|manager| manager.opener_policy = policy.clone())
+    }
+
+    /// Sets the Cross-Origin-Embedder-Policy builder (not sent until set).
+    ///
+    /// # Parameters
+    /// - `policy`: `CrossOrigin\EmbedderPolicy`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` under the production profile if the result would be weaker.
+    fn set_embedder_policy(&mut self, policy: &EmbedderPolicy) -> Result<()> {
+        self._change(|manager| manager.embedder_policy = Some(policy.clone()))
+    }
+
+    /// Replaces the Cross-Origin-Resource-Policy builder.
+    ///
+    /// # Parameters
+    /// - `policy`: `CrossOrigin\ResourcePolicy`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` under the production profile if the result would be weaker.
+    fn set_resource_policy(&mut self, policy: &ResourcePolicy) -> Result<()> {
+        self._change(|manager| manager.resource_policy = policy.clone())
+    }
+
+    /// Stops emitting a header, e.g. `disable('Strict-Transport-Security')` on plain-HTTP
+    /// development hosts.
+    ///
+    /// # Parameters
+    /// - `header`: `string` Header name, case-insensitive.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the manager does not emit this header.
+    /// - Throws `Exception` under the production profile if the header protects the response.
+    fn disable(&mut self, header: &str) -> Result<()> {
+        let header = Self::_managed_header(header)?;
+        self._change(|manager| {
+            if !manager.disabled.contains(&header) {
+                manager.disabled.push(header);
+            }
+        })
+    }
+
+    /// Emits a previously disabled header again.
+    ///
+    /// # Parameters
+    /// - `header`: `string` Header name, case-insensitive.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the manager does not emit this header.
+    fn enable(&mut self, header: &str) -> Result<()> {
+        let header = Self::_managed_header(header)?;
+        self.disabled.retain(|disabled| *disabled != header);
+        Ok(())
+    }
+
+    /// Builds every enabled, non-empty header.
+    ///
+    /// # Returns
+    /// - `array<string,string>` Header names mapped to values.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if a component is misconfigured (e.g. HSTS `preload` without
+    ///   `includeSubDomains`).
+    fn build(&mut self) -> Result<HashMap<&'static str, String>> {
+        Ok(to_map(self._pairs()?))
+    }
+
+    /// Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+    /// headers to a worker runtime's response object. Emitters set on the component
+    /// builders are not used by the manager.
+    ///
+    /// # Parameters
+    /// - `emitter`: `callable(string $name, string $value): void`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `emitter` is not callable.
+    fn set_emitter(&mut self, emitter: &Zval) -> Result<()> {
+        self.emitter.set(emitter)
+    }
+
+    /// Builds every enabled, non-empty header.
+    ///
+    /// # Returns
+    /// - `list<array{string, string}>` `[name, value]` pairs in sending order.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if a component is misconfigured.
+    fn build_pairs(&mut self) -> Result<Vec<Vec<String>>> {
+        Ok(to_php(self._pairs()?))
+    }
+
+    /// Sends every enabled header via PHP `header()`, or the callable set with `setEmitter()`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if a component is misconfigured.
+    /// - Throws `Exception` if PHP `header()` cannot be invoked or the emitter fails.
+    fn send(&mut self) -> Result<()> {
+        let pairs = self._pairs()?;
+        self.emitter.emit(&pairs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Manager, SecurityHeaderError, audit};
+    use crate::security_headers::cross_origin::opener_policy::OpenerPolicy;
+    use crate::security_headers::cross_origin::resource_policy::ResourcePolicy;
+    use crate::security_headers::hsts::StrictTransportSecurity;
+    use crate::security_headers::permissions::Feature;
+
+    #[test]
+    fn secure_defaults() {
+        let mut manager = Manager::__construct().unwrap();
+        let headers = manager.build().unwrap();
+        let get = |name| headers.get(name).map(String::as_str);
+        assert_eq!(
+            get("Content-Security-Policy"),
+            Some(
+                "default-src 'self';frame-ancestors 'none';object-src 'none';base-uri 'self';\
+                 form-action 'self'"
+            )
+        );
+        assert_eq!(
+            get("Strict-Transport-Security"),
+            Some("max-age=31536000; includeSubDomains")
+        );
+        assert_eq!(get("X-Frame-Options"), Some("DENY"));
+        assert_eq!(get("X-Content-Type-Options"), Some("nosniff"));
+        assert_eq!(get("X-XSS-Protection"), Some("0"));
+        assert_eq!(
+            get("Referrer-Policy"),
+            Some("strict-origin-when-cross-origin")
+        );
+        assert!(get("Permissions-Policy").unwrap().contains("camera=()"));
+        assert_eq!(get("Cross-Origin-Opener-Policy"), Some("same-origin"));
+        assert_eq!(get("Cross-Origin-Resource-Policy"), Some("same-origin"));
+        assert_eq!(get("Cross-Origin-Embedder-Policy"), None);
+        assert_eq!(get("Content-Security-Policy-Report-Only"), None);
+    }
+
+    #[test]
+    fn disable_and_replace() {
+        let mut manager = Manager::__construct().unwrap();
+        manager.disable("strict-transport-security").unwrap();
+        manager.disable("X-Frame-Options").unwrap();
+        assert!(manager.disable("X-Made-Up").is_err());
+        let headers = manager.build().unwrap();
+        assert!(!headers.contains_key("Strict-Transport-Security"));
+        assert!(!headers.contains_key("X-Frame-Options"));
+        assert!(headers.contains_key("X-Content-Type-Options"));
+
+        manager.enable("Strict-Transport-Security").unwrap();
+        let mut hsts = StrictTransportSecurity::__construct();
+        hsts.max_age(600);
+        manager.set_hsts(&hsts).unwrap();
+        assert_eq!(
+            manager
+                .build()
+                .unwrap()
+                .get("Strict-Transport-Security")
+                .map(String::as_str),
+            Some("max-age=600")
+        );
+    }
+
+    #[test]
+    fn build_pairs_in_order() {
+        let mut manager = Manager::__construct().unwrap();
+        manager.disable("Permissions-Policy").unwrap();
+        let names: Vec<String> = manager
+            .build_pairs()
+            .unwrap()
+            .into_iter()
+            .map(|pair| pair[0].clone())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "Content-Security-Policy",
+                "Strict-Transport-Security",
+                "X-Frame-Options",
+                "X-XSS-Protection",
+                "X-Content-Type-Options",
+                "X-Permitted-Cross-Domain-Policies",
+                "Referrer-Policy",
+                "Cross-Origin-Opener-Policy",
+                "Cross-Origin-Resource-Policy",
+            ]
+        );
+    }
+
+    #[test]
+    fn profiles() {
+        let mut production = Manager::for_profile("Production").unwrap();
+        assert_eq!(production.profile().as_deref(), Some("production"));
+        let headers: Vec<(String, String)> = production
+            ._pairs()
+            .unwrap()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        assert_eq!(audit::grade(&headers).grade, "A+");
+        assert!(matches!(
+            production.disable("Strict-Transport-Security"),
+            Err(SecurityHeaderError::ProfileViolation(_))
+        ));
+        let mut hsts = StrictTransportSecurity::__construct();
+        hsts.max_age(600);
+        assert!(production.set_hsts(&hsts).is_err());
+        assert!(production.build().unwrap()["Strict-Transport-Security"].contains("31536000"));
+        production
+            .disable("Content-Security-Policy-Report-Only")
+            .unwrap();
+
+        let mut dev = Manager::for_profile("dev").unwrap();
+        let headers = dev.build().unwrap();
+        assert!(!headers.contains_key("Strict-Transport-Security"));
+        assert!(
+            headers["Content-Security-Policy"].contains("connect-src 'self' http://localhost:*")
+        );
+        dev.disable("X-Frame-Options").unwrap();
+
+        let mut staging = Manager::for_profile("staging").unwrap();
+        let headers = staging.build().unwrap();
+        assert_eq!(headers["Strict-Transport-Security"], "max-age=86400");
+        assert_eq!(headers["X-Robots-Tag"], "noindex, nofollow");
+
+        assert!(Manager::for_profile("qa").is_err());
+        assert_eq!(Manager::__construct().unwrap().profile(), None);
+    }
+
+    #[test]
+    fn production_lock_compares_with_baseline() {
+        let mut production = Manager::for_profile("production").unwrap();
+        let refused = |result| matches!(result, Err(SecurityHeaderError::ProfileViolation(_)));
+        assert!(refused(production.disable("Cross-Origin-Resource-Policy")));
+        assert!(refused(
+            production.disable("X-Permitted-Cross-Domain-Policies")
+        ));
+        let unsafe_none = OpenerPolicy::__construct(Some("unsafe-none".into())).unwrap();
+        assert!(refused(production.set_opener_policy(&unsafe_none)));
+        let cross_origin = ResourcePolicy::__construct(Some("cross-origin".into())).unwrap();
+        assert!(refused(production.set_resource_policy(&cross_origin)));
+        let mut permissions = production.permissions_policy.clone();
+        permissions.allow(Feature::Camera, vec!["self".into()]);
+        assert!(refused(production.set_permissions_policy(&permissions)));
+
+        let headers = production.build().unwrap();
+        assert_eq!(headers["Cross-Origin-Resource-Policy"], "same-origin");
+        assert_eq!(headers["Cross-Origin-Opener-Policy"], "same-origin");
+        assert_eq!(headers["X-Permitted-Cross-Domain-Policies"], "none");
+        assert!(headers["Permissions-Policy"].contains("camera=()"));
+
+        // Changes that keep or tighten the baseline still go through
+        let mut permissions = production.permissions_policy.clone();
+        permissions.deny(Feature::Microphone);
+        production.set_permissions_policy(&permissions).unwrap();
+        let same_origin = ResourcePolicy::__construct(Some("same-origin".into())).unwrap();
+        production.set_resource_policy(&same_origin).unwrap();
+
+        let mut unlocked = Manager::__construct().unwrap();
+        unlocked.set_opener_policy(&unsafe_none).unwrap();
+        unlocked.disable("Cross-Origin-Resource-Policy").unwrap();
+    }
+}
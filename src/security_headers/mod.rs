@@ -2,11 +2,16 @@ use ext_php_rs::exception::PhpException;
 use ext_php_rs::zend::ce;
 use thiserror::Error;
 
+pub mod audit;
 pub mod cross_origin;
 pub mod csp;
+pub mod emitter;
 pub mod hsts;
+pub mod manager;
 pub mod permissions;
 pub mod referrer_policy;
+pub mod reporting;
+pub mod set_cookie;
 pub mod whatnot;
 
 // Error codes for security header errors: 1700-1799
@@ -24,6 +29,10 @@ pub mod error_codes {
     pub const HEADER_UNAVAILABLE: i32 = 1710;
     pub const HEADER_CALL_FAILED: i32 = 1711;
     pub const FORMAT_ERROR: i32 = 1712;
+    pub const COOKIE_RULE: i32 = 1713;
+    pub const EMITTER_NOT_CALLABLE: i32 = 1714;
+    pub const EMITTER_FAILED: i32 = 1715;
+    pub const PROFILE_VIOLATION: i32 = 1716;
 }
 
 /// Errors that can occur during security header operations.
@@ -67,6 +76,18 @@ pub enum Error {
 
     #[error("Format error: {0}")]
     FormatError(String),
+
+    #[error("Cookie rule violated: {0}")]
+    CookieRule(String),
+
+    #[error("Emitter must be callable")]
+    EmitterNotCallable,
+
+    #[error("Emitter call failed: {0}")]
+    EmitterFailed(String),
+
+    #[error("Change would weaken the production profile: {0}")]
+    ProfileViolation(String),
 }
 
 impl Error {
@@ -86,6 +107,10 @@ impl Error {
             Error::HeaderUnavailable => error_codes::HEADER_UNAVAILABLE,
             Error::HeaderCallFailed(_) => error_codes::HEADER_CALL_FAILED,
             Error::FormatError(_) => error_codes::FORMAT_ERROR,
+            Error::CookieRule(_) => error_codes::COOKIE_RULE,
+            Error::EmitterNotCallable => error_codes::EMITTER_NOT_CALLABLE,
+            Error::EmitterFailed(_) => error_codes::EMITTER_FAILED,
+            Error::ProfileViolation(_) => error_codes::PROFILE_VIOLATION,
         }
     }
 }
@@ -1,10 +1,14 @@
+use super::emitter::{Emitter, HeaderPair, to_php};
+use super::reporting::is_valid_name;
 use super::{Error as SecurityHeaderError, Result};
 use ext_php_rs::php_const;
-use ext_php_rs::zend::Function;
+use ext_php_rs::types::Zval;
 use ext_php_rs::{php_class, php_enum, php_impl};
 use std::collections::BTreeMap;
 use std::fmt::Write;
-use strum_macros::Display;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
 
 /// Supported Permissions-Policy features.
 ///
@@ -12,8 +16,8 @@ use strum_macros::Display;
 /// (kebab-case). See: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Permissions-Policy
 #[php_enum]
 #[php(name = "Hardened\\SecurityHeaders\\PermissionsPolicyFeature")]
-#[derive(Display, Debug, Clone, PartialEq, Eq, Ord, PartialOrd)]
-#[strum(serialize_all = "kebab-case")]
+#[derive(Display, EnumString, EnumIter, Debug, Clone, PartialEq, Eq, Ord, PartialOrd)]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
 pub enum Feature {
     /// Controls whether the current document is allowed to gather information
     /// about the acceleration of the device through the Accelerometer interface.
@@ -52,6 +56,19 @@ pub enum Feature {
     #[php(value = "camera")]
     Camera,
 
+    /// Controls whether the document may scroll and zoom captured tabs through the Captured
+    /// Surface Control API.
+    #[php(value = "captured-surface-control")]
+    CapturedSurfaceControl,
+
+    /// Controls whether the document may read the clipboard through the Async Clipboard API.
+    #[php(value = "clipboard-read")]
+    ClipboardRead,
+
+    /// Controls whether the document may write to the clipboard through the Async Clipboard API.
+    #[php(value = "clipboard-write")]
+    ClipboardWrite,
+
     /// Controls access to the Compute Pressure API.
     #[php(value = "compute-pressure")]
     ComputePressure,
@@ -73,11 +90,23 @@ pub enum Feature {
     #[php(value = "display-capture")]
     DisplayCapture,
 
+    /// Controls whether the document may set `document.domain` to relax the same-origin policy.
+    #[php(value = "document-domain")]
+    DocumentDomain,
+
     /// Controls whether the current document is allowed to use the Encrypted Media
     /// Extensions API (EME). When disabled, requestMediaKeySystemAccess() will reject.
     #[php(value = "encrypted-media")]
     EncryptedMedia,
 
+    /// Controls whether tasks may run in frames that are not rendered (e.g. `display: none`).
+    #[php(value = "execution-while-not-rendered")]
+    ExecutionWhileNotRendered,
+
+    /// Controls whether tasks may run in frames that are outside the visible viewport.
+    #[php(value = "execution-while-out-of-viewport")]
+    ExecutionWhileOutOfViewport,
+
     /// Controls whether the current document is allowed to use Element.requestFullscreen().
     /// When disabled, requestFullscreen() will reject with TypeError.
     #[php(value = "fullscreen")]
@@ -112,6 +141,18 @@ pub enum Feature {
     #[php(value = "idle-detection")]
     IdleDetection,
 
+    /// Controls FLoC cohort calculation. Deprecated, but `interest-cohort=()` is still common.
+    #[php(value = "interest-cohort")]
+    InterestCohort,
+
+    /// Controls whether the document may join interest groups of the Protected Audience API.
+    #[php(value = "join-ad-interest-group")]
+    JoinAdInterestGroup,
+
+    /// Controls whether the document may use `navigator.keyboard.getLayoutMap()`.
+    #[php(value = "keyboard-map")]
+    KeyboardMap,
+
     /// Controls access to the language detection functionality of Translator & Language Detector APIs.
     #[php(value = "language-detector")]
     LanguageDetector,
@@ -147,6 +188,14 @@ pub enum Feature {
     #[php(value = "picture-in-picture")]
     PictureInPicture,
 
+    /// Controls whether the document may request Private State Tokens to be issued.
+    #[php(value = "private-state-token-issuance")]
+    PrivateStateTokenIssuance,
+
+    /// Controls whether the document may redeem Private State Tokens.
+    #[php(value = "private-state-token-redemption")]
+    PrivateStateTokenRedemption,
+
     /// Controls whether the document may use Web Authentication API to create new credentials.
     #[php(value = "publickey-credentials-create")]
     PublickeyCredentialsCreate,
@@ -155,6 +204,10 @@ pub enum Feature {
     #[php(value = "publickey-credentials-get")]
     PublickeyCredentialsGet,
 
+    /// Controls whether the document may run ad auctions of the Protected Audience API.
+    #[php(value = "run-ad-auction")]
+    RunAdAuction,
+
     /// Controls whether the document may use the Screen Wake Lock API to keep the screen on.
     #[php(value = "screen-wake-lock")]
     ScreenWakeLock,
@@ -163,6 +216,14 @@ pub enum Feature {
     #[php(value = "serial")]
     Serial,
 
+    /// Controls whether the document may use the Shared Storage API.
+    #[php(value = "shared-storage")]
+    SharedStorage,
+
+    /// Controls whether the document may use `sharedStorage.selectURL()`.
+    #[php(value = "shared-storage-select-url")]
+    SharedStorageSelectUrl,
+
     /// Controls whether the document may list and select speakers via the Output Devices API.
     #[php(value = "speaker-selection")]
     SpeakerSelection,
@@ -171,6 +232,10 @@ pub enum Feature {
     #[php(value = "storage-access")]
     StorageAccess,
 
+    /// Controls whether the document may make synchronous `XMLHttpRequest` requests.
+    #[php(value = "sync-xhr")]
+    SyncXhr,
+
     /// Controls access to the translation functionality of Translator & Language Detector APIs.
     #[php(value = "translator")]
     Translator,
@@ -179,6 +244,10 @@ pub enum Feature {
     #[php(value = "summarizer")]
     Summarizer,
 
+    /// Controls whether the document may register `unload` event handlers.
+    #[php(value = "unload")]
+    Unload,
+
     /// Controls whether the document may use the WebUSB API to connect to USB devices.
     #[php(value = "usb")]
     Usb,
@@ -199,8 +268,58 @@ pub enum Feature {
 /// Permissions-Policy header builder.
 #[php_class]
 #[php(name = "Hardened\\SecurityHeaders\\PermissionsPolicy")]
+#[derive(Clone)]
 pub struct PermissionsPolicy {
     policies: BTreeMap<Feature, Vec<String>>,
+    report_to: Option<String>,
+    emitter: Emitter,
+}
+
+/// Parses one allowlist member (`*`, `self`, `src`, `'src'` or a quoted origin).
+///
+/// # Errors
+/// - Returns `InvalidValue` for bare tokens other than the keywords above.
+fn parse_origin(token: &str) -> Result<String> {
+    match token.to_ascii_lowercase().as_str() {
+        "*" => Ok("*".into()),
+        "self" => Ok("self".into()),
+        "src" | "'src'" => Ok("src".into()),
+        _ => token
+            .strip_prefix('"')
+            .and_then(|origin| origin.strip_suffix('"'))
+            .filter(|origin| !origin.is_empty() && !origin.contains('"'))
+            .map(str::to_string)
+            .ok_or_else(|| SecurityHeaderError::InvalidValue {
+                header_type: "Permissions-Policy origin".into(),
+                value: token.into(),
+            }),
+    }
+}
+
+/// Splits a structured-field dictionary on commas that are not inside quotes.
+fn split_members(header: &str) -> Vec<&str> {
+    let mut members = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, c) in header.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                members.push(&header[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    members.push(&header[start..]);
+    members
+}
+
+impl PermissionsPolicy {
+    /// Headers emitted by `send()`, in order.
+    fn _pairs(&self) -> Vec<HeaderPair> {
+        vec![("Permissions-Policy", self.build())]
+    }
 }
 
 #[php_impl]
@@ -215,10 +334,93 @@ impl PermissionsPolicy {
     ///
     /// # Returns
     /// - `PermissionsPolicy` New instance with an empty feature map.
-    fn __construct() -> Self {
+    pub(crate) fn __construct() -> Self {
         Self {
             policies: BTreeMap::new(),
+            report_to: None,
+            emitter: Emitter::default(),
+        }
+    }
+
+    /// Parses an existing `Permissions-Policy` header value into a builder, e.g. to audit
+    /// or extend a policy set by another layer.
+    ///
+    /// Both `src` and `'src'` are accepted, bare `*`/`self` items are treated as
+    /// single-entry allowlists, and the first `report-to` parameter is kept.
+    ///
+    /// # Parameters
+    /// - `header`: `string` Header value, e.g. `camera=(), geolocation=(self "https://a.example")`.
+    ///
+    /// # Returns
+    /// - `PermissionsPolicy` The parsed policy.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if a feature is unknown or an allowlist is malformed.
+    fn parse(header: &str) -> Result<Self> {
+        let mut policy = Self::__construct();
+        for member in split_members(header) {
+            let member = member.trim();
+            if member.is_empty() {
+                continue;
+            }
+            let (name, rest) = member.split_once('=').unwrap_or((member, ""));
+            let feature = Feature::from_str(name.trim())
+                .map_err(|_| SecurityHeaderError::InvalidFeature(name.trim().to_string()))?;
+            let rest = rest.trim_start();
+            let (list, params) = if let Some(inner) = rest.strip_prefix('(') {
+                inner
+                    .split_once(')')
+                    .ok_or_else(|| SecurityHeaderError::InvalidValue {
+                        header_type: "Permissions-Policy allowlist".into(),
+                        value: member.into(),
+                    })?
+            } else {
+                rest.split_once(';').unwrap_or((rest, ""))
+            };
+            let origins = list
+                .split_ascii_whitespace()
+                .map(parse_origin)
+                .collect::<Result<Vec<_>>>()?;
+            for param in params.split(';') {
+                if let Some((key, value)) = param.split_once('=')
+                    && key.trim().eq_ignore_ascii_case("report-to")
+                    && policy.report_to.is_none()
+                {
+                    policy.report_to = Some(value.trim().to_string());
+                }
+            }
+            policy.policies.entry(feature).or_insert(origins);
         }
+        Ok(policy)
+    }
+
+    /// Lists every feature token this builder knows about.
+    ///
+    /// # Returns
+    /// - `string[]` Feature names, e.g. `["accelerometer", "ambient-light-sensor", ...]`.
+    fn features() -> Vec<String> {
+        Feature::iter().map(|feature| feature.to_string()).collect()
+    }
+
+    /// Attaches a `report-to` parameter to every feature, so violations are reported to
+    /// the named endpoint (see `ReportingEndpoints`).
+    ///
+    /// # Parameters
+    /// - `endpoint`: `?string` Endpoint name, or `null` to stop reporting.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the name is not a valid endpoint name.
+    fn report_to(&mut self, endpoint: Option<String>) -> Result<()> {
+        if let Some(name) = &endpoint
+            && !is_valid_name(name)
+        {
+            return Err(SecurityHeaderError::InvalidValue {
+                header_type: "reporting endpoint name".into(),
+                value: name.clone(),
+            });
+        }
+        self.report_to = endpoint;
+        Ok(())
     }
     /// Allow a feature for the given list of origins.
     ///
@@ -228,7 +430,7 @@ impl PermissionsPolicy {
     ///
     /// # Errors
     /// - if `feature` is not recognized.
-    fn allow(&mut self, feature: Feature, origins: Vec<String>) {
+    pub(crate) fn allow(&mut self, feature: Feature, origins: Vec<String>) {
         self.policies.insert(feature, origins);
     }
 
@@ -239,7 +441,7 @@ impl PermissionsPolicy {
     ///
     /// # Errors
     /// - if `feature` is not recognized.
-    fn deny(&mut self, feature: Feature) {
+    pub(crate) fn deny(&mut self, feature: Feature) {
         self.policies.insert(feature, Vec::new());
     }
 
@@ -248,7 +450,7 @@ impl PermissionsPolicy {
     /// # Returns
     /// - `String`, e.g.:
     ///   `geolocation=(self "https://api.example.com"), camera=()`
-    fn build(&self) -> String {
+    pub(crate) fn build(&self) -> String {
         let mut header = String::new();
         let mut first = true;
 
@@ -278,21 +480,50 @@ impl PermissionsPolicy {
             }
 
             header.push(')');
+            if let Some(endpoint) = &self.report_to {
+                write!(header, ";report-to={endpoint}").unwrap();
+            }
         }
 
         header
     }
 
+    /// Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+    /// headers to a worker runtime's response object.
+    ///
+    /// # Parameters
+    /// - `emitter`: `callable(string $name, string $value): void`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `emitter` is not callable.
+    fn set_emitter(&mut self, emitter: &Zval) -> Result<()> {
+        self.emitter.set(emitter)
+    }
+
+    /// Builds the headers `send()` would emit.
+    ///
+    /// # Returns
+    /// - `list<array{string, string}>` `[name, value]` pairs in sending order.
+    fn build_pairs(&self) -> Vec<Vec<String>> {
+        to_php(self._pairs())
+    }
+
     /// Sends the Permissions-Policy header via PHP `header()` function.
     ///
     /// # Errors
     /// - Returns an error if PHP `header()` cannot be invoked.
     fn send(&self) -> Result<()> {
-        Function::try_from_function("header")
-            .ok_or(SecurityHeaderError::HeaderUnavailable)?
-            .try_call(vec![&format!("Permissions-Policy: {}", self.build())])
-            .map_err(|e| SecurityHeaderError::HeaderCallFailed(e.to_string()))?;
-        Ok(())
+        self.emitter.emit(&self._pairs())
+    }
+
+    /// Sends the policy as a `Permissions-Policy-Report-Only` header, to trial it: violations
+    /// are reported (see `reportTo()`) but nothing is blocked.
+    ///
+    /// # Errors
+    /// - Returns an error if PHP `header()` cannot be invoked.
+    fn send_report_only(&self) -> Result<()> {
+        self.emitter
+            .emit(&[("Permissions-Policy-Report-Only", self.build())])
     }
 }
 
@@ -300,6 +531,7 @@ impl PermissionsPolicy {
 mod tests {
     use super::{Feature, PermissionsPolicy};
     use crate::run_php_example;
+    use std::str::FromStr;
 
     #[test]
     fn build_empty_policy_returns_empty() {
@@ -356,6 +588,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_round_trip() {
+        let pp = PermissionsPolicy::parse(
+            "Camera=(), geolocation=(self \"https://a.example.com\");report-to=pp, \
+             midi=('src' src), fullscreen=*, , usb=self;report-to=other",
+        )
+        .unwrap();
+        assert_eq!(pp.report_to.as_deref(), Some("pp"));
+        assert_eq!(
+            pp.build(),
+            "camera=();report-to=pp, fullscreen=(*);report-to=pp, \
+             geolocation=(self \"https://a.example.com\");report-to=pp, \
+             midi=('src' 'src');report-to=pp, usb=(self);report-to=pp"
+        );
+        assert!(PermissionsPolicy::parse("made-up=()").is_err());
+        assert!(PermissionsPolicy::parse("camera=(https://a.example.com)").is_err());
+        assert!(PermissionsPolicy::parse("camera=(self").is_err());
+    }
+
+    #[test]
+    fn report_to_and_features() {
+        let mut pp = PermissionsPolicy::__construct();
+        pp.deny(Feature::Camera);
+        pp.report_to(Some("permissions".into())).unwrap();
+        assert_eq!(pp.build(), "camera=();report-to=permissions");
+        assert!(pp.report_to(Some("Bad Name".into())).is_err());
+        pp.report_to(None).unwrap();
+        assert_eq!(pp.build(), "camera=()");
+
+        let features = PermissionsPolicy::features();
+        assert_eq!(features.first().map(String::as_str), Some("accelerometer"));
+        assert!(features.iter().any(|f| f == "sync-xhr"));
+        assert!(features.iter().all(|f| Feature::from_str(f).is_ok()));
+    }
+
     #[test]
     fn php_example() -> crate::TestResult {
         run_php_example("security-headers/permissions-policy")?;
@@ -1,10 +1,11 @@
+use super::emitter::{Emitter, HeaderPair, to_php};
 use super::{Error as SecurityHeaderError, Result};
-use ext_php_rs::zend::Function;
+use ext_php_rs::types::Zval;
 use ext_php_rs::{php_class, php_impl};
 use std::str::FromStr;
 use strum_macros::{Display, EnumString};
 
-#[derive(EnumString, Display, Debug, Clone)]
+#[derive(EnumString, Display, Debug, Clone, PartialEq, Eq)]
 #[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
 /// Represents the set of allowed values for the `Referrer-Policy` HTTP header,
 /// which controls how much referrer information (the URL of the origin) is
@@ -48,9 +49,46 @@ pub enum ReferrerPolicyDirective {
 /// Referrer-Policy header builder.
 #[php_class]
 #[php(name = "Hardened\\SecurityHeaders\\ReferrerPolicy")]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ReferrerPolicy {
-    policy: ReferrerPolicyDirective,
+    /// Fallback chain, in header order; the last token the browser supports applies.
+    policies: Vec<ReferrerPolicyDirective>,
+    emitter: Emitter,
+}
+
+/// Parses one policy token or a comma-separated fallback chain, dropping repeated tokens.
+///
+/// # Errors
+/// - Returns `InvalidValue` if a token is unknown or the chain is empty.
+fn parse_chain<'a>(
+    tokens: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<ReferrerPolicyDirective>> {
+    let mut policies = Vec::new();
+    for token in tokens.into_iter().flat_map(|token| token.split(',')) {
+        let policy = ReferrerPolicyDirective::from_str(token.trim()).map_err(|_| {
+            SecurityHeaderError::InvalidValue {
+                header_type: "Referrer-Policy".into(),
+                value: token.trim().to_string(),
+            }
+        })?;
+        if !policies.contains(&policy) {
+            policies.push(policy);
+        }
+    }
+    if policies.is_empty() {
+        return Err(SecurityHeaderError::InvalidValue {
+            header_type: "Referrer-Policy".into(),
+            value: String::new(),
+        });
+    }
+    Ok(policies)
+}
+
+impl ReferrerPolicy {
+    /// Headers emitted by `send()`, in order.
+    fn _pairs(&self) -> Vec<HeaderPair> {
+        vec![("Referrer-Policy", self.build())]
+    }
 }
 
 #[php_impl]
@@ -71,54 +109,96 @@ impl ReferrerPolicy {
     ///   - `"strict-origin-when-cross-origin"`      — full URL same-origin; origin cross-origin non-downgrade; omit on downgrade.
     ///   - `"unsafe-url"`                           — always send full URL, regardless of context.
     ///
+    ///   A comma-separated fallback chain of these tokens is accepted too (see `setChain()`).
+    ///
     /// # Exceptions
     /// - Throws `Exception` if `policy` is not a recognized directive.
-    fn __construct(policy: Option<String>) -> Result<Self> {
-        let directive = if let Some(s) = policy {
-            ReferrerPolicyDirective::from_str(s.as_str()).map_err(|_| {
-                SecurityHeaderError::InvalidValue {
-                    header_type: "Referrer-Policy".into(),
-                    value: s,
-                }
-            })?
-        } else {
-            ReferrerPolicyDirective::NoReferrer
+    pub(crate) fn __construct(policy: Option<String>) -> Result<Self> {
+        let policies = match policy {
+            Some(policy) => parse_chain([policy.as_str()])?,
+            None => vec![ReferrerPolicyDirective::NoReferrer],
         };
-        Ok(Self { policy: directive })
+        Ok(Self {
+            policies,
+            emitter: Emitter::default(),
+        })
     }
 
     /// Update the active Referrer-Policy directive.
     ///
     /// # Parameters
-    /// - `policy`: Directive string. Must be one of the tokens listed above for `__construct`.
+    /// - `policy`: Directive string. Must be one of the tokens listed above for `__construct`,
+    ///   or a comma-separated fallback chain of them.
     ///
     /// # Exceptions
     /// - Throws `Exception` if the provided token is invalid.
     fn set(&mut self, policy: &str) -> Result<()> {
-        let parsed = ReferrerPolicyDirective::from_str(policy).map_err(|_| {
-            SecurityHeaderError::InvalidValue {
-                header_type: "Referrer-Policy".into(),
-                value: policy.to_string(),
-            }
-        })?;
-        self.policy = parsed;
+        self.policies = parse_chain([policy])?;
+        Ok(())
+    }
+
+    /// Sets an ordered fallback chain, e.g. `['no-referrer', 'strict-origin-when-cross-origin']`.
+    ///
+    /// Browsers use the last token they support, so older values go first and the preferred
+    /// (newest) one last. Repeated tokens are dropped.
+    ///
+    /// # Parameters
+    /// - `policies`: `string[]` Policy tokens, at least one.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if a token is invalid or the list is empty.
+    fn set_chain(&mut self, policies: Vec<String>) -> Result<()> {
+        self.policies = parse_chain(policies.iter().map(String::as_str))?;
         Ok(())
     }
 
+    /// Returns the configured fallback chain.
+    ///
+    /// # Returns
+    /// - `string[]` Policy tokens in header order.
+    fn chain(&self) -> Vec<String> {
+        self.policies.iter().map(ToString::to_string).collect()
+    }
+
     /// Get the current Referrer-Policy value.
     ///
     /// # Returns
-    /// - `string` the active policy token.
+    /// - `string` the active policy token, or the comma-separated chain.
     fn get(&self) -> String {
-        self.policy.to_string()
+        self.build()
     }
 
     /// Build the `Referrer-Policy` header value.
     ///
     /// # Returns
-    /// - `string` the configured policy value suitable for sending as a header.
-    fn build(&self) -> String {
-        self.policy.to_string()
+    /// - `string` the configured policy value suitable for sending as a header, e.g.
+    ///   `"no-referrer, strict-origin-when-cross-origin"` for a fallback chain.
+    pub(crate) fn build(&self) -> String {
+        self.policies
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+    /// headers to a worker runtime's response object.
+    ///
+    /// # Parameters
+    /// - `emitter`: `callable(string $name, string $value): void`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `emitter` is not callable.
+    fn set_emitter(&mut self, emitter: &Zval) -> Result<()> {
+        self.emitter.set(emitter)
+    }
+
+    /// Builds the headers `send()` would emit.
+    ///
+    /// # Returns
+    /// - `list<array{string, string}>` `[name, value]` pairs in sending order.
+    fn build_pairs(&self) -> Vec<Vec<String>> {
+        to_php(self._pairs())
     }
 
     /// Send the `Referrer-Policy` header via PHP `header()` function.
@@ -126,11 +206,7 @@ impl ReferrerPolicy {
     /// # Exceptions
     /// - Throws `Exception` if the PHP `header()` function cannot be invoked.
     fn send(&self) -> Result<()> {
-        Function::try_from_function("header")
-            .ok_or(SecurityHeaderError::HeaderUnavailable)?
-            .try_call(vec![&format!("Referrer-Policy: {}", self.build())])
-            .map_err(|err| SecurityHeaderError::HeaderCallFailed(format!("{err:?}")))?;
-        Ok(())
+        self.emitter.emit(&self._pairs())
     }
 }
 
@@ -178,6 +254,27 @@ mod tests {
         assert!(msg.contains("Invalid"));
     }
 
+    #[test]
+    fn test_fallback_chain() {
+        let mut rp = ReferrerPolicy::__construct(Some(
+            "no-referrer, Strict-Origin-When-Cross-Origin".into(),
+        ))
+        .unwrap();
+        assert_eq!(rp.build(), "no-referrer, strict-origin-when-cross-origin");
+        rp.set_chain(vec![
+            "origin".into(),
+            "strict-origin".into(),
+            "origin".into(),
+        ])
+        .unwrap();
+        assert_eq!(rp.chain(), ["origin", "strict-origin"]);
+        assert_eq!(rp.build(), "origin, strict-origin");
+        assert!(rp.set_chain(Vec::new()).is_err());
+        assert!(rp.set_chain(vec!["origin".into(), "bogus".into()]).is_err());
+        assert!(rp.set("origin,").is_err());
+        assert_eq!(rp.build(), "origin, strict-origin");
+    }
+
     #[test]
     fn php_example() -> crate::TestResult {
         run_php_example("security-headers/referrer-policy")?;
@@ -0,0 +1,263 @@
+use super::emitter::{Emitter, HeaderPair, to_php};
+use super::{Error as SecurityHeaderError, Result};
+use ext_php_rs::types::Zval;
+use ext_php_rs::{php_class, php_impl};
+use serde_json::json;
+
+/// Builder for the `Reporting-Endpoints` header and its legacy `Report-To` counterpart.
+///
+/// Endpoints are registered once by name; `report-to` in CSP, COOP/COEP and NEL
+/// configuration then refers to those names, so every header points at the same URLs.
+#[php_class]
+#[php(name = "Hardened\\SecurityHeaders\\ReportingEndpoints")]
+pub struct ReportingEndpoints {
+    endpoints: Vec<(String, String)>,
+    legacy: Option<(u64, bool)>,
+    emitter: Emitter,
+}
+
+/// Returns true if `name` is a valid structured-field key, as required for endpoint names.
+pub(super) fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '*')
+        && chars.all(|c| {
+            c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-' | '.' | '*')
+        })
+}
+
+/// Returns true if `url` is an absolute `https:` URL or a path, and safe to quote.
+pub(super) fn is_valid_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    (lower.starts_with("https://") || (url.starts_with('/') && !url.starts_with("//")))
+        && !url
+            .chars()
+            .any(|c| c.is_ascii_control() || c.is_whitespace() || matches!(c, '"' | '\\'))
+}
+
+impl ReportingEndpoints {
+    /// Validates and registers an endpoint, replacing one with the same name.
+    ///
+    /// # Errors
+    /// - Returns `InvalidValue` if the name is not a structured-field key or the URL is not
+    ///   an `https:` URL or path.
+    fn _add(&mut self, name: &str, url: &str) -> Result<()> {
+        if !is_valid_name(name) {
+            return Err(SecurityHeaderError::InvalidValue {
+                header_type: "reporting endpoint name".into(),
+                value: name.into(),
+            });
+        }
+        if !is_valid_url(url) {
+            return Err(SecurityHeaderError::InvalidValue {
+                header_type: "reporting endpoint URL".into(),
+                value: url.into(),
+            });
+        }
+        if let Some(entry) = self.endpoints.iter_mut().find(|(n, _)| n == name) {
+            entry.1 = url.into();
+        } else {
+            self.endpoints.push((name.into(), url.into()));
+        }
+        Ok(())
+    }
+
+    /// Headers emitted by `send()`, in order; none if no endpoint is registered.
+    fn _pairs(&self) -> Vec<HeaderPair> {
+        if self.endpoints.is_empty() {
+            return Vec::new();
+        }
+        let mut pairs = vec![("Reporting-Endpoints", self.build())];
+        if self.legacy.is_some() {
+            pairs.push(("Report-To", self.build_report_to()));
+        }
+        pairs
+    }
+}
+
+#[php_impl]
+impl ReportingEndpoints {
+    /// Constructs a builder with no endpoints and the legacy `Report-To` header disabled.
+    fn __construct() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            legacy: None,
+            emitter: Emitter::default(),
+        }
+    }
+
+    /// Registers a named endpoint, replacing an existing one with the same name.
+    ///
+    /// # Parameters
+    /// - `name`: `string` Endpoint name, e.g. `csp-endpoint`; lowercase letters, digits, `_`, `-`,
+    ///   `.` and `*`, starting with a letter or `*`.
+    /// - `url`: `string` An `https://` URL or a path on the same origin.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the name or URL is invalid.
+    fn add(&mut self, name: &str, url: &str) -> Result<()> {
+        self._add(name, url)
+    }
+
+    /// Returns true if an endpoint with this name is registered.
+    ///
+    /// # Parameters
+    /// - `name`: `string` Endpoint name.
+    fn has(&self, name: &str) -> bool {
+        self.endpoints.iter().any(|(n, _)| n == name)
+    }
+
+    /// Returns the registered endpoint names, in registration order.
+    ///
+    /// # Returns
+    /// - `string[]` Names to use in `report-to` directives.
+    fn names(&self) -> Vec<String> {
+        self.endpoints
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Also emit the legacy `Report-To` header, for browsers without `Reporting-Endpoints`.
+    ///
+    /// # Parameters
+    /// - `maxAge`: `?int` Seconds browsers keep the endpoint groups. Default: `86400`.
+    /// - `includeSubdomains`: `?bool` Apply the groups to subdomains. Default: `false`.
+    fn legacy_report_to(&mut self, max_age: Option<u64>, include_subdomains: Option<bool>) {
+        self.legacy = Some((
+            max_age.unwrap_or(86400),
+            include_subdomains.unwrap_or(false),
+        ));
+    }
+
+    /// Builds the `Reporting-Endpoints` header value.
+    ///
+    /// # Returns
+    /// - `string` e.g. `csp-endpoint="https://example.com/csp", default="/reports"`.
+    fn build(&self) -> String {
+        self.endpoints
+            .iter()
+            .map(|(name, url)| format!("{name}=\"{url}\""))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Builds the legacy `Report-To` header value: one JSON group per endpoint.
+    ///
+    /// # Returns
+    /// - `string` Comma-separated JSON objects.
+    fn build_report_to(&self) -> String {
+        let (max_age, include_subdomains) = self.legacy.unwrap_or((86400, false));
+        self.endpoints
+            .iter()
+            .map(|(name, url)| {
+                let mut group = json!({
+                    "group": name,
+                    "max_age": max_age,
+                    "endpoints": [{ "url": url }],
+                });
+                if include_subdomains {
+                    group["include_subdomains"] = true.into();
+                }
+                group.to_string()
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+    /// headers to a worker runtime's response object.
+    ///
+    /// # Parameters
+    /// - `emitter`: `callable(string $name, string $value): void`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `emitter` is not callable.
+    fn set_emitter(&mut self, emitter: &Zval) -> Result<()> {
+        self.emitter.set(emitter)
+    }
+
+    /// Builds the headers `send()` would emit.
+    ///
+    /// # Returns
+    /// - `list<array{string, string}>` `[name, value]` pairs in sending order.
+    fn build_pairs(&self) -> Vec<Vec<String>> {
+        to_php(self._pairs())
+    }
+
+    /// Sends `Reporting-Endpoints` (and `Report-To` if enabled) via PHP `header()`.
+    /// Nothing is sent if no endpoint is registered.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if PHP `header()` cannot be invoked.
+    fn send(&self) -> Result<()> {
+        self.emitter.emit(&self._pairs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReportingEndpoints;
+
+    #[test]
+    fn test_build() {
+        let mut endpoints = ReportingEndpoints::__construct();
+        assert_eq!(endpoints.build(), "");
+        endpoints
+            .add("csp-endpoint", "https://example.com/csp")
+            .unwrap();
+        endpoints.add("default", "/old").unwrap();
+        endpoints.add("default", "/reports").unwrap();
+        assert_eq!(
+            endpoints.build(),
+            r#"csp-endpoint="https://example.com/csp", default="/reports""#
+        );
+        assert_eq!(endpoints.names(), ["csp-endpoint", "default"]);
+        assert!(endpoints.has("default"));
+        assert!(!endpoints.has("nel"));
+    }
+
+    #[test]
+    fn test_legacy_report_to() {
+        let mut endpoints = ReportingEndpoints::__construct();
+        endpoints.add("csp", "https://example.com/csp").unwrap();
+        endpoints.legacy_report_to(Some(3600), Some(true));
+        assert_eq!(
+            endpoints.build_report_to(),
+            r#"{"endpoints":[{"url":"https://example.com/csp"}],"group":"csp","include_subdomains":true,"max_age":3600}"#
+        );
+    }
+
+    #[test]
+    fn test_build_pairs() {
+        let mut endpoints = ReportingEndpoints::__construct();
+        assert!(endpoints.build_pairs().is_empty());
+        endpoints.add("csp", "/csp").unwrap();
+        endpoints.legacy_report_to(None, None);
+        let names: Vec<&str> = endpoints
+            .build_pairs()
+            .iter()
+            .map(|pair| pair[0].as_str())
+            .collect();
+        assert_eq!(names, ["Reporting-Endpoints", "Report-To"]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut endpoints = ReportingEndpoints::__construct();
+        for name in ["", "CSP", "1csp", "a b", "a\"b"] {
+            assert!(endpoints.add(name, "/r").is_err(), "{name}");
+        }
+        for url in [
+            "http://example.com/r",
+            "//evil.com/r",
+            "javascript:alert(1)",
+            "/r\"",
+            "/r x",
+        ] {
+            assert!(endpoints.add("r", url).is_err(), "{url}");
+        }
+        assert!(endpoints.names().is_empty());
+    }
+}
@@ -0,0 +1,359 @@
+use super::emitter::{Emitter, HeaderPair, to_php};
+use super::{Error as SecurityHeaderError, Result};
+use ext_php_rs::types::Zval;
+use ext_php_rs::{php_class, php_impl};
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+
+/// Longest lifetime browsers honour; Chrome caps `Max-Age` and `Expires` at 400 days.
+const MAX_AGE_LIMIT: u64 = 400 * 24 * 60 * 60;
+
+/// Values of the `SameSite` cookie attribute.
+#[derive(EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(ascii_case_insensitive)]
+pub enum SameSite {
+    /// Only sent on same-site requests.
+    Strict,
+    /// Also sent on top-level cross-site navigations (links), not on cross-site subrequests or POSTs.
+    Lax,
+    /// Sent on all requests; requires `Secure`.
+    None,
+}
+
+fn invalid(value: impl Into<String>) -> SecurityHeaderError {
+    SecurityHeaderError::InvalidValue {
+        header_type: "Set-Cookie".into(),
+        value: value.into(),
+    }
+}
+
+/// Returns true for an RFC 6265 `token`: visible ASCII without separators.
+fn is_token(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?={}".contains(&b))
+}
+
+/// Returns true for RFC 6265 `cookie-octet`s: visible ASCII except `"`, `,`, `;` and `\`.
+fn is_cookie_value(value: &str) -> bool {
+    value
+        .bytes()
+        .all(|b| b.is_ascii_graphic() && !b"\",;\\".contains(&b))
+}
+
+/// `Set-Cookie` header builder that enforces secure attributes.
+///
+/// Defaults to `Path=/; Secure; HttpOnly; SameSite=Lax`. `build()` rejects combinations
+/// browsers would silently drop or weaken: `__Host-` and `__Secure-` prefix rules,
+/// `SameSite=None` or `Partitioned` without `Secure`, and lifetimes over 400 days.
+#[php_class]
+#[php(name = "Hardened\\SecurityHeaders\\SetCookie")]
+#[derive(Debug, Clone)]
+pub struct SetCookie {
+    name: String,
+    value: String,
+    path: String,
+    domain: Option<String>,
+    max_age: Option<u64>,
+    secure: bool,
+    http_only: bool,
+    same_site: SameSite,
+    partitioned: bool,
+    emitter: Emitter,
+}
+
+impl SetCookie {
+    /// Backs `__construct()`.
+    pub(crate) fn _new(name: &str, value: &str) -> Result<Self> {
+        if !is_token(name) {
+            return Err(invalid(name));
+        }
+        if !is_cookie_value(value) {
+            return Err(invalid(value));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            value: value.to_string(),
+            path: "/".into(),
+            domain: None,
+            max_age: None,
+            secure: true,
+            http_only: true,
+            same_site: SameSite::Lax,
+            partitioned: false,
+            emitter: Emitter::default(),
+        })
+    }
+
+    /// Backs `maxAge()`.
+    pub(crate) fn _max_age(&mut self, seconds: Option<u64>) -> Result<()> {
+        if seconds.is_some_and(|seconds| seconds > MAX_AGE_LIMIT) {
+            return Err(SecurityHeaderError::CookieRule(format!(
+                "Max-Age must not exceed {MAX_AGE_LIMIT} seconds (400 days)"
+            )));
+        }
+        self.max_age = seconds;
+        Ok(())
+    }
+
+    /// Headers emitted by `send()`, in order.
+    fn _pairs(&self) -> Result<Vec<HeaderPair>> {
+        Ok(vec![("Set-Cookie", self.build()?)])
+    }
+}
+
+#[php_impl]
+impl SetCookie {
+    /// Creates a cookie builder with the secure defaults.
+    ///
+    /// # Parameters
+    /// - `name`: `string` Cookie name, optionally `__Host-` or `__Secure-` prefixed.
+    /// - `value`: `string` Cookie value; encode arbitrary data (e.g. with `rawurlencode()`) first.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the name is not a valid token or the value contains
+    ///   whitespace, control characters, `"`, `,`, `;` or `\`.
+    fn __construct(name: &str, value: &str) -> Result<Self> {
+        Self::_new(name, value)
+    }
+
+    /// Sets the `Path` attribute.
+    ///
+    /// # Parameters
+    /// - `path`: `string` Must start with `/`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the path does not start with `/` or contains `;` or control characters.
+    fn path(&mut self, path: &str) -> Result<()> {
+        if !path.starts_with('/') || path.chars().any(|c| c == ';' || c.is_control()) {
+            return Err(invalid(path));
+        }
+        self.path = path.to_string();
+        Ok(())
+    }
+
+    /// Sets or clears the `Domain` attribute. Without it the cookie is host-only, which is safer.
+    ///
+    /// # Parameters
+    /// - `domain`: `?string` e.g. `"example.com"` to share with subdomains, or `null`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the domain contains anything but letters, digits, `-` and `.`.
+    fn domain(&mut self, domain: Option<String>) -> Result<()> {
+        self.domain = match domain {
+            Some(domain) => {
+                let normalized = domain.trim_start_matches('.').to_ascii_lowercase();
+                if normalized.is_empty()
+                    || normalized.starts_with('-')
+                    || normalized.contains("..")
+                    || !normalized
+                        .bytes()
+                        .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'.')
+                {
+                    return Err(invalid(domain));
+                }
+                Some(normalized)
+            }
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Sets the `Max-Age` attribute, or makes it a session cookie.
+    ///
+    /// # Parameters
+    /// - `seconds`: `?int` Lifetime in seconds (`0` deletes the cookie), or `null` for a session cookie.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the lifetime exceeds 400 days.
+    fn max_age(&mut self, seconds: Option<u64>) -> Result<()> {
+        self._max_age(seconds)
+    }
+
+    /// Enables or disables the `Secure` attribute (enabled by default).
+    ///
+    /// # Parameters
+    /// - `enable`: `bool`
+    fn secure(&mut self, enable: bool) {
+        self.secure = enable;
+    }
+
+    /// Enables or disables the `HttpOnly` attribute (enabled by default).
+    ///
+    /// # Parameters
+    /// - `enable`: `bool`
+    fn http_only(&mut self, enable: bool) {
+        self.http_only = enable;
+    }
+
+    /// Sets the `SameSite` attribute (`Lax` by default).
+    ///
+    /// # Parameters
+    /// - `sameSite`: `string` `"Strict"`, `"Lax"` or `"None"`, case-insensitive.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` for any other value.
+    fn same_site(&mut self, same_site: &str) -> Result<()> {
+        self.same_site = SameSite::from_str(same_site).map_err(|_| invalid(same_site))?;
+        Ok(())
+    }
+
+    /// Enables or disables the `Partitioned` attribute (CHIPS), for third-party cookies
+    /// keyed by the top-level site.
+    ///
+    /// # Parameters
+    /// - `enable`: `bool`
+    fn partitioned(&mut self, enable: bool) {
+        self.partitioned = enable;
+    }
+
+    /// Builds the `Set-Cookie` header value.
+    ///
+    /// # Returns
+    /// - `string` e.g. `"__Host-sid=abc; Path=/; Secure; HttpOnly; SameSite=Lax"`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the attributes break a prefix rule, or `SameSite=None` or
+    ///   `Partitioned` is used without `Secure`.
+    fn build(&self) -> Result<String> {
+        let rule = |message: &str| Err(SecurityHeaderError::CookieRule(message.into()));
+        if self.name.starts_with("__Host-")
+            && (!self.secure || self.path != "/" || self.domain.is_some())
+        {
+            return rule("__Host- cookies require Secure, Path=/ and no Domain");
+        }
+        if self.name.starts_with("__Secure-") && !self.secure {
+            return rule("__Secure- cookies require Secure");
+        }
+        if self.same_site == SameSite::None && !self.secure {
+            return rule("SameSite=None requires Secure");
+        }
+        if self.partitioned && !self.secure {
+            return rule("Partitioned requires Secure");
+        }
+
+        let mut header = format!("{}={}; Path={}", self.name, self.value, self.path);
+        if let Some(domain) = &self.domain {
+            header.push_str("; Domain=");
+            header.push_str(domain);
+        }
+        if let Some(max_age) = self.max_age {
+            header.push_str(&format!("; Max-Age={max_age}"));
+        }
+        if self.secure {
+            header.push_str("; Secure");
+        }
+        if self.http_only {
+            header.push_str("; HttpOnly");
+        }
+        header.push_str(&format!("; SameSite={}", self.same_site));
+        if self.partitioned {
+            header.push_str("; Partitioned");
+        }
+        Ok(header)
+    }
+
+    /// Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+    /// headers to a worker runtime's response object.
+    ///
+    /// # Parameters
+    /// - `emitter`: `callable(string $name, string $value): void`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `emitter` is not callable.
+    fn set_emitter(&mut self, emitter: &Zval) -> Result<()> {
+        self.emitter.set(emitter)
+    }
+
+    /// Builds the headers `send()` would emit.
+    ///
+    /// # Returns
+    /// - `list<array{string, string}>` `[name, value]` pairs in sending order.
+    fn build_pairs(&self) -> Result<Vec<Vec<String>>> {
+        Ok(to_php(self._pairs()?))
+    }
+
+    /// Sends the cookie via PHP `header()` function, keeping previously set cookies.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `build()` fails or PHP `header()` cannot be invoked.
+    fn send(&self) -> Result<()> {
+        self.emitter.emit(&self._pairs()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SetCookie;
+    use crate::run_php_example;
+    use crate::security_headers::Error;
+
+    #[test]
+    fn test_default_build() -> crate::TestResult {
+        let cookie = SetCookie::__construct("sid", "abc123")?;
+        assert_eq!(
+            cookie.build()?,
+            "sid=abc123; Path=/; Secure; HttpOnly; SameSite=Lax"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_attributes() -> crate::TestResult {
+        let mut cookie = SetCookie::__construct("__Secure-pref", "dark")?;
+        cookie.path("/app")?;
+        cookie.domain(Some(".Example.com".into()))?;
+        cookie.max_age(Some(3600))?;
+        cookie.http_only(false);
+        cookie.same_site("strict")?;
+        assert_eq!(
+            cookie.build()?,
+            "__Secure-pref=dark; Path=/app; Domain=example.com; Max-Age=3600; Secure; SameSite=Strict"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_input() -> crate::TestResult {
+        assert!(SetCookie::__construct("bad name", "v").is_err());
+        assert!(SetCookie::__construct("sid", "a;b").is_err());
+        assert!(SetCookie::__construct("sid", "a b").is_err());
+
+        let mut cookie = SetCookie::__construct("sid", "v")?;
+        assert!(cookie.path("relative").is_err());
+        assert!(cookie.path("/a\r\nSet-Cookie: x=y").is_err());
+        assert!(cookie.domain(Some("example.com; Secure".into())).is_err());
+        assert!(cookie.same_site("sometimes").is_err());
+        assert!(matches!(
+            cookie.max_age(Some(401 * 24 * 60 * 60)),
+            Err(Error::CookieRule(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_prefix_rules() -> crate::TestResult {
+        let mut host = SetCookie::__construct("__Host-sid", "v")?;
+        host.build()?;
+        host.domain(Some("example.com".into()))?;
+        assert!(matches!(host.build(), Err(Error::CookieRule(_))));
+
+        let mut secure = SetCookie::__construct("__Secure-sid", "v")?;
+        secure.secure(false);
+        assert!(secure.build().is_err());
+
+        let mut none = SetCookie::__construct("sid", "v")?;
+        none.same_site("None")?;
+        none.build()?;
+        none.secure(false);
+        assert!(none.build().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn php_example() -> crate::TestResult {
+        run_php_example("security-headers/set-cookie")?;
+        Ok(())
+    }
+}
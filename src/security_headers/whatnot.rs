@@ -1,6 +1,7 @@
+use super::emitter::{Emitter, HeaderPair, to_map, to_php};
+use super::reporting::{is_valid_name, is_valid_url};
 use super::{Error as SecurityHeaderError, Result};
 use ext_php_rs::types::Zval;
-use ext_php_rs::zend::Function;
 use ext_php_rs::{php_class, php_enum, php_impl};
 use serde_json::{Map, Value};
 use std::collections::HashMap;
@@ -113,20 +114,173 @@ impl IntegrityPolicy {
     }
 }
 
+/// `X-Robots-Tag` directives that take no value.
+const ROBOTS_FLAGS: [&str; 9] = [
+    "all",
+    "noindex",
+    "nofollow",
+    "none",
+    "noarchive",
+    "nosnippet",
+    "indexifembedded",
+    "notranslate",
+    "noimageindex",
+];
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// Parses a fixed-width run of ASCII digits.
+fn parse_digits(s: &str, len: usize) -> Option<u32> {
+    (s.len() == len && s.bytes().all(|b| b.is_ascii_digit()))
+        .then(|| s.parse().ok())
+        .flatten()
+}
+
+/// Returns true if year/month/day form a real calendar date.
+fn is_valid_ymd(year: u32, month: u32, day: u32) -> bool {
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
+
+/// Returns true if `s` is `HH:MM` or `HH:MM:SS`.
+fn is_valid_time(s: &str) -> bool {
+    let mut parts = s.split(':');
+    let (Some(h), Some(m)) = (parts.next(), parts.next()) else {
+        return false;
+    };
+    let seconds = parts.next().map_or(Some(0), |sec| parse_digits(sec, 2));
+    parts.next().is_none()
+        && parse_digits(h, 2).is_some_and(|h| h < 24)
+        && parse_digits(m, 2).is_some_and(|m| m < 60)
+        && seconds.is_some_and(|sec| sec < 61)
+}
+
+/// Validates an `unavailable_after` date, in one of the formats search engines accept:
+/// ISO 8601 (`2030-12-31`, `2030-12-31T15:00:00Z`, `2030-12-31T15:00+01:00`) or
+/// RFC 822/850 style (`Tue, 31 Dec 2030 15:00:00 GMT`, `31 Dec 2030 15:00:00 PST`).
+fn is_valid_robots_date(date: &str) -> bool {
+    if let Some((ymd, rest)) = date.split_at_checked(10)
+        && let [y, m, d] = ymd.split('-').collect::<Vec<_>>()[..]
+        && let (Some(y), Some(m), Some(d)) =
+            (parse_digits(y, 4), parse_digits(m, 2), parse_digits(d, 2))
+    {
+        if !is_valid_ymd(y, m, d) {
+            return false;
+        }
+        let Some(time) = rest.strip_prefix('T') else {
+            return rest.is_empty();
+        };
+        if let Some(time) = time.strip_suffix('Z') {
+            return is_valid_time(time);
+        }
+        return match time.rfind(['+', '-']) {
+            Some(i) => {
+                is_valid_time(&time[..i])
+                    && time[i + 1..].split_once(':').is_some_and(|(h, m)| {
+                        parse_digits(h, 2).is_some_and(|h| h < 24)
+                            && parse_digits(m, 2).is_some_and(|m| m < 60)
+                    })
+            }
+            None => is_valid_time(time),
+        };
+    }
+
+    // RFC 822/850: optional weekday, then `DD Mon YYYY HH:MM[:SS] ZONE`
+    let date = date.split_once(", ").map_or(date, |(weekday, rest)| {
+        if weekday.chars().all(|c| c.is_ascii_alphabetic()) {
+            rest
+        } else {
+            date
+        }
+    });
+    let [d, mon, y, time, zone] = date.split_ascii_whitespace().collect::<Vec<_>>()[..] else {
+        return false;
+    };
+    let month = MONTHS
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(mon))
+        .and_then(|i| u32::try_from(i + 1).ok());
+    let day = if d.len() == 1 {
+        parse_digits(d, 1)
+    } else {
+        parse_digits(d, 2)
+    };
+    matches!((day, month, parse_digits(y, 4)), (Some(d), Some(m), Some(y)) if is_valid_ymd(y, m, d))
+        && is_valid_time(time)
+        && ((zone.chars().all(|c| c.is_ascii_uppercase()) && (1..=5).contains(&zone.len()))
+            || (zone.len() == 5
+                && zone.starts_with(['+', '-'])
+                && parse_digits(&zone[1..], 4).is_some()))
+}
+
+/// Normalizes and validates one `X-Robots-Tag` directive.
+///
+/// # Errors
+/// - Returns `InvalidValue` for unknown directives and malformed values.
+fn parse_robots_directive(directive: &str) -> Result<String> {
+    let invalid = || SecurityHeaderError::InvalidValue {
+        header_type: "X-Robots-Tag".into(),
+        value: directive.to_string(),
+    };
+    let Some((name, value)) = directive.split_once(':') else {
+        let name = directive.trim().to_ascii_lowercase();
+        return if ROBOTS_FLAGS.contains(&name.as_str()) {
+            Ok(name)
+        } else {
+            Err(invalid())
+        };
+    };
+    let name = name.trim().to_ascii_lowercase();
+    let value = value.trim();
+    let valid = match name.as_str() {
+        "max-snippet" | "max-video-preview" => {
+            value == "-1" || (!value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()))
+        }
+        "max-image-preview" => ["none", "standard", "large"].contains(&value),
+        "unavailable_after" => {
+            return if is_valid_robots_date(value) {
+                Ok(format!("unavailable_after: {value}"))
+            } else {
+                Err(invalid())
+            };
+        }
+        _ => false,
+    };
+    if valid {
+        Ok(format!("{name}:{value}"))
+    } else {
+        Err(invalid())
+    }
+}
+
 /// Builder for miscellaneous HTTP security headers:
 /// `X-Frame-Options`, `X-XSS-Protection`, `X-Content-Type-Options`,
-/// `X-Permitted-Cross-Domain-Policies`, `Report-To`, `Integrity-Policy`,
-/// and `Integrity-Policy-Report-Only`.
+/// `X-Permitted-Cross-Domain-Policies`, `X-Robots-Tag`, `Report-To`, `Integrity-Policy`,
+/// `Integrity-Policy-Report-Only`, `NEL` and `Expect-CT`.
 #[php_class]
 #[php(name = "Hardened\\SecurityHeaders\\Whatnot")]
+#[derive(Clone)]
 pub struct Whatnot {
     frame: Option<(FrameOptions, Option<String>)>,
     xss: Option<(XssProtection, Option<String>)>,
     nosniff: bool,
     permitted_policies: Option<PermittedCrossDomainPolicies>,
-    report_to: Option<String>,
+    robots_tag: Vec<String>,
+    report_to: Vec<(String, String)>,
     integrity_policy: Option<IntegrityPolicy>,
     integrity_policy_report_only: Option<IntegrityPolicy>,
+    nel: Option<String>,
+    expect_ct: Option<String>,
+    emitter: Emitter,
 }
 
 impl Whatnot {
@@ -173,20 +327,80 @@ impl Whatnot {
             endpoints,
         })
     }
+
+    /// Headers emitted by `send()`, in order; one `Report-To` header per group.
+    pub(crate) fn _pairs(&self) -> Vec<HeaderPair> {
+        let mut pairs = Vec::new();
+
+        if let Some((mode, uri)) = &self.frame {
+            let value = match (mode, uri) {
+                (FrameOptions::AllowFrom, Some(u)) => format!("ALLOW-FROM {u}"),
+                _ => mode.to_string(),
+            };
+            pairs.push(("X-Frame-Options", value));
+        }
+
+        if let Some((mode, uri)) = &self.xss {
+            let value = if let Some(u) = uri {
+                format!("1; report={u}")
+            } else {
+                mode.to_string()
+            };
+            pairs.push(("X-XSS-Protection", value));
+        }
+
+        if self.nosniff {
+            pairs.push(("X-Content-Type-Options", "nosniff".into()));
+        }
+
+        if let Some(p) = &self.permitted_policies {
+            pairs.push(("X-Permitted-Cross-Domain-Policies", p.to_string()));
+        }
+
+        if !self.robots_tag.is_empty() {
+            pairs.push(("X-Robots-Tag", self.robots_tag.join(", ")));
+        }
+
+        for (_, group) in &self.report_to {
+            pairs.push(("Report-To", group.clone()));
+        }
+
+        if let Some(v) = &self.integrity_policy {
+            pairs.push(("Integrity-Policy", v.build()));
+        }
+
+        if let Some(v) = &self.integrity_policy_report_only {
+            pairs.push(("Integrity-Policy-Report-Only", v.build()));
+        }
+
+        if let Some(v) = &self.nel {
+            pairs.push(("NEL", v.clone()));
+        }
+
+        if let Some(v) = &self.expect_ct {
+            pairs.push(("Expect-CT", v.clone()));
+        }
+
+        pairs
+    }
 }
 
 #[php_impl]
 impl Whatnot {
     /// Constructs a new builder with all headers disabled.
-    fn __construct() -> Self {
+    pub(crate) fn __construct() -> Self {
         Self {
             frame: None,
             xss: None,
             nosniff: false,
             permitted_policies: None,
-            report_to: None,
+            robots_tag: Vec::new(),
+            report_to: Vec::new(),
             integrity_policy: None,
             integrity_policy_report_only: None,
+            nel: None,
+            expect_ct: None,
+            emitter: Emitter::default(),
         }
     }
 
@@ -198,7 +412,11 @@ impl Whatnot {
     ///
     /// # Exceptions
     /// - Throws if `AllowFrom` is given without a URI.
-    fn set_frame_options(&mut self, mode: FrameOptions, uri: Option<String>) -> Result<()> {
+    pub(crate) fn set_frame_options(
+        &mut self,
+        mode: FrameOptions,
+        uri: Option<String>,
+    ) -> Result<()> {
         if mode == FrameOptions::AllowFrom && uri.is_none() {
             return Err(SecurityHeaderError::AllowFromRequiresUri);
         }
@@ -214,7 +432,7 @@ impl Whatnot {
     ///
     /// # Exceptions
     /// - Throws if a `report_uri` is provided for `Off` mode.
-    fn set_xss_protection(
+    pub(crate) fn set_xss_protection(
         &mut self,
         mode: XssProtection,
         report_uri: Option<String>,
@@ -227,7 +445,7 @@ impl Whatnot {
     }
 
     /// Enable or disable `X-Content-Type-Options: nosniff`.
-    fn set_nosniff(&mut self, enable: bool) {
+    pub(crate) fn set_nosniff(&mut self, enable: bool) {
         self.nosniff = enable;
     }
 
@@ -235,11 +453,39 @@ impl Whatnot {
     ///
     /// # Parameters
     /// - `policy`: `CrossDomainPolicy::None`, `MasterOnly`, `ByContentType`, or `All`.
-    fn set_permitted_cross_domain_policies(&mut self, policy: PermittedCrossDomainPolicies) {
+    pub(crate) fn set_permitted_cross_domain_policies(
+        &mut self,
+        policy: PermittedCrossDomainPolicies,
+    ) {
         self.permitted_policies = Some(policy);
     }
 
-    /// Configure the `Report-To` header from structured arguments.
+    /// Set the `X-Robots-Tag` header, e.g. to keep staging sites or private documents out of
+    /// search engines. An empty array removes the header.
+    ///
+    /// # Parameters
+    /// - `directives`: `string[]` e.g. `['noindex', 'nofollow', 'unavailable_after: 2030-12-31']`.
+    ///   Supported: `all`, `noindex`, `nofollow`, `none`, `noarchive`, `nosnippet`,
+    ///   `indexifembedded`, `notranslate`, `noimageindex`, `max-snippet:<n>`,
+    ///   `max-video-preview:<n>`, `max-image-preview:<none|standard|large>` and
+    ///   `unavailable_after:<date>` (ISO 8601 or RFC 822/850).
+    ///
+    /// # Exceptions
+    /// - Throws if a directive is unknown or its value (e.g. the date) is invalid.
+    pub(crate) fn set_robots_tag(&mut self, directives: Vec<String>) -> Result<()> {
+        let mut robots_tag = Vec::with_capacity(directives.len());
+        for directive in directives {
+            let directive = parse_robots_directive(&directive)?;
+            if !robots_tag.contains(&directive) {
+                robots_tag.push(directive);
+            }
+        }
+        self.robots_tag = robots_tag;
+        Ok(())
+    }
+
+    /// Configure a `Report-To` group from structured arguments. Each group is sent as its
+    /// own `Report-To` header; setting a group name again replaces that group.
     ///
     /// # Parameters
     /// - `group`: report group name.
@@ -267,7 +513,12 @@ impl Whatnot {
         map.insert("include_subdomains".into(), Value::Bool(include_subdomains));
         map.insert("endpoints".into(), Value::Array(eps));
 
-        self.report_to = Some(Value::Object(map).to_string());
+        let value = Value::Object(map).to_string();
+        if let Some(entry) = self.report_to.iter_mut().find(|(name, _)| name == group) {
+            entry.1 = value;
+        } else {
+            self.report_to.push((group.to_string(), value));
+        }
         Ok(())
     }
 
@@ -317,61 +568,141 @@ impl Whatnot {
         Ok(())
     }
 
-    /// Build an associative array of header names → values.
-    fn build(&self) -> HashMap<&'static str, String> {
-        let mut headers = HashMap::new();
-
-        if let Some((mode, uri)) = &self.frame {
-            let value = match (mode, uri) {
-                (FrameOptions::AllowFrom, Some(u)) => format!("ALLOW-FROM {u}"),
-                _ => mode.to_string(),
-            };
-            headers.insert("X-Frame-Options", value);
+    /// Set the `NEL` (Network Error Logging) header, so browsers report DNS, TLS and HTTP
+    /// failures to a reporting endpoint. Pass `null` to remove the header.
+    ///
+    /// # Parameters
+    /// - `report_to`: `?string` Endpoint name registered with `ReportingEndpoints` (or a
+    ///   `Report-To` group for older browsers).
+    /// - `max_age`: `int` Seconds the browser keeps the policy; `0` removes a cached policy.
+    /// - `include_subdomains`: `?bool` Whether the policy also covers subdomains. Default: `false`.
+    /// - `success_fraction`: `?float` Share of successful requests to report, `0.0`–`1.0`.
+    /// - `failure_fraction`: `?float` Share of failed requests to report, `0.0`–`1.0`.
+    ///
+    /// # Exceptions
+    /// - Throws if the endpoint name is invalid, `max_age` is negative or a fraction is out
+    ///   of range.
+    fn set_nel(
+        &mut self,
+        report_to: Option<String>,
+        max_age: i64,
+        include_subdomains: Option<bool>,
+        success_fraction: Option<f64>,
+        failure_fraction: Option<f64>,
+    ) -> Result<()> {
+        let Some(report_to) = report_to else {
+            self.nel = None;
+            return Ok(());
+        };
+        if !is_valid_name(&report_to) {
+            return Err(SecurityHeaderError::InvalidValue {
+                header_type: "reporting endpoint name".into(),
+                value: report_to,
+            });
         }
-
-        if let Some((mode, uri)) = &self.xss {
-            let value = if let Some(u) = uri {
-                format!("1; report={u}")
-            } else {
-                mode.to_string()
-            };
-            headers.insert("X-XSS-Protection", value);
+        if max_age < 0 {
+            return Err(SecurityHeaderError::InvalidValue {
+                header_type: "NEL max_age".into(),
+                value: max_age.to_string(),
+            });
         }
 
-        if self.nosniff {
-            headers.insert("X-Content-Type-Options", "nosniff".into());
+        let mut map = Map::new();
+        map.insert("report_to".into(), Value::String(report_to));
+        map.insert("max_age".into(), Value::Number(max_age.into()));
+        if include_subdomains == Some(true) {
+            map.insert("include_subdomains".into(), Value::Bool(true));
         }
-
-        if let Some(p) = &self.permitted_policies {
-            headers.insert("X-Permitted-Cross-Domain-Policies", p.to_string());
+        for (key, fraction) in [
+            ("success_fraction", success_fraction),
+            ("failure_fraction", failure_fraction),
+        ] {
+            let Some(fraction) = fraction else { continue };
+            if !(0.0..=1.0).contains(&fraction) {
+                return Err(SecurityHeaderError::InvalidValue {
+                    header_type: format!("NEL {key}"),
+                    value: fraction.to_string(),
+                });
+            }
+            map.insert(key.into(), Value::from(fraction));
         }
+        self.nel = Some(Value::Object(map).to_string());
+        Ok(())
+    }
 
-        if let Some(v) = &self.report_to {
-            headers.insert("Report-To", v.clone());
+    /// Set the legacy `Expect-CT` header for Certificate Transparency monitoring. Current
+    /// browsers enforce CT without it; pass `null` as `max_age` to remove the header.
+    ///
+    /// # Parameters
+    /// - `max_age`: `?int` Seconds the browser remembers the policy.
+    /// - `enforce`: `?bool` Refuse connections that violate CT instead of only reporting.
+    ///   Default: `false`.
+    /// - `report_uri`: `?string` Absolute `https:` URL that receives violation reports.
+    ///
+    /// # Exceptions
+    /// - Throws if `max_age` is negative or `report_uri` is not an absolute `https:` URL.
+    fn set_expect_ct(
+        &mut self,
+        max_age: Option<i64>,
+        enforce: Option<bool>,
+        report_uri: Option<String>,
+    ) -> Result<()> {
+        let Some(max_age) = max_age else {
+            self.expect_ct = None;
+            return Ok(());
+        };
+        if max_age < 0 {
+            return Err(SecurityHeaderError::InvalidValue {
+                header_type: "Expect-CT max-age".into(),
+                value: max_age.to_string(),
+            });
         }
-
-        if let Some(v) = &self.integrity_policy {
-            headers.insert("Integrity-Policy", v.build());
+        let mut value = format!("max-age={max_age}");
+        if enforce == Some(true) {
+            value.push_str(", enforce");
         }
-
-        if let Some(v) = &self.integrity_policy_report_only {
-            headers.insert("Integrity-Policy-Report-Only", v.build());
+        if let Some(uri) = report_uri {
+            if uri.starts_with('/') || !is_valid_url(&uri) {
+                return Err(SecurityHeaderError::InvalidValue {
+                    header_type: "Expect-CT report-uri".into(),
+                    value: uri,
+                });
+            }
+            write!(value, ", report-uri=\"{uri}\"").expect("Could not write to string");
         }
+        self.expect_ct = Some(value);
+        Ok(())
+    }
 
-        headers
+    /// Build an associative array of header names → values. Several `Report-To` groups are
+    /// joined into one comma-separated value; `buildPairs()` keeps them as separate headers.
+    pub(crate) fn build(&self) -> HashMap<&'static str, String> {
+        to_map(self._pairs())
+    }
+
+    /// Routes `send()` through a callable instead of PHP `header()`, e.g. to attach the
+    /// headers to a worker runtime's response object.
+    ///
+    /// # Parameters
+    /// - `emitter`: `callable(string $name, string $value): void`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `emitter` is not callable.
+    fn set_emitter(&mut self, emitter: &Zval) -> Result<()> {
+        self.emitter.set(emitter)
+    }
+
+    /// Builds the headers `send()` would emit.
+    ///
+    /// # Returns
+    /// - `list<array{string, string}>` `[name, value]` pairs in sending order.
+    fn build_pairs(&self) -> Vec<Vec<String>> {
+        to_php(self._pairs())
     }
 
     /// Emit all configured headers via PHP `header()` calls.
     fn send(&self) -> Result<()> {
-        let header_fn =
-            Function::try_from_function("header").ok_or(SecurityHeaderError::HeaderUnavailable)?;
-        for (name, value) in self.build() {
-            let hdr = format!("{name}: {value}");
-            header_fn
-                .try_call(vec![&hdr])
-                .map_err(|err| SecurityHeaderError::HeaderCallFailed(err.to_string()))?;
-        }
-        Ok(())
+        self.emitter.emit(&self._pairs())
     }
 }
 
@@ -379,6 +710,7 @@ impl Whatnot {
 mod tests {
     use super::{FrameOptions, PermittedCrossDomainPolicies, Whatnot, XssProtection};
     use crate::run_php_example;
+    use serde_json::{Value, json};
     use std::collections::HashMap;
 
     #[test]
@@ -494,6 +826,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_set_robots_tag() {
+        let mut m = Whatnot::__construct();
+        m.set_robots_tag(vec![
+            "NoIndex".into(),
+            "nofollow".into(),
+            "noindex".into(),
+            "max-snippet: 20".into(),
+            "max-image-preview:large".into(),
+            "unavailable_after: 2030-12-31T15:00:00+01:00".into(),
+        ])
+        .unwrap();
+        assert_eq!(
+            m.build().get("X-Robots-Tag").map(String::as_str),
+            Some(
+                "noindex, nofollow, max-snippet:20, max-image-preview:large, \
+                 unavailable_after: 2030-12-31T15:00:00+01:00"
+            )
+        );
+
+        for date in [
+            "2030-12-31",
+            "2028-02-29T08:30Z",
+            "Tue, 31 Dec 2030 15:00:00 GMT",
+            "25 Jun 2010 15:00:00 PST",
+            "1 Jan 2031 00:00 +0100",
+        ] {
+            m.set_robots_tag(vec![format!("unavailable_after: {date}")])
+                .unwrap();
+        }
+        for directive in [
+            "unavailable_after: 2030-02-30",
+            "unavailable_after: 2030-12-31T25:00:00Z",
+            "unavailable_after: 31 Foo 2030 15:00:00 GMT",
+            "unavailable_after: tomorrow",
+            "max-snippet:ten",
+            "max-image-preview:huge",
+            "noindex, nofollow",
+            "index",
+        ] {
+            assert!(
+                m.set_robots_tag(vec![directive.into()]).is_err(),
+                "{directive}"
+            );
+        }
+
+        m.set_robots_tag(Vec::new()).unwrap();
+        assert!(!m.build().contains_key("X-Robots-Tag"));
+    }
+
     #[test]
     fn test_set_report_to() {
         let mut m = Whatnot::__construct();
@@ -508,6 +890,100 @@ mod tests {
         assert!(val.contains(r#""endpoints":["ep1","ep2"]"#));
     }
 
+    #[test]
+    fn test_report_to_groups() {
+        let mut m = Whatnot::__construct();
+        m.set_report_to("csp", 3600, false, vec!["https://example.com/csp"])
+            .unwrap();
+        m.set_report_to("nel", 60, false, vec!["https://example.com/nel"])
+            .unwrap();
+        m.set_report_to("csp", 7200, false, vec!["https://example.com/csp"])
+            .unwrap();
+        m.set_nosniff(true);
+        let pairs = m._pairs();
+        let names: Vec<&str> = pairs.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["X-Content-Type-Options", "Report-To", "Report-To"]);
+        assert!(pairs[1].1.contains(r#""max_age":7200"#));
+        assert!(pairs[2].1.contains(r#""group":"nel""#));
+        assert_eq!(
+            m.build()["Report-To"],
+            format!("{}, {}", pairs[1].1, pairs[2].1)
+        );
+    }
+
+    #[test]
+    fn test_set_nel() {
+        let mut m = Whatnot::__construct();
+        m.set_nel(
+            Some("network-errors".into()),
+            2_592_000,
+            Some(true),
+            None,
+            Some(0.5),
+        )
+        .unwrap();
+        let nel: Value = serde_json::from_str(&m.build()["NEL"]).unwrap();
+        assert_eq!(
+            nel,
+            json!({
+                "report_to": "network-errors",
+                "max_age": 2_592_000,
+                "include_subdomains": true,
+                "failure_fraction": 0.5,
+            })
+        );
+        m.set_nel(Some("nel".into()), 0, None, None, None).unwrap();
+        let expected = json!({"report_to": "nel", "max_age": 0});
+        assert_eq!(
+            serde_json::from_str::<Value>(&m.build()["NEL"]).unwrap(),
+            expected
+        );
+
+        assert!(
+            m.set_nel(Some("Bad Name".into()), 60, None, None, None)
+                .is_err()
+        );
+        assert!(m.set_nel(Some("nel".into()), -1, None, None, None).is_err());
+        assert!(
+            m.set_nel(Some("nel".into()), 60, None, Some(1.5), None)
+                .is_err()
+        );
+        assert!(
+            m.set_nel(Some("nel".into()), 60, None, None, Some(f64::NAN))
+                .is_err()
+        );
+        assert_eq!(
+            serde_json::from_str::<Value>(&m.build()["NEL"]).unwrap(),
+            expected
+        );
+
+        m.set_nel(None, 0, None, None, None).unwrap();
+        assert!(m.build().is_empty());
+    }
+
+    #[test]
+    fn test_set_expect_ct() {
+        let mut m = Whatnot::__construct();
+        m.set_expect_ct(Some(86400), None, None).unwrap();
+        assert_eq!(m.build()["Expect-CT"], "max-age=86400");
+        m.set_expect_ct(Some(0), Some(true), Some("https://example.com/ct".into()))
+            .unwrap();
+        assert_eq!(
+            m.build()["Expect-CT"],
+            r#"max-age=0, enforce, report-uri="https://example.com/ct""#
+        );
+
+        assert!(m.set_expect_ct(Some(-1), None, None).is_err());
+        assert!(m.set_expect_ct(Some(60), None, Some("/ct".into())).is_err());
+        assert!(
+            m.set_expect_ct(Some(60), None, Some("https://example.com/\"x".into()))
+                .is_err()
+        );
+
+        m.set_expect_ct(None, None, None).unwrap();
+        assert!(m.build().is_empty());
+    }
+
     #[test]
     fn test_combined_headers() {
         let mut m = Whatnot::__construct();
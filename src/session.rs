@@ -0,0 +1,432 @@
+//! Session hardening helpers to use alongside PHP's own session handling: strong session IDs,
+//! rotation decisions, idle and absolute timeouts, and client fingerprint binding.
+
+use crate::constant_time;
+use crate::rng::random_bytes;
+use crate::to_str;
+use data_encoding::{BASE32HEX_NOPAD, BASE64URL_NOPAD};
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::types::{ZendHashTable, Zval};
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+// Error codes for Session errors: 3400-3499
+pub mod error_codes {
+    pub const INVALID_OPTION: i32 = 3400;
+    pub const INVALID_IP: i32 = 3401;
+    pub const RANDOM: i32 = 3402;
+}
+
+/// Errors produced by `Session`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Invalid option: {0}")]
+    InvalidOption(String),
+
+    #[error("Invalid IP address: {0:?}")]
+    InvalidIp(String),
+
+    #[error("Random generator failed: {0}")]
+    Random(String),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::InvalidOption(_) => error_codes::INVALID_OPTION,
+            Error::InvalidIp(_) => error_codes::INVALID_IP,
+            Error::Random(_) => error_codes::RANDOM,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for session operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// OWASP asks for at least 64 bits; 128 leaves room for weaker assumptions.
+const MIN_ID_BITS: i64 = 128;
+const MAX_ID_BITS: i64 = 512;
+const DEFAULT_ID_BITS: i64 = 192;
+/// Context flags that always call for a new session ID.
+const ROTATE_EVENTS: &[&str] = &[
+    "login",
+    "logout",
+    "privilege_change",
+    "role_change",
+    "password_change",
+    "reauthenticated",
+];
+
+/// Current UNIX time in seconds.
+fn unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+/// PHP truthiness for the scalar types a context array holds.
+fn truthy(value: &Zval) -> bool {
+    value
+        .bool()
+        .or_else(|| value.long().map(|long| long != 0))
+        .or_else(|| {
+            value
+                .str()
+                .map(|string| !string.is_empty() && string != "0")
+        })
+        .unwrap_or(false)
+}
+
+/// Why a session is no longer valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Timeout {
+    Idle,
+    Absolute,
+}
+
+/// Facts about a request that may call for session ID rotation.
+#[derive(Debug, Default)]
+struct RotationContext {
+    event: bool,
+    user_changed: bool,
+    rotated_at: Option<i64>,
+}
+
+impl RotationContext {
+    fn from_table(table: &ZendHashTable) -> Self {
+        let user = |name: &str| table.get(name).and_then(|value| to_str(value).ok());
+        Self {
+            event: ROTATE_EVENTS
+                .iter()
+                .any(|name| table.get(*name).is_some_and(truthy)),
+            user_changed: user("user_id")
+                .zip(user("previous_user_id"))
+                .is_some_and(|(user, previous)| user != previous),
+            rotated_at: table.get("rotated_at").and_then(Zval::long),
+        }
+    }
+}
+
+/// Session ID, timeout and fingerprint policy.
+#[php_class]
+#[php(name = "Hardened\\Session")]
+pub struct Session {
+    idle_timeout: i64,
+    absolute_timeout: i64,
+    rotate_interval: i64,
+    ipv4_prefix: u8,
+    ipv6_prefix: u8,
+}
+
+impl Session {
+    /// Backs `__construct()`.
+    fn _new(
+        idle_timeout: Option<i64>,
+        absolute_timeout: Option<i64>,
+        rotate_interval: Option<i64>,
+    ) -> Result<Self> {
+        let positive = |value: Option<i64>, default: i64, name: &str| match value {
+            None => Ok(default),
+            Some(value) if value > 0 => Ok(value),
+            Some(value) => Err(Error::InvalidOption(format!(
+                "{name} must be positive, got {value}"
+            ))),
+        };
+        Ok(Self {
+            idle_timeout: positive(idle_timeout, 1800, "idleTimeout")?,
+            absolute_timeout: positive(absolute_timeout, 28_800, "absoluteTimeout")?,
+            rotate_interval: positive(rotate_interval, 900, "rotateInterval")?,
+            ipv4_prefix: 24,
+            ipv6_prefix: 64,
+        })
+    }
+
+    fn _should_rotate(&self, context: &RotationContext, now: i64) -> bool {
+        context.event
+            || context.user_changed
+            || context
+                .rotated_at
+                .is_some_and(|rotated_at| now.saturating_sub(rotated_at) >= self.rotate_interval)
+    }
+
+    fn _timeout(&self, created_at: i64, last_activity: i64, now: i64) -> Option<Timeout> {
+        if now.saturating_sub(created_at) >= self.absolute_timeout {
+            Some(Timeout::Absolute)
+        } else if now.saturating_sub(last_activity) >= self.idle_timeout {
+            Some(Timeout::Idle)
+        } else {
+            None
+        }
+    }
+
+    /// The network `ip` belongs to, as `address/prefix`.
+    fn _network(&self, ip: &str) -> Result<String> {
+        let parsed: IpAddr = ip
+            .trim()
+            .parse()
+            .map_err(|_| Error::InvalidIp(ip.to_string()))?;
+        let parsed = match parsed {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(parsed, IpAddr::V4),
+            v4 @ IpAddr::V4(_) => v4,
+        };
+        Ok(match parsed {
+            IpAddr::V4(v4) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.ipv4_prefix))
+                    .unwrap_or(0);
+                format!(
+                    "{}/{}",
+                    Ipv4Addr::from(u32::from(v4) & mask),
+                    self.ipv4_prefix
+                )
+            }
+            IpAddr::V6(v6) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.ipv6_prefix))
+                    .unwrap_or(0);
+                format!(
+                    "{}/{}",
+                    Ipv6Addr::from(u128::from(v6) & mask),
+                    self.ipv6_prefix
+                )
+            }
+        })
+    }
+
+    fn _fingerprint(&self, user_agent: &str, ip: &str) -> Result<String> {
+        let digest = Sha256::new()
+            .chain_update(b"hardened-session-fingerprint-v1\0")
+            .chain_update(user_agent.trim().as_bytes())
+            .chain_update([0])
+            .chain_update(self._network(ip)?.as_bytes())
+            .finalize();
+        Ok(BASE64URL_NOPAD.encode(&digest))
+    }
+}
+
+#[php_impl]
+impl Session {
+    /// Constructs a session policy.
+    ///
+    /// # Parameters
+    /// - `idleTimeout`: `?int` Seconds of inactivity after which a session ends. Default: 1800.
+    /// - `absoluteTimeout`: `?int` Seconds after creation after which a session ends,
+    ///   however active. Default: 28800.
+    /// - `rotateInterval`: `?int` Seconds after which the ID is rotated even without a
+    ///   privilege change. Default: 900.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if a value is not positive.
+    fn __construct(
+        idle_timeout: Option<i64>,
+        absolute_timeout: Option<i64>,
+        rotate_interval: Option<i64>,
+    ) -> Result<Self> {
+        Self::_new(idle_timeout, absolute_timeout, rotate_interval)
+    }
+
+    /// Generate a session ID from the CSPRNG.
+    ///
+    /// # Parameters
+    /// - `bits`: `?int` Entropy in bits, 128–512. Default: 192.
+    ///
+    /// # Returns
+    /// - `string` Lowercase `[0-9a-v]` characters, 5 bits each, valid for `session_id()`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `bits` is out of range.
+    fn generate_id(bits: Option<i64>) -> Result<String> {
+        let bits = bits.unwrap_or(DEFAULT_ID_BITS);
+        if !(MIN_ID_BITS..=MAX_ID_BITS).contains(&bits) {
+            return Err(Error::InvalidOption(format!(
+                "Session IDs need 128 to 512 bits, got {bits}"
+            )));
+        }
+        let bytes = usize::try_from(bits.div_ceil(8)).unwrap_or_default();
+        let random = random_bytes(bytes).map_err(|err| Error::Random(err.to_string()))?;
+        Ok(BASE32HEX_NOPAD.encode(&random).to_ascii_lowercase())
+    }
+
+    /// Decide whether to call `session_regenerate_id(true)`.
+    ///
+    /// # Parameters
+    /// - `context`: `array` Any of: `login`, `logout`, `privilege_change`, `role_change`,
+    ///   `password_change`, `reauthenticated` (truthy when it happened in this request);
+    ///   `user_id` and `previous_user_id` (rotate when both are given and differ); `rotated_at` (UNIX time
+    ///   of the last rotation, rotate when older than the interval); `now` (defaults to the
+    ///   current time).
+    ///
+    /// # Returns
+    /// - `bool`
+    fn should_rotate(&self, context: &ZendHashTable) -> bool {
+        let now = context
+            .get("now")
+            .and_then(Zval::long)
+            .unwrap_or_else(unix_time);
+        self._should_rotate(&RotationContext::from_table(context), now)
+    }
+
+    /// Evaluate the idle and absolute timeouts.
+    ///
+    /// # Parameters
+    /// - `createdAt`: `int` UNIX time the session was created (or the user logged in).
+    /// - `lastActivity`: `int` UNIX time of the previous request.
+    /// - `now`: `?int` Defaults to the current time.
+    ///
+    /// # Returns
+    /// - `?string` `null` if the session is still valid, otherwise `"idle"` or `"absolute"`.
+    fn timeout_status(
+        &self,
+        created_at: i64,
+        last_activity: i64,
+        now: Option<i64>,
+    ) -> Option<String> {
+        self._timeout(created_at, last_activity, now.unwrap_or_else(unix_time))
+            .map(|timeout| match timeout {
+                Timeout::Idle => "idle".into(),
+                Timeout::Absolute => "absolute".into(),
+            })
+    }
+
+    /// Whether either timeout has passed.
+    ///
+    /// # Parameters
+    /// - `createdAt`: `int` UNIX time the session was created.
+    /// - `lastActivity`: `int` UNIX time of the previous request.
+    /// - `now`: `?int` Defaults to the current time.
+    ///
+    /// # Returns
+    /// - `bool`
+    fn is_expired(&self, created_at: i64, last_activity: i64, now: Option<i64>) -> bool {
+        self._timeout(created_at, last_activity, now.unwrap_or_else(unix_time))
+            .is_some()
+    }
+
+    /// Set the network prefix lengths used for fingerprints, so clients that hop between
+    /// addresses of one network keep their session.
+    ///
+    /// # Parameters
+    /// - `ipv4`: `int` Default: 24.
+    /// - `ipv6`: `int` Default: 64.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if a prefix is longer than the address.
+    fn fingerprint_prefixes(&mut self, ipv4: i64, ipv6: i64) -> Result<()> {
+        let prefix = |value: i64, max: u8| {
+            u8::try_from(value)
+                .ok()
+                .filter(|value| *value <= max)
+                .ok_or_else(|| Error::InvalidOption(format!("Invalid prefix length {value}")))
+        };
+        self.ipv4_prefix = prefix(ipv4, 32)?;
+        self.ipv6_prefix = prefix(ipv6, 128)?;
+        Ok(())
+    }
+
+    /// Fingerprint a client: a hash of its user agent and network prefix, to store in the
+    /// session.
+    ///
+    /// # Parameters
+    /// - `userAgent`: `string` e.g. `$_SERVER['HTTP_USER_AGENT']`.
+    /// - `ip`: `string` Client IP address.
+    ///
+    /// # Returns
+    /// - `string` Base64URL-encoded SHA-256.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `ip` is not an IP address.
+    fn fingerprint(&self, user_agent: &str, ip: &str) -> Result<String> {
+        self._fingerprint(user_agent, ip)
+    }
+
+    /// Check a stored fingerprint in constant time.
+    ///
+    /// # Parameters
+    /// - `stored`: `string` Value from `fingerprint()`.
+    /// - `userAgent`: `string` Current user agent.
+    /// - `ip`: `string` Current client IP address.
+    ///
+    /// # Returns
+    /// - `bool` `false` on mismatch or an invalid IP.
+    fn verify_fingerprint(&self, stored: &str, user_agent: &str, ip: &str) -> bool {
+        self._fingerprint(user_agent, ip)
+            .is_ok_and(|current| constant_time::eq(current.as_bytes(), stored.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RotationContext, Session, Timeout};
+
+    #[test]
+    fn test_generate_id() {
+        let id = Session::generate_id(None).unwrap();
+        // 24 bytes, 5 bits per character
+        assert_eq!(id.len(), 39);
+        assert!(
+            id.bytes()
+                .all(|b| b.is_ascii_digit() || (b'a'..=b'v').contains(&b))
+        );
+        assert_ne!(id, Session::generate_id(None).unwrap());
+        assert!(Session::generate_id(Some(64)).is_err());
+    }
+
+    #[test]
+    fn test_rotation_and_timeouts() {
+        let session = Session::_new(Some(60), Some(3600), Some(300)).unwrap();
+        let quiet = RotationContext {
+            rotated_at: Some(1000),
+            ..RotationContext::default()
+        };
+        assert!(!session._should_rotate(&quiet, 1299));
+        assert!(session._should_rotate(&quiet, 1300));
+        let login = RotationContext {
+            event: true,
+            ..RotationContext::default()
+        };
+        assert!(session._should_rotate(&login, 0));
+
+        assert_eq!(session._timeout(1000, 1000, 1059), None);
+        assert_eq!(session._timeout(1000, 1000, 1060), Some(Timeout::Idle));
+        assert_eq!(session._timeout(1000, 4590, 4600), Some(Timeout::Absolute));
+        assert!(Session::_new(Some(0), None, None).is_err());
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let mut session = Session::_new(None, None, None).unwrap();
+        assert_eq!(session._network("203.0.113.77").unwrap(), "203.0.113.0/24");
+        assert_eq!(
+            session._network("::ffff:203.0.113.77").unwrap(),
+            "203.0.113.0/24"
+        );
+        assert_eq!(
+            session._network("2001:db8:1:2:3:4:5:6").unwrap(),
+            "2001:db8:1:2::/64"
+        );
+        let stored = session._fingerprint("Firefox", "203.0.113.77").unwrap();
+        assert!(session.verify_fingerprint(&stored, "Firefox", "203.0.113.9"));
+        assert!(!session.verify_fingerprint(&stored, "curl", "203.0.113.9"));
+        assert!(!session.verify_fingerprint(&stored, "Firefox", "198.51.100.1"));
+        assert!(!session.verify_fingerprint(&stored, "Firefox", "not an ip"));
+
+        session.fingerprint_prefixes(16, 48).unwrap();
+        assert_eq!(session._network("203.0.113.77").unwrap(), "203.0.0.0/16");
+        assert!(session.fingerprint_prefixes(33, 64).is_err());
+    }
+}
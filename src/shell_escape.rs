@@ -0,0 +1,226 @@
+//! Strict quoting of shell arguments for code that has to hand a command string to `exec()`,
+//! `proc_open()` or a legacy API instead of running it through `ShellCommand`.
+//!
+//! Every argument is quoted, even when it contains nothing special, and characters that no
+//! quoting can neutralize are rejected instead of silently dropped.
+
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::types::ZendHashTable;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+use thiserror::Error;
+
+// Error codes for ShellEscape errors: 4100-4199
+pub mod error_codes {
+    pub const UNKNOWN_PLATFORM: i32 = 4100;
+    pub const INVALID_CHARACTER: i32 = 4101;
+    pub const INVALID_ARGUMENT_TYPE: i32 = 4102;
+}
+
+/// Errors produced by `ShellEscape`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown platform: {0}")]
+    UnknownPlatform(String),
+
+    #[error("Argument contains {0:?}, which cannot be safely quoted for {1}")]
+    InvalidCharacter(char, Platform),
+
+    #[error("Argument {0}: value can only be string or int")]
+    InvalidArgumentType(String),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::UnknownPlatform(_) => error_codes::UNKNOWN_PLATFORM,
+            Error::InvalidCharacter(..) => error_codes::INVALID_CHARACTER,
+            Error::InvalidArgumentType(_) => error_codes::INVALID_ARGUMENT_TYPE,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for shell escaping operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Command line conventions.
+#[derive(EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Platform {
+    /// `/bin/sh`: single quotes, inside which nothing is special.
+    Posix,
+    /// `cmd.exe` passing the line to a program that splits it with `CommandLineToArgvW()`.
+    Windows,
+}
+
+impl Platform {
+    /// Parses an optional platform name, defaulting to POSIX.
+    fn parse(name: Option<&str>) -> Result<Self> {
+        name.map_or(Ok(Platform::Posix), |name| {
+            Platform::from_str(name).map_err(|_| Error::UnknownPlatform(name.into()))
+        })
+    }
+}
+
+/// Quotes one argument.
+///
+/// # Errors
+/// - Returns `Error::InvalidCharacter` for NUL, which cannot be part of an argument, and on
+///   Windows for `"`, `%`, `!`, CR and LF, which `cmd.exe` interprets even inside quotes.
+pub fn arg(value: &str, platform: Platform) -> Result<String> {
+    if value.contains('\0') {
+        return Err(Error::InvalidCharacter('\0', platform));
+    }
+    match platform {
+        Platform::Posix => {
+            let mut out = String::with_capacity(value.len() + 2);
+            out.push('\'');
+            for c in value.chars() {
+                if c == '\'' {
+                    // Close the quote, add an escaped quote, reopen
+                    out.push_str("'\\''");
+                } else {
+                    out.push(c);
+                }
+            }
+            out.push('\'');
+            Ok(out)
+        }
+        Platform::Windows => {
+            if let Some(c) = value
+                .chars()
+                .find(|c| matches!(c, '"' | '%' | '!' | '\r' | '\n'))
+            {
+                return Err(Error::InvalidCharacter(c, platform));
+            }
+            // Without `"` inside, backslashes are literal except for a run before the closing
+            // quote, which must be doubled so the quote still ends the argument
+            let trailing = value.len() - value.trim_end_matches('\\').len();
+            let mut out = String::with_capacity(value.len() + trailing + 2);
+            out.push('"');
+            out.push_str(value);
+            out.push_str(&"\\".repeat(trailing));
+            out.push('"');
+            Ok(out)
+        }
+    }
+}
+
+/// Quotes each argument and joins them with spaces.
+///
+/// # Errors
+/// - Returns `Error::InvalidCharacter` as `arg()` does.
+pub fn argv<S: AsRef<str>>(values: &[S], platform: Platform) -> Result<String> {
+    let quoted = values
+        .iter()
+        .map(|value| arg(value.as_ref(), platform))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(quoted.join(" "))
+}
+
+/// Shell argument quoting.
+#[php_class]
+#[php(name = "Hardened\\ShellEscape")]
+pub struct ShellEscape {}
+
+#[php_impl]
+impl ShellEscape {
+    /// Quote one argument, like a strict `escapeshellarg()`.
+    ///
+    /// # Parameters
+    /// - `value`: `string`
+    /// - `platform`: `?string` `posix` (default) or `windows`.
+    ///
+    /// # Returns
+    /// - `string` e.g. `'it'\''s'` or `"C:\dir\\"`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the platform is unknown, the value contains NUL, or on Windows
+    ///   it contains `"`, `%`, `!` or a line break.
+    fn arg(value: &str, platform: Option<String>) -> Result<String> {
+        arg(value, Platform::parse(platform.as_deref())?)
+    }
+
+    /// Quote every element of a list and join them into one command string.
+    ///
+    /// # Parameters
+    /// - `values`: `array<string|int>` Program and arguments, in order.
+    /// - `platform`: `?string` `posix` (default) or `windows`.
+    ///
+    /// # Returns
+    /// - `string` e.g. `'tar' '-czf' 'my backup.tgz' 'dir'`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the platform is unknown, an element is not a string or int, or
+    ///   an element cannot be quoted.
+    fn argv(values: &ZendHashTable, platform: Option<String>) -> Result<String> {
+        let platform = Platform::parse(platform.as_deref())?;
+        let mut args = Vec::with_capacity(values.len());
+        for (key, value) in values {
+            if let Some(string) = value.string() {
+                args.push(string);
+            } else if let Some(int) = value.long() {
+                args.push(int.to_string());
+            } else {
+                return Err(Error::InvalidArgumentType(key.to_string()));
+            }
+        }
+        argv(&args, platform)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Platform, arg, argv};
+
+    #[test]
+    fn test_posix() {
+        assert_eq!(arg("plain", Platform::Posix).unwrap(), "'plain'");
+        assert_eq!(arg("", Platform::Posix).unwrap(), "''");
+        assert_eq!(arg("it's", Platform::Posix).unwrap(), "'it'\\''s'");
+        assert_eq!(
+            arg("$(rm -rf /); `x` \"y\"\n", Platform::Posix).unwrap(),
+            "'$(rm -rf /); `x` \"y\"\n'"
+        );
+        assert!(matches!(
+            arg("a\0b", Platform::Posix),
+            Err(Error::InvalidCharacter('\0', Platform::Posix))
+        ));
+        assert_eq!(
+            argv(&["tar", "-czf", "my backup.tgz"], Platform::Posix).unwrap(),
+            "'tar' '-czf' 'my backup.tgz'"
+        );
+    }
+
+    #[test]
+    fn test_windows() {
+        assert_eq!(arg("a b", Platform::Windows).unwrap(), "\"a b\"");
+        assert_eq!(
+            arg("C:\\dir\\", Platform::Windows).unwrap(),
+            "\"C:\\dir\\\\\""
+        );
+        assert_eq!(arg("a\\\\b", Platform::Windows).unwrap(), "\"a\\\\b\"");
+        assert_eq!(arg("a&b|c", Platform::Windows).unwrap(), "\"a&b|c\"");
+        for value in ["a\"b", "%PATH%", "!x!", "a\r\nb"] {
+            assert!(matches!(
+                arg(value, Platform::Windows),
+                Err(Error::InvalidCharacter(..))
+            ));
+        }
+        assert_eq!(
+            argv(&["prog", ""], Platform::Windows).unwrap(),
+            "\"prog\" \"\""
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! Tamper-proof, expiring URLs.
+//!
+//! `sign()` appends an `expires` timestamp and an HMAC-SHA256 `signature` to a URL; `verify()`
+//! recomputes the signature over a canonical form of the URL, so links keep working when a
+//! proxy, mail client or framework reorders the query string, while any change to the scheme,
+//! host, path, parameters or expiry invalidates them.
+
+use data_encoding::{BASE64URL, BASE64URL_NOPAD};
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use url::Url;
+use url::form_urlencoded::Serializer;
+
+// Error codes for SignedUrl errors: 2500-2599
+pub mod error_codes {
+    pub const KEY_DECODE: i32 = 2500;
+    pub const KEY_LENGTH: i32 = 2501;
+    pub const URL_PARSE: i32 = 2502;
+    pub const INVALID_TTL: i32 = 2503;
+}
+
+/// Errors produced by `SignedUrl`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Signing key must be valid base64url: {0}")]
+    KeyDecodeError(String),
+
+    #[error("Signing key must contain at least 32 bytes")]
+    KeyLengthError,
+
+    #[error("URL parse error: {0}")]
+    UrlParseError(String),
+
+    #[error("TTL must be a positive number of seconds, got {0}")]
+    InvalidTtl(i64),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::KeyDecodeError(_) => error_codes::KEY_DECODE,
+            Error::KeyLengthError => error_codes::KEY_LENGTH,
+            Error::UrlParseError(_) => error_codes::URL_PARSE,
+            Error::InvalidTtl(_) => error_codes::INVALID_TTL,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for signed URL operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Shortest accepted signing key.
+const MIN_KEY_LEN: usize = 32;
+/// Query parameter holding the expiry as a UNIX timestamp.
+const EXPIRES_PARAM: &str = "expires";
+/// Query parameter holding the base64url HMAC.
+const SIGNATURE_PARAM: &str = "signature";
+
+/// Current UNIX time in seconds.
+fn unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+/// Decoded query parameters, minus `signature`.
+fn unsigned_pairs(url: &Url) -> Vec<(String, String)> {
+    url.query_pairs()
+        .filter(|(name, _)| name != SIGNATURE_PARAM)
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect()
+}
+
+/// The string the signature covers: the URL without fragment and `signature`, with the query
+/// re-encoded and sorted by parameter name.
+///
+/// The sort is stable, so repeated parameters such as `ids[]=1&ids[]=2` keep their relative
+/// order, which PHP relies on when building arrays.
+fn canonical(url: &Url) -> String {
+    let mut pairs = unsigned_pairs(url);
+    pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.set_query(Some(
+        &Serializer::new(String::new()).extend_pairs(pairs).finish(),
+    ));
+    url.into()
+}
+
+/// Generator and verifier of HMAC-signed, expiring URLs.
+#[php_class]
+#[php(name = "Hardened\\SignedUrl")]
+pub struct SignedUrl {
+    /// Key derived from the configured secret, so it is never shared with another primitive.
+    key: [u8; 32],
+}
+
+impl SignedUrl {
+    /// HMAC over the canonical form of a URL.
+    fn _mac(&self, canonical: &str) -> HmacSha256 {
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(&self.key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(canonical.as_bytes());
+        mac
+    }
+
+    /// Signs `url` as of `now`.
+    fn _sign_at(&self, url: &str, ttl: i64, now: i64) -> Result<String> {
+        if ttl <= 0 {
+            return Err(Error::InvalidTtl(ttl));
+        }
+        let expires = now.checked_add(ttl).ok_or(Error::InvalidTtl(ttl))?;
+        let mut url = Url::parse(url).map_err(|err| Error::UrlParseError(err.to_string()))?;
+        let mut pairs: Vec<_> = unsigned_pairs(&url)
+            .into_iter()
+            .filter(|(name, _)| name != EXPIRES_PARAM)
+            .collect();
+        pairs.push((EXPIRES_PARAM.to_string(), expires.to_string()));
+        url.set_query(Some(
+            &Serializer::new(String::new()).extend_pairs(pairs).finish(),
+        ));
+        let signature =
+            BASE64URL_NOPAD.encode(&self._mac(&canonical(&url)).finalize().into_bytes());
+        url.query_pairs_mut()
+            .append_pair(SIGNATURE_PARAM, &signature);
+        Ok(url.into())
+    }
+
+    /// Verifies `url` as of `now`; malformed URLs, missing or repeated `expires` and
+    /// `signature` parameters all fail.
+    fn _verify_at(&self, url: &str, now: i64) -> bool {
+        let Ok(url) = Url::parse(url) else {
+            return false;
+        };
+        let single = |param: &str| {
+            let mut values = url.query_pairs().filter(|(name, _)| name == param);
+            match (values.next(), values.next()) {
+                (Some((_, value)), None) => Some(value),
+                _ => None,
+            }
+        };
+        let Some(signature) = single(SIGNATURE_PARAM)
+            .and_then(|signature| BASE64URL_NOPAD.decode(signature.as_bytes()).ok())
+        else {
+            return false;
+        };
+        let Some(expires) = single(EXPIRES_PARAM).and_then(|expires| expires.parse::<i64>().ok())
+        else {
+            return false;
+        };
+        self._mac(&canonical(&url)).verify_slice(&signature).is_ok() && now <= expires
+    }
+}
+
+#[php_impl]
+impl SignedUrl {
+    /// Constructs a signer.
+    ///
+    /// # Parameters
+    /// - `key`: `string` Base64URL-encoded secret of at least 32 bytes.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the key cannot be decoded or is too short.
+    fn __construct(key: &str) -> Result<Self> {
+        let key = BASE64URL
+            .decode(key.as_bytes())
+            .or_else(|_| BASE64URL_NOPAD.decode(key.as_bytes()))
+            .map_err(|err| Error::KeyDecodeError(err.to_string()))?;
+        if key.len() < MIN_KEY_LEN {
+            return Err(Error::KeyLengthError);
+        }
+        let mut mac =
+            <HmacSha256 as Mac>::new_from_slice(&key).expect("HMAC accepts keys of any length");
+        mac.update(b"hardened-signed-url-v1");
+        Ok(Self {
+            key: mac.finalize().into_bytes().into(),
+        })
+    }
+
+    /// Signs a URL, valid for `ttl` seconds.
+    ///
+    /// Appends `expires` and `signature` query parameters, replacing any already present,
+    /// so a signed URL can be signed again to extend it.
+    ///
+    /// # Parameters
+    /// - `url`: `string` Absolute URL.
+    /// - `ttl`: `int` Lifetime in seconds.
+    ///
+    /// # Returns
+    /// - `string` The signed URL.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the URL cannot be parsed or `ttl` is not positive.
+    fn sign(&self, url: &str, ttl: i64) -> Result<String> {
+        self._sign_at(url, ttl, unix_time())
+    }
+
+    /// Checks a signed URL.
+    ///
+    /// The query string may be in any order; the fragment is ignored.
+    ///
+    /// # Parameters
+    /// - `url`: `string` The full URL as requested.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the signature matches and the URL has not expired.
+    fn verify(&self, url: &str) -> bool {
+        self._verify_at(url, unix_time())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, SignedUrl};
+    use data_encoding::BASE64URL;
+
+    fn signer(byte: u8) -> SignedUrl {
+        SignedUrl::__construct(&BASE64URL.encode(&[byte; 32])).unwrap()
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = signer(7);
+        let url = signer
+            ._sign_at(
+                "https://example.com/download?file=report.pdf&user=42#top",
+                300,
+                1000,
+            )
+            .unwrap();
+        assert!(url.starts_with(
+            "https://example.com/download?file=report.pdf&user=42&expires=1300&signature="
+        ));
+        assert!(url.ends_with("#top"));
+        assert!(signer._verify_at(&url, 1000));
+        assert!(signer._verify_at(&url, 1300));
+        assert!(!signer._verify_at(&url, 1301));
+
+        // Parameter order and fragment do not matter
+        let (_, query) = url.split_once('?').unwrap();
+        let (query, _) = query.split_once('#').unwrap();
+        let mut params: Vec<_> = query.split('&').collect();
+        params.reverse();
+        let reordered = format!("https://example.com/download?{}", params.join("&"));
+        assert!(signer._verify_at(&reordered, 1000));
+
+        // Re-signing replaces the old parameters
+        let extended = signer._sign_at(&url, 600, 1200).unwrap();
+        assert_eq!(extended.matches("expires=").count(), 1);
+        assert_eq!(extended.matches("signature=").count(), 1);
+        assert!(signer._verify_at(&extended, 1700));
+    }
+
+    #[test]
+    fn test_tampering() {
+        let signer = signer(7);
+        let url = signer
+            ._sign_at("https://example.com/a?ids[]=1&ids[]=2", 300, 1000)
+            .unwrap();
+        for tampered in [
+            url.replace("/a?", "/b?"),
+            url.replace("example.com", "example.org"),
+            url.replace("expires=1300", "expires=9999"),
+            url.replace("ids%5B%5D=1&ids%5B%5D=2", "ids%5B%5D=2&ids%5B%5D=1"),
+            format!("{url}&admin=1"),
+            format!("{url}&signature=x"),
+            url.replace("https:", "http:"),
+            url.split("&signature=").next().unwrap().to_string(),
+        ] {
+            assert!(!signer._verify_at(&tampered, 1000), "{tampered}");
+        }
+        assert!(!self::signer(8)._verify_at(&url, 1000));
+        assert!(!signer._verify_at("not a url", 1000));
+        assert!(matches!(
+            signer._sign_at("https://example.com/", 0, 1000),
+            Err(Error::InvalidTtl(0))
+        ));
+        assert!(matches!(
+            signer._sign_at("/relative", 60, 1000),
+            Err(Error::UrlParseError(_))
+        ));
+        assert!(matches!(
+            SignedUrl::__construct(&BASE64URL.encode(&[0; 16])),
+            Err(Error::KeyLengthError)
+        ));
+    }
+}
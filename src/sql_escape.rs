@@ -0,0 +1,268 @@
+//! Escaping for the two SQL injection spots prepared statements cannot cover: identifiers
+//! (table and column names chosen at runtime) and the wildcards of a bound `LIKE` pattern.
+//!
+//! Identifiers are validated against the dialect's rules and rejected rather than silently
+//! truncated or altered, so a quoted name always refers to exactly the object asked for.
+
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+use thiserror::Error;
+
+// Error codes for SqlEscape errors: 4000-4099
+pub mod error_codes {
+    pub const UNKNOWN_DIALECT: i32 = 4000;
+    pub const INVALID_IDENTIFIER: i32 = 4001;
+    pub const INVALID_ESCAPE_CHAR: i32 = 4002;
+}
+
+/// Errors produced by `SqlEscape`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown SQL dialect: {0}")]
+    UnknownDialect(String),
+
+    #[error("Invalid {0} identifier: {1}")]
+    InvalidIdentifier(Dialect, &'static str),
+
+    #[error("LIKE escape character must be a single character other than `%`, `_`, `[` or `]`")]
+    InvalidEscapeChar,
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::UnknownDialect(_) => error_codes::UNKNOWN_DIALECT,
+            Error::InvalidIdentifier(..) => error_codes::INVALID_IDENTIFIER,
+            Error::InvalidEscapeChar => error_codes::INVALID_ESCAPE_CHAR,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for SQL escaping operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// SQL dialects with distinct identifier quoting rules.
+#[derive(EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(ascii_case_insensitive)]
+pub enum Dialect {
+    /// `` `name` ``; at most 64 characters from the Basic Multilingual Plane.
+    #[strum(serialize = "mysql", serialize = "mariadb")]
+    MySql,
+    /// `"name"`; at most 63 bytes, the server truncates longer names.
+    #[strum(serialize = "postgres", serialize = "pgsql", serialize = "postgresql")]
+    Postgres,
+    /// `[name]`; at most 128 characters.
+    #[strum(serialize = "mssql", serialize = "sqlsrv")]
+    MsSql,
+    /// `"name"`; no length limit.
+    #[strum(serialize = "sqlite")]
+    Sqlite,
+}
+
+impl Dialect {
+    /// Opening and closing quote characters.
+    fn quotes(self) -> (char, char) {
+        match self {
+            Dialect::MySql => ('`', '`'),
+            Dialect::Postgres | Dialect::Sqlite => ('"', '"'),
+            Dialect::MsSql => ('[', ']'),
+        }
+    }
+
+    /// Checks `name` against the dialect's limits.
+    fn validate(self, name: &str) -> Result<()> {
+        let invalid = |reason| Err(Error::InvalidIdentifier(self, reason));
+        if name.is_empty() {
+            return invalid("empty name");
+        }
+        // NUL is never allowed; other control characters only enable log and error injection
+        if name.chars().any(char::is_control) {
+            return invalid("control character");
+        }
+        match self {
+            Dialect::MySql => {
+                if name.chars().count() > 64 {
+                    return invalid("longer than 64 characters");
+                }
+                if name.chars().any(|c| u32::from(c) > 0xFFFF) {
+                    return invalid("character outside the Basic Multilingual Plane");
+                }
+                if name.ends_with(' ') {
+                    return invalid("trailing space");
+                }
+            }
+            Dialect::Postgres => {
+                if name.len() > 63 {
+                    return invalid("longer than 63 bytes");
+                }
+            }
+            Dialect::MsSql => {
+                if name.chars().count() > 128 {
+                    return invalid("longer than 128 characters");
+                }
+            }
+            Dialect::Sqlite => {}
+        }
+        Ok(())
+    }
+}
+
+/// Validates and quotes a single identifier, doubling any closing quote inside it.
+///
+/// # Errors
+/// - Returns `Error::InvalidIdentifier` if the name breaks the dialect's rules.
+pub fn identifier(name: &str, dialect: Dialect) -> Result<String> {
+    dialect.validate(name)?;
+    let (open, close) = dialect.quotes();
+    let mut out = String::with_capacity(name.len() + 2);
+    out.push(open);
+    for c in name.chars() {
+        if c == close {
+            out.push(close);
+        }
+        out.push(c);
+    }
+    out.push(close);
+    Ok(out)
+}
+
+/// Escapes `LIKE` wildcards (`%`, `_`, and `[` for SQL Server) and the escape character itself.
+///
+/// # Errors
+/// - Returns `Error::InvalidEscapeChar` if the escape character is a wildcard or control
+///   character.
+pub fn like_pattern(value: &str, escape_char: char) -> Result<String> {
+    if matches!(escape_char, '%' | '_' | '[' | ']') || escape_char.is_control() {
+        return Err(Error::InvalidEscapeChar);
+    }
+    let mut out = String::with_capacity(value.len() + 8);
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '[') || c == escape_char {
+            out.push(escape_char);
+        }
+        out.push(c);
+    }
+    Ok(out)
+}
+
+/// SQL identifier quoting and `LIKE` pattern escaping.
+#[php_class]
+#[php(name = "Hardened\\SqlEscape")]
+pub struct SqlEscape {}
+
+#[php_impl]
+impl SqlEscape {
+    /// Quote a table, column or schema name for interpolation into SQL.
+    ///
+    /// The name is one identifier: `a.b` becomes a single quoted name containing a dot, so
+    /// quote each part of a qualified name separately. Still check names against an
+    /// allowlist when users pick them; quoting stops injection, not access to other columns.
+    ///
+    /// # Parameters
+    /// - `name`: `string` The identifier.
+    /// - `dialect`: `string` `mysql` (or `mariadb`), `postgres` (or `pgsql`), `mssql` (or
+    ///   `sqlsrv`), or `sqlite`.
+    ///
+    /// # Returns
+    /// - `string` e.g. `` `order` ``, `"order"` or `[order]`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the dialect is unknown, or the name is empty, contains control
+    ///   characters or breaks the dialect's length or character rules.
+    fn identifier(name: &str, dialect: &str) -> Result<String> {
+        let dialect =
+            Dialect::from_str(dialect).map_err(|_| Error::UnknownDialect(dialect.into()))?;
+        identifier(name, dialect)
+    }
+
+    /// Escape a value for use as a literal inside a bound `LIKE` pattern.
+    ///
+    /// Bind the result as a parameter and name the escape character in the query, e.g.
+    /// `WHERE name LIKE ? ESCAPE '\'` (SQL Server has no default escape character).
+    ///
+    /// # Parameters
+    /// - `value`: `string` Text to match literally.
+    /// - `escapeChar`: `?string` Escape character. Default: `\`.
+    ///
+    /// # Returns
+    /// - `string` The value with `%`, `_`, `[` and the escape character escaped; add your own
+    ///   wildcards around it, e.g. `'%' . SqlEscape::likePattern($q) . '%'`.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `escapeChar` is not exactly one character, or is a wildcard.
+    fn like_pattern(value: &str, escape_char: Option<String>) -> Result<String> {
+        let escape_char = match escape_char.as_deref() {
+            None => '\\',
+            Some(escape_char) => {
+                let mut chars = escape_char.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => return Err(Error::InvalidEscapeChar),
+                }
+            }
+        };
+        like_pattern(value, escape_char)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dialect, Error, identifier, like_pattern};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_identifier() {
+        assert_eq!(identifier("order", Dialect::MySql).unwrap(), "`order`");
+        assert_eq!(identifier("a`b", Dialect::MySql).unwrap(), "`a``b`");
+        assert_eq!(identifier("a\"b", Dialect::Postgres).unwrap(), "\"a\"\"b\"");
+        assert_eq!(identifier("a]b[c", Dialect::MsSql).unwrap(), "[a]]b[c]");
+        assert_eq!(identifier("t.c", Dialect::Sqlite).unwrap(), "\"t.c\"");
+        assert_eq!(Dialect::from_str("PgSQL").unwrap(), Dialect::Postgres);
+        assert!(Dialect::from_str("oracle").is_err());
+    }
+
+    #[test]
+    fn test_identifier_validation() {
+        for (name, dialect) in [
+            ("", Dialect::Sqlite),
+            ("a\0b", Dialect::MsSql),
+            ("a\nb", Dialect::Postgres),
+            ("name ", Dialect::MySql),
+            ("\u{1F600}", Dialect::MySql),
+        ] {
+            assert!(matches!(
+                identifier(name, dialect),
+                Err(Error::InvalidIdentifier(..))
+            ));
+        }
+        assert!(identifier(&"a".repeat(64), Dialect::MySql).is_ok());
+        assert!(identifier(&"a".repeat(65), Dialect::MySql).is_err());
+        assert!(identifier(&"a".repeat(63), Dialect::Postgres).is_ok());
+        // 32 two-byte characters exceed the 63-byte limit
+        assert!(identifier(&"\u{e9}".repeat(32), Dialect::Postgres).is_err());
+        assert!(identifier(&"a".repeat(129), Dialect::MsSql).is_err());
+    }
+
+    #[test]
+    fn test_like_pattern() {
+        assert_eq!(like_pattern("50%_off", '\\').unwrap(), "50\\%\\_off");
+        assert_eq!(like_pattern("a\\b[c]", '\\').unwrap(), "a\\\\b\\[c]");
+        assert_eq!(like_pattern("a!b%", '!').unwrap(), "a!!b!%");
+        assert!(matches!(
+            like_pattern("x", '%'),
+            Err(Error::InvalidEscapeChar)
+        ));
+    }
+}
@@ -0,0 +1,336 @@
+//! Subresource Integrity (SRI) metadata generation and verification.
+//!
+//! Produces `integrity` attribute values such as `sha384-…` for `<script>` and `<link>` tags
+//! and checks content against them the way browsers do: only the strongest algorithm present
+//! in the metadata counts, and any of its digests may match.
+
+use crate::constant_time;
+use crate::to_str;
+use data_encoding::{BASE64, BASE64_NOPAD};
+use ext_php_rs::binary::Binary;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::types::Zval;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use sha2::digest::DynDigest;
+use sha2::{Sha256, Sha384, Sha512};
+use std::fs::File;
+use std::io::Read;
+use std::str::FromStr;
+use strum::{Display, EnumString};
+use thiserror::Error;
+
+// Error codes for Sri errors: 2300-2399
+pub mod error_codes {
+    pub const UNSUPPORTED_ALGORITHM: i32 = 2300;
+    pub const INVALID_INTEGRITY: i32 = 2301;
+    pub const FILE_READ: i32 = 2302;
+    pub const STRING_CONVERSION: i32 = 2303;
+}
+
+/// Errors produced by `Sri`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unsupported SRI hash algorithm: {0} (expected sha256, sha384 or sha512)")]
+    UnsupportedAlgorithm(String),
+
+    #[error("Integrity metadata contains no supported hash: {0:?}")]
+    InvalidIntegrity(String),
+
+    #[error("Failed to read {0}: {1}")]
+    FileRead(String, String),
+
+    #[error("Algorithms must be a string or an array of strings")]
+    StringConversion,
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::UnsupportedAlgorithm(_) => error_codes::UNSUPPORTED_ALGORITHM,
+            Error::InvalidIntegrity(_) => error_codes::INVALID_INTEGRITY,
+            Error::FileRead(..) => error_codes::FILE_READ,
+            Error::StringConversion => error_codes::STRING_CONVERSION,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for SRI operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Hash algorithms allowed by the SRI specification, weakest first.
+#[derive(EnumString, Display, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Algorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl Algorithm {
+    fn hasher(self) -> Box<dyn DynDigest> {
+        match self {
+            Self::Sha256 => Box::new(Sha256::default()),
+            Self::Sha384 => Box::new(Sha384::default()),
+            Self::Sha512 => Box::new(Sha512::default()),
+        }
+    }
+
+    fn parse(algo: &str) -> Result<Self> {
+        Self::from_str(algo.trim()).map_err(|_| Error::UnsupportedAlgorithm(algo.to_string()))
+    }
+}
+
+/// Parses a list of algorithms, dropping duplicates; an empty list means `sha384`.
+///
+/// # Errors
+/// - Returns `Error::UnsupportedAlgorithm` for anything but `sha256`, `sha384`, `sha512`.
+pub fn parse_algorithms<'a>(algos: impl IntoIterator<Item = &'a str>) -> Result<Vec<Algorithm>> {
+    let mut parsed = Vec::new();
+    for algo in algos {
+        let algo = Algorithm::parse(algo)?;
+        if !parsed.contains(&algo) {
+            parsed.push(algo);
+        }
+    }
+    if parsed.is_empty() {
+        parsed.push(Algorithm::Sha384);
+    }
+    Ok(parsed)
+}
+
+/// Formats finished hashers as space-separated `algo-base64` tokens.
+fn metadata(algos: &[Algorithm], hashers: Vec<Box<dyn DynDigest>>) -> String {
+    algos
+        .iter()
+        .zip(hashers)
+        .map(|(algo, hasher)| format!("{algo}-{}", BASE64.encode(&hasher.finalize())))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Computes integrity metadata for `content`, one space-separated token per algorithm.
+#[must_use]
+pub fn hash(content: &[u8], algos: &[Algorithm]) -> String {
+    let mut hashers: Vec<_> = algos.iter().map(|algo| algo.hasher()).collect();
+    for hasher in &mut hashers {
+        hasher.update(content);
+    }
+    metadata(algos, hashers)
+}
+
+/// Computes integrity metadata for a file, reading it in chunks.
+///
+/// # Errors
+/// - Returns `Error::FileRead` if the file cannot be opened or read.
+pub fn hash_file(path: &str, algos: &[Algorithm]) -> Result<String> {
+    let read_error = |err: std::io::Error| Error::FileRead(path.to_string(), err.to_string());
+    let mut file = File::open(path).map_err(read_error)?;
+    let mut hashers: Vec<_> = algos.iter().map(|algo| algo.hasher()).collect();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).map_err(read_error)?;
+        if read == 0 {
+            break;
+        }
+        for hasher in &mut hashers {
+            hasher.update(&buffer[..read]);
+        }
+    }
+    Ok(metadata(algos, hashers))
+}
+
+/// Parses integrity metadata into `(algorithm, digest)` pairs.
+///
+/// Tokens with an unknown algorithm, bad base64 or a digest of the wrong length are skipped,
+/// as browsers do; `?options` suffixes are ignored.
+fn parse_metadata(integrity: &str) -> Vec<(Algorithm, Vec<u8>)> {
+    integrity
+        .split_ascii_whitespace()
+        .filter_map(|token| {
+            let token = token.split_once('?').map_or(token, |(token, _)| token);
+            let (algo, digest) = token.split_once('-')?;
+            let algo = Algorithm::from_str(algo).ok()?;
+            let digest = BASE64
+                .decode(digest.as_bytes())
+                .or_else(|_| BASE64_NOPAD.decode(digest.as_bytes()))
+                .ok()?;
+            (digest.len() == algo.hasher().output_size()).then_some((algo, digest))
+        })
+        .collect()
+}
+
+/// Checks `content` against integrity metadata.
+///
+/// Only digests of the strongest algorithm present are compared, so adding a weaker hash
+/// next to a strong one cannot widen what is accepted.
+///
+/// # Errors
+/// - Returns `Error::InvalidIntegrity` if the metadata has no usable token. Browsers treat
+///   that as "no integrity check"; here it is an error so a typo cannot disable verification.
+pub fn verify(content: &[u8], integrity: &str) -> Result<bool> {
+    let metadata = parse_metadata(integrity);
+    let strongest = metadata
+        .iter()
+        .map(|(algo, _)| *algo)
+        .max()
+        .ok_or_else(|| Error::InvalidIntegrity(integrity.to_string()))?;
+    let mut hasher = strongest.hasher();
+    hasher.update(content);
+    let actual = hasher.finalize();
+    Ok(metadata
+        .iter()
+        .filter(|(algo, _)| *algo == strongest)
+        .fold(false, |matched, (_, digest)| {
+            constant_time::eq(&actual, digest) | matched
+        }))
+}
+
+/// Reads the `string|array` algorithm argument; `null` means `sha384`.
+fn algorithms_arg(algos: Option<&Zval>) -> Result<Vec<Algorithm>> {
+    let Some(algos) = algos.filter(|algos| !algos.is_null()) else {
+        return parse_algorithms([]);
+    };
+    let list: Vec<String> = if let Some(array) = algos.array() {
+        array
+            .values()
+            .map(|value| to_str(value).map_err(|_| Error::StringConversion))
+            .collect::<Result<_>>()?
+    } else {
+        vec![to_str(algos).map_err(|_| Error::StringConversion)?]
+    };
+    if list.is_empty() {
+        return Err(Error::UnsupportedAlgorithm(String::new()));
+    }
+    parse_algorithms(list.iter().map(String::as_str))
+}
+
+/// Subresource Integrity hash generator and verifier.
+#[php_class]
+#[php(name = "Hardened\\Sri")]
+pub struct Sri {}
+
+#[php_impl]
+impl Sri {
+    /// Compute the `integrity` attribute value for a string.
+    ///
+    /// # Parameters
+    /// - `content`: `string` The resource body, byte for byte as served.
+    /// - `algos`: `string|array|null` One or more of `sha256`, `sha384`, `sha512`.
+    ///   Default: `sha384`.
+    ///
+    /// # Returns
+    /// - `string` Space-separated metadata, e.g. `sha384-…`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if an algorithm is not supported.
+    fn hash_string(content: Binary<u8>, algos: Option<&Zval>) -> Result<String> {
+        Ok(hash(&content, &algorithms_arg(algos)?))
+    }
+
+    /// Compute the `integrity` attribute value for a file without loading it into memory.
+    ///
+    /// # Parameters
+    /// - `path`: `string` Path of the built asset.
+    /// - `algos`: `string|array|null` One or more of `sha256`, `sha384`, `sha512`.
+    ///   Default: `sha384`.
+    ///
+    /// # Returns
+    /// - `string` Space-separated metadata, e.g. `sha256-… sha384-…`.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if an algorithm is not supported or the file cannot be read.
+    fn hash_file(path: &str, algos: Option<&Zval>) -> Result<String> {
+        hash_file(path, &algorithms_arg(algos)?)
+    }
+
+    /// Check content against an `integrity` attribute value.
+    ///
+    /// As in browsers, only the strongest algorithm listed is used and unknown tokens
+    /// are ignored.
+    ///
+    /// # Parameters
+    /// - `content`: `string` The resource body.
+    /// - `integrity`: `string` Metadata such as `sha384-… sha512-…`.
+    ///
+    /// # Returns
+    /// - `bool` `true` if a digest of the strongest algorithm matches.
+    ///
+    /// # Exceptions
+    /// - Throws an exception if the metadata contains no supported hash.
+    fn verify(content: Binary<u8>, integrity: &str) -> Result<bool> {
+        verify(&content, integrity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Algorithm, Error, hash, hash_file, parse_algorithms, verify};
+
+    #[test]
+    fn test_hash() {
+        // `echo -n alert(1) | openssl dgst -sha384 -binary | openssl base64 -A`
+        assert_eq!(
+            hash(b"alert(1)", &[Algorithm::Sha384]),
+            "sha384-HT2E9NfWiuQ/w1PRai+hTyqW16NIoCGA/m8VQDUopfAtcz6YQjtsMmQd5uRbVDpW"
+        );
+        let algos = parse_algorithms(["SHA256", "sha512", "sha256"]).unwrap();
+        assert_eq!(algos, [Algorithm::Sha256, Algorithm::Sha512]);
+        let metadata = hash(b"", &algos);
+        assert!(
+            metadata.starts_with("sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU= sha512-")
+        );
+        assert_eq!(parse_algorithms([]).unwrap(), [Algorithm::Sha384]);
+        assert!(matches!(
+            parse_algorithms(["md5"]),
+            Err(Error::UnsupportedAlgorithm(_))
+        ));
+    }
+
+    #[test]
+    fn test_hash_file() {
+        let path = std::env::temp_dir().join("php-hardened-sri-test.js");
+        std::fs::write(&path, vec![b'x'; 200_000]).unwrap();
+        let algos = [Algorithm::Sha256, Algorithm::Sha384];
+        assert_eq!(
+            hash_file(path.to_str().unwrap(), &algos).unwrap(),
+            hash(&vec![b'x'; 200_000], &algos)
+        );
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            hash_file(path.to_str().unwrap(), &algos),
+            Err(Error::FileRead(..))
+        ));
+    }
+
+    #[test]
+    fn test_verify() {
+        let sha256 = hash(b"alert(1)", &[Algorithm::Sha256]);
+        let sha384 = hash(b"alert(1)", &[Algorithm::Sha384]);
+        assert!(verify(b"alert(1)", &sha384).unwrap());
+        assert!(!verify(b"alert(2)", &sha384).unwrap());
+        // Unpadded base64, options and unknown tokens are tolerated
+        let unpadded = sha256.trim_end_matches('=');
+        assert!(verify(b"alert(1)", &format!("md5-abc {unpadded}?ct=js")).unwrap());
+        // Only the strongest algorithm counts
+        let wrong_sha512 = hash(b"other", &[Algorithm::Sha512]);
+        assert!(!verify(b"alert(1)", &format!("{sha256} {wrong_sha512}")).unwrap());
+        // Any digest of the strongest algorithm may match
+        let other_sha384 = hash(b"other", &[Algorithm::Sha384]);
+        assert!(verify(b"alert(1)", &format!("{other_sha384} {sha384}")).unwrap());
+        assert!(matches!(
+            verify(b"alert(1)", "sha384-tooShort"),
+            Err(Error::InvalidIntegrity(_))
+        ));
+        assert!(verify(b"alert(1)", "").is_err());
+    }
+}
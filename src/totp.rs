@@ -0,0 +1,459 @@
+//! Time-based (RFC 6238) and counter-based (RFC 4226) one-time passwords.
+//!
+//! Secrets are exchanged as base32, the format authenticator apps expect. Verification compares
+//! every code in the drift window in constant time and does not stop at the first match, so the
+//! timing reveals neither the code nor how far the client clock is off.
+
+use crate::constant_time;
+use crate::rng::random_bytes;
+use data_encoding::BASE32_NOPAD;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use strum::{Display, EnumString};
+use thiserror::Error;
+
+// Error codes for Totp errors: 2600-2699
+pub mod error_codes {
+    pub const INVALID_SECRET: i32 = 2600;
+    pub const SECRET_TOO_SHORT: i32 = 2601;
+    pub const INVALID_DIGITS: i32 = 2602;
+    pub const INVALID_PERIOD: i32 = 2603;
+    pub const UNSUPPORTED_ALGORITHM: i32 = 2604;
+    pub const NEGATIVE_VALUE: i32 = 2605;
+    pub const INVALID_LABEL: i32 = 2606;
+    pub const RANDOM: i32 = 2607;
+    pub const INVALID_WINDOW: i32 = 2608;
+}
+
+/// Errors produced by `Totp`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Secret must be base32: {0}")]
+    InvalidSecret(String),
+
+    #[error("Secret must be at least 16 bytes, got {0}")]
+    SecretTooShort(usize),
+
+    #[error("Digits must be between 6 and 8, got {0}")]
+    InvalidDigits(i64),
+
+    #[error("Period must be a positive number of seconds, got {0}")]
+    InvalidPeriod(i64),
+
+    #[error("Unsupported OTP algorithm: {0} (expected sha1, sha256 or sha512)")]
+    UnsupportedAlgorithm(String),
+
+    #[error("{0} must not be negative, got {1}")]
+    NegativeValue(&'static str, i64),
+
+    #[error("{0} must not be empty or contain a colon")]
+    InvalidLabel(&'static str),
+
+    #[error("Failed to generate secret: {0}")]
+    Random(String),
+
+    #[error("Window must be between 0 and 10 steps, got {0}")]
+    InvalidWindow(i64),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::InvalidSecret(_) => error_codes::INVALID_SECRET,
+            Error::SecretTooShort(_) => error_codes::SECRET_TOO_SHORT,
+            Error::InvalidDigits(_) => error_codes::INVALID_DIGITS,
+            Error::InvalidPeriod(_) => error_codes::INVALID_PERIOD,
+            Error::UnsupportedAlgorithm(_) => error_codes::UNSUPPORTED_ALGORITHM,
+            Error::NegativeValue(..) => error_codes::NEGATIVE_VALUE,
+            Error::InvalidLabel(_) => error_codes::INVALID_LABEL,
+            Error::Random(_) => error_codes::RANDOM,
+            Error::InvalidWindow(_) => error_codes::INVALID_WINDOW,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for OTP operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// RFC 4226 requires shared secrets of at least 128 bits.
+const MIN_SECRET_LEN: usize = 16;
+/// Secret size of `generateSecret()`, the output size of HMAC-SHA1.
+const DEFAULT_SECRET_LEN: usize = 20;
+const DEFAULT_DIGITS: u32 = 6;
+const DEFAULT_PERIOD: i64 = 30;
+const DEFAULT_WINDOW: i64 = 1;
+/// Largest drift window, which bounds the work an attacker can make `verify()` do.
+const MAX_WINDOW: i64 = 10;
+
+/// HMAC hash functions supported by authenticator apps.
+#[derive(EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "UPPERCASE", ascii_case_insensitive)]
+pub enum Algorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+/// HMAC of `message` under `key` with the hash function `M` is built on.
+fn mac<M: Mac + KeyInit>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Current UNIX time in seconds.
+fn unix_time() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+/// Decodes a base32 secret, tolerating lowercase, spaces and padding as apps display them.
+fn decode_secret(secret: &str) -> Result<Vec<u8>> {
+    let normalized: String = secret
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && *c != '=')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    BASE32_NOPAD
+        .decode(normalized.as_bytes())
+        .map_err(|err| Error::InvalidSecret(err.to_string()))
+}
+
+/// Percent-encodes everything but RFC 3986 unreserved characters, for the `otpauth:` URI.
+fn percent_encode(value: &str) -> String {
+    value.bytes().fold(String::new(), |mut out, byte| {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            out.push(char::from(byte));
+        } else {
+            let _ = write!(out, "%{byte:02X}");
+        }
+        out
+    })
+}
+
+/// One-time password generator and verifier for a single shared secret.
+#[php_class]
+#[php(name = "Hardened\\Totp")]
+pub struct Totp {
+    secret: Vec<u8>,
+    digits: u32,
+    period: i64,
+    algorithm: Algorithm,
+}
+
+impl Totp {
+    /// RFC 4226 code for `counter`.
+    fn _hotp(&self, counter: u64) -> String {
+        let counter = counter.to_be_bytes();
+        let digest = match self.algorithm {
+            Algorithm::Sha1 => mac::<Hmac<Sha1>>(&self.secret, &counter),
+            Algorithm::Sha256 => mac::<Hmac<Sha256>>(&self.secret, &counter),
+            Algorithm::Sha512 => mac::<Hmac<Sha512>>(&self.secret, &counter),
+        };
+        // Dynamic truncation: the low nibble of the last byte picks a 31-bit window
+        let offset = usize::from(digest[digest.len() - 1] & 0x0f);
+        let value = u32::from_be_bytes([
+            digest[offset] & 0x7f,
+            digest[offset + 1],
+            digest[offset + 2],
+            digest[offset + 3],
+        ]);
+        let digits = self.digits as usize;
+        format!("{:0digits$}", value % 10u32.pow(self.digits))
+    }
+
+    /// Time step containing `time`.
+    fn _step(&self, time: Option<i64>) -> Result<u64> {
+        let time = time.unwrap_or_else(unix_time);
+        let time = u64::try_from(time).map_err(|_| Error::NegativeValue("Time", time))?;
+        Ok(time / self.period as u64)
+    }
+
+    /// Whether `code` matches any time step within `window` steps of `step`.
+    fn _verify_step(&self, code: &str, window: u64, step: u64) -> bool {
+        let first = step.saturating_sub(window);
+        let last = step.saturating_add(window);
+        (first..=last).fold(false, |matched, step| {
+            constant_time::eq(self._hotp(step).as_bytes(), code.as_bytes()) | matched
+        })
+    }
+}
+
+#[php_impl]
+impl Totp {
+    /// Constructs a generator for a shared secret.
+    ///
+    /// # Parameters
+    /// - `secret`: `string` Base32 secret; spaces, padding and lowercase are accepted.
+    /// - `digits`: `?int` Code length, 6 to 8. Default: `6`.
+    /// - `period`: `?int` Time step in seconds. Default: `30`.
+    /// - `algorithm`: `?string` `sha1`, `sha256` or `sha512`. Default: `sha1`, the only one
+    ///   every authenticator app supports.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the secret is not base32 or shorter than 16 bytes, or an option
+    ///   is out of range.
+    fn __construct(
+        secret: &str,
+        digits: Option<i64>,
+        period: Option<i64>,
+        algorithm: Option<String>,
+    ) -> Result<Self> {
+        let secret = decode_secret(secret)?;
+        if secret.len() < MIN_SECRET_LEN {
+            return Err(Error::SecretTooShort(secret.len()));
+        }
+        let digits = match digits {
+            None => DEFAULT_DIGITS,
+            Some(digits @ 6..=8) => digits as u32,
+            Some(digits) => return Err(Error::InvalidDigits(digits)),
+        };
+        let period = period.unwrap_or(DEFAULT_PERIOD);
+        if period <= 0 {
+            return Err(Error::InvalidPeriod(period));
+        }
+        let algorithm = match algorithm {
+            None => Algorithm::Sha1,
+            Some(algorithm) => Algorithm::from_str(algorithm.trim())
+                .map_err(|_| Error::UnsupportedAlgorithm(algorithm))?,
+        };
+        Ok(Self {
+            secret,
+            digits,
+            period,
+            algorithm,
+        })
+    }
+
+    /// Generate a random base32 secret.
+    ///
+    /// # Parameters
+    /// - `bytes`: `?int` Secret size in bytes, at least 16. Default: `20`.
+    ///
+    /// # Returns
+    /// - `string` Unpadded base32 secret to store server-side and show to the user.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `bytes` is below 16 or random generation fails.
+    fn generate_secret(bytes: Option<usize>) -> Result<String> {
+        let bytes = bytes.unwrap_or(DEFAULT_SECRET_LEN);
+        if bytes < MIN_SECRET_LEN {
+            return Err(Error::SecretTooShort(bytes));
+        }
+        Ok(
+            BASE32_NOPAD
+                .encode(&random_bytes(bytes).map_err(|err| Error::Random(err.to_string()))?),
+        )
+    }
+
+    /// Compute the time-based code.
+    ///
+    /// # Parameters
+    /// - `time`: `?int` UNIX timestamp. Default: now.
+    ///
+    /// # Returns
+    /// - `string` Zero-padded code.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `time` is negative.
+    fn code(&self, time: Option<i64>) -> Result<String> {
+        Ok(self._hotp(self._step(time)?))
+    }
+
+    /// Compute the counter-based (HOTP) code.
+    ///
+    /// # Parameters
+    /// - `counter`: `int` Moving factor shared with the client.
+    ///
+    /// # Returns
+    /// - `string` Zero-padded code.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `counter` is negative.
+    fn hotp(&self, counter: i64) -> Result<String> {
+        let counter =
+            u64::try_from(counter).map_err(|_| Error::NegativeValue("Counter", counter))?;
+        Ok(self._hotp(counter))
+    }
+
+    /// Check a time-based code, allowing for clock drift.
+    ///
+    /// Every step in the window is computed and compared, so the run time is the same
+    /// whether and where the code matches. Callers should remember the last accepted step
+    /// (`intdiv(time(), $period)`) to reject a code being replayed within its window.
+    ///
+    /// # Parameters
+    /// - `code`: `string` Code entered by the user.
+    /// - `window`: `?int` Steps accepted before and after the current one, at most 10.
+    ///   Default: `1`.
+    /// - `time`: `?int` UNIX timestamp. Default: now.
+    ///
+    /// # Returns
+    /// - `bool` `true` if the code matches a step in the window.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `window` is not between 0 and 10 or `time` is negative.
+    fn verify(&self, code: &str, window: Option<i64>, time: Option<i64>) -> Result<bool> {
+        let window = window.unwrap_or(DEFAULT_WINDOW);
+        if !(0..=MAX_WINDOW).contains(&window) {
+            return Err(Error::InvalidWindow(window));
+        }
+        Ok(self._verify_step(code.trim(), window as u64, self._step(time)?))
+    }
+
+    /// Build the `otpauth://` URI that authenticator apps import, usually via a QR code.
+    ///
+    /// # Parameters
+    /// - `issuer`: `string` Service name shown in the app, e.g. `Example Inc.`.
+    /// - `account`: `string` Account name, e.g. the user's e-mail address.
+    ///
+    /// # Returns
+    /// - `string` `otpauth://totp/Issuer:account?secret=…&issuer=…&algorithm=…&digits=…&period=…`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if `issuer` or `account` is empty or contains a colon.
+    fn provisioning_uri(&self, issuer: &str, account: &str) -> Result<String> {
+        if issuer.is_empty() || issuer.contains(':') {
+            return Err(Error::InvalidLabel("Issuer"));
+        }
+        if account.is_empty() || account.contains(':') {
+            return Err(Error::InvalidLabel("Account"));
+        }
+        let issuer = percent_encode(issuer);
+        Ok(format!(
+            "otpauth://totp/{issuer}:{}?secret={}&issuer={issuer}&algorithm={}&digits={}&period={}",
+            percent_encode(account),
+            BASE32_NOPAD.encode(&self.secret),
+            self.algorithm,
+            self.digits,
+            self.period,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Totp};
+    use data_encoding::BASE32_NOPAD;
+
+    fn totp(secret: &[u8], digits: i64, algorithm: &str) -> Totp {
+        Totp::__construct(
+            &BASE32_NOPAD.encode(secret),
+            Some(digits),
+            None,
+            Some(algorithm.to_string()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rfc_vectors() {
+        // RFC 4226 Appendix D
+        let hotp = totp(b"12345678901234567890", 6, "sha1");
+        assert_eq!(hotp.hotp(0).unwrap(), "755224");
+        assert_eq!(hotp.hotp(1).unwrap(), "287082");
+        assert_eq!(hotp.hotp(9).unwrap(), "520489");
+
+        // RFC 6238 Appendix B
+        let sha1 = totp(b"12345678901234567890", 8, "SHA1");
+        for (time, code) in [
+            (59, "94287082"),
+            (1_111_111_109, "07081804"),
+            (1_111_111_111, "14050471"),
+            (1_234_567_890, "89005924"),
+            (2_000_000_000, "69279037"),
+        ] {
+            assert_eq!(sha1.code(Some(time)).unwrap(), code);
+        }
+        let sha256 = totp(b"12345678901234567890123456789012", 8, "sha256");
+        assert_eq!(sha256.code(Some(59)).unwrap(), "46119246");
+        let sha512 = totp(
+            b"1234567890123456789012345678901234567890123456789012345678901234",
+            8,
+            "sha512",
+        );
+        assert_eq!(sha512.code(Some(59)).unwrap(), "90693936");
+    }
+
+    #[test]
+    fn test_verify() {
+        let totp =
+            Totp::__construct("gezd gnbv gy3t qojq gezd gnbv gy3t qojq", None, None, None).unwrap();
+        let code = totp.code(Some(1_000_000)).unwrap();
+        assert!(totp.verify(&code, None, Some(1_000_000)).unwrap());
+        assert!(totp.verify(&code, None, Some(1_000_029)).unwrap());
+        assert!(!totp.verify(&code, None, Some(1_000_090)).unwrap());
+        assert!(totp.verify(&code, Some(3), Some(1_000_090)).unwrap());
+        assert!(!totp.verify(&code, Some(0), Some(999_960)).unwrap());
+        assert!(!totp.verify("", None, Some(1_000_000)).unwrap());
+        assert!(
+            !totp
+                .verify(&format!("{code}0"), None, Some(1_000_000))
+                .unwrap()
+        );
+        assert!(matches!(
+            totp.verify(&code, Some(-1), None),
+            Err(Error::InvalidWindow(-1))
+        ));
+    }
+
+    #[test]
+    fn test_secrets_and_uri() {
+        let secret = Totp::generate_secret(None).unwrap();
+        assert_eq!(secret.len(), 32);
+        assert_ne!(secret, Totp::generate_secret(None).unwrap());
+        assert!(matches!(
+            Totp::generate_secret(Some(10)),
+            Err(Error::SecretTooShort(10))
+        ));
+        assert!(matches!(
+            Totp::__construct("GEZDGNBVGY3TQOJQ", None, None, None),
+            Err(Error::SecretTooShort(10))
+        ));
+        assert!(matches!(
+            Totp::__construct("not base32!", None, None, None),
+            Err(Error::InvalidSecret(_))
+        ));
+        assert!(matches!(
+            Totp::__construct(&secret, Some(4), None, None),
+            Err(Error::InvalidDigits(4))
+        ));
+        assert!(matches!(
+            Totp::__construct(&secret, None, None, Some("md5".into())),
+            Err(Error::UnsupportedAlgorithm(_))
+        ));
+
+        let totp = totp(b"12345678901234567890", 6, "sha1");
+        assert_eq!(
+            totp.provisioning_uri("Example Inc.", "alice@example.com")
+                .unwrap(),
+            "otpauth://totp/Example%20Inc.:alice%40example.com\
+             ?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Example%20Inc.\
+             &algorithm=SHA1&digits=6&period=30"
+        );
+        assert!(matches!(
+            totp.provisioning_uri("Evil:Issuer", "alice"),
+            Err(Error::InvalidLabel("Issuer"))
+        ));
+    }
+}
@@ -0,0 +1,167 @@
+//! Impersonation checks for usernames and display names, after Unicode TS #39: confusable
+//! skeletons, mixed-script detection and removal of invisible characters.
+
+use crate::hostname::{is_invisible, skeleton};
+use ext_php_rs::{php_class, php_impl};
+use unicode_script::{Script, UnicodeScript};
+
+/// Removes invisible characters.
+#[must_use]
+pub fn strip_invisible(s: &str) -> String {
+    s.chars().filter(|c| !is_invisible(*c)).collect()
+}
+
+/// Whether two strings look alike.
+#[must_use]
+pub fn is_confusable(a: &str, b: &str) -> bool {
+    skeleton(a) == skeleton(b)
+}
+
+/// Writing systems a script can take part in. Following the UTS #39 augmentation, Han is
+/// also part of Japanese, Korean and Bopomofo-Han, represented here by Hiragana, Hangul and
+/// Bopomofo; Katakana counts as Japanese.
+fn writing_systems(script: Script) -> &'static [Script] {
+    match script {
+        Script::Han => &[
+            Script::Han,
+            Script::Hiragana,
+            Script::Hangul,
+            Script::Bopomofo,
+        ],
+        Script::Hiragana | Script::Katakana => &[Script::Hiragana],
+        Script::Hangul => &[Script::Hangul],
+        Script::Bopomofo => &[Script::Bopomofo],
+        _ => &[],
+    }
+}
+
+/// Whether the letters of a string cannot all belong to one writing system. Common and
+/// inherited characters (digits, punctuation, combining marks) fit any.
+#[must_use]
+pub fn is_mixed_script(s: &str) -> bool {
+    let mut candidates: Option<Vec<Script>> = None;
+    for script in s.chars().map(|c| c.script()) {
+        if matches!(script, Script::Common | Script::Inherited | Script::Unknown) {
+            continue;
+        }
+        let systems = match writing_systems(script) {
+            [] => vec![script],
+            systems => systems.to_vec(),
+        };
+        let remaining = match candidates {
+            Some(candidates) => candidates
+                .into_iter()
+                .filter(|candidate| systems.contains(candidate))
+                .collect(),
+            None => systems,
+        };
+        if remaining.is_empty() {
+            return true;
+        }
+        candidates = Some(remaining);
+    }
+    false
+}
+
+/// Checks against impersonation in usernames and display names.
+#[php_class]
+#[php(name = "Hardened\\UnicodeSecurity")]
+pub struct UnicodeSecurity {}
+
+#[php_impl]
+impl UnicodeSecurity {
+    /// Reduce a string to its confusable skeleton.
+    ///
+    /// Two strings with the same skeleton look alike. A skeleton is a comparison key, to be
+    /// stored next to a username and indexed for uniqueness; it is not meant for display.
+    ///
+    /// # Parameters
+    /// - `s`: `string`
+    ///
+    /// # Returns
+    /// - `string` e.g. `paypal` for `PаyPa1` with a Cyrillic `а`.
+    fn skeleton(s: &str) -> String {
+        skeleton(s)
+    }
+
+    /// Check whether two strings look alike, i.e. have the same skeleton. Identical strings
+    /// are confusable too.
+    ///
+    /// # Parameters
+    /// - `a`: `string`
+    /// - `b`: `string`
+    ///
+    /// # Returns
+    /// - `bool`
+    fn is_confusable_with(a: &str, b: &str) -> bool {
+        is_confusable(a, b)
+    }
+
+    /// Check whether a string mixes writing systems, e.g. Latin with Cyrillic.
+    ///
+    /// Digits, punctuation and combining marks fit any script. Han combined with kana or
+    /// Hangul is not mixed, as Japanese and Korean use both.
+    ///
+    /// # Parameters
+    /// - `s`: `string`
+    ///
+    /// # Returns
+    /// - `bool`
+    fn mixed_script(s: &str) -> bool {
+        is_mixed_script(s)
+    }
+
+    /// Remove invisible characters: zero-width spaces and joiners, bidi controls, soft
+    /// hyphens, variation selectors, fillers and tag characters.
+    ///
+    /// Joiners also hold emoji sequences and some Persian and Indic spellings together, so
+    /// strip them from identifiers rather than from free text.
+    ///
+    /// # Parameters
+    /// - `s`: `string`
+    ///
+    /// # Returns
+    /// - `string`
+    fn strip_invisible(s: &str) -> String {
+        strip_invisible(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_confusable, is_mixed_script, skeleton, strip_invisible};
+
+    #[test]
+    fn test_skeleton() {
+        assert_eq!(skeleton("PаyPa1"), "paypal");
+        assert_eq!(skeleton("ＡＤＭＩＮ"), skeleton("ADMIN"));
+        assert_eq!(skeleton("ad\u{200B}min"), skeleton("admin"));
+        assert!(is_confusable("rnicrosoft", "microsoft"));
+        assert!(is_confusable("аррӏе", "apple"));
+        assert!(is_confusable("G00gle", "google"));
+        assert!(is_confusable("paypaI", "paypal"));
+        assert!(!is_confusable("alice", "bob"));
+        assert!(!is_confusable("café", "cafe"));
+    }
+
+    #[test]
+    fn test_mixed_script() {
+        assert!(is_mixed_script("pаypal"));
+        assert!(is_mixed_script("αβc"));
+        assert!(!is_mixed_script("paypal 2024!"));
+        assert!(!is_mixed_script("аррӏе"));
+        assert!(!is_mixed_script("東京タワーとスカイツリー"));
+        assert!(!is_mixed_script("한국어漢字"));
+        assert!(is_mixed_script("ひらがな한국어"));
+        assert!(is_mixed_script("漢字abc"));
+    }
+
+    #[test]
+    fn test_strip_invisible() {
+        assert_eq!(
+            strip_invisible("ad\u{200B}m\u{200D}i\u{202E}n\u{FEFF}\u{AD}"),
+            "admin"
+        );
+        assert_eq!(strip_invisible("żółć"), "żółć");
+    }
+}
@@ -0,0 +1,169 @@
+//! UTF-8 validation, repair and normalization for external input.
+//!
+//! Validity is strict UTF-8 as RFC 3629 defines it: overlong encodings (`C0 AF`), UTF-16
+//! surrogates (`ED A0 80`, as produced by CESU-8 and "modified UTF-8") and code points above
+//! U+10FFFF are invalid, like truncated and stray continuation bytes.
+
+use crate::path::NormalizationForm;
+use ext_php_rs::binary::Binary;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+use thiserror::Error;
+
+// Error codes for Utf8 errors: 5000-5099
+pub mod error_codes {
+    pub const UNKNOWN_STRATEGY: i32 = 5000;
+    pub const UNKNOWN_NORMALIZATION_FORM: i32 = 5001;
+    pub const INVALID_UTF8: i32 = 5002;
+}
+
+/// Errors produced by `Utf8`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown repair strategy: {0}")]
+    UnknownStrategy(String),
+
+    #[error("Unknown normalization form: {0}")]
+    UnknownNormalizationForm(String),
+
+    #[error("Invalid UTF-8 at byte offset {0}")]
+    InvalidUtf8(usize),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::UnknownStrategy(_) => error_codes::UNKNOWN_STRATEGY,
+            Error::UnknownNormalizationForm(_) => error_codes::UNKNOWN_NORMALIZATION_FORM,
+            Error::InvalidUtf8(_) => error_codes::INVALID_UTF8,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for UTF-8 operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// What becomes of invalid sequences when repairing.
+#[derive(EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Strategy {
+    /// Each maximal invalid subpart becomes U+FFFD, as the WHATWG Encoding Standard does.
+    Replace,
+    /// Invalid sequences are removed.
+    Strip,
+}
+
+/// Repairs invalid UTF-8.
+#[must_use]
+pub fn repair(bytes: &[u8], strategy: Strategy) -> String {
+    match strategy {
+        Strategy::Replace => String::from_utf8_lossy(bytes).into_owned(),
+        Strategy::Strip => bytes.utf8_chunks().map(|chunk| chunk.valid()).collect(),
+    }
+}
+
+/// Strict UTF-8 validation, repair and Unicode normalization.
+#[php_class]
+#[php(name = "Hardened\\Utf8")]
+pub struct Utf8 {}
+
+#[php_impl]
+impl Utf8 {
+    /// Check whether a string is valid UTF-8.
+    ///
+    /// Overlong encodings, UTF-16 surrogates, code points above U+10FFFF, truncated
+    /// sequences and stray continuation bytes are all invalid.
+    ///
+    /// # Parameters
+    /// - `s`: `string`
+    ///
+    /// # Returns
+    /// - `bool`
+    fn is_valid(s: Binary<u8>) -> bool {
+        std::str::from_utf8(&s).is_ok()
+    }
+
+    /// Repair invalid UTF-8.
+    ///
+    /// # Parameters
+    /// - `s`: `string`
+    /// - `strategy`: `string` `replace` (each invalid sequence becomes U+FFFD, the default)
+    ///   or `strip` (invalid sequences are removed).
+    ///
+    /// # Returns
+    /// - `string` Valid UTF-8; unchanged if `s` already was.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the strategy is unknown.
+    fn repair(s: Binary<u8>, strategy: Option<&str>) -> Result<String> {
+        let strategy = match strategy {
+            Some(strategy) => Strategy::from_str(strategy)
+                .map_err(|_| Error::UnknownStrategy(strategy.to_string()))?,
+            None => Strategy::Replace,
+        };
+        Ok(repair(&s, strategy))
+    }
+
+    /// Normalize valid UTF-8 to a Unicode normalization form.
+    ///
+    /// # Parameters
+    /// - `s`: `string`
+    /// - `form`: `string` `NFC` (the default), `NFD`, `NFKC` or `NFKD`.
+    ///
+    /// # Returns
+    /// - `string`
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if the form is unknown or `s` is not valid UTF-8 (repair it first).
+    fn normalize(s: Binary<u8>, form: Option<&str>) -> Result<String> {
+        let form = match form {
+            Some(form) => NormalizationForm::from_str(form)
+                .ok()
+                .filter(|form| *form != NormalizationForm::None)
+                .ok_or_else(|| Error::UnknownNormalizationForm(form.to_string()))?,
+            None => NormalizationForm::Nfc,
+        };
+        let s = std::str::from_utf8(&s).map_err(|err| Error::InvalidUtf8(err.valid_up_to()))?;
+        Ok(form.apply(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Strategy, repair};
+
+    #[test]
+    fn test_repair() {
+        for (input, replaced, stripped) in [
+            (&b"caf\xC3\xA9"[..], "café", "café"),
+            // Overlong "/"
+            (b"a\xC0\xAFb", "a\u{FFFD}\u{FFFD}b", "ab"),
+            (b"a\xE0\x80\xAFb", "a\u{FFFD}\u{FFFD}\u{FFFD}b", "ab"),
+            // UTF-16 surrogate U+D800
+            (b"a\xED\xA0\x80b", "a\u{FFFD}\u{FFFD}\u{FFFD}b", "ab"),
+            // Above U+10FFFF
+            (
+                b"a\xF4\x90\x80\x80b",
+                "a\u{FFFD}\u{FFFD}\u{FFFD}\u{FFFD}b",
+                "ab",
+            ),
+            // Truncated sequence, stray continuation byte
+            (b"a\xE2\x82b\x80", "a\u{FFFD}b\u{FFFD}", "ab"),
+        ] {
+            assert_eq!(repair(input, Strategy::Replace), replaced, "{input:?}");
+            assert_eq!(repair(input, Strategy::Strip), stripped, "{input:?}");
+        }
+    }
+}
@@ -0,0 +1,341 @@
+//! XXE-safe XML parsing into plain PHP arrays, for SAML responses, feeds, sitemaps and other
+//! XML received from outside.
+//!
+//! Any `<!DOCTYPE>` is rejected, so no entity beyond the five predefined ones and character
+//! references can be declared: neither XXE nor "billion laughs" expansion is reachable. Size,
+//! depth and node-count limits are enforced before any PHP value is built.
+
+use ext_php_rs::binary::Binary;
+use ext_php_rs::exception::PhpException;
+use ext_php_rs::types::{ZendHashTable, Zval};
+use ext_php_rs::zend::ce;
+use ext_php_rs::{php_class, php_impl};
+use roxmltree::{Document, Edge, Node, ParsingOptions};
+use thiserror::Error;
+
+// Error codes for Xml errors: 4400-4499
+pub mod error_codes {
+    pub const TOO_LARGE: i32 = 4400;
+    pub const TOO_DEEP: i32 = 4401;
+    pub const NOT_UTF8: i32 = 4402;
+    pub const PARSE: i32 = 4403;
+    pub const INVALID_OPTION: i32 = 4404;
+    pub const CONVERSION: i32 = 4405;
+}
+
+/// Errors produced by `Xml`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("XML document is {0} bytes long, the limit is {1}")]
+    TooLarge(usize, usize),
+
+    #[error("XML elements are nested deeper than {0} levels")]
+    TooDeep(usize),
+
+    #[error("XML document must be UTF-8 encoded")]
+    NotUtf8,
+
+    #[error("XML parse error: {0}")]
+    Parse(String),
+
+    #[error("Invalid option: {0}")]
+    InvalidOption(String),
+
+    #[error("Failed to convert XML to an array: {0}")]
+    Conversion(String),
+}
+
+impl Error {
+    #[must_use]
+    pub fn code(&self) -> i32 {
+        match self {
+            Error::TooLarge(..) => error_codes::TOO_LARGE,
+            Error::TooDeep(_) => error_codes::TOO_DEEP,
+            Error::NotUtf8 => error_codes::NOT_UTF8,
+            Error::Parse(_) => error_codes::PARSE,
+            Error::InvalidOption(_) => error_codes::INVALID_OPTION,
+            Error::Conversion(_) => error_codes::CONVERSION,
+        }
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let code = err.code();
+        let message = err.to_string();
+        PhpException::new(message, code, ce::exception())
+    }
+}
+
+/// Result type alias for XML parsing.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Ceiling for `maxDepth`: converting the tree to PHP arrays recurses once per level.
+const MAX_DEPTH: usize = 256;
+
+/// Limits and switches for `Xml::parse()`.
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    /// Largest accepted document in bytes.
+    max_bytes: usize,
+    /// Deepest accepted element nesting; the root element is level 1.
+    max_depth: usize,
+    /// Most nodes (elements, text, comments, processing instructions) in the parsed tree.
+    max_nodes: u32,
+    /// Keep whitespace-only text nodes.
+    preserve_whitespace: bool,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 10 * 1024 * 1024,
+            max_depth: 64,
+            max_nodes: 100_000,
+            preserve_whitespace: false,
+        }
+    }
+}
+
+impl Limits {
+    /// Reads limits from a PHP array; unknown keys are rejected so a typo cannot silently
+    /// leave a default in place.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidOption` for an unknown key or a value of the wrong type.
+    fn from_table(table: &ZendHashTable) -> Result<Self> {
+        let mut limits = Self::default();
+        for (key, value) in table {
+            let key = key.to_string();
+            let invalid = || Error::InvalidOption(key.clone());
+            let positive = || value.long().filter(|value| *value > 0).ok_or_else(invalid);
+            match key.as_str() {
+                "maxBytes" => {
+                    limits.max_bytes = usize::try_from(positive()?).map_err(|_| invalid())?;
+                }
+                "maxDepth" => {
+                    limits.max_depth = usize::try_from(positive()?)
+                        .ok()
+                        .filter(|depth| *depth <= MAX_DEPTH)
+                        .ok_or_else(invalid)?;
+                }
+                "maxNodes" => {
+                    limits.max_nodes = u32::try_from(positive()?).map_err(|_| invalid())?;
+                }
+                "preserveWhitespace" => {
+                    limits.preserve_whitespace = value.bool().ok_or_else(invalid)?;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(limits)
+    }
+}
+
+/// Parses `xml` within `limits`.
+///
+/// # Errors
+/// - Returns `Err` if a limit is exceeded, or the input is not UTF-8, contains a DTD or is not
+///   well-formed.
+fn parse(xml: &[u8], limits: &Limits) -> Result<Document<'_>> {
+    if xml.len() > limits.max_bytes {
+        return Err(Error::TooLarge(xml.len(), limits.max_bytes));
+    }
+    let text = std::str::from_utf8(xml).map_err(|_| Error::NotUtf8)?;
+    let options = ParsingOptions {
+        allow_dtd: false,
+        nodes_limit: limits.max_nodes,
+    };
+    let document =
+        Document::parse_with_options(text, options).map_err(|err| Error::Parse(err.to_string()))?;
+    // Checked up front, so converting the tree afterwards cannot recurse too deep
+    let mut depth = 0;
+    for edge in document.root().traverse() {
+        match edge {
+            Edge::Open(node) if node.is_element() => {
+                depth += 1;
+                if depth > limits.max_depth {
+                    return Err(Error::TooDeep(limits.max_depth));
+                }
+            }
+            Edge::Close(node) if node.is_element() => depth -= 1,
+            _ => {}
+        }
+    }
+    Ok(document)
+}
+
+/// Name in Clark notation: `{namespace}local`, or `local` without a namespace.
+fn qualified_name(namespace: Option<&str>, name: &str) -> String {
+    match namespace {
+        Some(namespace) => format!("{{{namespace}}}{name}"),
+        None => name.to_string(),
+    }
+}
+
+/// Converts an element to `['name', 'namespace', 'attributes', 'children']`.
+fn element_to_zval(node: Node, limits: &Limits) -> Result<Zval> {
+    let conversion = |err: ext_php_rs::error::Error| Error::Conversion(err.to_string());
+    let mut attributes = ZendHashTable::new();
+    for attribute in node.attributes() {
+        attributes
+            .insert(
+                qualified_name(attribute.namespace(), attribute.name()).as_str(),
+                attribute.value(),
+            )
+            .map_err(conversion)?;
+    }
+    let mut children = ZendHashTable::new();
+    for child in node.children() {
+        if child.is_element() {
+            children
+                .push(element_to_zval(child, limits)?)
+                .map_err(conversion)?;
+        } else if let Some(text) = child.text().filter(|_| child.is_text())
+            && (limits.preserve_whitespace || !text.trim().is_empty())
+        {
+            children.push(text).map_err(conversion)?;
+        }
+    }
+    let mut element = ZendHashTable::new();
+    element
+        .insert("name", node.tag_name().name())
+        .map_err(conversion)?;
+    element
+        .insert("namespace", node.tag_name().namespace())
+        .map_err(conversion)?;
+    element
+        .insert("attributes", attributes)
+        .map_err(conversion)?;
+    element.insert("children", children).map_err(conversion)?;
+    let mut zval = Zval::new();
+    zval.set_hashtable(element);
+    Ok(zval)
+}
+
+/// Hardened XML parsing.
+#[php_class]
+#[php(name = "Hardened\\Xml")]
+pub struct Xml {}
+
+#[php_impl]
+impl Xml {
+    /// Parse an XML document into nested arrays.
+    ///
+    /// Each element becomes `['name' => string, 'namespace' => ?string, 'attributes' =>
+    /// array<string, string>, 'children' => array<array|string>]`, where children are elements
+    /// and text in document order (CDATA included, entities decoded). Namespaced attributes
+    /// are keyed as `{uri}name`. Comments and processing instructions are dropped.
+    ///
+    /// # Parameters
+    /// - `xml`: `string` UTF-8 encoded document.
+    /// - `limits`: `?array` Optional settings:
+    ///   - `maxBytes`: `int` largest document (default 10 MiB).
+    ///   - `maxDepth`: `int` deepest element nesting (default `64`, at most `256`).
+    ///   - `maxNodes`: `int` most nodes (default `100000`).
+    ///   - `preserveWhitespace`: `bool` keep whitespace-only text (default `false`).
+    ///
+    /// # Returns
+    /// - `array` The root element.
+    ///
+    /// # Exceptions
+    /// - Throws `Exception` if an option is invalid, a limit is exceeded, the document has a
+    ///   `<!DOCTYPE>`, or it is not well-formed UTF-8 XML.
+    fn parse(xml: Binary<u8>, limits: Option<&ZendHashTable>) -> Result<Zval> {
+        let limits = limits
+            .map(Limits::from_table)
+            .transpose()?
+            .unwrap_or_default();
+        let document = parse(&xml, &limits)?;
+        element_to_zval(document.root_element(), &limits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Limits, parse, qualified_name};
+    use crate::run_php_test;
+
+    #[test]
+    fn test_parse() {
+        let limits = Limits::default();
+        let xml = br#"<?xml version="1.0"?>
+            <feed xmlns="http://www.w3.org/2005/Atom"><title type="text">A &amp; B</title></feed>"#;
+        let document = parse(xml, &limits).unwrap();
+        let root = document.root_element();
+        assert_eq!(root.tag_name().name(), "feed");
+        assert_eq!(
+            root.tag_name().namespace(),
+            Some("http://www.w3.org/2005/Atom")
+        );
+        assert_eq!(root.first_element_child().unwrap().text(), Some("A & B"));
+        assert_eq!(qualified_name(Some("urn:x"), "id"), "{urn:x}id");
+    }
+
+    #[test]
+    fn test_rejects_dtd() {
+        let xxe = br#"<?xml version="1.0"?>
+            <!DOCTYPE foo [<!ENTITY xxe SYSTEM "file:///etc/passwd">]><foo>&xxe;</foo>"#;
+        assert!(matches!(
+            parse(xxe, &Limits::default()),
+            Err(Error::Parse(_))
+        ));
+
+        let laughs = br#"<!DOCTYPE lolz [
+            <!ENTITY lol "lollollollollollollollollollol">
+            <!ENTITY lol2 "&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;">
+            <!ENTITY lol3 "&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;">
+            <!ENTITY lol4 "&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;">
+            <!ENTITY lol5 "&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;">
+            <!ENTITY lol6 "&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;">
+            <!ENTITY lol7 "&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;">
+            ]><lolz>&lol7;</lolz>"#;
+        assert!(matches!(
+            parse(laughs, &Limits::default()),
+            Err(Error::Parse(_))
+        ));
+        // Undeclared entities are errors, not empty text
+        assert!(parse(b"<a>&nbsp;</a>", &Limits::default()).is_err());
+        assert!(parse(b"<a>&#60;&lt;</a>", &Limits::default()).is_ok());
+    }
+
+    #[test]
+    fn test_limits() {
+        let deep = format!("{}{}", "<a>".repeat(65), "</a>".repeat(65));
+        assert!(matches!(
+            parse(deep.as_bytes(), &Limits::default()),
+            Err(Error::TooDeep(64))
+        ));
+        let shallow = format!("{}{}", "<a>".repeat(64), "</a>".repeat(64));
+        assert!(parse(shallow.as_bytes(), &Limits::default()).is_ok());
+
+        let small = Limits {
+            max_bytes: 8,
+            ..Limits::default()
+        };
+        assert!(matches!(
+            parse(b"<a>123456</a>", &small),
+            Err(Error::TooLarge(13, 8))
+        ));
+
+        let few_nodes = Limits {
+            max_nodes: 5,
+            ..Limits::default()
+        };
+        assert!(parse(b"<a><b/><b/><b/><b/><b/><b/></a>", &few_nodes).is_err());
+        assert!(matches!(
+            parse(b"<a>\xff</a>", &Limits::default()),
+            Err(Error::NotUtf8)
+        ));
+        assert!(matches!(
+            parse(b"<a><b></a>", &Limits::default()),
+            Err(Error::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn php_test() -> crate::TestResult {
+        run_php_test("xml")?;
+        Ok(())
+    }
+}
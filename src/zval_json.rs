@@ -0,0 +1,105 @@
+//! Conversion between PHP values and `serde_json` values.
+//!
+//! Lists (arrays with keys `0..n`) map to JSON arrays and every other array to a JSON object,
+//! as `json_encode()` does. Objects and resources are rejected rather than guessed at.
+
+use ext_php_rs::types::{ArrayKey, ZendHashTable, Zval};
+use serde_json::{Map, Value};
+
+/// Converts a PHP value to JSON.
+///
+/// # Errors
+/// - Returns a description of the first value that has no JSON equivalent: an object,
+///   a resource, a non-finite float or a string that is not UTF-8.
+pub(crate) fn to_json(zval: &Zval) -> Result<Value, String> {
+    if zval.is_null() {
+        Ok(Value::Null)
+    } else if zval.is_bool() {
+        Ok(Value::Bool(zval.bool().unwrap_or_default()))
+    } else if zval.is_long() {
+        Ok(Value::from(zval.long().unwrap_or_default()))
+    } else if zval.is_double() {
+        let double = zval.double().unwrap_or_default();
+        serde_json::Number::from_f64(double)
+            .map(Value::Number)
+            .ok_or_else(|| format!("{double} cannot be represented in JSON"))
+    } else if zval.is_string() {
+        zval.string()
+            .map(Value::String)
+            .ok_or_else(|| "strings must be valid UTF-8".to_string())
+    } else if let Some(array) = zval.array() {
+        array_to_json(array)
+    } else {
+        Err(format!(
+            "{} values cannot be represented in JSON",
+            zval.get_type()
+        ))
+    }
+}
+
+/// Whether a PHP array is a list, i.e. its keys are `0..n` in order.
+pub(crate) fn is_list(array: &ZendHashTable) -> bool {
+    array
+        .iter()
+        .enumerate()
+        .all(|(index, (key, _))| matches!(key, ArrayKey::Long(key) if key == index as i64))
+}
+
+/// Converts a PHP array to a JSON array if it is a list, else to an object.
+///
+/// # Errors
+/// - See `to_json()`.
+pub(crate) fn array_to_json(array: &ZendHashTable) -> Result<Value, String> {
+    if is_list(array) {
+        return array
+            .values()
+            .map(to_json)
+            .collect::<Result<_, _>>()
+            .map(Value::Array);
+    }
+    array
+        .iter()
+        .map(|(key, value)| Ok((key.to_string(), to_json(value)?)))
+        .collect::<Result<Map<_, _>, String>>()
+        .map(Value::Object)
+}
+
+/// Converts JSON to a PHP value; objects become associative arrays.
+///
+/// # Errors
+/// - Returns `Err` if the Zend engine fails to allocate a string or array element.
+pub(crate) fn to_zval(value: &Value) -> ext_php_rs::error::Result<Zval> {
+    let mut zval = Zval::new();
+    match value {
+        Value::Null => zval.set_null(),
+        Value::Bool(value) => zval.set_bool(*value),
+        Value::Number(number) => match number.as_i64() {
+            Some(long) => zval.set_long(long),
+            None => zval.set_double(number.as_f64().unwrap_or_default()),
+        },
+        Value::String(value) => zval.set_string(value, false)?,
+        Value::Array(values) => {
+            let mut array = ZendHashTable::new();
+            for value in values {
+                array.push(to_zval(value)?)?;
+            }
+            zval.set_hashtable(array);
+        }
+        Value::Object(map) => zval.set_hashtable(map_to_array(map)?),
+    }
+    Ok(zval)
+}
+
+/// Converts a JSON object to a PHP associative array.
+///
+/// # Errors
+/// - See `to_zval()`.
+pub(crate) fn map_to_array(
+    map: &Map<String, Value>,
+) -> ext_php_rs::error::Result<ext_php_rs::boxed::ZBox<ZendHashTable>> {
+    let mut array = ZendHashTable::new();
+    for (key, value) in map {
+        array.insert(key.as_str(), to_zval(value)?)?;
+    }
+    Ok(array)
+}
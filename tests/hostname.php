@@ -0,0 +1,32 @@
+<?php
+// tests/hostname.php
+
+// Enable assertions
+ini_set('assert.active',   '1');
+ini_set('assert.warning',  '1');
+ini_set('assert.bail',     '0');
+
+use Hardened\Hostname;
+
+echo "Running Hostname tests...\n";
+
+// --- var_export() round trip ---
+$hostname = Hostname::from('Example.COM.');
+assert($hostname->hostname === 'example.com', 'hostname property holds the normalized hostname');
+$restored = eval('return ' . var_export($hostname, true) . ';');
+assert($restored instanceof Hostname, '__set_state returns a Hostname');
+assert((string) $restored === (string) $hostname, 'restored hostname equals the exported one');
+
+// --- __set_state() validates like from() ---
+try {
+    Hostname::__set_state(['hostname' => 'exa mple.com']);
+    assert(false, '__set_state should throw on an invalid hostname');
+} catch (\Exception $e) {
+}
+try {
+    Hostname::__set_state([]);
+    assert(false, '__set_state should throw without the hostname property');
+} catch (\Exception $e) {
+}
+
+echo "All Hostname tests passed.\n";
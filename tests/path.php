@@ -0,0 +1,38 @@
+<?php
+// tests/path.php
+
+// Enable assertions
+ini_set('assert.active',   '1');
+ini_set('assert.warning',  '1');
+ini_set('assert.bail',     '0');
+
+use Hardened\Path;
+
+echo "Running Path tests...\n";
+
+// --- var_export() round trip ---
+$path = Path::from('/var/www/../www/index.php');
+assert($path->path === '/var/www/index.php', 'path property holds the normalized path');
+$restored = eval('return ' . var_export($path, true) . ';');
+assert($restored instanceof Path, '__set_state returns a Path');
+assert((string) $restored === (string) $path, 'restored path equals the exported one');
+
+// --- __set_state() requires the path property ---
+try {
+    Path::__set_state([]);
+    assert(false, '__set_state should throw without the path property');
+} catch (\Exception $e) {
+}
+
+// --- sanitizeFileName() only accepts a fallback that is already sanitized ---
+assert(Path::sanitizeFileName('...', ['fallback' => 'upload.bin']) === 'upload.bin', 'fallback is used');
+assert(Path::sanitizeFileName('...', ['maxLength' => 2]) === 'fi', 'default fallback respects maxLength');
+foreach (['../x', '.htaccess', 'a/b', 'CON', ''] as $fallback) {
+    try {
+        Path::sanitizeFileName('...', ['fallback' => $fallback]);
+        assert(false, "fallback '$fallback' should be rejected");
+    } catch (\Exception $e) {
+    }
+}
+
+echo "All Path tests passed.\n";
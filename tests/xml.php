@@ -0,0 +1,25 @@
+<?php
+// tests/xml.php
+
+// Enable assertions
+ini_set('assert.active',   '1');
+ini_set('assert.warning',  '1');
+ini_set('assert.bail',     '0');
+
+use Hardened\Xml;
+
+echo "Running Xml tests...\n";
+
+// --- maxDepth is capped ---
+$doc = Xml::parse('<a><b/></a>', ['maxDepth' => 256]);
+assert($doc['name'] === 'a', 'maxDepth 256 is accepted');
+foreach ([257, 1 << 40, 0] as $depth) {
+    try {
+        Xml::parse('<a/>', ['maxDepth' => $depth]);
+        assert(false, "maxDepth $depth should be rejected");
+    } catch (\Exception $e) {
+        assert($e->getCode() === 4404, 'invalid option error code');
+    }
+}
+
+echo "All Xml tests passed.\n";